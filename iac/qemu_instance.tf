@@ -23,12 +23,18 @@ resource "proxmox_qemu_vm" "this" {
   # Hardware Configuration
   bios     = local.vm_config_map.bios
   machine  = local.vm_config_map.machine
-  cpu_type = lookup(local.vm_base_config_map, "cpu", "host")
   cores    = local.vm_config_map.cores
   sockets  = lookup(local.vm_base_config_map, "sockets", 1)
   vcpus    = lookup(local.vm_base_config_map, "vcpus", null)
-  memory   = local.vm_config_map.memory
-  balloon  = local.vm_config_map.balloon
+
+  cpu {
+    type = lookup(local.vm_base_config_map, "cpu", "host")
+  }
+
+  memory {
+    dedicated = local.vm_config_map.memory
+    floating  = local.vm_config_map.balloon
+  }
 
   # Boot Configuration
   boot     = local.vm_config_map.boot
@@ -36,10 +42,14 @@ resource "proxmox_qemu_vm" "this" {
   onboot   = local.vm_config_map.onboot
   start    = try(local.vm_config_map.start, true)
 
-  scsihw = local.vm_config_map.scsihw
-  agent   = 1
+  scsihw  = local.vm_config_map.scsihw
   qemu_os = lookup(local.vm_base_config_map, "qemu_os", "l26")
 
+  agent {
+    enabled           = true
+    trim_cloned_disks = true
+  }
+
   # Cloud-Init Configuration
   ipconfig0  = each.value.ipconfig
   ciuser     = try(local.vm_config_map.ciuser, null)
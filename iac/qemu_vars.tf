@@ -39,8 +39,8 @@ variable "target_node" {
 
 variable "tags" {
   description = "Tags for the VM"
-  type        = string
-  default     = "controlplane,talos"
+  type        = set(string)
+  default     = ["controlplane", "talos"]
 }
 
 variable "vm_config_map" {
@@ -107,8 +107,8 @@ variable "cipassword" {
 }
 
 variable "sshkeys" {
-  description = "SSH public keys for cloud-init"
-  type        = string
+  description = "SSH public keys for cloud-init, one key per entry"
+  type        = list(string)
   default     = null
 }
 
@@ -0,0 +1,503 @@
+//! Generates API models from the Proxmox VE API schema
+//!
+//! The schema is the `apiSchema` tree of the API viewer's `apidoc.js`
+//! (<https://pve.proxmox.com/pve-docs/api-viewer/apidoc.js>), or the same
+//! tree as plain JSON. The models listed in [`MODELS`] and a constant per
+//! endpoint path are rendered from it, so fields new Proxmox releases add
+//! come from regenerating rather than editing the request structs by hand.
+
+use proxmox::api::common::deserialize_proxmox_bool_option;
+use serde::{Deserialize, Deserializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    #[error("No apiSchema tree found")]
+    SchemaNotFound,
+
+    #[error("Failed to parse the API schema: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("The API schema has no {method} {path}")]
+    UnknownEndpoint { method: String, path: String },
+}
+
+/// An entry of the API tree, with the methods of its path
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiNode {
+    pub path: String,
+    #[serde(default)]
+    pub info: BTreeMap<String, ApiMethod>,
+    #[serde(default)]
+    pub children: Vec<ApiNode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiMethod {
+    #[serde(default)]
+    pub parameters: Option<ApiSchema>,
+    #[serde(default)]
+    pub returns: Option<ApiSchema>,
+}
+
+/// JSON schema of a parameter, a return value or one of their properties
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ApiSchema {
+    /// "string", "integer", "number", "boolean", "array", "object" or
+    /// "null". Unset for untyped return values.
+    #[serde(rename = "type", default, deserialize_with = "deserialize_kind")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_proxmox_bool_option")]
+    pub optional: Option<bool>,
+    #[serde(default)]
+    pub minimum: Option<f64>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, ApiSchema>,
+    #[serde(default)]
+    pub items: Option<Box<ApiSchema>>,
+}
+
+/// A few properties list several types, those are treated as untyped
+fn deserialize_kind<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(serde_json::Value::deserialize(deserializer)?
+        .as_str()
+        .map(str::to_string))
+}
+
+/// Which part of an endpoint a model is generated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelSource {
+    /// The request parameters, without the ones in the path
+    Parameters,
+    /// The returned object, or the items of a returned array
+    Returns,
+}
+
+/// A struct to generate
+#[derive(Debug, Clone, Copy)]
+pub struct ModelSpec {
+    pub name: &'static str,
+    pub method: &'static str,
+    pub path: &'static str,
+    pub source: ModelSource,
+    /// Properties left out, such as parameters a wrapping request sends
+    pub exclude: &'static [&'static str],
+    /// Highest index of indexed properties whose description documents no
+    /// range, or that the provider reads fewer of
+    pub index_ranges: &'static [(&'static str, u32)],
+    /// Rust types of properties the provider reads as another type than the
+    /// schema gives
+    pub types: &'static [(&'static str, &'static str)],
+}
+
+/// Indexed QEMU options. Only the first four unused volumes are read.
+const QEMU_INDEX_RANGES: &[(&str, u32)] =
+    &[("ipconfig", 31), ("net", 31), ("numa", 7), ("unused", 3)];
+
+/// Memory is a property string since PVE 8.1, the provider only sets its
+/// size
+const QEMU_TYPES: &[(&str, &str)] = &[
+    ("cores", "u32"),
+    ("cpuunits", "u32"),
+    ("memory", "u64"),
+    ("shares", "u32"),
+    ("smp", "u32"),
+    ("sockets", "u32"),
+    ("vcpus", "u32"),
+];
+
+/// The models checked in as `src/api/generated.rs`
+pub const MODELS: &[ModelSpec] = &[
+    ModelSpec {
+        name: "QemuConfig",
+        method: "GET",
+        path: "/nodes/{node}/qemu/{vmid}/config",
+        source: ModelSource::Returns,
+        exclude: &[],
+        index_ranges: QEMU_INDEX_RANGES,
+        types: QEMU_TYPES,
+    },
+    // Shared by the create and update requests, which send the parameters
+    // specific to them next to these
+    ModelSpec {
+        name: "QemuSettings",
+        method: "POST",
+        path: "/nodes/{node}/qemu/{vmid}/config",
+        source: ModelSource::Parameters,
+        exclude: &[
+            "background_delay",
+            "cipassword",
+            "ciupgrade",
+            "ciuser",
+            "delete",
+            "digest",
+            "force",
+            "revert",
+            "skiplock",
+            "sshkeys",
+        ],
+        index_ranges: QEMU_INDEX_RANGES,
+        types: QEMU_TYPES,
+    },
+];
+
+/// Parses the API tree from `apidoc.js`, or from JSON holding just the tree
+pub fn parse_schema(source: &str) -> Result<Vec<ApiNode>, CodegenError> {
+    let start = tree_start(source)?;
+    // The tree is followed by the viewer's own code, stop after the array
+    serde_json::Deserializer::from_str(&source[start..])
+        .into_iter::<Vec<ApiNode>>()
+        .next()
+        .ok_or(CodegenError::SchemaNotFound)?
+        .map_err(CodegenError::from)
+}
+
+/// Cuts the API tree of `apidoc.js` down to the endpoints of the models,
+/// keeping their methods and the nodes leading to them. This is the schema
+/// checked in next to the example.
+pub fn extract(source: &str, models: &[ModelSpec]) -> Result<serde_json::Value, CodegenError> {
+    let start = tree_start(source)?;
+    let tree = serde_json::Deserializer::from_str(&source[start..])
+        .into_iter::<Vec<serde_json::Value>>()
+        .next()
+        .ok_or(CodegenError::SchemaNotFound)??;
+    Ok(serde_json::Value::Array(prune(tree, models)))
+}
+
+fn prune(nodes: Vec<serde_json::Value>, models: &[ModelSpec]) -> Vec<serde_json::Value> {
+    nodes
+        .into_iter()
+        .filter_map(|mut node| {
+            let path = node["path"].as_str().unwrap_or_default().to_string();
+            if !models
+                .iter()
+                .any(|spec| spec.path == path || spec.path.starts_with(&format!("{}/", path)))
+            {
+                return None;
+            }
+            let node = node.as_object_mut()?;
+            if let Some(serde_json::Value::Object(info)) = node.get_mut("info") {
+                info.retain(|method, _| {
+                    models
+                        .iter()
+                        .any(|spec| spec.path == path && spec.method == method)
+                });
+            }
+            if let Some(serde_json::Value::Array(children)) = node.remove("children") {
+                let children = prune(children, models);
+                if !children.is_empty() {
+                    node.insert("children".to_string(), children.into());
+                }
+            }
+            Some(serde_json::Value::Object(std::mem::take(node)))
+        })
+        .collect()
+}
+
+/// Where the JSON array of the tree starts
+fn tree_start(source: &str) -> Result<usize, CodegenError> {
+    if source.trim_start().starts_with('[') {
+        return Ok(0);
+    }
+    let assignment = source
+        .find("apiSchema")
+        .ok_or(CodegenError::SchemaNotFound)?;
+    let bracket = source[assignment..]
+        .find('[')
+        .ok_or(CodegenError::SchemaNotFound)?;
+    Ok(assignment + bracket)
+}
+
+/// Finds the method of an endpoint, e.g. ("GET", "/nodes/{node}/qemu")
+pub fn find_method<'a>(nodes: &'a [ApiNode], method: &str, path: &str) -> Option<&'a ApiMethod> {
+    nodes.iter().find_map(|node| {
+        if node.path == path {
+            node.info.get(method)
+        } else {
+            find_method(&node.children, method, path)
+        }
+    })
+}
+
+/// Every path with methods, in tree order, with its methods
+pub fn endpoint_paths(nodes: &[ApiNode]) -> Vec<(&str, Vec<&str>)> {
+    let mut paths = vec![];
+    for node in nodes {
+        if !node.info.is_empty() {
+            let methods = node.info.keys().map(String::as_str).collect();
+            paths.push((node.path.as_str(), methods));
+        }
+        paths.extend(endpoint_paths(&node.children));
+    }
+    paths
+}
+
+/// Renders the models and the endpoint path constants as a Rust module.
+/// The output is meant to go through `cargo fmt` before it is checked in.
+pub fn generate(nodes: &[ApiNode], models: &[ModelSpec]) -> Result<String, CodegenError> {
+    let mut structs = String::new();
+    let mut helpers = BTreeSet::new();
+    for spec in models {
+        let method = find_method(nodes, spec.method, spec.path).ok_or_else(|| {
+            CodegenError::UnknownEndpoint {
+                method: spec.method.to_string(),
+                path: spec.path.to_string(),
+            }
+        })?;
+        render_struct(&mut structs, spec, method, &mut helpers);
+    }
+
+    let mut out = String::new();
+    out.push_str("//! Generated from the Proxmox VE API schema by examples/apigen.\n");
+    out.push_str("//! Regenerate instead of editing by hand.\n\n");
+    let helpers: Vec<_> = helpers.into_iter().collect();
+    match helpers.as_slice() {
+        [] => {}
+        [helper] => {
+            let _ = writeln!(out, "use crate::api::common::{};", helper);
+        }
+        _ => {
+            let _ = writeln!(out, "use crate::api::common::{{{}}};", helpers.join(", "));
+        }
+    }
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+    out.push_str(&structs);
+    out.push_str("\n/// Endpoint paths, relative to /api2/json\npub mod paths {\n");
+    for (path, methods) in endpoint_paths(nodes) {
+        let name = const_name(path);
+        if name.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out, "    /// {}", methods.join(", "));
+        let _ = writeln!(out, "    pub const {}: &str = \"{}\";", name, path);
+    }
+    out.push_str("}\n");
+    Ok(out)
+}
+
+/// Appends the struct of a model. Returns whether it deserializes Proxmox
+/// booleans, which need the 0/1 helper.
+fn render_struct(
+    out: &mut String,
+    spec: &ModelSpec,
+    method: &ApiMethod,
+    helpers: &mut BTreeSet<&'static str>,
+) {
+    let schema =
+        match spec.source {
+            ModelSource::Parameters => method.parameters.as_ref(),
+            ModelSource::Returns => method.returns.as_ref().map(|returns| {
+                match (returns.kind.as_deref(), &returns.items) {
+                    (Some("array"), Some(items)) => items.as_ref(),
+                    _ => returns,
+                }
+            }),
+        };
+    let properties = schema.map(|s| &s.properties);
+
+    out.push('\n');
+    match spec.source {
+        ModelSource::Parameters => {
+            let _ = writeln!(out, "/// Parameters of {} {}", spec.method, spec.path);
+            out.push_str("#[derive(Debug, Clone, Default, Serialize)]\n");
+        }
+        ModelSource::Returns => {
+            let _ = writeln!(out, "/// Returned by {} {}", spec.method, spec.path);
+            out.push_str("#[derive(Debug, Clone, Default, Deserialize, Serialize)]\n");
+        }
+    }
+    let _ = writeln!(out, "pub struct {} {{", spec.name);
+
+    let properties = properties
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| !spec.exclude.contains(&name.as_str()));
+    for (name, property) in expand_properties(properties, spec.index_ranges) {
+        if name.ends_with("[n]") {
+            let _ = writeln!(out, "    // {} is indexed but documents no range", name);
+            continue;
+        }
+        // Path parameters are part of the URL, not the body
+        if spec.source == ModelSource::Parameters && spec.path.contains(&format!("{{{}}}", name)) {
+            continue;
+        }
+        helpers.extend(render_field(out, spec, &name, property));
+    }
+    out.push_str("}\n");
+}
+
+/// Expands indexed properties like "ide[n]" into ide0 to ide3, using the
+/// range given for them or else the "(n is 0 to 3)" their descriptions end
+/// with. Indexed properties without either are kept as they are.
+fn expand_properties<'a>(
+    properties: impl Iterator<Item = (&'a String, &'a ApiSchema)>,
+    index_ranges: &[(&str, u32)],
+) -> Vec<(String, &'a ApiSchema)> {
+    let mut expanded = vec![];
+    for (name, property) in properties {
+        match name.strip_suffix("[n]") {
+            None => expanded.push((name.clone(), property)),
+            Some(prefix) => match lookup(index_ranges, prefix)
+                .or_else(|| property.description.as_deref().and_then(index_range))
+            {
+                Some(max) => {
+                    for n in 0..=max {
+                        expanded.push((format!("{}{}", prefix, n), property));
+                    }
+                }
+                None => expanded.push((name.clone(), property)),
+            },
+        }
+    }
+    expanded
+}
+
+/// The highest index of "(n is 0 to 30)"
+fn index_range(description: &str) -> Option<u32> {
+    let rest = &description[description.find("n is 0 to ")? + "n is 0 to ".len()..];
+    let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    digits.parse().ok()
+}
+
+/// Appends one field. Returns the deserializer from `api::common` it uses,
+/// if any: response values may come back as strings, so booleans and
+/// numbers in returned models accept either.
+fn render_field(
+    out: &mut String,
+    spec: &ModelSpec,
+    name: &str,
+    property: &ApiSchema,
+) -> Option<&'static str> {
+    let source = spec.source;
+    if let Some(description) = &property.description {
+        for line in wrap(description, 76) {
+            let _ = writeln!(out, "    /// {}", line);
+        }
+    }
+
+    let ident = field_ident(name);
+    let mut serde = vec![];
+    if ident.trim_start_matches("r#") != name {
+        serde.push(format!("rename = \"{}\"", name));
+    }
+
+    let rust_type = lookup(spec.types, name).unwrap_or_else(|| rust_type(property));
+    let optional = source == ModelSource::Returns || property.optional.unwrap_or(false);
+    let helper = match rust_type {
+        _ if source != ModelSource::Returns => None,
+        "bool" => Some("deserialize_proxmox_bool_option"),
+        "u32" | "u64" | "i64" | "f64" => Some("deserialize_number_option"),
+        _ => None,
+    };
+    if let Some(helper) = helper {
+        serde.push("default".to_string());
+        serde.push(format!("deserialize_with = \"{}\"", helper));
+    }
+    if optional {
+        serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+    }
+    if !serde.is_empty() {
+        let _ = writeln!(out, "    #[serde({})]", serde.join(", "));
+    }
+
+    if optional {
+        let _ = writeln!(out, "    pub {}: Option<{}>,", ident, rust_type);
+    } else {
+        let _ = writeln!(out, "    pub {}: {},", ident, rust_type);
+    }
+    helper
+}
+
+/// Value given for a property in one of the per model lists
+fn lookup<T: Copy>(list: &[(&str, T)], name: &str) -> Option<T> {
+    list.iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| *value)
+}
+
+fn rust_type(property: &ApiSchema) -> &'static str {
+    match property.kind.as_deref() {
+        Some("boolean") => "bool",
+        Some("integer") if property.minimum.is_some_and(|min| min >= 0.0) => "u64",
+        Some("integer") => "i64",
+        Some("number") => "f64",
+        Some("string") => "String",
+        Some("array") => match property.items.as_deref().and_then(|i| i.kind.as_deref()) {
+            Some("string") => "Vec<String>",
+            _ => "Vec<serde_json::Value>",
+        },
+        _ => "serde_json::Value",
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while",
+];
+
+/// "amd-sev" becomes amd_sev, keywords become raw identifiers
+fn field_ident(name: &str) -> String {
+    let ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", ident)
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{}", ident)
+    } else {
+        ident
+    }
+}
+
+/// "/nodes/{node}/qemu" becomes NODES_NODE_QEMU
+fn const_name(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            segment
+                .trim_matches(|c| c == '{' || c == '}')
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+#[path = "./codegen_test.rs"]
+mod codegen_test;
@@ -0,0 +1,144 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+
+    const APIDOC: &str = r#"// Generated by the API viewer
+const apiSchema = [
+   {
+      "path" : "/nodes",
+      "text" : "nodes",
+      "info" : { "GET" : { "description" : "Cluster node index." } },
+      "children" : [
+         {
+            "path" : "/nodes/{node}/qemu/{vmid}/config",
+            "text" : "config",
+            "leaf" : 1,
+            "info" : {
+               "GET" : {
+                  "returns" : {
+                     "type" : "object",
+                     "properties" : {
+                        "digest" : { "type" : "string", "description" : "SHA1 digest of the configuration." },
+                        "maxmem" : { "type" : "integer", "minimum" : 0, "optional" : 1 },
+                        "template" : { "type" : "boolean", "optional" : 1 }
+                     }
+                  }
+               },
+               "POST" : {
+                  "parameters" : {
+                     "type" : "object",
+                     "properties" : {
+                        "node" : { "type" : "string" },
+                        "vmid" : { "type" : "integer", "minimum" : 100 },
+                        "amd-sev" : { "type" : "string", "optional" : 1, "description" : "Secure Encrypted Virtualization (SEV) features by AMD CPUs" },
+                        "balloon" : { "type" : "integer", "minimum" : 0, "optional" : 1 },
+                        "ide[n]" : { "type" : "string", "optional" : 1, "description" : "Use volume as IDE hard disk or CD-ROM (n is 0 to 1)." },
+                        "net[n]" : { "type" : "string", "optional" : 1, "description" : "Specify network devices." },
+                        "type" : { "type" : "string" }
+                     }
+                  }
+               }
+            }
+         }
+      ]
+   }
+]
+;
+
+let method2cmd = { GET : 'get' };
+"#;
+
+    #[test]
+    fn test_parse_schema_from_apidoc() {
+        let nodes = parse_schema(APIDOC).unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert!(find_method(&nodes, "POST", "/nodes/{node}/qemu/{vmid}/config").is_some());
+        assert!(find_method(&nodes, "DELETE", "/nodes/{node}/qemu/{vmid}/config").is_none());
+
+        let paths = endpoint_paths(&nodes);
+        assert_eq!(
+            paths[1],
+            ("/nodes/{node}/qemu/{vmid}/config", vec!["GET", "POST"])
+        );
+
+        let json = &APIDOC[APIDOC.find('[').unwrap()..];
+        assert_eq!(parse_schema(json).unwrap().len(), 1);
+        assert!(matches!(
+            parse_schema("let x = 1;"),
+            Err(CodegenError::SchemaNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_generate_models() {
+        let nodes = parse_schema(APIDOC).unwrap();
+        let models = [
+            ModelSpec {
+                name: "QemuConfigParams",
+                method: "POST",
+                path: "/nodes/{node}/qemu/{vmid}/config",
+                source: ModelSource::Parameters,
+                exclude: &[],
+                index_ranges: &[],
+                types: &[],
+            },
+            ModelSpec {
+                name: "QemuConfigData",
+                method: "GET",
+                path: "/nodes/{node}/qemu/{vmid}/config",
+                source: ModelSource::Returns,
+                exclude: &[],
+                index_ranges: &[],
+                types: &[],
+            },
+        ];
+        let code = generate(&nodes, &models).unwrap();
+
+        assert!(code.contains(
+            "use crate::api::common::{deserialize_number_option, deserialize_proxmox_bool_option};"
+        ));
+        assert!(code.contains(
+            "    #[serde(rename = \"amd-sev\", skip_serializing_if = \"Option::is_none\")]\n    pub amd_sev: Option<String>,"
+        ));
+        assert!(code.contains("    pub balloon: Option<u64>,"));
+        assert!(code.contains("    pub ide0: Option<String>,"));
+        assert!(code.contains("    pub ide1: Option<String>,"));
+        assert!(!code.contains("ide2"));
+        assert!(code.contains("    // net[n] is indexed but documents no range"));
+        assert!(code.contains("    pub r#type: String,"));
+        // Path parameters stay out of the body
+        assert!(!code.contains("pub vmid"));
+        assert!(!code.contains("pub node"));
+
+        assert!(code.contains("    pub digest: Option<String>,"));
+        assert!(code.contains(
+            "    #[serde(default, deserialize_with = \"deserialize_number_option\", skip_serializing_if = \"Option::is_none\")]\n    pub maxmem: Option<u64>,"
+        ));
+        assert!(code.contains(
+            "    #[serde(default, deserialize_with = \"deserialize_proxmox_bool_option\", skip_serializing_if = \"Option::is_none\")]\n    pub template: Option<bool>,"
+        ));
+        assert!(code.contains(
+            "    /// GET, POST\n    pub const NODES_NODE_QEMU_VMID_CONFIG: &str = \"/nodes/{node}/qemu/{vmid}/config\";"
+        ));
+    }
+
+    #[test]
+    fn test_generate_rejects_unknown_endpoint() {
+        let nodes = parse_schema(APIDOC).unwrap();
+        let models = [ModelSpec {
+            name: "QemuCreateParams",
+            method: "POST",
+            path: "/nodes/{node}/qemu",
+            source: ModelSource::Parameters,
+            exclude: &[],
+            index_ranges: &[],
+            types: &[],
+        }];
+        let err = generate(&nodes, &models).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The API schema has no POST /nodes/{node}/qemu"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Renders the API models listed in `codegen::MODELS` from the Proxmox VE
+//! API schema checked in as `apidoc.json`:
+//!
+//! ```sh
+//! cargo run -p proxmox --example apigen -- proxmox/examples/apigen/apidoc.json \
+//!     > proxmox/src/api/generated.rs
+//! cargo fmt
+//! ```
+//!
+//! `apidoc.json` holds only the endpoints of the models. To pick up a new
+//! Proxmox release, or after adding a model, cut it from the API viewer's
+//! schema first:
+//!
+//! ```sh
+//! curl -O https://pve.proxmox.com/pve-docs/api-viewer/apidoc.js
+//! cargo run -p proxmox --example apigen -- extract apidoc.js \
+//!     > proxmox/examples/apigen/apidoc.json
+//! ```
+//!
+//! On a node the same file is at /usr/share/pve-docs/api-viewer/apidoc.js.
+
+mod codegen;
+
+use codegen::{extract, generate, parse_schema, MODELS};
+
+const USAGE: &str = "usage: apigen [extract] <apidoc.js|apidoc.json>";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [command, path] if command == "extract" => {
+            let source = std::fs::read_to_string(path)?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&extract(&source, MODELS)?)?
+            );
+        }
+        [path] => {
+            let source = std::fs::read_to_string(path)?;
+            print!("{}", generate(&parse_schema(&source)?, MODELS)?);
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_list_users_and_groups() {
+        let mut server = Server::new_async().await;
+        let _users = server
+            .mock("GET", "/api2/json/access/users")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"userid": "root@pam", "enable": 1, "expire": 0, "groups": ""},
+                    {"userid": "alice@pve", "enable": 0, "email": "alice@example.com", "groups": "admins,ops"}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+        let _groups = server
+            .mock("GET", "/api2/json/access/groups")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"groupid": "admins", "comment": "Admins", "users": "alice@pve,bob@pve"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let access = AccessApi::new(&client);
+
+        let users = access.users().list().await.unwrap();
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].realm(), "pam");
+        assert!(users[0].is_enabled());
+        assert!(users[0].groups.is_empty());
+        assert!(!users[1].is_enabled());
+        assert_eq!(users[1].groups, vec!["admins", "ops"]);
+
+        let groups = access.groups().list().await.unwrap();
+        assert_eq!(groups[0].groupid, "admins");
+        assert_eq!(groups[0].users, vec!["alice@pve", "bob@pve"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_roles_and_realms() {
+        let mut server = Server::new_async().await;
+        let _roles = server
+            .mock("GET", "/api2/json/access/roles")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"roleid": "PVEAuditor", "special": 1, "privs": "Sys.Audit,VM.Audit"},
+                    {"roleid": "Custom", "privs": "VM.PowerMgmt"}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+        let _realms = server
+            .mock("GET", "/api2/json/access/domains")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"realm": "pam", "type": "pam", "comment": "Linux PAM", "default": 1}, {"realm": "pve", "type": "pve"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let access = AccessApi::new(&client);
+
+        let roles = access.roles().list().await.unwrap();
+        assert!(roles[0].is_builtin());
+        assert_eq!(roles[0].privs, vec!["Sys.Audit", "VM.Audit"]);
+        assert!(!roles[1].is_builtin());
+
+        let realms = access.realms().list().await.unwrap();
+        assert_eq!(realms[0].default, Some(true));
+        assert_eq!(realms[1].default, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_ldap_realm() {
+        let mut server = Server::new_async().await;
+        let _realm = server
+            .mock("GET", "/api2/json/access/domains/corp")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"type": "ldap", "server1": "ldap.example.com", "port": "636",
+                    "mode": "ldaps", "verify": 1, "base_dn": "dc=example,dc=com",
+                    "user_attr": "uid", "bind_dn": "cn=pve,dc=example,dc=com",
+                    "sync-defaults-options": "scope=both,enable-new=0,remove-vanished=acl;entry",
+                    "case-sensitive": 0, "digest": "abc"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let realm = AccessApi::new(&client).realms().get("corp").await.unwrap();
+
+        assert_eq!(realm.realm_type, "ldap");
+        assert_eq!(realm.ldap.port, Some(636));
+        assert_eq!(realm.ldap.verify, Some(true));
+        assert_eq!(realm.ldap.case_sensitive, Some(false));
+        assert_eq!(realm.ldap.base_dn.as_deref(), Some("dc=example,dc=com"));
+        let options =
+            realms::RealmSyncOptions::parse(realm.ldap.sync_defaults_options.as_deref().unwrap());
+        assert_eq!(
+            options,
+            realms::RealmSyncOptions {
+                scope: Some("both".to_string()),
+                enable_new: Some(false),
+                remove_vanished: Some("acl;entry".to_string()),
+            }
+        );
+        assert_eq!(
+            options.to_property_string().as_deref(),
+            Some("scope=both,enable-new=0,remove-vanished=acl;entry")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_realm() {
+        let mut server = Server::new_async().await;
+        let sync = server
+            .mock("POST", "/api2/json/access/domains/corp/sync")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "scope": "users",
+                "remove-vanished": "none",
+                "dry-run": true
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve1:00001234:00000000:65000000:auth-realm-sync:corp:root@pam:"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let upid = AccessApi::new(&client)
+            .realms()
+            .sync(
+                "corp",
+                &realms::RealmSyncRequest {
+                    options: realms::RealmSyncOptions {
+                        scope: Some("users".to_string()),
+                        enable_new: None,
+                        remove_vanished: Some("none".to_string()),
+                    },
+                    dry_run: Some(true),
+                },
+            )
+            .await
+            .unwrap();
+
+        sync.assert_async().await;
+        assert_eq!(upid.node(), Some("pve1"));
+    }
+
+    #[tokio::test]
+    async fn test_create_ticket() {
+        let mut server = Server::new_async().await;
+        let ticket = server
+            .mock("POST", "/api2/json/access/ticket")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("username".into(), "ci@pve".into()),
+                Matcher::UrlEncoded("password".into(), "secret".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"username": "ci@pve", "ticket": "PVE:ci@pve:65000000::sig", "CSRFPreventionToken": "65000000:token"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let issued = AccessApi::new(&client)
+            .ticket()
+            .create(&ticket::CreateTicketRequest {
+                username: "ci@pve".to_string(),
+                password: "secret".to_string(),
+                otp: None,
+            })
+            .await
+            .unwrap();
+
+        ticket.assert_async().await;
+        assert_eq!(issued.ticket, "PVE:ci@pve:65000000::sig");
+        assert_eq!(issued.csrf_prevention_token, "65000000:token");
+        assert_eq!(issued.clustername, None);
+    }
+}
@@ -0,0 +1,32 @@
+//! Group API implementation
+
+use crate::api::common::deserialize_comma_list;
+use crate::api::{error::ApiError, Client};
+use serde::{Deserialize, Serialize};
+
+/// Group API (/access/groups)
+pub struct GroupsApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> GroupsApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/access/groups
+    pub async fn list(&self) -> Result<Vec<GroupInfo>, ApiError> {
+        self.client.get("/api2/json/access/groups").await
+    }
+}
+
+/// Group entry returned by the group listing
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GroupInfo {
+    pub groupid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Member user IDs
+    #[serde(deserialize_with = "deserialize_comma_list", default)]
+    pub users: Vec<String>,
+}
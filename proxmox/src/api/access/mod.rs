@@ -1,4 +1,9 @@
+pub mod groups;
 pub mod realms;
+pub mod roles;
+pub mod tfa;
+pub mod ticket;
+pub mod users;
 
 use crate::api::Client;
 
@@ -16,4 +21,33 @@ impl<'a> AccessApi<'a> {
     pub fn realms(&self) -> realms::RealmsApi<'a> {
         realms::RealmsApi::new(self.client)
     }
+
+    /// Access user operations
+    pub fn users(&self) -> users::UsersApi<'a> {
+        users::UsersApi::new(self.client)
+    }
+
+    /// Access group operations
+    pub fn groups(&self) -> groups::GroupsApi<'a> {
+        groups::GroupsApi::new(self.client)
+    }
+
+    /// Access role operations
+    pub fn roles(&self) -> roles::RolesApi<'a> {
+        roles::RolesApi::new(self.client)
+    }
+
+    /// Access two-factor authentication operations
+    pub fn tfa(&self) -> tfa::TfaApi<'a> {
+        tfa::TfaApi::new(self.client)
+    }
+
+    /// Access ticket operations
+    pub fn ticket(&self) -> ticket::TicketApi<'a> {
+        ticket::TicketApi::new(self.client)
+    }
 }
+
+#[cfg(test)]
+#[path = "./access_test.rs"]
+mod access_test;
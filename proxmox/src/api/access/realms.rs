@@ -1,10 +1,220 @@
 //! Realm (authentication domain) API implementation
 
-use super::super::common::{deserialize_proxmox_bool_option, ProxmoxApiResource};
+use super::super::common::{
+    deserialize_proxmox_bool_option, string_or_u32, ProxmoxApiResource, TaskId,
+};
+use super::super::propstring::PropertyString;
 use serde::{Deserialize, Serialize};
 
 pub type Realm = RealmConfig;
 
+/// Connection modes of LDAP and AD realms
+pub const LDAP_MODES: &[&str] = &["ldap", "ldaps", "ldap+starttls"];
+
+/// What a realm sync imports
+pub const REALM_SYNC_SCOPES: &[&str] = &["users", "groups", "both"];
+
+/// What a realm sync removes when it vanished from the directory
+pub const REALM_SYNC_REMOVE_VANISHED: &[&str] = &["acl", "entry", "properties"];
+
+/// LDAP and AD specific realm settings. Some names use underscores and
+/// others dashes, exactly as the API expects them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LdapRealmSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server2: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "string_or_u32",
+        default
+    )]
+    pub port: Option<u32>,
+    /// One of [`LDAP_MODES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub verify: Option<bool>,
+    /// Path to the CA certificate store on the nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capath: Option<String>,
+    /// Path to the client certificate on the nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert: Option<String>,
+    /// Path to the client certificate key on the nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certkey: Option<String>,
+    /// AD domain name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_dn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_attr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_dn: Option<String>,
+    /// Bind password. Never returned by the API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_classes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_dn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_classes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_name_attr: Option<String>,
+    /// Comma separated `key=attribute` pairs, e.g. "email=mail"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_attributes: Option<String>,
+    /// Property string with the defaults for syncing, see [`RealmSyncOptions`]
+    #[serde(
+        rename = "sync-defaults-options",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sync_defaults_options: Option<String>,
+    #[serde(
+        rename = "case-sensitive",
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub case_sensitive: Option<bool>,
+}
+
+impl LdapRealmSettings {
+    /// Settings that can be cleared with `delete`, as (API parameter, set)
+    pub fn optional_settings(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("server2", self.server2.is_some()),
+            ("port", self.port.is_some()),
+            ("mode", self.mode.is_some()),
+            ("verify", self.verify.is_some()),
+            ("capath", self.capath.is_some()),
+            ("cert", self.cert.is_some()),
+            ("certkey", self.certkey.is_some()),
+            ("bind_dn", self.bind_dn.is_some()),
+            ("password", self.password.is_some()),
+            ("filter", self.filter.is_some()),
+            ("user_classes", self.user_classes.is_some()),
+            ("group_dn", self.group_dn.is_some()),
+            ("group_filter", self.group_filter.is_some()),
+            ("group_classes", self.group_classes.is_some()),
+            ("group_name_attr", self.group_name_attr.is_some()),
+            ("sync_attributes", self.sync_attributes.is_some()),
+            (
+                "sync-defaults-options",
+                self.sync_defaults_options.is_some(),
+            ),
+            ("case-sensitive", self.case_sensitive.is_some()),
+        ]
+    }
+}
+
+/// Time-based one-time passwords enforced for all users of a realm, stored
+/// as the realm's `tfa` property string
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RealmTotp {
+    pub digits: Option<u32>,
+    /// Seconds a code is valid
+    pub step: Option<u32>,
+}
+
+impl RealmTotp {
+    /// Parses a `tfa` property string. Returns None unless it enforces TOTP
+    /// ("type=oath"), Yubico OTP is not modelled.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut totp = Self::default();
+        let mut oath = false;
+        for (key, val) in PropertyString::parse(value, "").params() {
+            match key {
+                "type" => oath = val == "oath",
+                "digits" => totp.digits = val.parse().ok(),
+                "step" => totp.step = val.parse().ok(),
+                _ => {}
+            }
+        }
+        oath.then_some(totp)
+    }
+
+    pub fn to_property_string(&self) -> String {
+        let mut value = "type=oath".to_string();
+        if let Some(digits) = self.digits {
+            value.push_str(&format!(",digits={}", digits));
+        }
+        if let Some(step) = self.step {
+            value.push_str(&format!(",step={}", step));
+        }
+        value
+    }
+}
+
+/// Options of a realm sync, sent with a sync request or stored as the
+/// realm's `sync-defaults-options` property string
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RealmSyncOptions {
+    /// One of [`REALM_SYNC_SCOPES`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(rename = "enable-new", skip_serializing_if = "Option::is_none")]
+    pub enable_new: Option<bool>,
+    /// Semicolon separated values of [`REALM_SYNC_REMOVE_VANISHED`], or "none"
+    #[serde(rename = "remove-vanished", skip_serializing_if = "Option::is_none")]
+    pub remove_vanished: Option<String>,
+}
+
+impl RealmSyncOptions {
+    /// Parses a `sync-defaults-options` property string like
+    /// "scope=both,enable-new=1,remove-vanished=acl;entry"
+    pub fn parse(value: &str) -> Self {
+        let mut options = Self::default();
+        for (key, val) in PropertyString::parse(value, "").params() {
+            match key {
+                "scope" => options.scope = Some(val.to_string()),
+                "enable-new" => options.enable_new = Some(val == "1" || val == "true"),
+                "remove-vanished" => options.remove_vanished = Some(val.to_string()),
+                _ => {}
+            }
+        }
+        options
+    }
+
+    /// Formats the options as a property string, or None if none is set
+    pub fn to_property_string(&self) -> Option<String> {
+        let mut parts = vec![];
+        if let Some(scope) = &self.scope {
+            parts.push(format!("scope={}", scope));
+        }
+        if let Some(enable_new) = self.enable_new {
+            parts.push(format!("enable-new={}", u8::from(enable_new)));
+        }
+        if let Some(remove_vanished) = &self.remove_vanished {
+            parts.push(format!("remove-vanished={}", remove_vanished));
+        }
+        (!parts.is_empty()).then(|| parts.join(","))
+    }
+}
+
+/// Request body for POST /api2/json/access/domains/{realm}/sync. Unset
+/// options fall back to the realm's `sync-defaults-options`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RealmSyncRequest {
+    #[serde(flatten)]
+    pub options: RealmSyncOptions,
+    /// Only report what would change
+    #[serde(rename = "dry-run", skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+}
+
 /// Realm configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RealmConfig {
@@ -13,8 +223,15 @@ pub struct RealmConfig {
     pub realm_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
     pub default: Option<bool>,
+    /// Second factor required for every user of the realm, see [`RealmTotp`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
 
     // OpenID specific fields
     #[serde(rename = "issuer-url", skip_serializing_if = "Option::is_none")]
@@ -25,12 +242,29 @@ pub struct RealmConfig {
     pub client_key: Option<String>,
     #[serde(rename = "username-claim", skip_serializing_if = "Option::is_none")]
     pub username_claim: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
     pub autocreate: Option<bool>,
-    #[serde(rename = "groups-overwrite", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "groups-overwrite",
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
     pub groups_overwrite: Option<bool>,
-    #[serde(rename = "groups-autocreate", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "groups-autocreate",
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
     pub groups_autocreate: Option<bool>,
+
+    #[serde(flatten)]
+    pub ldap: LdapRealmSettings,
 }
 
 /// Response from GET /api2/json/access/domains/{realm}
@@ -46,6 +280,8 @@ struct GetRealmResponse {
         default
     )]
     default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tfa: Option<String>,
 
     // OpenID specific fields
     #[serde(rename = "issuer-url", skip_serializing_if = "Option::is_none")]
@@ -77,6 +313,9 @@ struct GetRealmResponse {
     )]
     groups_autocreate: Option<bool>,
 
+    #[serde(flatten)]
+    ldap: LdapRealmSettings,
+
     // Extra field from API
     #[allow(dead_code)]
     digest: Option<String>,
@@ -92,6 +331,9 @@ pub struct CreateRealmRequest {
     pub comment: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<bool>,
+    /// Second factor required for every user of the realm, see [`RealmTotp`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
 
     // OpenID specific fields
     #[serde(rename = "issuer-url", skip_serializing_if = "Option::is_none")]
@@ -108,6 +350,9 @@ pub struct CreateRealmRequest {
     pub groups_overwrite: Option<bool>,
     #[serde(rename = "groups-autocreate", skip_serializing_if = "Option::is_none")]
     pub groups_autocreate: Option<bool>,
+
+    #[serde(flatten)]
+    pub ldap: LdapRealmSettings,
 }
 
 /// Request body for updating realms
@@ -119,6 +364,9 @@ pub struct UpdateRealmRequest {
     pub comment: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<bool>,
+    /// Second factor required for every user of the realm, see [`RealmTotp`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tfa: Option<String>,
 
     // OpenID specific fields
     #[serde(rename = "issuer-url", skip_serializing_if = "Option::is_none")]
@@ -135,6 +383,12 @@ pub struct UpdateRealmRequest {
     pub groups_overwrite: Option<bool>,
     #[serde(rename = "groups-autocreate", skip_serializing_if = "Option::is_none")]
     pub groups_autocreate: Option<bool>,
+
+    #[serde(flatten)]
+    pub ldap: LdapRealmSettings,
+    /// Comma separated list of settings to remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
 }
 
 impl ProxmoxApiResource for RealmConfig {
@@ -162,6 +416,7 @@ impl super::super::Client {
             realm_type: response.realm_type,
             comment: response.comment,
             default: response.default,
+            tfa: response.tfa,
             issuer_url: response.issuer_url,
             client_id: response.client_id,
             client_key: response.client_key,
@@ -169,6 +424,7 @@ impl super::super::Client {
             autocreate: response.autocreate,
             groups_overwrite: response.groups_overwrite,
             groups_autocreate: response.groups_autocreate,
+            ldap: response.ldap,
         })
     }
 
@@ -181,6 +437,7 @@ impl super::super::Client {
             realm_type: config.realm_type.clone(),
             comment: config.comment.clone(),
             default: config.default,
+            tfa: config.tfa.clone(),
             issuer_url: config.issuer_url.clone(),
             client_id: config.client_id.clone(),
             client_key: config.client_key.clone(),
@@ -188,6 +445,7 @@ impl super::super::Client {
             autocreate: config.autocreate,
             groups_overwrite: config.groups_overwrite,
             groups_autocreate: config.groups_autocreate,
+            ldap: config.ldap.clone(),
         };
 
         self.post::<(), _>(path, &request).await.map(|_| ())
@@ -201,6 +459,7 @@ impl super::super::Client {
             realm_type: config.realm_type.clone(),
             comment: config.comment.clone(),
             default: config.default,
+            tfa: config.tfa.clone(),
             issuer_url: config.issuer_url.clone(),
             client_id: config.client_id.clone(),
             client_key: config.client_key.clone(),
@@ -208,6 +467,8 @@ impl super::super::Client {
             autocreate: config.autocreate,
             groups_overwrite: config.groups_overwrite,
             groups_autocreate: config.groups_autocreate,
+            ldap: config.ldap.clone(),
+            delete: None,
         };
 
         self.put::<(), _>(&path, &request).await.map(|_| ())
@@ -245,6 +506,7 @@ impl<'a> RealmsApi<'a> {
             realm_type: response.realm_type,
             comment: response.comment,
             default: response.default,
+            tfa: response.tfa,
             issuer_url: response.issuer_url,
             client_id: response.client_id,
             client_key: response.client_key,
@@ -252,6 +514,7 @@ impl<'a> RealmsApi<'a> {
             autocreate: response.autocreate,
             groups_overwrite: response.groups_overwrite,
             groups_autocreate: response.groups_autocreate,
+            ldap: response.ldap,
         })
     }
 
@@ -282,4 +545,19 @@ impl<'a> RealmsApi<'a> {
             .await
             .map(|_| ())
     }
+
+    /// POST /api2/json/access/domains/{realm}/sync. Syncs users and groups
+    /// of an LDAP or AD realm in a task on the node the request went to.
+    pub async fn sync(
+        &self,
+        realm: &str,
+        request: &RealmSyncRequest,
+    ) -> Result<TaskId, super::super::ApiError> {
+        self.client
+            .post(
+                &format!("/api2/json/access/domains/{}/sync", realm),
+                request,
+            )
+            .await
+    }
 }
@@ -0,0 +1,42 @@
+//! Role API implementation
+
+use crate::api::common::{deserialize_comma_list, deserialize_proxmox_bool_option};
+use crate::api::{error::ApiError, Client};
+use serde::{Deserialize, Serialize};
+
+/// Role API (/access/roles)
+pub struct RolesApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> RolesApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/access/roles
+    pub async fn list(&self) -> Result<Vec<RoleInfo>, ApiError> {
+        self.client.get("/api2/json/access/roles").await
+    }
+}
+
+/// Role entry returned by the role listing
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RoleInfo {
+    pub roleid: String,
+    #[serde(deserialize_with = "deserialize_comma_list", default)]
+    pub privs: Vec<String>,
+    /// Built-in roles are marked special and cannot be modified
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub special: Option<bool>,
+}
+
+impl RoleInfo {
+    pub fn is_builtin(&self) -> bool {
+        self.special.unwrap_or(false)
+    }
+}
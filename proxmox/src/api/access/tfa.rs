@@ -0,0 +1,60 @@
+//! Two-factor authentication API implementation
+
+use crate::api::common::{deserialize_number_option, deserialize_proxmox_bool_option};
+use crate::api::{error::ApiError, Client};
+use serde::{Deserialize, Serialize};
+
+/// TFA API (/access/tfa)
+pub struct TfaApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> TfaApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/access/tfa
+    pub async fn list(&self) -> Result<Vec<UserTfa>, ApiError> {
+        self.client.get("/api2/json/access/tfa").await
+    }
+}
+
+/// Second factors registered by a user
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserTfa {
+    pub userid: String,
+    #[serde(default)]
+    pub entries: Vec<TfaEntry>,
+}
+
+impl UserTfa {
+    /// Whether the user registered a WebAuthn credential. Those are bound to
+    /// the configured relying party ID.
+    pub fn has_webauthn(&self) -> bool {
+        self.entries.iter().any(|e| e.entry_type == "webauthn")
+    }
+}
+
+/// A single second factor
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TfaEntry {
+    pub id: String,
+    /// One of totp, u2f, webauthn, recovery or yubico
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub created: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub enable: Option<bool>,
+}
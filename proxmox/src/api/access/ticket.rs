@@ -0,0 +1,49 @@
+//! Authentication ticket API implementation
+
+use crate::api::{error::ApiError, BodyEncoding, Client};
+use serde::{Deserialize, Serialize};
+
+/// Tickets are valid for two hours after they were issued
+pub const TICKET_LIFETIME_SECS: u64 = 2 * 60 * 60;
+
+/// Ticket API (/access/ticket)
+pub struct TicketApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> TicketApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// POST /api2/json/access/ticket
+    pub async fn create(&self, request: &CreateTicketRequest) -> Result<Ticket, ApiError> {
+        self.client
+            .post_with_encoding("/api2/json/access/ticket", request, BodyEncoding::Form)
+            .await
+    }
+}
+
+/// Credentials exchanged for a ticket
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateTicketRequest {
+    /// User ID in the form name@realm
+    pub username: String,
+    pub password: String,
+    /// One-time password for users with a second factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub otp: Option<String>,
+}
+
+/// Ticket issued by /access/ticket
+#[derive(Debug, Clone, Deserialize)]
+pub struct Ticket {
+    pub username: String,
+    /// Sent as the PVEAuthCookie cookie
+    pub ticket: String,
+    /// Required as the CSRFPreventionToken header on writes
+    #[serde(rename = "CSRFPreventionToken")]
+    pub csrf_prevention_token: String,
+    #[serde(default)]
+    pub clustername: Option<String>,
+}
@@ -0,0 +1,65 @@
+//! User API implementation
+
+use crate::api::common::{
+    deserialize_comma_list, deserialize_number_option, deserialize_proxmox_bool_option,
+};
+use crate::api::{error::ApiError, Client};
+use serde::{Deserialize, Serialize};
+
+/// User API (/access/users)
+pub struct UsersApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> UsersApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/access/users
+    pub async fn list(&self) -> Result<Vec<UserInfo>, ApiError> {
+        self.client.get("/api2/json/access/users").await
+    }
+}
+
+/// User entry returned by the user listing
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserInfo {
+    /// User ID in the form name@realm
+    pub userid: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub enable: Option<bool>,
+    /// Account expiration as a unix timestamp, 0 for never
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub expire: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub firstname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lastname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(deserialize_with = "deserialize_comma_list", default)]
+    pub groups: Vec<String>,
+}
+
+impl UserInfo {
+    /// The realm part of the user ID
+    pub fn realm(&self) -> &str {
+        self.userid.rsplit_once('@').map_or("", |(_, realm)| realm)
+    }
+
+    /// Users are enabled unless explicitly disabled
+    pub fn is_enabled(&self) -> bool {
+        self.enable.unwrap_or(true)
+    }
+}
@@ -1,20 +1,29 @@
-use reqwest::header::AUTHORIZATION;
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use super::common::{ApiErrorDetails, ApiErrorResponse, ApiQueryParams, ApiResponse};
+use super::common::{ApiQueryParams, ApiResponse};
 use super::error::ApiError;
 use super::pool::{ConnectionPoolConfig, ConnectionPoolManager};
 
+/// User-Agent sent with every API request
+pub const DEFAULT_USER_AGENT: &str =
+    concat!("terraform-provider-proxmox/", env!("CARGO_PKG_VERSION"));
+
 /// Proxmox API client
 #[derive(Clone)]
 pub struct Client {
     inner: Arc<ClientInner>,
+    user_agent: String,
 }
 
 struct ClientInner {
     http_client: reqwest::Client,
-    base_url: String,
+    /// API URLs of the cluster nodes, any of which serves the cluster API
+    base_urls: Vec<String>,
+    /// Index into base_urls of the node requests go to first
+    active_url: AtomicUsize,
     auth_header: String,
     retry_config: RetryConfig,
     pool_manager: ConnectionPoolManager,
@@ -39,12 +48,38 @@ impl Default for RetryConfig {
     }
 }
 
+/// How request bodies are encoded
+///
+/// Most endpoints accept JSON, but some fields such as cloud-init SSH keys
+/// are handled differently by pveproxy depending on the encoding. Form
+/// encoding matches what the Proxmox web UI and pvesh send.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyEncoding {
+    #[default]
+    Json,
+    /// application/x-www-form-urlencoded
+    Form,
+}
+
+impl BodyEncoding {
+    fn apply<B: Serialize + ?Sized>(
+        self,
+        builder: reqwest::RequestBuilder,
+        body: &B,
+    ) -> reqwest::RequestBuilder {
+        match self {
+            BodyEncoding::Json => builder.json(body),
+            BodyEncoding::Form => builder.form(body),
+        }
+    }
+}
+
 impl Client {
     /// Execute a GET request and expect no data wrapper
     pub async fn get_raw<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T, ApiError> {
         self.execute_with_retry(
-            || async {
-                let url = format!("{}{}", self.inner.base_url, path);
+            |base_url| async move {
+                let url = format!("{}{}", base_url, path);
 
                 tracing::debug!("GET request to: {}", url);
 
@@ -52,6 +87,7 @@ impl Client {
                     .http_client
                     .get(&url)
                     .header(AUTHORIZATION, &self.inner.auth_header)
+                    .header(USER_AGENT, &self.user_agent)
                     .send()
                     .await
             },
@@ -82,33 +118,95 @@ impl Client {
         insecure: bool,
         retry_config: RetryConfig,
     ) -> Result<Self, ApiError> {
+        Self::with_pool_config(
+            endpoint,
+            api_token,
+            insecure,
+            retry_config,
+            ConnectionPoolConfig::default(),
+        )
+    }
+
+    /// Create a new API client with custom retry and connection pool
+    /// configuration. The request timeout is taken from the retry
+    /// configuration.
+    pub fn with_pool_config(
+        endpoint: &str,
+        api_token: &str,
+        insecure: bool,
+        retry_config: RetryConfig,
+        pool_config: ConnectionPoolConfig,
+    ) -> Result<Self, ApiError> {
+        Self::with_endpoints(
+            &[endpoint.to_string()],
+            api_token,
+            insecure,
+            retry_config,
+            pool_config,
+        )
+    }
+
+    /// Create a new API client for a cluster reachable through several
+    /// endpoints. Requests go to the first endpoint until it can't be
+    /// connected to, then fail over to the next one.
+    pub fn with_endpoints(
+        endpoints: &[String],
+        api_token: &str,
+        insecure: bool,
+        retry_config: RetryConfig,
+        pool_config: ConnectionPoolConfig,
+    ) -> Result<Self, ApiError> {
+        if endpoints.is_empty() {
+            return Err(ApiError::InvalidConfig(
+                "At least one endpoint is required".to_string(),
+            ));
+        }
+
         let pool_config = ConnectionPoolConfig {
             request_timeout: std::time::Duration::from_secs(retry_config.timeout_seconds),
-            ..Default::default()
+            ..pool_config
         };
 
         let pool_manager = ConnectionPoolManager::new(pool_config);
         let http_client = pool_manager.build_client(insecure)?;
 
-        let base_url = endpoint.trim_end_matches('/').to_string();
+        let base_urls = endpoints
+            .iter()
+            .map(|endpoint| endpoint.trim_end_matches('/').to_string())
+            .collect();
         let auth_header = format!("PVEAPIToken={}", api_token);
 
         Ok(Self {
             inner: Arc::new(ClientInner {
                 http_client,
-                base_url,
+                base_urls,
+                active_url: AtomicUsize::new(0),
                 auth_header,
                 retry_config,
                 pool_manager,
             }),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
         })
     }
 
+    /// The same client, tagging its requests with the Terraform module they
+    /// are made for. Connections and failover state stay shared.
+    pub fn with_module(&self, module_name: &str) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            user_agent: format!("{} module/{}", DEFAULT_USER_AGENT, module_name),
+        }
+    }
+
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
     /// Execute a GET request with retry logic
     pub async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T, ApiError> {
         self.execute_with_retry(
-            || async {
-                let url = format!("{}{}", self.inner.base_url, path);
+            |base_url| async move {
+                let url = format!("{}{}", base_url, path);
 
                 tracing::debug!("GET request to: {}", url);
 
@@ -116,6 +214,7 @@ impl Client {
                     .http_client
                     .get(&url)
                     .header(AUTHORIZATION, &self.inner.auth_header)
+                    .header(USER_AGENT, &self.user_agent)
                     .send()
                     .await
             },
@@ -130,18 +229,28 @@ impl Client {
         path: &str,
         body: &B,
     ) -> Result<T, ApiError> {
-        let body_clone = body;
+        self.post_with_encoding(path, body, BodyEncoding::Json)
+            .await
+    }
+
+    /// Execute a POST request with the given body encoding
+    pub async fn post_with_encoding<T: for<'de> Deserialize<'de>, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+        encoding: BodyEncoding,
+    ) -> Result<T, ApiError> {
         self.execute_with_retry(
-            || async {
-                let url = format!("{}{}", self.inner.base_url, path);
+            |base_url| async move {
+                let url = format!("{}{}", base_url, path);
 
-                self.inner
+                let request = self
+                    .inner
                     .http_client
                     .post(&url)
                     .header(AUTHORIZATION, &self.inner.auth_header)
-                    .json(body_clone)
-                    .send()
-                    .await
+                    .header(USER_AGENT, &self.user_agent);
+                encoding.apply(request, body).send().await
             },
             path,
         )
@@ -154,18 +263,27 @@ impl Client {
         path: &str,
         body: &B,
     ) -> Result<T, ApiError> {
-        let body_clone = body;
+        self.put_with_encoding(path, body, BodyEncoding::Json).await
+    }
+
+    /// Execute a PUT request with the given body encoding
+    pub async fn put_with_encoding<T: for<'de> Deserialize<'de>, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+        encoding: BodyEncoding,
+    ) -> Result<T, ApiError> {
         self.execute_with_retry(
-            || async {
-                let url = format!("{}{}", self.inner.base_url, path);
+            |base_url| async move {
+                let url = format!("{}{}", base_url, path);
 
-                self.inner
+                let request = self
+                    .inner
                     .http_client
                     .put(&url)
                     .header(AUTHORIZATION, &self.inner.auth_header)
-                    .json(body_clone)
-                    .send()
-                    .await
+                    .header(USER_AGENT, &self.user_agent);
+                encoding.apply(request, body).send().await
             },
             path,
         )
@@ -182,6 +300,21 @@ impl Client {
         crate::api::access::AccessApi::new(self)
     }
 
+    /// Cluster API operations
+    pub fn cluster(&self) -> crate::api::cluster::ClusterApi<'_> {
+        crate::api::cluster::ClusterApi::new(self)
+    }
+
+    /// Resource pool API operations
+    pub fn pools(&self) -> crate::api::pools::PoolsApi<'_> {
+        crate::api::pools::PoolsApi::new(self)
+    }
+
+    /// Storage configuration API operations
+    pub fn storage(&self) -> crate::api::storage::StorageConfigApi<'_> {
+        crate::api::storage::StorageConfigApi::new(self)
+    }
+
     /// Nodes API operations
     pub fn nodes(&self) -> crate::api::nodes::NodesApi<'_> {
         crate::api::nodes::NodesApi::new(self)
@@ -190,13 +323,14 @@ impl Client {
     /// Execute a DELETE request with retry logic
     pub async fn delete<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T, ApiError> {
         self.execute_with_retry(
-            || async {
-                let url = format!("{}{}", self.inner.base_url, path);
+            |base_url| async move {
+                let url = format!("{}{}", base_url, path);
 
                 self.inner
                     .http_client
                     .delete(&url)
                     .header(AUTHORIZATION, &self.inner.auth_header)
+                    .header(USER_AGENT, &self.user_agent)
                     .send()
                     .await
             },
@@ -205,10 +339,47 @@ impl Client {
         .await
     }
 
+    /// Sends a request to the active endpoint. When it can't be connected
+    /// to, the request is sent to the other endpoints in turn, and the first
+    /// one that answers becomes the active endpoint.
+    async fn send_with_failover<F, Fut>(
+        &self,
+        request_fn: &F,
+    ) -> Result<reqwest::Response, reqwest::Error>
+    where
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
+    {
+        let base_urls = &self.inner.base_urls;
+        let cannot_connect = |result: &Result<reqwest::Response, reqwest::Error>| matches!(result, Err(e) if e.is_connect());
+
+        let start = self.inner.active_url.load(Ordering::Relaxed);
+        let mut index = start;
+        let mut result = request_fn(base_urls[index].clone()).await;
+        for _ in 1..base_urls.len() {
+            if !cannot_connect(&result) {
+                break;
+            }
+            let next = (index + 1) % base_urls.len();
+            tracing::warn!(
+                "Cannot connect to {}, failing over to {}",
+                base_urls[index],
+                base_urls[next]
+            );
+            index = next;
+            result = request_fn(base_urls[index].clone()).await;
+        }
+        if index != start && !cannot_connect(&result) {
+            self.inner.active_url.store(index, Ordering::Relaxed);
+        }
+
+        result
+    }
+
     /// Execute request with retry logic
     async fn execute_with_retry<F, Fut, T>(&self, request_fn: F, path: &str) -> Result<T, ApiError>
     where
-        F: Fn() -> Fut,
+        F: Fn(String) -> Fut,
         Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
         T: for<'de> Deserialize<'de>,
     {
@@ -230,7 +401,11 @@ impl Client {
                 tokio::time::sleep(tokio::time::Duration::from_millis(backoff)).await;
             }
 
-            match request_fn().await {
+            // Held until this attempt's response is handled, but not across
+            // the backoff so waiting retries don't block other requests
+            let _slot = self.inner.pool_manager.acquire_request_slot().await;
+
+            match self.send_with_failover(&request_fn).await {
                 Ok(response) => {
                     let status = response.status();
 
@@ -241,17 +416,11 @@ impl Client {
 
                     self.inner.pool_manager.record_request(false).await;
 
-                    if status == reqwest::StatusCode::UNAUTHORIZED {
-                        return Err(ApiError::AuthError);
-                    }
-
-                    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                        last_error = Some(ApiError::RateLimited);
-                    } else if status.is_server_error() {
-                        last_error = Some(ApiError::ServiceUnavailable);
-                    } else {
-                        return self.handle_error_response(response).await;
+                    let error = self.error_from_response(response).await;
+                    if !error.is_retriable() {
+                        return Err(error);
                     }
+                    last_error = Some(error);
                 }
                 Err(e) => {
                     self.inner.pool_manager.record_request(false).await;
@@ -296,26 +465,14 @@ impl Client {
         }
     }
 
-    /// Handle error response
-    async fn handle_error_response<T>(&self, response: reqwest::Response) -> Result<T, ApiError> {
+    /// Classify an error response
+    async fn error_from_response(&self, response: reqwest::Response) -> ApiError {
         let status = response.status().as_u16();
         let text = response
             .text()
             .await
             .unwrap_or_else(|_| "Unknown error".to_string());
 
-        let details = match serde_json::from_str::<ApiErrorResponse>(&text) {
-            Ok(err_resp) => Some(Box::new(ApiErrorDetails {
-                errors: err_resp.errors,
-                field_errors: err_resp.data,
-            })),
-            Err(_) => None,
-        };
-
-        Err(ApiError::ApiError {
-            status,
-            message: text,
-            details,
-        })
+        ApiError::from_response(status, &text)
     }
 }
@@ -0,0 +1,308 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_cluster_resources_vm_filter() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {
+                        "id": "qemu/100",
+                        "type": "qemu",
+                        "node": "pve1",
+                        "vmid": 100,
+                        "name": "web-1",
+                        "status": "running",
+                        "pool": "prod",
+                        "tags": "web;prod",
+                        "template": 0
+                    },
+                    {
+                        "id": "lxc/200",
+                        "type": "lxc",
+                        "node": "pve2",
+                        "vmid": 200,
+                        "name": "ct-1",
+                        "status": "stopped"
+                    }
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let resources = ClusterApi::new(&client)
+            .resources(Some(ClusterResourceType::Vm))
+            .await
+            .unwrap();
+
+        assert_eq!(resources.len(), 2);
+        assert!(resources[0].is_qemu());
+        assert!(!resources[1].is_qemu());
+        assert_eq!(resources[0].template, Some(false));
+        assert_eq!(resources[0].tag_list(), vec!["web", "prod"]);
+        assert!(resources[1].tag_list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_status() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/status")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"id": "cluster", "type": "cluster", "name": "homelab", "nodes": 2, "quorate": 1, "version": 5},
+                    {"id": "node/pve1", "type": "node", "name": "pve1", "nodeid": 1, "ip": "10.0.0.1", "online": 1, "local": 1, "level": ""},
+                    {"id": "node/pve2", "type": "node", "name": "pve2", "nodeid": 2, "ip": "10.0.0.2", "online": 0, "local": 0, "level": ""}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let status = ClusterApi::new(&client).status().await.unwrap();
+
+        assert_eq!(status.len(), 3);
+        assert!(status[0].is_cluster());
+        assert_eq!(status[0].quorate, Some(true));
+        assert_eq!(status[0].nodes, Some(2));
+        assert!(status[2].is_node());
+        assert_eq!(status[2].online, Some(false));
+        assert_eq!(status[1].ip.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_ha_current_status() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/ha/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"id": "quorum", "type": "quorum", "node": "pve1", "status": "OK", "quorate": 1},
+                    {"id": "master", "type": "master", "node": "pve1", "status": "pve1 (active, Fri Oct 16 10:00:00 2026)"},
+                    {"id": "service:vm:100", "type": "service", "sid": "vm:100", "node": "pve2", "state": "started", "crm_state": "started", "request_state": "started", "status": "vm:100 (pve2, started)"}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let status = ClusterApi::new(&client)
+            .ha()
+            .current_status()
+            .await
+            .unwrap();
+
+        assert_eq!(status.len(), 3);
+        assert!(status[0].is_type("quorum"));
+        assert_eq!(status[0].quorate, Some(true));
+        assert_eq!(status[2].sid.as_deref(), Some("vm:100"));
+        assert_eq!(status[2].crm_state.as_deref(), Some("started"));
+    }
+
+    #[tokio::test]
+    async fn test_ha_resource_and_relocate() {
+        let mut server = Server::new_async().await;
+        let _get = server
+            .mock("GET", "/api2/json/cluster/ha/resources/vm:100")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"sid": "vm:100", "type": "vm", "state": "started", "max_restart": "2", "digest": "abc"}}"#,
+            )
+            .create_async()
+            .await;
+        let relocate = server
+            .mock("POST", "/api2/json/cluster/ha/resources/vm:100/relocate")
+            .match_body(Matcher::Json(serde_json::json!({"node": "pve2"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let ha = ClusterApi::new(&client).ha();
+        let resource = ha.get_resource("vm:100").await.unwrap();
+        ha.relocate_resource("vm:100", "pve2").await.unwrap();
+
+        assert_eq!(resource.resource_type.as_deref(), Some("vm"));
+        assert_eq!(resource.state.as_deref(), Some("started"));
+        assert_eq!(resource.max_restart, Some(2));
+        assert_eq!(resource.max_relocate, None);
+        relocate.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_dir_mapping() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/mapping/dir/share")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": {
+                    "description": "Shared datasets",
+                    "digest": "3f1c0e",
+                    "map": ["node=pve1,path=/srv/share", "node=pve2,path=/mnt/share"]
+                }
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let mapping = ClusterApi::new(&client)
+            .mapping()
+            .get_dir("share")
+            .await
+            .unwrap();
+
+        assert_eq!(mapping.description.as_deref(), Some("Shared datasets"));
+        assert_eq!(
+            mapping.entries(),
+            vec![
+                DirMapEntry {
+                    node: "pve1".to_string(),
+                    path: "/srv/share".to_string(),
+                },
+                DirMapEntry {
+                    node: "pve2".to_string(),
+                    path: "/mnt/share".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_dir_mapping() {
+        let mut server = Server::new_async().await;
+        let m = server
+            .mock("POST", "/api2/json/cluster/mapping/dir")
+            .match_body(Matcher::Json(serde_json::json!({
+                "id": "share",
+                "map": ["node=pve1,path=/srv/share"]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let entry = DirMapEntry {
+            node: "pve1".to_string(),
+            path: "/srv/share".to_string(),
+        };
+        ClusterApi::new(&client)
+            .mapping()
+            .create_dir(&CreateDirMappingRequest {
+                id: "share".to_string(),
+                description: None,
+                map: vec![entry.to_property_string()],
+            })
+            .await
+            .unwrap();
+
+        m.assert_async().await;
+    }
+
+    #[test]
+    fn test_dir_map_entry_needs_node_and_path() {
+        assert_eq!(
+            DirMapEntry::parse("path=/srv/share,node=pve1"),
+            Some(DirMapEntry {
+                node: "pve1".to_string(),
+                path: "/srv/share".to_string(),
+            })
+        );
+        assert_eq!(DirMapEntry::parse("node=pve1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_cluster_numbers_links() {
+        let mut server = Server::new_async().await;
+        let m = server
+            .mock("POST", "/api2/json/cluster/config")
+            .match_body(Matcher::Json(serde_json::json!({
+                "clustername": "lab",
+                "link0": "10.0.0.11",
+                "link1": "10.1.0.11"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve1:00001234:00005678:65000000:clustercreate:lab:root@pam:"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let upid = client
+            .cluster()
+            .config()
+            .create(&CreateClusterRequest {
+                clustername: "lab".to_string(),
+                links: cluster_links(&["10.0.0.11".to_string(), "10.1.0.11".to_string()]),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        m.assert_async().await;
+        assert_eq!(upid.node(), Some("pve1"));
+    }
+
+    #[tokio::test]
+    async fn test_cluster_join_info() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/config/join")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {
+                    "config_digest": "2a5e1f",
+                    "preferred_node": "pve1",
+                    "nodelist": [
+                        {"name": "pve2", "nodeid": "2", "pve_addr": "10.0.0.12", "pve_fp": "BB:22", "quorum_votes": "1", "ring0_addr": "10.0.0.12"},
+                        {"name": "pve1", "nodeid": "1", "pve_addr": "10.0.0.11", "pve_fp": "AA:11", "quorum_votes": "1", "ring0_addr": "10.0.0.11"}
+                    ],
+                    "totem": {"cluster_name": "lab", "version": "2"}
+                }}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let info = client.cluster().config().join_info(None).await.unwrap();
+
+        let preferred = info.preferred().unwrap();
+        assert_eq!(preferred.name, "pve1");
+        assert_eq!(preferred.pve_addr.as_deref(), Some("10.0.0.11"));
+        assert_eq!(preferred.pve_fp.as_deref(), Some("AA:11"));
+    }
+}
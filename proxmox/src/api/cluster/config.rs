@@ -0,0 +1,119 @@
+//! Cluster creation and joining under /cluster/config
+
+use crate::api::{
+    common::{ApiQueryParams, TaskId},
+    error::ApiError,
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Corosync supports up to eight links, link0 to link7
+pub const MAX_CLUSTER_LINKS: usize = 8;
+
+/// Cluster configuration API
+pub struct ClusterConfigApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> ClusterConfigApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// POST /api2/json/cluster/config. Creates a cluster with the node the
+    /// client talks to as its only member.
+    pub async fn create(&self, request: &CreateClusterRequest) -> Result<TaskId, ApiError> {
+        self.client.post("/api2/json/cluster/config", request).await
+    }
+
+    /// GET /api2/json/cluster/config/join. What a node needs to join the
+    /// cluster through `node`, or through the node the client talks to.
+    pub async fn join_info(&self, node: Option<&str>) -> Result<ClusterJoinInfo, ApiError> {
+        let params = ApiQueryParams::new().add_optional("node", node);
+        self.client
+            .get_with_params("/api2/json/cluster/config/join", &params)
+            .await
+    }
+
+    /// POST /api2/json/cluster/config/join. Joins the node the client talks
+    /// to into the cluster of `request.hostname`.
+    pub async fn join(&self, request: &JoinClusterRequest) -> Result<TaskId, ApiError> {
+        self.client
+            .post("/api2/json/cluster/config/join", request)
+            .await
+    }
+}
+
+/// Request body for POST /cluster/config
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateClusterRequest {
+    pub clustername: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodeid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub votes: Option<u32>,
+    /// link0 to link7, see [`cluster_links`]
+    #[serde(flatten)]
+    pub links: BTreeMap<String, String>,
+}
+
+/// Request body for POST /cluster/config/join
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JoinClusterRequest {
+    /// Address of the cluster node to join through
+    pub hostname: String,
+    /// SHA-256 fingerprint of that node's API certificate
+    pub fingerprint: String,
+    /// root@pam password of that node
+    pub password: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodeid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub votes: Option<u32>,
+    /// Join even if the node already has guests or a corosync config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+    /// link0 to link7, see [`cluster_links`]
+    #[serde(flatten)]
+    pub links: BTreeMap<String, String>,
+}
+
+/// Numbers corosync link addresses in order, the first becoming link0
+pub fn cluster_links(addresses: &[String]) -> BTreeMap<String, String> {
+    addresses
+        .iter()
+        .enumerate()
+        .map(|(i, address)| (format!("link{}", i), address.clone()))
+        .collect()
+}
+
+/// Join information from GET /cluster/config/join
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterJoinInfo {
+    #[serde(default)]
+    pub nodelist: Vec<ClusterJoinNode>,
+    /// The node the information is for
+    pub preferred_node: String,
+}
+
+impl ClusterJoinInfo {
+    /// The entry of the preferred node
+    pub fn preferred(&self) -> Option<&ClusterJoinNode> {
+        self.nodelist
+            .iter()
+            .find(|node| node.name == self.preferred_node)
+    }
+}
+
+/// A cluster member as listed in the join information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterJoinNode {
+    pub name: String,
+    /// Address of the node's API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pve_addr: Option<String>,
+    /// SHA-256 fingerprint of the node's API certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pve_fp: Option<String>,
+}
@@ -0,0 +1,187 @@
+//! High availability API under /cluster/ha
+
+use crate::api::{
+    common::{deserialize_number_option, deserialize_proxmox_bool_option},
+    error::ApiError,
+    Client,
+};
+use serde::{Deserialize, Serialize};
+
+/// HA manager API
+pub struct HaApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> HaApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/cluster/ha/status/current
+    pub async fn current_status(&self) -> Result<Vec<HaStatusEntry>, ApiError> {
+        self.client
+            .get("/api2/json/cluster/ha/status/current")
+            .await
+    }
+
+    /// GET /api2/json/cluster/ha/resources
+    pub async fn list_resources(&self) -> Result<Vec<HaResource>, ApiError> {
+        self.client.get("/api2/json/cluster/ha/resources").await
+    }
+
+    /// GET /api2/json/cluster/ha/resources/{sid}
+    pub async fn get_resource(&self, sid: &str) -> Result<HaResource, ApiError> {
+        let path = format!("/api2/json/cluster/ha/resources/{}", sid);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/cluster/ha/resources
+    pub async fn create_resource(&self, request: &CreateHaResourceRequest) -> Result<(), ApiError> {
+        let _: serde_json::Value = self
+            .client
+            .post("/api2/json/cluster/ha/resources", request)
+            .await?;
+        Ok(())
+    }
+
+    /// PUT /api2/json/cluster/ha/resources/{sid}
+    pub async fn update_resource(
+        &self,
+        sid: &str,
+        request: &UpdateHaResourceRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!("/api2/json/cluster/ha/resources/{}", sid);
+        let _: serde_json::Value = self.client.put(&path, request).await?;
+        Ok(())
+    }
+
+    /// DELETE /api2/json/cluster/ha/resources/{sid}. The guest itself is
+    /// left as it is.
+    pub async fn delete_resource(&self, sid: &str) -> Result<(), ApiError> {
+        let path = format!("/api2/json/cluster/ha/resources/{}", sid);
+        let _: serde_json::Value = self.client.delete(&path).await?;
+        Ok(())
+    }
+
+    /// POST /api2/json/cluster/ha/resources/{sid}/migrate. Only queues the
+    /// request, the CRM carries it out and reports progress in the status.
+    pub async fn migrate_resource(&self, sid: &str, node: &str) -> Result<(), ApiError> {
+        let path = format!("/api2/json/cluster/ha/resources/{}/migrate", sid);
+        let _: serde_json::Value = self.client.post(&path, &HaMoveRequest { node }).await?;
+        Ok(())
+    }
+
+    /// POST /api2/json/cluster/ha/resources/{sid}/relocate. Like a
+    /// migration, but the guest is stopped and started on the target node.
+    pub async fn relocate_resource(&self, sid: &str, node: &str) -> Result<(), ApiError> {
+        let path = format!("/api2/json/cluster/ha/resources/{}/relocate", sid);
+        let _: serde_json::Value = self.client.post(&path, &HaMoveRequest { node }).await?;
+        Ok(())
+    }
+}
+
+/// Guest managed by the HA manager, as configured
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct HaResource {
+    /// Service ID such as "vm:100"
+    pub sid: String,
+    /// Requested state: started, stopped, disabled or ignored
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub max_restart: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub max_relocate: Option<u32>,
+    /// vm or ct
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+/// Request body for POST /api2/json/cluster/ha/resources
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateHaResourceRequest {
+    pub sid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_restart: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_relocate: Option<u32>,
+}
+
+/// Request body for PUT /api2/json/cluster/ha/resources/{sid}
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateHaResourceRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_restart: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_relocate: Option<u32>,
+    /// Comma separated list of options to clear
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+}
+
+/// Request body for the migrate and relocate endpoints
+#[derive(Debug, Serialize)]
+struct HaMoveRequest<'b> {
+    node: &'b str,
+}
+
+/// Item in the HA manager status. The type is one of quorum, master, lrm
+/// (one per node) or service (one per HA managed guest).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HaStatusEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    /// Human readable status line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub quorate: Option<bool>,
+    /// Service ID such as "vm:100", only set on service entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crm_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_state: Option<String>,
+}
+
+impl HaStatusEntry {
+    pub fn is_type(&self, entry_type: &str) -> bool {
+        self.entry_type == entry_type
+    }
+}
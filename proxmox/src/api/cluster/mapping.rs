@@ -0,0 +1,130 @@
+//! Resource mappings under /cluster/mapping
+
+use crate::api::{error::ApiError, propstring::PropertyString, Client};
+use serde::{Deserialize, Serialize};
+
+/// Resource mapping API. Mappings give a host resource a cluster-wide ID
+/// that guests refer to, with the node-specific details kept per node.
+pub struct MappingApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> MappingApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/cluster/mapping/dir
+    pub async fn list_dirs(&self) -> Result<Vec<DirMapping>, ApiError> {
+        self.client.get("/api2/json/cluster/mapping/dir").await
+    }
+
+    /// GET /api2/json/cluster/mapping/dir/{id}
+    pub async fn get_dir(&self, id: &str) -> Result<DirMapping, ApiError> {
+        let path = format!("/api2/json/cluster/mapping/dir/{}", id);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/cluster/mapping/dir
+    pub async fn create_dir(&self, request: &CreateDirMappingRequest) -> Result<(), ApiError> {
+        let _: serde_json::Value = self
+            .client
+            .post("/api2/json/cluster/mapping/dir", request)
+            .await?;
+        Ok(())
+    }
+
+    /// PUT /api2/json/cluster/mapping/dir/{id}
+    pub async fn update_dir(
+        &self,
+        id: &str,
+        request: &UpdateDirMappingRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!("/api2/json/cluster/mapping/dir/{}", id);
+        let _: serde_json::Value = self.client.put(&path, request).await?;
+        Ok(())
+    }
+
+    /// DELETE /api2/json/cluster/mapping/dir/{id}. Guests still referring
+    /// to the mapping fail to start afterwards.
+    pub async fn delete_dir(&self, id: &str) -> Result<(), ApiError> {
+        let path = format!("/api2/json/cluster/mapping/dir/{}", id);
+        let _: serde_json::Value = self.client.delete(&path).await?;
+        Ok(())
+    }
+}
+
+/// Directory mapping, used by virtiofs shares of VMs. PVE 8.4 and newer.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DirMapping {
+    /// Only returned when listing mappings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// One property string per node, see [`DirMapEntry`]
+    #[serde(default)]
+    pub map: Vec<String>,
+}
+
+impl DirMapping {
+    /// The per node entries, skipping any that don't parse
+    pub fn entries(&self) -> Vec<DirMapEntry> {
+        self.map
+            .iter()
+            .filter_map(|entry| DirMapEntry::parse(entry))
+            .collect()
+    }
+}
+
+/// Request body for POST /api2/json/cluster/mapping/dir
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateDirMappingRequest {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub map: Vec<String>,
+}
+
+/// Request body for PUT /api2/json/cluster/mapping/dir/{id}. The map
+/// replaces every entry.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateDirMappingRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub map: Vec<String>,
+    /// Comma separated list of options to clear
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+}
+
+/// Directory a mapping points at on one node
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DirMapEntry {
+    pub node: String,
+    /// Absolute path of the directory on the node
+    pub path: String,
+}
+
+impl DirMapEntry {
+    /// Parses a property string like "node=pve1,path=/srv/share". Returns
+    /// None unless both keys are set.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (mut node, mut path) = (None, None);
+        for (key, val) in PropertyString::parse(value, "").params() {
+            match key {
+                "node" => node = Some(val.to_string()),
+                "path" => path = Some(val.to_string()),
+                _ => {}
+            }
+        }
+        Some(Self {
+            node: node?,
+            path: path?,
+        })
+    }
+
+    pub fn to_property_string(&self) -> String {
+        format!("node={},path={}", self.node, self.path)
+    }
+}
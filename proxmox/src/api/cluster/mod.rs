@@ -0,0 +1,264 @@
+//! Cluster API module for datacenter wide resources
+
+pub mod config;
+pub mod ha;
+pub mod mapping;
+pub mod options;
+
+pub use config::{
+    cluster_links, ClusterJoinInfo, ClusterJoinNode, CreateClusterRequest, JoinClusterRequest,
+    MAX_CLUSTER_LINKS,
+};
+pub use ha::{CreateHaResourceRequest, HaResource, HaStatusEntry, UpdateHaResourceRequest};
+pub use mapping::{CreateDirMappingRequest, DirMapEntry, DirMapping, UpdateDirMappingRequest};
+pub use options::{ClusterOptions, UpdateClusterOptionsRequest, WebauthnSettings};
+
+use crate::api::{
+    common::{
+        deserialize_number_option, deserialize_proxmox_bool_option, deserialize_seconds_option,
+        deserialize_size_option, string_or_u32, ApiQueryParams,
+    },
+    error::ApiError,
+    Client,
+};
+use serde::{Deserialize, Serialize};
+
+/// Cluster API providing datacenter wide operations
+pub struct ClusterApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> ClusterApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/cluster/resources
+    pub async fn resources(
+        &self,
+        resource_type: Option<ClusterResourceType>,
+    ) -> Result<Vec<ClusterResource>, ApiError> {
+        let params = ApiQueryParams::new().add_optional("type", resource_type.map(|t| t.as_str()));
+        self.client
+            .get_with_params("/api2/json/cluster/resources", &params)
+            .await
+    }
+
+    /// Creating a cluster and joining nodes to it
+    pub fn config(&self) -> config::ClusterConfigApi<'a> {
+        config::ClusterConfigApi::new(self.client)
+    }
+
+    /// High availability operations
+    pub fn ha(&self) -> ha::HaApi<'a> {
+        ha::HaApi::new(self.client)
+    }
+
+    /// Resource mappings such as directories for virtiofs
+    pub fn mapping(&self) -> mapping::MappingApi<'a> {
+        mapping::MappingApi::new(self.client)
+    }
+
+    /// GET /api2/json/cluster/status
+    pub async fn status(&self) -> Result<Vec<ClusterStatusEntry>, ApiError> {
+        self.client.get("/api2/json/cluster/status").await
+    }
+
+    /// GET /api2/json/cluster/nextid
+    ///
+    /// Without a VMID this returns the lowest free VMID. With one it returns the
+    /// same VMID if it is free and fails with HTTP 400 if it is taken.
+    pub async fn next_id(&self, vmid: Option<u32>) -> Result<u32, ApiError> {
+        let params = ApiQueryParams::new().add_optional("vmid", vmid);
+        let response: NextId = self
+            .client
+            .get_with_params("/api2/json/cluster/nextid", &params)
+            .await?;
+        response
+            .0
+            .ok_or_else(|| ApiError::ParseError("cluster/nextid returned no VMID".to_string()))
+    }
+}
+
+/// The nextid endpoint returns the VMID as a string
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+struct NextId(#[serde(with = "string_or_u32")] Option<u32>);
+
+/// Resource type filter accepted by /cluster/resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterResourceType {
+    Vm,
+    Storage,
+    Node,
+    Sdn,
+}
+
+impl ClusterResourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClusterResourceType::Vm => "vm",
+            ClusterResourceType::Storage => "storage",
+            ClusterResourceType::Node => "node",
+            ClusterResourceType::Sdn => "sdn",
+        }
+    }
+}
+
+/// Item in the cluster resources response
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClusterResource {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub vmid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub template: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hastate: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cpu: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub maxcpu: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub mem: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub maxmem: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub disk: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub maxdisk: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_seconds_option",
+        default
+    )]
+    pub uptime: Option<u64>,
+}
+
+impl ClusterResource {
+    /// Whether this entry is a QEMU VM (as opposed to an LXC container)
+    pub fn is_qemu(&self) -> bool {
+        self.resource_type == "qemu"
+    }
+
+    /// Tags split on the separators Proxmox accepts
+    pub fn tag_list(&self) -> Vec<String> {
+        self.tags
+            .as_deref()
+            .unwrap_or_default()
+            .split([';', ',', ' '])
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .collect()
+    }
+}
+
+/// Item in the cluster status response. There is one "cluster" entry when the
+/// node is part of a cluster and one "node" entry per member node.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ClusterStatusEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub name: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub nodeid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub online: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub local: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub quorate: Option<bool>,
+    /// Number of nodes, only set on the cluster entry
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub nodes: Option<u32>,
+    /// Config version, only set on the cluster entry
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub version: Option<u64>,
+}
+
+impl ClusterStatusEntry {
+    pub fn is_cluster(&self) -> bool {
+        self.entry_type == "cluster"
+    }
+
+    pub fn is_node(&self) -> bool {
+        self.entry_type == "node"
+    }
+}
+
+#[cfg(test)]
+#[path = "./cluster_test.rs"]
+mod cluster_test;
@@ -0,0 +1,94 @@
+//! Datacenter options under /cluster/options
+
+use super::ClusterApi;
+use crate::api::{error::ApiError, propstring::PropertyString};
+use serde::{Deserialize, Serialize};
+
+/// Datacenter options from datacenter.cfg. Only the options managed by the
+/// provider are modelled.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ClusterOptions {
+    /// WebAuthn property string, see [`WebauthnSettings`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webauthn: Option<String>,
+}
+
+/// Request body for PUT /api2/json/cluster/options
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateClusterOptionsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webauthn: Option<String>,
+    /// Comma separated list of options to clear
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+}
+
+/// WebAuthn relying party settings. Credentials are bound to the relying
+/// party ID, so changing it invalidates every registered WebAuthn factor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WebauthnSettings {
+    /// Relying party name shown by the authenticator
+    pub rp: Option<String>,
+    /// Origin the GUI is reached at, e.g. https://pve.example.com:8006
+    pub origin: Option<String>,
+    /// Relying party ID, the domain of the origin
+    pub id: Option<String>,
+    /// Accept logins from subdomains of the ID as well
+    pub allow_subdomains: Option<bool>,
+}
+
+impl WebauthnSettings {
+    /// Parses a property string like "rp=pve,origin=https://pve:8006,id=pve"
+    pub fn parse(value: &str) -> Self {
+        let mut settings = Self::default();
+        for (key, val) in PropertyString::parse(value, "").params() {
+            match key {
+                "rp" => settings.rp = Some(val.to_string()),
+                "origin" => settings.origin = Some(val.to_string()),
+                "id" => settings.id = Some(val.to_string()),
+                "allow-subdomains" => settings.allow_subdomains = Some(val == "1" || val == "true"),
+                _ => {}
+            }
+        }
+        settings
+    }
+
+    /// Formats the settings as a property string, or None if none is set
+    pub fn to_property_string(&self) -> Option<String> {
+        let mut parts = vec![];
+        for (key, value) in [("rp", &self.rp), ("origin", &self.origin), ("id", &self.id)] {
+            if let Some(value) = value {
+                parts.push(format!("{}={}", key, value));
+            }
+        }
+        if let Some(allow_subdomains) = self.allow_subdomains {
+            parts.push(format!("allow-subdomains={}", u8::from(allow_subdomains)));
+        }
+        (!parts.is_empty()).then(|| parts.join(","))
+    }
+}
+
+impl ClusterOptions {
+    pub fn webauthn_settings(&self) -> Option<WebauthnSettings> {
+        self.webauthn.as_deref().map(WebauthnSettings::parse)
+    }
+}
+
+impl ClusterApi<'_> {
+    /// GET /api2/json/cluster/options
+    pub async fn options(&self) -> Result<ClusterOptions, ApiError> {
+        self.client.get("/api2/json/cluster/options").await
+    }
+
+    /// PUT /api2/json/cluster/options
+    pub async fn update_options(
+        &self,
+        request: &UpdateClusterOptionsRequest,
+    ) -> Result<(), ApiError> {
+        let _: serde_json::Value = self
+            .client
+            .put("/api2/json/cluster/options", request)
+            .await?;
+        Ok(())
+    }
+}
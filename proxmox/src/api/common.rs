@@ -1,20 +1,54 @@
 //! Common types and utilities for Proxmox API
 
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct TaskId(pub String);
 
+impl TaskId {
+    /// The node running the task, taken from the UPID
+    /// ("UPID:node:pid:pstart:starttime:type:id:user:")
+    pub fn node(&self) -> Option<&str> {
+        self.0
+            .strip_prefix("UPID:")
+            .and_then(|rest| rest.split(':').next())
+            .filter(|node| !node.is_empty())
+    }
+
+    /// When the task started as a unix timestamp, by the clock of the node
+    /// running it. The UPID holds it in hex.
+    pub fn start_time(&self) -> Option<u64> {
+        self.0
+            .strip_prefix("UPID:")
+            .and_then(|rest| rest.split(':').nth(3))
+            .and_then(|start| u64::from_str_radix(start, 16).ok())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ApiResponse<T> {
     pub data: T,
 }
 
+/// Body of an unsuccessful response
 #[derive(Debug, Deserialize)]
 pub struct ApiErrorResponse {
-    pub errors: Option<Vec<String>>,
-    pub data: Option<HashMap<String, Vec<String>>>,
+    #[serde(default)]
+    pub errors: Option<ApiErrorList>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// The `errors` member of an error response. Parameter verification failures
+/// come back as a map from parameter name to message.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ApiErrorList {
+    Parameters(HashMap<String, String>),
+    General(Vec<String>),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -24,6 +58,54 @@ pub struct ApiErrorDetails {
     pub field_errors: Option<HashMap<String, Vec<String>>>,
 }
 
+impl ApiErrorDetails {
+    /// One-line description of the error, preferring the server message
+    pub fn summary(&self) -> Option<String> {
+        if let Some(errors) = self.errors.as_ref().filter(|e| !e.is_empty()) {
+            return Some(errors.join("; "));
+        }
+
+        let mut fields: Vec<String> = self
+            .field_errors
+            .iter()
+            .flatten()
+            .flat_map(|(param, messages)| messages.iter().map(move |m| format!("{}: {}", param, m)))
+            .collect();
+        fields.sort();
+        (!fields.is_empty()).then(|| fields.join("; "))
+    }
+}
+
+impl From<ApiErrorResponse> for ApiErrorDetails {
+    fn from(response: ApiErrorResponse) -> Self {
+        let mut errors: Vec<String> = response
+            .message
+            .map(|m| m.trim_end().to_string())
+            .filter(|m| !m.is_empty())
+            .into_iter()
+            .collect();
+        let mut field_errors = None;
+
+        match response.errors {
+            Some(ApiErrorList::Parameters(params)) => {
+                field_errors = Some(
+                    params
+                        .into_iter()
+                        .map(|(param, message)| (param, vec![message.trim_end().to_string()]))
+                        .collect(),
+                );
+            }
+            Some(ApiErrorList::General(general)) => errors.extend(general),
+            None => {}
+        }
+
+        Self {
+            errors: (!errors.is_empty()).then_some(errors),
+            field_errors,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ProxmoxBool(pub bool);
 
@@ -68,6 +150,7 @@ impl<'de> Deserialize<'de> for ProxmoxBool {
         enum BoolOrInt {
             Bool(bool),
             Int(u8),
+            String(String),
         }
 
         match BoolOrInt::deserialize(deserializer)? {
@@ -75,10 +158,29 @@ impl<'de> Deserialize<'de> for ProxmoxBool {
             BoolOrInt::Int(0) => Ok(ProxmoxBool(false)),
             BoolOrInt::Int(1) => Ok(ProxmoxBool(true)),
             BoolOrInt::Int(_) => Err(serde::de::Error::custom("expected 0 or 1")),
+            BoolOrInt::String(s) => parse_bool(&s).map(ProxmoxBool).ok_or_else(|| {
+                serde::de::Error::custom(format!("expected a boolean, got \"{}\"", s))
+            }),
         }
     }
 }
 
+/// Reads a boolean in any of the spellings Proxmox accepts
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "on" | "yes" | "true" => Some(true),
+        "0" | "off" | "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+pub fn deserialize_proxmox_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(ProxmoxBool::deserialize(deserializer)?.0)
+}
+
 pub fn deserialize_proxmox_bool_option<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
 where
     D: Deserializer<'de>,
@@ -86,6 +188,226 @@ where
     Ok(Option::<ProxmoxBool>::deserialize(deserializer)?.map(|b| b.0))
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString<T> {
+    Number(T),
+    String(String),
+}
+
+/// Deserializes a number that Proxmox returns either as a JSON number or as
+/// a string, which it does for many config values such as `cores` or
+/// `memory`. An empty string reads as no value.
+pub fn deserialize_number_option<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: fmt::Display,
+{
+    match Option::<NumberOrString<T>>::deserialize(deserializer)? {
+        Some(NumberOrString::Number(n)) => Ok(Some(n)),
+        Some(NumberOrString::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(NumberOrString::String(s)) => s
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|e| serde::de::Error::custom(format!("invalid number \"{}\": {}", s, e))),
+        None => Ok(None),
+    }
+}
+
+/// Like [`deserialize_number_option`], for fields that are always present
+pub fn deserialize_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: fmt::Display,
+{
+    deserialize_number_option(deserializer)?
+        .ok_or_else(|| serde::de::Error::custom("expected a number, got an empty value"))
+}
+
+/// A size in bytes. Proxmox reports sizes as byte counts, but also as
+/// strings with a binary unit such as `10G` or `512M`, and takes both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    const UNITS: [(char, u64); 4] = [
+        ('T', 1 << 40),
+        ('G', 1 << 30),
+        ('M', 1 << 20),
+        ('K', 1 << 10),
+    ];
+
+    /// Parses a byte count with an optional unit of K, M, G or T, e.g.
+    /// "512", "4K" or "1.5G". A trailing "B" and the case of the unit are
+    /// ignored, so "10gb" reads as "10G".
+    pub fn parse(size: &str) -> Option<Self> {
+        let size = size.trim();
+        let (number, unit) = match size.find(|c: char| !c.is_ascii_digit() && c != '.') {
+            Some(index) => size.split_at(index),
+            None => (size, ""),
+        };
+        let multiplier: u64 = match unit.trim().to_ascii_uppercase().trim_end_matches('B') {
+            "" => 1,
+            "K" => 1 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            "T" => 1 << 40,
+            _ => return None,
+        };
+        let number: f64 = number.parse().ok()?;
+        Some(Self((number * multiplier as f64).round() as u64))
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Renders the size in the largest unit that holds it exactly, e.g. "10G"
+/// or "1536M", and as a plain byte count otherwise
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match Self::UNITS
+            .iter()
+            .find(|(_, multiplier)| self.0 != 0 && self.0 % multiplier == 0)
+        {
+            Some((unit, multiplier)) => write!(f, "{}{}", self.0 / multiplier, unit),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match NumberOrString::<u64>::deserialize(deserializer)? {
+            NumberOrString::Number(n) => Ok(Self(n)),
+            NumberOrString::String(s) => Self::parse(&s)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid size \"{}\"", s))),
+        }
+    }
+}
+
+/// Deserializes an optional size in bytes given as a number, a numeric
+/// string or a string with a unit
+pub fn deserialize_size_option<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<NumberOrString<u64>>::deserialize(deserializer)? {
+        Some(NumberOrString::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(NumberOrString::String(s)) => ByteSize::parse(&s)
+            .map(|size| Some(size.0))
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid size \"{}\"", s))),
+        Some(NumberOrString::Number(n)) => Ok(Some(n)),
+        None => Ok(None),
+    }
+}
+
+/// Parses a duration in seconds, either a plain number or numbers with a
+/// unit of s, m, h, d or w such as "90s" or "1h 30m"
+pub fn parse_seconds(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let mut rest: String = value.split_whitespace().collect();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0;
+    while !rest.is_empty() {
+        let number_end = rest.find(|c: char| !c.is_ascii_digit())?;
+        let number: u64 = rest[..number_end].parse().ok()?;
+        let unit_end = rest[number_end..]
+            .find(|c: char| c.is_ascii_digit())
+            .map_or(rest.len(), |end| number_end + end);
+        let multiplier = match &rest[number_end..unit_end] {
+            "s" | "sec" => 1,
+            "m" | "min" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            "w" => 604800,
+            _ => return None,
+        };
+        total += number * multiplier;
+        rest = rest[unit_end..].to_string();
+    }
+    Some(total)
+}
+
+/// Deserializes an optional duration in seconds given as a number or a
+/// string accepted by [`parse_seconds`]
+pub fn deserialize_seconds_option<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<NumberOrString<u64>>::deserialize(deserializer)? {
+        Some(NumberOrString::String(s)) if s.trim().is_empty() => Ok(None),
+        Some(NumberOrString::String(s)) => parse_seconds(&s)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid duration \"{}\"", s))),
+        Some(NumberOrString::Number(n)) => Ok(Some(n)),
+        None => Ok(None),
+    }
+}
+
+/// Percent-encodes a value for fields that pveproxy URI-unescapes on its
+/// side, such as cloud-init SSH keys. Without this, spaces, `+` and
+/// newlines in the keys are mangled or rejected.
+pub fn serialize_percent_encoded_option<S>(
+    value: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(v) => serializer.serialize_str(&urlencoding::encode(v)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Deserializes a list that Proxmox returns either as a JSON array or as a
+/// comma separated string, e.g. group members or role privileges
+pub fn deserialize_comma_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        String(String),
+        List(Vec<String>),
+    }
+
+    Ok(match Option::<StringOrList>::deserialize(deserializer)? {
+        Some(StringOrList::String(s)) => s
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect(),
+        Some(StringOrList::List(list)) => list,
+        None => Vec::new(),
+    })
+}
+
 pub trait ProxmoxApiResource: Sized {
     type CreateRequest: Serialize;
     type UpdateRequest: Serialize;
@@ -163,13 +485,14 @@ impl PaginationParams {
     }
 }
 
+#[derive(Debug, Deserialize)]
 pub struct ApiListResponse<T> {
     pub data: Vec<T>,
     pub total: Option<u32>,
 }
 
 pub mod string_or_u64 {
-    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::{Deserializer, Serializer};
 
     pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -185,25 +508,12 @@ pub mod string_or_u64 {
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum StringOrU64 {
-            String(String),
-            U64(u64),
-        }
-
-        match Option::<StringOrU64>::deserialize(deserializer)? {
-            Some(StringOrU64::String(s)) => {
-                s.parse::<u64>().map(Some).map_err(serde::de::Error::custom)
-            }
-            Some(StringOrU64::U64(u)) => Ok(Some(u)),
-            None => Ok(None),
-        }
+        super::deserialize_number_option(deserializer)
     }
 }
 
 pub mod string_or_u32 {
-    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::{Deserializer, Serializer};
 
     pub fn serialize<S>(value: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -219,19 +529,6 @@ pub mod string_or_u32 {
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        #[serde(untagged)]
-        enum StringOrU32 {
-            String(String),
-            U32(u32),
-        }
-
-        match Option::<StringOrU32>::deserialize(deserializer)? {
-            Some(StringOrU32::String(s)) => {
-                s.parse::<u32>().map(Some).map_err(serde::de::Error::custom)
-            }
-            Some(StringOrU32::U32(u)) => Ok(Some(u)),
-            None => Ok(None),
-        }
+        super::deserialize_number_option(deserializer)
     }
 }
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use super::common::ApiErrorDetails;
+use super::common::{ApiErrorDetails, ApiErrorResponse};
 
 #[derive(Debug, Error)]
 pub enum ApiError {
@@ -21,6 +21,23 @@ pub enum ApiError {
     #[error("Authentication failed")]
     AuthError,
 
+    #[error("Permission denied: {message}")]
+    PermissionDenied { message: String },
+
+    #[error("Not found (HTTP {status}): {message}")]
+    NotFound { status: u16, message: String },
+
+    #[error("Task {upid} failed: {exitstatus}{}", task_log_detail(.log))]
+    TaskFailed {
+        upid: String,
+        exitstatus: String,
+        /// Last lines of the task log, empty if it couldn't be fetched
+        log: Vec<String>,
+    },
+
+    #[error("Stopped waiting for task {upid}, which is still running")]
+    TaskCancelled { upid: String },
+
     #[error("Request timeout after {0} seconds")]
     Timeout(u64),
 
@@ -29,4 +46,109 @@ pub enum ApiError {
 
     #[error("Service unavailable, retry later")]
     ServiceUnavailable,
+
+    #[error("Invalid client configuration: {0}")]
+    InvalidConfig(String),
+}
+
+impl ApiError {
+    /// Classifies an unsuccessful response. Proxmox reports most failures,
+    /// including missing guests and configs, as HTTP 500 with the reason in
+    /// the body, so the message is looked at as well as the status.
+    pub fn from_response(status: u16, body: &str) -> Self {
+        let details = serde_json::from_str::<ApiErrorResponse>(body)
+            .ok()
+            .map(|response| Box::new(ApiErrorDetails::from(response)));
+        let message = details
+            .as_ref()
+            .and_then(|d| d.summary())
+            .unwrap_or_else(|| body.trim().to_string());
+        let lowercase = message.to_lowercase();
+        let has_parameter_errors = details
+            .as_ref()
+            .is_some_and(|d| d.field_errors.as_ref().is_some_and(|e| !e.is_empty()));
+
+        match status {
+            401 => ApiError::AuthError,
+            403 => ApiError::PermissionDenied { message },
+            404 => ApiError::NotFound { status, message },
+            429 => ApiError::RateLimited,
+            // Rejected parameters, even if the reason is a missing storage or
+            // bridge the parameter refers to
+            400 if has_parameter_errors => ApiError::ApiError {
+                status,
+                message: body.to_string(),
+                details,
+            },
+            _ if lowercase.contains("permission check failed") => {
+                ApiError::PermissionDenied { message }
+            }
+            _ if is_not_found_message(&lowercase) => ApiError::NotFound { status, message },
+            // Lock timeouts clear up once the other task finishes
+            _ if lowercase.contains("got timeout") => ApiError::ServiceUnavailable,
+            500 if details.as_ref().is_some_and(|d| d.summary().is_some()) => ApiError::ApiError {
+                status,
+                message: body.to_string(),
+                details,
+            },
+            500.. => ApiError::ServiceUnavailable,
+            _ => ApiError::ApiError {
+                status,
+                message: body.to_string(),
+                details,
+            },
+        }
+    }
+
+    /// Whether the same request may succeed when sent again
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            ApiError::Timeout(_) | ApiError::RateLimited | ApiError::ServiceUnavailable => true,
+            ApiError::RequestError(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            _ => false,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ApiError::NotFound { .. })
+    }
+
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, ApiError::PermissionDenied { .. })
+    }
+
+    /// Messages Proxmox attached to individual request parameters, sorted by
+    /// parameter name
+    pub fn parameter_errors(&self) -> Vec<(&str, &str)> {
+        let mut errors: Vec<(&str, &str)> = match self {
+            ApiError::ApiError {
+                details: Some(details),
+                ..
+            } => details
+                .field_errors
+                .iter()
+                .flatten()
+                .flat_map(|(param, messages)| {
+                    messages.iter().map(move |m| (param.as_str(), m.as_str()))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        errors.sort_unstable();
+        errors
+    }
+}
+
+/// The task log lines shown below a failed task's exit status
+fn task_log_detail(log: &[String]) -> String {
+    if log.is_empty() {
+        return String::new();
+    }
+    format!("\n\nTask log:\n{}", log.join("\n"))
+}
+
+fn is_not_found_message(message: &str) -> bool {
+    message.contains("does not exist")
+        || message.contains("not found")
+        || message.contains("no such")
 }
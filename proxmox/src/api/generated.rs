@@ -0,0 +1,1546 @@
+//! Generated from the Proxmox VE API schema by examples/apigen.
+//! Regenerate instead of editing by hand.
+
+use crate::api::common::{deserialize_number_option, deserialize_proxmox_bool_option};
+use serde::{Deserialize, Serialize};
+
+/// Returned by GET /nodes/{node}/qemu/{vmid}/config
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct QemuConfig {
+    /// Enable/disable ACPI.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub acpi: Option<bool>,
+    /// List of host cores used to execute guest processes, for example: 0,5,8-11
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub affinity: Option<String>,
+    /// Enable/disable communication with the QEMU Guest Agent and its properties.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Secure Encrypted Virtualization (SEV) features by AMD CPUs
+    #[serde(rename = "amd-sev", skip_serializing_if = "Option::is_none")]
+    pub amd_sev: Option<String>,
+    /// Virtual processor architecture. Defaults to the host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    /// Arbitrary arguments passed to kvm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<String>,
+    /// Configure a audio device, useful in combination with QXL/Spice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio0: Option<String>,
+    /// Automatic restart after crash (currently ignored).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub autostart: Option<bool>,
+    /// Amount of target RAM for the VM in MiB. Using zero disables the ballon
+    /// driver.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub balloon: Option<u64>,
+    /// Select BIOS implementation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bios: Option<String>,
+    /// Specify guest boot order. Use the 'order=' sub-property as usage with no key
+    /// or 'legacy=' is deprecated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot: Option<String>,
+    /// Enable booting from specified disk. Deprecated: Use 'boot: order=foo;bar'
+    /// instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootdisk: Option<String>,
+    /// This is an alias for option -ide2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cdrom: Option<String>,
+    /// cloud-init: Specify custom files to replace the automatically generated ones
+    /// at start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cicustom: Option<String>,
+    /// cloud-init: Password to assign the user. Using this is generally not
+    /// recommended. Use ssh keys instead. Also note that older cloud-init versions
+    /// do not support hashed passwords.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cipassword: Option<String>,
+    /// Specifies the cloud-init configuration format. The default depends on the
+    /// configured operating system type (`ostype`. We use the `nocloud` format for
+    /// Linux, and `configdrive2` for windows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citype: Option<String>,
+    /// cloud-init: do an automatic package upgrade after the first boot.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ciupgrade: Option<bool>,
+    /// cloud-init: User name to change ssh keys and password for instead of the
+    /// image's configured default user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ciuser: Option<String>,
+    /// The number of cores per socket.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cores: Option<u32>,
+    /// Emulated CPU type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    /// Limit of CPU usage.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cpulimit: Option<f64>,
+    /// CPU weight for a VM, will be clamped to [1, 10000] in cgroup v2.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cpuunits: Option<u32>,
+    /// Description for the VM. Shown in the web-interface VM's summary. This is
+    /// saved as comment inside the configuration file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// SHA1 digest of configuration file. This can be used to prevent concurrent
+    /// modifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// Configure a disk for storing EFI vars.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub efidisk0: Option<String>,
+    /// Freeze CPU at startup (use 'c' monitor command to start execution).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub freeze: Option<bool>,
+    /// Script that will be executed during various steps in the vms lifetime.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hookscript: Option<String>,
+    // hostpci[n] is indexed but documents no range
+    /// Selectively enable hotplug features. This is a comma separated list of
+    /// hotplug features: 'network', 'disk', 'cpu', 'memory', 'usb' and 'cloudinit'.
+    /// Use '0' to disable hotplug completely. Using '1' as value is an alias for
+    /// the default `network,disk,usb`. USB hotplugging is possible for guests with
+    /// machine version >= 7.1 and ostype l26 or windows > 7.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotplug: Option<String>,
+    /// Enables hugepages memory. Sets the size of hugepages in MiB. If the value is
+    /// set to 'any' then 1 GiB hugepages will be used if possible, otherwise the
+    /// size will fall back to 2 MiB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hugepages: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide0: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide1: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide2: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide3: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig0: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig1: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig2: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig3: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig4: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig5: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig6: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig7: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig8: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig9: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig10: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig11: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig12: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig13: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig14: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig15: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig16: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig17: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig18: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig19: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig20: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig21: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig22: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig23: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig24: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig25: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig26: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig27: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig28: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig29: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig30: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig31: Option<String>,
+    /// Inter-VM shared memory. Useful for direct communication between VMs, or to
+    /// the host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ivshmem: Option<String>,
+    /// Use together with hugepages. If enabled, hugepages will not not be deleted
+    /// after VM shutdown and can be used for subsequent starts.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub keephugepages: Option<bool>,
+    /// Keyboard layout for VNC server. This option is generally not required and is
+    /// often better handled from within the guest OS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<String>,
+    /// Enable/disable KVM hardware virtualization.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub kvm: Option<bool>,
+    /// Set the real time clock (RTC) to local time. This is enabled by default if
+    /// the `ostype` indicates a Microsoft Windows OS.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub localtime: Option<bool>,
+    /// Lock/unlock the VM.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock: Option<String>,
+    /// Specify the QEMU machine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// Memory properties.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory: Option<u64>,
+    /// Set maximum tolerated downtime (in seconds) for migrations. Should the
+    /// migration not be able to converge in the very end, because too much newly
+    /// dirtied RAM needs to be transferred, the limit will be increased
+    /// automatically step-by-step until migration can converge.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub migrate_downtime: Option<f64>,
+    /// Set maximum speed (in MB/s) for migrations. Value 0 is no limit.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub migrate_speed: Option<u64>,
+    /// Set a name for the VM. Only used on the configuration web interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// cloud-init: Sets DNS server IP address for a container. Create will
+    /// automatically use the setting from the host if neither searchdomain nor
+    /// nameserver are set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameserver: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net0: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net1: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net2: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net3: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net4: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net5: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net6: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net7: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net8: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net9: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net10: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net11: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net12: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net13: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net14: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net15: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net16: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net17: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net18: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net19: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net20: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net21: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net22: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net23: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net24: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net25: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net26: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net27: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net28: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net29: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net30: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net31: Option<String>,
+    /// Enable/disable NUMA.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub numa: Option<bool>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa0: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa1: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa2: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa3: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa4: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa5: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa6: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa7: Option<String>,
+    /// Specifies whether a VM will be started during system bootup.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub onboot: Option<bool>,
+    /// Specify guest operating system.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ostype: Option<String>,
+    /// Map host parallel devices (n is 0 to 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel0: Option<String>,
+    /// Map host parallel devices (n is 0 to 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel1: Option<String>,
+    /// Map host parallel devices (n is 0 to 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel2: Option<String>,
+    /// Sets the protection flag of the VM. This will disable the remove VM and
+    /// remove disk operations.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub protection: Option<bool>,
+    /// Allow reboot. If set to '0' the VM exit on reboot.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub reboot: Option<bool>,
+    /// Configure a VirtIO-based Random Number Generator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rng0: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata0: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata1: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata2: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata3: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata4: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata5: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi0: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi1: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi2: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi3: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi4: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi5: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi6: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi7: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi8: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi9: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi10: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi11: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi12: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi13: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi14: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi15: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi16: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi17: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi18: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi19: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi20: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi21: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi22: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi23: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi24: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi25: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi26: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi27: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi28: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi29: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi30: Option<String>,
+    /// SCSI controller model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsihw: Option<String>,
+    /// cloud-init: Sets DNS search domains for a container. Create will
+    /// automatically use the setting from the host if neither searchdomain nor
+    /// nameserver are set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub searchdomain: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial0: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial1: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial2: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial3: Option<String>,
+    /// Amount of memory shares for auto-ballooning. The larger the number is, the
+    /// more memory this VM gets. Number is relative to weights of all other running
+    /// VMs. Using zero disables auto-ballooning. Auto-ballooning is done by
+    /// pvestatd.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub shares: Option<u32>,
+    /// Specify SMBIOS type 1 fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smbios1: Option<String>,
+    /// The number of CPUs. Please use option -sockets instead.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub smp: Option<u32>,
+    /// The number of CPU sockets.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sockets: Option<u32>,
+    /// Configure additional enhancements for SPICE.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spice_enhancements: Option<String>,
+    /// cloud-init: Setup public SSH keys (one key per line, OpenSSH format).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sshkeys: Option<String>,
+    /// Set the initial date of the real time clock. Valid format for date are:'now'
+    /// or '2006-06-17T16:01:21' or '2006-06-17'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startdate: Option<String>,
+    /// Startup and shutdown behavior. Order is a non-negative number defining the
+    /// general startup order. Shutdown in done with reverse ordering. Additionally
+    /// you can set the 'up' or 'down' delay in seconds, which specifies a delay to
+    /// wait before the next VM is started or stopped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup: Option<String>,
+    /// Enable/disable the USB tablet device. This device is usually needed to allow
+    /// absolute mouse positioning with VNC. Else the mouse runs out of sync with
+    /// normal VNC clients. If you're running lots of console-only guests on one
+    /// host, you may consider disabling this to save some context switches. This is
+    /// turned off by default if you use spice (`qm set <vmid> --vga qxl`).
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tablet: Option<bool>,
+    /// Tags of the VM. This is only meta information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    /// Enable/disable Template.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_proxmox_bool_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub template: Option<bool>,
+    /// Configure a Disk for storing TPM state. The format is fixed to 'raw'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tpmstate0: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused0: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused1: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused2: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused3: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb0: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb1: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb2: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb3: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb4: Option<String>,
+    /// Number of hotplugged vcpus.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_number_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub vcpus: Option<u32>,
+    /// Configure the VGA hardware.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vga: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio0: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio1: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio2: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio3: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio4: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio5: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio6: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio7: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio8: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio9: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio10: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio11: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio12: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio13: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio14: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio15: Option<String>,
+    /// Set VM Generation ID. Use '1' to autogenerate on create or update, pass '0'
+    /// to disable explicitly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vmgenid: Option<String>,
+    /// Default storage for VM state volumes/files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vmstatestorage: Option<String>,
+    /// Create a virtual hardware watchdog device.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchdog: Option<String>,
+}
+
+/// Parameters of POST /nodes/{node}/qemu/{vmid}/config
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QemuSettings {
+    /// Enable/disable ACPI.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acpi: Option<bool>,
+    /// List of host cores used to execute guest processes, for example: 0,5,8-11
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub affinity: Option<String>,
+    /// Enable/disable communication with the QEMU Guest Agent and its properties.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// Secure Encrypted Virtualization (SEV) features by AMD CPUs
+    #[serde(rename = "amd-sev", skip_serializing_if = "Option::is_none")]
+    pub amd_sev: Option<String>,
+    /// Virtual processor architecture. Defaults to the host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    /// Arbitrary arguments passed to kvm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<String>,
+    /// Configure a audio device, useful in combination with QXL/Spice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio0: Option<String>,
+    /// Automatic restart after crash (currently ignored).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autostart: Option<bool>,
+    /// Amount of target RAM for the VM in MiB. Using zero disables the ballon
+    /// driver.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balloon: Option<u64>,
+    /// Select BIOS implementation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bios: Option<String>,
+    /// Specify guest boot order. Use the 'order=' sub-property as usage with no key
+    /// or 'legacy=' is deprecated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot: Option<String>,
+    /// Enable booting from specified disk. Deprecated: Use 'boot: order=foo;bar'
+    /// instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootdisk: Option<String>,
+    /// This is an alias for option -ide2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cdrom: Option<String>,
+    /// cloud-init: Specify custom files to replace the automatically generated ones
+    /// at start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cicustom: Option<String>,
+    /// Specifies the cloud-init configuration format. The default depends on the
+    /// configured operating system type (`ostype`. We use the `nocloud` format for
+    /// Linux, and `configdrive2` for windows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub citype: Option<String>,
+    /// The number of cores per socket.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cores: Option<u32>,
+    /// Emulated CPU type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    /// Limit of CPU usage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpulimit: Option<f64>,
+    /// CPU weight for a VM, will be clamped to [1, 10000] in cgroup v2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuunits: Option<u32>,
+    /// Description for the VM. Shown in the web-interface VM's summary. This is
+    /// saved as comment inside the configuration file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Configure a disk for storing EFI vars.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub efidisk0: Option<String>,
+    /// Freeze CPU at startup (use 'c' monitor command to start execution).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freeze: Option<bool>,
+    /// Script that will be executed during various steps in the vms lifetime.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hookscript: Option<String>,
+    // hostpci[n] is indexed but documents no range
+    /// Selectively enable hotplug features. This is a comma separated list of
+    /// hotplug features: 'network', 'disk', 'cpu', 'memory', 'usb' and 'cloudinit'.
+    /// Use '0' to disable hotplug completely. Using '1' as value is an alias for
+    /// the default `network,disk,usb`. USB hotplugging is possible for guests with
+    /// machine version >= 7.1 and ostype l26 or windows > 7.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotplug: Option<String>,
+    /// Enables hugepages memory. Sets the size of hugepages in MiB. If the value is
+    /// set to 'any' then 1 GiB hugepages will be used if possible, otherwise the
+    /// size will fall back to 2 MiB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hugepages: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide0: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide1: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide2: Option<String>,
+    /// Use volume as IDE hard disk or CD-ROM (n is 0 to 3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ide3: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig0: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig1: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig2: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig3: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig4: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig5: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig6: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig7: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig8: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig9: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig10: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig11: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig12: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig13: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig14: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig15: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig16: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig17: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig18: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig19: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig20: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig21: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig22: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig23: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig24: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig25: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig26: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig27: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig28: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig29: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig30: Option<String>,
+    /// cloud-init: Specify IP addresses and gateways for the corresponding
+    /// interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipconfig31: Option<String>,
+    /// Inter-VM shared memory. Useful for direct communication between VMs, or to
+    /// the host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ivshmem: Option<String>,
+    /// Use together with hugepages. If enabled, hugepages will not not be deleted
+    /// after VM shutdown and can be used for subsequent starts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keephugepages: Option<bool>,
+    /// Keyboard layout for VNC server. This option is generally not required and is
+    /// often better handled from within the guest OS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<String>,
+    /// Enable/disable KVM hardware virtualization.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kvm: Option<bool>,
+    /// Set the real time clock (RTC) to local time. This is enabled by default if
+    /// the `ostype` indicates a Microsoft Windows OS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localtime: Option<bool>,
+    /// Lock/unlock the VM.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock: Option<String>,
+    /// Specify the QEMU machine.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub machine: Option<String>,
+    /// Memory properties.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u64>,
+    /// Set maximum tolerated downtime (in seconds) for migrations. Should the
+    /// migration not be able to converge in the very end, because too much newly
+    /// dirtied RAM needs to be transferred, the limit will be increased
+    /// automatically step-by-step until migration can converge.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrate_downtime: Option<f64>,
+    /// Set maximum speed (in MB/s) for migrations. Value 0 is no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrate_speed: Option<u64>,
+    /// Set a name for the VM. Only used on the configuration web interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// cloud-init: Sets DNS server IP address for a container. Create will
+    /// automatically use the setting from the host if neither searchdomain nor
+    /// nameserver are set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nameserver: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net0: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net1: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net2: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net3: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net4: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net5: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net6: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net7: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net8: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net9: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net10: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net11: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net12: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net13: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net14: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net15: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net16: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net17: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net18: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net19: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net20: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net21: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net22: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net23: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net24: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net25: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net26: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net27: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net28: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net29: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net30: Option<String>,
+    /// Specify network devices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net31: Option<String>,
+    /// Enable/disable NUMA.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa: Option<bool>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa0: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa1: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa2: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa3: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa4: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa5: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa6: Option<String>,
+    /// NUMA topology.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub numa7: Option<String>,
+    /// Specifies whether a VM will be started during system bootup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onboot: Option<bool>,
+    /// Specify guest operating system.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ostype: Option<String>,
+    /// Map host parallel devices (n is 0 to 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel0: Option<String>,
+    /// Map host parallel devices (n is 0 to 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel1: Option<String>,
+    /// Map host parallel devices (n is 0 to 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel2: Option<String>,
+    /// Sets the protection flag of the VM. This will disable the remove VM and
+    /// remove disk operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protection: Option<bool>,
+    /// Allow reboot. If set to '0' the VM exit on reboot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reboot: Option<bool>,
+    /// Configure a VirtIO-based Random Number Generator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rng0: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata0: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata1: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata2: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata3: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata4: Option<String>,
+    /// Use volume as SATA hard disk or CD-ROM (n is 0 to 5).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sata5: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi0: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi1: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi2: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi3: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi4: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi5: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi6: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi7: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi8: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi9: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi10: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi11: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi12: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi13: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi14: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi15: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi16: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi17: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi18: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi19: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi20: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi21: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi22: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi23: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi24: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi25: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi26: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi27: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi28: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi29: Option<String>,
+    /// Use volume as SCSI hard disk or CD-ROM (n is 0 to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsi30: Option<String>,
+    /// SCSI controller model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scsihw: Option<String>,
+    /// cloud-init: Sets DNS search domains for a container. Create will
+    /// automatically use the setting from the host if neither searchdomain nor
+    /// nameserver are set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub searchdomain: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial0: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial1: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial2: Option<String>,
+    /// Create a serial device inside the VM (n is 0 to 3), and pass through a host
+    /// serial device (i.e. /dev/ttyS0), or create a unix socket on the host side
+    /// (use 'qm terminal' to open a terminal connection).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial3: Option<String>,
+    /// Amount of memory shares for auto-ballooning. The larger the number is, the
+    /// more memory this VM gets. Number is relative to weights of all other running
+    /// VMs. Using zero disables auto-ballooning. Auto-ballooning is done by
+    /// pvestatd.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shares: Option<u32>,
+    /// Specify SMBIOS type 1 fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smbios1: Option<String>,
+    /// The number of CPUs. Please use option -sockets instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smp: Option<u32>,
+    /// The number of CPU sockets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sockets: Option<u32>,
+    /// Configure additional enhancements for SPICE.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spice_enhancements: Option<String>,
+    /// Set the initial date of the real time clock. Valid format for date are:'now'
+    /// or '2006-06-17T16:01:21' or '2006-06-17'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startdate: Option<String>,
+    /// Startup and shutdown behavior. Order is a non-negative number defining the
+    /// general startup order. Shutdown in done with reverse ordering. Additionally
+    /// you can set the 'up' or 'down' delay in seconds, which specifies a delay to
+    /// wait before the next VM is started or stopped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub startup: Option<String>,
+    /// Enable/disable the USB tablet device. This device is usually needed to allow
+    /// absolute mouse positioning with VNC. Else the mouse runs out of sync with
+    /// normal VNC clients. If you're running lots of console-only guests on one
+    /// host, you may consider disabling this to save some context switches. This is
+    /// turned off by default if you use spice (`qm set <vmid> --vga qxl`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tablet: Option<bool>,
+    /// Tags of the VM. This is only meta information.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    /// Enable/disable Template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<bool>,
+    /// Configure a Disk for storing TPM state. The format is fixed to 'raw'.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tpmstate0: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused0: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused1: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused2: Option<String>,
+    /// Reference to unused volumes. This is used internally, and should not be
+    /// modified manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unused3: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb0: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb1: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb2: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb3: Option<String>,
+    /// Configure an USB device (n is 0 to 4, for machine version >= 7.1 and ostype
+    /// l26 or windows > 7, n can be up to 14).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb4: Option<String>,
+    /// Number of hotplugged vcpus.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vcpus: Option<u32>,
+    /// Configure the VGA hardware.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vga: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio0: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio1: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio2: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio3: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio4: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio5: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio6: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio7: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio8: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio9: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio10: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio11: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio12: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio13: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio14: Option<String>,
+    /// Use volume as VIRTIO hard disk (n is 0 to 15).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtio15: Option<String>,
+    /// Set VM Generation ID. Use '1' to autogenerate on create or update, pass '0'
+    /// to disable explicitly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vmgenid: Option<String>,
+    /// Default storage for VM state volumes/files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vmstatestorage: Option<String>,
+    /// Create a virtual hardware watchdog device.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchdog: Option<String>,
+}
+
+/// Endpoint paths, relative to /api2/json
+pub mod paths {
+    /// GET, POST
+    pub const NODES_NODE_QEMU_VMID_CONFIG: &str = "/nodes/{node}/qemu/{vmid}/config";
+}
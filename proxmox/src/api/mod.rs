@@ -2,20 +2,29 @@
 
 pub mod access;
 pub mod client;
+pub mod cluster;
 pub mod common;
 pub mod error;
+pub mod generated;
 pub mod nodes;
 pub mod pool;
+pub mod pools;
+pub mod propstring;
 pub mod response;
+pub mod storage;
 pub mod version;
 
 #[cfg(test)]
-mod test_helpers;
+pub(crate) mod test_helpers;
 
 pub use access::AccessApi;
 pub use client::*;
+pub use cluster::ClusterApi;
 pub use common::{
-    deserialize_proxmox_bool_option, ApiErrorDetails, ApiErrorResponse, ApiQueryParams,
-    ApiResponse, PaginationParams, ProxmoxApiResource, ProxmoxBool, TaskId,
+    deserialize_proxmox_bool_option, ApiErrorDetails, ApiErrorList, ApiErrorResponse,
+    ApiListResponse, ApiQueryParams, ApiResponse, ByteSize, PaginationParams, ProxmoxApiResource,
+    ProxmoxBool, TaskId,
 };
 pub use error::*;
+pub use pools::PoolsApi;
+pub use storage::StorageConfigApi;
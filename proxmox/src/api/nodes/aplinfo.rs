@@ -0,0 +1,59 @@
+//! Appliance template index, what `pveam available` and `pveam download`
+//! use
+
+use super::NodeApi;
+use crate::api::{common::TaskId, error::ApiError};
+use serde::{Deserialize, Serialize};
+
+/// An appliance template the node's index offers for download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplianceTemplate {
+    /// File name of the template, e.g.
+    /// "debian-12-standard_12.7-1_amd64.tar.zst"
+    pub template: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    /// system, mail or turnkeylinux
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha512sum: Option<String>,
+}
+
+/// Request body for POST /nodes/{node}/aplinfo
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DownloadTemplateRequest {
+    pub storage: String,
+    pub template: String,
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/aplinfo. The templates `pveam update`
+    /// last fetched the index of.
+    pub async fn aplinfo(&self) -> Result<Vec<ApplianceTemplate>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/aplinfo", self.node);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/nodes/{node}/aplinfo. Downloads a template from the
+    /// index into the vztmpl content of a storage.
+    pub async fn download_template(
+        &self,
+        request: &DownloadTemplateRequest,
+    ) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/aplinfo", self.node);
+        self.client.post(&path, request).await
+    }
+}
+
+#[cfg(test)]
+#[path = "./aplinfo_test.rs"]
+mod aplinfo_test;
@@ -0,0 +1,70 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_aplinfo() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/aplinfo")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"template": "debian-12-standard_12.7-1_amd64.tar.zst", "package": "debian-12-standard",
+                     "version": "12.7-1", "os": "debian-12", "section": "system", "type": "lxc",
+                     "headline": "Debian 12 Bookworm (standard)",
+                     "location": "http://download.proxmox.com/images/system/debian-12-standard_12.7-1_amd64.tar.zst",
+                     "sha512sum": "39f6d06e"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let templates = client.nodes().node("pve").aplinfo().await.unwrap();
+
+        assert_eq!(templates.len(), 1);
+        assert_eq!(
+            templates[0].template,
+            "debian-12-standard_12.7-1_amd64.tar.zst"
+        );
+        assert_eq!(templates[0].section.as_deref(), Some("system"));
+    }
+
+    #[tokio::test]
+    async fn test_download_template() {
+        let mut server = Server::new_async().await;
+        let m = server
+            .mock("POST", "/api2/json/nodes/pve/aplinfo")
+            .match_body(Matcher::Json(serde_json::json!({
+                "storage": "local",
+                "template": "debian-12-standard_12.7-1_amd64.tar.zst"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve:0000A1B2:0012C3D4:66321F20:download:debian-12-standard_12.7-1_amd64.tar.zst:root@pam:"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let upid = client
+            .nodes()
+            .node("pve")
+            .download_template(&DownloadTemplateRequest {
+                storage: "local".to_string(),
+                template: "debian-12-standard_12.7-1_amd64.tar.zst".to_string(),
+            })
+            .await
+            .unwrap();
+
+        m.assert_async().await;
+        assert_eq!(upid.node(), Some("pve"));
+    }
+}
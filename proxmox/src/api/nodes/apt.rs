@@ -0,0 +1,208 @@
+//! APT repositories and pending package updates of a node
+
+use super::NodeApi;
+use crate::api::{common::deserialize_proxmox_bool_option, error::ApiError};
+use serde::{Deserialize, Serialize};
+
+/// Repository configuration from GET /nodes/{node}/apt/repositories
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AptRepositories {
+    #[serde(default)]
+    pub files: Vec<AptRepositoryFile>,
+    /// The Proxmox repositories and whether each is configured
+    #[serde(rename = "standard-repos", default)]
+    pub standard_repos: Vec<AptStandardRepository>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+/// A sources.list or deb822 .sources file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AptRepositoryFile {
+    pub path: String,
+    #[serde(default)]
+    pub repositories: Vec<AptRepository>,
+}
+
+/// Repository entry of a file. Field names follow the deb822 format.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AptRepository {
+    #[serde(rename = "URIs", default)]
+    pub uris: Vec<String>,
+    #[serde(default)]
+    pub suites: Vec<String>,
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub enabled: Option<bool>,
+}
+
+impl AptRepository {
+    /// Whether this entry is the standard repository with the given handle,
+    /// e.g. "enterprise", "no-subscription" or "ceph-squid-enterprise"
+    pub fn is_standard(&self, handle: &str) -> bool {
+        let (path, component) = match handle.strip_prefix("ceph-") {
+            Some(ceph) => match ceph.split_once('-') {
+                Some((release, kind)) => (format!("/debian/ceph-{}", release), kind.to_string()),
+                None => return false,
+            },
+            None => {
+                let component = match handle {
+                    "enterprise" => "pve-enterprise",
+                    "no-subscription" => "pve-no-subscription",
+                    "test" => "pvetest",
+                    _ => return false,
+                };
+                ("/debian/pve".to_string(), component.to_string())
+            }
+        };
+        self.uris
+            .iter()
+            .any(|uri| uri.trim_end_matches('/').ends_with(&path))
+            && self.components.contains(&component)
+    }
+}
+
+/// One of the repositories Proxmox offers, as reported with the
+/// configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AptStandardRepository {
+    pub handle: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Unset when the repository isn't configured, otherwise whether it is
+    /// enabled
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub status: Option<bool>,
+}
+
+impl AptRepositories {
+    /// Whether the standard repository is configured and enabled
+    pub fn is_enabled(&self, handle: &str) -> bool {
+        self.standard_repos
+            .iter()
+            .any(|repo| repo.handle == handle && repo.status == Some(true))
+    }
+
+    /// Whether the standard repository is in one of the files, enabled or not
+    pub fn is_configured(&self, handle: &str) -> bool {
+        self.standard_repos
+            .iter()
+            .any(|repo| repo.handle == handle && repo.status.is_some())
+    }
+
+    /// File path and index of the entries for the standard repository that
+    /// aren't enabled or disabled as wanted. Entries without an Enabled
+    /// field are enabled, as in deb822.
+    pub fn entries_to_toggle(&self, handle: &str, enabled: bool) -> Vec<(String, usize)> {
+        self.files
+            .iter()
+            .flat_map(|file| {
+                file.repositories
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, repo)| {
+                        repo.is_standard(handle) && repo.enabled.unwrap_or(true) != enabled
+                    })
+                    .map(|(index, _)| (file.path.clone(), index))
+            })
+            .collect()
+    }
+}
+
+/// Whether the handle names a standard repository: enterprise,
+/// no-subscription, test or ceph-{release}-{one of those}
+pub fn is_standard_repository_handle(handle: &str) -> bool {
+    const KINDS: &[&str] = &["enterprise", "no-subscription", "test"];
+    match handle.strip_prefix("ceph-") {
+        Some(ceph) => ceph.split_once('-').is_some_and(|(release, kind)| {
+            !release.is_empty()
+                && release.chars().all(|c| c.is_ascii_lowercase())
+                && KINDS.contains(&kind)
+        }),
+        None => KINDS.contains(&handle),
+    }
+}
+
+/// Request body for POST /nodes/{node}/apt/repositories
+#[derive(Debug, Clone, Serialize)]
+pub struct SetAptRepositoryRequest {
+    pub path: String,
+    pub index: usize,
+    pub enabled: bool,
+}
+
+/// Request body for PUT /nodes/{node}/apt/repositories
+#[derive(Debug, Clone, Serialize)]
+pub struct AddAptRepositoryRequest {
+    pub handle: String,
+}
+
+/// Package update from GET /nodes/{node}/apt/update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AptUpdate {
+    pub package: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Version the package would be updated to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Installed version, unset for new packages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/apt/repositories
+    pub async fn apt_repositories(&self) -> Result<AptRepositories, ApiError> {
+        let path = format!("/api2/json/nodes/{}/apt/repositories", self.node);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/nodes/{node}/apt/repositories. Enables or disables
+    /// the entry at `index` of the file at `path`.
+    pub async fn set_apt_repository(
+        &self,
+        request: &SetAptRepositoryRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!("/api2/json/nodes/{}/apt/repositories", self.node);
+        self.client.post::<(), _>(&path, request).await.map(|_| ())
+    }
+
+    /// PUT /api2/json/nodes/{node}/apt/repositories. Adds a standard
+    /// repository, enabled.
+    pub async fn add_apt_repository(
+        &self,
+        request: &AddAptRepositoryRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!("/api2/json/nodes/{}/apt/repositories", self.node);
+        self.client.put::<(), _>(&path, request).await.map(|_| ())
+    }
+
+    /// GET /api2/json/nodes/{node}/apt/update. The list comes from the
+    /// node's package cache, which the node refreshes daily.
+    pub async fn apt_updates(&self) -> Result<Vec<AptUpdate>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/apt/update", self.node);
+        self.client.get(&path).await
+    }
+}
+
+#[cfg(test)]
+#[path = "./apt_test.rs"]
+mod apt_test;
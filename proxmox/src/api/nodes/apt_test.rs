@@ -0,0 +1,153 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    const REPOSITORIES: &str = r#"{"data": {
+        "digest": "4b0f3a",
+        "files": [
+            {"path": "/etc/apt/sources.list", "file-type": "list", "digest": [1, 2],
+             "repositories": [
+                {"Types": ["deb"], "URIs": ["http://deb.debian.org/debian"], "Suites": ["bookworm"],
+                 "Components": ["main", "contrib"], "Enabled": 1, "FileType": "list"},
+                {"Types": ["deb"], "URIs": ["http://download.proxmox.com/debian/pve"], "Suites": ["bookworm"],
+                 "Components": ["pve-no-subscription"], "Enabled": 0, "FileType": "list"}
+             ]},
+            {"path": "/etc/apt/sources.list.d/pve-enterprise.list", "file-type": "list", "digest": [3, 4],
+             "repositories": [
+                {"Types": ["deb"], "URIs": ["https://enterprise.proxmox.com/debian/pve"], "Suites": ["bookworm"],
+                 "Components": ["pve-enterprise"], "Enabled": 1, "FileType": "list"}
+             ]}
+        ],
+        "errors": [],
+        "infos": [],
+        "standard-repos": [
+            {"handle": "enterprise", "name": "Enterprise", "status": 1},
+            {"handle": "no-subscription", "name": "No-Subscription", "status": 0},
+            {"handle": "test", "name": "Test"},
+            {"handle": "ceph-quincy-enterprise", "name": "Ceph Quincy Enterprise"}
+        ]
+    }}"#;
+
+    #[tokio::test]
+    async fn test_apt_repositories() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/apt/repositories")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(REPOSITORIES)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let repos = client.nodes().node("pve").apt_repositories().await.unwrap();
+
+        assert!(repos.is_enabled("enterprise"));
+        assert!(!repos.is_enabled("no-subscription"));
+        assert!(repos.is_configured("no-subscription"));
+        assert!(!repos.is_configured("test"));
+        assert_eq!(
+            repos.entries_to_toggle("no-subscription", true),
+            vec![("/etc/apt/sources.list".to_string(), 1)]
+        );
+        assert!(repos.entries_to_toggle("no-subscription", false).is_empty());
+        assert_eq!(
+            repos.entries_to_toggle("enterprise", false),
+            vec![("/etc/apt/sources.list.d/pve-enterprise.list".to_string(), 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_and_add_apt_repository() {
+        let mut server = Server::new_async().await;
+        let post = server
+            .mock("POST", "/api2/json/nodes/pve/apt/repositories")
+            .match_body(Matcher::Json(serde_json::json!({
+                "path": "/etc/apt/sources.list",
+                "index": 1,
+                "enabled": true
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/api2/json/nodes/pve/apt/repositories")
+            .match_body(Matcher::Json(serde_json::json!({"handle": "test"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = client.nodes().node("pve");
+        node.set_apt_repository(&SetAptRepositoryRequest {
+            path: "/etc/apt/sources.list".to_string(),
+            index: 1,
+            enabled: true,
+        })
+        .await
+        .unwrap();
+        node.add_apt_repository(&AddAptRepositoryRequest {
+            handle: "test".to_string(),
+        })
+        .await
+        .unwrap();
+
+        post.assert_async().await;
+        put.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_apt_updates() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/apt/update")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"Package": "pve-manager", "Title": "Proxmox Virtual Environment Management Tools",
+                     "Version": "8.2.4", "OldVersion": "8.2.2", "Origin": "Proxmox", "Priority": "optional",
+                     "Section": "admin", "Arch": "amd64"},
+                    {"Package": "proxmox-kernel-6.8.8-2-pve-signed", "Version": "6.8.8-2",
+                     "Origin": "Proxmox", "Priority": "optional", "Section": "kernel", "Arch": "amd64"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let updates = client.nodes().node("pve").apt_updates().await.unwrap();
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].package, "pve-manager");
+        assert_eq!(updates[0].old_version.as_deref(), Some("8.2.2"));
+        assert!(updates[1].old_version.is_none());
+    }
+
+    #[test]
+    fn test_standard_repository_handles() {
+        assert!(is_standard_repository_handle("no-subscription"));
+        assert!(is_standard_repository_handle("ceph-squid-enterprise"));
+        assert!(!is_standard_repository_handle("ceph-squid"));
+        assert!(!is_standard_repository_handle("pve-enterprise"));
+
+        let ceph = AptRepository {
+            uris: vec!["http://download.proxmox.com/debian/ceph-squid/".to_string()],
+            suites: vec!["bookworm".to_string()],
+            components: vec!["no-subscription".to_string()],
+            enabled: None,
+        };
+        assert!(ceph.is_standard("ceph-squid-no-subscription"));
+        assert!(!ceph.is_standard("ceph-reef-no-subscription"));
+        assert!(!ceph.is_standard("no-subscription"));
+    }
+}
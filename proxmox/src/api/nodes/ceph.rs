@@ -0,0 +1,225 @@
+//! Ceph pools and OSDs of a hyperconverged cluster
+//!
+//! The Ceph endpoints live under a node, but pools and OSDs are cluster wide.
+//! Any node with the Ceph packages installed can be used.
+
+use super::NodeApi;
+use crate::api::{
+    common::{
+        deserialize_number, deserialize_number_option, deserialize_proxmox_bool_option,
+        ApiQueryParams, TaskId,
+    },
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Applications a pool can be tagged with
+pub const CEPH_POOL_APPLICATIONS: &[&str] = &["rbd", "cephfs", "rgw"];
+
+/// Placement group autoscaler modes
+pub const CEPH_PG_AUTOSCALE_MODES: &[&str] = &["on", "off", "warn"];
+
+/// Pool as listed by GET /nodes/{node}/ceph/pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CephPool {
+    pub pool_name: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub pool: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub size: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub min_size: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub pg_num: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_autoscale_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crush_rule_name: Option<String>,
+    /// Enabled applications, e.g. `{"rbd": {}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub application_metadata: Option<HashMap<String, serde_json::Value>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub bytes_used: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub percent_used: Option<f64>,
+}
+
+impl CephPool {
+    /// The application the pool is used for. Pools normally have one.
+    pub fn application(&self) -> Option<String> {
+        let mut applications: Vec<&String> = self
+            .application_metadata
+            .iter()
+            .flat_map(|m| m.keys())
+            .collect();
+        applications.sort();
+        applications.first().map(|a| a.to_string())
+    }
+}
+
+/// Request body for POST /nodes/{node}/ceph/pool
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateCephPoolRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_num: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_autoscale_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    /// Also create an RBD storage named after the pool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_storages: Option<bool>,
+}
+
+/// Request body for PUT /nodes/{node}/ceph/pool/{name}
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateCephPoolRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_num: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pg_autoscale_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+}
+
+/// Entry of the OSD tree from GET /nodes/{node}/ceph/osd. Hosts and the
+/// root carry their OSDs as children.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CephOsdNode {
+    #[serde(deserialize_with = "deserialize_number")]
+    pub id: i64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    /// "up" or "down" for OSDs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(
+        rename = "in",
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub is_in: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<CephOsdNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CephOsdTree {
+    pub root: CephOsdNode,
+}
+
+impl CephOsdTree {
+    /// OSDs that are up and in, i.e. able to hold a replica
+    pub fn active_osds(&self) -> usize {
+        fn count(node: &CephOsdNode) -> usize {
+            let own = node.node_type == "osd"
+                && node.status.as_deref() == Some("up")
+                && node.is_in.unwrap_or(false);
+            usize::from(own) + node.children.iter().map(count).sum::<usize>()
+        }
+        count(&self.root)
+    }
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/ceph/pool
+    pub async fn ceph_pools(&self) -> Result<Vec<CephPool>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/ceph/pool", self.node);
+        self.client.get(&path).await
+    }
+
+    /// Looks a pool up by name in the pool list
+    pub async fn ceph_pool(&self, name: &str) -> Result<CephPool, ApiError> {
+        self.ceph_pools()
+            .await?
+            .into_iter()
+            .find(|pool| pool.pool_name == name)
+            .ok_or_else(|| ApiError::NotFound {
+                status: 404,
+                message: format!("Ceph pool '{}' does not exist", name),
+            })
+    }
+
+    /// POST /api2/json/nodes/{node}/ceph/pool
+    pub async fn create_ceph_pool(
+        &self,
+        request: &CreateCephPoolRequest,
+    ) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/ceph/pool", self.node);
+        self.client.post(&path, request).await
+    }
+
+    /// PUT /api2/json/nodes/{node}/ceph/pool/{name}
+    pub async fn update_ceph_pool(
+        &self,
+        name: &str,
+        request: &UpdateCephPoolRequest,
+    ) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/ceph/pool/{}", self.node, name);
+        self.client.put(&path, request).await
+    }
+
+    /// DELETE /api2/json/nodes/{node}/ceph/pool/{name}. `remove_storages`
+    /// also removes the storage entries using the pool.
+    pub async fn delete_ceph_pool(
+        &self,
+        name: &str,
+        remove_storages: bool,
+    ) -> Result<TaskId, ApiError> {
+        let params =
+            ApiQueryParams::new().add_optional("remove_storages", remove_storages.then_some("1"));
+        let path = format!(
+            "/api2/json/nodes/{}/ceph/pool/{}{}",
+            self.node,
+            name,
+            params.to_query_string()
+        );
+        self.client.delete(&path).await
+    }
+
+    /// GET /api2/json/nodes/{node}/ceph/osd
+    pub async fn ceph_osds(&self) -> Result<CephOsdTree, ApiError> {
+        let path = format!("/api2/json/nodes/{}/ceph/osd", self.node);
+        self.client.get(&path).await
+    }
+}
+
+#[cfg(test)]
+#[path = "./ceph_test.rs"]
+mod ceph_test;
@@ -0,0 +1,116 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_ceph_pool_lookup() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/ceph/pool")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"pool": 1, "pool_name": ".mgr", "size": 3, "min_size": 2, "pg_num": 1,
+                     "application_metadata": {"mgr": {}}},
+                    {"pool": 2, "pool_name": "vm", "size": 3, "min_size": 2, "pg_num": 32,
+                     "pg_autoscale_mode": "on", "crush_rule_name": "replicated_rule",
+                     "application_metadata": {"rbd": {}}, "bytes_used": 1024, "percent_used": 0.01}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = client.nodes().node("pve");
+
+        let pool = node.ceph_pool("vm").await.unwrap();
+        assert_eq!(pool.size, Some(3));
+        assert_eq!(pool.pg_autoscale_mode.as_deref(), Some("on"));
+        assert_eq!(pool.application().as_deref(), Some("rbd"));
+
+        assert!(node.ceph_pool("missing").await.unwrap_err().is_not_found());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_ceph_pool() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/nodes/pve/ceph/pool")
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "vm",
+                "size": 3,
+                "min_size": 2,
+                "application": "rbd",
+                "add_storages": true
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve:00001234:00000000:65000000:cephcreatepool:vm:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/api2/json/nodes/pve/ceph/pool/vm")
+            .match_query(Matcher::UrlEncoded(
+                "remove_storages".to_string(),
+                "1".to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve:00001235:00000000:65000000:cephdestroypool:vm:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = client.nodes().node("pve");
+
+        let request = CreateCephPoolRequest {
+            name: "vm".to_string(),
+            size: Some(3),
+            min_size: Some(2),
+            application: Some("rbd".to_string()),
+            add_storages: Some(true),
+            ..Default::default()
+        };
+        assert!(node.create_ceph_pool(&request).await.is_ok());
+        assert!(node.delete_ceph_pool("vm", true).await.is_ok());
+
+        create.assert_async().await;
+        delete.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_active_osds() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/ceph/osd")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"root": {"id": -1, "name": "default", "type": "root", "children": [
+                    {"id": -2, "name": "pve", "type": "host", "children": [
+                        {"id": 0, "name": "osd.0", "type": "osd", "status": "up", "in": 1},
+                        {"id": 1, "name": "osd.1", "type": "osd", "status": "down", "in": 1}
+                    ]},
+                    {"id": -3, "name": "pve2", "type": "host", "children": [
+                        {"id": 2, "name": "osd.2", "type": "osd", "status": "up", "in": 1},
+                        {"id": 3, "name": "osd.3", "type": "osd", "status": "up", "in": 0}
+                    ]}
+                ]}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let tree = client.nodes().node("pve").ceph_osds().await.unwrap();
+
+        assert_eq!(tree.active_osds(), 2);
+    }
+}
@@ -0,0 +1,283 @@
+//! Physical disks of a node and the storages initialized on them
+
+use super::NodeApi;
+use crate::api::{
+    common::{
+        deserialize_number, deserialize_number_option, deserialize_proxmox_bool_option,
+        ApiQueryParams, TaskId,
+    },
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+
+/// RAID levels a ZFS pool can be created with
+pub const ZFS_RAID_LEVELS: &[&str] = &["single", "mirror", "raid10", "raidz", "raidz2", "raidz3"];
+
+/// Compression algorithms a ZFS pool can be created with
+pub const ZFS_COMPRESSION: &[&str] = &["on", "off", "gzip", "lz4", "lzjb", "zle", "zstd"];
+
+/// Filesystems a directory storage can be formatted with
+pub const DIRECTORY_FILESYSTEMS: &[&str] = &["ext4", "xfs"];
+
+/// Disk as listed by GET /nodes/{node}/disks/list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeDisk {
+    /// Device node, e.g. /dev/sdb
+    pub devpath: String,
+    /// Size in bytes
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub size: u64,
+    /// hdd, ssd, nvme, usb or unknown
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub disk_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wwn: Option<String>,
+    /// SMART health, e.g. PASSED
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<String>,
+    /// What the disk is used for, e.g. LVM, ZFS, partitions or mounted.
+    /// Unset for unused disks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub gpt: Option<bool>,
+    /// Stable /dev/disk/by-id path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_id_link: Option<String>,
+    /// ID of the Ceph OSD on the disk, -1 if there is none
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub osdid: Option<i64>,
+}
+
+/// Kinds of storage a disk can be initialized as. Each has its own endpoint
+/// under /nodes/{node}/disks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskStorageKind {
+    Lvm,
+    LvmThin,
+    Zfs,
+    Directory,
+}
+
+impl DiskStorageKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lvm => "lvm",
+            Self::LvmThin => "lvmthin",
+            Self::Zfs => "zfs",
+            Self::Directory => "directory",
+        }
+    }
+}
+
+/// Request body for POST /nodes/{node}/disks/{lvm,lvmthin,zfs,directory}.
+/// ZFS takes `devices` and the pool options, the others a single `device`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateDiskStorageRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    /// Comma separated list of devices
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub devices: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raidlevel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ashift: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filesystem: Option<String>,
+    /// Also add a storage named after the new volume group, pool or
+    /// directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_storage: Option<bool>,
+}
+
+/// Volume group from GET /nodes/{node}/disks/lvm. The API returns a tree
+/// with the volume groups as children of the root and their physical
+/// volumes below them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LvmVolumeGroup {
+    pub name: String,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub size: u64,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub free: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LvmTree {
+    #[serde(default)]
+    children: Vec<LvmVolumeGroup>,
+}
+
+/// Thin pool from GET /nodes/{node}/disks/lvmthin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LvmThinPool {
+    pub lv: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vg: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub lv_size: u64,
+}
+
+/// Pool from GET /nodes/{node}/disks/zfs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZfsPool {
+    pub name: String,
+    /// Size in bytes
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub size: u64,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub free: u64,
+    /// ONLINE, DEGRADED, FAULTED and so on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<String>,
+}
+
+/// Mounted directory from GET /nodes/{node}/disks/directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskDirectory {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub fs_type: Option<String>,
+}
+
+impl DiskDirectory {
+    /// Directories created through the API are mounted at /mnt/pve/{name}
+    pub fn name(&self) -> Option<&str> {
+        self.path.strip_prefix("/mnt/pve/")
+    }
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/disks/list. `unused_only` leaves out
+    /// disks that hold partitions, filesystems, LVM, ZFS or an OSD.
+    pub async fn disks(&self, unused_only: bool) -> Result<Vec<NodeDisk>, ApiError> {
+        let params = ApiQueryParams::new().add_optional("type", unused_only.then_some("unused"));
+        let path = format!("/api2/json/nodes/{}/disks/list", self.node);
+        self.client.get_with_params(&path, &params).await
+    }
+
+    /// GET /api2/json/nodes/{node}/disks/lvm
+    pub async fn lvm_volume_groups(&self) -> Result<Vec<LvmVolumeGroup>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/disks/lvm", self.node);
+        let tree: LvmTree = self.client.get(&path).await?;
+        Ok(tree.children)
+    }
+
+    /// GET /api2/json/nodes/{node}/disks/lvmthin
+    pub async fn lvm_thin_pools(&self) -> Result<Vec<LvmThinPool>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/disks/lvmthin", self.node);
+        self.client.get(&path).await
+    }
+
+    /// GET /api2/json/nodes/{node}/disks/zfs
+    pub async fn zfs_pools(&self) -> Result<Vec<ZfsPool>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/disks/zfs", self.node);
+        self.client.get(&path).await
+    }
+
+    /// Looks a pool up by name in the pool list
+    pub async fn zfs_pool(&self, name: &str) -> Result<ZfsPool, ApiError> {
+        self.zfs_pools()
+            .await?
+            .into_iter()
+            .find(|pool| pool.name == name)
+            .ok_or_else(|| ApiError::NotFound {
+                status: 404,
+                message: format!("ZFS pool '{}' does not exist", name),
+            })
+    }
+
+    /// GET /api2/json/nodes/{node}/disks/directory
+    pub async fn disk_directories(&self) -> Result<Vec<DiskDirectory>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/disks/directory", self.node);
+        self.client.get(&path).await
+    }
+
+    /// Whether a storage of the given kind exists on the node
+    pub async fn disk_storage_exists(
+        &self,
+        kind: DiskStorageKind,
+        name: &str,
+    ) -> Result<bool, ApiError> {
+        Ok(match kind {
+            DiskStorageKind::Lvm => self
+                .lvm_volume_groups()
+                .await?
+                .iter()
+                .any(|vg| vg.name == name),
+            DiskStorageKind::LvmThin => self
+                .lvm_thin_pools()
+                .await?
+                .iter()
+                .any(|pool| pool.lv == name && pool.vg.as_deref().map_or(true, |vg| vg == name)),
+            DiskStorageKind::Zfs => self.zfs_pools().await?.iter().any(|pool| pool.name == name),
+            DiskStorageKind::Directory => self
+                .disk_directories()
+                .await?
+                .iter()
+                .any(|dir| dir.name() == Some(name)),
+        })
+    }
+
+    /// POST /api2/json/nodes/{node}/disks/{kind}
+    pub async fn create_disk_storage(
+        &self,
+        kind: DiskStorageKind,
+        request: &CreateDiskStorageRequest,
+    ) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/disks/{}", self.node, kind.as_str());
+        self.client.post(&path, request).await
+    }
+
+    /// DELETE /api2/json/nodes/{node}/disks/{kind}/{name}. `cleanup_config`
+    /// also removes the storages using it, `cleanup_disks` wipes the disks.
+    /// Thin pools are removed from the volume group of the same name, which
+    /// is how they are created.
+    pub async fn delete_disk_storage(
+        &self,
+        kind: DiskStorageKind,
+        name: &str,
+        cleanup_config: bool,
+        cleanup_disks: bool,
+    ) -> Result<TaskId, ApiError> {
+        let params = ApiQueryParams::new()
+            .add_optional(
+                "volume-group",
+                (kind == DiskStorageKind::LvmThin).then_some(name),
+            )
+            .add_optional("cleanup-config", cleanup_config.then_some("1"))
+            .add_optional("cleanup-disks", cleanup_disks.then_some("1"));
+        let path = format!(
+            "/api2/json/nodes/{}/disks/{}/{}{}",
+            self.node,
+            kind.as_str(),
+            name,
+            params.to_query_string()
+        );
+        self.client.delete(&path).await
+    }
+}
+
+#[cfg(test)]
+#[path = "./disks_test.rs"]
+mod disks_test;
@@ -0,0 +1,130 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_list_unused_disks() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/disks/list")
+            .match_query(Matcher::UrlEncoded("type".into(), "unused".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"devpath": "/dev/sdb", "size": 500107862016, "type": "ssd", "model": "Samsung_SSD_870",
+                     "serial": "S6PNNX0R", "health": "PASSED", "gpt": 0, "osdid": -1,
+                     "by_id_link": "/dev/disk/by-id/ata-Samsung_SSD_870_S6PNNX0R"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let disks = client.nodes().node("pve").disks(true).await.unwrap();
+
+        assert_eq!(disks.len(), 1);
+        assert_eq!(disks[0].devpath, "/dev/sdb");
+        assert_eq!(disks[0].disk_type.as_deref(), Some("ssd"));
+        assert_eq!(disks[0].gpt, Some(false));
+        assert!(disks[0].used.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_exists() {
+        let mut server = Server::new_async().await;
+        let _lvm = server
+            .mock("GET", "/api2/json/nodes/pve/disks/lvm")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"leaf": 0, "children": [
+                    {"name": "pve", "size": 499570991104, "free": 16907239424, "leaf": 0,
+                     "children": [{"name": "/dev/sda3", "size": 499570991104, "free": 16907239424, "leaf": 1}]}
+                ]}}"#,
+            )
+            .create_async()
+            .await;
+        let _directory = server
+            .mock("GET", "/api2/json/nodes/pve/disks/directory")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"path": "/mnt/pve/backups", "device": "/dev/sdc1", "type": "ext4",
+                    "options": "defaults", "unitfile": "/etc/systemd/system/mnt-pve-backups.mount"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = client.nodes().node("pve");
+
+        assert!(node
+            .disk_storage_exists(DiskStorageKind::Lvm, "pve")
+            .await
+            .unwrap());
+        assert!(!node
+            .disk_storage_exists(DiskStorageKind::Lvm, "data")
+            .await
+            .unwrap());
+        assert!(node
+            .disk_storage_exists(DiskStorageKind::Directory, "backups")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_thin_pool() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/nodes/pve/disks/lvmthin")
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "fast",
+                "device": "/dev/nvme0n1",
+                "add_storage": true
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve:00001234:00000000:65000000:lvmthincreate:fast:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/api2/json/nodes/pve/disks/lvmthin/fast")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("volume-group".into(), "fast".into()),
+                Matcher::UrlEncoded("cleanup-config".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve:00001235:00000000:65000000:lvmthinremove:fast:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = client.nodes().node("pve");
+        node.create_disk_storage(
+            DiskStorageKind::LvmThin,
+            &CreateDiskStorageRequest {
+                name: "fast".to_string(),
+                device: Some("/dev/nvme0n1".to_string()),
+                add_storage: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        node.delete_disk_storage(DiskStorageKind::LvmThin, "fast", true, false)
+            .await
+            .unwrap();
+
+        create.assert_async().await;
+        delete.assert_async().await;
+    }
+}
@@ -0,0 +1,198 @@
+//! Node hardware inventory API for PCI and USB devices
+
+use crate::api::{
+    client::Client,
+    common::{
+        deserialize_number, deserialize_number_option, deserialize_proxmox_bool_option,
+        string_or_u32,
+    },
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+
+/// Strips the `0x` prefix Proxmox puts on hexadecimal IDs and lowercases them,
+/// so `0x10DE` and `10de` compare equal
+pub fn normalize_hex_id(id: &str) -> String {
+    let id = id.trim();
+    id.strip_prefix("0x")
+        .or_else(|| id.strip_prefix("0X"))
+        .unwrap_or(id)
+        .to_lowercase()
+}
+
+/// Hardware API scoped to a single node
+pub struct HardwareApi<'a> {
+    client: &'a Client,
+    node: String,
+}
+
+impl<'a> HardwareApi<'a> {
+    pub fn new(client: &'a Client, node: &str) -> Self {
+        Self {
+            client,
+            node: node.to_string(),
+        }
+    }
+
+    /// GET /api2/json/nodes/{node}/hardware/pci
+    ///
+    /// Proxmox hides bridges and memory controllers by default.
+    pub async fn pci(&self) -> Result<Vec<PciDevice>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/hardware/pci", self.node);
+        self.client.get(&path).await
+    }
+
+    /// GET /api2/json/nodes/{node}/hardware/usb
+    pub async fn usb(&self) -> Result<Vec<UsbDevice>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/hardware/usb", self.node);
+        self.client.get(&path).await
+    }
+}
+
+/// PCI device as reported by the node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PciDevice {
+    /// PCI address, e.g. 0000:01:00.0
+    pub id: String,
+    /// Class code in hex, e.g. 0x030000 for a VGA controller
+    pub class: String,
+    pub vendor: String,
+    pub device: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vendor_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsystem_vendor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsystem_device: Option<String>,
+    /// IOMMU group, -1 when IOMMU is disabled
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub iommugroup: Option<i64>,
+    /// Whether the device supports mediated devices
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub mdev: Option<bool>,
+}
+
+impl PciDevice {
+    pub fn vendor_id(&self) -> String {
+        normalize_hex_id(&self.vendor)
+    }
+
+    pub fn device_id(&self) -> String {
+        normalize_hex_id(&self.device)
+    }
+
+    /// Whether the class code starts with the given hex prefix, e.g. `03`
+    /// for all display controllers or `0300` for VGA controllers only
+    pub fn has_class_prefix(&self, prefix: &str) -> bool {
+        normalize_hex_id(&self.class).starts_with(&normalize_hex_id(prefix))
+    }
+
+    /// The IOMMU group, or None when IOMMU is disabled
+    pub fn iommu_group(&self) -> Option<i64> {
+        self.iommugroup.filter(|group| *group >= 0)
+    }
+}
+
+/// USB device as reported by the node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsbDevice {
+    #[serde(deserialize_with = "deserialize_number")]
+    pub busnum: u32,
+    #[serde(deserialize_with = "deserialize_number")]
+    pub devnum: u32,
+    /// Port path below the bus, e.g. 1.2
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "string_or_u32_path",
+        default
+    )]
+    pub usbpath: Option<String>,
+    pub vendid: String,
+    pub prodid: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "string_or_u32",
+        default
+    )]
+    pub class: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manufacturer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial: Option<String>,
+}
+
+impl UsbDevice {
+    pub fn vendor_id(&self) -> String {
+        normalize_hex_id(&self.vendid)
+    }
+
+    pub fn product_id(&self) -> String {
+        normalize_hex_id(&self.prodid)
+    }
+
+    /// Device identifier in the vendor:product form accepted by usbN host=
+    pub fn host_id(&self) -> String {
+        format!("{}:{}", self.vendor_id(), self.product_id())
+    }
+
+    /// Port identifier in the bus-port form accepted by usbN host=, which
+    /// passes through whatever is plugged into that port
+    pub fn host_port(&self) -> Option<String> {
+        self.usbpath
+            .as_ref()
+            .map(|path| format!("{}-{}", self.busnum, path))
+    }
+}
+
+/// The USB path is usually a string like "1.2" but a single level path is
+/// sometimes sent as a bare number
+mod string_or_u32_path {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(v) => serializer.serialize_str(v),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrNumber {
+            String(String),
+            Number(u64),
+        }
+
+        Ok(
+            Option::<StringOrNumber>::deserialize(deserializer)?.map(|v| match v {
+                StringOrNumber::String(s) => s,
+                StringOrNumber::Number(n) => n.to_string(),
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "./hardware_test.rs"]
+mod hardware_test;
@@ -0,0 +1,62 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_list_pci_devices() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/hardware/pci")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"id": "0000:01:00.0", "class": "0x030000", "vendor": "0x10de", "device": "0x1c82", "vendor_name": "NVIDIA Corporation", "device_name": "GP107", "iommugroup": 14, "mdev": 1},
+                    {"id": "0000:00:1f.3", "class": "0x040300", "vendor": "0x8086", "device": "0xa348", "iommugroup": -1}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let devices = HardwareApi::new(&client, "pve").pci().await.unwrap();
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].vendor_id(), "10de");
+        assert!(devices[0].has_class_prefix("03"));
+        assert!(devices[0].has_class_prefix("0x0300"));
+        assert!(!devices[1].has_class_prefix("03"));
+        assert_eq!(devices[0].iommu_group(), Some(14));
+        assert_eq!(devices[1].iommu_group(), None);
+        assert_eq!(devices[0].mdev, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_list_usb_devices() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/hardware/usb")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"busnum": 1, "devnum": 3, "usbpath": "1.2", "vendid": "0x0A12", "prodid": "0x0001", "class": 224, "speed": "12", "product": "Bluetooth Dongle"},
+                    {"busnum": 2, "devnum": 2, "usbpath": 4, "vendid": "0x046d", "prodid": "0xc52b", "class": "0"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let devices = HardwareApi::new(&client, "pve").usb().await.unwrap();
+
+        assert_eq!(devices[0].host_id(), "0a12:0001");
+        assert_eq!(devices[0].host_port().as_deref(), Some("1-1.2"));
+        assert_eq!(devices[0].class, Some(224));
+        assert_eq!(devices[1].host_port().as_deref(), Some("2-4"));
+        assert_eq!(devices[1].class, Some(0));
+    }
+}
@@ -0,0 +1,286 @@
+//! LXC container API implementation
+
+use crate::api::{
+    common::{deserialize_number_option, deserialize_proxmox_bool_option, ApiQueryParams, TaskId},
+    error::ApiError,
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Mount points a container can have, mp0 to mp255
+pub const MAX_MOUNT_POINTS: u32 = 256;
+
+/// Passed through devices a container can have, dev0 to dev255
+pub const MAX_DEVICES: u32 = 256;
+
+/// Network interfaces a container can have, net0 to net31
+pub const MAX_LXC_NETWORK_INTERFACES: u32 = 32;
+
+/// LXC API providing container operations
+pub struct LxcApi<'a> {
+    client: &'a Client,
+    node: String,
+}
+
+impl<'a> LxcApi<'a> {
+    pub fn new(client: &'a Client, node: &str) -> Self {
+        Self {
+            client,
+            node: node.to_string(),
+        }
+    }
+
+    /// GET /api2/json/nodes/{node}/lxc/{vmid}/config
+    pub async fn get_config(&self, vmid: u32) -> Result<LxcConfig, ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc/{}/config", self.node, vmid);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/nodes/{node}/lxc
+    pub async fn create(&self, request: &CreateLxcRequest) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc", self.node);
+        self.client.post(&path, request).await
+    }
+
+    /// PUT /api2/json/nodes/{node}/lxc/{vmid}/config
+    pub async fn update_config(
+        &self,
+        vmid: u32,
+        request: &UpdateLxcRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc/{}/config", self.node, vmid);
+        let _: serde_json::Value = self.client.put(&path, request).await?;
+        Ok(())
+    }
+
+    /// PUT /api2/json/nodes/{node}/lxc/{vmid}/resize
+    ///
+    /// Grows the root filesystem or a mount point to `size`, e.g. "16G".
+    /// Volumes cannot shrink.
+    pub async fn resize(&self, vmid: u32, disk: &str, size: &str) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc/{}/resize", self.node, vmid);
+        self.client
+            .put(&path, &ResizeLxcRequest { disk, size })
+            .await
+    }
+
+    /// DELETE /api2/json/nodes/{node}/lxc/{vmid}. `purge` also removes the
+    /// container from backup jobs, replication and HA.
+    pub async fn delete(&self, vmid: u32, purge: bool) -> Result<TaskId, ApiError> {
+        let params = ApiQueryParams::new().add_optional("purge", purge.then_some("1"));
+        let path = format!(
+            "/api2/json/nodes/{}/lxc/{}{}",
+            self.node,
+            vmid,
+            params.to_query_string()
+        );
+        self.client.delete(&path).await
+    }
+
+    /// POST /api2/json/nodes/{node}/lxc/{vmid}/status/start
+    pub async fn start(&self, vmid: u32) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc/{}/status/start", self.node, vmid);
+        self.client.post(&path, &()).await
+    }
+
+    /// POST /api2/json/nodes/{node}/lxc/{vmid}/status/stop
+    pub async fn stop(&self, vmid: u32) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc/{}/status/stop", self.node, vmid);
+        self.client.post(&path, &()).await
+    }
+
+    /// GET /api2/json/nodes/{node}/lxc/{vmid}/status/current
+    pub async fn get_status(&self, vmid: u32) -> Result<LxcStatus, ApiError> {
+        let path = format!("/api2/json/nodes/{}/lxc/{}/status/current", self.node, vmid);
+        self.client.get(&path).await
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ResizeLxcRequest<'a> {
+    disk: &'a str,
+    size: &'a str,
+}
+
+/// Container configuration
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct LxcConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ostype: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub onboot: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub unprivileged: Option<bool>,
+    /// Property string of the enabled features, e.g. "nesting=1,keyctl=1"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rootfs: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cores: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cpulimit: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cpuunits: Option<u32>,
+    /// Memory in MB
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub memory: Option<u64>,
+    /// Swap in MB
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub swap: Option<u64>,
+    /// Console mode: tty, console or shell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmode: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub tty: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub console: Option<bool>,
+    /// Raw `lxc.*` keys of the config file as (key, value), in file order.
+    /// The API only reports them, they are written to the file directly.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub lxc: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// Indexed options such as mp0, dev0, net0 and unused0, and any option
+    /// without a field
+    #[serde(flatten)]
+    pub other: BTreeMap<String, serde_json::Value>,
+}
+
+impl LxcConfig {
+    /// Values of the options named `prefix` followed by an index, e.g. "mp"
+    /// for the mount points, ordered by index
+    pub fn indexed(&self, prefix: &str) -> Vec<(u32, &str)> {
+        let mut options: Vec<(u32, &str)> = self
+            .other
+            .iter()
+            .filter_map(|(key, value)| {
+                let index = key.strip_prefix(prefix)?.parse().ok()?;
+                Some((index, value.as_str()?))
+            })
+            .collect();
+        options.sort_by_key(|(index, _)| *index);
+        options
+    }
+}
+
+/// Settings shared by the create and update requests
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LxcSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub onboot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cores: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpulimit: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuunits: Option<u32>,
+    /// Memory in MB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u64>,
+    /// Swap in MB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tty: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub console: Option<bool>,
+    /// mp0 to mp255, dev0 to dev255 and net0 to net31
+    #[serde(flatten)]
+    pub indexed: BTreeMap<String, String>,
+}
+
+/// Request body for POST /nodes/{node}/lxc
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateLxcRequest {
+    pub vmid: u32,
+    /// Template volume, e.g. "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst"
+    pub ostemplate: String,
+    /// Root filesystem, "STORAGE:SIZE_IN_GiB" to allocate a new volume
+    pub rootfs: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Public keys for root, one per line
+    #[serde(rename = "ssh-public-keys", skip_serializing_if = "Option::is_none")]
+    pub ssh_public_keys: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unprivileged: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<bool>,
+    #[serde(flatten)]
+    pub settings: LxcSettings,
+}
+
+/// Request body for PUT /nodes/{node}/lxc/{vmid}/config
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateLxcRequest {
+    #[serde(flatten)]
+    pub settings: LxcSettings,
+    /// Comma separated list of options to remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+/// Container status from GET status/current
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LxcStatus {
+    /// "running" or "stopped"
+    pub status: String,
+}
+
+#[cfg(test)]
+#[path = "./lxc_test.rs"]
+mod lxc_test;
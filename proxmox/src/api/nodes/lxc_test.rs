@@ -0,0 +1,165 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_get_config_indexed_options() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/lxc/200/config")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {
+                    "hostname": "web", "arch": "amd64", "ostype": "debian",
+                    "unprivileged": 1, "onboot": "0", "features": "nesting=1,keyctl=1",
+                    "rootfs": "local-lvm:vm-200-disk-0,size=8G", "memory": 512,
+                    "cores": 2, "cpulimit": "1.5", "cmode": "console", "tty": 0,
+                    "lxc": [["lxc.cgroup2.devices.allow", "c 188:* rwm"],
+                            ["lxc.mount.entry", "/dev/ttyUSB0 dev/ttyUSB0 none bind,optional,create=file"]],
+                    "mp10": "/srv/data,mp=/data,ro=1",
+                    "mp2": "local-lvm:vm-200-disk-1,mp=/var/lib/db,size=16G",
+                    "dev0": "/dev/ttyUSB0,mode=0660",
+                    "net0": "name=eth0,bridge=vmbr0,hwaddr=BC:24:11:00:00:01,ip=dhcp,type=veth",
+                    "digest": "abc"
+                }}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let config = client
+            .nodes()
+            .node("pve")
+            .lxc()
+            .get_config(200)
+            .await
+            .unwrap();
+
+        assert_eq!(config.hostname.as_deref(), Some("web"));
+        assert_eq!(config.unprivileged, Some(true));
+        assert_eq!(config.onboot, Some(false));
+        assert_eq!(config.memory, Some(512));
+        assert_eq!(config.cpulimit, Some(1.5));
+        assert_eq!(config.cmode.as_deref(), Some("console"));
+        assert_eq!(config.tty, Some(0));
+        assert_eq!(config.lxc.len(), 2);
+        assert_eq!(
+            config.lxc[0],
+            (
+                "lxc.cgroup2.devices.allow".to_string(),
+                "c 188:* rwm".to_string()
+            )
+        );
+        assert_eq!(
+            config.indexed("mp"),
+            vec![
+                (2, "local-lvm:vm-200-disk-1,mp=/var/lib/db,size=16G"),
+                (10, "/srv/data,mp=/data,ro=1"),
+            ]
+        );
+        assert_eq!(config.indexed("dev"), vec![(0, "/dev/ttyUSB0,mode=0660")]);
+        assert_eq!(config.indexed("net").len(), 1);
+        assert!(config.indexed("unused").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_container() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/nodes/pve/lxc")
+            .match_body(Matcher::Json(serde_json::json!({
+                "vmid": 200,
+                "ostemplate": "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst",
+                "rootfs": "local-lvm:8",
+                "ssh-public-keys": "ssh-ed25519 AAAA user@host",
+                "unprivileged": true,
+                "hostname": "web",
+                "features": "nesting=1",
+                "mp0": "local-lvm:16,mp=/var/lib/db",
+                "net0": "name=eth0,bridge=vmbr0,ip=dhcp"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve:00001234:00000000:65000000:vzcreate:200:root@pam:"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let mut request = CreateLxcRequest {
+            vmid: 200,
+            ostemplate: "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst".to_string(),
+            rootfs: "local-lvm:8".to_string(),
+            ssh_public_keys: Some("ssh-ed25519 AAAA user@host".to_string()),
+            unprivileged: Some(true),
+            ..Default::default()
+        };
+        request.settings.hostname = Some("web".to_string());
+        request.settings.features = Some("nesting=1".to_string());
+        request
+            .settings
+            .indexed
+            .insert("mp0".to_string(), "local-lvm:16,mp=/var/lib/db".to_string());
+        request.settings.indexed.insert(
+            "net0".to_string(),
+            "name=eth0,bridge=vmbr0,ip=dhcp".to_string(),
+        );
+
+        let upid = client
+            .nodes()
+            .node("pve")
+            .lxc()
+            .create(&request)
+            .await
+            .unwrap();
+
+        create.assert_async().await;
+        assert_eq!(upid.node(), Some("pve"));
+    }
+
+    #[tokio::test]
+    async fn test_update_config_and_delete() {
+        let mut server = Server::new_async().await;
+        let update = server
+            .mock("PUT", "/api2/json/nodes/pve/lxc/200/config")
+            .match_body(Matcher::Json(serde_json::json!({
+                "mp0": "local-lvm:vm-200-disk-1,mp=/srv,backup=1",
+                "delete": "dev0,mp1",
+                "digest": "abc"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/api2/json/nodes/pve/lxc/200")
+            .match_query(Matcher::UrlEncoded("purge".to_string(), "1".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve:00001235:00000000:65000000:vzdestroy:200:root@pam:"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let lxc = client.nodes().node("pve").lxc();
+        let mut request = UpdateLxcRequest {
+            delete: Some("dev0,mp1".to_string()),
+            digest: Some("abc".to_string()),
+            ..Default::default()
+        };
+        request.settings.indexed.insert(
+            "mp0".to_string(),
+            "local-lvm:vm-200-disk-1,mp=/srv,backup=1".to_string(),
+        );
+
+        lxc.update_config(200, &request).await.unwrap();
+        lxc.delete(200, true).await.unwrap();
+
+        update.assert_async().await;
+        delete.assert_async().await;
+    }
+}
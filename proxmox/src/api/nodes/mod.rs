@@ -1,10 +1,56 @@
 //! Nodes API module for accessing node-specific resources
 
-use crate::api::{client::Client, error::ApiError};
+use crate::api::{
+    client::Client,
+    common::{deserialize_number_option, deserialize_seconds_option, deserialize_size_option},
+    error::ApiError,
+};
 use serde::{Deserialize, Serialize};
 
+mod aplinfo;
+mod apt;
+mod ceph;
+mod disks;
+mod hardware;
+mod lxc;
+mod network;
 mod qemu;
-pub use qemu::{CreateQemuRequest, QemuApi, QemuConfig, QemuStatus, QemuVmInfo, UpdateQemuRequest};
+mod rrd;
+mod storage;
+mod system;
+mod tasks;
+mod vzdump;
+pub use aplinfo::{ApplianceTemplate, DownloadTemplateRequest};
+pub use apt::{
+    is_standard_repository_handle, AddAptRepositoryRequest, AptRepositories, AptRepository,
+    AptRepositoryFile, AptStandardRepository, AptUpdate, SetAptRepositoryRequest,
+};
+pub use ceph::{
+    CephOsdNode, CephOsdTree, CephPool, CreateCephPoolRequest, UpdateCephPoolRequest,
+    CEPH_PG_AUTOSCALE_MODES, CEPH_POOL_APPLICATIONS,
+};
+pub use disks::{
+    CreateDiskStorageRequest, DiskDirectory, DiskStorageKind, LvmThinPool, LvmVolumeGroup,
+    NodeDisk, ZfsPool, DIRECTORY_FILESYSTEMS, ZFS_COMPRESSION, ZFS_RAID_LEVELS,
+};
+pub use hardware::{normalize_hex_id, HardwareApi, PciDevice, UsbDevice};
+pub use lxc::{
+    CreateLxcRequest, LxcApi, LxcConfig, LxcSettings, LxcStatus, UpdateLxcRequest, MAX_DEVICES,
+    MAX_LXC_NETWORK_INTERFACES, MAX_MOUNT_POINTS,
+};
+pub use network::NodeNetworkInterface;
+pub use qemu::{
+    AgentExecRequest, AgentExecStatus, AgentSetUserPasswordRequest, CreateQemuRequest,
+    GuestIpAddress, GuestNetworkInterface, QemuApi, QemuConfig, QemuPendingChange, QemuSettings,
+    QemuStatus, QemuVmInfo, UpdateQemuRequest, DRIVE_SLOTS, MAX_NETWORK_INTERFACES,
+};
+pub use rrd::{RrdConsolidation, RrdDataPoint, RrdTimeframe};
+pub use storage::{
+    parse_volid, DownloadUrlRequest, StorageApi, StorageContent, VolumeInfo, CHECKSUM_ALGORITHMS,
+};
+pub use system::{NodeDns, NodeTime, UpdateNodeDnsRequest, MAX_DNS_SERVERS};
+pub use tasks::{TaskLogLine, TaskStatus};
+pub use vzdump::{VzdumpRequest, VZDUMP_COMPRESSION, VZDUMP_MODES};
 
 pub struct NodesApi<'a> {
     client: &'a Client,
@@ -33,9 +79,27 @@ pub struct NodeApi<'a> {
 }
 
 impl<'a> NodeApi<'a> {
+    pub fn hardware(&self) -> HardwareApi<'a> {
+        HardwareApi::new(self.client, &self.node)
+    }
+
+    pub fn lxc(&self) -> LxcApi<'a> {
+        LxcApi::new(self.client, &self.node)
+    }
+
     pub fn qemu(&self) -> QemuApi<'a> {
         QemuApi::new(self.client, &self.node)
     }
+
+    pub fn storage(&self, storage: &str) -> StorageApi<'a> {
+        StorageApi::new(self.client, &self.node, storage)
+    }
+
+    /// GET /api2/json/nodes/{node}/status
+    pub async fn status(&self) -> Result<NodeStatusDetail, ApiError> {
+        let path = format!("/api2/json/nodes/{}/status", self.node);
+        self.client.get(&path).await
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,11 +108,77 @@ pub struct NodeStatus {
     pub status: String,
     #[serde(rename = "type")]
     pub type_: String,
+    #[serde(deserialize_with = "deserialize_number_option", default)]
     pub cpu: Option<f64>,
+    #[serde(deserialize_with = "deserialize_number_option", default)]
     pub maxcpu: Option<u32>,
+    #[serde(deserialize_with = "deserialize_size_option", default)]
     pub mem: Option<u64>,
+    #[serde(deserialize_with = "deserialize_size_option", default)]
     pub maxmem: Option<u64>,
+    #[serde(deserialize_with = "deserialize_size_option", default)]
     pub disk: Option<u64>,
+    #[serde(deserialize_with = "deserialize_size_option", default)]
     pub maxdisk: Option<u64>,
+    #[serde(deserialize_with = "deserialize_seconds_option", default)]
     pub uptime: Option<u64>,
 }
+
+/// Detailed node status, including host CPU information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatusDetail {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuinfo: Option<NodeCpuInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kversion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pveversion: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_seconds_option",
+        default
+    )]
+    pub uptime: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeCpuInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cpus: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub sockets: Option<u32>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cores: Option<u32>,
+    /// Space separated list of CPU flags as reported by /proc/cpuinfo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<String>,
+}
+
+impl NodeCpuInfo {
+    /// Returns the hardware virtualization flag exposed by the host CPU,
+    /// `vmx` for Intel VT-x or `svm` for AMD-V
+    pub fn virtualization_flag(&self) -> Option<&'static str> {
+        let flags = self.flags.as_deref()?;
+        if flags.split_whitespace().any(|f| f == "vmx") {
+            Some("vmx")
+        } else if flags.split_whitespace().any(|f| f == "svm") {
+            Some("svm")
+        } else {
+            None
+        }
+    }
+}
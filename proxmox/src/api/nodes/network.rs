@@ -0,0 +1,42 @@
+//! Network interfaces of a node
+
+use super::NodeApi;
+use crate::api::{
+    common::{deserialize_proxmox_bool_option, ApiQueryParams},
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+
+/// Network interface of a node, from GET /nodes/{node}/network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeNetworkInterface {
+    pub iface: String,
+    /// "bridge", "OVSBridge", "eth", "bond", "vlan", ...
+    #[serde(rename = "type")]
+    pub iface_type: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/network, optionally only interfaces of
+    /// one type such as "any_bridge"
+    pub async fn network(
+        &self,
+        iface_type: Option<&str>,
+    ) -> Result<Vec<NodeNetworkInterface>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/network", self.node);
+        let params = ApiQueryParams::new().add_optional("type", iface_type);
+        self.client.get_with_params(&path, &params).await
+    }
+}
+
+#[cfg(test)]
+#[path = "./network_test.rs"]
+mod network_test;
@@ -0,0 +1,41 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_list_bridges() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/network")
+            .match_query(Matcher::UrlEncoded(
+                "type".to_string(),
+                "any_bridge".to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"iface": "vmbr0", "type": "bridge", "active": 1, "autostart": 1},
+                    {"iface": "vmbr1", "type": "OVSBridge"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let bridges = client
+            .nodes()
+            .node("pve")
+            .network(Some("any_bridge"))
+            .await
+            .unwrap();
+
+        assert_eq!(bridges.len(), 2);
+        assert_eq!(bridges[0].iface, "vmbr0");
+        assert_eq!(bridges[0].active, Some(true));
+        assert_eq!(bridges[1].iface_type, "OVSBridge");
+        assert_eq!(bridges[1].active, None);
+    }
+}
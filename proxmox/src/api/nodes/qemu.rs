@@ -1,47 +1,18 @@
 //! QEMU/KVM virtual machine API implementation
 
-use crate::api::{common::TaskId, error::ApiError, Client};
-use serde::{Deserialize, Deserializer, Serialize};
-
-fn deserialize_optional_string_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrU64 {
-        String(String),
-        U64(u64),
-    }
-
-    match Option::<StringOrU64>::deserialize(deserializer)? {
-        Some(StringOrU64::String(s)) => {
-            s.parse::<u64>().map(Some).map_err(serde::de::Error::custom)
-        }
-        Some(StringOrU64::U64(u)) => Ok(Some(u)),
-        None => Ok(None),
-    }
-}
-
-fn deserialize_optional_string_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrU32 {
-        String(String),
-        U32(u32),
-    }
+use super::rrd::{rrd_query_params, RrdConsolidation, RrdDataPoint, RrdTimeframe};
+use crate::api::{
+    common::{
+        deserialize_number, deserialize_number_option, deserialize_proxmox_bool,
+        deserialize_proxmox_bool_option, deserialize_seconds_option, deserialize_size_option,
+        serialize_percent_encoded_option, ApiQueryParams, TaskId,
+    },
+    error::ApiError,
+    BodyEncoding, Client,
+};
+use serde::{Deserialize, Serialize};
 
-    match Option::<StringOrU32>::deserialize(deserializer)? {
-        Some(StringOrU32::String(s)) => {
-            s.parse::<u32>().map(Some).map_err(serde::de::Error::custom)
-        }
-        Some(StringOrU32::U32(u)) => Ok(Some(u)),
-        None => Ok(None),
-    }
-}
+pub use crate::api::generated::{QemuConfig, QemuSettings};
 
 /// QEMU API providing virtual machine operations
 pub struct QemuApi<'a> {
@@ -76,7 +47,9 @@ impl<'a> QemuApi<'a> {
         request: &CreateQemuRequest,
     ) -> Result<TaskId, ApiError> {
         let path = format!("/api2/json/nodes/{}/qemu", self.node);
-        self.client.post(&path, request).await
+        self.client
+            .post_with_encoding(&path, request, request.body_encoding())
+            .await
     }
 
     /// POST /api2/json/nodes/{node}/qemu/{vmid}/config
@@ -86,7 +59,9 @@ impl<'a> QemuApi<'a> {
         request: &UpdateQemuRequest,
     ) -> Result<Option<TaskId>, ApiError> {
         let path = format!("/api2/json/nodes/{}/qemu/{}/config", self.node, vmid);
-        self.client.post(&path, request).await
+        self.client
+            .post_with_encoding(&path, request, request.body_encoding())
+            .await
     }
 
     /// DELETE /api2/json/nodes/{node}/qemu/{vmid}
@@ -111,6 +86,58 @@ impl<'a> QemuApi<'a> {
         self.client.post(&path, &()).await
     }
 
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/status/shutdown
+    ///
+    /// Asks the guest to power off through ACPI or the guest agent.
+    pub async fn shutdown(&self, vmid: u32) -> Result<TaskId, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/qemu/{}/status/shutdown",
+            self.node, vmid
+        );
+        self.client.post(&path, &()).await
+    }
+
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/status/reset
+    ///
+    /// Resets the VM like pressing its reset button, without asking the guest.
+    pub async fn reset(&self, vmid: u32) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/status/reset", self.node, vmid);
+        self.client.post(&path, &()).await
+    }
+
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/status/reboot
+    ///
+    /// Shuts the VM down and starts it again, applying pending changes.
+    pub async fn reboot(&self, vmid: u32) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/status/reboot", self.node, vmid);
+        self.client.post(&path, &()).await
+    }
+
+    /// GET /api2/json/nodes/{node}/qemu/{vmid}/pending
+    pub async fn pending(&self, vmid: u32) -> Result<Vec<QemuPendingChange>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/pending", self.node, vmid);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/template
+    pub async fn template(&self, vmid: u32) -> Result<Option<TaskId>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/template", self.node, vmid);
+        self.client.post(&path, &()).await
+    }
+
+    /// GET /api2/json/nodes/{node}/qemu/{vmid}/rrddata
+    pub async fn rrddata(
+        &self,
+        vmid: u32,
+        timeframe: RrdTimeframe,
+        cf: Option<RrdConsolidation>,
+    ) -> Result<Vec<RrdDataPoint>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/rrddata", self.node, vmid);
+        self.client
+            .get_with_params(&path, &rrd_query_params(timeframe, cf))
+            .await
+    }
+
     /// GET /api2/json/nodes/{node}/qemu/{vmid}/status/current
     pub async fn get_status(&self, vmid: u32) -> Result<QemuStatus, ApiError> {
         let path = format!(
@@ -119,733 +146,462 @@ impl<'a> QemuApi<'a> {
         );
         self.client.get(&path).await
     }
+
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/agent/ping
+    ///
+    /// Succeeds once the guest agent inside the VM answers.
+    pub async fn agent_ping(&self, vmid: u32) -> Result<(), ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/agent/ping", self.node, vmid);
+        let _: serde_json::Value = self.client.post(&path, &()).await?;
+        Ok(())
+    }
+
+    /// GET /api2/json/nodes/{node}/qemu/{vmid}/agent/network-get-interfaces
+    ///
+    /// Fails while the guest agent is not running inside the VM.
+    pub async fn agent_network_interfaces(
+        &self,
+        vmid: u32,
+    ) -> Result<Vec<GuestNetworkInterface>, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/qemu/{}/agent/network-get-interfaces",
+            self.node, vmid
+        );
+        let response: AgentResult<Vec<GuestNetworkInterface>> = self.client.get(&path).await?;
+        Ok(response.result)
+    }
+
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/agent/exec
+    ///
+    /// Starts a command inside the guest and returns its pid. The command
+    /// keeps running, agent_exec_status tells when it is done.
+    pub async fn agent_exec(&self, vmid: u32, request: &AgentExecRequest) -> Result<u64, ApiError> {
+        let path = format!("/api2/json/nodes/{}/qemu/{}/agent/exec", self.node, vmid);
+        let response: AgentExecStarted = self.client.post(&path, request).await?;
+        Ok(response.pid)
+    }
+
+    /// GET /api2/json/nodes/{node}/qemu/{vmid}/agent/exec-status
+    pub async fn agent_exec_status(
+        &self,
+        vmid: u32,
+        pid: u64,
+    ) -> Result<AgentExecStatus, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/qemu/{}/agent/exec-status",
+            self.node, vmid
+        );
+        self.client
+            .get_with_params(&path, &ApiQueryParams::new().add("pid", pid))
+            .await
+    }
+
+    /// POST /api2/json/nodes/{node}/qemu/{vmid}/agent/set-user-password
+    ///
+    /// Sets the password of a user inside the guest. The user must exist.
+    pub async fn agent_set_user_password(
+        &self,
+        vmid: u32,
+        request: &AgentSetUserPasswordRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/qemu/{}/agent/set-user-password",
+            self.node, vmid
+        );
+        let _: serde_json::Value = self.client.post(&path, request).await?;
+        Ok(())
+    }
+}
+
+/// Guest agent commands wrap their output in a result object
+#[derive(Debug, Deserialize)]
+struct AgentResult<T> {
+    result: T,
+}
+
+/// Command to run inside the guest through the guest agent
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentExecRequest {
+    /// Program followed by its arguments
+    pub command: Vec<String>,
+    /// Passed to the command on stdin
+    #[serde(rename = "input-data", skip_serializing_if = "Option::is_none")]
+    pub input_data: Option<String>,
+}
+
+/// Password to set for a user inside the guest through the guest agent
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentSetUserPasswordRequest {
+    pub username: String,
+    pub password: String,
+    /// The password is already hashed as crypt(3) expects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crypted: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AgentExecStarted {
+    #[serde(deserialize_with = "deserialize_number")]
+    pid: u64,
+}
+
+/// State of a command started with agent_exec
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AgentExecStatus {
+    #[serde(deserialize_with = "deserialize_proxmox_bool_option", default)]
+    pub exited: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub exitcode: Option<i64>,
+    /// Set instead of exitcode when a signal ended the command
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub signal: Option<i64>,
+    #[serde(rename = "out-data", skip_serializing_if = "Option::is_none")]
+    pub out_data: Option<String>,
+    #[serde(rename = "err-data", skip_serializing_if = "Option::is_none")]
+    pub err_data: Option<String>,
+    #[serde(
+        rename = "out-truncated",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub out_truncated: Option<bool>,
+    #[serde(
+        rename = "err-truncated",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub err_truncated: Option<bool>,
+}
+
+impl AgentExecStatus {
+    pub fn has_exited(&self) -> bool {
+        self.exited == Some(true)
+    }
+}
+
+/// Network interface reported by the guest agent
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GuestNetworkInterface {
+    pub name: String,
+    #[serde(rename = "hardware-address", skip_serializing_if = "Option::is_none")]
+    pub hardware_address: Option<String>,
+    #[serde(rename = "ip-addresses", default)]
+    pub ip_addresses: Vec<GuestIpAddress>,
+}
+
+/// Address assigned to a guest network interface
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GuestIpAddress {
+    #[serde(rename = "ip-address")]
+    pub ip_address: String,
+    /// ipv4 or ipv6
+    #[serde(rename = "ip-address-type")]
+    pub ip_address_type: String,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub prefix: Option<u8>,
+}
+
+impl GuestNetworkInterface {
+    pub fn is_loopback(&self) -> bool {
+        self.name == "lo" || self.name.starts_with("Loopback")
+    }
+
+    /// Addresses of the given type, either "ipv4" or "ipv6"
+    pub fn addresses(&self, ip_type: &str) -> Vec<String> {
+        self.ip_addresses
+            .iter()
+            .filter(|a| a.ip_address_type == ip_type)
+            .map(|a| a.ip_address.clone())
+            .collect()
+    }
 }
 
 /// Item in VM list response
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct QemuVmInfo {
+    #[serde(deserialize_with = "deserialize_number")]
     pub vmid: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     pub status: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub cpu: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub cpus: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub maxdisk: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub maxmem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub mem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub netin: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub netout: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub disk: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub diskread: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub diskwrite: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub pid: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub qmpstatus: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_seconds_option",
+        default
+    )]
     pub uptime: Option<u64>,
 }
 
-/// VM configuration
-#[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct QemuConfig {
+/// Request for creating a VM
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct CreateQemuRequest {
+    pub vmid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub acpi: Option<bool>,
+    #[serde(rename = "full_clone")]
+    pub full: Option<bool>,
+    /// Backup volume ID to restore the VM from
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub agent: Option<String>,
+    pub archive: Option<String>,
+    /// Storage for the restored disks, only used with archive
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub args: Option<String>,
+    pub storage: Option<String>,
+    /// Give the restored VM new MAC addresses, only used with archive
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub autostart: Option<bool>,
+    pub unique: Option<bool>,
+    /// Resource pool the new VM joins
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub balloon: Option<u64>,
+    pub pool: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub bios: Option<String>,
+    pub start: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub boot: Option<String>,
+    pub ciuser: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub bootdisk: Option<String>,
+    pub cipassword: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub cdrom: Option<String>,
+    pub ciupgrade: Option<bool>,
     #[serde(
         skip_serializing_if = "Option::is_none",
-        deserialize_with = "deserialize_optional_string_u32",
-        default
+        serialize_with = "serialize_percent_encoded_option"
     )]
-    pub cores: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpu: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpulimit: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpuunits: Option<u32>,
+    pub sshkeys: Option<String>,
+    #[serde(flatten)]
+    pub settings: QemuSettings,
+}
+
+impl CreateQemuRequest {
+    /// SSH keys and multi-line values only survive form encoding, everything
+    /// else is sent as JSON
+    pub fn body_encoding(&self) -> BodyEncoding {
+        if self.sshkeys.is_some() || is_multi_line(&self.settings.description) {
+            BodyEncoding::Form
+        } else {
+            BodyEncoding::Json
+        }
+    }
+}
+
+fn is_multi_line(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| v.contains('\n'))
+}
+
+/// Request for updating a VM
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct UpdateQemuRequest {
+    #[serde(flatten)]
+    pub settings: QemuSettings,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
+    pub delete: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub digest: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub efidisk0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub freeze: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hookscript: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hotplug: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hugepages: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide3: Option<String>,
+    pub revert: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_percent_encoded_option"
+    )]
+    pub sshkeys: Option<String>,
+}
+
+impl UpdateQemuRequest {
+    /// SSH keys and multi-line descriptions are sent form encoded,
+    /// everything else as JSON
+    pub fn body_encoding(&self) -> BodyEncoding {
+        if self.sshkeys.is_some() || is_multi_line(&self.settings.description) {
+            BodyEncoding::Form
+        } else {
+            BodyEncoding::Json
+        }
+    }
+}
+
+/// Config option with its current and staged value, from GET /pending
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QemuPendingChange {
+    pub key: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub kvm: Option<bool>,
+    pub value: Option<serde_json::Value>,
+    /// New value that applies on the next reboot
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub localtime: Option<bool>,
+    pub pending: Option<serde_json::Value>,
+    /// Set when the option is removed on the next reboot
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option"
+    )]
+    pub delete: Option<u8>,
+}
+
+impl QemuPendingChange {
+    /// Whether the option changes on the next reboot
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some() || self.delete.is_some_and(|delete| delete > 0)
+    }
+}
+
+/// VM status information
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QemuStatus {
+    pub status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub lock: Option<String>,
+    pub ha: Option<HaStatus>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub machine: Option<String>,
+    pub qmpstatus: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
-        deserialize_with = "deserialize_optional_string_u64",
+        deserialize_with = "deserialize_number_option",
         default
     )]
-    pub memory: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub migrate_downtime: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub migrate_speed: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub nameserver: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub onboot: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ostype: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub protection: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reboot: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi6: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi7: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsihw: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub searchdomain: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub shares: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub smbios1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub smp: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sockets: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub startup: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub startdate: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tablet: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub template: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vcpus: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vga: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio6: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio7: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio8: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio9: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio10: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio11: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio12: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio13: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio14: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio15: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vmgenid: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vmstatestorage: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub watchdog: Option<String>,
-}
-
-/// Request for creating a VM
-#[derive(Debug, Clone, Serialize, Default)]
-pub struct CreateQemuRequest {
-    pub vmid: u32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub clone: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "full_clone")]
-    pub full: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub acpi: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub agent: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub args: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub autostart: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub balloon: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub bios: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub boot: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub bootdisk: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cdrom: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cores: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpu: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpulimit: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpuunits: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub efidisk0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub freeze: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hookscript: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hotplug: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hugepages: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub kvm: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub localtime: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub lock: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub machine: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub memory: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub migrate_downtime: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub migrate_speed: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub nameserver: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub onboot: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ostype: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub protection: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reboot: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi6: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi7: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsihw: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub searchdomain: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub shares: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub smbios1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub smp: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sockets: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub startup: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub startdate: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tablet: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub template: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vcpus: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vga: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio6: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio7: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio8: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio9: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio10: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio11: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio12: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio13: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio14: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio15: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vmgenid: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vmstatestorage: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub watchdog: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ciuser: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cipassword: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ciupgrade: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ipconfig0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ipconfig1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sshkeys: Option<String>,
-}
-
-/// Request for updating a VM
-#[derive(Debug, Clone, Serialize, Default)]
-pub struct UpdateQemuRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub acpi: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub agent: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub args: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub autostart: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub balloon: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub bios: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub boot: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub bootdisk: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cdrom: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cores: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpu: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpulimit: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cpuunits: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub delete: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub digest: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub efidisk0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub freeze: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hookscript: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hotplug: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub hugepages: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ide3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub kvm: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub localtime: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub lock: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub machine: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub memory: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub migrate_downtime: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub migrate_speed: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub nameserver: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub net3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub numa1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub onboot: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ostype: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub protection: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub reboot: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub revert: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sata5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi6: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsi7: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scsihw: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub searchdomain: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub serial3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub shares: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub smbios1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub smp: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub sockets: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub startup: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub startdate: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tablet: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tags: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub template: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub unused3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub usb3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vcpus: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vga: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio0: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio1: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio2: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio3: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio4: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio5: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio6: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio7: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio8: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio9: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio10: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio11: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio12: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio13: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio14: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub virtio15: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vmgenid: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vmstatestorage: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub watchdog: Option<String>,
-}
-
-/// VM status information
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct QemuStatus {
-    pub status: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub ha: Option<HaStatus>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub qmpstatus: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub pid: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_seconds_option",
+        default
+    )]
     pub uptime: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub cpu: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub cpus: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub mem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub maxmem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub disk: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub maxdisk: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub diskread: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub diskwrite: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub netin: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub netout: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ballooninfo: Option<BalloonInfo>,
@@ -862,51 +618,211 @@ pub struct QemuStatus {
 /// HA status information
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HaStatus {
-    #[serde(deserialize_with = "deserialize_bool_from_int")]
+    #[serde(deserialize_with = "deserialize_proxmox_bool")]
     pub managed: bool,
 }
 
-fn deserialize_bool_from_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum BoolOrInt {
-        Bool(bool),
-        Int(u8),
-    }
-
-    match BoolOrInt::deserialize(deserializer)? {
-        BoolOrInt::Bool(b) => Ok(b),
-        BoolOrInt::Int(0) => Ok(false),
-        BoolOrInt::Int(1) => Ok(true),
-        BoolOrInt::Int(_) => Err(serde::de::Error::custom("expected 0 or 1")),
-    }
-}
-
 /// Balloon memory information
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BalloonInfo {
+    #[serde(deserialize_with = "deserialize_number")]
     pub actual: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub max_mem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub total_mem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
     pub free_mem: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub mem_swapped_in: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub mem_swapped_out: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub major_page_faults: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub minor_page_faults: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
     pub last_update: Option<u64>,
 }
 
+/// Generates slot based accessors for the drive fields shared by the config
+/// and the request settings
+macro_rules! drive_slots {
+    ($($slot:ident),* $(,)?) => {
+        /// Drive slots a volume can be attached to
+        pub const DRIVE_SLOTS: &[&str] = &[$(stringify!($slot)),*];
+
+        impl QemuConfig {
+            /// Returns the drive in the given slot, e.g. "scsi1"
+            pub fn drive(&self, slot: &str) -> Option<&str> {
+                match slot {
+                    $(stringify!($slot) => self.$slot.as_deref(),)*
+                    _ => None,
+                }
+            }
+
+            /// Volumes detached from the VM but not yet deleted
+            pub fn unused_volumes(&self) -> Vec<&str> {
+                [&self.unused0, &self.unused1, &self.unused2, &self.unused3]
+                    .into_iter()
+                    .filter_map(|v| v.as_deref())
+                    .collect()
+            }
+        }
+
+        impl QemuSettings {
+            /// Sets the drive in the given slot. Returns false for unknown slots
+            pub fn set_drive(&mut self, slot: &str, value: String) -> bool {
+                match slot {
+                    $(stringify!($slot) => self.$slot = Some(value),)*
+                    _ => return false,
+                }
+                true
+            }
+
+            /// Removes and returns the drive in the given slot
+            pub fn take_drive(&mut self, slot: &str) -> Option<String> {
+                match slot {
+                    $(stringify!($slot) => self.$slot.take(),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+drive_slots!(
+    ide0, ide1, ide2, ide3, sata0, sata1, sata2, sata3, sata4, sata5, scsi0, scsi1, scsi2, scsi3,
+    scsi4, scsi5, scsi6, scsi7, virtio0, virtio1, virtio2, virtio3, virtio4, virtio5, virtio6,
+    virtio7, virtio8, virtio9, virtio10, virtio11, virtio12, virtio13, virtio14, virtio15,
+);
+
+/// Network interfaces a VM can have, net0 to net31
+pub const MAX_NETWORK_INTERFACES: u32 = 32;
+
+/// Generates id based accessors for the network interface fields and the
+/// cloud-init ipconfig fields paired with them
+macro_rules! network_slots {
+    ($(($id:literal, $net:ident, $ipconfig:ident)),* $(,)?) => {
+        impl QemuConfig {
+            /// Returns the network interface with the given id
+            pub fn net(&self, id: u32) -> Option<&str> {
+                match id {
+                    $($id => self.$net.as_deref(),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the cloud-init network settings of the interface with
+            /// the given id
+            pub fn ipconfig(&self, id: u32) -> Option<&str> {
+                match id {
+                    $($id => self.$ipconfig.as_deref(),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl QemuSettings {
+            /// Returns the network interface with the given id
+            pub fn net(&self, id: u32) -> Option<&str> {
+                match id {
+                    $($id => self.$net.as_deref(),)*
+                    _ => None,
+                }
+            }
+
+            /// Sets the network interface with the given id. Returns false
+            /// for unknown ids
+            pub fn set_net(&mut self, id: u32, value: String) -> bool {
+                match id {
+                    $($id => self.$net = Some(value),)*
+                    _ => return false,
+                }
+                true
+            }
+
+            /// Sets the cloud-init network settings of the interface with
+            /// the given id. Returns false for unknown ids
+            pub fn set_ipconfig(&mut self, id: u32, value: String) -> bool {
+                match id {
+                    $($id => self.$ipconfig = Some(value),)*
+                    _ => return false,
+                }
+                true
+            }
+        }
+
+    };
+}
+
+network_slots!(
+    (0, net0, ipconfig0),
+    (1, net1, ipconfig1),
+    (2, net2, ipconfig2),
+    (3, net3, ipconfig3),
+    (4, net4, ipconfig4),
+    (5, net5, ipconfig5),
+    (6, net6, ipconfig6),
+    (7, net7, ipconfig7),
+    (8, net8, ipconfig8),
+    (9, net9, ipconfig9),
+    (10, net10, ipconfig10),
+    (11, net11, ipconfig11),
+    (12, net12, ipconfig12),
+    (13, net13, ipconfig13),
+    (14, net14, ipconfig14),
+    (15, net15, ipconfig15),
+    (16, net16, ipconfig16),
+    (17, net17, ipconfig17),
+    (18, net18, ipconfig18),
+    (19, net19, ipconfig19),
+    (20, net20, ipconfig20),
+    (21, net21, ipconfig21),
+    (22, net22, ipconfig22),
+    (23, net23, ipconfig23),
+    (24, net24, ipconfig24),
+    (25, net25, ipconfig25),
+    (26, net26, ipconfig26),
+    (27, net27, ipconfig27),
+    (28, net28, ipconfig28),
+    (29, net29, ipconfig29),
+    (30, net30, ipconfig30),
+    (31, net31, ipconfig31),
+);
+
 #[cfg(test)]
 #[path = "./qemu_test.rs"]
 mod qemu_test;
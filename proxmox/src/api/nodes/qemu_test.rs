@@ -1,5 +1,6 @@
 #[cfg(test)]
 #[allow(unused_mut)]
+#[allow(clippy::disallowed_methods)]
 mod tests {
     use super::super::*;
     use crate::api::test_helpers::create_test_client;
@@ -144,8 +145,11 @@ mod tests {
 
         let request = CreateQemuRequest {
             vmid: 100,
-            name: Some("test-vm".to_string()),
-            memory: Some(2048),
+            settings: QemuSettings {
+                name: Some("test-vm".to_string()),
+                memory: Some(2048),
+                ..Default::default()
+            },
             ..Default::default()
         };
 
@@ -155,6 +159,47 @@ mod tests {
         assert!(task_id.0.starts_with("UPID:"));
     }
 
+    #[tokio::test]
+    async fn test_create_vm_with_sshkeys_uses_form_encoding() {
+        let keys = "ssh-ed25519 AAAAC3Nz+key a@example.com\nssh-rsa AAAAB3 b@example.com";
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/api2/json/nodes/node1/qemu")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("vmid".to_string(), "100".to_string()),
+                Matcher::UrlEncoded("memory".to_string(), "2048".to_string()),
+                Matcher::UrlEncoded(
+                    "sshkeys".to_string(),
+                    urlencoding::encode(keys).into_owned(),
+                ),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:node1:00001234:00000000:5F000000:qmcreate:100:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+
+        let request = CreateQemuRequest {
+            vmid: 100,
+            sshkeys: Some(keys.to_string()),
+            settings: QemuSettings {
+                memory: Some(2048),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(request.body_encoding(), BodyEncoding::Form);
+
+        let result = api.create(100, &request).await;
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
     #[tokio::test]
     async fn test_update_config() {
         let mut server = Server::new_async().await;
@@ -178,8 +223,11 @@ mod tests {
         let api = QemuApi::new(&client, "node1");
 
         let request = UpdateQemuRequest {
-            memory: Some(4096),
-            cores: Some(4),
+            settings: QemuSettings {
+                memory: Some(4096),
+                cores: Some(4),
+                ..Default::default()
+            },
             ..Default::default()
         };
 
@@ -188,6 +236,38 @@ mod tests {
         assert!(result.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_update_sshkeys_uses_form_encoding() {
+        let keys = "ssh-ed25519 AAAAC3Nz+key a@example.com\nssh-rsa AAAAB3 b@example.com";
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/config")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body(Matcher::UrlEncoded(
+                "sshkeys".to_string(),
+                urlencoding::encode(keys).into_owned(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+
+        let request = UpdateQemuRequest {
+            sshkeys: Some(keys.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(request.body_encoding(), BodyEncoding::Form);
+
+        let result = api.update_config(100, &request).await;
+        assert!(result.is_ok(), "{:?}", result.err());
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_delete_vm() {
         let mut server = Server::new_async().await;
@@ -260,6 +340,155 @@ mod tests {
         assert!(task_id.0.starts_with("UPID:"));
     }
 
+    #[tokio::test]
+    async fn test_reboot_vm() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/status/reboot")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:node1:00001234:00000000:5F000000:qmreboot:100:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let task_id = api.reboot(100).await.unwrap();
+
+        assert!(task_id.0.contains(":qmreboot:100:"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_vm() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/status/shutdown")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:node1:00001234:00000000:5F000000:qmshutdown:100:root@pam:"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let task_id = api.shutdown(100).await.unwrap();
+
+        assert!(task_id.0.contains(":qmshutdown:100:"));
+    }
+
+    #[tokio::test]
+    async fn test_reset_vm() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/status/reset")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:node1:00001234:00000000:5F000000:qmreset:100:root@pam:"}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let task_id = api.reset(100).await.unwrap();
+
+        assert!(task_id.0.contains(":qmreset:100:"));
+    }
+
+    #[tokio::test]
+    async fn test_pending() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/node1/qemu/100/pending")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"key": "cores", "value": 2},
+                    {"key": "memory", "value": 2048, "pending": 4096},
+                    {"key": "tablet", "value": 1, "delete": 1}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let pending = api.pending(100).await.unwrap();
+
+        let keys: Vec<&str> = pending
+            .iter()
+            .filter(|change| change.is_pending())
+            .map(|change| change.key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["memory", "tablet"]);
+    }
+
+    #[tokio::test]
+    async fn test_template_vm() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/template")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": "UPID:node1:00001234:00000000:5F000000:qmtemplate:100:root@pam:"
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let result = api.template(100).await;
+
+        assert!(result.is_ok());
+        let task_id = result.unwrap().unwrap();
+        assert!(task_id.0.starts_with("UPID:"));
+    }
+
+    #[tokio::test]
+    async fn test_rrddata() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/node1/qemu/100/rrddata")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("timeframe".into(), "hour".into()),
+                Matcher::UrlEncoded("cf".into(), "AVERAGE".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"time": 1700000000, "cpu": 0.25, "maxcpu": 2, "mem": 512, "maxmem": 2048},
+                    {"time": 1700000060}
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let points = api
+            .rrddata(100, RrdTimeframe::Hour, Some(RrdConsolidation::Average))
+            .await
+            .unwrap();
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].cpu, Some(0.25));
+        assert_eq!(points[0].memory_usage(), Some(0.25));
+        assert_eq!(points[1].cpu, None);
+        assert_eq!(points[1].memory_usage(), None);
+    }
+
     #[tokio::test]
     async fn test_stop_vm() {
         let mut server = Server::new_async().await;
@@ -322,6 +551,47 @@ mod tests {
         assert_eq!(status.maxmem, Some(2147483648));
     }
 
+    #[tokio::test]
+    async fn test_get_status_with_string_values() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/node1/qemu/100/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": {
+                    "status": "running",
+                    "ha": {"managed": "0"},
+                    "pid": "1234",
+                    "uptime": "1h 30m",
+                    "cpu": "0.25",
+                    "cpus": "2",
+                    "mem": "512M",
+                    "maxmem": "2G",
+                    "maxdisk": "",
+                    "netin": "2048"
+                }
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let status = api.get_status(100).await.unwrap();
+
+        assert!(!status.ha.unwrap().managed);
+        assert_eq!(status.pid, Some(1234));
+        assert_eq!(status.uptime, Some(5400));
+        assert_eq!(status.cpu, Some(0.25));
+        assert_eq!(status.cpus, Some(2));
+        assert_eq!(status.mem, Some(512 << 20));
+        assert_eq!(status.maxmem, Some(2 << 30));
+        assert_eq!(status.maxdisk, None);
+        assert_eq!(status.netin, Some(2048));
+    }
+
     #[tokio::test]
     async fn test_api_error_handling() {
         let mut server = Server::new_async().await;
@@ -349,4 +619,184 @@ mod tests {
             assert!(e.to_string().contains("404"));
         }
     }
+
+    #[tokio::test]
+    async fn test_agent_network_interfaces() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock(
+                "GET",
+                "/api2/json/nodes/node1/qemu/100/agent/network-get-interfaces",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": {
+                    "result": [
+                        {
+                            "name": "lo",
+                            "hardware-address": "00:00:00:00:00:00",
+                            "ip-addresses": [
+                                {"ip-address": "127.0.0.1", "ip-address-type": "ipv4", "prefix": 8}
+                            ]
+                        },
+                        {
+                            "name": "eth0",
+                            "hardware-address": "bc:24:11:aa:bb:cc",
+                            "ip-addresses": [
+                                {"ip-address": "10.0.0.50", "ip-address-type": "ipv4", "prefix": 24},
+                                {"ip-address": "fe80::be24:11ff:feaa:bbcc", "ip-address-type": "ipv6", "prefix": 64}
+                            ],
+                            "statistics": {"rx-bytes": 1024}
+                        }
+                    ]
+                }
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let interfaces = QemuApi::new(&client, "node1")
+            .agent_network_interfaces(100)
+            .await
+            .unwrap();
+
+        assert_eq!(interfaces.len(), 2);
+        assert!(interfaces[0].is_loopback());
+        assert_eq!(interfaces[1].addresses("ipv4"), vec!["10.0.0.50"]);
+        assert_eq!(interfaces[1].addresses("ipv6").len(), 1);
+        assert_eq!(
+            interfaces[1].hardware_address.as_deref(),
+            Some("bc:24:11:aa:bb:cc")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_ping() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/agent/ping")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"result": {}}}"#)
+            .create_async()
+            .await;
+        let _down = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/101/agent/ping")
+            .with_status(500)
+            .with_body("QEMU guest agent is not running")
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+
+        assert!(api.agent_ping(100).await.is_ok());
+        assert!(api.agent_ping(101).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_agent_exec() {
+        let mut server = Server::new_async().await;
+        let exec = server
+            .mock("POST", "/api2/json/nodes/node1/qemu/100/agent/exec")
+            .match_body(Matcher::Json(serde_json::json!({
+                "command": ["sh", "-c", "cat > /etc/motd"],
+                "input-data": "hello"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"pid": 4242}}"#)
+            .create_async()
+            .await;
+        let _status = server
+            .mock(
+                "GET",
+                "/api2/json/nodes/node1/qemu/100/agent/exec-status?pid=4242",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"exited": 1, "exitcode": 2, "out-data": "", "err-data": "denied\n"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        let pid = api
+            .agent_exec(
+                100,
+                &AgentExecRequest {
+                    command: vec!["sh".into(), "-c".into(), "cat > /etc/motd".into()],
+                    input_data: Some("hello".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        let status = api.agent_exec_status(100, pid).await.unwrap();
+
+        exec.assert_async().await;
+        assert_eq!(pid, 4242);
+        assert!(status.has_exited());
+        assert_eq!(status.exitcode, Some(2));
+        assert_eq!(status.err_data.as_deref(), Some("denied\n"));
+    }
+
+    #[tokio::test]
+    async fn test_agent_set_user_password() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock(
+                "POST",
+                "/api2/json/nodes/node1/qemu/100/agent/set-user-password",
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "username": "admin",
+                "password": "s3cret-pass"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"result": {}}}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = QemuApi::new(&client, "node1");
+        api.agent_set_user_password(
+            100,
+            &AgentSetUserPasswordRequest {
+                username: "admin".to_string(),
+                password: "s3cret-pass".to_string(),
+                crypted: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_network_slots() {
+        let mut request = QemuSettings::default();
+        assert!(request.set_net(31, "virtio,bridge=vmbr0".to_string()));
+        assert!(request.set_ipconfig(31, "ip=dhcp".to_string()));
+        assert!(!request.set_net(MAX_NETWORK_INTERFACES, "virtio".to_string()));
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["net31"], "virtio,bridge=vmbr0");
+        assert_eq!(json["ipconfig31"], "ip=dhcp");
+
+        let config: QemuConfig = serde_json::from_value(serde_json::json!({
+            "net7": "virtio=BC:24:11:00:00:07,bridge=vmbr7",
+            "ipconfig7": "ip=10.0.7.2/24"
+        }))
+        .unwrap();
+        assert_eq!(config.net(7), Some("virtio=BC:24:11:00:00:07,bridge=vmbr7"));
+        assert_eq!(config.ipconfig(7), Some("ip=10.0.7.2/24"));
+        assert_eq!(config.net(0), None);
+    }
 }
@@ -0,0 +1,276 @@
+//! RRD (round robin database) statistics for nodes and guests
+
+use super::NodeApi;
+use crate::api::{
+    common::{deserialize_number, deserialize_number_option, ApiQueryParams},
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Time window covered by an RRD query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RrdTimeframe {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+    Decade,
+}
+
+impl RrdTimeframe {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RrdTimeframe::Hour => "hour",
+            RrdTimeframe::Day => "day",
+            RrdTimeframe::Week => "week",
+            RrdTimeframe::Month => "month",
+            RrdTimeframe::Year => "year",
+            RrdTimeframe::Decade => "decade",
+        }
+    }
+}
+
+impl fmt::Display for RrdTimeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RrdTimeframe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hour" => Ok(RrdTimeframe::Hour),
+            "day" => Ok(RrdTimeframe::Day),
+            "week" => Ok(RrdTimeframe::Week),
+            "month" => Ok(RrdTimeframe::Month),
+            "year" => Ok(RrdTimeframe::Year),
+            "decade" => Ok(RrdTimeframe::Decade),
+            _ => Err(format!(
+                "invalid timeframe '{}', expected one of: hour, day, week, month, year, decade",
+                s
+            )),
+        }
+    }
+}
+
+/// Consolidation function applied to each RRD step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RrdConsolidation {
+    Average,
+    Max,
+}
+
+impl RrdConsolidation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RrdConsolidation::Average => "AVERAGE",
+            RrdConsolidation::Max => "MAX",
+        }
+    }
+}
+
+impl fmt::Display for RrdConsolidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RrdConsolidation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AVERAGE" => Ok(RrdConsolidation::Average),
+            "MAX" => Ok(RrdConsolidation::Max),
+            _ => Err(format!(
+                "invalid consolidation function '{}', expected AVERAGE or MAX",
+                s
+            )),
+        }
+    }
+}
+
+/// A single RRD sample. Nodes and guests report different metrics, so every
+/// value is optional and steps without data omit their fields entirely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RrdDataPoint {
+    #[serde(deserialize_with = "deserialize_number")]
+    pub time: u64,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub cpu: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub maxcpu: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub iowait: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub loadavg: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub memused: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub memtotal: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub swapused: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub swaptotal: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub rootused: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub roottotal: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub mem: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub maxmem: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub disk: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub maxdisk: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub diskread: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub diskwrite: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub netin: Option<f64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub netout: Option<f64>,
+}
+
+impl RrdDataPoint {
+    /// Looks up a metric by its API name
+    pub fn metric(&self, name: &str) -> Option<f64> {
+        match name {
+            "time" => Some(self.time as f64),
+            "cpu" => self.cpu,
+            "maxcpu" => self.maxcpu,
+            "iowait" => self.iowait,
+            "loadavg" => self.loadavg,
+            "memused" => self.memused,
+            "memtotal" => self.memtotal,
+            "swapused" => self.swapused,
+            "swaptotal" => self.swaptotal,
+            "rootused" => self.rootused,
+            "roottotal" => self.roottotal,
+            "mem" => self.mem,
+            "maxmem" => self.maxmem,
+            "disk" => self.disk,
+            "maxdisk" => self.maxdisk,
+            "diskread" => self.diskread,
+            "diskwrite" => self.diskwrite,
+            "netin" => self.netin,
+            "netout" => self.netout,
+            _ => None,
+        }
+    }
+
+    /// Memory usage as a fraction of the available memory
+    pub fn memory_usage(&self) -> Option<f64> {
+        let (used, total) = match (self.memused, self.memtotal) {
+            (Some(used), Some(total)) => (used, total),
+            _ => (self.mem?, self.maxmem?),
+        };
+        if total > 0.0 {
+            Some(used / total)
+        } else {
+            None
+        }
+    }
+}
+
+pub(crate) fn rrd_query_params(
+    timeframe: RrdTimeframe,
+    cf: Option<RrdConsolidation>,
+) -> ApiQueryParams {
+    ApiQueryParams::new()
+        .add("timeframe", timeframe)
+        .add_optional("cf", cf)
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/rrddata
+    pub async fn rrddata(
+        &self,
+        timeframe: RrdTimeframe,
+        cf: Option<RrdConsolidation>,
+    ) -> Result<Vec<RrdDataPoint>, ApiError> {
+        let path = format!("/api2/json/nodes/{}/rrddata", self.node);
+        self.client
+            .get_with_params(&path, &rrd_query_params(timeframe, cf))
+            .await
+    }
+}
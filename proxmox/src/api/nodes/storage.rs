@@ -0,0 +1,187 @@
+//! Node storage API for inspecting storage content
+
+use crate::api::{
+    client::Client,
+    common::{
+        deserialize_number_option, deserialize_proxmox_bool_option, deserialize_size_option,
+        ApiQueryParams, TaskId,
+    },
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+
+/// Checksum algorithms download-url can verify a download with
+pub const CHECKSUM_ALGORITHMS: &[&str] = &["md5", "sha1", "sha224", "sha256", "sha384", "sha512"];
+
+/// Storage API scoped to a single storage on a node
+pub struct StorageApi<'a> {
+    client: &'a Client,
+    node: String,
+    storage: String,
+}
+
+impl<'a> StorageApi<'a> {
+    pub fn new(client: &'a Client, node: &str, storage: &str) -> Self {
+        Self {
+            client,
+            node: node.to_string(),
+            storage: storage.to_string(),
+        }
+    }
+
+    /// GET /api2/json/nodes/{node}/storage/{storage}/content/{volume}
+    pub async fn volume(&self, volid: &str) -> Result<VolumeInfo, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/storage/{}/content/{}",
+            self.node,
+            self.storage,
+            urlencoding::encode(volid)
+        );
+        self.client.get(&path).await
+    }
+
+    /// GET /api2/json/nodes/{node}/storage/{storage}/content
+    pub async fn content(
+        &self,
+        content_type: Option<&str>,
+    ) -> Result<Vec<StorageContent>, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/storage/{}/content",
+            self.node, self.storage
+        );
+        let params = ApiQueryParams::new().add_optional("content", content_type);
+        self.client.get_with_params(&path, &params).await
+    }
+
+    /// GET /api2/json/nodes/{node}/storage/{storage}/content?content=backup,
+    /// optionally only the backups of one guest
+    pub async fn backups(&self, vmid: Option<u32>) -> Result<Vec<StorageContent>, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/storage/{}/content",
+            self.node, self.storage
+        );
+        let params = ApiQueryParams::new()
+            .add("content", "backup")
+            .add_optional("vmid", vmid);
+        self.client.get_with_params(&path, &params).await
+    }
+
+    /// POST /api2/json/nodes/{node}/storage/{storage}/download-url
+    pub async fn download_url(&self, request: &DownloadUrlRequest) -> Result<TaskId, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/storage/{}/download-url",
+            self.node, self.storage
+        );
+        self.client.post(&path, request).await
+    }
+
+    /// DELETE /api2/json/nodes/{node}/storage/{storage}/content/{volume}.
+    /// Returns the task removing the volume, if the storage runs one.
+    pub async fn delete_volume(&self, volid: &str) -> Result<Option<TaskId>, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/storage/{}/content/{}",
+            self.node,
+            self.storage,
+            urlencoding::encode(volid)
+        );
+        self.client.delete(&path).await
+    }
+}
+
+/// Request body for POST /nodes/{node}/storage/{storage}/download-url
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DownloadUrlRequest {
+    /// Content type to store the file as, e.g. iso or vztmpl
+    pub content: String,
+    pub filename: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// One of [`CHECKSUM_ALGORITHMS`], required with checksum
+    #[serde(rename = "checksum-algorithm", skip_serializing_if = "Option::is_none")]
+    pub checksum_algorithm: Option<String>,
+}
+
+/// Entry in a storage content listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageContent {
+    pub volid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub size: Option<u64>,
+    /// Creation time as a unix timestamp
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub ctime: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub vmid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Whether a backup is protected from pruning and removal
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub protected: Option<bool>,
+    /// Guest type of a backup, qemu or lxc
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtype: Option<String>,
+}
+
+impl StorageContent {
+    /// File name part of the volume ID, e.g. "debian-12.iso" for
+    /// "local:iso/debian-12.iso"
+    pub fn file_name(&self) -> &str {
+        let volume = parse_volid(&self.volid).map_or(self.volid.as_str(), |(_, v)| v);
+        volume.rsplit('/').next().unwrap_or(volume)
+    }
+}
+
+/// Volume attributes reported by the storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub size: Option<u64>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_size_option",
+        default
+    )]
+    pub used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+/// Splits a volume ID like "local-lvm:vm-100-disk-1" into storage and volume name
+pub fn parse_volid(volid: &str) -> Option<(&str, &str)> {
+    volid
+        .split_once(':')
+        .filter(|(storage, volume)| !storage.is_empty() && !volume.is_empty())
+}
+
+#[cfg(test)]
+#[path = "./storage_test.rs"]
+mod storage_test;
@@ -0,0 +1,142 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_get_volume() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock(
+                "GET",
+                "/api2/json/nodes/pve/storage/local-lvm/content/local-lvm%3Avm-100-disk-1",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"format": "raw", "path": "/dev/pve/vm-100-disk-1", "size": 10737418240, "used": 0}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let volume = StorageApi::new(&client, "pve", "local-lvm")
+            .volume("local-lvm:vm-100-disk-1")
+            .await
+            .unwrap();
+
+        assert_eq!(volume.format.as_deref(), Some("raw"));
+        assert_eq!(volume.size, Some(10737418240));
+    }
+
+    #[tokio::test]
+    async fn test_list_content() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/storage/local/content")
+            .match_query(Matcher::UrlEncoded("content".into(), "iso".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"volid": "local:iso/debian-12.iso", "content": "iso", "format": "iso", "size": 657457152, "ctime": 1700000000},
+                    {"volid": "local:iso/ubuntu-24.04.iso", "content": "iso", "format": "iso", "size": 2754981888, "ctime": 1710000000}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let content = StorageApi::new(&client, "pve", "local")
+            .content(Some("iso"))
+            .await
+            .unwrap();
+
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0].file_name(), "debian-12.iso");
+        assert_eq!(content[1].ctime, Some(1710000000));
+    }
+
+    #[tokio::test]
+    async fn test_list_backups_of_guest() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/storage/pbs/content")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("content".into(), "backup".into()),
+                Matcher::UrlEncoded("vmid".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"volid": "pbs:backup/vm/100/2024-05-01T10:00:00Z", "content": "backup", "format": "pbs-vm",
+                     "size": 34359738368, "ctime": 1714557600, "vmid": 100, "subtype": "qemu",
+                     "notes": "web01", "protected": 1}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let backups = StorageApi::new(&client, "pve", "pbs")
+            .backups(Some(100))
+            .await
+            .unwrap();
+
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].protected, Some(true));
+        assert_eq!(backups[0].subtype.as_deref(), Some("qemu"));
+    }
+
+    #[tokio::test]
+    async fn test_download_url() {
+        let mut server = Server::new_async().await;
+        let m = server
+            .mock("POST", "/api2/json/nodes/pve/storage/local/download-url")
+            .match_body(Matcher::Json(serde_json::json!({
+                "content": "vztmpl",
+                "filename": "alpine.tar.xz",
+                "url": "https://example.com/alpine.tar.xz",
+                "checksum": "abc123",
+                "checksum-algorithm": "sha256"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve:00001234:00000000:5F000000:download:alpine.tar.xz:root@pam:"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = StorageApi::new(&client, "pve", "local")
+            .download_url(&DownloadUrlRequest {
+                content: "vztmpl".to_string(),
+                filename: "alpine.tar.xz".to_string(),
+                url: "https://example.com/alpine.tar.xz".to_string(),
+                checksum: Some("abc123".to_string()),
+                checksum_algorithm: Some("sha256".to_string()),
+            })
+            .await;
+
+        assert!(result.is_ok(), "{:?}", result.err());
+        m.assert_async().await;
+    }
+
+    #[test]
+    fn test_parse_volid() {
+        assert_eq!(
+            parse_volid("local-lvm:vm-100-disk-1"),
+            Some(("local-lvm", "vm-100-disk-1"))
+        );
+        assert_eq!(
+            parse_volid("local:100/vm-100-disk-0.qcow2"),
+            Some(("local", "100/vm-100-disk-0.qcow2"))
+        );
+        assert_eq!(parse_volid("vm-100-disk-1"), None);
+        assert_eq!(parse_volid(":vm-100-disk-1"), None);
+        assert_eq!(parse_volid("local-lvm:"), None);
+    }
+}
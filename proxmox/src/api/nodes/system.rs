@@ -0,0 +1,125 @@
+//! Node DNS and time settings
+
+use super::NodeApi;
+use crate::api::common::deserialize_number_option;
+use crate::api::error::ApiError;
+use serde::{Deserialize, Serialize};
+
+/// Number of DNS servers a node can be configured with
+pub const MAX_DNS_SERVERS: usize = 3;
+
+/// DNS resolver settings of a node
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeDns {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns2: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns3: Option<String>,
+}
+
+impl NodeDns {
+    /// The configured DNS servers in order
+    pub fn servers(&self) -> Vec<String> {
+        [&self.dns1, &self.dns2, &self.dns3]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Request body for PUT /api2/json/nodes/{node}/dns
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateNodeDnsRequest {
+    pub search: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns2: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns3: Option<String>,
+    /// Comma separated list of settings to clear
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+}
+
+impl UpdateNodeDnsRequest {
+    /// Builds a request setting the given servers in order and clearing the
+    /// remaining slots
+    pub fn new(search: String, servers: &[String]) -> Self {
+        let server = |index: usize| servers.get(index).cloned();
+        let cleared: Vec<String> = (servers.len()..MAX_DNS_SERVERS)
+            .map(|index| format!("dns{}", index + 1))
+            .collect();
+
+        Self {
+            search,
+            dns1: server(0),
+            dns2: server(1),
+            dns3: server(2),
+            delete: (!cleared.is_empty()).then(|| cleared.join(",")),
+        }
+    }
+}
+
+/// Time settings of a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeTime {
+    pub timezone: String,
+    /// Current time as a unix timestamp
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub time: Option<u64>,
+    /// Current local time as seconds since the epoch in the node's timezone
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_number_option",
+        default
+    )]
+    pub localtime: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateNodeTimeRequest<'a> {
+    timezone: &'a str,
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/dns
+    pub async fn dns(&self) -> Result<NodeDns, ApiError> {
+        let path = format!("/api2/json/nodes/{}/dns", self.node);
+        self.client.get(&path).await
+    }
+
+    /// PUT /api2/json/nodes/{node}/dns
+    pub async fn update_dns(&self, request: &UpdateNodeDnsRequest) -> Result<(), ApiError> {
+        let path = format!("/api2/json/nodes/{}/dns", self.node);
+        self.client.put::<(), _>(&path, request).await.map(|_| ())
+    }
+
+    /// GET /api2/json/nodes/{node}/time
+    pub async fn time(&self) -> Result<NodeTime, ApiError> {
+        let path = format!("/api2/json/nodes/{}/time", self.node);
+        self.client.get(&path).await
+    }
+
+    /// PUT /api2/json/nodes/{node}/time
+    pub async fn set_timezone(&self, timezone: &str) -> Result<(), ApiError> {
+        let path = format!("/api2/json/nodes/{}/time", self.node);
+        self.client
+            .put::<(), _>(&path, &UpdateNodeTimeRequest { timezone })
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+#[path = "./system_test.rs"]
+mod system_test;
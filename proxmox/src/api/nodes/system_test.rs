@@ -0,0 +1,94 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::nodes::NodesApi;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[test]
+    fn test_update_dns_request_clears_unused_servers() {
+        let request =
+            UpdateNodeDnsRequest::new("example.com".to_string(), &["1.1.1.1".to_string()]);
+        assert_eq!(request.dns1.as_deref(), Some("1.1.1.1"));
+        assert_eq!(request.dns2, None);
+        assert_eq!(request.delete.as_deref(), Some("dns2,dns3"));
+
+        let servers: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let request = UpdateNodeDnsRequest::new("example.com".to_string(), &servers);
+        assert_eq!(request.dns3.as_deref(), Some("c"));
+        assert_eq!(request.delete, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_and_update_dns() {
+        let mut server = Server::new_async().await;
+        let _get = server
+            .mock("GET", "/api2/json/nodes/pve/dns")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"search": "lan", "dns1": "10.0.0.1", "dns3": "9.9.9.9"}}"#)
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/api2/json/nodes/pve/dns")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"search": "lan", "dns1": "10.0.0.2", "delete": "dns2,dns3"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = NodesApi::new(&client).node("pve");
+
+        let dns = node.dns().await.unwrap();
+        assert_eq!(dns.servers(), vec!["10.0.0.1", "9.9.9.9"]);
+
+        node.update_dns(&UpdateNodeDnsRequest::new(
+            "lan".to_string(),
+            &["10.0.0.2".to_string()],
+        ))
+        .await
+        .unwrap();
+        put.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_and_set_timezone() {
+        let mut server = Server::new_async().await;
+        let _get = server
+            .mock("GET", "/api2/json/nodes/pve/time")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"timezone": "Europe/Berlin", "time": 1700000000, "localtime": 1700003600}}"#,
+            )
+            .create_async()
+            .await;
+        let put = server
+            .mock("PUT", "/api2/json/nodes/pve/time")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"timezone": "UTC"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let node = NodesApi::new(&client).node("pve");
+
+        let time = node.time().await.unwrap();
+        assert_eq!(time.timezone, "Europe/Berlin");
+        assert_eq!(time.time, Some(1700000000));
+
+        node.set_timezone("UTC").await.unwrap();
+        put.assert_async().await;
+    }
+}
@@ -0,0 +1,148 @@
+//! Node task status and waiting for tasks to finish
+
+use super::NodeApi;
+use crate::api::{
+    common::{deserialize_number, ApiListResponse, PaginationParams, TaskId},
+    error::ApiError,
+};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// How often a running task is polled
+const TASK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Lines from the end of a failed task's log that go into its error
+const TASK_LOG_TAIL_LINES: u32 = 20;
+
+/// Status of a task, from GET /nodes/{node}/tasks/{upid}/status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub upid: String,
+    /// "running" or "stopped"
+    pub status: String,
+    /// "OK", "WARNINGS: n" or the error message once the task stopped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exitstatus: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub task_type: Option<String>,
+}
+
+impl TaskStatus {
+    pub fn is_running(&self) -> bool {
+        self.status == "running"
+    }
+
+    /// Whether the task finished without errors. Warnings count as success.
+    pub fn is_successful(&self) -> bool {
+        self.exitstatus
+            .as_deref()
+            .is_some_and(|s| s == "OK" || s.starts_with("WARNINGS"))
+    }
+}
+
+/// A line of a task log, from GET /nodes/{node}/tasks/{upid}/log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskLogLine {
+    /// Line number, counting from 1
+    #[serde(deserialize_with = "deserialize_number")]
+    pub n: u64,
+    /// Line text
+    pub t: String,
+}
+
+impl NodeApi<'_> {
+    /// GET /api2/json/nodes/{node}/tasks/{upid}/status
+    pub async fn task_status(&self, upid: &TaskId) -> Result<TaskStatus, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/tasks/{}/status",
+            self.node,
+            urlencoding::encode(&upid.0)
+        );
+        self.client.get(&path).await
+    }
+
+    /// GET /api2/json/nodes/{node}/tasks/{upid}/log
+    pub async fn task_log(
+        &self,
+        upid: &TaskId,
+        pagination: &PaginationParams,
+    ) -> Result<ApiListResponse<TaskLogLine>, ApiError> {
+        let path = format!(
+            "/api2/json/nodes/{}/tasks/{}/log{}",
+            self.node,
+            urlencoding::encode(&upid.0),
+            pagination.to_query_params().to_query_string()
+        );
+        self.client.get_raw(&path).await
+    }
+
+    /// The last `lines` lines of a task log. Proxmox pages the log from the
+    /// start, so the first page tells how long it is.
+    pub async fn task_log_tail(&self, upid: &TaskId, lines: u32) -> Result<Vec<String>, ApiError> {
+        let mut page = self
+            .task_log(upid, &PaginationParams::new().with_limit(lines))
+            .await?;
+        if let Some(total) = page.total.filter(|&total| total > lines) {
+            page = self
+                .task_log(
+                    upid,
+                    &PaginationParams::new()
+                        .with_start(total - lines)
+                        .with_limit(lines),
+                )
+                .await?;
+        }
+        Ok(page.data.into_iter().map(|line| line.t).collect())
+    }
+
+    /// Polls a task until it stops. Fails if it does not finish within
+    /// `timeout` or finishes with an error, in which case the error carries
+    /// the end of the task log. Gives up waiting once `cancelled` completes,
+    /// leaving the task running.
+    pub async fn wait_for_task(
+        &self,
+        upid: &TaskId,
+        timeout: Duration,
+        cancelled: impl Future<Output = ()>,
+    ) -> Result<TaskStatus, ApiError> {
+        tokio::select! {
+            result = self.poll_task(upid, timeout) => result,
+            _ = cancelled => Err(ApiError::TaskCancelled {
+                upid: upid.0.clone(),
+            }),
+        }
+    }
+
+    async fn poll_task(&self, upid: &TaskId, timeout: Duration) -> Result<TaskStatus, ApiError> {
+        let started = Instant::now();
+        loop {
+            let status = self.task_status(upid).await?;
+            if !status.is_running() {
+                if status.is_successful() {
+                    return Ok(status);
+                }
+                // The exit status is only the last error line, the log says
+                // what led up to it
+                let log = self
+                    .task_log_tail(upid, TASK_LOG_TAIL_LINES)
+                    .await
+                    .unwrap_or_default();
+                return Err(ApiError::TaskFailed {
+                    upid: upid.0.clone(),
+                    exitstatus: status.exitstatus.unwrap_or_else(|| "unknown".to_string()),
+                    log,
+                });
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(ApiError::Timeout(timeout.as_secs()));
+            }
+            tokio::time::sleep(TASK_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tasks_test.rs"]
+mod tasks_test;
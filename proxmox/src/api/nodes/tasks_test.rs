@@ -0,0 +1,193 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    const UPID: &str = "UPID:pve:0000A1B2:00C3D4E5:65000000:cephcreatepool:rbd:root@pam:";
+    const STATUS_PATH: &str = "/api2/json/nodes/pve/tasks/UPID%3Apve%3A0000A1B2%3A00C3D4E5%3A65000000%3Acephcreatepool%3Arbd%3Aroot%40pam%3A/status";
+    const LOG_PATH: &str = "/api2/json/nodes/pve/tasks/UPID%3Apve%3A0000A1B2%3A00C3D4E5%3A65000000%3Acephcreatepool%3Arbd%3Aroot%40pam%3A/log";
+
+    #[tokio::test]
+    async fn test_wait_for_finished_task() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", STATUS_PATH)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK", "type": "cephcreatepool"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let status = client
+            .nodes()
+            .node("pve")
+            .wait_for_task(
+                &TaskId(UPID.to_string()),
+                Duration::from_secs(5),
+                std::future::pending(),
+            )
+            .await
+            .unwrap();
+
+        assert!(status.is_successful());
+        assert_eq!(status.task_type.as_deref(), Some("cephcreatepool"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_failed_task() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", STATUS_PATH)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "pool 'rbd' already exists"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+        let _log = server
+            .mock("GET", LOG_PATH)
+            .match_query(Matcher::UrlEncoded("limit".into(), "20".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"n": 1, "t": "creating pool 'rbd'"},
+                    {"n": 2, "t": "TASK ERROR: pool 'rbd' already exists"}
+                ], "total": 2}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client
+            .nodes()
+            .node("pve")
+            .wait_for_task(
+                &TaskId(UPID.to_string()),
+                Duration::from_secs(5),
+                std::future::pending(),
+            )
+            .await;
+
+        match result {
+            Err(ApiError::TaskFailed {
+                exitstatus, log, ..
+            }) => {
+                assert_eq!(exitstatus, "pool 'rbd' already exists");
+                assert_eq!(
+                    log,
+                    vec![
+                        "creating pool 'rbd'",
+                        "TASK ERROR: pool 'rbd' already exists"
+                    ]
+                );
+            }
+            other => panic!("expected task failure, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_task_failed_shows_log() {
+        let error = ApiError::TaskFailed {
+            upid: UPID.to_string(),
+            exitstatus: "clone failed".to_string(),
+            log: vec!["create full clone of drive scsi0".to_string()],
+        };
+
+        assert!(error
+            .to_string()
+            .ends_with("failed: clone failed\n\nTask log:\ncreate full clone of drive scsi0"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_task_stops_when_cancelled() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", STATUS_PATH)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "running"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let result = client
+            .nodes()
+            .node("pve")
+            .wait_for_task(
+                &TaskId(UPID.to_string()),
+                Duration::from_secs(60),
+                tokio::time::sleep(Duration::from_millis(50)),
+            )
+            .await;
+
+        match result {
+            Err(ApiError::TaskCancelled { upid }) => assert_eq!(upid, UPID),
+            other => panic!("expected cancellation, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_log_tail_fetches_last_page() {
+        let mut server = Server::new_async().await;
+        let first = server
+            .mock("GET", LOG_PATH)
+            .match_query(Matcher::UrlEncoded("limit".into(), "2".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"n": 1, "t": "a"}, {"n": 2, "t": "b"}], "total": 5}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let last = server
+            .mock("GET", LOG_PATH)
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("start".into(), "3".into()),
+                Matcher::UrlEncoded("limit".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"n": 4, "t": "d"}, {"n": 5, "t": "e"}], "total": 5}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let lines = client
+            .nodes()
+            .node("pve")
+            .task_log_tail(&TaskId(UPID.to_string()), 2)
+            .await
+            .unwrap();
+
+        last.assert_async().await;
+        first.assert_async().await;
+        assert_eq!(lines, vec!["d", "e"]);
+    }
+
+    #[test]
+    fn test_task_status_success() {
+        let status = |exitstatus: Option<&str>| TaskStatus {
+            upid: UPID.to_string(),
+            status: "stopped".to_string(),
+            exitstatus: exitstatus.map(str::to_string),
+            task_type: None,
+        };
+
+        assert!(status(Some("OK")).is_successful());
+        assert!(status(Some("WARNINGS: 2")).is_successful());
+        assert!(!status(Some("command failed")).is_successful());
+        assert!(!status(None).is_successful());
+    }
+}
@@ -0,0 +1,42 @@
+//! One-off guest backups with vzdump
+
+use super::NodeApi;
+use crate::api::{common::TaskId, error::ApiError};
+use serde::Serialize;
+
+/// How vzdump keeps the guest consistent while backing it up
+pub const VZDUMP_MODES: &[&str] = &["snapshot", "suspend", "stop"];
+
+/// Compression for file based backup storages. "0" turns it off, "1" picks
+/// the default. Proxmox Backup Server always compresses.
+pub const VZDUMP_COMPRESSION: &[&str] = &["0", "1", "gzip", "lz4", "zstd"];
+
+/// Request body for POST /nodes/{node}/vzdump for a single guest
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VzdumpRequest {
+    pub vmid: u32,
+    pub storage: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compress: Option<String>,
+    /// Notes for the backup. {{cluster}}, {{guestname}}, {{node}} and
+    /// {{vmid}} are replaced.
+    #[serde(rename = "notes-template", skip_serializing_if = "Option::is_none")]
+    pub notes_template: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
+}
+
+impl NodeApi<'_> {
+    /// POST /api2/json/nodes/{node}/vzdump. The guest has to be on this
+    /// node.
+    pub async fn vzdump(&self, request: &VzdumpRequest) -> Result<TaskId, ApiError> {
+        let path = format!("/api2/json/nodes/{}/vzdump", self.node);
+        self.client.post(&path, request).await
+    }
+}
+
+#[cfg(test)]
+#[path = "./vzdump_test.rs"]
+mod vzdump_test;
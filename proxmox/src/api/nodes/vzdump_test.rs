@@ -0,0 +1,45 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_vzdump_single_guest() {
+        let mut server = Server::new_async().await;
+        let m = server
+            .mock("POST", "/api2/json/nodes/pve/vzdump")
+            .match_body(Matcher::Json(serde_json::json!({
+                "vmid": 100,
+                "storage": "local",
+                "mode": "snapshot",
+                "compress": "zstd",
+                "notes-template": "{{guestname}} before upgrade"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve:0000A1B2:0012C3D4:66321F20:vzdump:100:root@pam:"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let upid = client
+            .nodes()
+            .node("pve")
+            .vzdump(&VzdumpRequest {
+                vmid: 100,
+                storage: "local".to_string(),
+                mode: Some("snapshot".to_string()),
+                compress: Some("zstd".to_string()),
+                notes_template: Some("{{guestname}} before upgrade".to_string()),
+                protected: None,
+            })
+            .await
+            .unwrap();
+
+        m.assert_async().await;
+        assert_eq!(upid.start_time(), Some(0x66321F20));
+    }
+}
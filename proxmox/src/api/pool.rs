@@ -2,24 +2,35 @@
 
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore, SemaphorePermit};
 
 pub struct ConnectionPoolConfig {
     pub max_idle_connections: usize,
+    /// Maximum number of requests sent to the API at the same time, unlimited
+    /// when unset. pveproxy runs three workers by default, so a small node
+    /// under Terraform's default parallelism of 10 may need a limit to avoid
+    /// 596/599 errors.
+    pub max_concurrent_requests: Option<usize>,
     pub idle_timeout: Duration,
     pub connection_timeout: Duration,
     pub request_timeout: Duration,
     pub tcp_keepalive: Option<Duration>,
+    /// Proxy all API traffic goes through. Supports http, https, socks5 and
+    /// socks5h URLs. Without one, reqwest picks the proxy from the
+    /// environment and honours NO_PROXY.
+    pub proxy_url: Option<String>,
 }
 
 impl Default for ConnectionPoolConfig {
     fn default() -> Self {
         Self {
             max_idle_connections: 10,
+            max_concurrent_requests: None,
             idle_timeout: Duration::from_secs(90),
             connection_timeout: Duration::from_secs(10),
             request_timeout: Duration::from_secs(30),
             tcp_keepalive: Some(Duration::from_secs(30)),
+            proxy_url: None,
         }
     }
 }
@@ -34,6 +45,7 @@ pub struct ConnectionStats {
 
 pub struct ConnectionPoolManager {
     stats: Arc<RwLock<ConnectionStats>>,
+    request_slots: Option<Semaphore>,
     config: ConnectionPoolConfig,
 }
 
@@ -41,10 +53,19 @@ impl ConnectionPoolManager {
     pub fn new(config: ConnectionPoolConfig) -> Self {
         Self {
             stats: Arc::new(RwLock::new(ConnectionStats::default())),
+            request_slots: config
+                .max_concurrent_requests
+                .map(|limit| Semaphore::new(limit.max(1))),
             config,
         }
     }
 
+    /// Waits for a free request slot. The slot is released when the permit
+    /// is dropped. Returns None right away when requests are unlimited.
+    pub async fn acquire_request_slot(&self) -> Option<SemaphorePermit<'_>> {
+        self.request_slots.as_ref()?.acquire().await.ok()
+    }
+
     pub async fn record_request(&self, success: bool) {
         let mut stats = self.stats.write().await;
         stats.total_requests += 1;
@@ -59,7 +80,10 @@ impl ConnectionPoolManager {
         ConnectionStats {
             total_requests: stats.total_requests,
             failed_requests: stats.failed_requests,
-            active_connections: stats.active_connections,
+            active_connections: match (&self.request_slots, self.config.max_concurrent_requests) {
+                (Some(slots), Some(limit)) => limit.max(1) - slots.available_permits(),
+                _ => stats.active_connections,
+            },
             last_request: stats.last_request,
         }
     }
@@ -76,6 +100,10 @@ impl ConnectionPoolManager {
             builder = builder.tcp_keepalive(keepalive);
         }
 
+        if let Some(proxy_url) = &self.config.proxy_url {
+            builder = builder.proxy(reqwest::Proxy::all(proxy_url)?);
+        }
+
         builder.build()
     }
 }
@@ -0,0 +1,121 @@
+//! Resource pool API module
+//!
+//! Not to be confused with `api::pool`, which manages HTTP connections.
+
+use crate::api::{common::string_or_u32, error::ApiError, Client};
+use serde::{Deserialize, Serialize};
+
+/// Resource pool API (/pools)
+pub struct PoolsApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> PoolsApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/pools
+    pub async fn list(&self) -> Result<Vec<PoolSummary>, ApiError> {
+        self.client.get("/api2/json/pools").await
+    }
+
+    /// GET /api2/json/pools/{poolid}
+    pub async fn get(&self, pool_id: &str) -> Result<PoolInfo, ApiError> {
+        let path = format!("/api2/json/pools/{}", pool_id);
+        self.client.get(&path).await
+    }
+
+    /// PUT /api2/json/pools/{poolid}
+    pub async fn update(&self, pool_id: &str, request: &UpdatePoolRequest) -> Result<(), ApiError> {
+        let path = format!("/api2/json/pools/{}", pool_id);
+        self.client.put::<(), _>(&path, request).await.map(|_| ())
+    }
+
+    /// Adds guests to a pool, or removes them when `remove` is set
+    pub async fn update_guests(
+        &self,
+        pool_id: &str,
+        vmids: &[u32],
+        remove: bool,
+    ) -> Result<(), ApiError> {
+        let vms = vmids
+            .iter()
+            .map(|vmid| vmid.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let request = UpdatePoolRequest {
+            vms: Some(vms),
+            delete: remove.then_some(true),
+            ..Default::default()
+        };
+        self.update(pool_id, &request).await
+    }
+}
+
+/// Request body for PUT /pools/{poolid}
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdatePoolRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Comma separated VMIDs to add or remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vms: Option<String>,
+    /// Comma separated storage IDs to add or remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
+    /// Remove the listed members instead of adding them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<bool>,
+}
+
+/// Pool entry returned by the pool listing
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PoolSummary {
+    pub poolid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Pool details including its members
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PoolInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub members: Vec<PoolMember>,
+}
+
+/// Guest or storage that belongs to a pool
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PoolMember {
+    pub id: String,
+    /// qemu, lxc or storage
+    #[serde(rename = "type")]
+    pub member_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "string_or_u32",
+        default
+    )]
+    pub vmid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl PoolMember {
+    /// Whether the member is a guest (VM or container) rather than a storage
+    pub fn is_guest(&self) -> bool {
+        matches!(self.member_type.as_str(), "qemu" | "lxc")
+    }
+}
+
+#[cfg(test)]
+#[path = "./pools_test.rs"]
+mod pools_test;
@@ -0,0 +1,74 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_get_pool_members() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/pools/prod")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": {
+                    "comment": "Production",
+                    "members": [
+                        {"id": "qemu/100", "type": "qemu", "node": "pve1", "vmid": 100, "name": "web-1", "status": "running"},
+                        {"id": "lxc/200", "type": "lxc", "node": "pve2", "vmid": "200", "name": "ct-1"},
+                        {"id": "storage/pve1/local-lvm", "type": "storage", "node": "pve1", "storage": "local-lvm"}
+                    ]
+                }
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let pool = PoolsApi::new(&client).get("prod").await.unwrap();
+
+        assert_eq!(pool.comment.as_deref(), Some("Production"));
+        assert_eq!(pool.members.len(), 3);
+        assert_eq!(pool.members[1].vmid, Some(200));
+        assert!(pool.members[1].is_guest());
+        assert!(!pool.members[2].is_guest());
+        assert_eq!(pool.members[2].storage.as_deref(), Some("local-lvm"));
+    }
+
+    #[tokio::test]
+    async fn test_update_guests() {
+        let mut server = Server::new_async().await;
+        let add = server
+            .mock("PUT", "/api2/json/pools/prod")
+            .match_body(Matcher::Json(serde_json::json!({"vms": "100,101"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+        let remove = server
+            .mock("PUT", "/api2/json/pools/dev")
+            .match_body(Matcher::Json(
+                serde_json::json!({"vms": "100", "delete": true}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let pools = PoolsApi::new(&client);
+        pools
+            .update_guests("prod", &[100, 101], false)
+            .await
+            .unwrap();
+        pools.update_guests("dev", &[100], true).await.unwrap();
+
+        add.assert_async().await;
+        remove.assert_async().await;
+    }
+}
@@ -0,0 +1,154 @@
+//! Proxmox property strings
+//!
+//! Many options pack several settings into one comma separated string, such
+//! as `virtio,bridge=vmbr0,firewall=1` or `local-lvm:32,ssd=1`. A leading
+//! value without a key belongs to the format's default key: the volume of a
+//! disk, the model of a NIC. Values containing a comma or a double quote are
+//! written double quoted, with `"` and `\` escaped by a backslash.
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A parsed property string. Entries keep the order they were parsed or set
+/// in, so rendering a string built by hand gives it back unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyString {
+    default_key: String,
+    entries: Vec<(String, String)>,
+}
+
+impl PropertyString {
+    /// An empty property string. The value of `default_key` is rendered
+    /// first and without its key. Formats without a named default key pass
+    /// "", the leading value is then only reachable through
+    /// [`PropertyString::default_value`].
+    pub fn new(default_key: &str) -> Self {
+        Self {
+            default_key: default_key.to_string(),
+            entries: vec![],
+        }
+    }
+
+    /// Parses a property string. A leading part without `=` is the value of
+    /// the default key, later ones are flags with an empty value.
+    pub fn parse(value: &str, default_key: &str) -> Self {
+        let mut parsed = Self::new(default_key);
+        let mut chars = value.chars().peekable();
+        while chars.peek().is_some() {
+            let (key, separator) = read_token(&mut chars, true);
+            let value = (separator == Some('=')).then(|| read_token(&mut chars, false).0);
+            let key = key.trim();
+            match value {
+                Some(value) => parsed.set(key, value.trim()),
+                None if key.is_empty() => {}
+                None if parsed.entries.is_empty() => parsed.set(default_key, key),
+                None => parsed.set(key, ""),
+            }
+        }
+        parsed
+    }
+
+    /// Value of a key, "" for flags set without a value
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Value of the default key
+    pub fn default_value(&self) -> Option<&str> {
+        self.get(&self.default_key)
+    }
+
+    /// Sets a key, keeping its position if it is already set
+    pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    /// Removes a key, returning its value
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Keys other than the default key with their values, in order
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .filter(|(key, _)| *key != self.default_key)
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Renders the default value first, then the other keys in order. Flags
+/// with an empty value are written as the bare key.
+impl fmt::Display for PropertyString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = self.default_value().map(quote).into_iter().collect();
+        parts.extend(self.params().map(|(key, value)| {
+            if value.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}={}", key, quote(value))
+            }
+        }));
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+/// Reads up to the next `,` outside quotes, or the next `=` when reading a
+/// key. Returns the unquoted text and the separator it stopped at.
+fn read_token(chars: &mut Peekable<Chars<'_>>, key: bool) -> (String, Option<char>) {
+    let mut token = String::new();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => quoted = false,
+            // Only a quote opening the value starts a quoted value
+            '"' if token.trim().is_empty() => {
+                token.clear();
+                quoted = true;
+            }
+            '\\' if quoted => {
+                if let Some(escaped) = chars.next() {
+                    token.push(escaped);
+                }
+            }
+            ',' if !quoted => return (token, Some(',')),
+            '=' if key && !quoted => return (token, Some('=')),
+            c => token.push(c),
+        }
+    }
+    (token, None)
+}
+
+/// Quotes values a comma or quote would otherwise split or break
+fn quote(value: &str) -> String {
+    if !value.contains([',', '"']) {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+#[path = "./propstring_test.rs"]
+mod propstring_test;
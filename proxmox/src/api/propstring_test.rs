@@ -0,0 +1,94 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_parse_default_key_and_params() {
+        let disk = PropertyString::parse("local-lvm:vm-100-disk-0,ssd=1,discard=on", "file");
+        assert_eq!(disk.default_value(), Some("local-lvm:vm-100-disk-0"));
+        assert_eq!(disk.get("file"), Some("local-lvm:vm-100-disk-0"));
+        assert_eq!(disk.get("ssd"), Some("1"));
+        assert_eq!(
+            disk.params().collect::<Vec<_>>(),
+            vec![("ssd", "1"), ("discard", "on")]
+        );
+
+        // Only a leading bare value is the default, later ones are flags
+        let cdrom = PropertyString::parse("none,media=cdrom,snapshot", "file");
+        assert_eq!(cdrom.default_value(), Some("none"));
+        assert_eq!(cdrom.get("snapshot"), Some(""));
+
+        let keyed = PropertyString::parse("file=local:iso/debian.iso,media=cdrom", "file");
+        assert_eq!(keyed.default_value(), Some("local:iso/debian.iso"));
+    }
+
+    #[test]
+    fn test_parse_skips_empty_parts_and_whitespace() {
+        let parsed = PropertyString::parse(" virtio , bridge=vmbr0,,tag=10,", "model");
+        assert_eq!(parsed.default_value(), Some("virtio"));
+        assert_eq!(parsed.get("bridge"), Some("vmbr0"));
+        assert_eq!(parsed.get("tag"), Some("10"));
+        assert_eq!(parsed.params().count(), 2);
+        assert!(PropertyString::parse("", "model").is_empty());
+    }
+
+    #[test]
+    fn test_parse_quoted_values() {
+        let parsed = PropertyString::parse(
+            r#"file=local:snippets/a.yaml,label="web, db",note="say \"hi\" \\o/""#,
+            "file",
+        );
+        assert_eq!(parsed.get("label"), Some("web, db"));
+        assert_eq!(parsed.get("note"), Some(r#"say "hi" \o/"#));
+
+        // Quotes inside an unquoted value are kept as they are
+        let parsed = PropertyString::parse(r#"name=a"b,x=1"#, "");
+        assert_eq!(parsed.get("name"), Some(r#"a"b"#));
+        assert_eq!(parsed.get("x"), Some("1"));
+    }
+
+    #[test]
+    fn test_render_quotes_only_when_needed() {
+        let mut props = PropertyString::new("model");
+        props.set("bridge", "vmbr0");
+        props.set("model", "virtio");
+        props.set("description", r#"web, "db""#);
+        props.set("link_down", "");
+        assert_eq!(
+            props.to_string(),
+            r#"virtio,bridge=vmbr0,description="web, \"db\"",link_down"#
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (value, default_key) in [
+            (
+                "virtio=BC:24:11:00:00:01,bridge=vmbr0,firewall=1,tag=20",
+                "model",
+            ),
+            ("local-lvm:vm-100-disk-0,iothread=1,size=32G", "file"),
+            ("ip=10.0.0.5/24,gw=10.0.0.1", ""),
+            (r#"local:iso/a.iso,media=cdrom,label="a,b""#, "file"),
+            (r#"uuid=1234,serial="x\\y\"z""#, ""),
+        ] {
+            let parsed = PropertyString::parse(value, default_key);
+            assert_eq!(parsed.to_string(), value, "{}", value);
+            assert_eq!(
+                PropertyString::parse(&parsed.to_string(), default_key),
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn test_set_and_remove_keep_order() {
+        let mut props = PropertyString::parse("virtio,bridge=vmbr0,tag=10", "model");
+        props.set("bridge", "vmbr1");
+        props.set("mtu", "9000");
+        assert_eq!(props.remove("tag"), Some("10".to_string()));
+        assert_eq!(props.remove("tag"), None);
+        assert_eq!(props.to_string(), "virtio,bridge=vmbr1,mtu=9000");
+    }
+}
@@ -55,30 +55,6 @@ impl ProxmoxResponseHandler {
     }
 
     async fn extract_error<T>(status: u16, text: String) -> Result<T, ApiError> {
-        if status == 401 {
-            return Err(ApiError::AuthError);
-        }
-
-        if status == 429 {
-            return Err(ApiError::RateLimited);
-        }
-
-        if status >= 500 {
-            return Err(ApiError::ServiceUnavailable);
-        }
-
-        let details = match serde_json::from_str::<super::ApiErrorResponse>(&text) {
-            Ok(err_resp) => Some(Box::new(super::ApiErrorDetails {
-                errors: err_resp.errors,
-                field_errors: err_resp.data,
-            })),
-            Err(_) => None,
-        };
-
-        Err(ApiError::ApiError {
-            status,
-            message: text,
-            details,
-        })
+        Err(ApiError::from_response(status, &text))
     }
 }
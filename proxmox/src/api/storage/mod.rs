@@ -0,0 +1,208 @@
+//! Storage API module for the datacenter storage configuration
+
+use crate::api::{
+    common::{deserialize_proxmox_bool_option, string_or_u32, ApiQueryParams},
+    error::ApiError,
+    Client,
+};
+use serde::{Deserialize, Serialize};
+
+/// Storage configuration API (/storage), shared by all nodes
+pub struct StorageConfigApi<'a> {
+    client: &'a Client,
+}
+
+impl<'a> StorageConfigApi<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        Self { client }
+    }
+
+    /// GET /api2/json/storage
+    pub async fn list(&self, storage_type: Option<&str>) -> Result<Vec<StorageConfig>, ApiError> {
+        let params = ApiQueryParams::new().add_optional("type", storage_type);
+        self.client
+            .get_with_params("/api2/json/storage", &params)
+            .await
+    }
+
+    /// GET /api2/json/storage/{storage}
+    pub async fn get(&self, storage: &str) -> Result<StorageConfig, ApiError> {
+        let path = format!("/api2/json/storage/{}", storage);
+        self.client.get(&path).await
+    }
+
+    /// POST /api2/json/storage
+    pub async fn create(&self, request: &CreateStorageRequest) -> Result<(), ApiError> {
+        let _: serde_json::Value = self.client.post("/api2/json/storage", request).await?;
+        Ok(())
+    }
+
+    /// PUT /api2/json/storage/{storage}
+    pub async fn update(
+        &self,
+        storage: &str,
+        request: &UpdateStorageRequest,
+    ) -> Result<(), ApiError> {
+        let path = format!("/api2/json/storage/{}", storage);
+        let _: serde_json::Value = self.client.put(&path, request).await?;
+        Ok(())
+    }
+
+    /// DELETE /api2/json/storage/{storage}. Only the definition is removed,
+    /// the data stays where it is.
+    pub async fn delete(&self, storage: &str) -> Result<(), ApiError> {
+        let path = format!("/api2/json/storage/{}", storage);
+        let _: serde_json::Value = self.client.delete(&path).await?;
+        Ok(())
+    }
+}
+
+/// Settings shared by creating and updating a storage. Only the fields used
+/// by the storage types managed here are modelled.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Password or API token secret. Never returned by the API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Client-side encryption key as JSON, or "autogen". Never returned by
+    /// the API
+    #[serde(rename = "encryption-key", skip_serializing_if = "Option::is_none")]
+    pub encryption_key: Option<String>,
+}
+
+/// Request body for POST /api2/json/storage
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateStorageRequest {
+    pub storage: String,
+    #[serde(rename = "type")]
+    pub storage_type: String,
+    #[serde(flatten)]
+    pub settings: StorageSettings,
+}
+
+/// Request body for PUT /api2/json/storage/{storage}
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateStorageRequest {
+    #[serde(flatten)]
+    pub settings: StorageSettings,
+    /// Comma separated list of settings to clear
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<String>,
+}
+
+/// Storage definition as stored in storage.cfg
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StorageConfig {
+    pub storage: String,
+    #[serde(rename = "type")]
+    pub storage_type: String,
+    /// Comma separated content types, e.g. "images,rootdir"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub shared: Option<bool>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_proxmox_bool_option",
+        default
+    )]
+    pub disable: Option<bool>,
+    /// Comma separated node list. Unset means the storage is on every node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nodes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vgname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinpool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "string_or_u32",
+        default
+    )]
+    pub port: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datastore: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl StorageConfig {
+    /// Content types the storage accepts, e.g. images, iso, backup
+    pub fn content_types(&self) -> Vec<String> {
+        split_list(self.content.as_deref())
+    }
+
+    /// Nodes the storage is restricted to. Empty means all nodes
+    pub fn node_list(&self) -> Vec<String> {
+        split_list(self.nodes.as_deref())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.disable.unwrap_or(false)
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.shared.unwrap_or(false)
+    }
+
+    /// Whether the storage can hold the given content type
+    pub fn supports(&self, content: &str) -> bool {
+        self.content_types().iter().any(|c| c == content)
+    }
+
+    /// Whether the storage is usable from the given node
+    pub fn available_on(&self, node: &str) -> bool {
+        let nodes = self.node_list();
+        nodes.is_empty() || nodes.iter().any(|n| n == node)
+    }
+}
+
+#[cfg(test)]
+#[path = "./storage_test.rs"]
+mod storage_test;
@@ -0,0 +1,148 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::create_test_client;
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_list_storages() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/storage")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {
+                        "storage": "local",
+                        "type": "dir",
+                        "path": "/var/lib/vz",
+                        "content": "iso,vztmpl,backup"
+                    },
+                    {
+                        "storage": "ceph-vm",
+                        "type": "rbd",
+                        "pool": "vm",
+                        "content": "images,rootdir",
+                        "shared": 1,
+                        "nodes": "pve1, pve2",
+                        "disable": 1
+                    }
+                ]
+            }"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let storages = StorageConfigApi::new(&client).list(None).await.unwrap();
+
+        assert_eq!(storages.len(), 2);
+        let local = &storages[0];
+        assert!(local.is_enabled());
+        assert!(!local.is_shared());
+        assert!(local.supports("iso"));
+        assert!(!local.supports("images"));
+        assert!(local.available_on("any-node"));
+
+        let ceph = &storages[1];
+        assert!(!ceph.is_enabled());
+        assert!(ceph.is_shared());
+        assert_eq!(ceph.content_types(), vec!["images", "rootdir"]);
+        assert_eq!(ceph.node_list(), vec!["pve1", "pve2"]);
+        assert!(ceph.available_on("pve2"));
+        assert!(!ceph.available_on("pve3"));
+    }
+
+    #[tokio::test]
+    async fn test_storage_crud() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/storage")
+            .match_body(Matcher::Json(serde_json::json!({
+                "storage": "pbs",
+                "type": "pbs",
+                "content": "backup",
+                "server": "pbs.example.com",
+                "datastore": "store1",
+                "username": "backup@pbs!pve",
+                "password": "secret",
+                "encryption-key": "autogen"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"type": "pbs", "storage": "pbs"}}"#)
+            .create_async()
+            .await;
+        let update = server
+            .mock("PUT", "/api2/json/storage/pbs")
+            .match_body(Matcher::Json(serde_json::json!({
+                "datastore": "store2",
+                "delete": "fingerprint"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/api2/json/storage/pbs")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+        let _get = server
+            .mock("GET", "/api2/json/storage/pbs")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"storage": "pbs", "type": "pbs", "content": "backup",
+                    "server": "pbs.example.com", "port": "8007", "datastore": "store1",
+                    "username": "backup@pbs!pve", "digest": "abc"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = create_test_client(&server.url());
+        let api = StorageConfigApi::new(&client);
+
+        api.create(&CreateStorageRequest {
+            storage: "pbs".to_string(),
+            storage_type: "pbs".to_string(),
+            settings: StorageSettings {
+                content: Some("backup".to_string()),
+                server: Some("pbs.example.com".to_string()),
+                datastore: Some("store1".to_string()),
+                username: Some("backup@pbs!pve".to_string()),
+                password: Some("secret".to_string()),
+                encryption_key: Some("autogen".to_string()),
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+        api.update(
+            "pbs",
+            &UpdateStorageRequest {
+                settings: StorageSettings {
+                    datastore: Some("store2".to_string()),
+                    ..Default::default()
+                },
+                delete: Some("fingerprint".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        let storage = api.get("pbs").await.unwrap();
+        api.delete("pbs").await.unwrap();
+
+        create.assert_async().await;
+        update.assert_async().await;
+        delete.assert_async().await;
+        assert_eq!(storage.port, Some(8007));
+        assert_eq!(storage.datastore.as_deref(), Some("store1"));
+    }
+}
@@ -2,11 +2,20 @@
 
 #[cfg(test)]
 #[allow(dead_code)]
+#[allow(clippy::disallowed_methods)]
 pub fn create_test_client(url: &str) -> super::Client {
     super::Client::new(url, "test@pam!test=secret", true).unwrap()
 }
 
+/// Provider data around a test client, for resources and data sources
 #[cfg(test)]
+#[allow(dead_code)]
+pub fn provider_data(url: &str) -> crate::ProxmoxProviderData {
+    crate::ProxmoxProviderData::new(create_test_client(url))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
 mod tests {
     use super::super::*;
 
@@ -33,6 +42,89 @@ mod tests {
         assert!(b);
     }
 
+    #[test]
+    fn test_proxmox_bool_spellings() {
+        use common::ProxmoxBool;
+
+        for (value, expected) in [
+            ("1", true),
+            ("0", false),
+            ("true", true),
+            ("\"1\"", true),
+            ("\"0\"", false),
+            ("\"on\"", true),
+            ("\"No\"", false),
+        ] {
+            let parsed: ProxmoxBool = serde_json::from_str(value).unwrap();
+            assert_eq!(parsed.as_bool(), expected, "{}", value);
+        }
+        assert!(serde_json::from_str::<ProxmoxBool>("2").is_err());
+        assert!(serde_json::from_str::<ProxmoxBool>("\"maybe\"").is_err());
+    }
+
+    #[test]
+    fn test_numbers_as_strings() {
+        #[derive(serde::Deserialize)]
+        struct Model {
+            #[serde(deserialize_with = "common::deserialize_number")]
+            vmid: u32,
+            #[serde(default, deserialize_with = "common::deserialize_number_option")]
+            cores: Option<u32>,
+            #[serde(default, deserialize_with = "common::deserialize_number_option")]
+            cpulimit: Option<f64>,
+        }
+
+        let model: Model =
+            serde_json::from_str(r#"{"vmid": "100", "cores": " 4 ", "cpulimit": "1.5"}"#).unwrap();
+        assert_eq!(model.vmid, 100);
+        assert_eq!(model.cores, Some(4));
+        assert_eq!(model.cpulimit, Some(1.5));
+
+        let model: Model = serde_json::from_str(r#"{"vmid": 100, "cores": ""}"#).unwrap();
+        assert_eq!(model.cores, None);
+        assert_eq!(model.cpulimit, None);
+
+        let err = serde_json::from_str::<Model>(r#"{"vmid": "abc"}"#)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("invalid number \"abc\""));
+    }
+
+    #[test]
+    fn test_byte_size() {
+        use common::ByteSize;
+
+        assert_eq!(ByteSize::parse("512"), Some(ByteSize(512)));
+        assert_eq!(ByteSize::parse("4K"), Some(ByteSize(4096)));
+        assert_eq!(ByteSize::parse("10G"), ByteSize::parse("10240M"));
+        assert_eq!(ByteSize::parse("10gb"), ByteSize::parse("10G"));
+        assert_eq!(ByteSize::parse("10X"), None);
+
+        assert_eq!(ByteSize(10 << 30).to_string(), "10G");
+        assert_eq!(ByteSize(1536 << 20).to_string(), "1536M");
+        assert_eq!(ByteSize(1000).to_string(), "1000");
+        assert_eq!(ByteSize(0).to_string(), "0");
+
+        let size: ByteSize = serde_json::from_str("\"32G\"").unwrap();
+        assert_eq!(size.bytes(), 32 << 30);
+        let size: ByteSize = serde_json::from_str("1024").unwrap();
+        assert_eq!(serde_json::to_string(&size).unwrap(), "1024");
+    }
+
+    #[test]
+    fn test_parse_seconds() {
+        use common::parse_seconds;
+
+        assert_eq!(parse_seconds("3600"), Some(3600));
+        assert_eq!(parse_seconds("90s"), Some(90));
+        assert_eq!(parse_seconds("5min"), Some(300));
+        assert_eq!(parse_seconds("1h 30m"), Some(5400));
+        assert_eq!(parse_seconds("2d1h"), Some(176400));
+        assert_eq!(parse_seconds("1y"), None);
+        assert_eq!(parse_seconds("h"), None);
+        assert_eq!(parse_seconds(""), None);
+    }
+
     #[tokio::test]
     async fn test_api_query_params() {
         use common::ApiQueryParams;
@@ -69,10 +161,31 @@ mod tests {
 
         let config = ConnectionPoolConfig::default();
         assert_eq!(config.max_idle_connections, 10);
+        assert!(config.max_concurrent_requests.is_none());
         assert_eq!(config.idle_timeout.as_secs(), 90);
         assert_eq!(config.connection_timeout.as_secs(), 10);
         assert_eq!(config.request_timeout.as_secs(), 30);
         assert_eq!(config.tcp_keepalive.unwrap().as_secs(), 30);
+        assert!(config.proxy_url.is_none());
+    }
+
+    #[test]
+    fn test_build_client_with_proxy() {
+        use pool::{ConnectionPoolConfig, ConnectionPoolManager};
+
+        for proxy_url in ["http://proxy.example.com:3128", "socks5h://127.0.0.1:1080"] {
+            let manager = ConnectionPoolManager::new(ConnectionPoolConfig {
+                proxy_url: Some(proxy_url.to_string()),
+                ..Default::default()
+            });
+            assert!(manager.build_client(false).is_ok(), "{}", proxy_url);
+        }
+
+        let manager = ConnectionPoolManager::new(ConnectionPoolConfig {
+            proxy_url: Some("not a url".to_string()),
+            ..Default::default()
+        });
+        assert!(manager.build_client(false).is_err());
     }
 
     #[tokio::test]
@@ -94,6 +207,196 @@ mod tests {
         assert!(stats.last_request.is_some());
     }
 
+    #[tokio::test]
+    async fn test_request_slots_limit_concurrency() {
+        use pool::{ConnectionPoolConfig, ConnectionPoolManager};
+        use std::time::Duration;
+
+        let manager = ConnectionPoolManager::new(ConnectionPoolConfig {
+            max_concurrent_requests: Some(2),
+            ..Default::default()
+        });
+
+        let first = manager.acquire_request_slot().await;
+        let _second = manager.acquire_request_slot().await;
+        assert_eq!(manager.get_stats().await.active_connections, 2);
+
+        let blocked =
+            tokio::time::timeout(Duration::from_millis(50), manager.acquire_request_slot()).await;
+        assert!(blocked.is_err());
+
+        drop(first);
+        let third =
+            tokio::time::timeout(Duration::from_millis(50), manager.acquire_request_slot()).await;
+        assert!(third.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_request_slots_unlimited_by_default() {
+        use pool::{ConnectionPoolConfig, ConnectionPoolManager};
+
+        let manager = ConnectionPoolManager::new(ConnectionPoolConfig::default());
+        let slots: Vec<_> =
+            futures::future::join_all((0..20).map(|_| manager.acquire_request_slot())).await;
+        assert!(slots.iter().all(|slot| slot.is_none()));
+    }
+
+    #[test]
+    fn test_api_error_classification() {
+        let error = ApiError::from_response(
+            500,
+            r#"{"data": null, "message": "Configuration file 'nodes/pve/qemu-server/100.conf' does not exist\n"}"#,
+        );
+        assert!(error.is_not_found());
+        assert!(!error.is_retriable());
+        assert!(error.to_string().contains("100.conf' does not exist"));
+
+        let error = ApiError::from_response(
+            403,
+            r#"{"data": null, "message": "Permission check failed (/vms/100, VM.Audit)\n"}"#,
+        );
+        assert!(error.is_permission_denied());
+
+        let error = ApiError::from_response(
+            500,
+            r#"{"data": null, "message": "can't lock file '/var/lock/qemu-server/lock-100.conf' - got timeout\n"}"#,
+        );
+        assert!(error.is_retriable());
+
+        let error = ApiError::from_response(
+            500,
+            r#"{"data": null, "message": "VM 100 already running\n"}"#,
+        );
+        assert!(matches!(error, ApiError::ApiError { status: 500, .. }));
+        assert!(!error.is_retriable());
+
+        assert!(ApiError::from_response(500, r#"{"data": null}"#).is_retriable());
+        assert!(ApiError::from_response(596, "").is_retriable());
+        assert!(matches!(
+            ApiError::from_response(401, ""),
+            ApiError::AuthError
+        ));
+    }
+
+    #[test]
+    fn test_api_error_parameter_errors() {
+        let error = ApiError::from_response(
+            400,
+            r#"{"data": null, "errors": {"storage": "storage 'missing' does not exist", "cores": "value must have a minimum value of 1"}}"#,
+        );
+
+        assert!(!error.is_not_found());
+        assert_eq!(
+            error.parameter_errors(),
+            vec![
+                ("cores", "value must have a minimum value of 1"),
+                ("storage", "storage 'missing' does not exist"),
+            ]
+        );
+        assert!(ApiError::AuthError.parameter_errors().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api2/json/pools/missing")
+            .with_status(500)
+            .with_body(r#"{"data": null, "message": "pool 'missing' does not exist\n"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = super::create_test_client(&server.url());
+        let result: Result<serde_json::Value, _> = client.get("/api2/json/pools/missing").await;
+
+        assert!(result.unwrap_err().is_not_found());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api2/json/version")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"version": "8.2"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        // Nothing listens on the port of a dropped listener
+        let down = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let down_url = format!("http://{}", down.local_addr().unwrap());
+        drop(down);
+
+        let client = Client::with_endpoints(
+            &[down_url, server.url()],
+            "test@pam!test=secret",
+            true,
+            RetryConfig {
+                max_retries: 0,
+                ..Default::default()
+            },
+            pool::ConnectionPoolConfig::default(),
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            let version: serde_json::Value = client.get("/api2/json/version").await.unwrap();
+            assert_eq!(version["version"], "8.2");
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_user_agent() {
+        let mut server = mockito::Server::new_async().await;
+        let plain = server
+            .mock("GET", "/api2/json/version")
+            .match_header("user-agent", DEFAULT_USER_AGENT)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"version": "8.2"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let tagged = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/100/status/start")
+            .match_header(
+                "user-agent",
+                format!("{} module/web", DEFAULT_USER_AGENT).as_str(),
+            )
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve:1:2:3:qmstart:100:root@pam:"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = super::create_test_client(&server.url());
+        let _: serde_json::Value = client.get("/api2/json/version").await.unwrap();
+        let _: serde_json::Value = client
+            .with_module("web")
+            .post("/api2/json/nodes/pve/qemu/100/status/start", &())
+            .await
+            .unwrap();
+
+        assert!(DEFAULT_USER_AGENT.starts_with("terraform-provider-proxmox/"));
+        plain.assert_async().await;
+        tagged.assert_async().await;
+    }
+
+    #[test]
+    fn test_client_requires_an_endpoint() {
+        let result = Client::with_endpoints(
+            &[],
+            "test@pam!test=secret",
+            true,
+            RetryConfig::default(),
+            pool::ConnectionPoolConfig::default(),
+        );
+        assert!(matches!(result, Err(ApiError::InvalidConfig(_))));
+    }
+
     #[test]
     fn test_api_error_formatting() {
         use std::collections::HashMap;
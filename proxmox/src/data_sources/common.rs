@@ -0,0 +1,41 @@
+//! Helpers shared by the data source implementations
+
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, ReadDataSourceResponse,
+};
+use tfplug::types::{Diagnostic, DynamicValue};
+
+/// Stores the provider data handed to a data source's configure call
+pub(crate) fn configure_provider_data(
+    request: ConfigureDataSourceRequest,
+    target: &mut Option<crate::ProxmoxProviderData>,
+) -> ConfigureDataSourceResponse {
+    let mut diagnostics = vec![];
+
+    if let Some(data) = request.provider_data {
+        if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+            *target = Some(provider_data.clone());
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "Invalid provider data",
+                "Failed to extract ProxmoxProviderData from provider data",
+            ));
+        }
+    } else {
+        diagnostics.push(Diagnostic::error(
+            "No provider data",
+            "No provider data was provided to the data source",
+        ));
+    }
+
+    ConfigureDataSourceResponse { diagnostics }
+}
+
+/// Read response carrying only diagnostics, for reads that failed
+pub(crate) fn error_response(diagnostics: Vec<Diagnostic>) -> ReadDataSourceResponse {
+    ReadDataSourceResponse {
+        state: DynamicValue::null(),
+        diagnostics,
+        deferred: None,
+    }
+}
@@ -0,0 +1,689 @@
+//! Access subsystem data sources: users, groups, roles and realms
+
+use crate::api::access::groups::GroupInfo;
+use crate::api::access::realms::Realm;
+use crate::api::access::roles::RoleInfo;
+use crate::api::access::users::UserInfo;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn optional_string(value: &Option<String>) -> Dynamic {
+    value
+        .as_ref()
+        .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+}
+
+fn string_list(values: &[String]) -> Dynamic {
+    Dynamic::List(values.iter().cloned().map(Dynamic::String).collect())
+}
+
+fn string_list_type() -> AttributeType {
+    AttributeType::List(Box::new(AttributeType::String))
+}
+
+fn not_configured() -> ReadDataSourceResponse {
+    error_response(vec![Diagnostic::error(
+        "Provider not configured",
+        "Provider data was not properly configured",
+    )])
+}
+
+fn user_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("user_id".to_string(), AttributeType::String);
+    fields.insert("realm".to_string(), AttributeType::String);
+    fields.insert("enabled".to_string(), AttributeType::Bool);
+    fields.insert("expire".to_string(), AttributeType::Number);
+    fields.insert("first_name".to_string(), AttributeType::String);
+    fields.insert("last_name".to_string(), AttributeType::String);
+    fields.insert("email".to_string(), AttributeType::String);
+    fields.insert("comment".to_string(), AttributeType::String);
+    fields.insert("groups".to_string(), string_list_type());
+    AttributeType::Object(fields)
+}
+
+fn user_to_dynamic(user: &UserInfo) -> Dynamic {
+    let mut groups = user.groups.clone();
+    groups.sort();
+
+    let mut obj = HashMap::new();
+    obj.insert("user_id".to_string(), Dynamic::String(user.userid.clone()));
+    obj.insert(
+        "realm".to_string(),
+        Dynamic::String(user.realm().to_string()),
+    );
+    obj.insert("enabled".to_string(), Dynamic::Bool(user.is_enabled()));
+    obj.insert(
+        "expire".to_string(),
+        Dynamic::Number(user.expire.unwrap_or(0) as f64),
+    );
+    obj.insert("first_name".to_string(), optional_string(&user.firstname));
+    obj.insert("last_name".to_string(), optional_string(&user.lastname));
+    obj.insert("email".to_string(), optional_string(&user.email));
+    obj.insert("comment".to_string(), optional_string(&user.comment));
+    obj.insert("groups".to_string(), string_list(&groups));
+    Dynamic::Map(obj)
+}
+
+/// Filters applied to the user listing
+#[derive(Debug, Default)]
+struct UserFilter {
+    realm: Option<String>,
+    enabled_only: bool,
+}
+
+impl UserFilter {
+    fn from_config(config: &DynamicValue) -> Self {
+        Self {
+            realm: config.get_string(&AttributePath::new("realm")).ok(),
+            enabled_only: config
+                .get_bool(&AttributePath::new("enabled_only"))
+                .unwrap_or(false),
+        }
+    }
+
+    fn matches(&self, user: &UserInfo) -> bool {
+        if self.enabled_only && !user.is_enabled() {
+            return false;
+        }
+        self.realm
+            .as_deref()
+            .map_or(true, |realm| user.realm() == realm)
+    }
+}
+
+/// Builds the users state from the matching users, sorted by user ID
+fn build_users_state(
+    mut state: DynamicValue,
+    filter: &UserFilter,
+    mut users: Vec<&UserInfo>,
+) -> DynamicValue {
+    users.sort_by(|a, b| a.userid.cmp(&b.userid));
+
+    let id = match &filter.realm {
+        Some(realm) => format!("users/{}", realm),
+        None => "users".to_string(),
+    };
+    let _ = state.set_string(&AttributePath::new("id"), id);
+    let _ = state.set_list(
+        &AttributePath::new("users"),
+        users.iter().map(|u| user_to_dynamic(u)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("user_ids"),
+        users
+            .iter()
+            .map(|u| Dynamic::String(u.userid.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the users known to the access subsystem
+#[derive(Default)]
+pub struct UsersDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl UsersDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for UsersDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_users"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists users, e.g. to check that an ACL principal exists before granting it permissions")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("realm", AttributeType::String)
+                    .description("Only list users of this realm, e.g. pve or pam")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("enabled_only", AttributeType::Bool)
+                    .description("Only list enabled users. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("users", AttributeType::List(Box::new(user_object_type())))
+                    .description("The matching users, sorted by user ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("user_ids", string_list_type())
+                    .description("The IDs of the matching users")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => return not_configured(),
+        };
+
+        let filter = UserFilter::from_config(&request.config);
+        match provider_data.client.access().users().list().await {
+            Ok(users) => {
+                let users = users.iter().filter(|u| filter.matches(u)).collect();
+                ReadDataSourceResponse {
+                    state: build_users_state(request.config, &filter, users),
+                    diagnostics: vec![],
+                    deferred: None,
+                }
+            }
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list users",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for UsersDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+fn group_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("group_id".to_string(), AttributeType::String);
+    fields.insert("comment".to_string(), AttributeType::String);
+    fields.insert("members".to_string(), string_list_type());
+    AttributeType::Object(fields)
+}
+
+fn group_to_dynamic(group: &GroupInfo) -> Dynamic {
+    let mut members = group.users.clone();
+    members.sort();
+
+    let mut obj = HashMap::new();
+    obj.insert(
+        "group_id".to_string(),
+        Dynamic::String(group.groupid.clone()),
+    );
+    obj.insert("comment".to_string(), optional_string(&group.comment));
+    obj.insert("members".to_string(), string_list(&members));
+    Dynamic::Map(obj)
+}
+
+/// Builds the groups state, sorted by group ID
+fn build_groups_state(mut state: DynamicValue, groups: &[GroupInfo]) -> DynamicValue {
+    let mut groups: Vec<&GroupInfo> = groups.iter().collect();
+    groups.sort_by(|a, b| a.groupid.cmp(&b.groupid));
+
+    let _ = state.set_string(&AttributePath::new("id"), "groups".to_string());
+    let _ = state.set_list(
+        &AttributePath::new("groups"),
+        groups.iter().map(|g| group_to_dynamic(g)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("group_ids"),
+        groups
+            .iter()
+            .map(|g| Dynamic::String(g.groupid.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the groups and their members
+#[derive(Default)]
+pub struct GroupsDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl GroupsDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for GroupsDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_groups"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists groups and their members")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("groups", AttributeType::List(Box::new(group_object_type())))
+                    .description("All groups, sorted by group ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("group_ids", string_list_type())
+                    .description("The IDs of all groups")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => return not_configured(),
+        };
+
+        match provider_data.client.access().groups().list().await {
+            Ok(groups) => ReadDataSourceResponse {
+                state: build_groups_state(request.config, &groups),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list groups",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for GroupsDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+fn role_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("role_id".to_string(), AttributeType::String);
+    fields.insert("privileges".to_string(), string_list_type());
+    fields.insert("builtin".to_string(), AttributeType::Bool);
+    AttributeType::Object(fields)
+}
+
+fn role_to_dynamic(role: &RoleInfo) -> Dynamic {
+    let mut privileges = role.privs.clone();
+    privileges.sort();
+
+    let mut obj = HashMap::new();
+    obj.insert("role_id".to_string(), Dynamic::String(role.roleid.clone()));
+    obj.insert("privileges".to_string(), string_list(&privileges));
+    obj.insert("builtin".to_string(), Dynamic::Bool(role.is_builtin()));
+    Dynamic::Map(obj)
+}
+
+/// Builds the roles state, sorted by role ID
+fn build_roles_state(mut state: DynamicValue, roles: &[RoleInfo]) -> DynamicValue {
+    let mut roles: Vec<&RoleInfo> = roles.iter().collect();
+    roles.sort_by(|a, b| a.roleid.cmp(&b.roleid));
+
+    let _ = state.set_string(&AttributePath::new("id"), "roles".to_string());
+    let _ = state.set_list(
+        &AttributePath::new("roles"),
+        roles.iter().map(|r| role_to_dynamic(r)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("role_ids"),
+        roles
+            .iter()
+            .map(|r| Dynamic::String(r.roleid.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the built-in and custom roles with their privileges
+#[derive(Default)]
+pub struct RolesDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl RolesDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for RolesDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_roles"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the built-in and custom roles with their privileges")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("roles", AttributeType::List(Box::new(role_object_type())))
+                    .description("All roles, sorted by role ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("role_ids", string_list_type())
+                    .description("The IDs of all roles")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => return not_configured(),
+        };
+
+        match provider_data.client.access().roles().list().await {
+            Ok(roles) => ReadDataSourceResponse {
+                state: build_roles_state(request.config, &roles),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list roles",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for RolesDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+fn realm_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("realm".to_string(), AttributeType::String);
+    fields.insert("type".to_string(), AttributeType::String);
+    fields.insert("comment".to_string(), AttributeType::String);
+    fields.insert("default".to_string(), AttributeType::Bool);
+    AttributeType::Object(fields)
+}
+
+fn realm_to_dynamic(realm: &Realm) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("realm".to_string(), Dynamic::String(realm.realm.clone()));
+    obj.insert(
+        "type".to_string(),
+        Dynamic::String(realm.realm_type.clone()),
+    );
+    obj.insert("comment".to_string(), optional_string(&realm.comment));
+    obj.insert(
+        "default".to_string(),
+        Dynamic::Bool(realm.default.unwrap_or(false)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the realms state, sorted by realm name
+fn build_realms_state(mut state: DynamicValue, realms: &[Realm]) -> DynamicValue {
+    let mut realms: Vec<&Realm> = realms.iter().collect();
+    realms.sort_by(|a, b| a.realm.cmp(&b.realm));
+
+    let _ = state.set_string(&AttributePath::new("id"), "realms".to_string());
+    let _ = state.set_list(
+        &AttributePath::new("realms"),
+        realms.iter().map(|r| realm_to_dynamic(r)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("names"),
+        realms
+            .iter()
+            .map(|r| Dynamic::String(r.realm.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the authentication realms
+#[derive(Default)]
+pub struct RealmsDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl RealmsDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for RealmsDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_realms"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description(
+                "Lists the authentication realms, including the built-in pam and pve realms",
+            )
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("realms", AttributeType::List(Box::new(realm_object_type())))
+                    .description("All realms, sorted by name")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("names", string_list_type())
+                    .description("The names of all realms")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => return not_configured(),
+        };
+
+        match provider_data.client.access().realms().list().await {
+            Ok(realms) => ReadDataSourceResponse {
+                state: build_realms_state(request.config, &realms),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list realms",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for RealmsDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_access_test.rs"]
+mod data_source_access_test;
@@ -0,0 +1,188 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request, strings};
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_users_filters_by_realm_and_enabled() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/access/users")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                "data": [
+                    {"userid": "root@pam", "enable": 1},
+                    {"userid": "terraform@pve", "enable": 1, "groups": "ops,admins", "email": "tf@example.com"},
+                    {"userid": "old@pve", "enable": 0}
+                ]
+            }"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let data_source = UsersDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_users",
+                    config(vec![("realm", Dynamic::String("pve".to_string()))]),
+                ),
+            )
+            .await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            strings(&response.state, "user_ids"),
+            vec!["old@pve", "terraform@pve"]
+        );
+        assert_eq!(
+            response
+                .state
+                .get_string(&AttributePath::new("id"))
+                .unwrap(),
+            "users/pve"
+        );
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_users",
+                    config(vec![("enabled_only", Dynamic::Bool(true))]),
+                ),
+            )
+            .await;
+        assert_eq!(
+            strings(&response.state, "user_ids"),
+            vec!["root@pam", "terraform@pve"]
+        );
+
+        let users = response
+            .state
+            .get_list(&AttributePath::new("users"))
+            .unwrap();
+        match &users[1] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("realm"), Some(&Dynamic::String("pve".to_string())));
+                assert_eq!(
+                    obj.get("groups"),
+                    Some(&Dynamic::List(vec![
+                        Dynamic::String("admins".to_string()),
+                        Dynamic::String("ops".to_string())
+                    ]))
+                );
+                assert_eq!(obj.get("first_name"), Some(&Dynamic::Null));
+            }
+            other => panic!("expected user object, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_groups_roles_and_realms() {
+        let mut server = Server::new_async().await;
+        let _groups = server
+            .mock("GET", "/api2/json/access/groups")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"groupid": "ops", "users": "b@pve,a@pve"}, {"groupid": "admins"}]}"#,
+            )
+            .create_async()
+            .await;
+        let _roles = server
+            .mock("GET", "/api2/json/access/roles")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"roleid": "PVEAuditor", "special": 1, "privs": "VM.Audit,Sys.Audit"}, {"roleid": "Deployer", "privs": "VM.Allocate"}]}"#,
+            )
+            .create_async()
+            .await;
+        let _realms = server
+            .mock("GET", "/api2/json/access/domains")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"realm": "pve", "type": "pve"}, {"realm": "pam", "type": "pam", "default": 1}]}"#)
+            .create_async()
+            .await;
+
+        let groups = GroupsDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(
+            Context::new(),
+            read_request("proxmox_groups", config(vec![])),
+        )
+        .await;
+        assert!(groups.diagnostics.is_empty());
+        assert_eq!(strings(&groups.state, "group_ids"), vec!["admins", "ops"]);
+        match &groups
+            .state
+            .get_list(&AttributePath::new("groups"))
+            .unwrap()[1]
+        {
+            Dynamic::Map(obj) => assert_eq!(
+                obj.get("members"),
+                Some(&Dynamic::List(vec![
+                    Dynamic::String("a@pve".to_string()),
+                    Dynamic::String("b@pve".to_string())
+                ]))
+            ),
+            other => panic!("expected group object, got {:?}", other),
+        }
+
+        let roles = RolesDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(
+            Context::new(),
+            read_request("proxmox_roles", config(vec![])),
+        )
+        .await;
+        assert!(roles.diagnostics.is_empty());
+        assert_eq!(
+            strings(&roles.state, "role_ids"),
+            vec!["Deployer", "PVEAuditor"]
+        );
+        match &roles.state.get_list(&AttributePath::new("roles")).unwrap()[1] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("builtin"), Some(&Dynamic::Bool(true)));
+                assert_eq!(
+                    obj.get("privileges"),
+                    Some(&Dynamic::List(vec![
+                        Dynamic::String("Sys.Audit".to_string()),
+                        Dynamic::String("VM.Audit".to_string())
+                    ]))
+                );
+            }
+            other => panic!("expected role object, got {:?}", other),
+        }
+
+        let realms = RealmsDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(
+            Context::new(),
+            read_request("proxmox_realms", config(vec![])),
+        )
+        .await;
+        assert!(realms.diagnostics.is_empty());
+        assert_eq!(strings(&realms.state, "names"), vec!["pam", "pve"]);
+        match &realms
+            .state
+            .get_list(&AttributePath::new("realms"))
+            .unwrap()[0]
+        {
+            Dynamic::Map(obj) => assert_eq!(obj.get("default"), Some(&Dynamic::Bool(true))),
+            other => panic!("expected realm object, got {:?}", other),
+        }
+    }
+}
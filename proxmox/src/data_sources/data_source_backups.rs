@@ -0,0 +1,260 @@
+//! Backup listing data source
+
+use crate::api::nodes::StorageContent;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn optional_string(value: &Option<String>) -> Dynamic {
+    value
+        .as_ref()
+        .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+}
+
+fn backup_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("volid".to_string(), AttributeType::String);
+    fields.insert("vmid".to_string(), AttributeType::Number);
+    fields.insert("type".to_string(), AttributeType::String);
+    fields.insert("format".to_string(), AttributeType::String);
+    fields.insert("size".to_string(), AttributeType::Number);
+    fields.insert("ctime".to_string(), AttributeType::Number);
+    fields.insert("notes".to_string(), AttributeType::String);
+    fields.insert("protected".to_string(), AttributeType::Bool);
+    AttributeType::Object(fields)
+}
+
+fn backup_to_dynamic(backup: &StorageContent) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("volid".to_string(), Dynamic::String(backup.volid.clone()));
+    obj.insert(
+        "vmid".to_string(),
+        backup
+            .vmid
+            .map_or(Dynamic::Null, |vmid| Dynamic::Number(vmid as f64)),
+    );
+    obj.insert("type".to_string(), optional_string(&backup.subtype));
+    obj.insert("format".to_string(), optional_string(&backup.format));
+    obj.insert(
+        "size".to_string(),
+        backup
+            .size
+            .map_or(Dynamic::Null, |size| Dynamic::Number(size as f64)),
+    );
+    obj.insert(
+        "ctime".to_string(),
+        backup
+            .ctime
+            .map_or(Dynamic::Null, |ctime| Dynamic::Number(ctime as f64)),
+    );
+    obj.insert("notes".to_string(), optional_string(&backup.notes));
+    obj.insert(
+        "protected".to_string(),
+        Dynamic::Bool(backup.protected.unwrap_or(false)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the state from the backups, oldest first. The newest is exposed
+/// as latest_volid.
+fn build_backups_state(
+    mut state: DynamicValue,
+    id: String,
+    mut backups: Vec<StorageContent>,
+) -> DynamicValue {
+    backups.sort_by(|a, b| (a.ctime, &a.volid).cmp(&(b.ctime, &b.volid)));
+
+    let _ = state.set_string(&AttributePath::new("id"), id);
+    let _ = state.set_list(
+        &AttributePath::new("backups"),
+        backups.iter().map(backup_to_dynamic).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("volids"),
+        backups
+            .iter()
+            .map(|b| Dynamic::String(b.volid.clone()))
+            .collect(),
+    );
+    let _ = match backups.last() {
+        Some(latest) => state.set_string(&AttributePath::new("latest_volid"), latest.volid.clone()),
+        None => state.set_null(&AttributePath::new("latest_volid")),
+    };
+    state
+}
+
+/// Lists the backups on a storage, e.g. to pick one to restore a guest from
+#[derive(Default)]
+pub struct BackupsDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl BackupsDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for BackupsDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_backups"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the vzdump backups on a storage, e.g. to pick the newest backup of a guest to restore from")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to query the storage on")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("storage", AttributeType::String)
+                    .description("The backup storage")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("Only list backups of this guest")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("backups", AttributeType::List(Box::new(backup_object_type())))
+                    .description("The backups, oldest first. type is qemu or lxc")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("volids", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The volume IDs of the backups, oldest first")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("latest_volid", AttributeType::String)
+                    .description("The volume ID of the newest backup. Null when there is none")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let required = |name: &str| {
+            request
+                .config
+                .get_string(&AttributePath::new(name))
+                .map_err(|_| {
+                    Diagnostic::error(
+                        format!("Missing {}", name),
+                        format!("The '{}' attribute is required", name),
+                    )
+                })
+        };
+        let (node, storage) = match (required("node"), required("storage")) {
+            (Ok(node), Ok(storage)) => (node, storage),
+            (Err(diag), _) | (_, Err(diag)) => return error_response(vec![diag]),
+        };
+        let vmid = request.config.get_u32(&AttributePath::new("vmid")).ok();
+        let id = match vmid {
+            Some(vmid) => format!("{}/{}/backups/{}", node, storage, vmid),
+            None => format!("{}/{}/backups", node, storage),
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .storage(&storage)
+            .backups(vmid)
+            .await
+        {
+            Ok(backups) => ReadDataSourceResponse {
+                state: build_backups_state(request.config, id, backups),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list backups",
+                format!(
+                    "Unable to list backups on storage '{}' on node '{}': {}",
+                    storage, node, e
+                ),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for BackupsDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_backups_test.rs"]
+mod data_source_backups_test;
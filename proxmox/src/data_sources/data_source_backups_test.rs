@@ -0,0 +1,70 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::{Matcher, Server};
+
+    #[tokio::test]
+    async fn test_backups_of_guest_oldest_first() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/storage/backup/content")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("content".into(), "backup".into()),
+                Matcher::UrlEncoded("vmid".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"volid": "backup:backup/vzdump-qemu-100-2024_05_02-12_00_00.vma.zst", "content": "backup", "format": "vma.zst", "size": 2048, "vmid": 100, "ctime": 1714651200, "subtype": "qemu", "notes": "nightly"},
+                    {"volid": "backup:backup/vzdump-qemu-100-2024_05_01-12_00_00.vma.zst", "content": "backup", "format": "vma.zst", "size": 1024, "vmid": 100, "ctime": 1714564800, "subtype": "qemu", "protected": 1}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let data_source = BackupsDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_backups",
+                    config(vec![
+                        ("node", Dynamic::String("pve".to_string())),
+                        ("storage", Dynamic::String("backup".to_string())),
+                        ("vmid", Dynamic::Number(100.0)),
+                    ]),
+                ),
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/backup/backups/100"
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("latest_volid"))
+                .unwrap(),
+            "backup:backup/vzdump-qemu-100-2024_05_02-12_00_00.vma.zst"
+        );
+        let backup = |index: i64, name: &str| {
+            state
+                .get(&AttributePath::new("backups").index(index).attribute(name))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(backup(0, "size"), Dynamic::Number(1024.0));
+        assert_eq!(backup(0, "protected"), Dynamic::Bool(true));
+        assert_eq!(backup(0, "notes"), Dynamic::Null);
+        assert_eq!(backup(1, "type"), Dynamic::String("qemu".to_string()));
+        assert_eq!(backup(1, "protected"), Dynamic::Bool(false));
+    }
+}
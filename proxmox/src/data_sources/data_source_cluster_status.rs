@@ -0,0 +1,212 @@
+//! Cluster status data source implementation
+
+use crate::api::cluster::ClusterStatusEntry;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn node_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("name".to_string(), AttributeType::String);
+    fields.insert("node_id".to_string(), AttributeType::Number);
+    fields.insert("ip".to_string(), AttributeType::String);
+    fields.insert("online".to_string(), AttributeType::Bool);
+    fields.insert("local".to_string(), AttributeType::Bool);
+    AttributeType::Object(fields)
+}
+
+fn node_to_dynamic(node: &ClusterStatusEntry) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("name".to_string(), Dynamic::String(node.name.clone()));
+    obj.insert(
+        "node_id".to_string(),
+        node.nodeid
+            .map_or(Dynamic::Null, |id| Dynamic::Number(id as f64)),
+    );
+    obj.insert(
+        "ip".to_string(),
+        node.ip
+            .as_ref()
+            .map_or(Dynamic::Null, |ip| Dynamic::String(ip.clone())),
+    );
+    obj.insert(
+        "online".to_string(),
+        Dynamic::Bool(node.online.unwrap_or(false)),
+    );
+    obj.insert(
+        "local".to_string(),
+        Dynamic::Bool(node.local.unwrap_or(false)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the data source state. A standalone node reports no cluster entry,
+/// in which case there is no cluster name and the node is always quorate.
+fn build_state(mut state: DynamicValue, entries: &[ClusterStatusEntry]) -> DynamicValue {
+    let cluster = entries.iter().find(|e| e.is_cluster());
+    let mut nodes: Vec<&ClusterStatusEntry> = entries.iter().filter(|e| e.is_node()).collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let quorate = cluster.map_or(true, |c| c.quorate.unwrap_or(false));
+    let online: Vec<Dynamic> = nodes
+        .iter()
+        .filter(|n| n.online.unwrap_or(false))
+        .map(|n| Dynamic::String(n.name.clone()))
+        .collect();
+
+    let id = cluster.map_or_else(|| "standalone".to_string(), |c| c.name.clone());
+    let _ = state.set_string(&AttributePath::new("id"), id);
+    let _ = match cluster {
+        Some(cluster) => {
+            state.set_string(&AttributePath::new("cluster_name"), cluster.name.clone())
+        }
+        None => state.set_null(&AttributePath::new("cluster_name")),
+    };
+    let _ = state.set_bool(&AttributePath::new("quorate"), quorate);
+    let _ = state.set_i64(&AttributePath::new("node_count"), nodes.len() as i64);
+    let _ = state.set_list(
+        &AttributePath::new("nodes"),
+        nodes.iter().map(|n| node_to_dynamic(n)).collect(),
+    );
+    let _ = state.set_list(&AttributePath::new("online_nodes"), online);
+    state
+}
+
+/// Reads cluster membership and quorum state
+#[derive(Default)]
+pub struct ClusterStatusDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl ClusterStatusDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for ClusterStatusDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_cluster_status"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Reads cluster membership and quorum state, e.g. to fail a plan early in a precondition when the cluster is not quorate")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cluster_name", AttributeType::String)
+                    .description("The cluster name. Null on a standalone node")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("quorate", AttributeType::Bool)
+                    .description("Whether the cluster has quorum. Always true on a standalone node")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node_count", AttributeType::Number)
+                    .description("Number of nodes in the cluster")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nodes", AttributeType::List(Box::new(node_object_type())))
+                    .description("The cluster nodes, sorted by name")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("online_nodes", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Names of the nodes that are online")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        match provider_data.client.cluster().status().await {
+            Ok(entries) => ReadDataSourceResponse {
+                state: build_state(request.config, &entries),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read cluster status",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for ClusterStatusDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_cluster_status_test.rs"]
+mod data_source_cluster_status_test;
@@ -0,0 +1,84 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::Server;
+
+    async fn read_status(body: &str) -> ReadDataSourceResponse {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/status")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let data_source = ClusterStatusDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        data_source
+            .read(
+                Context::new(),
+                read_request("proxmox_cluster_status", config(vec![])),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_cluster_without_quorum() {
+        let response = read_status(
+            r#"{"data": [
+                {"id": "node/pve2", "type": "node", "name": "pve2", "nodeid": 2, "ip": "10.0.0.2", "online": 0, "local": 0},
+                {"id": "cluster", "type": "cluster", "name": "homelab", "nodes": 2, "quorate": 0, "version": 5},
+                {"id": "node/pve1", "type": "node", "name": "pve1", "nodeid": 1, "ip": "10.0.0.1", "online": 1, "local": 1}
+            ]}"#,
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("cluster_name"))
+                .unwrap(),
+            "homelab"
+        );
+        assert!(!state.get_bool(&AttributePath::new("quorate")).unwrap());
+        assert_eq!(state.get_u32(&AttributePath::new("node_count")).unwrap(), 2);
+        assert_eq!(
+            state.get_list(&AttributePath::new("online_nodes")).unwrap(),
+            vec![Dynamic::String("pve1".to_string())]
+        );
+        let nodes = state.get_list(&AttributePath::new("nodes")).unwrap();
+        match &nodes[0] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("name"), Some(&Dynamic::String("pve1".to_string())));
+                assert_eq!(obj.get("local"), Some(&Dynamic::Bool(true)));
+            }
+            other => panic!("expected node object, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_standalone_node_is_quorate() {
+        let response = read_status(
+            r#"{"data": [
+                {"id": "node/pve", "type": "node", "name": "pve", "nodeid": 0, "ip": "10.0.0.5", "online": 1, "local": 1}
+            ]}"#,
+        )
+        .await;
+
+        let state = response.state;
+        assert!(state.get_bool(&AttributePath::new("quorate")).unwrap());
+        assert!(state
+            .get_string(&AttributePath::new("cluster_name"))
+            .is_err());
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "standalone"
+        );
+    }
+}
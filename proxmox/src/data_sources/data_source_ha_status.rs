@@ -0,0 +1,225 @@
+//! HA manager status data source implementation
+
+use crate::api::cluster::ha::HaStatusEntry;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn optional_string(value: &Option<String>) -> Dynamic {
+    value
+        .as_ref()
+        .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+}
+
+fn lrm_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("node".to_string(), AttributeType::String);
+    fields.insert("status".to_string(), AttributeType::String);
+    AttributeType::Object(fields)
+}
+
+fn service_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("sid".to_string(), AttributeType::String);
+    fields.insert("node".to_string(), AttributeType::String);
+    fields.insert("state".to_string(), AttributeType::String);
+    fields.insert("crm_state".to_string(), AttributeType::String);
+    fields.insert("request_state".to_string(), AttributeType::String);
+    fields.insert("status".to_string(), AttributeType::String);
+    AttributeType::Object(fields)
+}
+
+fn lrm_to_dynamic(entry: &HaStatusEntry) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("node".to_string(), optional_string(&entry.node));
+    obj.insert("status".to_string(), optional_string(&entry.status));
+    Dynamic::Map(obj)
+}
+
+fn service_to_dynamic(entry: &HaStatusEntry) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("sid".to_string(), optional_string(&entry.sid));
+    obj.insert("node".to_string(), optional_string(&entry.node));
+    obj.insert("state".to_string(), optional_string(&entry.state));
+    obj.insert("crm_state".to_string(), optional_string(&entry.crm_state));
+    obj.insert(
+        "request_state".to_string(),
+        optional_string(&entry.request_state),
+    );
+    obj.insert("status".to_string(), optional_string(&entry.status));
+    Dynamic::Map(obj)
+}
+
+/// Builds the data source state from the HA manager status entries
+fn build_state(mut state: DynamicValue, entries: &[HaStatusEntry]) -> DynamicValue {
+    let quorum = entries.iter().find(|e| e.is_type("quorum"));
+    let master = entries.iter().find(|e| e.is_type("master"));
+
+    let mut lrms: Vec<&HaStatusEntry> = entries.iter().filter(|e| e.is_type("lrm")).collect();
+    lrms.sort_by(|a, b| a.node.cmp(&b.node));
+    let mut services: Vec<&HaStatusEntry> =
+        entries.iter().filter(|e| e.is_type("service")).collect();
+    services.sort_by(|a, b| a.sid.cmp(&b.sid));
+
+    let _ = state.set_string(&AttributePath::new("id"), "ha_status".to_string());
+    let _ = state.set_bool(
+        &AttributePath::new("quorate"),
+        quorum.and_then(|q| q.quorate).unwrap_or(false),
+    );
+    let _ = match quorum.and_then(|q| q.status.clone()) {
+        Some(status) => state.set_string(&AttributePath::new("quorum_status"), status),
+        None => state.set_null(&AttributePath::new("quorum_status")),
+    };
+    let _ = match master.and_then(|m| m.node.clone()) {
+        Some(node) => state.set_string(&AttributePath::new("master_node"), node),
+        None => state.set_null(&AttributePath::new("master_node")),
+    };
+    let _ = state.set_list(
+        &AttributePath::new("lrm"),
+        lrms.iter().map(|e| lrm_to_dynamic(e)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("services"),
+        services.iter().map(|e| service_to_dynamic(e)).collect(),
+    );
+    state
+}
+
+/// Reads the current state of the HA manager
+#[derive(Default)]
+pub struct HaStatusDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl HaStatusDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for HaStatusDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_ha_status"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Reads the current HA manager state: quorum, the active master, local resource managers and HA managed services")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("quorate", AttributeType::Bool)
+                    .description("Whether the HA manager reports quorum")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("quorum_status", AttributeType::String)
+                    .description("The quorum status reported by the HA manager, e.g. OK")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("master_node", AttributeType::String)
+                    .description("The node running the active HA master. Null when there is none")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("lrm", AttributeType::List(Box::new(lrm_object_type())))
+                    .description("Local resource manager status per node, sorted by node")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("services", AttributeType::List(Box::new(service_object_type())))
+                    .description("HA managed services, sorted by service ID")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        match provider_data.client.cluster().ha().current_status().await {
+            Ok(entries) => ReadDataSourceResponse {
+                state: build_state(request.config, &entries),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read HA status",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for HaStatusDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_ha_status_test.rs"]
+mod data_source_ha_status_test;
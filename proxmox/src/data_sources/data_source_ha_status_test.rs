@@ -0,0 +1,72 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_read_ha_status() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/cluster/ha/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"id": "quorum", "type": "quorum", "node": "pve1", "status": "OK", "quorate": 1},
+                    {"id": "master", "type": "master", "node": "pve1", "status": "pve1 (active)"},
+                    {"id": "lrm:pve2", "type": "lrm", "node": "pve2", "status": "pve2 (idle)"},
+                    {"id": "lrm:pve1", "type": "lrm", "node": "pve1", "status": "pve1 (active)"},
+                    {"id": "service:vm:101", "type": "service", "sid": "vm:101", "node": "pve1", "state": "stopped", "crm_state": "stopped", "request_state": "stopped"},
+                    {"id": "service:vm:100", "type": "service", "sid": "vm:100", "node": "pve2", "state": "started", "crm_state": "started", "request_state": "started"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let data_source = HaStatusDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request("proxmox_ha_status", config(vec![])),
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert!(state.get_bool(&AttributePath::new("quorate")).unwrap());
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("master_node"))
+                .unwrap(),
+            "pve1"
+        );
+
+        let lrm = state.get_list(&AttributePath::new("lrm")).unwrap();
+        assert_eq!(lrm.len(), 2);
+        match &lrm[0] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("node"), Some(&Dynamic::String("pve1".to_string())))
+            }
+            other => panic!("expected lrm object, got {:?}", other),
+        }
+
+        let services = state.get_list(&AttributePath::new("services")).unwrap();
+        assert_eq!(services.len(), 2);
+        match &services[0] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("sid"), Some(&Dynamic::String("vm:100".to_string())));
+                assert_eq!(
+                    obj.get("crm_state"),
+                    Some(&Dynamic::String("started".to_string()))
+                );
+                assert_eq!(obj.get("status"), Some(&Dynamic::Null));
+            }
+            other => panic!("expected service object, got {:?}", other),
+        }
+    }
+}
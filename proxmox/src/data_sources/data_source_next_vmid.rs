@@ -0,0 +1,246 @@
+//! Next free VMID data source implementation
+
+use crate::api::cluster::ClusterResourceType;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+
+const MIN_VMID: u32 = 100;
+const MAX_VMID: u32 = 999_999_999;
+
+/// Upper bound on candidates checked against /cluster/nextid in one read
+const MAX_PROBES: u32 = 1000;
+
+fn vmid_bound(config: &DynamicValue, name: &str) -> Result<Option<u32>, Diagnostic> {
+    let path = AttributePath::new(name);
+    if config.get_number(&path).is_err() {
+        return Ok(None);
+    }
+
+    match config.get_u32(&path) {
+        Ok(value) if (MIN_VMID..=MAX_VMID).contains(&value) => Ok(Some(value)),
+        _ => Err(Diagnostic::error(
+            format!("Invalid {}", name),
+            format!(
+                "'{}' must be a whole number between {} and {}",
+                name, MIN_VMID, MAX_VMID
+            ),
+        )),
+    }
+}
+
+fn parse_bounds(config: &DynamicValue) -> Result<(Option<u32>, u32), Diagnostic> {
+    let floor = vmid_bound(config, "floor")?;
+    let ceiling = vmid_bound(config, "ceiling")?.unwrap_or(MAX_VMID);
+
+    if let Some(floor) = floor {
+        if floor > ceiling {
+            return Err(Diagnostic::error(
+                "Invalid VMID range",
+                format!(
+                    "floor ({}) must not be greater than ceiling ({})",
+                    floor, ceiling
+                ),
+            ));
+        }
+    }
+
+    Ok((floor, ceiling))
+}
+
+/// Finds a free VMID in the range and reserves it for this provider run.
+/// Candidates are skipped if they are in use according to the cluster
+/// resources or were already handed out, and each remaining candidate is
+/// confirmed with /cluster/nextid before it is returned.
+async fn allocate_vmid(
+    provider_data: &crate::ProxmoxProviderData,
+    floor: Option<u32>,
+    ceiling: u32,
+) -> Result<u32, Diagnostic> {
+    let cluster = provider_data.client.cluster();
+
+    let mut candidate = match floor {
+        Some(floor) => floor,
+        None => cluster.next_id(None).await.map_err(|e| {
+            Diagnostic::error("Failed to get next VMID", format!("API error: {}", e))
+        })?,
+    };
+
+    let used: HashSet<u32> = cluster
+        .resources(Some(ClusterResourceType::Vm))
+        .await
+        .map(|resources| resources.iter().filter_map(|r| r.vmid).collect())
+        .unwrap_or_default();
+
+    let mut probes = 0;
+    while candidate <= ceiling {
+        if used.contains(&candidate) || provider_data.is_vmid_reserved(candidate).await {
+            candidate += 1;
+            continue;
+        }
+
+        if probes == MAX_PROBES {
+            break;
+        }
+        probes += 1;
+
+        match cluster.next_id(Some(candidate)).await {
+            Ok(vmid) if provider_data.reserve_vmid(vmid).await => return Ok(vmid),
+            Ok(_) => {}
+            // The VMID is taken, e.g. by a guest created since the listing
+            Err(crate::api::ApiError::ApiError { status: 400, .. }) => {}
+            Err(e) => {
+                return Err(Diagnostic::error(
+                    "Failed to check VMID",
+                    format!("API error while checking VMID {}: {}", candidate, e),
+                ));
+            }
+        }
+        candidate += 1;
+    }
+
+    Err(Diagnostic::error(
+        "No free VMID",
+        format!(
+            "Could not find a free VMID between {} and {}",
+            floor.unwrap_or(MIN_VMID),
+            ceiling
+        ),
+    ))
+}
+
+/// Returns the next free VMID in the cluster
+#[derive(Default)]
+pub struct NextVmidDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NextVmidDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NextVmidDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_next_vmid"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Gets the next free VMID in the cluster. VMIDs returned within the same provider run are never handed out twice")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("floor", AttributeType::Number)
+                    .description("Lowest acceptable VMID")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ceiling", AttributeType::Number)
+                    .description("Highest acceptable VMID")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The free VMID")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+        if let Err(diag) = parse_bounds(&request.config) {
+            diagnostics.push(diag);
+        }
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let result = match parse_bounds(&request.config) {
+            Ok((floor, ceiling)) => allocate_vmid(provider_data, floor, ceiling).await,
+            Err(diag) => Err(diag),
+        };
+
+        match result {
+            Ok(vmid) => {
+                let mut state = request.config;
+                let _ = state.set_string(&AttributePath::new("id"), vmid.to_string());
+                let _ = state.set_i64(&AttributePath::new("vmid"), vmid.into());
+                ReadDataSourceResponse {
+                    state,
+                    diagnostics: vec![],
+                    deferred: None,
+                }
+            }
+            Err(diag) => error_response(vec![diag]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NextVmidDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_next_vmid_test.rs"]
+mod data_source_next_vmid_test;
@@ -0,0 +1,134 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::Dynamic;
+
+    fn bounds(floor: Option<f64>, ceiling: Option<f64>) -> DynamicValue {
+        let mut obj = HashMap::new();
+        if let Some(floor) = floor {
+            obj.insert("floor".to_string(), Dynamic::Number(floor));
+        }
+        if let Some(ceiling) = ceiling {
+            obj.insert("ceiling".to_string(), Dynamic::Number(ceiling));
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    async fn mock_resources(server: &mut Server) -> mockito::Mock {
+        server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"id": "qemu/5000", "type": "qemu", "node": "pve", "vmid": 5000},
+                    {"id": "lxc/5001", "type": "lxc", "node": "pve", "vmid": 5001}
+                ]}"#,
+            )
+            .create_async()
+            .await
+    }
+
+    async fn mock_next_id(server: &mut Server, vmid: &str, status: usize) -> mockito::Mock {
+        let body = if status == 200 {
+            format!(r#"{{"data": "{}"}}"#, vmid)
+        } else {
+            format!(
+                r#"{{"data": null, "errors": {{"vmid": "VM {} already exists"}}}}"#,
+                vmid
+            )
+        };
+        server
+            .mock("GET", "/api2/json/cluster/nextid")
+            .match_query(Matcher::UrlEncoded("vmid".into(), vmid.into()))
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    #[test]
+    fn test_parse_bounds() {
+        assert_eq!(parse_bounds(&bounds(None, None)).unwrap(), (None, MAX_VMID));
+        assert_eq!(
+            parse_bounds(&bounds(Some(5000.0), Some(5999.0))).unwrap(),
+            (Some(5000), 5999)
+        );
+        assert_eq!(
+            parse_bounds(&bounds(Some(6000.0), Some(5999.0)))
+                .unwrap_err()
+                .summary,
+            "Invalid VMID range"
+        );
+        assert_eq!(
+            parse_bounds(&bounds(Some(100.5), None))
+                .unwrap_err()
+                .summary,
+            "Invalid floor"
+        );
+        assert_eq!(
+            parse_bounds(&bounds(None, Some(50.0))).unwrap_err().summary,
+            "Invalid ceiling"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_allocate_vmid_skips_used_and_reserved() {
+        let mut server = Server::new_async().await;
+        let _resources = mock_resources(&mut server).await;
+        let _taken = mock_next_id(&mut server, "5002", 400).await;
+        let _free = mock_next_id(&mut server, "5003", 200).await;
+        let _next = mock_next_id(&mut server, "5004", 200).await;
+
+        let provider_data = provider_data(&server.url());
+
+        let vmid = allocate_vmid(&provider_data, Some(5000), 5999)
+            .await
+            .unwrap();
+        assert_eq!(vmid, 5003);
+
+        // The same provider run must not hand out 5003 again
+        let vmid = allocate_vmid(&provider_data, Some(5000), 5999)
+            .await
+            .unwrap();
+        assert_eq!(vmid, 5004);
+    }
+
+    #[tokio::test]
+    async fn test_allocate_vmid_without_floor_uses_nextid() {
+        let mut server = Server::new_async().await;
+        let _resources = mock_resources(&mut server).await;
+        let _lowest = server
+            .mock("GET", "/api2/json/cluster/nextid")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "104"}"#)
+            .create_async()
+            .await;
+        let _confirm = mock_next_id(&mut server, "104", 200).await;
+
+        let provider_data = provider_data(&server.url());
+
+        let vmid = allocate_vmid(&provider_data, None, MAX_VMID).await.unwrap();
+        assert_eq!(vmid, 104);
+    }
+
+    #[tokio::test]
+    async fn test_allocate_vmid_exhausted_range() {
+        let mut server = Server::new_async().await;
+        let _resources = mock_resources(&mut server).await;
+
+        let provider_data = provider_data(&server.url());
+
+        let err = allocate_vmid(&provider_data, Some(5000), 5001)
+            .await
+            .unwrap_err();
+        assert_eq!(err.summary, "No free VMID");
+    }
+}
@@ -0,0 +1,201 @@
+//! Node package updates data source
+
+use crate::api::nodes::AptUpdate;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn optional_string(value: &Option<String>) -> Dynamic {
+    value
+        .as_ref()
+        .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+}
+
+fn update_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("package".to_string(), AttributeType::String);
+    fields.insert("title".to_string(), AttributeType::String);
+    fields.insert("version".to_string(), AttributeType::String);
+    fields.insert("old_version".to_string(), AttributeType::String);
+    fields.insert("origin".to_string(), AttributeType::String);
+    fields.insert("priority".to_string(), AttributeType::String);
+    fields.insert("section".to_string(), AttributeType::String);
+    AttributeType::Object(fields)
+}
+
+fn update_to_dynamic(update: &AptUpdate) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert(
+        "package".to_string(),
+        Dynamic::String(update.package.clone()),
+    );
+    obj.insert("title".to_string(), optional_string(&update.title));
+    obj.insert("version".to_string(), optional_string(&update.version));
+    obj.insert(
+        "old_version".to_string(),
+        optional_string(&update.old_version),
+    );
+    obj.insert("origin".to_string(), optional_string(&update.origin));
+    obj.insert("priority".to_string(), optional_string(&update.priority));
+    obj.insert("section".to_string(), optional_string(&update.section));
+    Dynamic::Map(obj)
+}
+
+/// Builds the state from the updates, sorted by package name
+fn build_updates_state(
+    mut state: DynamicValue,
+    node: &str,
+    mut updates: Vec<AptUpdate>,
+) -> DynamicValue {
+    updates.sort_by(|a, b| a.package.cmp(&b.package));
+
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/apt-updates", node));
+    let _ = state.set_list(
+        &AttributePath::new("updates"),
+        updates.iter().map(update_to_dynamic).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("packages"),
+        updates
+            .iter()
+            .map(|u| Dynamic::String(u.package.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the package updates available to a node
+#[derive(Default)]
+pub struct NodeAptUpdatesDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeAptUpdatesDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeAptUpdatesDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_apt_updates"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the package updates available to a node, as of the node's last package list refresh. Proxmox refreshes the lists daily; reading this data source does not trigger a refresh")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to list updates of")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("updates", AttributeType::List(Box::new(update_object_type())))
+                    .description("The updates, sorted by package. old_version is the installed version and null for packages an update newly pulls in")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("packages", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The names of the packages with updates")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let node = match request.config.get_string(&AttributePath::new("node")) {
+            Ok(node) => node,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing node",
+                    "The 'node' attribute is required",
+                )]);
+            }
+        };
+        match provider_data.client.nodes().node(&node).apt_updates().await {
+            Ok(updates) => ReadDataSourceResponse {
+                state: build_updates_state(request.config, &node, updates),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list package updates",
+                format!("Unable to list package updates of node '{}': {}", node, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeAptUpdatesDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_node_apt_updates_test.rs"]
+mod data_source_node_apt_updates_test;
@@ -0,0 +1,70 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_node_apt_updates_sorted_by_package() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/apt/update")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"Package": "pve-manager", "Title": "Proxmox Virtual Environment Management Tools",
+                     "Version": "8.2.4", "OldVersion": "8.2.2", "Origin": "Proxmox", "Priority": "optional",
+                     "Section": "admin", "Arch": "amd64"},
+                    {"Package": "proxmox-kernel-6.8.8-2-pve-signed", "Version": "6.8.8-2",
+                     "Origin": "Proxmox", "Priority": "optional", "Section": "kernel", "Arch": "amd64"},
+                    {"Package": "libpve-common-perl", "Version": "8.2.1", "OldVersion": "8.2.0",
+                     "Origin": "Proxmox", "Priority": "optional", "Section": "perl", "Arch": "all"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let data_source = NodeAptUpdatesDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_node_apt_updates",
+                    config(vec![("node", Dynamic::String("pve".to_string()))]),
+                ),
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/apt-updates"
+        );
+        assert_eq!(
+            state.get_list(&AttributePath::new("packages")).unwrap(),
+            vec![
+                Dynamic::String("libpve-common-perl".to_string()),
+                Dynamic::String("proxmox-kernel-6.8.8-2-pve-signed".to_string()),
+                Dynamic::String("pve-manager".to_string()),
+            ]
+        );
+        let update = |index: i64, name: &str| {
+            state
+                .get(&AttributePath::new("updates").index(index).attribute(name))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(update(1, "old_version"), Dynamic::Null);
+        assert_eq!(
+            update(2, "old_version"),
+            Dynamic::String("8.2.2".to_string())
+        );
+        assert_eq!(update(2, "version"), Dynamic::String("8.2.4".to_string()));
+    }
+}
@@ -0,0 +1,227 @@
+//! Node disk inventory data source
+
+use crate::api::nodes::NodeDisk;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn optional_string(value: &Option<String>) -> Dynamic {
+    value
+        .as_ref()
+        .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+}
+
+fn disk_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("devpath".to_string(), AttributeType::String);
+    fields.insert("by_id_link".to_string(), AttributeType::String);
+    fields.insert("size".to_string(), AttributeType::Number);
+    fields.insert("type".to_string(), AttributeType::String);
+    fields.insert("model".to_string(), AttributeType::String);
+    fields.insert("serial".to_string(), AttributeType::String);
+    fields.insert("vendor".to_string(), AttributeType::String);
+    fields.insert("wwn".to_string(), AttributeType::String);
+    fields.insert("health".to_string(), AttributeType::String);
+    fields.insert("used".to_string(), AttributeType::String);
+    fields.insert("gpt".to_string(), AttributeType::Bool);
+    fields.insert("osd_id".to_string(), AttributeType::Number);
+    AttributeType::Object(fields)
+}
+
+fn disk_to_dynamic(disk: &NodeDisk) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("devpath".to_string(), Dynamic::String(disk.devpath.clone()));
+    obj.insert("by_id_link".to_string(), optional_string(&disk.by_id_link));
+    obj.insert("size".to_string(), Dynamic::Number(disk.size as f64));
+    obj.insert("type".to_string(), optional_string(&disk.disk_type));
+    obj.insert("model".to_string(), optional_string(&disk.model));
+    obj.insert("serial".to_string(), optional_string(&disk.serial));
+    obj.insert("vendor".to_string(), optional_string(&disk.vendor));
+    obj.insert("wwn".to_string(), optional_string(&disk.wwn));
+    obj.insert("health".to_string(), optional_string(&disk.health));
+    obj.insert("used".to_string(), optional_string(&disk.used));
+    obj.insert("gpt".to_string(), Dynamic::Bool(disk.gpt.unwrap_or(false)));
+    obj.insert(
+        "osd_id".to_string(),
+        disk.osdid
+            .filter(|id| *id >= 0)
+            .map_or(Dynamic::Null, |id| Dynamic::Number(id as f64)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the state from the disks, sorted by device path
+fn build_disks_state(
+    mut state: DynamicValue,
+    node: &str,
+    mut disks: Vec<NodeDisk>,
+) -> DynamicValue {
+    disks.sort_by(|a, b| a.devpath.cmp(&b.devpath));
+
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/disks", node));
+    let _ = state.set_list(
+        &AttributePath::new("disks"),
+        disks.iter().map(disk_to_dynamic).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("devpaths"),
+        disks
+            .iter()
+            .map(|d| Dynamic::String(d.devpath.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the physical disks of a node
+#[derive(Default)]
+pub struct NodeDisksDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeDisksDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeDisksDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_disks"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the physical disks of a node, e.g. to pick the disks to initialize as LVM, ZFS or directory storage")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to list disks of")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("unused_only", AttributeType::Bool)
+                    .description("Only list disks without partitions, filesystems, LVM, ZFS or a Ceph OSD on them")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("disks", AttributeType::List(Box::new(disk_object_type())))
+                    .description("The disks, sorted by device path. used says what a disk holds, e.g. LVM, ZFS or partitions, and is null for unused disks")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("devpaths", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The device paths of the disks, e.g. /dev/sdb")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let node = match request.config.get_string(&AttributePath::new("node")) {
+            Ok(node) => node,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing node",
+                    "The 'node' attribute is required",
+                )]);
+            }
+        };
+        let unused_only = request
+            .config
+            .get_bool(&AttributePath::new("unused_only"))
+            .unwrap_or(false);
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .disks(unused_only)
+            .await
+        {
+            Ok(disks) => ReadDataSourceResponse {
+                state: build_disks_state(request.config, &node, disks),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list disks",
+                format!("Unable to list disks of node '{}': {}", node, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeDisksDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_node_disks_test.rs"]
+mod data_source_node_disks_test;
@@ -0,0 +1,64 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_node_disks_sorted_by_devpath() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/disks/list")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"devpath": "/dev/sdb", "size": 500107862016, "type": "ssd", "gpt": 0, "osdid": -1},
+                    {"devpath": "/dev/sda", "size": 500107862016, "type": "ssd", "used": "LVM", "gpt": 1, "osdid": -1},
+                    {"devpath": "/dev/nvme0n1", "size": 1000204886016, "type": "nvme", "used": "mounted", "gpt": 1, "osdid": 3}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let data_source = NodeDisksDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_node_disks",
+                    config(vec![("node", Dynamic::String("pve".to_string()))]),
+                ),
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/disks"
+        );
+        assert_eq!(
+            state.get_list(&AttributePath::new("devpaths")).unwrap(),
+            vec![
+                Dynamic::String("/dev/nvme0n1".to_string()),
+                Dynamic::String("/dev/sda".to_string()),
+                Dynamic::String("/dev/sdb".to_string()),
+            ]
+        );
+        let disk = |index: i64, name: &str| {
+            state
+                .get(&AttributePath::new("disks").index(index).attribute(name))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(disk(0, "osd_id"), Dynamic::Number(3.0));
+        assert_eq!(disk(1, "used"), Dynamic::String("LVM".to_string()));
+        assert_eq!(disk(2, "used"), Dynamic::Null);
+        assert_eq!(disk(2, "osd_id"), Dynamic::Null);
+    }
+}
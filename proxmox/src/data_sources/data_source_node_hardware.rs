@@ -0,0 +1,564 @@
+//! Node PCI and USB device inventory data sources
+
+use crate::api::nodes::{normalize_hex_id, PciDevice, UsbDevice};
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+fn optional_string(value: &Option<String>) -> Dynamic {
+    value
+        .as_ref()
+        .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+}
+
+fn required_node(config: &DynamicValue) -> Result<String, Diagnostic> {
+    config
+        .get_string(&AttributePath::new("node"))
+        .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))
+}
+
+/// Reads an optional hexadecimal ID filter, normalized to lowercase without
+/// the 0x prefix
+fn hex_filter(config: &DynamicValue, name: &str) -> Result<Option<String>, Diagnostic> {
+    let value = match config.get_string(&AttributePath::new(name)) {
+        Ok(value) => value,
+        Err(_) => return Ok(None),
+    };
+    let normalized = normalize_hex_id(&value);
+    if normalized.is_empty() || !normalized.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Diagnostic::error(
+            format!("Invalid {}", name),
+            format!("'{}' is not a hexadecimal ID, e.g. 10de or 0x10de", value),
+        )
+        .with_attribute(AttributePath::new(name)));
+    }
+    Ok(Some(normalized))
+}
+
+/// Filters applied to the PCI device listing
+#[derive(Debug, Default)]
+struct PciFilter {
+    class: Option<String>,
+    vendor_id: Option<String>,
+    device_id: Option<String>,
+}
+
+impl PciFilter {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        Ok(Self {
+            class: hex_filter(config, "class")?,
+            vendor_id: hex_filter(config, "vendor_id")?,
+            device_id: hex_filter(config, "device_id")?,
+        })
+    }
+
+    fn matches(&self, device: &PciDevice) -> bool {
+        self.class
+            .as_deref()
+            .map_or(true, |class| device.has_class_prefix(class))
+            && self
+                .vendor_id
+                .as_deref()
+                .map_or(true, |id| device.vendor_id() == id)
+            && self
+                .device_id
+                .as_deref()
+                .map_or(true, |id| device.device_id() == id)
+    }
+}
+
+fn pci_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("id".to_string(), AttributeType::String);
+    fields.insert("class".to_string(), AttributeType::String);
+    fields.insert("vendor_id".to_string(), AttributeType::String);
+    fields.insert("device_id".to_string(), AttributeType::String);
+    fields.insert("vendor_name".to_string(), AttributeType::String);
+    fields.insert("device_name".to_string(), AttributeType::String);
+    fields.insert("subsystem_vendor_id".to_string(), AttributeType::String);
+    fields.insert("subsystem_device_id".to_string(), AttributeType::String);
+    fields.insert("iommu_group".to_string(), AttributeType::Number);
+    fields.insert("mdev".to_string(), AttributeType::Bool);
+    AttributeType::Object(fields)
+}
+
+fn pci_to_dynamic(device: &PciDevice) -> Dynamic {
+    let optional_hex = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map_or(Dynamic::Null, |id| Dynamic::String(normalize_hex_id(id)))
+    };
+
+    let mut obj = HashMap::new();
+    obj.insert("id".to_string(), Dynamic::String(device.id.clone()));
+    obj.insert(
+        "class".to_string(),
+        Dynamic::String(normalize_hex_id(&device.class)),
+    );
+    obj.insert("vendor_id".to_string(), Dynamic::String(device.vendor_id()));
+    obj.insert("device_id".to_string(), Dynamic::String(device.device_id()));
+    obj.insert(
+        "vendor_name".to_string(),
+        optional_string(&device.vendor_name),
+    );
+    obj.insert(
+        "device_name".to_string(),
+        optional_string(&device.device_name),
+    );
+    obj.insert(
+        "subsystem_vendor_id".to_string(),
+        optional_hex(&device.subsystem_vendor),
+    );
+    obj.insert(
+        "subsystem_device_id".to_string(),
+        optional_hex(&device.subsystem_device),
+    );
+    obj.insert(
+        "iommu_group".to_string(),
+        device
+            .iommu_group()
+            .map_or(Dynamic::Null, |group| Dynamic::Number(group as f64)),
+    );
+    obj.insert(
+        "mdev".to_string(),
+        Dynamic::Bool(device.mdev.unwrap_or(false)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the PCI state from the matching devices, sorted by address
+fn build_pci_state(
+    mut state: DynamicValue,
+    node: &str,
+    mut devices: Vec<&PciDevice>,
+) -> DynamicValue {
+    devices.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/pci", node));
+    let _ = state.set_list(
+        &AttributePath::new("devices"),
+        devices.iter().map(|d| pci_to_dynamic(d)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("ids"),
+        devices
+            .iter()
+            .map(|d| Dynamic::String(d.id.clone()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the PCI devices of a node
+#[derive(Default)]
+pub struct NodePciDevicesDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodePciDevicesDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodePciDevicesDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_pci_devices"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the PCI devices of a node, e.g. to derive hostpci passthrough addresses from the hardware inventory. Bridges and memory controllers are hidden by Proxmox")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to list devices of")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("class", AttributeType::String)
+                    .description("Only list devices whose hex class code starts with this prefix, e.g. 03 for display controllers or 0300 for VGA")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vendor_id", AttributeType::String)
+                    .description("Only list devices of this hex vendor ID, e.g. 10de")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("device_id", AttributeType::String)
+                    .description("Only list devices with this hex device ID")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("devices", AttributeType::List(Box::new(pci_object_type())))
+                    .description("The matching devices, sorted by PCI address. IDs are lowercase hex without the 0x prefix")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ids", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The PCI addresses of the matching devices")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+        if let Err(diag) = PciFilter::from_config(&request.config) {
+            diagnostics.push(diag);
+        }
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let (node, filter) = match required_node(&request.config)
+            .and_then(|node| Ok((node, PciFilter::from_config(&request.config)?)))
+        {
+            Ok(parsed) => parsed,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .hardware()
+            .pci()
+            .await
+        {
+            Ok(devices) => {
+                let devices = devices.iter().filter(|d| filter.matches(d)).collect();
+                ReadDataSourceResponse {
+                    state: build_pci_state(request.config, &node, devices),
+                    diagnostics: vec![],
+                    deferred: None,
+                }
+            }
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list PCI devices",
+                format!("Unable to list PCI devices of node '{}': {}", node, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodePciDevicesDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+/// Filters applied to the USB device listing
+#[derive(Debug, Default)]
+struct UsbFilter {
+    vendor_id: Option<String>,
+    product_id: Option<String>,
+    class: Option<u32>,
+}
+
+impl UsbFilter {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        Ok(Self {
+            vendor_id: hex_filter(config, "vendor_id")?,
+            product_id: hex_filter(config, "product_id")?,
+            class: config.get_u32(&AttributePath::new("class")).ok(),
+        })
+    }
+
+    fn matches(&self, device: &UsbDevice) -> bool {
+        self.vendor_id
+            .as_deref()
+            .map_or(true, |id| device.vendor_id() == id)
+            && self
+                .product_id
+                .as_deref()
+                .map_or(true, |id| device.product_id() == id)
+            && self.class.map_or(true, |class| device.class == Some(class))
+    }
+}
+
+fn usb_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("host_id".to_string(), AttributeType::String);
+    fields.insert("host_port".to_string(), AttributeType::String);
+    fields.insert("bus".to_string(), AttributeType::Number);
+    fields.insert("device".to_string(), AttributeType::Number);
+    fields.insert("vendor_id".to_string(), AttributeType::String);
+    fields.insert("product_id".to_string(), AttributeType::String);
+    fields.insert("class".to_string(), AttributeType::Number);
+    fields.insert("speed".to_string(), AttributeType::String);
+    fields.insert("manufacturer".to_string(), AttributeType::String);
+    fields.insert("product".to_string(), AttributeType::String);
+    fields.insert("serial".to_string(), AttributeType::String);
+    AttributeType::Object(fields)
+}
+
+fn usb_to_dynamic(device: &UsbDevice) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("host_id".to_string(), Dynamic::String(device.host_id()));
+    obj.insert(
+        "host_port".to_string(),
+        optional_string(&device.host_port()),
+    );
+    obj.insert("bus".to_string(), Dynamic::Number(device.busnum as f64));
+    obj.insert("device".to_string(), Dynamic::Number(device.devnum as f64));
+    obj.insert("vendor_id".to_string(), Dynamic::String(device.vendor_id()));
+    obj.insert(
+        "product_id".to_string(),
+        Dynamic::String(device.product_id()),
+    );
+    obj.insert(
+        "class".to_string(),
+        device
+            .class
+            .map_or(Dynamic::Null, |class| Dynamic::Number(class as f64)),
+    );
+    obj.insert("speed".to_string(), optional_string(&device.speed));
+    obj.insert(
+        "manufacturer".to_string(),
+        optional_string(&device.manufacturer),
+    );
+    obj.insert("product".to_string(), optional_string(&device.product));
+    obj.insert("serial".to_string(), optional_string(&device.serial));
+    Dynamic::Map(obj)
+}
+
+/// Builds the USB state from the matching devices, sorted by bus and device
+fn build_usb_state(
+    mut state: DynamicValue,
+    node: &str,
+    mut devices: Vec<&UsbDevice>,
+) -> DynamicValue {
+    devices.sort_by_key(|d| (d.busnum, d.devnum));
+
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/usb", node));
+    let _ = state.set_list(
+        &AttributePath::new("devices"),
+        devices.iter().map(|d| usb_to_dynamic(d)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("host_ids"),
+        devices
+            .iter()
+            .map(|d| Dynamic::String(d.host_id()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists the USB devices attached to a node
+#[derive(Default)]
+pub struct NodeUsbDevicesDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeUsbDevicesDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeUsbDevicesDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_usb_devices"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the USB devices attached to a node, e.g. to derive usb passthrough host IDs from the hardware inventory")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to list devices of")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vendor_id", AttributeType::String)
+                    .description("Only list devices of this hex vendor ID, e.g. 046d")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("product_id", AttributeType::String)
+                    .description("Only list devices with this hex product ID")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("class", AttributeType::Number)
+                    .description("Only list devices of this USB class code, e.g. 224 for wireless controllers")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("devices", AttributeType::List(Box::new(usb_object_type())))
+                    .description("The matching devices, sorted by bus and device number. host_id (vendor:product) and host_port (bus-port) can be used as the host of a usb device")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("host_ids", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The vendor:product IDs of the matching devices")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = validate_integers(&request.config, &["class"]);
+        if let Err(diag) = UsbFilter::from_config(&request.config) {
+            diagnostics.push(diag);
+        }
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let (node, filter) = match required_node(&request.config)
+            .and_then(|node| Ok((node, UsbFilter::from_config(&request.config)?)))
+        {
+            Ok(parsed) => parsed,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .hardware()
+            .usb()
+            .await
+        {
+            Ok(devices) => {
+                let devices = devices.iter().filter(|d| filter.matches(d)).collect();
+                ReadDataSourceResponse {
+                    state: build_usb_state(request.config, &node, devices),
+                    diagnostics: vec![],
+                    deferred: None,
+                }
+            }
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list USB devices",
+                format!("Unable to list USB devices of node '{}': {}", node, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeUsbDevicesDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_node_hardware_test.rs"]
+mod data_source_node_hardware_test;
@@ -0,0 +1,138 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request, strings};
+    use mockito::Server;
+
+    #[tokio::test]
+    async fn test_pci_devices_filter_by_class_and_vendor() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/hardware/pci")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"id": "0000:01:00.1", "class": "0x040300", "vendor": "0x10de", "device": "0x0fb9", "iommugroup": 14},
+                    {"id": "0000:01:00.0", "class": "0x030000", "vendor": "0x10de", "device": "0x1c82", "device_name": "GP107", "iommugroup": 14},
+                    {"id": "0000:00:02.0", "class": "0x030000", "vendor": "0x8086", "device": "0x3e92", "iommugroup": 2}
+                ]}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let data_source = NodePciDevicesDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_node_pci_devices",
+                    config(vec![
+                        ("node", Dynamic::String("pve".to_string())),
+                        ("vendor_id", Dynamic::String("0x10DE".to_string())),
+                    ]),
+                ),
+            )
+            .await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            strings(&response.state, "ids"),
+            vec!["0000:01:00.0", "0000:01:00.1"]
+        );
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_node_pci_devices",
+                    config(vec![
+                        ("node", Dynamic::String("pve".to_string())),
+                        ("class", Dynamic::String("03".to_string())),
+                        ("vendor_id", Dynamic::String("10de".to_string())),
+                    ]),
+                ),
+            )
+            .await;
+        assert_eq!(strings(&response.state, "ids"), vec!["0000:01:00.0"]);
+        match &response
+            .state
+            .get_list(&AttributePath::new("devices"))
+            .unwrap()[0]
+        {
+            Dynamic::Map(obj) => {
+                assert_eq!(
+                    obj.get("device_id"),
+                    Some(&Dynamic::String("1c82".to_string()))
+                );
+                assert_eq!(obj.get("iommu_group"), Some(&Dynamic::Number(14.0)));
+            }
+            other => panic!("expected device object, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pci_devices_rejects_non_hex_filter() {
+        let response = NodePciDevicesDataSource::new()
+            .validate(
+                Context::new(),
+                ValidateDataSourceConfigRequest {
+                    type_name: "proxmox_node_pci_devices".to_string(),
+                    config: config(vec![("vendor_id", Dynamic::String("nvidia".to_string()))]),
+                },
+            )
+            .await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid vendor_id");
+    }
+
+    #[tokio::test]
+    async fn test_usb_devices_filter_by_vendor() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/hardware/usb")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"busnum": 2, "devnum": 2, "usbpath": "4", "vendid": "0x046d", "prodid": "0xc52b", "class": 0},
+                    {"busnum": 1, "devnum": 3, "usbpath": "1.2", "vendid": "0x1050", "prodid": "0x0407", "class": 0, "product": "YubiKey"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let response = NodeUsbDevicesDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(
+            Context::new(),
+            read_request(
+                "proxmox_node_usb_devices",
+                config(vec![
+                    ("node", Dynamic::String("pve".to_string())),
+                    ("vendor_id", Dynamic::String("1050".to_string())),
+                ]),
+            ),
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(strings(&response.state, "host_ids"), vec!["1050:0407"]);
+        match &response
+            .state
+            .get_list(&AttributePath::new("devices"))
+            .unwrap()[0]
+        {
+            Dynamic::Map(obj) => assert_eq!(
+                obj.get("host_port"),
+                Some(&Dynamic::String("1-1.2".to_string()))
+            ),
+            other => panic!("expected device object, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Node selector data source implementation
+
+use crate::api::cluster::{ClusterResource, ClusterResourceType};
+use crate::api::ByteSize;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::StringOneOfValidator;
+
+/// Ways of ranking the nodes that pass the filters
+const STRATEGIES: &[&str] = &["memory", "cpu", "guests"];
+
+/// Headroom and placement of one node
+#[derive(Debug, Clone, PartialEq)]
+struct Candidate {
+    node: String,
+    free_memory: u64,
+    free_cpus: f64,
+    /// Guests placed on the node, templates left out
+    guests: Vec<String>,
+}
+
+impl Candidate {
+    /// Builds the candidate for an online node entry of /cluster/resources
+    fn new(node: &ClusterResource, guests: &[ClusterResource]) -> Option<Self> {
+        if node.status.as_deref() != Some("online") {
+            return None;
+        }
+        let name = node.node.clone()?;
+        let maxcpu = node.maxcpu.unwrap_or(0.0);
+        let placed: Vec<&ClusterResource> = guests
+            .iter()
+            .filter(|guest| guest.node.as_deref() == Some(&name) && guest.template != Some(true))
+            .collect();
+
+        Some(Self {
+            free_memory: node
+                .maxmem
+                .unwrap_or(0)
+                .saturating_sub(node.mem.unwrap_or(0)),
+            free_cpus: (maxcpu * (1.0 - node.cpu.unwrap_or(0.0))).max(0.0),
+            guests: placed.iter().map(|guest| guest.id.clone()).collect(),
+            node: name,
+        })
+    }
+}
+
+/// Requirements taken from the configuration
+#[derive(Debug, Default)]
+struct Selector {
+    nodes: Vec<String>,
+    min_free_memory: Option<u64>,
+    min_free_cpus: Option<f64>,
+    affinity_tags: Vec<String>,
+    anti_affinity_tags: Vec<String>,
+    strategy: String,
+}
+
+impl Selector {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let strings = |name: &str| -> Vec<String> {
+            config
+                .get_list(&AttributePath::new(name))
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|value| match value {
+                    Dynamic::String(s) => Some(s),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let min_free_memory = config
+            .get_string(&AttributePath::new("min_free_memory"))
+            .ok()
+            .map(|size| {
+                ByteSize::parse(&size)
+                    .map(|size| size.bytes())
+                    .ok_or_else(|| {
+                        Diagnostic::attribute_error(
+                            AttributePath::new("min_free_memory"),
+                            "Invalid min_free_memory",
+                            format!("'{}' is not a size such as 512M or 16G", size),
+                        )
+                    })
+            })
+            .transpose()?;
+
+        let min_free_cpus = config.get_number(&AttributePath::new("min_free_cpus")).ok();
+        if min_free_cpus.is_some_and(|cpus| cpus < 0.0) {
+            return Err(Diagnostic::attribute_error(
+                AttributePath::new("min_free_cpus"),
+                "Invalid min_free_cpus",
+                "min_free_cpus can't be negative",
+            ));
+        }
+
+        Ok(Self {
+            nodes: strings("nodes"),
+            min_free_memory,
+            min_free_cpus,
+            affinity_tags: strings("affinity_tags"),
+            anti_affinity_tags: strings("anti_affinity_tags"),
+            strategy: config
+                .get_string(&AttributePath::new("strategy"))
+                .unwrap_or_else(|_| "memory".to_string()),
+        })
+    }
+
+    /// Whether a node meets every requirement. `tags` maps each guest ID to
+    /// the guest's tags.
+    fn accepts(&self, candidate: &Candidate, tags: &HashMap<&str, Vec<String>>) -> bool {
+        if !self.nodes.is_empty() && !self.nodes.contains(&candidate.node) {
+            return false;
+        }
+        if self
+            .min_free_memory
+            .is_some_and(|min| candidate.free_memory < min)
+        {
+            return false;
+        }
+        if self
+            .min_free_cpus
+            .is_some_and(|min| candidate.free_cpus < min)
+        {
+            return false;
+        }
+
+        let guest_tags = || {
+            candidate
+                .guests
+                .iter()
+                .filter_map(|id| tags.get(id.as_str()))
+        };
+        // Affinity wants a guest carrying all of the tags on the node,
+        // anti-affinity no guest carrying any of them
+        let affine = self.affinity_tags.is_empty()
+            || guest_tags().any(|guest| self.affinity_tags.iter().all(|t| guest.contains(t)));
+        let repelled = guest_tags().any(|guest| {
+            self.anti_affinity_tags
+                .iter()
+                .any(|tag| guest.contains(tag))
+        });
+        affine && !repelled
+    }
+
+    /// Nodes meeting the requirements, best first. Ties go to the node
+    /// whose name sorts first, so the choice is stable between runs.
+    fn rank(&self, nodes: &[ClusterResource], guests: &[ClusterResource]) -> Vec<Candidate> {
+        let tags: HashMap<&str, Vec<String>> = guests
+            .iter()
+            .map(|guest| (guest.id.as_str(), guest.tag_list()))
+            .collect();
+        let mut candidates: Vec<Candidate> = nodes
+            .iter()
+            .filter_map(|node| Candidate::new(node, guests))
+            .filter(|candidate| self.accepts(candidate, &tags))
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            let best = match self.strategy.as_str() {
+                "cpu" => b.free_cpus.total_cmp(&a.free_cpus),
+                "guests" => a.guests.len().cmp(&b.guests.len()),
+                _ => b.free_memory.cmp(&a.free_memory),
+            };
+            best.then_with(|| a.node.cmp(&b.node))
+        });
+        candidates
+    }
+}
+
+fn candidate_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("node".to_string(), AttributeType::String);
+    fields.insert("free_memory".to_string(), AttributeType::Number);
+    fields.insert("free_cpus".to_string(), AttributeType::Number);
+    fields.insert("guests".to_string(), AttributeType::Number);
+    AttributeType::Object(fields)
+}
+
+fn candidate_to_dynamic(candidate: &Candidate) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("node".to_string(), Dynamic::String(candidate.node.clone()));
+    obj.insert(
+        "free_memory".to_string(),
+        Dynamic::Number(candidate.free_memory as f64),
+    );
+    obj.insert(
+        "free_cpus".to_string(),
+        Dynamic::Number(candidate.free_cpus),
+    );
+    obj.insert(
+        "guests".to_string(),
+        Dynamic::Number(candidate.guests.len() as f64),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Picks the node with the most headroom for a new guest, for modules that
+/// place VMs without hardcoding a node
+#[derive(Default)]
+pub struct NodeSelectorDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeSelectorDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeSelectorDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_selector"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Selects the online node with the most headroom that meets the given CPU, memory and guest placement requirements. Usage is read from /cluster/resources when the data source is read, so the choice can change between runs")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nodes", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Only consider these nodes. Considers every online node when omitted")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("min_free_memory", AttributeType::String)
+                    .description("Memory a node must have unused, e.g. 16G")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("min_free_cpus", AttributeType::Number)
+                    .description("CPUs a node must have idle, counting partly used CPUs by their idle share")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("affinity_tags", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Only consider nodes running a guest that carries all of these tags")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("anti_affinity_tags", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Skip nodes running a guest that carries any of these tags, e.g. to spread replicas")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("strategy", AttributeType::String)
+                    .description("What the best node has the most of: memory (free memory), cpu (idle CPUs) or guests (fewest guests) (default: memory)")
+                    .optional()
+                    .validator(StringOneOfValidator::create(
+                        STRATEGIES.iter().map(|s| s.to_string()).collect(),
+                    ))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The selected node")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("candidates", AttributeType::List(Box::new(candidate_object_type())))
+                    .description("Every node meeting the requirements, best first, with its free memory in bytes, idle CPUs and number of guests")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+        if let Err(diag) = Selector::from_config(&request.config) {
+            diagnostics.push(diag);
+        }
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let selector = match Selector::from_config(&request.config) {
+            Ok(selector) => selector,
+            Err(diag) => {
+                return error_response(vec![diag]);
+            }
+        };
+
+        let listed = match provider_data
+            .client
+            .cluster()
+            .resources(Some(ClusterResourceType::Node))
+            .await
+        {
+            Ok(nodes) => provider_data.cluster_vms().await.map(|vms| (nodes, vms)),
+            Err(e) => Err(e),
+        };
+        let (nodes, guests) = match listed {
+            Ok(listed) => listed,
+            Err(e) => {
+                return error_response(vec![Diagnostic::error(
+                    "Failed to list cluster resources",
+                    format!("API error: {}", e),
+                )]);
+            }
+        };
+
+        let candidates = selector.rank(&nodes, &guests);
+        let Some(best) = candidates.first() else {
+            return error_response(vec![Diagnostic::error(
+                "No node available",
+                "No online node meets the requirements of the node selector",
+            )]);
+        };
+
+        let mut state = request.config;
+        let _ = state.set_string(&AttributePath::new("id"), "node_selector".to_string());
+        let _ = state.set_string(&AttributePath::new("node"), best.node.clone());
+        let _ = state.set_list(
+            &AttributePath::new("candidates"),
+            candidates.iter().map(candidate_to_dynamic).collect(),
+        );
+        ReadDataSourceResponse {
+            state,
+            diagnostics: vec![],
+            deferred: None,
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeSelectorDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_node_selector_test.rs"]
+mod data_source_node_selector_test;
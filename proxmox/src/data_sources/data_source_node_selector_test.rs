@@ -0,0 +1,152 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::{Matcher, Server};
+
+    // pve1: 24G of 64G free, 12 of 16 CPUs idle, runs db-1
+    // pve2: 48G of 64G free, 4 of 8 CPUs idle, runs web-1 and web-2
+    // pve3: offline
+    const NODES: &str = r#"{
+        "data": [
+            {"id": "node/pve1", "type": "node", "node": "pve1", "status": "online", "cpu": 0.25, "maxcpu": 16, "mem": 42949672960, "maxmem": 68719476736},
+            {"id": "node/pve2", "type": "node", "node": "pve2", "status": "online", "cpu": 0.5, "maxcpu": 8, "mem": 17179869184, "maxmem": 68719476736},
+            {"id": "node/pve3", "type": "node", "node": "pve3", "status": "offline", "maxcpu": 32, "maxmem": 137438953472}
+        ]
+    }"#;
+
+    const GUESTS: &str = r#"{
+        "data": [
+            {"id": "qemu/100", "type": "qemu", "node": "pve2", "vmid": 100, "name": "web-1", "status": "running", "tags": "web;prod"},
+            {"id": "qemu/101", "type": "qemu", "node": "pve2", "vmid": 101, "name": "web-2", "status": "running", "tags": "web"},
+            {"id": "lxc/200", "type": "lxc", "node": "pve1", "vmid": 200, "name": "db-1", "status": "running", "tags": "db;prod"},
+            {"id": "qemu/9000", "type": "qemu", "node": "pve1", "vmid": 9000, "name": "web-template", "status": "stopped", "template": 1, "tags": "web"}
+        ]
+    }"#;
+
+    fn strings(values: &[&str]) -> Dynamic {
+        Dynamic::List(
+            values
+                .iter()
+                .map(|v| Dynamic::String(v.to_string()))
+                .collect(),
+        )
+    }
+
+    async fn read_with(server: &mut Server, config: DynamicValue) -> ReadDataSourceResponse {
+        let _nodes = server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".into(), "node".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(NODES)
+            .create_async()
+            .await;
+        let _guests = server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(GUESTS)
+            .create_async()
+            .await;
+
+        NodeSelectorDataSource {
+            provider_data: provider_data(server),
+        }
+        .read(
+            Context::new(),
+            read_request("proxmox_node_selector", config),
+        )
+        .await
+    }
+
+    fn selected(response: &ReadDataSourceResponse) -> String {
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        response
+            .state
+            .get_string(&AttributePath::new("node"))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_strategies() {
+        let mut server = Server::new_async().await;
+
+        let response = read_with(&mut server, config(vec![])).await;
+        assert_eq!(selected(&response), "pve2");
+        let candidates = response
+            .state
+            .get_list(&AttributePath::new("candidates"))
+            .unwrap();
+        assert_eq!(candidates.len(), 2);
+        let Dynamic::Map(best) = &candidates[0] else {
+            panic!("candidate is not an object");
+        };
+        assert_eq!(best["free_memory"], Dynamic::Number(51539607552.0));
+        assert_eq!(best["free_cpus"], Dynamic::Number(4.0));
+        assert_eq!(best["guests"], Dynamic::Number(2.0));
+
+        let cpu = config(vec![("strategy", Dynamic::String("cpu".to_string()))]);
+        assert_eq!(selected(&read_with(&mut server, cpu).await), "pve1");
+
+        // The template on pve1 doesn't count
+        let guests = config(vec![("strategy", Dynamic::String("guests".to_string()))]);
+        assert_eq!(selected(&read_with(&mut server, guests).await), "pve1");
+    }
+
+    #[tokio::test]
+    async fn test_headroom_and_affinity() {
+        let mut server = Server::new_async().await;
+
+        let cpus = config(vec![("min_free_cpus", Dynamic::Number(6.0))]);
+        assert_eq!(selected(&read_with(&mut server, cpus).await), "pve1");
+
+        let spread = config(vec![("anti_affinity_tags", strings(&["web"]))]);
+        assert_eq!(selected(&read_with(&mut server, spread).await), "pve1");
+
+        let near_db = config(vec![("affinity_tags", strings(&["db", "prod"]))]);
+        assert_eq!(selected(&read_with(&mut server, near_db).await), "pve1");
+
+        let only = config(vec![("nodes", strings(&["pve1", "pve3"]))]);
+        assert_eq!(selected(&read_with(&mut server, only).await), "pve1");
+    }
+
+    #[tokio::test]
+    async fn test_no_node_fits() {
+        let mut server = Server::new_async().await;
+        let huge = config(vec![(
+            "min_free_memory",
+            Dynamic::String("64G".to_string()),
+        )]);
+
+        let response = read_with(&mut server, huge).await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "No node available");
+    }
+
+    #[tokio::test]
+    async fn test_validate_min_free_memory() {
+        let response = NodeSelectorDataSource::new()
+            .validate(
+                Context::new(),
+                ValidateDataSourceConfigRequest {
+                    type_name: "proxmox_node_selector".to_string(),
+                    config: config(vec![(
+                        "min_free_memory",
+                        Dynamic::String("lots".to_string()),
+                    )]),
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid min_free_memory");
+    }
+}
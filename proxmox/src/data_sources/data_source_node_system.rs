@@ -0,0 +1,283 @@
+//! Node DNS and time settings data sources
+
+use crate::api::nodes::{NodeDns, NodeTime};
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{Attribute, AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+/// Resolves the provider data and node shared by both data sources
+fn provider_and_node<'a>(
+    provider_data: &'a Option<crate::ProxmoxProviderData>,
+    config: &DynamicValue,
+) -> Result<(&'a crate::ProxmoxProviderData, String), Diagnostic> {
+    let provider_data = provider_data.as_ref().ok_or_else(|| {
+        Diagnostic::error(
+            "Provider not configured",
+            "Provider data was not properly configured",
+        )
+    })?;
+    let node = config
+        .get_string(&AttributePath::new("node"))
+        .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+    Ok((provider_data, node))
+}
+
+fn node_attribute() -> Attribute {
+    AttributeBuilder::new("node", AttributeType::String)
+        .description("The node to read the settings of")
+        .required()
+        .build()
+}
+
+fn id_attribute() -> Attribute {
+    AttributeBuilder::new("id", AttributeType::String)
+        .description("The data source ID")
+        .computed()
+        .build()
+}
+
+fn build_dns_state(mut state: DynamicValue, node: &str, dns: &NodeDns) -> DynamicValue {
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/dns", node));
+    let _ = match &dns.search {
+        Some(search) => state.set_string(&AttributePath::new("search"), search.clone()),
+        None => state.set_null(&AttributePath::new("search")),
+    };
+    let _ = state.set_list(
+        &AttributePath::new("servers"),
+        dns.servers().into_iter().map(Dynamic::String).collect(),
+    );
+    state
+}
+
+fn build_time_state(mut state: DynamicValue, node: &str, time: &NodeTime) -> DynamicValue {
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/time", node));
+    let _ = state.set_string(&AttributePath::new("timezone"), time.timezone.clone());
+    let _ = match time.time {
+        Some(now) => state.set_i64(&AttributePath::new("time"), now as i64),
+        None => state.set_null(&AttributePath::new("time")),
+    };
+    let _ = match time.localtime {
+        Some(local) => state.set_i64(&AttributePath::new("local_time"), local as i64),
+        None => state.set_null(&AttributePath::new("local_time")),
+    };
+    state
+}
+
+/// Reads the DNS resolver settings of a node
+#[derive(Default)]
+pub struct NodeDnsDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeDnsDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeDnsDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_dns"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Reads the DNS resolver settings of a node")
+            .attribute(id_attribute())
+            .attribute(node_attribute())
+            .attribute(
+                AttributeBuilder::new("search", AttributeType::String)
+                    .description("The search domain")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "servers",
+                    AttributeType::List(Box::new(AttributeType::String)),
+                )
+                .description("The DNS servers in order of preference")
+                .computed()
+                .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let (provider_data, node) = match provider_and_node(&self.provider_data, &request.config) {
+            Ok(resolved) => resolved,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data.client.nodes().node(&node).dns().await {
+            Ok(dns) => ReadDataSourceResponse {
+                state: build_dns_state(request.config, &node, &dns),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read DNS settings",
+                format!("Unable to read DNS settings of node '{}': {}", node, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeDnsDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+/// Reads the time and timezone of a node
+#[derive(Default)]
+pub struct NodeTimeDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeTimeDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeTimeDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_time"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Reads the time and timezone of a node")
+            .attribute(id_attribute())
+            .attribute(node_attribute())
+            .attribute(
+                AttributeBuilder::new("timezone", AttributeType::String)
+                    .description("The timezone, e.g. Europe/Berlin")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("time", AttributeType::Number)
+                    .description("The current time as a unix timestamp")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("local_time", AttributeType::Number)
+                    .description("The current local time in seconds since the epoch, shifted by the timezone offset")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let (provider_data, node) = match provider_and_node(&self.provider_data, &request.config) {
+            Ok(resolved) => resolved,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data.client.nodes().node(&node).time().await {
+            Ok(time) => ReadDataSourceResponse {
+                state: build_time_state(request.config, &node, &time),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read time settings",
+                format!("Unable to read time settings of node '{}': {}", node, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeTimeDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_node_system_test.rs"]
+mod data_source_node_system_test;
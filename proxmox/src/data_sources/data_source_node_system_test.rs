@@ -0,0 +1,78 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::Server;
+
+    fn node_config() -> DynamicValue {
+        config(vec![("node", Dynamic::String("pve".to_string()))])
+    }
+
+    #[tokio::test]
+    async fn test_dns_reads_servers_in_order() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/dns")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"search": "lan", "dns1": "10.0.0.1", "dns2": "1.1.1.1"}}"#)
+            .create_async()
+            .await;
+
+        let response = NodeDnsDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(Context::new(), read_request("proxmox_dns", node_config()))
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/dns"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("search")).unwrap(),
+            "lan"
+        );
+        assert_eq!(
+            state.get_list(&AttributePath::new("servers")).unwrap(),
+            vec![
+                Dynamic::String("10.0.0.1".to_string()),
+                Dynamic::String("1.1.1.1".to_string())
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_reads_timezone() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/time")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"timezone": "UTC", "time": 1700000000, "localtime": 1700000000}}"#,
+            )
+            .create_async()
+            .await;
+
+        let response = NodeTimeDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(Context::new(), read_request("proxmox_time", node_config()))
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("timezone")).unwrap(),
+            "UTC"
+        );
+        assert_eq!(
+            state.get_number(&AttributePath::new("time")).unwrap(),
+            1700000000.0
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Resource pool membership data source implementation
+
+use crate::api::pools::{PoolInfo, PoolMember};
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+fn member_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("id".to_string(), AttributeType::String);
+    fields.insert("type".to_string(), AttributeType::String);
+    fields.insert("node".to_string(), AttributeType::String);
+    fields.insert("vmid".to_string(), AttributeType::Number);
+    fields.insert("name".to_string(), AttributeType::String);
+    fields.insert("storage".to_string(), AttributeType::String);
+    AttributeType::Object(fields)
+}
+
+fn member_to_dynamic(member: &PoolMember) -> Dynamic {
+    let optional_string = |value: &Option<String>| {
+        value
+            .as_ref()
+            .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+    };
+
+    let mut obj = HashMap::new();
+    obj.insert("id".to_string(), Dynamic::String(member.id.clone()));
+    obj.insert(
+        "type".to_string(),
+        Dynamic::String(member.member_type.clone()),
+    );
+    obj.insert("node".to_string(), optional_string(&member.node));
+    obj.insert(
+        "vmid".to_string(),
+        member
+            .vmid
+            .map_or(Dynamic::Null, |id| Dynamic::Number(id as f64)),
+    );
+    obj.insert("name".to_string(), optional_string(&member.name));
+    obj.insert("storage".to_string(), optional_string(&member.storage));
+    Dynamic::Map(obj)
+}
+
+/// Builds the data source state from the pool, with members sorted by id
+fn build_state(mut state: DynamicValue, pool_id: &str, pool: &PoolInfo) -> DynamicValue {
+    let mut members: Vec<&PoolMember> = pool.members.iter().collect();
+    members.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut vmids: Vec<u32> = members
+        .iter()
+        .filter(|m| m.is_guest())
+        .filter_map(|m| m.vmid)
+        .collect();
+    vmids.sort_unstable();
+
+    let mut storages: Vec<String> = members
+        .iter()
+        .filter(|m| m.member_type == "storage")
+        .filter_map(|m| m.storage.clone())
+        .collect();
+    storages.sort();
+    storages.dedup();
+
+    let _ = state.set_string(&AttributePath::new("id"), pool_id.to_string());
+    let _ = match &pool.comment {
+        Some(comment) => state.set_string(&AttributePath::new("comment"), comment.clone()),
+        None => state.set_null(&AttributePath::new("comment")),
+    };
+    let _ = state.set_list(
+        &AttributePath::new("members"),
+        members.iter().map(|m| member_to_dynamic(m)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("vmids"),
+        vmids
+            .into_iter()
+            .map(|id| Dynamic::Number(id as f64))
+            .collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("storages"),
+        storages.into_iter().map(Dynamic::String).collect(),
+    );
+    state
+}
+
+/// Reads the members of a resource pool
+#[derive(Default)]
+pub struct PoolDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl PoolDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for PoolDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_pool"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description(
+                "Reads a resource pool and the VMs, containers and storages that belong to it",
+            )
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pool_id", AttributeType::String)
+                    .description("The pool identifier")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("comment", AttributeType::String)
+                    .description("The pool comment")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "members",
+                    AttributeType::List(Box::new(member_object_type())),
+                )
+                .description("All pool members, sorted by id")
+                .computed()
+                .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "vmids",
+                    AttributeType::List(Box::new(AttributeType::Number)),
+                )
+                .description("The VMIDs of the VMs and containers in the pool")
+                .computed()
+                .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "storages",
+                    AttributeType::List(Box::new(AttributeType::String)),
+                )
+                .description("The storages in the pool")
+                .computed()
+                .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        if let Ok(pool_id) = request.config.get_string(&AttributePath::new("pool_id")) {
+            if pool_id.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid pool_id",
+                    "The pool identifier must not be empty",
+                ));
+            }
+        }
+
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let pool_id = match request.config.get_string(&AttributePath::new("pool_id")) {
+            Ok(pool_id) => pool_id,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing pool_id",
+                    "The 'pool_id' attribute is required",
+                )]);
+            }
+        };
+
+        match provider_data.client.pools().get(&pool_id).await {
+            Ok(pool) => ReadDataSourceResponse {
+                state: build_state(request.config, &pool_id, &pool),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read pool",
+                format!("Unable to read pool '{}': {}", pool_id, e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for PoolDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_pool_test.rs"]
+mod data_source_pool_test;
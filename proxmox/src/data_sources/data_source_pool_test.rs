@@ -0,0 +1,93 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{provider_data, read_request};
+    use mockito::Server;
+
+    fn config(pool_id: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("pool_id".to_string(), Dynamic::String(pool_id.to_string()));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    async fn read_pool(server: &Server, pool_id: &str) -> ReadDataSourceResponse {
+        let data_source = PoolDataSource {
+            provider_data: provider_data(server),
+        };
+
+        data_source
+            .read(
+                Context::new(),
+                read_request("proxmox_pool", config(pool_id)),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_read_pool_members() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/pools/prod")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"comment": "Production", "members": [
+                    {"id": "qemu/101", "type": "qemu", "node": "pve1", "vmid": 101, "name": "web-2"},
+                    {"id": "storage/pve1/local-lvm", "type": "storage", "node": "pve1", "storage": "local-lvm"},
+                    {"id": "lxc/200", "type": "lxc", "node": "pve2", "vmid": 200, "name": "ct-1"},
+                    {"id": "qemu/100", "type": "qemu", "node": "pve2", "vmid": 100, "name": "web-1"},
+                    {"id": "storage/pve2/local-lvm", "type": "storage", "node": "pve2", "storage": "local-lvm"}
+                ]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let response = read_pool(&server, "prod").await;
+        assert!(response.diagnostics.is_empty());
+
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("comment")).unwrap(),
+            "Production"
+        );
+        assert_eq!(
+            state.get_list(&AttributePath::new("vmids")).unwrap(),
+            vec![
+                Dynamic::Number(100.0),
+                Dynamic::Number(101.0),
+                Dynamic::Number(200.0)
+            ]
+        );
+        assert_eq!(
+            state.get_list(&AttributePath::new("storages")).unwrap(),
+            vec![Dynamic::String("local-lvm".to_string())]
+        );
+
+        let members = state.get_list(&AttributePath::new("members")).unwrap();
+        assert_eq!(members.len(), 5);
+        match &members[0] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("id"), Some(&Dynamic::String("lxc/200".to_string())));
+                assert_eq!(obj.get("storage"), Some(&Dynamic::Null));
+            }
+            other => panic!("expected member object, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_missing_pool() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/pools/missing")
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null, "errors": {"poolid": "pool 'missing' does not exist"}}"#)
+            .create_async()
+            .await;
+
+        let response = read_pool(&server, "missing").await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Failed to read pool");
+    }
+}
@@ -0,0 +1,328 @@
+//! QEMU VM listing data source implementation
+
+use crate::api::cluster::ClusterResource;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+/// Filters applied to the cluster wide VM listing
+#[derive(Debug, Default)]
+struct VmFilter {
+    node: Option<String>,
+    name_regex: Option<Regex>,
+    tags: Vec<String>,
+    status: Option<String>,
+    pool: Option<String>,
+    template: Option<bool>,
+}
+
+impl VmFilter {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let name_regex = match config.get_string(&AttributePath::new("name_regex")) {
+            Ok(pattern) => Some(Regex::new(&pattern).map_err(|e| {
+                Diagnostic::error(
+                    "Invalid name_regex",
+                    format!("'{}' is not a valid regular expression: {}", pattern, e),
+                )
+            })?),
+            Err(_) => None,
+        };
+
+        let tags = config
+            .get_list(&AttributePath::new("tags"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tag| match tag {
+                Dynamic::String(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+
+        Ok(Self {
+            node: config.get_string(&AttributePath::new("node")).ok(),
+            name_regex,
+            tags,
+            status: config.get_string(&AttributePath::new("status")).ok(),
+            pool: config.get_string(&AttributePath::new("pool")).ok(),
+            template: config.get_bool(&AttributePath::new("template")).ok(),
+        })
+    }
+
+    fn matches(&self, vm: &ClusterResource) -> bool {
+        if !vm.is_qemu() {
+            return false;
+        }
+        if self.node.is_some() && vm.node != self.node {
+            return false;
+        }
+        if self.status.is_some() && vm.status != self.status {
+            return false;
+        }
+        if self.pool.is_some() && vm.pool != self.pool {
+            return false;
+        }
+        if let Some(template) = self.template {
+            if vm.template.unwrap_or(false) != template {
+                return false;
+            }
+        }
+        if let Some(re) = &self.name_regex {
+            if !re.is_match(vm.name.as_deref().unwrap_or_default()) {
+                return false;
+            }
+        }
+        // Every requested tag has to be present on the VM
+        let vm_tags = vm.tag_list();
+        self.tags.iter().all(|tag| vm_tags.contains(tag))
+    }
+}
+
+fn vm_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("vmid".to_string(), AttributeType::Number);
+    fields.insert("name".to_string(), AttributeType::String);
+    fields.insert("node".to_string(), AttributeType::String);
+    fields.insert("status".to_string(), AttributeType::String);
+    fields.insert("pool".to_string(), AttributeType::String);
+    fields.insert(
+        "tags".to_string(),
+        AttributeType::List(Box::new(AttributeType::String)),
+    );
+    fields.insert("template".to_string(), AttributeType::Bool);
+    AttributeType::Object(fields)
+}
+
+fn vm_to_dynamic(vm: &ClusterResource) -> Dynamic {
+    let optional_string = |value: &Option<String>| {
+        value
+            .as_ref()
+            .map_or(Dynamic::Null, |s| Dynamic::String(s.clone()))
+    };
+
+    let mut obj = HashMap::new();
+    obj.insert(
+        "vmid".to_string(),
+        vm.vmid
+            .map_or(Dynamic::Null, |id| Dynamic::Number(id as f64)),
+    );
+    obj.insert("name".to_string(), optional_string(&vm.name));
+    obj.insert("node".to_string(), optional_string(&vm.node));
+    obj.insert("status".to_string(), optional_string(&vm.status));
+    obj.insert("pool".to_string(), optional_string(&vm.pool));
+    obj.insert(
+        "tags".to_string(),
+        Dynamic::List(vm.tag_list().into_iter().map(Dynamic::String).collect()),
+    );
+    obj.insert(
+        "template".to_string(),
+        Dynamic::Bool(vm.template.unwrap_or(false)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the data source state from the filtered VMs
+fn build_state(mut state: DynamicValue, vms: &[&ClusterResource]) -> DynamicValue {
+    let id = match state.get_string(&AttributePath::new("node")) {
+        Ok(node) => format!("qemu_vms/{}", node),
+        Err(_) => "qemu_vms".to_string(),
+    };
+
+    let _ = state.set_string(&AttributePath::new("id"), id);
+    let _ = state.set_list(
+        &AttributePath::new("vms"),
+        vms.iter().map(|vm| vm_to_dynamic(vm)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("vmids"),
+        vms.iter()
+            .filter_map(|vm| vm.vmid)
+            .map(|id| Dynamic::Number(id as f64))
+            .collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("names"),
+        vms.iter()
+            .map(|vm| Dynamic::String(vm.name.clone().unwrap_or_default()))
+            .collect(),
+    );
+    state
+}
+
+/// Lists QEMU VMs across the cluster with optional filters
+#[derive(Default)]
+pub struct QemuVmsDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl QemuVmsDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for QemuVmsDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_qemu_vms"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists QEMU VMs on a node or across the whole cluster, optionally filtered by name, tags, status or pool")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("Only list VMs on this node. Lists the whole cluster when omitted")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("name_regex", AttributeType::String)
+                    .description("Only list VMs whose name matches this regular expression")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("tags", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Only list VMs carrying all of these tags")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("status", AttributeType::String)
+                    .description("Only list VMs in this state, e.g. running or stopped")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pool", AttributeType::String)
+                    .description("Only list VMs that are members of this pool")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("template", AttributeType::Bool)
+                    .description("When set, only list templates (true) or regular VMs (false)")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vms", AttributeType::List(Box::new(vm_object_type())))
+                    .description("The matching VMs")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmids", AttributeType::List(Box::new(AttributeType::Number)))
+                    .description("The IDs of the matching VMs")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("names", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The names of the matching VMs")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+        if let Err(diag) = VmFilter::from_config(&request.config) {
+            diagnostics.push(diag);
+        }
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let filter = match VmFilter::from_config(&request.config) {
+            Ok(filter) => filter,
+            Err(diag) => {
+                return error_response(vec![diag]);
+            }
+        };
+
+        match provider_data.cluster_vms().await {
+            Ok(resources) => {
+                let mut vms: Vec<&ClusterResource> =
+                    resources.iter().filter(|vm| filter.matches(vm)).collect();
+                vms.sort_by_key(|vm| vm.vmid);
+
+                ReadDataSourceResponse {
+                    state: build_state(request.config, &vms),
+                    diagnostics: vec![],
+                    deferred: None,
+                }
+            }
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list VMs",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for QemuVmsDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_qemu_vms_test.rs"]
+mod data_source_qemu_vms_test;
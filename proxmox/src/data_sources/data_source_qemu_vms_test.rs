@@ -0,0 +1,115 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::{Matcher, Server};
+
+    const RESOURCES: &str = r#"{
+        "data": [
+            {"id": "qemu/101", "type": "qemu", "node": "pve1", "vmid": 101, "name": "web-2", "status": "running", "tags": "web;prod"},
+            {"id": "qemu/100", "type": "qemu", "node": "pve2", "vmid": 100, "name": "web-1", "status": "running", "pool": "prod", "tags": "web,prod"},
+            {"id": "qemu/102", "type": "qemu", "node": "pve1", "vmid": 102, "name": "db-1", "status": "stopped", "tags": "db"},
+            {"id": "qemu/9000", "type": "qemu", "node": "pve1", "vmid": 9000, "name": "web-template", "status": "stopped", "template": 1},
+            {"id": "lxc/200", "type": "lxc", "node": "pve1", "vmid": 200, "name": "web-ct", "status": "running"}
+        ]
+    }"#;
+
+    async fn read_with(server: &mut Server, config: DynamicValue) -> ReadDataSourceResponse {
+        let _m = server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(RESOURCES)
+            .create_async()
+            .await;
+
+        let data_source = QemuVmsDataSource {
+            provider_data: provider_data(server),
+        };
+
+        data_source
+            .read(Context::new(), read_request("proxmox_qemu_vms", config))
+            .await
+    }
+
+    fn vmids(state: &DynamicValue) -> Vec<f64> {
+        state
+            .get_list(&AttributePath::new("vmids"))
+            .unwrap()
+            .into_iter()
+            .filter_map(|v| match v {
+                Dynamic::Number(n) => Some(n),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_read_without_filters_lists_qemu_vms_sorted() {
+        let mut server = Server::new_async().await;
+        let response = read_with(&mut server, config(vec![])).await;
+
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(vmids(&response.state), vec![100.0, 101.0, 102.0, 9000.0]);
+        assert_eq!(
+            response
+                .state
+                .get_string(&AttributePath::new("id"))
+                .unwrap(),
+            "qemu_vms"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_applies_filters() {
+        let mut server = Server::new_async().await;
+        let response = read_with(
+            &mut server,
+            config(vec![
+                ("name_regex", Dynamic::String("^web-".to_string())),
+                (
+                    "tags",
+                    Dynamic::List(vec![
+                        Dynamic::String("web".to_string()),
+                        Dynamic::String("prod".to_string()),
+                    ]),
+                ),
+                ("status", Dynamic::String("running".to_string())),
+                ("template", Dynamic::Bool(false)),
+            ]),
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(vmids(&response.state), vec![100.0, 101.0]);
+
+        let response = read_with(
+            &mut server,
+            config(vec![
+                ("node", Dynamic::String("pve2".to_string())),
+                ("pool", Dynamic::String("prod".to_string())),
+            ]),
+        )
+        .await;
+        assert_eq!(vmids(&response.state), vec![100.0]);
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_invalid_regex() {
+        let data_source = QemuVmsDataSource::new();
+        let response = data_source
+            .validate(
+                Context::new(),
+                ValidateDataSourceConfigRequest {
+                    type_name: "proxmox_qemu_vms".to_string(),
+                    config: config(vec![("name_regex", Dynamic::String("web-(".to_string()))]),
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid name_regex");
+    }
+}
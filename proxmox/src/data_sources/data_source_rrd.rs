@@ -0,0 +1,469 @@
+//! RRD metrics data source implementations
+
+use crate::api::nodes::{RrdConsolidation, RrdDataPoint, RrdTimeframe};
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+const NODE_METRICS: &[&str] = &[
+    "time",
+    "cpu",
+    "maxcpu",
+    "iowait",
+    "loadavg",
+    "memused",
+    "memtotal",
+    "swapused",
+    "swaptotal",
+    "rootused",
+    "roottotal",
+    "netin",
+    "netout",
+];
+
+const QEMU_METRICS: &[&str] = &[
+    "time",
+    "cpu",
+    "maxcpu",
+    "mem",
+    "maxmem",
+    "disk",
+    "maxdisk",
+    "diskread",
+    "diskwrite",
+    "netin",
+    "netout",
+];
+
+fn metrics_object_type(metrics: &[&str]) -> AttributeType {
+    AttributeType::Object(
+        metrics
+            .iter()
+            .map(|m| (m.to_string(), AttributeType::Number))
+            .collect(),
+    )
+}
+
+fn schema_builder(description: &str, metrics: &[&str]) -> SchemaBuilder {
+    SchemaBuilder::new()
+        .version(0)
+        .description(description)
+        .attribute(
+            AttributeBuilder::new("id", AttributeType::String)
+                .description("The data source ID")
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("node", AttributeType::String)
+                .description("The name of the Proxmox node")
+                .required()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("timeframe", AttributeType::String)
+                .description("Time window: hour, day, week, month, year or decade (default: hour)")
+                .optional()
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("cf", AttributeType::String)
+                .description("Consolidation function: AVERAGE or MAX (default: AVERAGE)")
+                .optional()
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("cpu_average", AttributeType::Number)
+                .description("Average CPU usage over the timeframe as a fraction (0-1), null without samples")
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("cpu_max", AttributeType::Number)
+                .description("Highest CPU usage over the timeframe as a fraction (0-1), null without samples")
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("memory_average", AttributeType::Number)
+                .description("Average memory usage over the timeframe as a fraction (0-1), null without samples")
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new("memory_max", AttributeType::Number)
+                .description("Highest memory usage over the timeframe as a fraction (0-1), null without samples")
+                .computed()
+                .build(),
+        )
+        .attribute(
+            AttributeBuilder::new(
+                "data",
+                AttributeType::List(Box::new(metrics_object_type(metrics))),
+            )
+            .description("Raw samples, oldest first. Metrics missing from a sample are null")
+            .computed()
+            .build(),
+        )
+}
+
+fn validate_query(config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+    if let Ok(timeframe) = config.get_string(&AttributePath::new("timeframe")) {
+        if let Err(e) = timeframe.parse::<RrdTimeframe>() {
+            diagnostics.push(Diagnostic::error("Invalid timeframe", e));
+        }
+    }
+
+    if let Ok(cf) = config.get_string(&AttributePath::new("cf")) {
+        if let Err(e) = cf.parse::<RrdConsolidation>() {
+            diagnostics.push(Diagnostic::error("Invalid consolidation function", e));
+        }
+    }
+}
+
+fn parse_query(config: &DynamicValue) -> Result<(RrdTimeframe, RrdConsolidation), Diagnostic> {
+    let timeframe = match config.get_string(&AttributePath::new("timeframe")) {
+        Ok(timeframe) => timeframe
+            .parse()
+            .map_err(|e: String| Diagnostic::error("Invalid timeframe", e))?,
+        Err(_) => RrdTimeframe::Hour,
+    };
+
+    let cf = match config.get_string(&AttributePath::new("cf")) {
+        Ok(cf) => cf
+            .parse()
+            .map_err(|e: String| Diagnostic::error("Invalid consolidation function", e))?,
+        Err(_) => RrdConsolidation::Average,
+    };
+
+    Ok((timeframe, cf))
+}
+
+/// Average of the samples, None when there are none
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Highest sample, None when there are none
+fn maximum(values: &[f64]) -> Option<f64> {
+    values.iter().copied().reduce(f64::max)
+}
+
+/// Builds the data source state from the RRD samples
+fn build_state(
+    mut state: DynamicValue,
+    id: String,
+    timeframe: RrdTimeframe,
+    cf: RrdConsolidation,
+    points: &[RrdDataPoint],
+    metrics: &[&str],
+) -> DynamicValue {
+    let cpu: Vec<f64> = points.iter().filter_map(|p| p.cpu).collect();
+    let memory: Vec<f64> = points.iter().filter_map(|p| p.memory_usage()).collect();
+
+    let data = points
+        .iter()
+        .map(|point| {
+            let sample: HashMap<String, Dynamic> = metrics
+                .iter()
+                .map(|m| {
+                    let value = point.metric(m).map_or(Dynamic::Null, Dynamic::Number);
+                    (m.to_string(), value)
+                })
+                .collect();
+            Dynamic::Map(sample)
+        })
+        .collect();
+
+    let _ = state.set_string(&AttributePath::new("id"), id);
+    let _ = state.set_string(
+        &AttributePath::new("timeframe"),
+        timeframe.as_str().to_string(),
+    );
+    let _ = state.set_string(&AttributePath::new("cf"), cf.as_str().to_string());
+    // Without samples there is nothing to aggregate, which is not 0% usage
+    for (name, value) in [
+        ("cpu_average", average(&cpu)),
+        ("cpu_max", maximum(&cpu)),
+        ("memory_average", average(&memory)),
+        ("memory_max", maximum(&memory)),
+    ] {
+        let path = AttributePath::new(name);
+        let _ = match value {
+            Some(value) => state.set_number(&path, value),
+            None => state.set_null(&path),
+        };
+    }
+    let _ = state.set_list(&AttributePath::new("data"), data);
+    state
+}
+
+/// Reads node level RRD statistics
+#[derive(Default)]
+pub struct NodeRrdDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeRrdDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for NodeRrdDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_rrd"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        DataSourceSchemaResponse {
+            schema: schema_builder("Gets RRD utilization statistics for a node", NODE_METRICS)
+                .build(),
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+        validate_query(&request.config, &mut diagnostics);
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let node = match request.config.get_string(&AttributePath::new("node")) {
+            Ok(node) => node,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing node",
+                    "The 'node' attribute is required",
+                )]);
+            }
+        };
+
+        let (timeframe, cf) = match parse_query(&request.config) {
+            Ok(query) => query,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .rrddata(timeframe, Some(cf))
+            .await
+        {
+            Ok(points) => ReadDataSourceResponse {
+                state: build_state(
+                    request.config,
+                    format!("{}/{}/{}", node, timeframe, cf),
+                    timeframe,
+                    cf,
+                    &points,
+                    NODE_METRICS,
+                ),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read node RRD data",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for NodeRrdDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+/// Reads RRD statistics for a single QEMU VM
+#[derive(Default)]
+pub struct QemuVmRrdDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl QemuVmRrdDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for QemuVmRrdDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_qemu_vm_rrd"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = schema_builder(
+            "Gets RRD utilization statistics for a QEMU VM",
+            QEMU_METRICS,
+        )
+        .attribute(
+            AttributeBuilder::new("vmid", AttributeType::Number)
+                .description("The VM identifier")
+                .required()
+                .build(),
+        )
+        .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = validate_integers(&request.config, &["vmid"]);
+        validate_query(&request.config, &mut diagnostics);
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let node = match request.config.get_string(&AttributePath::new("node")) {
+            Ok(node) => node,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing node",
+                    "The 'node' attribute is required",
+                )]);
+            }
+        };
+
+        let vmid = match request.config.get_u32(&AttributePath::new("vmid")) {
+            Ok(vmid) => vmid,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing vmid",
+                    "The 'vmid' attribute is required",
+                )]);
+            }
+        };
+
+        let (timeframe, cf) = match parse_query(&request.config) {
+            Ok(query) => query,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .qemu()
+            .rrddata(vmid, timeframe, Some(cf))
+            .await
+        {
+            Ok(points) => ReadDataSourceResponse {
+                state: build_state(
+                    request.config,
+                    format!("{}/{}/{}/{}", node, vmid, timeframe, cf),
+                    timeframe,
+                    cf,
+                    &points,
+                    QEMU_METRICS,
+                ),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to read VM RRD data",
+                format!("API error: {}", e),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for QemuVmRrdDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_rrd_test.rs"]
+mod data_source_rrd_test;
@@ -0,0 +1,126 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{provider_data, read_request};
+    use mockito::{Matcher, Server};
+
+    fn config() -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+        obj.insert("timeframe".to_string(), Dynamic::Null);
+        obj.insert("cf".to_string(), Dynamic::Null);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn points(json: &str) -> Vec<RrdDataPoint> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn number(state: &DynamicValue, name: &str) -> Option<f64> {
+        state.get_number(&AttributePath::new(name)).ok()
+    }
+
+    #[test]
+    fn test_aggregates() {
+        assert_eq!(average(&[0.25, 0.75]), Some(0.5));
+        assert_eq!(maximum(&[0.25, 0.75]), Some(0.75));
+        assert_eq!(average(&[]), None);
+        assert_eq!(maximum(&[]), None);
+    }
+
+    #[test]
+    fn test_build_state_skips_null_samples() {
+        // The newest sample of a window is often still empty
+        let samples = points(
+            r#"[
+                {"time": 60, "cpu": 0.2, "mem": 512, "maxmem": 2048},
+                {"time": 120, "cpu": 0.6, "mem": 1024, "maxmem": 2048},
+                {"time": 180}
+            ]"#,
+        );
+
+        let state = build_state(
+            config(),
+            "pve/100/hour/AVERAGE".to_string(),
+            RrdTimeframe::Hour,
+            RrdConsolidation::Average,
+            &samples,
+            QEMU_METRICS,
+        );
+
+        assert!((number(&state, "cpu_average").unwrap() - 0.4).abs() < 1e-9);
+        assert_eq!(number(&state, "cpu_max"), Some(0.6));
+        assert_eq!(number(&state, "memory_average"), Some(0.375));
+        assert_eq!(number(&state, "memory_max"), Some(0.5));
+        let data = state.get_list(&AttributePath::new("data")).unwrap();
+        assert_eq!(data.len(), 3);
+        let Dynamic::Map(last) = &data[2] else {
+            panic!("sample is not an object");
+        };
+        assert_eq!(last["time"], Dynamic::Number(180.0));
+        assert_eq!(last["cpu"], Dynamic::Null);
+    }
+
+    #[test]
+    fn test_build_state_without_samples() {
+        let samples = points(r#"[{"time": 60}, {"time": 120}]"#);
+
+        let state = build_state(
+            config(),
+            "pve/100/hour/AVERAGE".to_string(),
+            RrdTimeframe::Hour,
+            RrdConsolidation::Average,
+            &samples,
+            QEMU_METRICS,
+        );
+
+        for name in ["cpu_average", "cpu_max", "memory_average", "memory_max"] {
+            assert_eq!(
+                state.get(&AttributePath::new(name)).unwrap(),
+                &Dynamic::Null,
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_vm_rrd() {
+        let mut server = Server::new_async().await;
+        let _rrd = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/100/rrddata")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("timeframe".into(), "hour".into()),
+                Matcher::UrlEncoded("cf".into(), "AVERAGE".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"time": 60, "cpu": 0.5, "mem": 1024, "maxmem": 2048}]}"#)
+            .create_async()
+            .await;
+
+        let response = QemuVmRrdDataSource {
+            provider_data: provider_data(&server),
+        }
+        .read(
+            Context::new(),
+            read_request("proxmox_qemu_vm_rrd", config()),
+        )
+        .await;
+
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/100/hour/AVERAGE"
+        );
+        assert_eq!(number(&state, "cpu_average"), Some(0.5));
+        assert_eq!(number(&state, "memory_max"), Some(0.5));
+    }
+}
@@ -0,0 +1,455 @@
+//! Storage data source implementations
+
+use crate::api::storage::StorageConfig;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::StringOneOfValidator;
+
+/// Content types a storage can be configured for
+const CONTENT_TYPES: &[&str] = &[
+    "images", "rootdir", "iso", "vztmpl", "backup", "snippets", "import",
+];
+
+fn string_list(values: Vec<String>) -> Dynamic {
+    Dynamic::List(values.into_iter().map(Dynamic::String).collect())
+}
+
+fn storage_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("storage".to_string(), AttributeType::String);
+    fields.insert("type".to_string(), AttributeType::String);
+    fields.insert(
+        "content".to_string(),
+        AttributeType::List(Box::new(AttributeType::String)),
+    );
+    fields.insert("shared".to_string(), AttributeType::Bool);
+    fields.insert("enabled".to_string(), AttributeType::Bool);
+    fields.insert(
+        "nodes".to_string(),
+        AttributeType::List(Box::new(AttributeType::String)),
+    );
+    fields.insert("path".to_string(), AttributeType::String);
+    AttributeType::Object(fields)
+}
+
+fn storage_to_dynamic(storage: &StorageConfig) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert(
+        "storage".to_string(),
+        Dynamic::String(storage.storage.clone()),
+    );
+    obj.insert(
+        "type".to_string(),
+        Dynamic::String(storage.storage_type.clone()),
+    );
+    obj.insert("content".to_string(), string_list(storage.content_types()));
+    obj.insert("shared".to_string(), Dynamic::Bool(storage.is_shared()));
+    obj.insert("enabled".to_string(), Dynamic::Bool(storage.is_enabled()));
+    obj.insert("nodes".to_string(), string_list(storage.node_list()));
+    obj.insert(
+        "path".to_string(),
+        storage
+            .path
+            .as_ref()
+            .map_or(Dynamic::Null, |p| Dynamic::String(p.clone())),
+    );
+    Dynamic::Map(obj)
+}
+
+fn validate_content_type(config: &DynamicValue) -> Option<Diagnostic> {
+    let content_type = config
+        .get_string(&AttributePath::new("content_type"))
+        .ok()?;
+    if CONTENT_TYPES.contains(&content_type.as_str()) {
+        return None;
+    }
+    Some(
+        Diagnostic::error(
+            "Invalid content_type",
+            format!(
+                "'{}' is not a storage content type. Expected one of: {}",
+                content_type,
+                CONTENT_TYPES.join(", ")
+            ),
+        )
+        .with_attribute(AttributePath::new("content_type")),
+    )
+}
+
+/// Lists the storages defined in the datacenter
+#[derive(Default)]
+pub struct StoragesDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl StoragesDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for StoragesDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_storages"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists the storages defined in the datacenter along with their content types and node restrictions")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("Only list storages available on this node")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("content_type", AttributeType::String)
+                    .description("Only list storages that accept this content type, e.g. images or iso")
+                    .optional()
+                    .validator(StringOneOfValidator::create(
+                        CONTENT_TYPES.iter().map(|c| c.to_string()).collect(),
+                    ))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("enabled_only", AttributeType::Bool)
+                    .description("Skip disabled storages. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "storages",
+                    AttributeType::List(Box::new(storage_object_type())),
+                )
+                .description("The matching storages")
+                .computed()
+                .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("names", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The names of the matching storages")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        ValidateDataSourceConfigResponse {
+            diagnostics: validate_content_type(&request.config).into_iter().collect(),
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        if let Some(diag) = validate_content_type(&request.config) {
+            return error_response(vec![diag]);
+        }
+
+        let node = request.config.get_string(&AttributePath::new("node")).ok();
+        let content_type = request
+            .config
+            .get_string(&AttributePath::new("content_type"))
+            .ok();
+        let enabled_only = request
+            .config
+            .get_bool(&AttributePath::new("enabled_only"))
+            .unwrap_or(false);
+
+        let storages = match provider_data.client.storage().list(None).await {
+            Ok(storages) => storages,
+            Err(e) => {
+                return error_response(vec![Diagnostic::error(
+                    "Failed to list storages",
+                    format!("API error: {}", e),
+                )]);
+            }
+        };
+
+        let mut matching: Vec<&StorageConfig> = storages
+            .iter()
+            .filter(|s| node.as_deref().map_or(true, |n| s.available_on(n)))
+            .filter(|s| content_type.as_deref().map_or(true, |c| s.supports(c)))
+            .filter(|s| !enabled_only || s.is_enabled())
+            .collect();
+        matching.sort_by(|a, b| a.storage.cmp(&b.storage));
+
+        let id = match &node {
+            Some(node) => format!("storages/{}", node),
+            None => "storages".to_string(),
+        };
+
+        let mut state = request.config;
+        let _ = state.set_string(&AttributePath::new("id"), id);
+        let _ = state.set_list(
+            &AttributePath::new("storages"),
+            matching.iter().map(|s| storage_to_dynamic(s)).collect(),
+        );
+        let _ = state.set_list(
+            &AttributePath::new("names"),
+            matching
+                .iter()
+                .map(|s| Dynamic::String(s.storage.clone()))
+                .collect(),
+        );
+
+        ReadDataSourceResponse {
+            state,
+            diagnostics: vec![],
+            deferred: None,
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for StoragesDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+/// Reads a single storage definition
+#[derive(Default)]
+pub struct StorageDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl StorageDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for StorageDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_storage"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Reads a storage definition, e.g. to check that it accepts VM disk images before apply")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("storage", AttributeType::String)
+                    .description("The storage identifier")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("type", AttributeType::String)
+                    .description("The storage type, e.g. dir, lvmthin, zfspool or rbd")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("content", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Content types the storage accepts")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("shared", AttributeType::Bool)
+                    .description("Whether the storage is shared between nodes")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("enabled", AttributeType::Bool)
+                    .description("Whether the storage is enabled")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nodes", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Nodes the storage is restricted to. Empty when available on all nodes")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("path", AttributeType::String)
+                    .description("The filesystem path for directory based storages")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        if let Ok(storage) = request.config.get_string(&AttributePath::new("storage")) {
+            if storage.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid storage",
+                    "The storage identifier must not be empty",
+                ));
+            }
+        }
+
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let name = match request.config.get_string(&AttributePath::new("storage")) {
+            Ok(name) => name,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing storage",
+                    "The 'storage' attribute is required",
+                )]);
+            }
+        };
+
+        let storage = match provider_data.client.storage().get(&name).await {
+            Ok(storage) => storage,
+            Err(e) => {
+                return error_response(vec![Diagnostic::error(
+                    "Failed to read storage",
+                    format!("Unable to read storage '{}': {}", name, e),
+                )]);
+            }
+        };
+
+        let mut state = request.config;
+        let _ = state.set_string(&AttributePath::new("id"), name);
+        let _ = state.set_string(&AttributePath::new("type"), storage.storage_type.clone());
+        let _ = state.set_list(
+            &AttributePath::new("content"),
+            storage
+                .content_types()
+                .into_iter()
+                .map(Dynamic::String)
+                .collect(),
+        );
+        let _ = state.set_bool(&AttributePath::new("shared"), storage.is_shared());
+        let _ = state.set_bool(&AttributePath::new("enabled"), storage.is_enabled());
+        let _ = state.set_list(
+            &AttributePath::new("nodes"),
+            storage
+                .node_list()
+                .into_iter()
+                .map(Dynamic::String)
+                .collect(),
+        );
+        let _ = match &storage.path {
+            Some(path) => state.set_string(&AttributePath::new("path"), path.clone()),
+            None => state.set_null(&AttributePath::new("path")),
+        };
+
+        ReadDataSourceResponse {
+            state,
+            diagnostics: vec![],
+            deferred: None,
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for StorageDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_storage_test.rs"]
+mod data_source_storage_test;
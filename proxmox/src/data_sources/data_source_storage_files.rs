@@ -0,0 +1,333 @@
+//! Storage content listing data source implementation
+
+use crate::api::nodes::StorageContent;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::StringOneOfValidator;
+
+/// File based content types that can be listed
+const FILE_CONTENT_TYPES: &[&str] = &["iso", "vztmpl", "snippets", "backup", "import"];
+
+const DEFAULT_CONTENT_TYPE: &str = "iso";
+
+/// Query built from the data source configuration
+#[derive(Debug)]
+struct FileQuery {
+    node: String,
+    storage: String,
+    content_type: String,
+    name_regex: Option<Regex>,
+}
+
+/// Parses the content type and name filter, which can be checked before the
+/// node and storage are known
+fn parse_filters(config: &DynamicValue) -> Result<(String, Option<Regex>), Diagnostic> {
+    let content_type = config
+        .get_string(&AttributePath::new("content_type"))
+        .unwrap_or_else(|_| DEFAULT_CONTENT_TYPE.to_string());
+    if !FILE_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(Diagnostic::error(
+            "Invalid content_type",
+            format!(
+                "'{}' is not a file content type. Expected one of: {}",
+                content_type,
+                FILE_CONTENT_TYPES.join(", ")
+            ),
+        )
+        .with_attribute(AttributePath::new("content_type")));
+    }
+
+    let name_regex = match config.get_string(&AttributePath::new("name_regex")) {
+        Ok(pattern) => Some(Regex::new(&pattern).map_err(|e| {
+            Diagnostic::error(
+                "Invalid name_regex",
+                format!("'{}' is not a valid regular expression: {}", pattern, e),
+            )
+        })?),
+        Err(_) => None,
+    };
+
+    Ok((content_type, name_regex))
+}
+
+impl FileQuery {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let required = |name: &str| {
+            config.get_string(&AttributePath::new(name)).map_err(|_| {
+                Diagnostic::error(
+                    format!("Missing {}", name),
+                    format!("The '{}' attribute is required", name),
+                )
+            })
+        };
+
+        let (content_type, name_regex) = parse_filters(config)?;
+
+        Ok(Self {
+            node: required("node")?,
+            storage: required("storage")?,
+            content_type,
+            name_regex,
+        })
+    }
+
+    fn matches(&self, file: &StorageContent) -> bool {
+        if file
+            .content
+            .as_deref()
+            .is_some_and(|content| content != self.content_type)
+        {
+            return false;
+        }
+        self.name_regex
+            .as_ref()
+            .map_or(true, |re| re.is_match(file.file_name()))
+    }
+}
+
+fn file_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("volid".to_string(), AttributeType::String);
+    fields.insert("name".to_string(), AttributeType::String);
+    fields.insert("format".to_string(), AttributeType::String);
+    fields.insert("size".to_string(), AttributeType::Number);
+    fields.insert("ctime".to_string(), AttributeType::Number);
+    AttributeType::Object(fields)
+}
+
+fn file_to_dynamic(file: &StorageContent) -> Dynamic {
+    let mut obj = HashMap::new();
+    obj.insert("volid".to_string(), Dynamic::String(file.volid.clone()));
+    obj.insert(
+        "name".to_string(),
+        Dynamic::String(file.file_name().to_string()),
+    );
+    obj.insert(
+        "format".to_string(),
+        file.format
+            .as_ref()
+            .map_or(Dynamic::Null, |f| Dynamic::String(f.clone())),
+    );
+    obj.insert(
+        "size".to_string(),
+        file.size
+            .map_or(Dynamic::Null, |s| Dynamic::Number(s as f64)),
+    );
+    obj.insert(
+        "ctime".to_string(),
+        file.ctime
+            .map_or(Dynamic::Null, |t| Dynamic::Number(t as f64)),
+    );
+    Dynamic::Map(obj)
+}
+
+/// Builds the data source state from the matching files, sorted by volid.
+/// The newest file by creation time is exposed as latest_volid.
+fn build_state(
+    mut state: DynamicValue,
+    query: &FileQuery,
+    mut files: Vec<&StorageContent>,
+) -> DynamicValue {
+    files.sort_by(|a, b| a.volid.cmp(&b.volid));
+    let latest = files
+        .iter()
+        .max_by_key(|f| (f.ctime.unwrap_or_default(), f.volid.as_str()))
+        .map(|f| f.volid.clone());
+
+    let _ = state.set_string(
+        &AttributePath::new("id"),
+        format!("{}/{}/{}", query.node, query.storage, query.content_type),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("files"),
+        files.iter().map(|f| file_to_dynamic(f)).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("volids"),
+        files
+            .iter()
+            .map(|f| Dynamic::String(f.volid.clone()))
+            .collect(),
+    );
+    let _ = match latest {
+        Some(volid) => state.set_string(&AttributePath::new("latest_volid"), volid),
+        None => state.set_null(&AttributePath::new("latest_volid")),
+    };
+    state
+}
+
+/// Lists files such as ISO images or container templates on a storage
+#[derive(Default)]
+pub struct StorageIsoFilesDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl StorageIsoFilesDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for StorageIsoFilesDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_storage_iso_files"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Lists files of one content type on a storage, e.g. to pick the newest ISO matching a pattern for a cdrom drive")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to query the storage on")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("storage", AttributeType::String)
+                    .description("The storage identifier")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("content_type", AttributeType::String)
+                    .description("The content type to list: iso, vztmpl, snippets, backup or import. Defaults to iso")
+                    .optional()
+                    .validator(StringOneOfValidator::create(
+                        FILE_CONTENT_TYPES.iter().map(|c| c.to_string()).collect(),
+                    ))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("name_regex", AttributeType::String)
+                    .description("Only list files whose name matches this regular expression")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("files", AttributeType::List(Box::new(file_object_type())))
+                    .description("The matching files, sorted by volid")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("volids", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The volume IDs of the matching files")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("latest_volid", AttributeType::String)
+                    .description("The volume ID of the most recently created matching file. Null when nothing matches")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+        if let Err(diag) = parse_filters(&request.config) {
+            diagnostics.push(diag);
+        }
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let query = match FileQuery::from_config(&request.config) {
+            Ok(query) => query,
+            Err(diag) => return error_response(vec![diag]),
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&query.node)
+            .storage(&query.storage)
+            .content(Some(&query.content_type))
+            .await
+        {
+            Ok(content) => {
+                let files = content.iter().filter(|f| query.matches(f)).collect();
+                ReadDataSourceResponse {
+                    state: build_state(request.config, &query, files),
+                    diagnostics: vec![],
+                    deferred: None,
+                }
+            }
+            Err(e) => error_response(vec![Diagnostic::error(
+                "Failed to list storage content",
+                format!(
+                    "Unable to list {} content of storage '{}' on node '{}': {}",
+                    query.content_type, query.storage, query.node, e
+                ),
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for StorageIsoFilesDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_storage_files_test.rs"]
+mod data_source_storage_files_test;
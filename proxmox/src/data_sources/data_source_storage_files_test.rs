@@ -0,0 +1,117 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request};
+    use mockito::{Matcher, Server};
+
+    const ISO_CONTENT: &str = r#"{
+        "data": [
+            {"volid": "local:iso/ubuntu-22.04.iso", "content": "iso", "format": "iso", "size": 1000, "ctime": 1650000000},
+            {"volid": "local:iso/debian-12.2.iso", "content": "iso", "format": "iso", "size": 2000, "ctime": 1700000000},
+            {"volid": "local:iso/debian-12.5.iso", "content": "iso", "format": "iso", "size": 3000, "ctime": 1710000000},
+            {"volid": "local:iso/debian-11.iso", "content": "iso", "format": "iso", "size": 4000, "ctime": 1720000000}
+        ]
+    }"#;
+
+    async fn read_with(server: &mut Server, config: DynamicValue) -> ReadDataSourceResponse {
+        let _m = server
+            .mock("GET", "/api2/json/nodes/pve/storage/local/content")
+            .match_query(Matcher::UrlEncoded("content".into(), "iso".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(ISO_CONTENT)
+            .create_async()
+            .await;
+
+        let data_source = StorageIsoFilesDataSource {
+            provider_data: provider_data(server),
+        };
+
+        data_source
+            .read(
+                Context::new(),
+                read_request("proxmox_storage_iso_files", config),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_latest_matching_iso() {
+        let mut server = Server::new_async().await;
+        let response = read_with(
+            &mut server,
+            config(vec![
+                ("node", Dynamic::String("pve".to_string())),
+                ("storage", Dynamic::String("local".to_string())),
+                ("name_regex", Dynamic::String(r"^debian-12\.".to_string())),
+            ]),
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_list(&AttributePath::new("volids")).unwrap(),
+            vec![
+                Dynamic::String("local:iso/debian-12.2.iso".to_string()),
+                Dynamic::String("local:iso/debian-12.5.iso".to_string()),
+            ]
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("latest_volid"))
+                .unwrap(),
+            "local:iso/debian-12.5.iso"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/local/iso"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_match_leaves_latest_null() {
+        let mut server = Server::new_async().await;
+        let response = read_with(
+            &mut server,
+            config(vec![
+                ("node", Dynamic::String("pve".to_string())),
+                ("storage", Dynamic::String("local".to_string())),
+                ("name_regex", Dynamic::String("^windows".to_string())),
+            ]),
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert!(response
+            .state
+            .get_list(&AttributePath::new("files"))
+            .unwrap()
+            .is_empty());
+        assert!(response
+            .state
+            .get_string(&AttributePath::new("latest_volid"))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_non_file_content() {
+        let data_source = StorageIsoFilesDataSource::new();
+        let response = data_source
+            .validate(
+                Context::new(),
+                ValidateDataSourceConfigRequest {
+                    type_name: "proxmox_storage_iso_files".to_string(),
+                    config: config(vec![(
+                        "content_type",
+                        Dynamic::String("images".to_string()),
+                    )]),
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid content_type");
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{config, provider_data, read_request, strings};
+    use mockito::{Matcher, Server};
+
+    const STORAGES: &str = r#"{
+        "data": [
+            {"storage": "local-lvm", "type": "lvmthin", "vgname": "pve", "thinpool": "data", "content": "images,rootdir"},
+            {"storage": "local", "type": "dir", "path": "/var/lib/vz", "content": "iso,vztmpl,backup"},
+            {"storage": "ceph", "type": "rbd", "pool": "vm", "content": "images", "shared": 1, "nodes": "pve2"},
+            {"storage": "old-nfs", "type": "nfs", "content": "images", "disable": 1}
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn test_storages_filters_by_node_content_and_enabled() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/storage")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(STORAGES)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let data_source = StoragesDataSource {
+            provider_data: provider_data(&server),
+        };
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_storages",
+                    config(vec![
+                        ("node", Dynamic::String("pve1".to_string())),
+                        ("content_type", Dynamic::String("images".to_string())),
+                    ]),
+                ),
+            )
+            .await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            strings(&response.state, "names"),
+            vec!["local-lvm", "old-nfs"]
+        );
+        assert_eq!(
+            response
+                .state
+                .get_string(&AttributePath::new("id"))
+                .unwrap(),
+            "storages/pve1"
+        );
+
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_storages",
+                    config(vec![
+                        ("content_type", Dynamic::String("images".to_string())),
+                        ("enabled_only", Dynamic::Bool(true)),
+                    ]),
+                ),
+            )
+            .await;
+        assert_eq!(strings(&response.state, "names"), vec!["ceph", "local-lvm"]);
+
+        let storages = response
+            .state
+            .get_list(&AttributePath::new("storages"))
+            .unwrap();
+        match &storages[0] {
+            Dynamic::Map(obj) => {
+                assert_eq!(obj.get("shared"), Some(&Dynamic::Bool(true)));
+                assert_eq!(
+                    obj.get("nodes"),
+                    Some(&Dynamic::List(vec![Dynamic::String("pve2".to_string())]))
+                );
+            }
+            other => panic!("expected storage object, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_storages_rejects_unknown_content_type() {
+        let data_source = StoragesDataSource::new();
+        let response = data_source
+            .validate(
+                Context::new(),
+                ValidateDataSourceConfigRequest {
+                    type_name: "proxmox_storages".to_string(),
+                    config: config(vec![("content_type", Dynamic::String("disks".to_string()))]),
+                },
+            )
+            .await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid content_type");
+    }
+
+    #[tokio::test]
+    async fn test_storage_reads_single_storage() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", "/api2/json/storage/local")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"storage": "local", "type": "dir", "path": "/var/lib/vz", "content": "iso,backup", "digest": "abc"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let data_source = StorageDataSource {
+            provider_data: provider_data(&server),
+        };
+        let response = data_source
+            .read(
+                Context::new(),
+                read_request(
+                    "proxmox_storage",
+                    config(vec![("storage", Dynamic::String("local".to_string()))]),
+                ),
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("type")).unwrap(),
+            "dir"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("path")).unwrap(),
+            "/var/lib/vz"
+        );
+        assert!(state.get_bool(&AttributePath::new("enabled")).unwrap());
+        assert!(!state.get_bool(&AttributePath::new("shared")).unwrap());
+        assert_eq!(
+            state.get_list(&AttributePath::new("content")).unwrap(),
+            vec![
+                Dynamic::String("iso".to_string()),
+                Dynamic::String("backup".to_string())
+            ]
+        );
+    }
+}
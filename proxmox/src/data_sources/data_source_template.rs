@@ -0,0 +1,247 @@
+//! Template lookup data source implementation
+
+use crate::api::cluster::ClusterResource;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic};
+
+/// Picks the single template matching the name and optional node
+fn find_template<'a>(
+    resources: &'a [ClusterResource],
+    name: &str,
+    node: Option<&str>,
+) -> Result<&'a ClusterResource, Diagnostic> {
+    let matches: Vec<&ClusterResource> = resources
+        .iter()
+        .filter(|r| r.is_qemu() && r.template == Some(true))
+        .filter(|r| r.name.as_deref() == Some(name))
+        .filter(|r| node.is_none() || r.node.as_deref() == node)
+        .collect();
+
+    let location = match node {
+        Some(node) => format!("on node '{}'", node),
+        None => "in the cluster".to_string(),
+    };
+
+    match matches.as_slice() {
+        [template] => Ok(template),
+        [] => Err(Diagnostic::error(
+            "Template not found",
+            format!("No template named '{}' exists {}", name, location),
+        )),
+        many => {
+            let found: Vec<String> = many
+                .iter()
+                .map(|t| {
+                    format!(
+                        "{} on {}",
+                        t.vmid.unwrap_or_default(),
+                        t.node.as_deref().unwrap_or("unknown")
+                    )
+                })
+                .collect();
+            Err(Diagnostic::error(
+                "Multiple templates found",
+                format!(
+                    "{} templates named '{}' exist {} ({}). Set 'node' to pick one",
+                    many.len(),
+                    name,
+                    location,
+                    found.join(", ")
+                ),
+            ))
+        }
+    }
+}
+
+/// Resolves a template VM by name to its VMID
+#[derive(Default)]
+pub struct TemplateDataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl TemplateDataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DataSource for TemplateDataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_template"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Looks up a QEMU template by name, e.g. to use its vmid as the clone source of a proxmox_qemu_vm")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .description("The name of the template")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node hosting the template. Searches the whole cluster when omitted")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The VMID of the template")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pool", AttributeType::String)
+                    .description("The pool the template belongs to")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("tags", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Tags set on the template")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        if let Ok(name) = request.config.get_string(&AttributePath::new("name")) {
+            if name.is_empty() {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid name",
+                    "The template name must not be empty",
+                ));
+            }
+        }
+
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let name = match request.config.get_string(&AttributePath::new("name")) {
+            Ok(name) => name,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing name",
+                    "The 'name' attribute is required",
+                )]);
+            }
+        };
+        let node = request.config.get_string(&AttributePath::new("node")).ok();
+
+        let resources = match provider_data.cluster_vms().await {
+            Ok(resources) => resources,
+            Err(e) => {
+                return error_response(vec![Diagnostic::error(
+                    "Failed to list VMs",
+                    format!("API error: {}", e),
+                )]);
+            }
+        };
+
+        let template = match find_template(&resources, &name, node.as_deref()) {
+            Ok(template) => template,
+            Err(diag) => {
+                return error_response(vec![diag]);
+            }
+        };
+
+        let mut state = request.config;
+        let vmid = template.vmid.unwrap_or_default();
+        let template_node = template.node.clone().unwrap_or_default();
+        let _ = state.set_string(
+            &AttributePath::new("id"),
+            format!("{}/{}", template_node, vmid),
+        );
+        let _ = state.set_string(&AttributePath::new("node"), template_node);
+        let _ = state.set_i64(&AttributePath::new("vmid"), vmid.into());
+        let _ = match &template.pool {
+            Some(pool) => state.set_string(&AttributePath::new("pool"), pool.clone()),
+            None => state.set_null(&AttributePath::new("pool")),
+        };
+        let _ = state.set_list(
+            &AttributePath::new("tags"),
+            template
+                .tag_list()
+                .into_iter()
+                .map(Dynamic::String)
+                .collect(),
+        );
+
+        ReadDataSourceResponse {
+            state,
+            diagnostics: vec![],
+            deferred: None,
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for TemplateDataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_template_test.rs"]
+mod data_source_template_test;
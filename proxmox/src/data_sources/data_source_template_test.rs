@@ -0,0 +1,42 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+
+    fn resources() -> Vec<ClusterResource> {
+        serde_json::from_str(
+            r#"[
+                {"id": "qemu/9000", "type": "qemu", "node": "pve1", "vmid": 9000, "name": "ubuntu-24.04", "template": 1, "tags": "linux"},
+                {"id": "qemu/9001", "type": "qemu", "node": "pve2", "vmid": 9001, "name": "ubuntu-24.04", "template": 1},
+                {"id": "qemu/9002", "type": "qemu", "node": "pve1", "vmid": 9002, "name": "debian-12", "template": 1},
+                {"id": "qemu/100", "type": "qemu", "node": "pve1", "vmid": 100, "name": "debian-13", "template": 0}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_find_template_by_name() {
+        let resources = resources();
+        let template = find_template(&resources, "debian-12", None).unwrap();
+        assert_eq!(template.vmid, Some(9002));
+    }
+
+    #[test]
+    fn test_find_template_ignores_regular_vms() {
+        let resources = resources();
+        let err = find_template(&resources, "debian-13", None).unwrap_err();
+        assert_eq!(err.summary, "Template not found");
+    }
+
+    #[test]
+    fn test_find_template_ambiguous_name_needs_node() {
+        let resources = resources();
+        let err = find_template(&resources, "ubuntu-24.04", None).unwrap_err();
+        assert_eq!(err.summary, "Multiple templates found");
+        assert!(err.detail.contains("9000 on pve1"));
+
+        let template = find_template(&resources, "ubuntu-24.04", Some("pve2")).unwrap();
+        assert_eq!(template.vmid, Some(9001));
+    }
+}
@@ -1,5 +1,6 @@
 //! Version data source implementation
 
+use crate::data_sources::common::{configure_provider_data, error_response};
 use async_trait::async_trait;
 use tfplug::context::Context;
 use tfplug::data_source::{
@@ -103,11 +104,7 @@ impl DataSource for VersionDataSource {
                     "Provider not configured",
                     "Provider data was not properly configured",
                 ));
-                return ReadDataSourceResponse {
-                    state: DynamicValue::null(),
-                    diagnostics,
-                    deferred: None,
-                };
+                return error_response(diagnostics);
             }
         };
 
@@ -130,11 +127,7 @@ impl DataSource for VersionDataSource {
                     "Failed to get version information",
                     format!("API error: {}", e),
                 ));
-                ReadDataSourceResponse {
-                    state: DynamicValue::null(),
-                    diagnostics,
-                    deferred: None,
-                }
+                error_response(diagnostics)
             }
         }
     }
@@ -147,34 +140,6 @@ impl DataSourceWithConfigure for VersionDataSource {
         _ctx: Context,
         request: ConfigureDataSourceRequest,
     ) -> ConfigureDataSourceResponse {
-        let mut diagnostics = vec![];
-
-        tracing::debug!(
-            "Configuring version data source, provider_data provided: {:?}",
-            request.provider_data.is_some()
-        );
-
-        if let Some(data) = request.provider_data {
-            tracing::debug!("Attempting to downcast provider data");
-            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
-                self.provider_data = Some(provider_data.clone());
-                tracing::debug!("Successfully configured version data source with provider data");
-            } else {
-                tracing::error!("Failed to downcast provider data to ProxmoxProviderData");
-                tracing::error!("Provider data type id: {:?}", data.type_id());
-                diagnostics.push(Diagnostic::error(
-                    "Invalid provider data",
-                    "Failed to extract ProxmoxProviderData from provider data",
-                ));
-            }
-        } else {
-            tracing::warn!("No provider data provided to version data source");
-            diagnostics.push(Diagnostic::error(
-                "No provider data",
-                "No provider data was provided to the data source",
-            ));
-        }
-
-        ConfigureDataSourceResponse { diagnostics }
+        configure_provider_data(request, &mut self.provider_data)
     }
 }
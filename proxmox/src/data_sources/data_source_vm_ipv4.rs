@@ -0,0 +1,362 @@
+//! Guest network interfaces data source implementation
+
+use crate::api::nodes::GuestNetworkInterface;
+use crate::data_sources::common::{configure_provider_data, error_response};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_RETRY_INTERVAL_SECS: u64 = 5;
+
+/// Whether an IPv4 address is usable to reach the guest, i.e. neither
+/// loopback nor link-local
+fn is_routable_ipv4(address: &str) -> bool {
+    match address.parse::<std::net::Ipv4Addr>() {
+        Ok(ip) => !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified(),
+        Err(_) => false,
+    }
+}
+
+/// Routable IPv4 addresses across all non-loopback interfaces, in the order
+/// the guest agent reports them
+fn routable_ipv4_addresses(interfaces: &[GuestNetworkInterface]) -> Vec<String> {
+    interfaces
+        .iter()
+        .filter(|i| !i.is_loopback())
+        .flat_map(|i| i.addresses("ipv4"))
+        .filter(|a| is_routable_ipv4(a))
+        .collect()
+}
+
+fn interface_object_type() -> AttributeType {
+    let mut fields = HashMap::new();
+    fields.insert("name".to_string(), AttributeType::String);
+    fields.insert("mac_address".to_string(), AttributeType::String);
+    fields.insert(
+        "ipv4_addresses".to_string(),
+        AttributeType::List(Box::new(AttributeType::String)),
+    );
+    fields.insert(
+        "ipv6_addresses".to_string(),
+        AttributeType::List(Box::new(AttributeType::String)),
+    );
+    AttributeType::Object(fields)
+}
+
+fn interface_to_dynamic(interface: &GuestNetworkInterface) -> Dynamic {
+    let string_list =
+        |values: Vec<String>| Dynamic::List(values.into_iter().map(Dynamic::String).collect());
+
+    let mut obj = HashMap::new();
+    obj.insert("name".to_string(), Dynamic::String(interface.name.clone()));
+    obj.insert(
+        "mac_address".to_string(),
+        interface
+            .hardware_address
+            .as_ref()
+            .map_or(Dynamic::Null, |mac| Dynamic::String(mac.clone())),
+    );
+    obj.insert(
+        "ipv4_addresses".to_string(),
+        string_list(interface.addresses("ipv4")),
+    );
+    obj.insert(
+        "ipv6_addresses".to_string(),
+        string_list(interface.addresses("ipv6")),
+    );
+    Dynamic::Map(obj)
+}
+
+fn build_state(
+    mut state: DynamicValue,
+    node: &str,
+    vmid: u32,
+    interfaces: &[GuestNetworkInterface],
+) -> DynamicValue {
+    let addresses = routable_ipv4_addresses(interfaces);
+
+    let _ = state.set_string(&AttributePath::new("id"), format!("{}/{}", node, vmid));
+    let _ = match addresses.first() {
+        Some(address) => state.set_string(&AttributePath::new("ipv4_address"), address.clone()),
+        None => state.set_null(&AttributePath::new("ipv4_address")),
+    };
+    let _ = state.set_list(
+        &AttributePath::new("ipv4_addresses"),
+        addresses.into_iter().map(Dynamic::String).collect(),
+    );
+    let _ = state.set_list(
+        &AttributePath::new("interfaces"),
+        interfaces.iter().map(interface_to_dynamic).collect(),
+    );
+    state
+}
+
+fn duration_setting(config: &DynamicValue, name: &str, default: u64) -> u64 {
+    config.get_u64(&AttributePath::new(name)).unwrap_or(default)
+}
+
+/// Reads a running VM's network interfaces through the QEMU guest agent
+#[derive(Default)]
+pub struct VmIpv4DataSource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl VmIpv4DataSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls the guest agent until it reports a routable IPv4 address or the
+    /// timeout passes. Returns the last interface listing when the agent
+    /// answered but no address showed up in time.
+    async fn poll_interfaces(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        node: &str,
+        vmid: u32,
+        timeout: Duration,
+        retry_interval: Duration,
+    ) -> Result<Vec<GuestNetworkInterface>, Diagnostic> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let qemu = provider_data.client.nodes().node(node).qemu();
+        let mut last_interfaces = None;
+        let mut last_error = None;
+
+        loop {
+            match qemu.agent_network_interfaces(vmid).await {
+                Ok(interfaces) if !routable_ipv4_addresses(&interfaces).is_empty() => {
+                    return Ok(interfaces)
+                }
+                Ok(interfaces) => last_interfaces = Some(interfaces),
+                Err(e) => last_error = Some(e),
+            }
+
+            if tokio::time::Instant::now() + retry_interval > deadline {
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(retry_interval) => {}
+                _ = ctx.cancelled() => {
+                    return Err(Diagnostic::error(
+                        "Guest agent not available",
+                        format!(
+                            "Terraform was interrupted before the guest agent of VM {} on node '{}' reported an IPv4 address",
+                            vmid, node
+                        ),
+                    ));
+                }
+            }
+        }
+
+        match (last_interfaces, last_error) {
+            (Some(interfaces), _) => Ok(interfaces),
+            (None, error) => Err(Diagnostic::error(
+                "Guest agent not available",
+                format!(
+                    "Could not query the guest agent of VM {} on node '{}' within {} seconds. Make sure the VM is running with the QEMU guest agent installed and enabled: {}",
+                    vmid,
+                    node,
+                    timeout.as_secs(),
+                    error.map_or_else(|| "no response".to_string(), |e| e.to_string())
+                ),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSource for VmIpv4DataSource {
+    fn type_name(&self) -> &str {
+        "proxmox_vm_ipv4"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        DataSourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Reads the network interfaces and IPv4 addresses of a running VM from the QEMU guest agent. Works for VMs not managed by Terraform")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The data source ID")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node the VM runs on")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The VM ID")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("timeout", AttributeType::Number)
+                    .description("Seconds to wait for the guest agent to report an IPv4 address. Defaults to 120")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("retry_interval", AttributeType::Number)
+                    .description("Seconds between guest agent queries. Defaults to 5")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ipv4_address", AttributeType::String)
+                    .description("The first routable IPv4 address. Null if none was reported before the timeout")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ipv4_addresses", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("All routable IPv4 addresses, excluding loopback and link-local")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("interfaces", AttributeType::List(Box::new(interface_object_type())))
+                    .description("The network interfaces reported by the guest agent")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        DataSourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        let mut diagnostics =
+            validate_integers(&request.config, &["vmid", "timeout", "retry_interval"]);
+
+        if let Ok(timeout) = request.config.get_number(&AttributePath::new("timeout")) {
+            if timeout < 0.0 {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid timeout",
+                    "timeout must not be negative",
+                ));
+            }
+        }
+
+        if let Ok(interval) = request
+            .config
+            .get_number(&AttributePath::new("retry_interval"))
+        {
+            if interval < 1.0 {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid retry_interval",
+                    "retry_interval must be at least 1 second",
+                ));
+            }
+        }
+
+        ValidateDataSourceConfigResponse { diagnostics }
+    }
+
+    async fn read(&self, ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return error_response(vec![Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                )]);
+            }
+        };
+
+        let node = match request.config.get_string(&AttributePath::new("node")) {
+            Ok(node) => node,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing node",
+                    "The 'node' attribute is required",
+                )]);
+            }
+        };
+
+        let vmid = match request.config.get_u32(&AttributePath::new("vmid")) {
+            Ok(vmid) => vmid,
+            Err(_) => {
+                return error_response(vec![Diagnostic::error(
+                    "Missing vmid",
+                    "The 'vmid' attribute is required",
+                )]);
+            }
+        };
+
+        let timeout = duration_setting(&request.config, "timeout", DEFAULT_TIMEOUT_SECS);
+        let retry_interval = duration_setting(
+            &request.config,
+            "retry_interval",
+            DEFAULT_RETRY_INTERVAL_SECS,
+        )
+        .max(1);
+
+        match Self::poll_interfaces(
+            &ctx,
+            provider_data,
+            &node,
+            vmid,
+            Duration::from_secs(timeout),
+            Duration::from_secs(retry_interval),
+        )
+        .await
+        {
+            Ok(interfaces) => ReadDataSourceResponse {
+                state: build_state(request.config, &node, vmid, &interfaces),
+                diagnostics: vec![],
+                deferred: None,
+            },
+            Err(diag) => error_response(vec![diag]),
+        }
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for VmIpv4DataSource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        configure_provider_data(request, &mut self.provider_data)
+    }
+}
+
+#[cfg(test)]
+#[path = "./data_source_vm_ipv4_test.rs"]
+mod data_source_vm_ipv4_test;
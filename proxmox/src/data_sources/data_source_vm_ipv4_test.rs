@@ -0,0 +1,130 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::data_sources::test_helpers::{provider_data, read_request};
+    use mockito::Server;
+
+    const AGENT_PATH: &str = "/api2/json/nodes/pve/qemu/100/agent/network-get-interfaces";
+
+    fn config(timeout: f64) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+        obj.insert("timeout".to_string(), Dynamic::Number(timeout));
+        obj.insert("retry_interval".to_string(), Dynamic::Number(1.0));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    async fn read(server: &Server, config: DynamicValue) -> ReadDataSourceResponse {
+        let data_source = VmIpv4DataSource {
+            provider_data: provider_data(server),
+        };
+
+        data_source
+            .read(Context::new(), read_request("proxmox_vm_ipv4", config))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_reads_routable_addresses() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", AGENT_PATH)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"result": [
+                    {"name": "lo", "ip-addresses": [{"ip-address": "127.0.0.1", "ip-address-type": "ipv4", "prefix": 8}]},
+                    {"name": "eth0", "hardware-address": "bc:24:11:aa:bb:cc", "ip-addresses": [
+                        {"ip-address": "169.254.10.1", "ip-address-type": "ipv4", "prefix": 16},
+                        {"ip-address": "10.0.0.50", "ip-address-type": "ipv4", "prefix": 24},
+                        {"ip-address": "fe80::1", "ip-address-type": "ipv6", "prefix": 64}
+                    ]},
+                    {"name": "eth1", "ip-addresses": [{"ip-address": "192.168.1.5", "ip-address-type": "ipv4", "prefix": 24}]}
+                ]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let response = read(&server, config(0.0)).await;
+        assert!(response.diagnostics.is_empty());
+
+        let state = response.state;
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("ipv4_address"))
+                .unwrap(),
+            "10.0.0.50"
+        );
+        assert_eq!(
+            state
+                .get_list(&AttributePath::new("ipv4_addresses"))
+                .unwrap(),
+            vec![
+                Dynamic::String("10.0.0.50".to_string()),
+                Dynamic::String("192.168.1.5".to_string())
+            ]
+        );
+        assert_eq!(
+            state
+                .get_list(&AttributePath::new("interfaces"))
+                .unwrap()
+                .len(),
+            3
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("id")).unwrap(),
+            "pve/100"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_address_appears() {
+        let mut server = Server::new_async().await;
+        let pending = server
+            .mock("GET", AGENT_PATH)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"result": [{"name": "eth0", "ip-addresses": []}]}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _ready = server
+            .mock("GET", AGENT_PATH)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"result": [{"name": "eth0", "ip-addresses": [{"ip-address": "10.0.0.51", "ip-address-type": "ipv4"}]}]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let response = read(&server, config(5.0)).await;
+        pending.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            response
+                .state
+                .get_string(&AttributePath::new("ipv4_address"))
+                .unwrap(),
+            "10.0.0.51"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_not_running() {
+        let mut server = Server::new_async().await;
+        let _m = server
+            .mock("GET", AGENT_PATH)
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null, "message": "QEMU guest agent is not running"}"#)
+            .create_async()
+            .await;
+
+        let response = read(&server, config(0.0)).await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Guest agent not available");
+    }
+}
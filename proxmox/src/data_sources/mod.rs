@@ -1,3 +1,24 @@
 //! Data source implementations
 
+pub(crate) mod common;
+pub mod data_source_access;
+pub mod data_source_backups;
+pub mod data_source_cluster_status;
+pub mod data_source_ha_status;
+pub mod data_source_next_vmid;
+pub mod data_source_node_apt_updates;
+pub mod data_source_node_disks;
+pub mod data_source_node_hardware;
+pub mod data_source_node_selector;
+pub mod data_source_node_system;
+pub mod data_source_pool;
+pub mod data_source_qemu_vms;
+pub mod data_source_rrd;
+pub mod data_source_storage;
+pub mod data_source_storage_files;
+pub mod data_source_template;
 pub mod data_source_version;
+pub mod data_source_vm_ipv4;
+
+#[cfg(test)]
+mod test_helpers;
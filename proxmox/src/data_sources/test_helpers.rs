@@ -0,0 +1,47 @@
+//! Test helpers for the data sources
+
+use mockito::Server;
+use std::collections::HashMap;
+use tfplug::data_source::ReadDataSourceRequest;
+use tfplug::types::{AttributePath, ClientCapabilities, Dynamic, DynamicValue};
+
+/// A config object with the given attributes
+pub fn config(entries: Vec<(&str, Dynamic)>) -> DynamicValue {
+    let obj = entries
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect::<HashMap<_, _>>();
+    DynamicValue::new(Dynamic::Map(obj))
+}
+
+/// Provider data whose client talks to the mock server
+pub fn provider_data(server: &Server) -> Option<crate::ProxmoxProviderData> {
+    Some(crate::api::test_helpers::provider_data(&server.url()))
+}
+
+/// A read request for `type_name` with `config`
+pub fn read_request(type_name: &str, config: DynamicValue) -> ReadDataSourceRequest {
+    ReadDataSourceRequest {
+        type_name: type_name.to_string(),
+        config,
+        provider_meta: None,
+        client_capabilities: ClientCapabilities {
+            deferral_allowed: false,
+            write_only_attributes_allowed: false,
+        },
+    }
+}
+
+/// The string elements of the list attribute `name`
+#[allow(clippy::disallowed_methods)]
+pub fn strings(state: &DynamicValue, name: &str) -> Vec<String> {
+    state
+        .get_list(&AttributePath::new(name))
+        .unwrap()
+        .into_iter()
+        .filter_map(|v| match v {
+            Dynamic::String(s) => Some(s),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,257 @@
+//! Ticket ephemeral resource implementation
+
+use crate::api::access::ticket::{CreateTicketRequest, TICKET_LIFETIME_SECS};
+use async_trait::async_trait;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tfplug::context::Context;
+use tfplug::ephemeral::{
+    CloseEphemeralResourceRequest, CloseEphemeralResourceResponse,
+    ConfigureEphemeralResourceRequest, ConfigureEphemeralResourceResponse, EphemeralResource,
+    EphemeralResourceMetadataRequest, EphemeralResourceMetadataResponse,
+    EphemeralResourceSchemaRequest, EphemeralResourceSchemaResponse,
+    EphemeralResourceWithConfigure, OpenEphemeralResourceRequest, OpenEphemeralResourceResponse,
+    RenewEphemeralResourceRequest, RenewEphemeralResourceResponse,
+    ValidateEphemeralResourceConfigRequest, ValidateEphemeralResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::StringRegexValidator;
+
+/// Mints a short-lived authentication ticket for a user. The ticket is only
+/// handed to the configuration that asked for it and never written to state.
+#[derive(Default)]
+pub struct TicketEphemeralResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl TicketEphemeralResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn open_error(diagnostic: Diagnostic) -> OpenEphemeralResourceResponse {
+    OpenEphemeralResourceResponse {
+        diagnostics: vec![diagnostic],
+        renew_at: None,
+        result: DynamicValue::null(),
+        private: None,
+        deferred: None,
+    }
+}
+
+#[async_trait]
+impl EphemeralResource for TicketEphemeralResource {
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: EphemeralResourceMetadataRequest,
+    ) -> EphemeralResourceMetadataResponse {
+        EphemeralResourceMetadataResponse {
+            type_name: "proxmox_ticket".to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: EphemeralResourceSchemaRequest,
+    ) -> EphemeralResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Issues a Proxmox VE authentication ticket for a user. Tickets are valid for two hours and cannot be revoked, so they simply expire.")
+            .attribute(
+                AttributeBuilder::new("username", AttributeType::String)
+                    .description("User ID in the form name@realm, e.g. terraform@pve")
+                    .required()
+                    .validators(vec![StringRegexValidator::create(
+                        r"[^@\s]+@[^@\s]+",
+                        "must be in the form name@realm",
+                    )])
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("password", AttributeType::String)
+                    .description("Password of the user")
+                    .required()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("otp", AttributeType::String)
+                    .description("One-time password for users with a second factor")
+                    .optional()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ticket", AttributeType::String)
+                    .description("Ticket to send as the PVEAuthCookie cookie")
+                    .computed()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("csrf_prevention_token", AttributeType::String)
+                    .description("Token to send as the CSRFPreventionToken header on writes")
+                    .computed()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("expires_at", AttributeType::Number)
+                    .description("Unix time after which the ticket is no longer accepted")
+                    .computed()
+                    .build(),
+            )
+            .build();
+
+        EphemeralResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        _request: ValidateEphemeralResourceConfigRequest,
+    ) -> ValidateEphemeralResourceConfigResponse {
+        ValidateEphemeralResourceConfigResponse {
+            diagnostics: vec![],
+        }
+    }
+
+    async fn open(
+        &self,
+        _ctx: Context,
+        request: OpenEphemeralResourceRequest,
+    ) -> OpenEphemeralResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return open_error(Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                ))
+            }
+        };
+
+        let config = &request.config;
+        let (username, password) = match (
+            config.get_string(&AttributePath::new("username")),
+            config.get_string(&AttributePath::new("password")),
+        ) {
+            (Ok(username), Ok(password)) => (username, password),
+            _ => {
+                return open_error(Diagnostic::error(
+                    "Missing credentials",
+                    "Both username and password are required to issue a ticket",
+                ))
+            }
+        };
+        let otp = config.get_string(&AttributePath::new("otp")).ok();
+
+        let issued_at = SystemTime::now();
+        let ticket = match provider_data
+            .client
+            .access()
+            .ticket()
+            .create(&CreateTicketRequest {
+                username: username.clone(),
+                password,
+                otp,
+            })
+            .await
+        {
+            Ok(ticket) => ticket,
+            Err(e) => {
+                return open_error(Diagnostic::error(
+                    "Failed to issue ticket",
+                    format!("Could not issue a ticket for {}: {}", username, e),
+                ))
+            }
+        };
+
+        let expires_at = issued_at + Duration::from_secs(TICKET_LIFETIME_SECS);
+        let mut result = config.clone();
+        let _ = result.set_string(&AttributePath::new("ticket"), ticket.ticket);
+        let _ = result.set_string(
+            &AttributePath::new("csrf_prevention_token"),
+            ticket.csrf_prevention_token,
+        );
+        let _ = result.set_number(
+            &AttributePath::new("expires_at"),
+            expires_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as f64,
+        );
+
+        OpenEphemeralResourceResponse {
+            diagnostics: vec![],
+            renew_at: None,
+            result,
+            private: None,
+            deferred: None,
+        }
+    }
+
+    async fn renew(
+        &self,
+        _ctx: Context,
+        _request: RenewEphemeralResourceRequest,
+    ) -> RenewEphemeralResourceResponse {
+        // Open never asks for renewal: a renewed ticket would be a new value
+        // that Terraform cannot pass on
+        RenewEphemeralResourceResponse {
+            diagnostics: vec![],
+            renew_at: None,
+            private: None,
+        }
+    }
+
+    async fn close(
+        &self,
+        _ctx: Context,
+        _request: CloseEphemeralResourceRequest,
+    ) -> CloseEphemeralResourceResponse {
+        // Tickets are stateless signatures that expire on their own
+        CloseEphemeralResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl EphemeralResourceWithConfigure for TicketEphemeralResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureEphemeralResourceRequest,
+    ) -> ConfigureEphemeralResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the ephemeral resource",
+            ));
+        }
+
+        ConfigureEphemeralResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./ephemeral_resource_ticket_test.rs"]
+mod ephemeral_resource_ticket_test;
@@ -0,0 +1,112 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::{ClientCapabilities, Dynamic};
+
+    fn config(username: &str, password: &str) -> DynamicValue {
+        DynamicValue::new(Dynamic::Map(HashMap::from([
+            (
+                "username".to_string(),
+                Dynamic::String(username.to_string()),
+            ),
+            (
+                "password".to_string(),
+                Dynamic::String(password.to_string()),
+            ),
+            ("otp".to_string(), Dynamic::Null),
+            ("ticket".to_string(), Dynamic::Null),
+            ("csrf_prevention_token".to_string(), Dynamic::Null),
+            ("expires_at".to_string(), Dynamic::Null),
+        ])))
+    }
+
+    async fn open(server: &Server, config: DynamicValue) -> OpenEphemeralResourceResponse {
+        let resource = TicketEphemeralResource {
+            provider_data: Some(provider_data(&server.url())),
+        };
+
+        resource
+            .open(
+                Context::new(),
+                OpenEphemeralResourceRequest {
+                    type_name: "proxmox_ticket".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_open_issues_ticket() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api2/json/access/ticket")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("username".into(), "ci@pve".into()),
+                Matcher::UrlEncoded("password".into(), "secret".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"username": "ci@pve", "ticket": "PVE:ci@pve:65000000::sig", "CSRFPreventionToken": "65000000:token"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let before = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let response = open(&server, config("ci@pve", "secret")).await;
+
+        mock.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        assert!(response.renew_at.is_none());
+        assert!(response.private.is_none());
+        let result = response.result;
+        assert_eq!(
+            result.get_string(&AttributePath::new("ticket")).unwrap(),
+            "PVE:ci@pve:65000000::sig"
+        );
+        assert_eq!(
+            result
+                .get_string(&AttributePath::new("csrf_prevention_token"))
+                .unwrap(),
+            "65000000:token"
+        );
+        assert_eq!(
+            result.get_string(&AttributePath::new("username")).unwrap(),
+            "ci@pve"
+        );
+        let expires_at = result
+            .get_number(&AttributePath::new("expires_at"))
+            .unwrap() as u64;
+        assert!(expires_at >= before + TICKET_LIFETIME_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_open_reports_rejected_credentials() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/api2/json/access/ticket")
+            .with_status(401)
+            .with_body("authentication failure")
+            .create_async()
+            .await;
+
+        let response = open(&server, config("ci@pve", "wrong")).await;
+
+        assert!(response.result.is_null());
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Failed to issue ticket");
+        assert!(response.diagnostics[0].detail.contains("ci@pve"));
+    }
+}
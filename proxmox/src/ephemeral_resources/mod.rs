@@ -0,0 +1,3 @@
+//! Ephemeral resource implementations
+
+pub mod ephemeral_resource_ticket;
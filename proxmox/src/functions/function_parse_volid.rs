@@ -0,0 +1,115 @@
+//! parse_volid function implementation
+
+use crate::api::nodes::parse_volid;
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use tfplug::context::Context;
+use tfplug::function::{
+    CallFunctionRequest, CallFunctionResponse, Function, FunctionBuilder,
+    FunctionDefinitionRequest, FunctionDefinitionResponse, FunctionMetadataRequest,
+    FunctionMetadataResponse, Parameter,
+};
+use tfplug::schema::AttributeType;
+use tfplug::types::{Dynamic, DynamicValue};
+
+/// Owner VMID in volume names: "vm-100-disk-0", "base-100-disk-0",
+/// "subvol-100-disk-0", "100/vm-100-disk-0.qcow2" and
+/// "backup/vzdump-qemu-100-2024_01_01-00_00_00.vma.zst"
+static VMID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(?:vm|base|subvol)-(\d+)-|(\d+)/|.*vzdump-(?:qemu|lxc|openvz)-(\d+)-)")
+        .expect("valid vmid pattern")
+});
+
+/// Splits a volume ID into storage, volume name and owning VMID
+#[derive(Default)]
+pub struct ParseVolidFunction;
+
+impl ParseVolidFunction {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn owner_vmid(volume: &str) -> Option<u32> {
+    VMID.captures(volume)?
+        .iter()
+        .skip(1)
+        .flatten()
+        .next()?
+        .as_str()
+        .parse()
+        .ok()
+}
+
+#[async_trait]
+impl Function for ParseVolidFunction {
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: FunctionMetadataRequest,
+    ) -> FunctionMetadataResponse {
+        FunctionMetadataResponse {
+            name: "parse_volid".to_string(),
+        }
+    }
+
+    async fn definition(
+        &self,
+        _ctx: Context,
+        _request: FunctionDefinitionRequest,
+    ) -> FunctionDefinitionResponse {
+        let result = HashMap::from([
+            ("storage".to_string(), AttributeType::String),
+            ("volume".to_string(), AttributeType::String),
+            ("vmid".to_string(), AttributeType::Number),
+        ]);
+        FunctionDefinitionResponse {
+            definition: FunctionBuilder::new(AttributeType::Object(result))
+                .summary("Split a Proxmox volume ID")
+                .description(
+                    "Splits a volume ID such as `local-lvm:vm-100-disk-0` into its storage, volume name and the VMID owning the volume. `vmid` is null for volumes not owned by a guest, such as ISO images.",
+                )
+                .parameter(
+                    Parameter::new("volid", AttributeType::String)
+                        .description("Volume ID in the form <storage>:<volume>"),
+                )
+                .build(),
+            diagnostics: vec![],
+        }
+    }
+
+    async fn call(&self, _ctx: Context, request: CallFunctionRequest) -> CallFunctionResponse {
+        let volid = match request.arguments.first().map(|arg| &arg.value) {
+            Some(Dynamic::String(volid)) => volid,
+            _ => return CallFunctionResponse::error("volid must be a string", Some(0)),
+        };
+
+        let (storage, volume) = match parse_volid(volid) {
+            Some(parts) => parts,
+            None => {
+                return CallFunctionResponse::error(
+                    format!(
+                        "'{}' is not a valid volume ID, expected <storage>:<volume>",
+                        volid
+                    ),
+                    Some(0),
+                )
+            }
+        };
+
+        let vmid = owner_vmid(volume)
+            .map(|vmid| Dynamic::Number(vmid as f64))
+            .unwrap_or(Dynamic::Null);
+        CallFunctionResponse::result(DynamicValue::new(Dynamic::Map(HashMap::from([
+            ("storage".to_string(), Dynamic::String(storage.to_string())),
+            ("volume".to_string(), Dynamic::String(volume.to_string())),
+            ("vmid".to_string(), vmid),
+        ]))))
+    }
+}
+
+#[cfg(test)]
+#[path = "./function_parse_volid_test.rs"]
+mod function_parse_volid_test;
@@ -0,0 +1,63 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use tfplug::types::AttributePath;
+
+    async fn call(volid: &str) -> CallFunctionResponse {
+        ParseVolidFunction::new()
+            .call(
+                Context::new(),
+                CallFunctionRequest {
+                    name: "parse_volid".to_string(),
+                    arguments: vec![DynamicValue::new(Dynamic::String(volid.to_string()))],
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn parse_volid_splits_guest_volumes() {
+        let result = call("local-lvm:vm-100-disk-0").await.result.unwrap();
+        assert_eq!(
+            result.get_string(&AttributePath::new("storage")).unwrap(),
+            "local-lvm"
+        );
+        assert_eq!(
+            result.get_string(&AttributePath::new("volume")).unwrap(),
+            "vm-100-disk-0"
+        );
+        assert_eq!(
+            result.get_number(&AttributePath::new("vmid")).unwrap(),
+            100.0
+        );
+    }
+
+    #[test]
+    fn owner_vmid_handles_volume_layouts() {
+        assert_eq!(owner_vmid("base-9000-disk-0"), Some(9000));
+        assert_eq!(owner_vmid("subvol-101-disk-1"), Some(101));
+        assert_eq!(owner_vmid("102/vm-102-disk-0.qcow2"), Some(102));
+        assert_eq!(
+            owner_vmid("backup/vzdump-qemu-103-2024_01_01-00_00_00.vma.zst"),
+            Some(103)
+        );
+        assert_eq!(owner_vmid("iso/debian-12.iso"), None);
+    }
+
+    #[tokio::test]
+    async fn parse_volid_returns_null_vmid_for_unowned_volumes() {
+        let result = call("local:iso/debian-12.iso").await.result.unwrap();
+        let Dynamic::Map(values) = &result.value else {
+            panic!("result must be an object");
+        };
+        assert_eq!(values["vmid"], Dynamic::Null);
+    }
+
+    #[tokio::test]
+    async fn parse_volid_rejects_missing_storage() {
+        let error = call("vm-100-disk-0").await.error.unwrap();
+        assert_eq!(error.function_argument, Some(0));
+        assert!(error.text.contains("<storage>:<volume>"));
+    }
+}
@@ -0,0 +1,79 @@
+//! size_to_bytes function implementation
+
+use crate::resources::config_string::size_bytes;
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::function::{
+    CallFunctionRequest, CallFunctionResponse, Function, FunctionBuilder,
+    FunctionDefinitionRequest, FunctionDefinitionResponse, FunctionMetadataRequest,
+    FunctionMetadataResponse, Parameter,
+};
+use tfplug::schema::AttributeType;
+use tfplug::types::{Dynamic, DynamicValue};
+
+/// Converts a Proxmox size such as "32G" to a number of bytes
+#[derive(Default)]
+pub struct SizeToBytesFunction;
+
+impl SizeToBytesFunction {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Function for SizeToBytesFunction {
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: FunctionMetadataRequest,
+    ) -> FunctionMetadataResponse {
+        FunctionMetadataResponse {
+            name: "size_to_bytes".to_string(),
+        }
+    }
+
+    async fn definition(
+        &self,
+        _ctx: Context,
+        _request: FunctionDefinitionRequest,
+    ) -> FunctionDefinitionResponse {
+        FunctionDefinitionResponse {
+            definition: FunctionBuilder::new(AttributeType::Number)
+                .summary("Convert a Proxmox size to bytes")
+                .description(
+                    "Converts a size as used in disk and memory settings, e.g. `32G`, `512M` or `1.5T`, to a number of bytes. Values without a unit are bytes.",
+                )
+                .parameter(
+                    Parameter::new("size", AttributeType::String)
+                        .description("Size with an optional K, M, G or T unit"),
+                )
+                .build(),
+            diagnostics: vec![],
+        }
+    }
+
+    async fn call(&self, _ctx: Context, request: CallFunctionRequest) -> CallFunctionResponse {
+        let size = match request.arguments.first().map(|arg| &arg.value) {
+            Some(Dynamic::String(size)) => size,
+            _ => return CallFunctionResponse::error("size must be a string", Some(0)),
+        };
+
+        match size_bytes(size) {
+            Some(bytes) => {
+                CallFunctionResponse::result(DynamicValue::new(Dynamic::Number(bytes as f64)))
+            }
+            None => CallFunctionResponse::error(
+                format!(
+                    "'{}' is not a valid size, expected a number with an optional K, M, G or T unit",
+                    size
+                ),
+                Some(0),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./function_size_to_bytes_test.rs"]
+mod function_size_to_bytes_test;
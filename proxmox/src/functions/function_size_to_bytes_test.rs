@@ -0,0 +1,40 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+
+    async fn call(size: &str) -> CallFunctionResponse {
+        SizeToBytesFunction::new()
+            .call(
+                Context::new(),
+                CallFunctionRequest {
+                    name: "size_to_bytes".to_string(),
+                    arguments: vec![DynamicValue::new(Dynamic::String(size.to_string()))],
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn size_to_bytes_converts_units() {
+        for (size, bytes) in [
+            ("512", 512.0),
+            ("4K", 4096.0),
+            ("32G", 34359738368.0),
+            ("1.5T", 1649267441664.0),
+        ] {
+            let response = call(size).await;
+            assert!(response.error.is_none(), "{}", size);
+            assert_eq!(response.result.unwrap().value, Dynamic::Number(bytes));
+        }
+    }
+
+    #[tokio::test]
+    async fn size_to_bytes_rejects_invalid_sizes() {
+        let response = call("10X").await;
+        assert!(response.result.is_none());
+        let error = response.error.unwrap();
+        assert_eq!(error.function_argument, Some(0));
+        assert!(error.text.contains("'10X'"));
+    }
+}
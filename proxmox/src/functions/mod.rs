@@ -0,0 +1,4 @@
+//! Provider-defined functions
+
+pub mod function_parse_volid;
+pub mod function_size_to_bytes;
@@ -8,19 +8,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tfplug::context::Context;
 use tfplug::provider::{
-    ConfigureProviderRequest, ConfigureProviderResponse, DataSourceFactory, Provider,
-    ProviderMetaSchemaRequest, ProviderMetaSchemaResponse, ProviderMetadataRequest,
-    ProviderMetadataResponse, ProviderSchemaRequest, ProviderSchemaResponse, ResourceFactory,
-    StopProviderRequest, StopProviderResponse, ValidateProviderConfigRequest,
-    ValidateProviderConfigResponse,
+    ConfigureProviderRequest, ConfigureProviderResponse, DataSourceFactory,
+    EphemeralResourceFactory, FunctionFactory, Provider, ProviderMetaSchemaRequest,
+    ProviderMetaSchemaResponse, ProviderMetadataRequest, ProviderMetadataResponse,
+    ProviderSchemaRequest, ProviderSchemaResponse, ResourceFactory, StopProviderRequest,
+    StopProviderResponse, ValidateProviderConfigRequest, ValidateProviderConfigResponse,
 };
-use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
-use tfplug::types::{AttributePath, Diagnostic, ServerCapabilities};
+use tfplug::schema::{
+    AttributeBuilder, AttributeType, Block, NestedBlock, NestingMode, SchemaBuilder, StringKind,
+    Validator, ValidatorRequest,
+};
+use tfplug::timeouts::parse_duration;
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, ServerCapabilities};
+use tfplug::validator::{validate_integers, DurationValidator, IntegerValidator};
 
 pub mod api;
 pub mod data_sources;
+pub mod ephemeral_resources;
+pub mod functions;
 mod provider_data;
 pub mod resources;
+pub mod ssh;
 
 pub use provider_data::ProxmoxProviderData;
 
@@ -58,7 +66,7 @@ impl Provider for ProxmoxProvider {
             server_capabilities: ServerCapabilities {
                 plan_destroy: false,
                 get_provider_schema_optional: false,
-                move_resource_state: false,
+                move_resource_state: true,
             },
         }
     }
@@ -77,6 +85,12 @@ impl Provider for ProxmoxProvider {
                     .optional()
                     .build(),
             )
+            .attribute(
+                AttributeBuilder::new("endpoints", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("API endpoint URLs of further cluster nodes. Requests fail over to them in order when the node in use can't be connected to, since any node serves the cluster API")
+                    .optional()
+                    .build(),
+            )
             .attribute(
                 AttributeBuilder::new("api_token", AttributeType::String)
                     .description("API token for authentication (format: user@realm!tokenid=secret)")
@@ -84,12 +98,136 @@ impl Provider for ProxmoxProvider {
                     .sensitive()
                     .build(),
             )
+            .attribute(
+                AttributeBuilder::new("api_token_file", AttributeType::String)
+                    .description("Path of a file holding the API token, such as a mounted secret. Conflicts with api_token")
+                    .optional()
+                    .build(),
+            )
             .attribute(
                 AttributeBuilder::new("insecure", AttributeType::Bool)
                     .description("Skip TLS certificate verification")
                     .optional()
                     .build(),
             )
+            .attribute(
+                AttributeBuilder::new("max_concurrent_requests", AttributeType::Number)
+                    .description("Maximum number of API requests in flight at once, independent of Terraform's parallelism. Defaults to the PROXMOX_MAX_CONCURRENT_REQUESTS environment variable, unlimited when neither is set")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("proxy_url", AttributeType::String)
+                    .description("HTTP, HTTPS or SOCKS5 proxy used to reach the API (e.g. socks5h://bastion:1080). Without it the HTTPS_PROXY and NO_PROXY environment variables apply")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("default_node", AttributeType::String)
+                    .description("Node that resources are created on when they don't set target_node")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("default_storage", AttributeType::String)
+                    .description("Storage that disks are created on when they don't set storage")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("check_bridges", AttributeType::Bool)
+                    .description("Check while planning that the bridges of new or changed VM network interfaces exist on the target node. Bridges that only exist as SDN vnets are not listed by the node, so leave this off for VMs on SDN. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cluster_cache_ttl", AttributeType::String)
+                    .description("How long the cluster's VM and storage listings are reused for VM lookups, plan-time storage checks and the VM list data source, so refreshing or planning many VMs doesn't list the cluster for each one. \"0s\" turns the cache off. Defaults to 5s")
+                    .optional()
+                    .build(),
+            )
+            .block(NestedBlock {
+                type_name: "retry".to_string(),
+                block: Block {
+                    version: 0,
+                    attributes: vec![
+                        AttributeBuilder::new("max_retries", AttributeType::Number)
+                            .description("Retries of a request that failed with a timeout, a connection error or a 5xx response. Defaults to 3")
+                            .optional()
+                            .build(),
+                        AttributeBuilder::new("backoff", AttributeType::String)
+                            .description("Wait before the first retry, doubled on every further retry (e.g. 500ms). Defaults to 100ms")
+                            .optional()
+                            .build(),
+                        AttributeBuilder::new("max_backoff", AttributeType::String)
+                            .description("Longest wait between two retries. Defaults to 10s")
+                            .optional()
+                            .build(),
+                    ],
+                    block_types: vec![],
+                    description: "How failed API requests are retried".to_string(),
+                    description_kind: StringKind::Plain,
+                    deprecated: false,
+                },
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 1,
+            })
+            .block(NestedBlock {
+                type_name: "timeouts".to_string(),
+                block: Block {
+                    version: 0,
+                    attributes: vec![
+                        AttributeBuilder::new("connect", AttributeType::String)
+                            .description("Time to wait for a connection to the API (e.g. 5s). Defaults to 10s")
+                            .optional()
+                            .build(),
+                        AttributeBuilder::new("request", AttributeType::String)
+                            .description("Time to wait for a single API request to complete, including its response. Defaults to 30s")
+                            .optional()
+                            .build(),
+                    ],
+                    block_types: vec![],
+                    description: "Timeouts of API requests, so an unreachable node fails the apply instead of hanging it".to_string(),
+                    description_kind: StringKind::Plain,
+                    deprecated: false,
+                },
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 1,
+            })
+            .block(NestedBlock {
+                type_name: "ssh".to_string(),
+                block: Block {
+                    version: 0,
+                    attributes: vec![
+                        AttributeBuilder::new("host", AttributeType::String)
+                            .description("Host to connect to. Defaults to the host of the endpoint")
+                            .optional()
+                            .build(),
+                        AttributeBuilder::new("user", AttributeType::String)
+                            .description("User to log in as. Defaults to root")
+                            .optional()
+                            .build(),
+                        AttributeBuilder::new("private_key", AttributeType::String)
+                            .description("Private key contents. Defaults to the keys ssh finds on its own")
+                            .optional()
+                            .sensitive()
+                            .build(),
+                        AttributeBuilder::new("agent", AttributeType::Bool)
+                            .description("Offer keys from the running SSH agent")
+                            .optional()
+                            .build(),
+                    ],
+                    block_types: vec![],
+                    description: "Shell access to the nodes for operations that have no API, such as importing disks. Disabled unless this block is present".to_string(),
+                    description_kind: StringKind::Plain,
+                    deprecated: false,
+                },
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 1,
+            })
             .build();
 
         ProviderSchemaResponse {
@@ -103,8 +241,25 @@ impl Provider for ProxmoxProvider {
         _ctx: Context,
         _request: ProviderMetaSchemaRequest,
     ) -> ProviderMetaSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Module level settings, set in a provider_meta \"proxmox\" block of the terraform block")
+            .attribute(
+                AttributeBuilder::new("module_name", AttributeType::String)
+                    .description("Name appended to the User-Agent of API requests made for proxmox_qemu_vm resources of the module")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("default_node", AttributeType::String)
+                    .description("Node for proxmox_qemu_vm resources of the module that leave out target_node. Overrides the provider's default_node")
+                    .optional()
+                    .build(),
+            )
+            .build();
+
         ProviderMetaSchemaResponse {
-            schema: None,
+            schema: Some(schema),
             diagnostics: vec![],
         }
     }
@@ -122,11 +277,7 @@ impl Provider for ProxmoxProvider {
             .ok()
             .or_else(|| std::env::var("PROXMOX_ENDPOINT").ok());
 
-        let api_token = request
-            .config
-            .get_string(&AttributePath::new("api_token"))
-            .ok()
-            .or_else(|| std::env::var("PROXMOX_API_TOKEN").ok());
+        let api_token = api_token(&request.config);
 
         let insecure = request
             .config
@@ -138,12 +289,58 @@ impl Provider for ProxmoxProvider {
                     .unwrap_or(false)
             });
 
-        let endpoint = match endpoint {
-            Some(e) => e,
+        let max_concurrent_requests = request
+            .config
+            .get_u64(&AttributePath::new("max_concurrent_requests"))
+            .ok()
+            .or_else(|| {
+                std::env::var("PROXMOX_MAX_CONCURRENT_REQUESTS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+            })
+            .map(|n| n.max(1) as usize);
+
+        let proxy_url = request
+            .config
+            .get_string(&AttributePath::new("proxy_url"))
+            .ok()
+            .filter(|url| !url.is_empty());
+
+        let default_node = request
+            .config
+            .get_string(&AttributePath::new("default_node"))
+            .ok();
+        let default_storage = request
+            .config
+            .get_string(&AttributePath::new("default_storage"))
+            .ok();
+        let check_bridges = request
+            .config
+            .get_bool(&AttributePath::new("check_bridges"))
+            .unwrap_or(false);
+
+        // Failover endpoints follow the primary one
+        let endpoints: Vec<String> = endpoint
+            .into_iter()
+            .chain(
+                request
+                    .config
+                    .get_list(&AttributePath::new("endpoints"))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|endpoint| match endpoint {
+                        Dynamic::String(endpoint) => Some(endpoint),
+                        _ => None,
+                    }),
+            )
+            .collect();
+
+        let endpoint = match endpoints.first() {
+            Some(e) => e.clone(),
             None => {
                 diagnostics.push(Diagnostic::error(
                     "Missing endpoint",
-                    "The 'endpoint' configuration is required. Set it in the provider config or PROXMOX_ENDPOINT environment variable.",
+                    "The 'endpoint' configuration is required. Set it or 'endpoints' in the provider config, or the PROXMOX_ENDPOINT environment variable.",
                 ));
                 return ConfigureProviderResponse {
                     diagnostics,
@@ -153,22 +350,69 @@ impl Provider for ProxmoxProvider {
         };
 
         let api_token = match api_token {
-            Some(t) => t,
-            None => {
+            Ok(Some(t)) => t,
+            Ok(None) => {
                 diagnostics.push(Diagnostic::error(
                     "Missing API token",
-                    "The 'api_token' configuration is required. Set it in the provider config or PROXMOX_API_TOKEN environment variable.",
+                    "The 'api_token' configuration is required. Set it or 'api_token_file' in the provider config, or the PROXMOX_API_TOKEN or PROXMOX_API_TOKEN_FILE environment variable.",
                 ));
                 return ConfigureProviderResponse {
                     diagnostics,
                     provider_data: None,
                 };
             }
+            Err(diag) => {
+                diagnostics.push(diag);
+                return ConfigureProviderResponse {
+                    diagnostics,
+                    provider_data: None,
+                };
+            }
         };
 
-        match api::Client::new(&endpoint, &api_token, insecure) {
+        let (retry_config, connection_timeout) = client_settings(&request.config);
+        let pool_config = api::pool::ConnectionPoolConfig {
+            max_concurrent_requests,
+            proxy_url,
+            connection_timeout,
+            ..Default::default()
+        };
+
+        match api::Client::with_endpoints(
+            &endpoints,
+            &api_token,
+            insecure,
+            retry_config,
+            pool_config,
+        ) {
             Ok(client) => {
-                let provider_data = ProxmoxProviderData::new(client.clone());
+                let mut provider_data = ProxmoxProviderData::new(client.clone())
+                    .with_defaults(default_node, default_storage)
+                    .with_bridge_checks(check_bridges)
+                    .with_cluster_cache(cluster_cache_ttl(&request.config));
+                match ssh_config(&request.config, &endpoint) {
+                    Ok(Some(config)) => match ssh::SshClient::new(config) {
+                        Ok(ssh) => provider_data = provider_data.with_ssh(ssh),
+                        Err(e) => {
+                            diagnostics.push(Diagnostic::error(
+                                "Failed to set up SSH access",
+                                e.to_string(),
+                            ));
+                            return ConfigureProviderResponse {
+                                diagnostics,
+                                provider_data: None,
+                            };
+                        }
+                    },
+                    Ok(None) => {}
+                    Err(diag) => {
+                        diagnostics.push(diag);
+                        return ConfigureProviderResponse {
+                            diagnostics,
+                            provider_data: None,
+                        };
+                    }
+                }
                 self.client = Some(client);
                 ConfigureProviderResponse {
                     diagnostics,
@@ -204,6 +448,38 @@ impl Provider for ProxmoxProvider {
             }
         }
 
+        if request
+            .config
+            .get_string(&AttributePath::new("api_token"))
+            .is_ok()
+            && request
+                .config
+                .get_string(&AttributePath::new("api_token_file"))
+                .is_ok()
+        {
+            diagnostics.push(Diagnostic::attribute_error(
+                AttributePath::new("api_token_file"),
+                "Conflicting API token settings",
+                "Only one of api_token and api_token_file can be set",
+            ));
+        }
+
+        let endpoints = request
+            .config
+            .get_list(&AttributePath::new("endpoints"))
+            .unwrap_or_default();
+        for (index, endpoint) in endpoints.iter().enumerate() {
+            if let Dynamic::String(endpoint) = endpoint {
+                if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new("endpoints").index(index as i64),
+                        "Invalid endpoint",
+                        "The endpoint must start with http:// or https://",
+                    ));
+                }
+            }
+        }
+
         if let Ok(api_token) = request.config.get_string(&AttributePath::new("api_token")) {
             if !api_token.contains('!') || !api_token.contains('=') {
                 diagnostics.push(Diagnostic::warning(
@@ -213,6 +489,72 @@ impl Provider for ProxmoxProvider {
             }
         }
 
+        diagnostics.extend(validate_integers(
+            &request.config,
+            &["max_concurrent_requests"],
+        ));
+        if let Ok(limit) = request
+            .config
+            .get_number(&AttributePath::new("max_concurrent_requests"))
+        {
+            if limit < 1.0 {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid max_concurrent_requests",
+                        "max_concurrent_requests must be at least 1",
+                    )
+                    .with_attribute(AttributePath::new("max_concurrent_requests")),
+                );
+            }
+        }
+
+        if let Ok(proxy_url) = request.config.get_string(&AttributePath::new("proxy_url")) {
+            const SCHEMES: [&str; 4] = ["http://", "https://", "socks5://", "socks5h://"];
+            if !SCHEMES.iter().any(|scheme| proxy_url.starts_with(scheme)) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid proxy_url",
+                        "proxy_url must start with http://, https://, socks5:// or socks5h://",
+                    )
+                    .with_attribute(AttributePath::new("proxy_url")),
+                );
+            }
+        }
+
+        if let Ok(ttl) = request
+            .config
+            .get_string(&AttributePath::new("cluster_cache_ttl"))
+        {
+            if let Err(e) = parse_duration(&ttl) {
+                diagnostics.push(
+                    Diagnostic::error("Invalid cluster_cache_ttl", e)
+                        .with_attribute(AttributePath::new("cluster_cache_ttl")),
+                );
+            }
+        }
+
+        let retry = AttributePath::new("retry").index(0);
+        let timeouts = AttributePath::new("timeouts").index(0);
+        diagnostics.extend(validate_nested(
+            &request.config,
+            &retry,
+            "max_retries",
+            IntegerValidator::create(),
+        ));
+        for (block, name) in [
+            (&retry, "backoff"),
+            (&retry, "max_backoff"),
+            (&timeouts, "connect"),
+            (&timeouts, "request"),
+        ] {
+            diagnostics.extend(validate_nested(
+                &request.config,
+                block,
+                name,
+                DurationValidator::create(),
+            ));
+        }
+
         ValidateProviderConfigResponse { diagnostics }
     }
 
@@ -230,6 +572,22 @@ impl Provider for ProxmoxProvider {
             }) as ResourceFactory,
         );
 
+        resources.insert(
+            "proxmox_realm_sync".to_string(),
+            Box::new(|| {
+                Box::new(resources::RealmSyncResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_tfa_webauthn".to_string(),
+            Box::new(|| {
+                Box::new(resources::TfaWebauthnResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
         resources.insert(
             "proxmox_qemu_vm".to_string(),
             Box::new(|| {
@@ -237,6 +595,165 @@ impl Provider for ProxmoxProvider {
             }) as ResourceFactory,
         );
 
+        resources.insert(
+            "proxmox_vm_template".to_string(),
+            Box::new(|| {
+                Box::new(resources::VmTemplateResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_vm_power".to_string(),
+            Box::new(|| {
+                Box::new(resources::VmPowerResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_vm_agent_exec".to_string(),
+            Box::new(|| {
+                Box::new(resources::VmAgentExecResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_vm_qga_password".to_string(),
+            Box::new(|| {
+                Box::new(resources::VmQgaPasswordResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_vzdump_backup".to_string(),
+            Box::new(|| {
+                Box::new(resources::VzdumpBackupResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_lxc".to_string(),
+            Box::new(|| {
+                Box::new(resources::LxcResource::new()) as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_lxc_template".to_string(),
+            Box::new(|| {
+                Box::new(resources::LxcTemplateResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_dns".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeDnsResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_time".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeTimeResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_apt_repositories".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeAptRepositoriesResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_ceph_pool".to_string(),
+            Box::new(|| {
+                Box::new(resources::CephPoolResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_storage_pbs".to_string(),
+            Box::new(|| {
+                Box::new(resources::PbsStorageResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_disk_lvm".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeDiskStorageResource::lvm())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_disk_lvmthin".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeDiskStorageResource::lvm_thin())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_zfs_pool".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeDiskStorageResource::zfs_pool())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_node_disk_directory".to_string(),
+            Box::new(|| {
+                Box::new(resources::NodeDiskStorageResource::directory())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_mapping_dir".to_string(),
+            Box::new(|| {
+                Box::new(resources::MappingDirResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_ha_resource".to_string(),
+            Box::new(|| {
+                Box::new(resources::HaResourceResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_cluster".to_string(),
+            Box::new(|| {
+                Box::new(resources::ClusterResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
+        resources.insert(
+            "proxmox_cluster_join".to_string(),
+            Box::new(|| {
+                Box::new(resources::ClusterJoinResource::new())
+                    as Box<dyn tfplug::ResourceWithConfigure>
+            }) as ResourceFactory,
+        );
+
         resources
     }
 
@@ -251,6 +768,389 @@ impl Provider for ProxmoxProvider {
             }) as DataSourceFactory,
         );
 
+        data_sources.insert(
+            "proxmox_cluster_status".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_cluster_status::ClusterStatusDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_ha_status".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_ha_status::HaStatusDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_next_vmid".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_next_vmid::NextVmidDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_node_selector".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_node_selector::NodeSelectorDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_pool".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_pool::PoolDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_qemu_vms".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_qemu_vms::QemuVmsDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_node_rrd".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_rrd::NodeRrdDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_qemu_vm_rrd".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_rrd::QemuVmRrdDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_users".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_access::UsersDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_groups".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_access::GroupsDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_roles".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_access::RolesDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_realms".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_access::RealmsDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_node_apt_updates".to_string(),
+            Box::new(|| {
+                Box::new(
+                    data_sources::data_source_node_apt_updates::NodeAptUpdatesDataSource::new(),
+                ) as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_node_disks".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_node_disks::NodeDisksDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_node_pci_devices".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_node_hardware::NodePciDevicesDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_node_usb_devices".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_node_hardware::NodeUsbDevicesDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_dns".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_node_system::NodeDnsDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_time".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_node_system::NodeTimeDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_storages".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_storage::StoragesDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_storage".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_storage::StorageDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_storage_iso_files".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_storage_files::StorageIsoFilesDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_backups".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_backups::BackupsDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_template".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_template::TemplateDataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
+        data_sources.insert(
+            "proxmox_vm_ipv4".to_string(),
+            Box::new(|| {
+                Box::new(data_sources::data_source_vm_ipv4::VmIpv4DataSource::new())
+                    as Box<dyn tfplug::DataSourceWithConfigure>
+            }) as DataSourceFactory,
+        );
+
         data_sources
     }
+
+    fn functions(&self) -> HashMap<String, FunctionFactory> {
+        let mut functions = HashMap::new();
+
+        functions.insert(
+            "parse_volid".to_string(),
+            Box::new(|| {
+                Box::new(crate::functions::function_parse_volid::ParseVolidFunction::new())
+                    as Box<dyn tfplug::function::Function>
+            }) as FunctionFactory,
+        );
+
+        functions.insert(
+            "size_to_bytes".to_string(),
+            Box::new(|| {
+                Box::new(crate::functions::function_size_to_bytes::SizeToBytesFunction::new())
+                    as Box<dyn tfplug::function::Function>
+            }) as FunctionFactory,
+        );
+
+        functions
+    }
+
+    fn ephemeral_resources(&self) -> HashMap<String, EphemeralResourceFactory> {
+        let mut ephemeral_resources = HashMap::new();
+
+        ephemeral_resources.insert(
+            "proxmox_ticket".to_string(),
+            Box::new(|| {
+                Box::new(crate::ephemeral_resources::ephemeral_resource_ticket::TicketEphemeralResource::new())
+                    as Box<dyn tfplug::ephemeral::EphemeralResourceWithConfigure>
+            }) as EphemeralResourceFactory,
+        );
+
+        ephemeral_resources
+    }
+}
+
+/// The API token from the provider configuration, a token file or the
+/// environment, in that order. Tokens read from files are trimmed and must
+/// look like a token, so a file with the wrong contents fails early.
+fn api_token(config: &tfplug::types::DynamicValue) -> Result<Option<String>, Diagnostic> {
+    if let Ok(token) = config.get_string(&AttributePath::new("api_token")) {
+        return Ok(Some(token));
+    }
+    if let Ok(path) = config.get_string(&AttributePath::new("api_token_file")) {
+        return read_api_token_file(&path).map(Some);
+    }
+    if let Ok(token) = std::env::var("PROXMOX_API_TOKEN") {
+        return Ok(Some(token));
+    }
+    match std::env::var("PROXMOX_API_TOKEN_FILE") {
+        Ok(path) => read_api_token_file(&path).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+fn read_api_token_file(path: &str) -> Result<String, Diagnostic> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        Diagnostic::error(
+            "Failed to read API token file",
+            format!("Could not read '{}': {}", path, e),
+        )
+    })?;
+    let token = contents.trim();
+    if !token.contains('!') || !token.contains('=') {
+        return Err(Diagnostic::error(
+            "Invalid API token file",
+            format!(
+                "'{}' does not hold an API token in the format user@realm!tokenid=secret",
+                path
+            ),
+        ));
+    }
+    Ok(token.to_string())
+}
+
+/// Retry configuration and connect timeout of the API client, from the
+/// retry and timeouts blocks
+fn client_settings(
+    config: &tfplug::types::DynamicValue,
+) -> (api::RetryConfig, std::time::Duration) {
+    let defaults = api::RetryConfig::default();
+    let duration = |path: AttributePath| {
+        config
+            .get_string(&path)
+            .ok()
+            .and_then(|value| parse_duration(&value).ok())
+    };
+    let millis = |duration: std::time::Duration| duration.as_millis() as u64;
+
+    let retry = AttributePath::new("retry").index(0);
+    let timeouts = AttributePath::new("timeouts").index(0);
+    let retry_config = api::RetryConfig {
+        max_retries: config
+            .get_u32(&retry.clone().attribute("max_retries"))
+            .unwrap_or(defaults.max_retries),
+        initial_backoff_ms: duration(retry.clone().attribute("backoff"))
+            .map_or(defaults.initial_backoff_ms, millis),
+        max_backoff_ms: duration(retry.attribute("max_backoff"))
+            .map_or(defaults.max_backoff_ms, millis),
+        // The client counts its request timeout in whole seconds
+        timeout_seconds: duration(timeouts.clone().attribute("request"))
+            .map_or(defaults.timeout_seconds, |timeout| {
+                timeout.as_secs_f64().ceil().max(1.0) as u64
+            }),
+    };
+    let connection_timeout = duration(timeouts.attribute("connect"))
+        .unwrap_or(api::pool::ConnectionPoolConfig::default().connection_timeout);
+
+    (retry_config, connection_timeout)
+}
+
+/// Default unless `cluster_cache_ttl` says otherwise
+const DEFAULT_CLUSTER_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn cluster_cache_ttl(config: &tfplug::types::DynamicValue) -> std::time::Duration {
+    config
+        .get_string(&AttributePath::new("cluster_cache_ttl"))
+        .ok()
+        .and_then(|ttl| parse_duration(&ttl).ok())
+        .unwrap_or(DEFAULT_CLUSTER_CACHE_TTL)
+}
+
+/// Runs an attribute validator against an attribute of one of the
+/// provider's blocks. The provider schema isn't validated attribute by
+/// attribute, so `validate` calls the validators itself.
+fn validate_nested(
+    config: &tfplug::types::DynamicValue,
+    block: &AttributePath,
+    name: &str,
+    validator: Box<dyn Validator>,
+) -> Vec<Diagnostic> {
+    let path = block.clone().attribute(name);
+    let (Ok(value), Ok(parent)) = (config.get(&path), config.get(block)) else {
+        return vec![];
+    };
+    validator
+        .validate(ValidatorRequest {
+            config_value: tfplug::types::DynamicValue::new(value.clone()),
+            path,
+            parent: tfplug::types::DynamicValue::new(parent.clone()),
+        })
+        .diagnostics
+}
+
+/// Reads the `ssh` block. Returns `None` when shell access was not enabled.
+fn ssh_config(
+    config: &tfplug::types::DynamicValue,
+    endpoint: &str,
+) -> Result<Option<ssh::SshConfig>, Diagnostic> {
+    let has_block = config
+        .get_list(&AttributePath::new("ssh"))
+        .map(|list| !list.is_empty())
+        .unwrap_or(false);
+    if !has_block {
+        return Ok(None);
+    }
+
+    let block = AttributePath::new("ssh").index(0);
+    let host = match config.get_string(&block.clone().attribute("host")) {
+        Ok(host) => host,
+        Err(_) => url::Url::parse(endpoint)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(|| {
+                Diagnostic::error(
+                    "Missing SSH host",
+                    format!(
+                        "Could not derive the SSH host from endpoint '{}'. Set host in the ssh block.",
+                        endpoint
+                    ),
+                )
+            })?,
+    };
+
+    Ok(Some(ssh::SshConfig {
+        host,
+        user: config
+            .get_string(&block.clone().attribute("user"))
+            .unwrap_or_else(|_| "root".to_string()),
+        private_key: config
+            .get_string(&block.clone().attribute("private_key"))
+            .ok(),
+        agent: config.get_bool(&block.attribute("agent")).unwrap_or(false),
+    }))
 }
+
+#[cfg(test)]
+#[path = "./lib_test.rs"]
+mod lib_test;
@@ -0,0 +1,213 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use std::collections::HashMap;
+    use tfplug::types::{Dynamic, DynamicValue};
+
+    fn config_with_ssh(block: Option<HashMap<String, Dynamic>>) -> DynamicValue {
+        let mut obj = HashMap::new();
+        let blocks = block.into_iter().map(Dynamic::Map).collect();
+        obj.insert("ssh".to_string(), Dynamic::List(blocks));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    #[test]
+    fn test_ssh_disabled_without_block() {
+        let config = config_with_ssh(None);
+        let ssh = ssh_config(&config, "https://pve.example.com:8006").unwrap();
+        assert!(ssh.is_none());
+    }
+
+    #[test]
+    fn test_ssh_defaults_from_endpoint() {
+        let config = config_with_ssh(Some(HashMap::new()));
+        let ssh = ssh_config(&config, "https://pve.example.com:8006")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(ssh.host, "pve.example.com");
+        assert_eq!(ssh.user, "root");
+        assert!(ssh.private_key.is_none());
+        assert!(!ssh.agent);
+    }
+
+    #[test]
+    fn test_ssh_block_values() {
+        let mut block = HashMap::new();
+        block.insert("host".to_string(), Dynamic::String("10.0.0.2".to_string()));
+        block.insert("user".to_string(), Dynamic::String("terraform".to_string()));
+        block.insert("agent".to_string(), Dynamic::Bool(true));
+        let config = config_with_ssh(Some(block));
+
+        let ssh = ssh_config(&config, "https://pve.example.com:8006")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(ssh.host, "10.0.0.2");
+        assert_eq!(ssh.user, "terraform");
+        assert!(ssh.agent);
+    }
+
+    #[tokio::test]
+    async fn test_meta_schema_accepts_module_name_and_default_node() {
+        let provider = ProxmoxProvider::new();
+        let response = provider
+            .meta_schema(Context::new(), ProviderMetaSchemaRequest)
+            .await;
+
+        let schema = response.schema.unwrap();
+        let names: Vec<&str> = schema
+            .block
+            .attributes
+            .iter()
+            .map(|attr| attr.name.as_str())
+            .collect();
+        assert_eq!(names, ["module_name", "default_node"]);
+        assert!(schema.block.attributes.iter().all(|attr| attr.optional));
+    }
+
+    #[tokio::test]
+    async fn test_schema_json_lists_every_resource_and_data_source() {
+        let provider = ProxmoxProvider::new();
+        let address = "registry.terraform.io/mrdvince/proxmox";
+        let schemas = tfplug::schema_json::provider_schemas_json(&provider, address)
+            .await
+            .unwrap();
+
+        let schemas = &schemas["provider_schemas"][address];
+        assert!(schemas["provider"]["block"]["attributes"]["endpoint"].is_object());
+        assert_eq!(
+            schemas["resource_schemas"].as_object().unwrap().len(),
+            provider.resources().len()
+        );
+        assert_eq!(
+            schemas["data_source_schemas"].as_object().unwrap().len(),
+            provider.data_sources().len()
+        );
+        assert!(
+            schemas["resource_schemas"]["proxmox_qemu_vm"]["block"]["attributes"]["vmid"]
+                .is_object()
+        );
+    }
+
+    fn config_with_blocks(retry: &[(&str, Dynamic)], timeouts: &[(&str, Dynamic)]) -> DynamicValue {
+        let block = |fields: &[(&str, Dynamic)]| {
+            let fields = fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect();
+            Dynamic::List(vec![Dynamic::Map(fields)])
+        };
+        let mut obj = HashMap::new();
+        obj.insert("retry".to_string(), block(retry));
+        obj.insert("timeouts".to_string(), block(timeouts));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    #[test]
+    fn test_client_settings_default_without_blocks() {
+        let (retry, connect) = client_settings(&DynamicValue::new(Dynamic::Map(HashMap::new())));
+        assert_eq!(retry.max_retries, 3);
+        assert_eq!(retry.initial_backoff_ms, 100);
+        assert_eq!(retry.max_backoff_ms, 10_000);
+        assert_eq!(retry.timeout_seconds, 30);
+        assert_eq!(connect, std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_client_settings_from_blocks() {
+        let config = config_with_blocks(
+            &[
+                ("max_retries", Dynamic::Number(5.0)),
+                ("backoff", Dynamic::String("500ms".to_string())),
+                ("max_backoff", Dynamic::String("1m".to_string())),
+            ],
+            &[
+                ("connect", Dynamic::String("3s".to_string())),
+                ("request", Dynamic::String("2m".to_string())),
+            ],
+        );
+        let (retry, connect) = client_settings(&config);
+        assert_eq!(retry.max_retries, 5);
+        assert_eq!(retry.initial_backoff_ms, 500);
+        assert_eq!(retry.max_backoff_ms, 60_000);
+        assert_eq!(retry.timeout_seconds, 120);
+        assert_eq!(connect, std::time::Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_bad_retry_and_timeout_values() {
+        let config = config_with_blocks(
+            &[("max_retries", Dynamic::Number(1.5))],
+            &[("request", Dynamic::String("soon".to_string()))],
+        );
+        let response = ProxmoxProvider::new()
+            .validate(
+                Context::new(),
+                ValidateProviderConfigRequest {
+                    config,
+                    client_capabilities: tfplug::types::ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let paths: Vec<_> = response
+            .diagnostics
+            .iter()
+            .map(|diag| diag.attribute.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                Some(
+                    AttributePath::new("retry")
+                        .index(0)
+                        .attribute("max_retries")
+                ),
+                Some(AttributePath::new("timeouts").index(0).attribute("request")),
+            ]
+        );
+    }
+
+    fn config_with_token_file(path: &std::path::Path) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert(
+            "api_token_file".to_string(),
+            Dynamic::String(path.display().to_string()),
+        );
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    #[test]
+    fn test_api_token_file_is_trimmed() {
+        let path = std::env::temp_dir().join(format!("surtr-token-{}", std::process::id()));
+        std::fs::write(&path, "terraform@pve!provider=secret\n").unwrap();
+
+        let token = api_token(&config_with_token_file(&path));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            token.unwrap().as_deref(),
+            Some("terraform@pve!provider=secret")
+        );
+    }
+
+    #[test]
+    fn test_api_token_file_errors() {
+        let path = std::env::temp_dir().join(format!("surtr-bad-token-{}", std::process::id()));
+        std::fs::write(&path, "\n").unwrap();
+
+        let invalid = api_token(&config_with_token_file(&path));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(invalid.unwrap_err().summary, "Invalid API token file");
+
+        let missing = api_token(&config_with_token_file(&path));
+        assert_eq!(
+            missing.unwrap_err().summary,
+            "Failed to read API token file"
+        );
+    }
+}
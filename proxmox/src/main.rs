@@ -1,31 +1,26 @@
 use proxmox::ProxmoxProvider;
 use std::env;
 
+/// Source address Terraform knows the provider by
+const PROVIDER_ADDRESS: &str = "registry.terraform.io/mrdvince/proxmox";
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::DEBUG)
-        .with_writer(std::io::stderr)
-        .init();
+    tfplug::tflog::init();
 
     rustls::crypto::aws_lc_rs::default_provider()
         .install_default()
         .map_err(|_| "Failed to install rustls crypto provider")?;
 
-    let mut config = tfplug::ServerConfig::default();
-
-    let exe_dir = env::current_exe()?
-        .parent()
-        .ok_or("Failed to get executable directory")?
-        .to_path_buf();
-
-    let cert_path = exe_dir.join("../../certs/localhost.pem");
-    let key_path = exe_dir.join("../../certs/localhost-key.pem");
+    let provider = ProxmoxProvider::new();
+    let config = tfplug::ServerConfig::default().with_provider_address(PROVIDER_ADDRESS);
 
-    config.cert_path = cert_path;
-    config.key_path = key_path;
+    // Started by hand for a debugger instead of by Terraform
+    if env::args().any(|arg| arg == "--debug") {
+        tfplug::serve_debug(provider, PROVIDER_ADDRESS, config).await?;
+        return Ok(());
+    }
 
-    let provider = ProxmoxProvider::new();
     tfplug::serve(provider, config).await?;
 
     Ok(())
@@ -1,17 +1,188 @@
 //! Provider data structure passed to resources and data sources
 
-use crate::api::Client;
+use crate::api::cluster::{ClusterResource, ClusterResourceType};
+use crate::api::storage::StorageConfig;
+use crate::api::{ApiError, Client};
+use crate::ssh::SshClient;
+use std::collections::HashSet;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tokio::sync::{Mutex, RwLock};
+
+/// A listing and when it was fetched
+type CachedListing<T> = Arc<RwLock<Option<(Instant, Arc<Vec<T>>)>>>;
 
 #[derive(Clone)]
 pub struct ProxmoxProviderData {
     pub client: Arc<Client>,
+    /// Only set when the provider has an `ssh` block
+    pub ssh: Option<Arc<SshClient>>,
+    /// Node for resources that leave out target_node
+    pub default_node: Option<String>,
+    /// Storage for disks that leave out storage
+    pub default_storage: Option<String>,
+    /// Whether planning a VM checks that its network bridges exist
+    pub check_bridges: bool,
+    /// VMIDs handed out by proxmox_next_vmid during this provider run
+    vmid_reservations: Arc<Mutex<HashSet<u32>>>,
+    /// How long cluster_vms and storages reuse a listing, None when
+    /// caching is off
+    cluster_cache_ttl: Option<Duration>,
+    cluster_vms: CachedListing<ClusterResource>,
+    storages: CachedListing<StorageConfig>,
 }
 
 impl ProxmoxProviderData {
     pub fn new(client: Client) -> Self {
         Self {
             client: Arc::new(client),
+            ssh: None,
+            default_node: None,
+            default_storage: None,
+            check_bridges: false,
+            vmid_reservations: Arc::new(Mutex::new(HashSet::new())),
+            cluster_cache_ttl: None,
+            cluster_vms: Arc::new(RwLock::new(None)),
+            storages: Arc::new(RwLock::new(None)),
         }
     }
+
+    /// Reuses the cluster's VM and storage listings for `ttl`. A zero ttl
+    /// keeps caching off.
+    pub fn with_cluster_cache(mut self, ttl: Duration) -> Self {
+        self.cluster_cache_ttl = (!ttl.is_zero()).then_some(ttl);
+        self
+    }
+
+    pub fn with_bridge_checks(mut self, check_bridges: bool) -> Self {
+        self.check_bridges = check_bridges;
+        self
+    }
+
+    pub fn with_ssh(mut self, ssh: SshClient) -> Self {
+        self.ssh = Some(Arc::new(ssh));
+        self
+    }
+
+    pub fn with_defaults(
+        mut self,
+        default_node: Option<String>,
+        default_storage: Option<String>,
+    ) -> Self {
+        self.default_node = default_node;
+        self.default_storage = default_storage;
+        self
+    }
+
+    /// Applies the provider_meta block of the module a resource is declared
+    /// in: module_name tags the API requests, default_node overrides the
+    /// provider's default_node
+    pub fn with_provider_meta(&self, provider_meta: Option<&DynamicValue>) -> Self {
+        let mut data = self.clone();
+        let Some(meta) = provider_meta else {
+            return data;
+        };
+        if let Ok(module_name) = meta.get_string(&AttributePath::new("module_name")) {
+            data.client = Arc::new(data.client.with_module(&module_name));
+        }
+        if let Ok(default_node) = meta.get_string(&AttributePath::new("default_node")) {
+            data.default_node = Some(default_node);
+        }
+        data
+    }
+
+    /// SSH client for operations that have no API. Fails with a diagnostic
+    /// naming the operation when shell access was not enabled.
+    pub fn require_ssh(&self, operation: &str) -> Result<&SshClient, Diagnostic> {
+        self.ssh.as_deref().ok_or_else(|| {
+            Diagnostic::error(
+                "SSH access required",
+                format!(
+                    "{} needs shell access to the node. Add an ssh block to the provider configuration to allow it.",
+                    operation
+                ),
+            )
+        })
+    }
+
+    /// VMs of the cluster from /cluster/resources. With the cluster cache
+    /// on, a listing is reused until it is older than the TTL, so refreshing
+    /// many VMs lists the cluster once instead of once per VM.
+    pub async fn cluster_vms(&self) -> Result<Arc<Vec<ClusterResource>>, ApiError> {
+        self.cached(&self.cluster_vms, || async {
+            self.client
+                .cluster()
+                .resources(Some(ClusterResourceType::Vm))
+                .await
+        })
+        .await
+    }
+
+    /// Storages of the datacenter from /storage, cached like cluster_vms so
+    /// planning many VMs checks their storages against one listing
+    pub async fn storages(&self) -> Result<Arc<Vec<StorageConfig>>, ApiError> {
+        self.cached(&self.storages, || async {
+            self.client.storage().list(None).await
+        })
+        .await
+    }
+
+    async fn cached<T, F, Fut>(
+        &self,
+        cache: &CachedListing<T>,
+        list: F,
+    ) -> Result<Arc<Vec<T>>, ApiError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<T>, ApiError>>,
+    {
+        let Some(ttl) = self.cluster_cache_ttl else {
+            return list().await.map(Arc::new);
+        };
+        if let Some((fetched, items)) = cache.read().await.as_ref() {
+            if fetched.elapsed() < ttl {
+                return Ok(Arc::clone(items));
+            }
+        }
+
+        // Holding the write lock while listing lets concurrent readers wait
+        // for this listing instead of each sending their own
+        let mut cache = cache.write().await;
+        if let Some((fetched, items)) = cache.as_ref() {
+            if fetched.elapsed() < ttl {
+                return Ok(Arc::clone(items));
+            }
+        }
+        let items = Arc::new(list().await?);
+        *cache = Some((Instant::now(), Arc::clone(&items)));
+        Ok(items)
+    }
+
+    /// Drops the cached VM listing after VMs were created, changed or
+    /// removed
+    pub async fn invalidate_cluster_vms(&self) {
+        *self.cluster_vms.write().await = None;
+    }
+
+    /// Drops the cached storage listing after storages were added, changed
+    /// or removed
+    pub async fn invalidate_storages(&self) {
+        *self.storages.write().await = None;
+    }
+
+    /// Reserves a VMID for this provider run. Returns false if it was already
+    /// handed out, so two lookups in the same plan never get the same VMID.
+    pub async fn reserve_vmid(&self, vmid: u32) -> bool {
+        self.vmid_reservations.lock().await.insert(vmid)
+    }
+
+    pub async fn is_vmid_reserved(&self, vmid: u32) -> bool {
+        self.vmid_reservations.lock().await.contains(&vmid)
+    }
 }
+
+#[cfg(test)]
+#[path = "./provider_data_test.rs"]
+mod provider_data_test;
@@ -0,0 +1,84 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+
+    async fn mock_vms(server: &mut Server, calls: usize) -> mockito::Mock {
+        server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".to_string(), "vm".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"id": "qemu/100", "type": "qemu", "node": "pve", "vmid": 100, "name": "web"}]}"#,
+            )
+            .expect(calls)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_cluster_vms_reuses_listing_within_ttl() {
+        let mut server = Server::new_async().await;
+        let mock = mock_vms(&mut server, 1).await;
+
+        let data = provider_data(&server.url()).with_cluster_cache(Duration::from_secs(60));
+        // Every resource works on its own clone of the provider data
+        for _ in 0..3 {
+            let vms = data.clone().cluster_vms().await.unwrap();
+            assert_eq!(vms[0].vmid, Some(100));
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cluster_vms_lists_again_after_invalidation() {
+        let mut server = Server::new_async().await;
+        let mock = mock_vms(&mut server, 2).await;
+
+        let data = provider_data(&server.url()).with_cluster_cache(Duration::from_secs(60));
+        data.cluster_vms().await.unwrap();
+        data.invalidate_cluster_vms().await;
+        data.cluster_vms().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_cluster_vms_without_cache() {
+        let mut server = Server::new_async().await;
+        let mock = mock_vms(&mut server, 2).await;
+
+        let data = provider_data(&server.url()).with_cluster_cache(Duration::ZERO);
+        data.cluster_vms().await.unwrap();
+        data.cluster_vms().await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_storages_reuses_listing_until_invalidated() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api2/json/storage")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"storage": "local", "type": "dir", "content": "iso"}]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let data = provider_data(&server.url()).with_cluster_cache(Duration::from_secs(60));
+        for _ in 0..3 {
+            let storages = data.storages().await.unwrap();
+            assert_eq!(storages[0].storage, "local");
+        }
+        data.invalidate_storages().await;
+        data.storages().await.unwrap();
+
+        mock.assert_async().await;
+    }
+}
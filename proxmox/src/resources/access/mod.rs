@@ -1,2 +1,6 @@
 pub mod resource_realm;
+pub mod resource_realm_sync;
+pub mod resource_tfa_webauthn;
 pub use resource_realm::RealmResource;
+pub use resource_realm_sync::RealmSyncResource;
+pub use resource_tfa_webauthn::TfaWebauthnResource;
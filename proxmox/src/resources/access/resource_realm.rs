@@ -1,5 +1,13 @@
 //! Realm resource implementation
 
+use crate::api::access::realms::{
+    LdapRealmSettings, RealmSyncOptions, RealmTotp, LDAP_MODES, REALM_SYNC_REMOVE_VANISHED,
+    REALM_SYNC_SCOPES,
+};
+use crate::resources::comment::{
+    comment_attribute, comment_delete, comment_from_config, set_comment_state,
+};
+use crate::resources::diagnostics::api_error_diagnostics;
 use async_trait::async_trait;
 use tfplug::context::Context;
 use tfplug::resource::{
@@ -10,7 +18,206 @@ use tfplug::resource::{
     UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
 };
 use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
-use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+const REALM_ATTRIBUTES: &[&str] = &[
+    "realm",
+    "type",
+    "issuer_url",
+    "client_id",
+    "client_key",
+    "username_claim",
+    "autocreate",
+    "default",
+    "groups_overwrite",
+    "groups_autocreate",
+    "comment",
+    "server1",
+    "server2",
+    "port",
+    "mode",
+    "verify",
+    "capath",
+    "cert",
+    "certkey",
+    "domain",
+    "base_dn",
+    "user_attr",
+    "bind_dn",
+    "password",
+    "filter",
+    "user_classes",
+    "group_dn",
+    "group_filter",
+    "group_classes",
+    "group_name_attr",
+    "sync_attributes",
+    "case_sensitive",
+];
+
+/// Reads the enforced second factor as the realm's `tfa` property string
+fn realm_tfa(config: &DynamicValue) -> Option<String> {
+    let required = config
+        .get_bool(&AttributePath::new("totp_required"))
+        .unwrap_or(false);
+    required.then(|| {
+        RealmTotp {
+            digits: config.get_u32(&AttributePath::new("totp_digits")).ok(),
+            step: config.get_u32(&AttributePath::new("totp_step")).ok(),
+        }
+        .to_property_string()
+    })
+}
+
+/// Copies the enforced second factor reported by the API into state
+fn set_totp_state(state: &mut DynamicValue, tfa: Option<&str>) {
+    match tfa.and_then(RealmTotp::parse) {
+        Some(totp) => {
+            let _ = state.set_bool(&AttributePath::new("totp_required"), true);
+            if let Some(digits) = totp.digits {
+                let _ = state.set_number(&AttributePath::new("totp_digits"), digits as f64);
+            }
+            if let Some(step) = totp.step {
+                let _ = state.set_number(&AttributePath::new("totp_step"), step as f64);
+            }
+        }
+        None => {
+            if state
+                .get_bool(&AttributePath::new("totp_required"))
+                .unwrap_or(false)
+            {
+                let _ = state.set_bool(&AttributePath::new("totp_required"), false);
+            }
+        }
+    }
+}
+
+/// Reads the LDAP and AD settings from a config, plan or state value
+fn ldap_settings(config: &DynamicValue) -> LdapRealmSettings {
+    let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+    let remove_vanished = config
+        .get_list(&AttributePath::new("sync_remove_vanished"))
+        .ok()
+        .map(|values| {
+            let values: Vec<String> = values
+                .into_iter()
+                .filter_map(|value| match value {
+                    Dynamic::String(s) => Some(s),
+                    _ => None,
+                })
+                .collect();
+            if values.is_empty() {
+                "none".to_string()
+            } else {
+                values.join(";")
+            }
+        });
+    let sync_options = RealmSyncOptions {
+        scope: string("sync_scope"),
+        enable_new: config.get_bool(&AttributePath::new("sync_enable_new")).ok(),
+        remove_vanished,
+    };
+
+    LdapRealmSettings {
+        server1: string("server1"),
+        server2: string("server2"),
+        port: config.get_u32(&AttributePath::new("port")).ok(),
+        mode: string("mode"),
+        verify: config.get_bool(&AttributePath::new("verify")).ok(),
+        capath: string("capath"),
+        cert: string("cert"),
+        certkey: string("certkey"),
+        domain: string("domain"),
+        base_dn: string("base_dn"),
+        user_attr: string("user_attr"),
+        bind_dn: string("bind_dn"),
+        password: string("password"),
+        filter: string("filter"),
+        user_classes: string("user_classes"),
+        group_dn: string("group_dn"),
+        group_filter: string("group_filter"),
+        group_classes: string("group_classes"),
+        group_name_attr: string("group_name_attr"),
+        sync_attributes: string("sync_attributes"),
+        sync_defaults_options: sync_options.to_property_string(),
+        case_sensitive: config.get_bool(&AttributePath::new("case_sensitive")).ok(),
+    }
+}
+
+/// Builds the `delete` parameter for settings that were removed from the
+/// configuration, since Proxmox keeps values that are simply omitted
+fn realm_delete(prior: &DynamicValue, config: &DynamicValue) -> Option<String> {
+    let configured = ldap_settings(config).optional_settings();
+    let mut delete: Vec<String> = comment_delete(prior, config).into_iter().collect();
+    if realm_tfa(prior).is_some() && realm_tfa(config).is_none() {
+        delete.push("tfa".to_string());
+    }
+    delete.extend(
+        ldap_settings(prior)
+            .optional_settings()
+            .into_iter()
+            .zip(configured)
+            .filter(|((_, was_set), (_, is_set))| *was_set && !is_set)
+            .map(|((param, _), _)| param.to_string()),
+    );
+
+    (!delete.is_empty()).then(|| delete.join(","))
+}
+
+/// Copies the LDAP and AD settings reported by the API into state. The bind
+/// password is never returned and keeps its configured value.
+fn set_ldap_state(state: &mut DynamicValue, ldap: &LdapRealmSettings) {
+    for (name, value) in [
+        ("server1", &ldap.server1),
+        ("server2", &ldap.server2),
+        ("mode", &ldap.mode),
+        ("capath", &ldap.capath),
+        ("cert", &ldap.cert),
+        ("certkey", &ldap.certkey),
+        ("domain", &ldap.domain),
+        ("base_dn", &ldap.base_dn),
+        ("user_attr", &ldap.user_attr),
+        ("bind_dn", &ldap.bind_dn),
+        ("filter", &ldap.filter),
+        ("user_classes", &ldap.user_classes),
+        ("group_dn", &ldap.group_dn),
+        ("group_filter", &ldap.group_filter),
+        ("group_classes", &ldap.group_classes),
+        ("group_name_attr", &ldap.group_name_attr),
+        ("sync_attributes", &ldap.sync_attributes),
+    ] {
+        if let Some(value) = value {
+            let _ = state.set_string(&AttributePath::new(name), value.clone());
+        }
+    }
+    if let Some(port) = ldap.port {
+        let _ = state.set_number(&AttributePath::new("port"), port as f64);
+    }
+    if let Some(verify) = ldap.verify {
+        let _ = state.set_bool(&AttributePath::new("verify"), verify);
+    }
+    if let Some(case_sensitive) = ldap.case_sensitive {
+        let _ = state.set_bool(&AttributePath::new("case_sensitive"), case_sensitive);
+    }
+    if let Some(options) = ldap.sync_defaults_options.as_deref() {
+        let options = RealmSyncOptions::parse(options);
+        if let Some(scope) = options.scope {
+            let _ = state.set_string(&AttributePath::new("sync_scope"), scope);
+        }
+        if let Some(enable_new) = options.enable_new {
+            let _ = state.set_bool(&AttributePath::new("sync_enable_new"), enable_new);
+        }
+        if let Some(remove_vanished) = options.remove_vanished {
+            let values = remove_vanished
+                .split(';')
+                .filter(|v| !v.is_empty() && *v != "none")
+                .map(|v| Dynamic::String(v.to_string()))
+                .collect();
+            let _ = state.set_list(&AttributePath::new("sync_remove_vanished"), values);
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct RealmResource {
@@ -96,9 +303,22 @@ impl Resource for RealmResource {
                     .optional()
                     .build(),
             )
+            .attribute(comment_attribute("realm"))
             .attribute(
-                AttributeBuilder::new("comment", AttributeType::String)
-                    .description("Description of the realm")
+                AttributeBuilder::new("totp_required", AttributeType::Bool)
+                    .description("Require every user of the realm to log in with a TOTP code as second factor")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("totp_digits", AttributeType::Number)
+                    .description("Number of digits of a TOTP code. Defaults to 6")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("totp_step", AttributeType::Number)
+                    .description("Seconds a TOTP code is valid. Defaults to 30")
                     .optional()
                     .build(),
             )
@@ -114,6 +334,154 @@ impl Resource for RealmResource {
                     .optional()
                     .build(),
             )
+            .attribute(
+                AttributeBuilder::new("server1", AttributeType::String)
+                    .description("LDAP or AD server address")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("server2", AttributeType::String)
+                    .description("Fallback server address, used when server1 is unreachable")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("port", AttributeType::Number)
+                    .description("Server port. Defaults to 389, or 636 for ldaps")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("mode", AttributeType::String)
+                    .description("Connection mode: ldap, ldaps or ldap+starttls")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("verify", AttributeType::Bool)
+                    .description("Verify the server's TLS certificate")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("capath", AttributeType::String)
+                    .description("Path to the CA certificate store on the nodes")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cert", AttributeType::String)
+                    .description("Path to the client certificate on the nodes")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("certkey", AttributeType::String)
+                    .description("Path to the client certificate key on the nodes")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("domain", AttributeType::String)
+                    .description("AD domain name")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("base_dn", AttributeType::String)
+                    .description("LDAP base domain name users are searched in")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("user_attr", AttributeType::String)
+                    .description("LDAP user attribute holding the username, e.g. uid")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("bind_dn", AttributeType::String)
+                    .description("Domain name to bind as. Anonymous binds are used when unset")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("password", AttributeType::String)
+                    .description("Password of the bind user")
+                    .optional()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("filter", AttributeType::String)
+                    .description("LDAP filter for user sync")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("user_classes", AttributeType::String)
+                    .description("Comma separated object classes of users")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("group_dn", AttributeType::String)
+                    .description("LDAP base domain name groups are searched in")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("group_filter", AttributeType::String)
+                    .description("LDAP filter for group sync")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("group_classes", AttributeType::String)
+                    .description("Comma separated object classes of groups")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("group_name_attr", AttributeType::String)
+                    .description("LDAP attribute holding the group name")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("sync_attributes", AttributeType::String)
+                    .description("Comma separated key=attribute pairs of user properties to sync, e.g. email=mail")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("case_sensitive", AttributeType::Bool)
+                    .description("Treat usernames as case-sensitive")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("sync_scope", AttributeType::String)
+                    .description("Default scope of a sync: users, groups or both")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("sync_enable_new", AttributeType::Bool)
+                    .description("Enable users created by a sync by default")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "sync_remove_vanished",
+                    AttributeType::List(Box::new(AttributeType::String)),
+                )
+                .description("What a sync removes for users and groups no longer in the directory by default: acl, entry and/or properties")
+                .optional()
+                .build(),
+            )
             .build();
 
         ResourceSchemaResponse {
@@ -127,10 +495,11 @@ impl Resource for RealmResource {
         _ctx: Context,
         request: ValidateResourceConfigRequest,
     ) -> ValidateResourceConfigResponse {
-        let mut diagnostics = vec![];
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["port", "totp_digits", "totp_step"]);
 
         // Validate realm type
-        if let Ok(realm_type) = request.config.get_string(&AttributePath::new("type")) {
+        if let Ok(realm_type) = config.get_string(&AttributePath::new("type")) {
             let valid_types = ["openid", "ldap", "ad", "pam", "pve"];
             if !valid_types.contains(&realm_type.as_str()) {
                 diagnostics.push(Diagnostic::error(
@@ -138,6 +507,55 @@ impl Resource for RealmResource {
                     format!("Realm type must be one of: {:?}", valid_types),
                 ));
             }
+
+            let required: &[&str] = match realm_type.as_str() {
+                "ldap" => &["server1", "base_dn", "user_attr"],
+                "ad" => &["server1", "domain"],
+                _ => &[],
+            };
+            for name in required {
+                if config.get_string(&AttributePath::new(name)).is_err() {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!("Missing {}", name),
+                            format!("{} is required for {} realms", name, realm_type),
+                        )
+                        .with_attribute(AttributePath::new(name)),
+                    );
+                }
+            }
+        }
+
+        for (name, allowed) in [("mode", LDAP_MODES), ("sync_scope", REALM_SYNC_SCOPES)] {
+            if let Ok(value) = config.get_string(&AttributePath::new(name)) {
+                if !allowed.contains(&value.as_str()) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!("Invalid {}", name),
+                            format!("{} must be one of: {}", name, allowed.join(", ")),
+                        )
+                        .with_attribute(AttributePath::new(name)),
+                    );
+                }
+            }
+        }
+        if let Ok(values) = config.get_list(&AttributePath::new("sync_remove_vanished")) {
+            for value in values {
+                if let Dynamic::String(value) = value {
+                    if !REALM_SYNC_REMOVE_VANISHED.contains(&value.as_str()) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                "Invalid sync_remove_vanished",
+                                format!(
+                                    "sync_remove_vanished values must be one of: {}",
+                                    REALM_SYNC_REMOVE_VANISHED.join(", ")
+                                ),
+                            )
+                            .with_attribute(AttributePath::new("sync_remove_vanished")),
+                        );
+                    }
+                }
+            }
         }
 
         ValidateResourceConfigResponse { diagnostics }
@@ -174,6 +592,7 @@ impl Resource for RealmResource {
                     realm_type: realm_config.realm_type.clone(),
                     comment: realm_config.comment.clone(),
                     default: realm_config.default,
+                    tfa: realm_config.tfa.clone(),
                     issuer_url: realm_config.issuer_url.clone(),
                     client_id: realm_config.client_id.clone(),
                     client_key: realm_config.client_key.clone(),
@@ -181,6 +600,7 @@ impl Resource for RealmResource {
                     autocreate: realm_config.autocreate,
                     groups_overwrite: realm_config.groups_overwrite,
                     groups_autocreate: realm_config.groups_autocreate,
+                    ldap: realm_config.ldap.clone(),
                 };
                 match provider_data
                     .client
@@ -198,9 +618,10 @@ impl Resource for RealmResource {
                         }
                     }
                     Err(e) => {
-                        diagnostics.push(Diagnostic::error(
+                        diagnostics.extend(api_error_diagnostics(
                             "Failed to create realm",
-                            format!("API error: {}", e),
+                            &e,
+                            REALM_ATTRIBUTES,
                         ));
                         CreateResourceResponse {
                             new_state: request.planned_state,
@@ -273,9 +694,7 @@ impl Resource for RealmResource {
 
                 // Update values that might have changed
                 let _ = new_state.set_string(&AttributePath::new("type"), realm_config.realm_type);
-                if let Some(comment) = realm_config.comment {
-                    let _ = new_state.set_string(&AttributePath::new("comment"), comment);
-                }
+                set_comment_state(&mut new_state, realm_config.comment.as_deref());
                 if let Some(default) = realm_config.default {
                     let _ = new_state.set_bool(&AttributePath::new("default"), default);
                 }
@@ -300,6 +719,8 @@ impl Resource for RealmResource {
                     let _ = new_state
                         .set_bool(&AttributePath::new("groups_autocreate"), groups_autocreate);
                 }
+                set_totp_state(&mut new_state, realm_config.tfa.as_deref());
+                set_ldap_state(&mut new_state, &realm_config.ldap);
 
                 ReadResourceResponse {
                     new_state: Some(new_state),
@@ -309,9 +730,7 @@ impl Resource for RealmResource {
                     new_identity: None,
                 }
             }
-            Err(crate::api::ApiError::ApiError { message, .. })
-                if message.contains("does not exist") =>
-            {
+            Err(e) if e.is_not_found() => {
                 // Resource doesn't exist - return None to signal Terraform to create it
                 ReadResourceResponse {
                     new_state: None,
@@ -367,6 +786,7 @@ impl Resource for RealmResource {
                     realm_type: realm_config.realm_type.clone(),
                     comment: realm_config.comment.clone(),
                     default: realm_config.default,
+                    tfa: realm_config.tfa.clone(),
                     issuer_url: realm_config.issuer_url.clone(),
                     client_id: realm_config.client_id.clone(),
                     client_key: realm_config.client_key.clone(),
@@ -374,6 +794,8 @@ impl Resource for RealmResource {
                     autocreate: realm_config.autocreate,
                     groups_overwrite: realm_config.groups_overwrite,
                     groups_autocreate: realm_config.groups_autocreate,
+                    ldap: realm_config.ldap.clone(),
+                    delete: realm_delete(&request.prior_state, &request.config),
                 };
                 match provider_data
                     .client
@@ -389,9 +811,10 @@ impl Resource for RealmResource {
                         new_identity: None,
                     },
                     Err(e) => {
-                        diagnostics.push(Diagnostic::error(
+                        diagnostics.extend(api_error_diagnostics(
                             "Failed to update realm",
-                            format!("API error: {}", e),
+                            &e,
+                            REALM_ATTRIBUTES,
                         ));
                         UpdateResourceResponse {
                             new_state: request.prior_state,
@@ -472,7 +895,7 @@ impl RealmResource {
             .get_string(&AttributePath::new("type"))
             .map_err(|_| Diagnostic::error("Missing type", "The 'type' attribute is required"))?;
 
-        let comment = config.get_string(&AttributePath::new("comment")).ok();
+        let comment = comment_from_config(config);
         let default = config.get_bool(&AttributePath::new("default")).ok();
         let issuer_url = config.get_string(&AttributePath::new("issuer_url")).ok();
         let client_id = config.get_string(&AttributePath::new("client_id")).ok();
@@ -493,6 +916,7 @@ impl RealmResource {
             realm_type,
             comment,
             default,
+            tfa: realm_tfa(config),
             issuer_url,
             client_id,
             client_key,
@@ -500,6 +924,7 @@ impl RealmResource {
             autocreate,
             groups_overwrite,
             groups_autocreate,
+            ldap: ldap_settings(config),
         })
     }
 }
@@ -532,3 +957,7 @@ impl ResourceWithConfigure for RealmResource {
         ConfigureResourceResponse { diagnostics }
     }
 }
+
+#[cfg(test)]
+#[path = "./resource_realm_test.rs"]
+mod resource_realm_test;
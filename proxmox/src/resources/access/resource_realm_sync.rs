@@ -0,0 +1,409 @@
+//! Realm sync resource implementation
+
+use crate::api::access::realms::{
+    RealmSyncOptions, RealmSyncRequest, REALM_SYNC_REMOVE_VANISHED, REALM_SYNC_SCOPES,
+};
+use crate::resources::diagnostics::{api_error_diagnostics, task_cancelled_warning};
+use async_trait::async_trait;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+/// Large directories take a while to sync.
+const SYNC_TASK_TIMEOUT: Duration = Duration::from_secs(600);
+
+const SYNC_ATTRIBUTES: &[&str] = &["realm", "scope", "enable_new", "remove_vanished", "dry_run"];
+
+/// Builds the sync request from the configuration. Unset options fall back
+/// to the realm's sync defaults.
+fn sync_request(config: &DynamicValue) -> RealmSyncRequest {
+    let remove_vanished = config
+        .get_list(&AttributePath::new("remove_vanished"))
+        .ok()
+        .map(|values| {
+            let values: Vec<String> = values
+                .into_iter()
+                .filter_map(|value| match value {
+                    Dynamic::String(s) => Some(s),
+                    _ => None,
+                })
+                .collect();
+            if values.is_empty() {
+                "none".to_string()
+            } else {
+                values.join(";")
+            }
+        });
+
+    RealmSyncRequest {
+        options: RealmSyncOptions {
+            scope: config.get_string(&AttributePath::new("scope")).ok(),
+            enable_new: config.get_bool(&AttributePath::new("enable_new")).ok(),
+            remove_vanished,
+        },
+        dry_run: config.get_bool(&AttributePath::new("dry_run")).ok(),
+    }
+}
+
+/// Syncs users and groups of an LDAP or AD realm. The sync runs on create
+/// and again whenever an argument or one of the triggers changes, so it can
+/// be tied to changes elsewhere in the configuration. Destroying the
+/// resource leaves synced users and groups in place.
+#[derive(Default)]
+pub struct RealmSyncResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl RealmSyncResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a sync and waits for its task. Returns the task's UPID.
+    async fn sync(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        config: &DynamicValue,
+        timeout: Duration,
+    ) -> Result<String, Vec<Diagnostic>> {
+        let realm = config
+            .get_string(&AttributePath::new("realm"))
+            .map_err(|_| {
+                vec![Diagnostic::error(
+                    "Missing realm",
+                    "The 'realm' attribute is required",
+                )]
+            })?;
+
+        let upid = provider_data
+            .client
+            .access()
+            .realms()
+            .sync(&realm, &sync_request(config))
+            .await
+            .map_err(|e| api_error_diagnostics("Failed to sync realm", &e, SYNC_ATTRIBUTES))?;
+        let node = upid.node().ok_or_else(|| {
+            vec![Diagnostic::error(
+                "Failed to sync realm",
+                format!("Unable to tell which node runs the sync task '{}'", upid.0),
+            )]
+        })?;
+
+        provider_data
+            .client
+            .nodes()
+            .node(node)
+            .wait_for_task(&upid, timeout, ctx.cancelled())
+            .await
+            .map_err(|e| {
+                let error = Diagnostic::error(
+                    "Failed to sync realm",
+                    format!("Sync of realm '{}' failed: {}", realm, e),
+                );
+                std::iter::once(error)
+                    .chain(task_cancelled_warning(&e))
+                    .collect::<Vec<_>>()
+            })?;
+
+        Ok(upid.0)
+    }
+}
+
+#[async_trait]
+impl Resource for RealmSyncResource {
+    fn type_name(&self) -> &str {
+        "proxmox_realm_sync"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Syncs users and groups of an LDAP or AD realm. The sync runs again whenever an argument or trigger changes")
+            .attribute(
+                AttributeBuilder::new("realm", AttributeType::String)
+                    .description("The LDAP or AD realm to sync")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("scope", AttributeType::String)
+                    .description("What to sync: users, groups or both. Defaults to the realm's sync_scope")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("enable_new", AttributeType::Bool)
+                    .description("Enable newly synced users. Defaults to the realm's sync_enable_new")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new(
+                    "remove_vanished",
+                    AttributeType::List(Box::new(AttributeType::String)),
+                )
+                .description("What to remove for users and groups no longer in the directory: acl, entry and/or properties. Defaults to the realm's sync_remove_vanished")
+                .optional()
+                .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("dry_run", AttributeType::Bool)
+                    .description("Only log what the sync would change")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("triggers", AttributeType::Map(Box::new(AttributeType::String)))
+                    .description("Arbitrary values that run the sync again when they change")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("upid", AttributeType::String)
+                    .description("UPID of the last sync task")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create, Operation::Update]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = vec![];
+
+        if let Ok(scope) = config.get_string(&AttributePath::new("scope")) {
+            if !REALM_SYNC_SCOPES.contains(&scope.as_str()) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid scope",
+                        format!("scope must be one of: {}", REALM_SYNC_SCOPES.join(", ")),
+                    )
+                    .with_attribute(AttributePath::new("scope")),
+                );
+            }
+        }
+        if let Ok(values) = config.get_list(&AttributePath::new("remove_vanished")) {
+            for value in values {
+                if let Dynamic::String(value) = value {
+                    if !REALM_SYNC_REMOVE_VANISHED.contains(&value.as_str()) {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                "Invalid remove_vanished",
+                                format!(
+                                    "remove_vanished values must be one of: {}",
+                                    REALM_SYNC_REMOVE_VANISHED.join(", ")
+                                ),
+                            )
+                            .with_attribute(AttributePath::new("remove_vanished")),
+                        );
+                    }
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, SYNC_TASK_TIMEOUT);
+        match Self::sync(&ctx, provider_data, &request.config, timeout).await {
+            Ok(upid) => {
+                let mut new_state = request.planned_state;
+                let _ = new_state.set_string(&AttributePath::new("upid"), upid);
+                CreateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                }
+            }
+            Err(diagnostics) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics,
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let realm = match request
+            .current_state
+            .get_string(&AttributePath::new("realm"))
+        {
+            Ok(realm) => realm,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        // A sync has nothing to read back. It only goes away with its realm.
+        match provider_data.client.access().realms().get(&realm).await {
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            _ => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).update(&ctx, SYNC_TASK_TIMEOUT);
+        match Self::sync(&ctx, provider_data, &request.config, timeout).await {
+            Ok(upid) => {
+                let mut new_state = request.planned_state;
+                let _ = new_state.set_string(&AttributePath::new("upid"), upid);
+                UpdateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                    new_identity: None,
+                }
+            }
+            Err(diagnostics) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // Synced users and groups stay, removing the resource only drops it
+        // from state
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for RealmSyncResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_realm_sync_test.rs"]
+mod resource_realm_sync_test;
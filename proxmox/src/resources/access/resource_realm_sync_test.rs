@@ -0,0 +1,151 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+
+    const UPID: &str = "UPID:pve1:00001234:00000000:65000000:auth-realm-sync:corp:root@pam:";
+
+    fn sync_config(values: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("realm".to_string(), Dynamic::String("corp".to_string()));
+        for name in [
+            "scope",
+            "enable_new",
+            "remove_vanished",
+            "dry_run",
+            "triggers",
+        ] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        obj.insert("upid".to_string(), Dynamic::Unknown);
+        for (name, value) in values {
+            obj.insert(name.to_string(), value.clone());
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> RealmSyncResource {
+        RealmSyncResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[test]
+    fn test_sync_request_uses_realm_defaults_when_unset() {
+        let request = sync_request(&sync_config(&[]));
+        assert_eq!(request.options, RealmSyncOptions::default());
+        assert!(request.dry_run.is_none());
+
+        let request = sync_request(&sync_config(&[
+            ("remove_vanished", Dynamic::List(vec![])),
+            ("enable_new", Dynamic::Bool(true)),
+        ]));
+        assert_eq!(request.options.remove_vanished.as_deref(), Some("none"));
+        assert_eq!(request.options.enable_new, Some(true));
+    }
+
+    async fn create(server: &Server, config: DynamicValue) -> CreateResourceResponse {
+        configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_realm_sync".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_create_waits_for_sync_task() {
+        let mut server = Server::new_async().await;
+        let sync = server
+            .mock("POST", "/api2/json/access/domains/corp/sync")
+            .match_body(Matcher::Json(serde_json::json!({
+                "scope": "both",
+                "remove-vanished": "acl;entry"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve1/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+
+        let response = create(
+            &server,
+            sync_config(&[
+                ("scope", Dynamic::String("both".to_string())),
+                (
+                    "remove_vanished",
+                    Dynamic::List(vec![
+                        Dynamic::String("acl".to_string()),
+                        Dynamic::String("entry".to_string()),
+                    ]),
+                ),
+            ]),
+        )
+        .await;
+
+        sync.assert_async().await;
+        task.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("upid"))
+                .unwrap(),
+            UPID
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_reports_failed_sync() {
+        let mut server = Server::new_async().await;
+        let _sync = server
+            .mock("POST", "/api2/json/access/domains/corp/sync")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .create_async()
+            .await;
+        let _task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve1/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "unable to bind"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+
+        let response = create(&server, sync_config(&[])).await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Failed to sync realm");
+        assert!(response.diagnostics[0].detail.contains("unable to bind"));
+    }
+}
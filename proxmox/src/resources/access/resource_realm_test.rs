@@ -0,0 +1,111 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::access::realms::RealmConfig;
+    use std::collections::HashMap;
+
+    fn realm_config(values: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("realm".to_string(), Dynamic::String("corp".to_string()));
+        obj.insert("type".to_string(), Dynamic::String("ldap".to_string()));
+        for (name, value) in values {
+            obj.insert(name.to_string(), value.clone());
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    #[test]
+    fn test_ldap_settings_build_sync_defaults() {
+        let ldap = ldap_settings(&realm_config(&[
+            ("sync_scope", Dynamic::String("users".to_string())),
+            ("sync_enable_new", Dynamic::Bool(true)),
+            (
+                "sync_remove_vanished",
+                Dynamic::List(vec![
+                    Dynamic::String("acl".to_string()),
+                    Dynamic::String("properties".to_string()),
+                ]),
+            ),
+        ]));
+
+        assert_eq!(
+            ldap.sync_defaults_options.as_deref(),
+            Some("scope=users,enable-new=1,remove-vanished=acl;properties")
+        );
+    }
+
+    #[test]
+    fn test_realm_delete_clears_removed_settings() {
+        let prior = realm_config(&[
+            ("comment", Dynamic::String("Corporate".to_string())),
+            ("server2", Dynamic::String("ldap2.example.com".to_string())),
+            ("bind_dn", Dynamic::String("cn=pve".to_string())),
+            ("sync_scope", Dynamic::String("both".to_string())),
+        ]);
+        let config = realm_config(&[("bind_dn", Dynamic::String("cn=pve".to_string()))]);
+
+        assert_eq!(
+            realm_delete(&prior, &config).as_deref(),
+            Some("comment,server2,sync-defaults-options")
+        );
+        assert!(realm_delete(&config, &config).is_none());
+    }
+
+    #[test]
+    fn test_set_ldap_state_keeps_password() {
+        let mut state = realm_config(&[("password", Dynamic::String("secret".to_string()))]);
+        let realm: RealmConfig = serde_json::from_str(
+            r#"{"realm": "corp", "type": "ldap", "server1": "ldap.example.com",
+                "sync-defaults-options": "scope=groups,remove-vanished=none"}"#,
+        )
+        .unwrap();
+
+        set_ldap_state(&mut state, &realm.ldap);
+
+        assert_eq!(
+            state.get_string(&AttributePath::new("server1")).unwrap(),
+            "ldap.example.com"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("sync_scope")).unwrap(),
+            "groups"
+        );
+        assert!(state
+            .get_list(&AttributePath::new("sync_remove_vanished"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            state.get_string(&AttributePath::new("password")).unwrap(),
+            "secret"
+        );
+    }
+
+    #[test]
+    fn test_totp_enforcement_round_trip() {
+        let config = realm_config(&[
+            ("totp_required", Dynamic::Bool(true)),
+            ("totp_digits", Dynamic::Number(8.0)),
+        ]);
+        let tfa = realm_tfa(&config);
+        assert_eq!(tfa.as_deref(), Some("type=oath,digits=8"));
+
+        let mut state = realm_config(&[("totp_required", Dynamic::Bool(true))]);
+        set_totp_state(&mut state, tfa.as_deref());
+        assert_eq!(
+            state
+                .get_number(&AttributePath::new("totp_digits"))
+                .unwrap(),
+            8.0
+        );
+
+        set_totp_state(&mut state, None);
+        assert!(!state
+            .get_bool(&AttributePath::new("totp_required"))
+            .unwrap());
+        assert_eq!(
+            realm_delete(&config, &realm_config(&[])).as_deref(),
+            Some("tfa")
+        );
+    }
+}
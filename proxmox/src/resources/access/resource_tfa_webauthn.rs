@@ -0,0 +1,444 @@
+//! WebAuthn two-factor settings resource implementation
+
+use crate::api::cluster::{ClusterOptions, UpdateClusterOptionsRequest, WebauthnSettings};
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+
+fn webauthn_from_config(config: &DynamicValue) -> WebauthnSettings {
+    let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+    WebauthnSettings {
+        rp: string("rp"),
+        origin: string("origin"),
+        id: string("id"),
+        allow_subdomains: config
+            .get_bool(&AttributePath::new("allow_subdomains"))
+            .ok(),
+    }
+}
+
+fn set_webauthn_state(state: &mut DynamicValue, settings: &WebauthnSettings) {
+    for (name, value) in [
+        ("rp", &settings.rp),
+        ("origin", &settings.origin),
+        ("id", &settings.id),
+    ] {
+        let path = AttributePath::new(name);
+        let _ = match value {
+            Some(value) => state.set_string(&path, value.clone()),
+            None => state.set_null(&path),
+        };
+    }
+    let path = AttributePath::new("allow_subdomains");
+    let _ = match settings.allow_subdomains {
+        Some(allow) => state.set_bool(&path, allow),
+        None => state.set_null(&path),
+    };
+}
+
+/// Host part of an origin like https://pve.example.com:8006
+fn origin_host(origin: &str) -> Option<&str> {
+    let rest = origin.split_once("://")?.1;
+    let host = rest.split(['/', ':']).next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// Manages the datacenter wide WebAuthn relying party that users register
+/// security keys against
+#[derive(Default)]
+pub struct TfaWebauthnResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl TfaWebauthnResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the settings and reads them back
+    async fn apply(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &WebauthnSettings,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let cluster = provider_data.client.cluster();
+        let request = UpdateClusterOptionsRequest {
+            webauthn: settings.to_property_string(),
+            delete: None,
+        };
+        cluster.update_options(&request).await.map_err(|e| {
+            Diagnostic::error(
+                "Failed to update WebAuthn settings",
+                format!("API error: {}", e),
+            )
+        })?;
+
+        let options = cluster.options().await.map_err(|e| {
+            Diagnostic::error(
+                "Failed to read WebAuthn settings",
+                format!("API error: {}", e),
+            )
+        })?;
+        set_webauthn_state(&mut state, &options.webauthn_settings().unwrap_or_default());
+        Ok(state)
+    }
+
+    /// Warns when changing the relying party ID locks users out of the
+    /// security keys they registered
+    async fn check_registered_keys(
+        provider_data: &crate::ProxmoxProviderData,
+        prior: &WebauthnSettings,
+        planned: &WebauthnSettings,
+    ) -> Option<Diagnostic> {
+        if prior.id == planned.id {
+            return None;
+        }
+        let users: Vec<String> = provider_data
+            .client
+            .access()
+            .tfa()
+            .list()
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|user| user.has_webauthn())
+            .map(|user| user.userid)
+            .collect();
+
+        (!users.is_empty()).then(|| {
+            Diagnostic::warning(
+                "WebAuthn credentials invalidated",
+                format!(
+                    "Changing the relying party ID invalidates the WebAuthn credentials of {}. They have to register their security keys again.",
+                    users.join(", ")
+                ),
+            )
+            .with_attribute(AttributePath::new("id"))
+        })
+    }
+}
+
+#[async_trait]
+impl Resource for TfaWebauthnResource {
+    fn type_name(&self) -> &str {
+        "proxmox_tfa_webauthn"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages the datacenter wide WebAuthn settings users register security keys against. There is one per cluster. TOTP is enforced per realm through proxmox_realm")
+            .attribute(
+                AttributeBuilder::new("rp", AttributeType::String)
+                    .description("Relying party name shown by authenticators")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("origin", AttributeType::String)
+                    .description("Origin users reach the web interface at, e.g. https://pve.example.com:8006")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("Relying party ID, the domain of the origin. Changing it invalidates all registered WebAuthn credentials")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("allow_subdomains", AttributeType::Bool)
+                    .description("Also accept logins from subdomains of the ID")
+                    .optional()
+                    .build(),
+            )
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = vec![];
+        let settings = webauthn_from_config(&request.config);
+
+        if let Some(origin) = &settings.origin {
+            match origin_host(origin) {
+                None => diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid origin",
+                        format!("'{}' is not an origin like https://host:port", origin),
+                    )
+                    .with_attribute(AttributePath::new("origin")),
+                ),
+                Some(host) => {
+                    if let Some(id) = &settings.id {
+                        if host != id && !host.ends_with(&format!(".{}", id)) {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    "Invalid id",
+                                    format!(
+                                        "The relying party ID '{}' must be the origin's domain '{}' or a parent domain of it",
+                                        id, host
+                                    ),
+                                )
+                                .with_attribute(AttributePath::new("id")),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = webauthn_from_config(&request.config);
+        // Settings configured outside of Terraform are overwritten as well
+        let current = provider_data
+            .client
+            .cluster()
+            .options()
+            .await
+            .ok()
+            .and_then(|options| options.webauthn_settings())
+            .unwrap_or_default();
+        let mut diagnostics: Vec<Diagnostic> =
+            Self::check_registered_keys(provider_data, &current, &settings)
+                .await
+                .into_iter()
+                .collect();
+
+        match Self::apply(provider_data, &settings, request.planned_state.clone()).await {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics,
+            },
+            Err(diag) => {
+                diagnostics.push(diag);
+                CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                }
+            }
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data.client.cluster().options().await {
+            Ok(ClusterOptions { webauthn: None, .. }) => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Ok(options) => {
+                let mut new_state = request.current_state.clone();
+                set_webauthn_state(
+                    &mut new_state,
+                    &options.webauthn_settings().unwrap_or_default(),
+                );
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read WebAuthn settings",
+                    format!("API error: {}", e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = webauthn_from_config(&request.config);
+        let prior = webauthn_from_config(&request.prior_state);
+        let mut diagnostics: Vec<Diagnostic> =
+            Self::check_registered_keys(provider_data, &prior, &settings)
+                .await
+                .into_iter()
+                .collect();
+
+        match Self::apply(provider_data, &settings, request.planned_state).await {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            },
+            Err(diag) => {
+                diagnostics.push(diag);
+                UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics,
+                    new_identity: None,
+                }
+            }
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let request = UpdateClusterOptionsRequest {
+            webauthn: None,
+            delete: Some("webauthn".to_string()),
+        };
+        let diagnostics = match provider_data
+            .client
+            .cluster()
+            .update_options(&request)
+            .await
+        {
+            Ok(()) => vec![],
+            Err(e) => vec![Diagnostic::error(
+                "Failed to remove WebAuthn settings",
+                format!("API error: {}", e),
+            )],
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for TfaWebauthnResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_tfa_webauthn_test.rs"]
+mod resource_tfa_webauthn_test;
@@ -0,0 +1,149 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::{ClientCapabilities, Dynamic};
+
+    fn webauthn_config(id: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("rp".to_string(), Dynamic::String("Proxmox".to_string()));
+        obj.insert(
+            "origin".to_string(),
+            Dynamic::String("https://pve.example.com:8006".to_string()),
+        );
+        obj.insert("id".to_string(), Dynamic::String(id.to_string()));
+        obj.insert("allow_subdomains".to_string(), Dynamic::Null);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> TfaWebauthnResource {
+        TfaWebauthnResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_id_matches_origin() {
+        for (id, errors) in [("example.com", 0), ("pve.example.com", 0), ("other.com", 1)] {
+            let response = TfaWebauthnResource::new()
+                .validate(
+                    Context::new(),
+                    ValidateResourceConfigRequest {
+                        type_name: "proxmox_tfa_webauthn".to_string(),
+                        config: webauthn_config(id),
+                        client_capabilities: ClientCapabilities {
+                            deferral_allowed: false,
+                            write_only_attributes_allowed: false,
+                        },
+                    },
+                )
+                .await;
+            assert_eq!(response.diagnostics.len(), errors, "id {}", id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_warns_about_invalidated_credentials() {
+        let mut server = Server::new_async().await;
+        let update = server
+            .mock("PUT", "/api2/json/cluster/options")
+            .match_body(Matcher::Json(serde_json::json!({
+                "webauthn": "rp=Proxmox,origin=https://pve.example.com:8006,id=pve.example.com"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _options = server
+            .mock("GET", "/api2/json/cluster/options")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"keyboard": "en-us",
+                    "webauthn": "rp=Proxmox,origin=https://pve.example.com:8006,id=pve.example.com"}}"#,
+            )
+            .create_async()
+            .await;
+        let _tfa = server
+            .mock("GET", "/api2/json/access/tfa")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"userid": "alice@pve", "entries": [{"id": "w1", "type": "webauthn", "description": "key", "created": 1700000000, "enable": 1}]},
+                    {"userid": "bob@pve", "entries": [{"id": "t1", "type": "totp", "description": "phone"}]}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = webauthn_config("pve.example.com");
+        let response = configured_resource(&server.url())
+            .update(
+                Context::new(),
+                UpdateResourceRequest {
+                    type_name: "proxmox_tfa_webauthn".to_string(),
+                    prior_state: webauthn_config("example.com"),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                    planned_identity: None,
+                },
+            )
+            .await;
+
+        update.assert_async().await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "WebAuthn credentials invalidated"
+        );
+        assert!(response.diagnostics[0].detail.contains("alice@pve"));
+        assert!(!response.diagnostics[0].detail.contains("bob@pve"));
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("id"))
+                .unwrap(),
+            "pve.example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_without_webauthn_removes_state() {
+        let mut server = Server::new_async().await;
+        let _options = server
+            .mock("GET", "/api2/json/cluster/options")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"keyboard": "en-us"}}"#)
+            .create_async()
+            .await;
+
+        let response = configured_resource(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_tfa_webauthn".to_string(),
+                    current_state: webauthn_config("example.com"),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert!(response.new_state.is_none());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod resource_cluster;
+pub mod resource_cluster_join;
+pub mod resource_ha_resource;
+pub mod resource_mapping_dir;
+pub use resource_cluster::ClusterResource;
+pub use resource_cluster_join::ClusterJoinResource;
+pub use resource_ha_resource::HaResourceResource;
+pub use resource_mapping_dir::MappingDirResource;
@@ -0,0 +1,386 @@
+//! Cluster creation resource implementation
+
+use crate::api::cluster::{cluster_links, CreateClusterRequest, MAX_CLUSTER_LINKS};
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::{validate_integers, ListLengthValidator};
+
+/// Creating a cluster restarts corosync and pve-cluster on the node.
+const CLUSTER_TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CLUSTER_ATTRIBUTES: &[&str] = &["nodeid", "votes"];
+
+/// Cluster settings taken from the configuration
+#[derive(Debug)]
+struct ClusterSettings {
+    name: String,
+    links: Vec<String>,
+    nodeid: Option<u32>,
+    votes: Option<u32>,
+}
+
+impl ClusterSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let name = config
+            .get_string(&AttributePath::new("name"))
+            .map_err(|_| Diagnostic::error("Missing name", "The 'name' attribute is required"))?;
+        let links = config
+            .get_list(&AttributePath::new("links"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|link| match link {
+                Dynamic::String(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+
+        Ok(Self {
+            name,
+            links,
+            nodeid: config.get_u32(&AttributePath::new("nodeid")).ok(),
+            votes: config.get_u32(&AttributePath::new("votes")).ok(),
+        })
+    }
+
+    fn create_request(&self) -> CreateClusterRequest {
+        CreateClusterRequest {
+            clustername: self.name.clone(),
+            nodeid: self.nodeid,
+            votes: self.votes,
+            links: cluster_links(&self.links),
+        }
+    }
+}
+
+/// Creates a cluster on the node the provider talks to, which becomes its
+/// first member. Proxmox cannot dissolve a cluster through the API, so
+/// destroying the resource only removes it from state.
+#[derive(Default)]
+pub struct ClusterResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl ClusterResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The name of the cluster the provider's node is in, None for a
+    /// standalone node
+    async fn cluster_name(
+        provider_data: &crate::ProxmoxProviderData,
+    ) -> Result<Option<String>, crate::api::ApiError> {
+        let status = provider_data.client.cluster().status().await?;
+        Ok(status
+            .into_iter()
+            .find(|entry| entry.is_cluster())
+            .map(|entry| entry.name))
+    }
+}
+
+#[async_trait]
+impl Resource for ClusterResource {
+    fn type_name(&self) -> &str {
+        "proxmox_cluster"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Creates a cluster on the standalone node the provider talks to. Join further nodes with proxmox_cluster_join. Every argument forces a new resource. Proxmox has no API to dissolve a cluster, so destroying this resource leaves the cluster as it is")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The cluster name")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .description("The cluster name, up to 15 letters, digits and '-'")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("links", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Addresses of this node for the corosync links in order, the first becoming link0. An address may be followed by ',priority=N'. Defaults to the address the node name resolves to")
+                    .optional()
+                    .validator(ListLengthValidator::max(MAX_CLUSTER_LINKS))
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nodeid", AttributeType::Number)
+                    .description("Corosync node ID of this node. Defaults to 1")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("votes", AttributeType::Number)
+                    .description("Quorum votes of this node. Defaults to 1")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node the cluster was created on")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["nodeid", "votes"]);
+
+        if let Ok(name) = config.get_string(&AttributePath::new("name")) {
+            let valid = !name.is_empty()
+                && name.len() <= 15
+                && !name.starts_with('-')
+                && !name.ends_with('-')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+            if !valid {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("name"),
+                    "Invalid cluster name",
+                    "name must be 1 to 15 letters, digits and '-', and must not start or end with '-'",
+                ));
+            }
+        }
+
+        if let Ok(nodeid) = config.get_number(&AttributePath::new("nodeid")) {
+            if nodeid < 1.0 {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("nodeid"),
+                    "Invalid node ID",
+                    "nodeid must be at least 1",
+                ));
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match ClusterSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, CLUSTER_TASK_TIMEOUT);
+        let result = match provider_data
+            .client
+            .cluster()
+            .config()
+            .create(&settings.create_request())
+            .await
+        {
+            Ok(upid) => match upid.node().map(str::to_string) {
+                Some(node) => provider_data
+                    .client
+                    .nodes()
+                    .node(&node)
+                    .wait_for_task(&upid, timeout, ctx.cancelled())
+                    .await
+                    .map(|_| node),
+                None => Err(crate::api::ApiError::ParseError(format!(
+                    "Task ID '{}' does not name a node",
+                    upid.0
+                ))),
+            },
+            Err(e) => Err(e),
+        };
+        let node = match result {
+            Ok(node) => node,
+            Err(e) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: api_error_diagnostics(
+                        "Failed to create cluster",
+                        &e,
+                        CLUSTER_ATTRIBUTES,
+                    ),
+                };
+            }
+        };
+
+        let mut new_state = request.planned_state;
+        let _ = new_state.set_string(&AttributePath::new("id"), settings.name.clone());
+        let _ = new_state.set_string(&AttributePath::new("node"), node);
+        CreateResourceResponse {
+            new_state,
+            private: vec![],
+            diagnostics: vec![],
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match Self::cluster_name(provider_data).await {
+            // The node left the cluster or was reinstalled
+            Ok(None) => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Ok(Some(name)) => {
+                let mut new_state = request.current_state.clone();
+                let _ = new_state.set_string(&AttributePath::new("name"), name);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read cluster",
+                    format!("Unable to read cluster status: {}", e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        // Every argument forces a new resource, only timeouts can change
+        UpdateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: vec![],
+            new_identity: None,
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // There is no API to dissolve a cluster, that takes stopping corosync
+        // and removing its configuration on every node
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for ClusterResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_cluster_test.rs"]
+mod resource_cluster_test;
@@ -0,0 +1,566 @@
+//! Cluster join resource implementation
+
+use crate::api::cluster::{cluster_links, JoinClusterRequest, MAX_CLUSTER_LINKS};
+use crate::api::{ApiError, Client, TaskId};
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::{validate_integers, ListLengthValidator};
+
+/// Joining copies the cluster configuration to the node and restarts its
+/// services.
+const JOIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the cluster is asked whether the node is in yet
+const JOIN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Lines from the end of a failed join task's log that go into its error
+const JOIN_LOG_TAIL_LINES: u32 = 20;
+
+const JOIN_ATTRIBUTES: &[&str] = &[
+    "hostname",
+    "fingerprint",
+    "password",
+    "nodeid",
+    "votes",
+    "force",
+];
+
+/// Join settings taken from the configuration
+#[derive(Debug)]
+struct JoinSettings {
+    node_endpoint: String,
+    node_api_token: String,
+    node_insecure: bool,
+    hostname: Option<String>,
+    fingerprint: Option<String>,
+    password: String,
+    links: Vec<String>,
+    nodeid: Option<u32>,
+    votes: Option<u32>,
+    force: bool,
+}
+
+impl JoinSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let required = |name: &str| {
+            config.get_string(&AttributePath::new(name)).map_err(|_| {
+                Diagnostic::error(
+                    format!("Missing {}", name),
+                    format!("The '{}' attribute is required", name),
+                )
+            })
+        };
+        let links = config
+            .get_list(&AttributePath::new("links"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|link| match link {
+                Dynamic::String(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+
+        Ok(Self {
+            node_endpoint: required("node_endpoint")?,
+            node_api_token: required("node_api_token")?,
+            node_insecure: config
+                .get_bool(&AttributePath::new("node_insecure"))
+                .unwrap_or(false),
+            hostname: config.get_string(&AttributePath::new("hostname")).ok(),
+            fingerprint: config.get_string(&AttributePath::new("fingerprint")).ok(),
+            password: required("password")?,
+            links,
+            nodeid: config.get_u32(&AttributePath::new("nodeid")).ok(),
+            votes: config.get_u32(&AttributePath::new("votes")).ok(),
+            force: config
+                .get_bool(&AttributePath::new("force"))
+                .unwrap_or(false),
+        })
+    }
+}
+
+/// Joins a standalone node to the cluster the provider talks to. The node
+/// is reached through its own endpoint and API token, which are only used
+/// for the join. Removing a node from a cluster takes shutting it down
+/// first, so destroying the resource only removes it from state.
+#[derive(Default)]
+pub struct ClusterJoinResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl ClusterJoinResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the cluster lists the node as online
+    async fn is_member(
+        provider_data: &crate::ProxmoxProviderData,
+        node: &str,
+    ) -> Result<bool, ApiError> {
+        let status = provider_data.client.cluster().status().await?;
+        Ok(status
+            .iter()
+            .any(|entry| entry.is_node() && entry.name == node && entry.online == Some(true)))
+    }
+
+    /// Waits for the node to show up online in the cluster. The join task
+    /// runs on the node, whose API goes away while its services restart
+    /// with the cluster configuration, so the task is only checked for
+    /// failure and errors asking about it don't count.
+    async fn wait_for_join(
+        provider_data: &crate::ProxmoxProviderData,
+        node_client: &Client,
+        node: &str,
+        upid: &TaskId,
+        timeout: Duration,
+    ) -> Result<(), ApiError> {
+        let node_api = node_client.nodes().node(node);
+        let started = Instant::now();
+        loop {
+            if Self::is_member(provider_data, node).await? {
+                return Ok(());
+            }
+            if let Ok(status) = node_api.task_status(upid).await {
+                if !status.is_running() && !status.is_successful() {
+                    let log = node_api
+                        .task_log_tail(upid, JOIN_LOG_TAIL_LINES)
+                        .await
+                        .unwrap_or_default();
+                    return Err(ApiError::TaskFailed {
+                        upid: upid.0.clone(),
+                        exitstatus: status.exitstatus.unwrap_or_else(|| "unknown".to_string()),
+                        log,
+                    });
+                }
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(ApiError::Timeout(timeout.as_secs()));
+            }
+            tokio::time::sleep(JOIN_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Fills in the address and fingerprint of the provider's node for
+    /// whichever of them the configuration leaves out, then has the node
+    /// join and waits for it. Returns the joined node's name along with the
+    /// address and fingerprint used.
+    async fn join(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &JoinSettings,
+        timeout: Duration,
+    ) -> Result<(String, String, String), ApiError> {
+        let (hostname, fingerprint) = match (&settings.hostname, &settings.fingerprint) {
+            (Some(hostname), Some(fingerprint)) => (hostname.clone(), fingerprint.clone()),
+            _ => {
+                let info = provider_data
+                    .client
+                    .cluster()
+                    .config()
+                    .join_info(None)
+                    .await?;
+                let preferred = info.preferred().ok_or_else(|| ApiError::NotFound {
+                    status: 404,
+                    message: format!(
+                        "Join information does not list node '{}'",
+                        info.preferred_node
+                    ),
+                })?;
+                let missing = |what: &str| {
+                    ApiError::ParseError(format!(
+                        "Join information of node '{}' has no {}",
+                        preferred.name, what
+                    ))
+                };
+                (
+                    match &settings.hostname {
+                        Some(hostname) => hostname.clone(),
+                        None => preferred
+                            .pve_addr
+                            .clone()
+                            .ok_or_else(|| missing("address"))?,
+                    },
+                    match &settings.fingerprint {
+                        Some(fingerprint) => fingerprint.clone(),
+                        None => preferred
+                            .pve_fp
+                            .clone()
+                            .ok_or_else(|| missing("fingerprint"))?,
+                    },
+                )
+            }
+        };
+
+        let node_client = Client::new(
+            &settings.node_endpoint,
+            &settings.node_api_token,
+            settings.node_insecure,
+        )?;
+        let upid = node_client
+            .cluster()
+            .config()
+            .join(&JoinClusterRequest {
+                hostname: hostname.clone(),
+                fingerprint: fingerprint.clone(),
+                password: settings.password.clone(),
+                nodeid: settings.nodeid,
+                votes: settings.votes,
+                force: settings.force.then_some(true),
+                links: cluster_links(&settings.links),
+            })
+            .await?;
+        let node = upid.node().map(str::to_string).ok_or_else(|| {
+            ApiError::ParseError(format!("Task ID '{}' does not name a node", upid.0))
+        })?;
+
+        tokio::select! {
+            result = Self::wait_for_join(provider_data, &node_client, &node, &upid, timeout) => result?,
+            _ = ctx.cancelled() => return Err(ApiError::TaskCancelled { upid: upid.0.clone() }),
+        }
+        Ok((node, hostname, fingerprint))
+    }
+}
+
+#[async_trait]
+impl Resource for ClusterJoinResource {
+    fn type_name(&self) -> &str {
+        "proxmox_cluster_join"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Joins a standalone node to the cluster the provider talks to. Changing anything but the credentials forces a new resource. Removing a node from a cluster takes shutting it down first, so destroying this resource leaves the node in the cluster")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The name of the joined node")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node_endpoint", AttributeType::String)
+                    .description("API URL of the node to join, e.g. https://10.0.0.12:8006")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node_api_token", AttributeType::String)
+                    .description("API token of the node to join, as user@realm!tokenid=secret. The join replaces the node's users and tokens with the cluster's, so it is only used for the join")
+                    .required()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node_insecure", AttributeType::Bool)
+                    .description("Skip TLS verification for node_endpoint, e.g. while the node still has its self-signed certificate. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("password", AttributeType::String)
+                    .description("root@pam password of the cluster node the node joins through")
+                    .required()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("hostname", AttributeType::String)
+                    .description("Address of the cluster node to join through. Defaults to the address of the node the provider talks to")
+                    .optional()
+                    .computed()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("fingerprint", AttributeType::String)
+                    .description("SHA-256 fingerprint of the API certificate of the node at hostname. Defaults to the fingerprint of the node the provider talks to")
+                    .optional()
+                    .computed()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("links", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Addresses of the joining node for the corosync links in order, the first becoming link0. The cluster decides how many links there are. Defaults to the address the node name resolves to")
+                    .optional()
+                    .validator(ListLengthValidator::max(MAX_CLUSTER_LINKS))
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nodeid", AttributeType::Number)
+                    .description("Corosync node ID of the joining node. Defaults to the lowest free ID")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("votes", AttributeType::Number)
+                    .description("Quorum votes of the joining node. Defaults to 1")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("force", AttributeType::Bool)
+                    .description("Join even if the node already has guests or a corosync configuration. Defaults to false")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The name of the joined node")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["nodeid", "votes"]);
+
+        if let Ok(endpoint) = config.get_string(&AttributePath::new("node_endpoint")) {
+            if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("node_endpoint"),
+                    "Invalid node endpoint",
+                    format!(
+                        "node_endpoint must be a URL like https://10.0.0.12:8006, got '{}'",
+                        endpoint
+                    ),
+                ));
+            }
+        }
+
+        if let Ok(token) = config.get_string(&AttributePath::new("node_api_token")) {
+            let valid = token
+                .split_once('!')
+                .and_then(|(user, rest)| {
+                    rest.split_once('=').map(|(id, secret)| (user, id, secret))
+                })
+                .is_some_and(|(user, id, secret)| {
+                    user.contains('@') && !id.is_empty() && !secret.is_empty()
+                });
+            if !valid {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("node_api_token"),
+                    "Invalid node API token",
+                    "node_api_token must look like user@realm!tokenid=secret",
+                ));
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match JoinSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, JOIN_TIMEOUT);
+        match Self::join(&ctx, provider_data, &settings, timeout).await {
+            Ok((node, hostname, fingerprint)) => {
+                let mut new_state = request.planned_state;
+                let _ = new_state.set_string(&AttributePath::new("id"), node.clone());
+                let _ = new_state.set_string(&AttributePath::new("node"), node);
+                let _ = new_state.set_string(&AttributePath::new("hostname"), hostname);
+                let _ = new_state.set_string(&AttributePath::new("fingerprint"), fingerprint);
+                CreateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                }
+            }
+            Err(e) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics("Failed to join cluster", &e, JOIN_ATTRIBUTES),
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let node = match request
+            .current_state
+            .get_string(&AttributePath::new("node"))
+        {
+            Ok(node) => node,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        // A node that is down is still a member, only one that was removed
+        // from the cluster is gone
+        match provider_data.client.cluster().status().await {
+            Ok(status) => {
+                let member = status
+                    .iter()
+                    .any(|entry| entry.is_node() && entry.name == node);
+                ReadResourceResponse {
+                    new_state: member.then_some(request.current_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read cluster",
+                    format!("Unable to read cluster status: {}", e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        // The credentials are only used to join, the rest forces a new
+        // resource
+        UpdateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: vec![],
+            new_identity: None,
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // Removing a running node corrupts its view of the cluster, it has to
+        // be shut down and removed with pvecm delnode
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for ClusterJoinResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_cluster_join_test.rs"]
+mod resource_cluster_join_test;
@@ -0,0 +1,149 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn join_config(endpoint: &str, token: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), Dynamic::Unknown);
+        obj.insert(
+            "node_endpoint".to_string(),
+            Dynamic::String(endpoint.to_string()),
+        );
+        obj.insert(
+            "node_api_token".to_string(),
+            Dynamic::String(token.to_string()),
+        );
+        obj.insert("node_insecure".to_string(), Dynamic::Bool(true));
+        obj.insert(
+            "password".to_string(),
+            Dynamic::String("hunter2".to_string()),
+        );
+        obj.insert("hostname".to_string(), Dynamic::Unknown);
+        obj.insert("fingerprint".to_string(), Dynamic::Unknown);
+        obj.insert("links".to_string(), Dynamic::Null);
+        obj.insert("nodeid".to_string(), Dynamic::Null);
+        obj.insert("votes".to_string(), Dynamic::Null);
+        obj.insert("force".to_string(), Dynamic::Null);
+        obj.insert("node".to_string(), Dynamic::Unknown);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> ClusterJoinResource {
+        ClusterJoinResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_node_connection() {
+        let response = ClusterJoinResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_cluster_join".to_string(),
+                    config: join_config("10.0.0.12:8006", "root@pam!join"),
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let summaries: Vec<&str> = response
+            .diagnostics
+            .iter()
+            .map(|d| d.summary.as_str())
+            .collect();
+        assert_eq!(
+            summaries,
+            vec!["Invalid node endpoint", "Invalid node API token"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_joins_through_provider_node() {
+        // The provider and the joining node share the mock server, the paths
+        // they are asked for don't overlap
+        let mut server = Server::new_async().await;
+        let _info = server
+            .mock("GET", "/api2/json/cluster/config/join")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"preferred_node": "pve1", "nodelist": [
+                    {"name": "pve1", "nodeid": "1", "pve_addr": "10.0.0.11", "pve_fp": "AA:11", "quorum_votes": "1"}
+                ]}}"#,
+            )
+            .create_async()
+            .await;
+        let join = server
+            .mock("POST", "/api2/json/cluster/config/join")
+            .match_header("authorization", "PVEAPIToken=root@pam!join=node-secret")
+            .match_body(Matcher::Json(serde_json::json!({
+                "hostname": "10.0.0.11",
+                "fingerprint": "AA:11",
+                "password": "hunter2"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": "UPID:pve2:00002222:00003333:65000000:clusterjoin::root@pam:"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _status = server
+            .mock("GET", "/api2/json/cluster/status")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"id": "cluster", "type": "cluster", "name": "lab", "nodes": 2, "quorate": 1, "version": 2},
+                    {"id": "node/pve1", "type": "node", "name": "pve1", "nodeid": 1, "online": 1, "local": 1},
+                    {"id": "node/pve2", "type": "node", "name": "pve2", "nodeid": 2, "online": 1, "local": 0}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = join_config(&server.url(), "root@pam!join=node-secret");
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_cluster_join".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        join.assert_async().await;
+        let state = response.new_state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("node")).unwrap(),
+            "pve2"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("hostname")).unwrap(),
+            "10.0.0.11"
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("fingerprint"))
+                .unwrap(),
+            "AA:11"
+        );
+    }
+}
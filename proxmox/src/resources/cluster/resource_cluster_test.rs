@@ -0,0 +1,122 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn cluster_config(name: &str, links: &[&str]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), Dynamic::Unknown);
+        obj.insert("name".to_string(), Dynamic::String(name.to_string()));
+        obj.insert(
+            "links".to_string(),
+            Dynamic::List(
+                links
+                    .iter()
+                    .map(|link| Dynamic::String(link.to_string()))
+                    .collect(),
+            ),
+        );
+        obj.insert("nodeid".to_string(), Dynamic::Null);
+        obj.insert("votes".to_string(), Dynamic::Null);
+        obj.insert("node".to_string(), Dynamic::Unknown);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> ClusterResource {
+        ClusterResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_cluster_name() {
+        for (name, valid) in [
+            ("lab", true),
+            ("prod-east-01", true),
+            ("-lab", false),
+            ("lab_01", false),
+            ("a-very-long-cluster", false),
+        ] {
+            let response = ClusterResource::new()
+                .validate(
+                    Context::new(),
+                    ValidateResourceConfigRequest {
+                        type_name: "proxmox_cluster".to_string(),
+                        config: cluster_config(name, &[]),
+                        client_capabilities: ClientCapabilities {
+                            deferral_allowed: false,
+                            write_only_attributes_allowed: false,
+                        },
+                    },
+                )
+                .await;
+            assert_eq!(response.diagnostics.is_empty(), valid, "{}", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_waits_for_task() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/cluster/config")
+            .match_body(Matcher::Json(serde_json::json!({
+                "clustername": "lab",
+                "link0": "10.0.0.11"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": "UPID:pve1:00001234:00005678:65000000:clustercreate:lab:root@pam:"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let _task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve1/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"upid": "UPID:pve1:00001234:00005678:65000000:clustercreate:lab:root@pam:", "status": "stopped", "exitstatus": "OK"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = cluster_config("lab", &["10.0.0.11"]);
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_cluster".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        create.assert_async().await;
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("id"))
+                .unwrap(),
+            "lab"
+        );
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("node"))
+                .unwrap(),
+            "pve1"
+        );
+    }
+}
@@ -0,0 +1,837 @@
+//! HA resource implementation
+
+use crate::api::cluster::{
+    ClusterResourceType, CreateHaResourceRequest, HaResource, HaStatusEntry,
+    UpdateHaResourceRequest,
+};
+use crate::resources::comment::{
+    comment_attribute, comment_delete, comment_from_config, set_comment_state,
+};
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ModifyPlanRequest,
+    ModifyPlanResponse, ReadResourceRequest, ReadResourceResponse, Resource,
+    ResourceMetadataRequest, ResourceMetadataResponse, ResourceSchemaRequest,
+    ResourceSchemaResponse, ResourceWithConfigure, ResourceWithModifyPlan, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::{validate_integers, StringOneOfValidator};
+
+/// How long the HA manager may take to bring the guest into the requested
+/// state on the requested node.
+const HA_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// How often the HA status is checked while waiting
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// States the HA manager can be asked to keep the guest in
+const HA_STATES: &[&str] = &["started", "stopped", "disabled", "ignored"];
+
+/// State and restart limits Proxmox uses when they are not set
+const DEFAULT_STATE: &str = "started";
+const DEFAULT_MAX_RESTART: u32 = 1;
+const DEFAULT_MAX_RELOCATE: u32 = 1;
+
+const HA_ATTRIBUTES: &[&str] = &[
+    "sid",
+    "state",
+    "group",
+    "comment",
+    "max_restart",
+    "max_relocate",
+];
+
+/// Arguments that make the HA manager move or start/stop the guest when
+/// they change
+const ENFORCE_ATTRIBUTES: &[&str] = &["state", "node", "relocate"];
+
+/// VMID of a service ID such as "vm:100" or "ct:101"
+fn sid_vmid(sid: &str) -> Option<u32> {
+    let (kind, vmid) = sid.split_once(':')?;
+    if !matches!(kind, "vm" | "ct") || !vmid.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    vmid.parse().ok()
+}
+
+/// CRM state the service settles in once the requested state is reached.
+/// Ignored services are left alone by the HA manager, there is nothing to
+/// wait for.
+fn settled_crm_state(state: &str) -> Option<&'static str> {
+    match state {
+        "started" => Some("started"),
+        "stopped" | "disabled" => Some("stopped"),
+        _ => None,
+    }
+}
+
+/// Status entry of the service with the given ID
+fn service_entry<'e>(entries: &'e [HaStatusEntry], sid: &str) -> Option<&'e HaStatusEntry> {
+    entries
+        .iter()
+        .find(|entry| entry.is_type("service") && entry.sid.as_deref() == Some(sid))
+}
+
+/// HA settings taken from the configuration
+#[derive(Debug)]
+struct HaSettings {
+    sid: String,
+    state: String,
+    group: Option<String>,
+    comment: Option<String>,
+    max_restart: Option<u32>,
+    max_relocate: Option<u32>,
+    /// Node the guest should be on
+    node: Option<String>,
+    relocate: bool,
+}
+
+impl HaSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let sid = config
+            .get_string(&AttributePath::new("sid"))
+            .map_err(|_| Diagnostic::error("Missing sid", "The 'sid' attribute is required"))?;
+
+        Ok(Self {
+            sid,
+            state: config
+                .get_string(&AttributePath::new("state"))
+                .unwrap_or_else(|_| DEFAULT_STATE.to_string()),
+            group: config.get_string(&AttributePath::new("group")).ok(),
+            comment: comment_from_config(config),
+            max_restart: config.get_u32(&AttributePath::new("max_restart")).ok(),
+            max_relocate: config.get_u32(&AttributePath::new("max_relocate")).ok(),
+            node: config.get_string(&AttributePath::new("node")).ok(),
+            relocate: config
+                .get_bool(&AttributePath::new("relocate"))
+                .unwrap_or(false),
+        })
+    }
+
+    fn create_request(&self) -> CreateHaResourceRequest {
+        CreateHaResourceRequest {
+            sid: self.sid.clone(),
+            state: Some(self.state.clone()),
+            group: self.group.clone(),
+            comment: self.comment.clone(),
+            max_restart: self.max_restart,
+            max_relocate: self.max_relocate,
+        }
+    }
+
+    /// Settings removed from the configuration are cleared through
+    /// `delete`, which puts the restart limits back to their defaults
+    fn update_request(
+        &self,
+        prior: &DynamicValue,
+        config: &DynamicValue,
+    ) -> UpdateHaResourceRequest {
+        let mut cleared: Vec<String> = [
+            ("group", self.group.is_none()),
+            ("max_restart", self.max_restart.is_none()),
+            ("max_relocate", self.max_relocate.is_none()),
+        ]
+        .into_iter()
+        .filter(|(_, cleared)| *cleared)
+        .map(|(name, _)| name.to_string())
+        .collect();
+        cleared.extend(comment_delete(prior, config));
+
+        UpdateHaResourceRequest {
+            state: Some(self.state.clone()),
+            group: self.group.clone(),
+            comment: self.comment.clone(),
+            max_restart: self.max_restart,
+            max_relocate: self.max_relocate,
+            delete: (!cleared.is_empty()).then(|| cleared.join(",")),
+        }
+    }
+
+    /// Node the guest has to be moved to, if any. The HA manager only moves
+    /// guests it keeps started or stopped.
+    fn move_target(&self) -> Option<&str> {
+        self.node
+            .as_deref()
+            .filter(|_| matches!(self.state.as_str(), "started" | "stopped"))
+    }
+}
+
+/// Copies the HA configuration into state. Settings left at the Proxmox
+/// default stay null when they are not in state already.
+fn set_ha_state(state: &mut DynamicValue, resource: &HaResource) {
+    let keep = |state: &DynamicValue, name: &str, is_default: bool| {
+        is_default
+            && matches!(
+                state.get(&AttributePath::new(name)),
+                Ok(Dynamic::Null) | Err(_)
+            )
+    };
+
+    let ha_state = resource.state.as_deref().unwrap_or(DEFAULT_STATE);
+    if !keep(state, "state", ha_state == DEFAULT_STATE) {
+        let _ = state.set_string(&AttributePath::new("state"), ha_state.to_string());
+    }
+    let _ = match &resource.group {
+        Some(group) => state.set_string(&AttributePath::new("group"), group.clone()),
+        None => state.set_null(&AttributePath::new("group")),
+    };
+    set_comment_state(state, resource.comment.as_deref());
+    for (name, value, default) in [
+        ("max_restart", resource.max_restart, DEFAULT_MAX_RESTART),
+        ("max_relocate", resource.max_relocate, DEFAULT_MAX_RELOCATE),
+    ] {
+        let value = value.unwrap_or(default);
+        if !keep(state, name, value == default) {
+            let _ = state.set_number(&AttributePath::new(name), value as f64);
+        }
+    }
+}
+
+/// Copies where the guest runs and what the CRM is doing with it into
+/// state. Both are null while the HA manager has not picked it up yet.
+fn set_service_state(state: &mut DynamicValue, entry: Option<&HaStatusEntry>) {
+    let fields = [
+        ("current_node", entry.and_then(|e| e.node.clone())),
+        ("crm_state", entry.and_then(|e| e.crm_state.clone())),
+    ];
+    for (name, value) in fields {
+        let _ = match value {
+            Some(value) => state.set_string(&AttributePath::new(name), value),
+            None => state.set_null(&AttributePath::new(name)),
+        };
+    }
+}
+
+/// Puts a guest under the control of the HA manager, which then keeps it
+/// in the requested state and recovers it onto another node when its node
+/// fails. Setting `node` moves the guest there and keeps it there.
+#[derive(Default)]
+pub struct HaResourceResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl HaResourceResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Node the guest is on according to the cluster resources, which also
+    /// know guests the HA manager has not picked up yet
+    async fn guest_node(
+        provider_data: &crate::ProxmoxProviderData,
+        sid: &str,
+    ) -> Result<Option<String>, crate::api::ApiError> {
+        let vmid = sid_vmid(sid);
+        let guests = provider_data
+            .client
+            .cluster()
+            .resources(Some(ClusterResourceType::Vm))
+            .await?;
+        Ok(guests
+            .into_iter()
+            .find(|guest| guest.vmid.is_some() && guest.vmid == vmid)
+            .and_then(|guest| guest.node))
+    }
+
+    /// Asks the HA manager to move the guest when it is not on the
+    /// configured node, then waits until the CRM reports it settled in the
+    /// requested state on that node. Returns the service's status entry.
+    async fn enforce(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &HaSettings,
+        timeout: Duration,
+    ) -> Result<Option<HaStatusEntry>, Vec<Diagnostic>> {
+        let ha_api = provider_data.client.cluster().ha();
+        let sid = settings.sid.as_str();
+        let deadline = Instant::now() + timeout;
+
+        if let Some(target) = settings.move_target() {
+            let current = Self::guest_node(provider_data, sid).await.map_err(|e| {
+                vec![Diagnostic::error(
+                    "Failed to find HA guest",
+                    format!("Unable to look up the node of '{}': {}", sid, e),
+                )]
+            })?;
+            if current.as_deref() != Some(target) {
+                let requested = if settings.relocate {
+                    ha_api.relocate_resource(sid, target).await
+                } else {
+                    ha_api.migrate_resource(sid, target).await
+                };
+                requested.map_err(|e| {
+                    api_error_diagnostics(
+                        &format!("Failed to move '{}' to node '{}'", sid, target),
+                        &e,
+                        &["node"],
+                    )
+                })?;
+            }
+        }
+
+        let Some(settled) = settled_crm_state(&settings.state) else {
+            let status = ha_api.current_status().await.unwrap_or_default();
+            return Ok(service_entry(&status, sid).cloned());
+        };
+        loop {
+            if let Ok(status) = ha_api.current_status().await {
+                if let Some(entry) = service_entry(&status, sid) {
+                    if entry.crm_state.as_deref() == Some("error") {
+                        return Err(vec![Diagnostic::error(
+                            "HA service failed",
+                            format!(
+                                "The HA manager put '{}' into the error state: {}. Set state to disabled to recover it",
+                                sid,
+                                entry.status.as_deref().unwrap_or("no status")
+                            ),
+                        )]);
+                    }
+                    let on_node = settings
+                        .move_target()
+                        .map_or(true, |target| entry.node.as_deref() == Some(target));
+                    if entry.crm_state.as_deref() == Some(settled) && on_node {
+                        return Ok(Some(entry.clone()));
+                    }
+                }
+            }
+            if Instant::now() >= deadline {
+                let place = settings
+                    .move_target()
+                    .map(|node| format!(" on node '{}'", node))
+                    .unwrap_or_default();
+                return Err(vec![Diagnostic::error(
+                    "Timed out waiting for HA service",
+                    format!(
+                        "'{}' was not {}{} within {} seconds",
+                        sid,
+                        settled,
+                        place,
+                        timeout.as_secs()
+                    ),
+                )]);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(STATUS_POLL_INTERVAL) => {}
+                _ = ctx.cancelled() => {
+                    return Err(vec![Diagnostic::error(
+                        "Stopped waiting for HA service",
+                        format!(
+                            "Terraform was interrupted before '{}' was {}. The HA manager carries on without it",
+                            sid, settled
+                        ),
+                    )]);
+                }
+            }
+        }
+    }
+
+    /// Reads the HA configuration and service status back into state
+    async fn read_back(
+        provider_data: &crate::ProxmoxProviderData,
+        sid: &str,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let ha_api = provider_data.client.cluster().ha();
+        let resource = ha_api.get_resource(sid).await.map_err(|e| {
+            Diagnostic::error(
+                "Failed to read HA resource",
+                format!("Unable to read HA resource '{}': {}", sid, e),
+            )
+        })?;
+        set_ha_state(&mut state, &resource);
+        let status = ha_api.current_status().await.unwrap_or_default();
+        set_service_state(&mut state, service_entry(&status, sid));
+        Ok(state)
+    }
+
+    /// Runs `enforce` and reads the result back, as create and update both
+    /// finish
+    async fn apply(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &HaSettings,
+        timeout: Duration,
+        planned_state: DynamicValue,
+    ) -> Result<DynamicValue, Vec<Diagnostic>> {
+        let entry = Self::enforce(ctx, provider_data, settings, timeout).await?;
+        let mut new_state = Self::read_back(provider_data, &settings.sid, planned_state)
+            .await
+            .map_err(|diag| vec![diag])?;
+        if entry.is_some() {
+            set_service_state(&mut new_state, entry.as_ref());
+        }
+        Ok(new_state)
+    }
+}
+
+#[async_trait]
+impl Resource for HaResourceResource {
+    fn type_name(&self) -> &str {
+        "proxmox_ha_resource"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages a guest with the HA manager, which keeps it in the requested state and recovers it onto another node when its node fails. Applying waits until the cluster resource manager (CRM) reports the guest in that state")
+            .attribute(
+                AttributeBuilder::new("sid", AttributeType::String)
+                    .description("Service ID of the guest, e.g. vm:100 or ct:101")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("state", AttributeType::String)
+                    .description("State the HA manager keeps the guest in: started, stopped, disabled or ignored (default: started)")
+                    .optional()
+                    .validator(StringOneOfValidator::create(
+                        HA_STATES.iter().map(|state| state.to_string()).collect(),
+                    ))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("group", AttributeType::String)
+                    .description("HA group restricting the nodes the guest may run on")
+                    .optional()
+                    .build(),
+            )
+            .attribute(comment_attribute("HA resource"))
+            .attribute(
+                AttributeBuilder::new("max_restart", AttributeType::Number)
+                    .description("Restarts on the same node after a failed start (default: 1)")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("max_relocate", AttributeType::Number)
+                    .description("Relocations to another node after a failed start (default: 1)")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("Node the guest should run on. When it is found elsewhere, applying asks the HA manager to move it back. Only used with state started or stopped")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("relocate", AttributeType::Bool)
+                    .description("Move the guest by stopping it and starting it on the new node instead of migrating it live (default: false)")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("current_node", AttributeType::String)
+                    .description("Node the guest runs on according to the HA manager")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("crm_state", AttributeType::String)
+                    .description("State the CRM reports for the guest, e.g. started, stopped, migrate or error")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create, Operation::Update]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["max_restart", "max_relocate"]);
+
+        if let Ok(sid) = config.get_string(&AttributePath::new("sid")) {
+            if sid_vmid(&sid).is_none() {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("sid"),
+                    "Invalid sid",
+                    format!("sid must look like vm:<vmid> or ct:<vmid>, got '{}'", sid),
+                ));
+            }
+        }
+
+        if config.get_string(&AttributePath::new("node")).is_ok() {
+            if let Ok(state) = config.get_string(&AttributePath::new("state")) {
+                if matches!(state.as_str(), "disabled" | "ignored") {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new("node"),
+                        "Invalid node",
+                        format!("The HA manager doesn't move guests in state {}", state),
+                    ));
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match HaSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        if let Err(e) = provider_data
+            .client
+            .cluster()
+            .ha()
+            .create_resource(&settings.create_request())
+            .await
+        {
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics(
+                    "Failed to create HA resource",
+                    &e,
+                    HA_ATTRIBUTES,
+                ),
+            };
+        }
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, HA_TIMEOUT);
+        let planned_state = request.planned_state.clone();
+        match Self::apply(&ctx, provider_data, &settings, timeout, planned_state).await {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            },
+            Err(diagnostics) => {
+                // The guest is HA managed by now, so it has to be in state
+                // for the next apply to finish or remove it
+                let mut new_state = request.planned_state;
+                set_service_state(&mut new_state, None);
+                CreateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics,
+                }
+            }
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let sid = match request.current_state.get_string(&AttributePath::new("sid")) {
+            Ok(sid) => sid,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let ha_api = provider_data.client.cluster().ha();
+        match ha_api.get_resource(&sid).await {
+            Ok(resource) => {
+                let mut new_state = request.current_state.clone();
+                set_ha_state(&mut new_state, &resource);
+                let status = ha_api.current_status().await.unwrap_or_default();
+                set_service_state(&mut new_state, service_entry(&status, &sid));
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read HA resource",
+                    format!("Unable to read HA resource '{}': {}", sid, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match HaSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                    new_identity: None,
+                };
+            }
+        };
+
+        if let Err(e) = provider_data
+            .client
+            .cluster()
+            .ha()
+            .update_resource(
+                &settings.sid,
+                &settings.update_request(&request.prior_state, &request.config),
+            )
+            .await
+        {
+            return UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics(
+                    "Failed to update HA resource",
+                    &e,
+                    HA_ATTRIBUTES,
+                ),
+                new_identity: None,
+            };
+        }
+
+        let timeout = Timeouts::new(&request.config).update(&ctx, HA_TIMEOUT);
+        match Self::apply(
+            &ctx,
+            provider_data,
+            &settings,
+            timeout,
+            request.planned_state,
+        )
+        .await
+        {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            Err(diagnostics) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let sid = match request.prior_state.get_string(&AttributePath::new("sid")) {
+            Ok(sid) => sid,
+            Err(_) => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![],
+                }
+            }
+        };
+
+        let diagnostics = match provider_data
+            .client
+            .cluster()
+            .ha()
+            .delete_resource(&sid)
+            .await
+        {
+            Ok(()) => vec![],
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => vec![Diagnostic::error(
+                "Failed to delete HA resource",
+                format!("API error: {}", e),
+            )],
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for HaResourceResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ResourceWithModifyPlan for HaResourceResource {
+    /// Plans the service status as unknown whenever applying is going to
+    /// change it: on create, when the requested state or node changes, and
+    /// when the guest has left the configured node since the last apply
+    async fn modify_plan(&self, _ctx: Context, request: ModifyPlanRequest) -> ModifyPlanResponse {
+        let mut response = ModifyPlanResponse {
+            planned_state: request.proposed_new_state,
+            requires_replace: vec![],
+            planned_private: request.prior_private,
+            diagnostics: vec![],
+        };
+        if response.planned_state.is_null() {
+            return response;
+        }
+
+        let changed = |name: &str| {
+            let path = AttributePath::new(name);
+            request.prior_state.get(&path).ok() != response.planned_state.get(&path).ok()
+        };
+        let moved = match (
+            response
+                .planned_state
+                .get_string(&AttributePath::new("node")),
+            request
+                .prior_state
+                .get_string(&AttributePath::new("current_node")),
+        ) {
+            (Ok(node), Ok(current)) => node != current,
+            _ => false,
+        };
+        let state = response
+            .planned_state
+            .get_string(&AttributePath::new("state"))
+            .unwrap_or_else(|_| DEFAULT_STATE.to_string());
+        let enforced = moved && matches!(state.as_str(), "started" | "stopped");
+
+        if request.prior_state.is_null()
+            || enforced
+            || ENFORCE_ATTRIBUTES.iter().any(|name| changed(name))
+        {
+            for name in ["current_node", "crm_state"] {
+                let _ = response
+                    .planned_state
+                    .mark_unknown(&AttributePath::new(name));
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_ha_resource_test.rs"]
+mod resource_ha_resource_test;
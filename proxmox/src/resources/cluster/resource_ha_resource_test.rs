@@ -0,0 +1,357 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn ha_config(state: Option<&str>, node: Option<&str>) -> DynamicValue {
+        let string =
+            |value: Option<&str>| value.map_or(Dynamic::Null, |v| Dynamic::String(v.to_string()));
+        let mut obj = HashMap::new();
+        obj.insert("sid".to_string(), Dynamic::String("vm:100".to_string()));
+        obj.insert("state".to_string(), string(state));
+        obj.insert("group".to_string(), Dynamic::Null);
+        obj.insert("comment".to_string(), Dynamic::Null);
+        obj.insert("max_restart".to_string(), Dynamic::Null);
+        obj.insert("max_relocate".to_string(), Dynamic::Null);
+        obj.insert("node".to_string(), string(node));
+        obj.insert("relocate".to_string(), Dynamic::Null);
+        obj.insert("current_node".to_string(), Dynamic::Unknown);
+        obj.insert("crm_state".to_string(), Dynamic::Unknown);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> HaResourceResource {
+        HaResourceResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    async fn mock_status(server: &mut Server, node: &str, crm_state: &str) -> mockito::Mock {
+        server
+            .mock("GET", "/api2/json/cluster/ha/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": [
+                    {{"id": "quorum", "type": "quorum", "node": "pve1", "status": "OK", "quorate": 1}},
+                    {{"id": "service:vm:100", "type": "service", "sid": "vm:100", "node": "{}", "crm_state": "{}", "state": "{}", "request_state": "started"}}
+                ]}}"#,
+                node, crm_state, crm_state
+            ))
+            .create_async()
+            .await
+    }
+
+    async fn mock_get(server: &mut Server, state: &str) -> mockito::Mock {
+        server
+            .mock("GET", "/api2/json/cluster/ha/resources/vm:100")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"sid": "vm:100", "type": "vm", "state": "{}", "max_restart": 1, "max_relocate": 1, "digest": "abc"}}}}"#,
+                state
+            ))
+            .create_async()
+            .await
+    }
+
+    async fn plan(prior_state: DynamicValue, proposed: DynamicValue) -> ModifyPlanResponse {
+        HaResourceResource::new()
+            .modify_plan(
+                Context::new(),
+                ModifyPlanRequest {
+                    type_name: "proxmox_ha_resource".to_string(),
+                    config: proposed.clone(),
+                    prior_state,
+                    proposed_new_state: proposed,
+                    prior_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+    }
+
+    fn applied(node: Option<&str>, current_node: &str) -> DynamicValue {
+        let mut state = ha_config(None, node);
+        let _ = state.set_string(
+            &AttributePath::new("current_node"),
+            current_node.to_string(),
+        );
+        let _ = state.set_string(&AttributePath::new("crm_state"), "started".to_string());
+        state
+    }
+
+    #[test]
+    fn test_sid_vmid() {
+        assert_eq!(sid_vmid("vm:100"), Some(100));
+        assert_eq!(sid_vmid("ct:101"), Some(101));
+        assert_eq!(sid_vmid("100"), None);
+        assert_eq!(sid_vmid("vm:"), None);
+        assert_eq!(sid_vmid("vm:1a"), None);
+        assert_eq!(sid_vmid("qemu:100"), None);
+    }
+
+    #[test]
+    fn test_settled_crm_state() {
+        assert_eq!(settled_crm_state("started"), Some("started"));
+        assert_eq!(settled_crm_state("stopped"), Some("stopped"));
+        assert_eq!(settled_crm_state("disabled"), Some("stopped"));
+        assert_eq!(settled_crm_state("ignored"), None);
+    }
+
+    #[test]
+    fn test_update_request_clears_removed_settings() {
+        let mut config = ha_config(Some("stopped"), None);
+        let _ = config.set_string(&AttributePath::new("comment"), "web".to_string());
+        let _ = config.set_number(&AttributePath::new("max_restart"), 3.0);
+
+        let request = HaSettings::from_config(&config)
+            .unwrap()
+            .update_request(&config, &config);
+
+        assert_eq!(request.state.as_deref(), Some("stopped"));
+        assert_eq!(request.comment.as_deref(), Some("web"));
+        assert_eq!(request.max_restart, Some(3));
+        assert_eq!(request.delete.as_deref(), Some("group,max_relocate"));
+    }
+
+    #[test]
+    fn test_update_request_normalizes_comment() {
+        let mut prior = ha_config(None, None);
+        let _ = prior.set_string(&AttributePath::new("comment"), "web".to_string());
+        let mut config = ha_config(None, None);
+        let _ = config.set_string(&AttributePath::new("comment"), String::new());
+
+        let request = HaSettings::from_config(&config)
+            .unwrap()
+            .update_request(&prior, &config);
+        assert_eq!(request.comment, None);
+        assert_eq!(
+            request.delete.as_deref(),
+            Some("group,max_restart,max_relocate,comment")
+        );
+
+        // Nothing to delete when there was no comment before
+        let request = HaSettings::from_config(&config)
+            .unwrap()
+            .update_request(&config, &config);
+        assert_eq!(
+            request.delete.as_deref(),
+            Some("group,max_restart,max_relocate")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_sid_and_node() {
+        let mut config = ha_config(Some("disabled"), Some("pve2"));
+        let _ = config.set_string(&AttributePath::new("sid"), "100".to_string());
+
+        let response = HaResourceResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_ha_resource".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let summaries: Vec<&str> = response
+            .diagnostics
+            .iter()
+            .map(|d| d.summary.as_str())
+            .collect();
+        assert_eq!(summaries, ["Invalid sid", "Invalid node"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_migrates_and_waits() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/cluster/ha/resources")
+            .match_body(Matcher::Json(serde_json::json!({
+                "sid": "vm:100",
+                "state": "started"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _guests = server
+            .mock("GET", "/api2/json/cluster/resources")
+            .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"id": "qemu/100", "type": "qemu", "vmid": 100, "node": "pve1", "hastate": "started"}]}"#,
+            )
+            .create_async()
+            .await;
+        let migrate = server
+            .mock("POST", "/api2/json/cluster/ha/resources/vm:100/migrate")
+            .match_body(Matcher::Json(serde_json::json!({"node": "pve2"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _status = mock_status(&mut server, "pve2", "started").await;
+        let _get = mock_get(&mut server, "started").await;
+
+        let config = ha_config(None, Some("pve2"));
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_ha_resource".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        create.assert_async().await;
+        migrate.assert_async().await;
+        let state = &response.new_state;
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("current_node"))
+                .unwrap(),
+            "pve2"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("crm_state")).unwrap(),
+            "started"
+        );
+        // Defaults the configuration left out stay out of state
+        assert_eq!(
+            state.get(&AttributePath::new("state")).unwrap(),
+            &Dynamic::Null
+        );
+        assert_eq!(
+            state.get(&AttributePath::new("max_restart")).unwrap(),
+            &Dynamic::Null
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_fails_on_error_state() {
+        let mut server = Server::new_async().await;
+        let _update = server
+            .mock("PUT", "/api2/json/cluster/ha/resources/vm:100")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+        let _status = mock_status(&mut server, "pve1", "error").await;
+
+        let config = ha_config(Some("stopped"), None);
+        let response = configured_resource(&server.url())
+            .update(
+                Context::new(),
+                UpdateResourceRequest {
+                    type_name: "proxmox_ha_resource".to_string(),
+                    prior_state: applied(None, "pve1"),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                    planned_identity: None,
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "HA service failed");
+    }
+
+    #[tokio::test]
+    async fn test_read_reports_service_status() {
+        let mut server = Server::new_async().await;
+        let _get = mock_get(&mut server, "stopped").await;
+        let _status = mock_status(&mut server, "pve3", "stopped").await;
+
+        let response = configured_resource(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_ha_resource".to_string(),
+                    current_state: applied(None, "pve1"),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        let state = response.new_state.unwrap();
+        assert_eq!(
+            state.get_string(&AttributePath::new("state")).unwrap(),
+            "stopped"
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("current_node"))
+                .unwrap(),
+            "pve3"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("crm_state")).unwrap(),
+            "stopped"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_moves_guest_back_to_node() {
+        let is_unknown = |response: &ModifyPlanResponse| {
+            matches!(
+                response
+                    .planned_state
+                    .get(&AttributePath::new("crm_state"))
+                    .unwrap(),
+                Dynamic::Unknown
+            )
+        };
+
+        // Guest still on the configured node, nothing to do
+        let prior = applied(Some("pve1"), "pve1");
+        assert!(!is_unknown(&plan(prior.clone(), prior).await));
+
+        // Recovered onto another node since the last apply
+        let prior = applied(Some("pve1"), "pve2");
+        assert!(is_unknown(&plan(prior.clone(), prior).await));
+
+        // Without a node the HA manager may place the guest anywhere
+        let prior = applied(None, "pve2");
+        assert!(!is_unknown(&plan(prior.clone(), prior).await));
+
+        // Requesting another state
+        let prior = applied(None, "pve1");
+        let mut proposed = prior.clone();
+        let _ = proposed.set_string(&AttributePath::new("state"), "stopped".to_string());
+        assert!(is_unknown(&plan(prior, proposed).await));
+    }
+}
@@ -0,0 +1,526 @@
+//! Directory mapping resource implementation
+
+use crate::api::cluster::{
+    CreateDirMappingRequest, DirMapEntry, DirMapping, UpdateDirMappingRequest,
+};
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::model::{TfBlock, TfSchema};
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+const MAPPING_ATTRIBUTES: &[&str] = &["id", "description", "map"];
+
+/// Directory on one node
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct DirMap {
+    /// Node the directory is on
+    node: String,
+    /// Absolute path of the directory on the node
+    path: String,
+}
+
+impl From<DirMapEntry> for DirMap {
+    fn from(entry: DirMapEntry) -> Self {
+        Self {
+            node: entry.node,
+            path: entry.path,
+        }
+    }
+}
+
+impl DirMap {
+    fn to_property_string(&self) -> String {
+        DirMapEntry {
+            node: self.node.clone(),
+            path: self.path.clone(),
+        }
+        .to_property_string()
+    }
+}
+
+/// Mapping settings taken from the configuration
+#[derive(Debug)]
+struct MappingSettings {
+    id: String,
+    description: Option<String>,
+    map: Vec<String>,
+}
+
+impl MappingSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let id = config
+            .get_string(&AttributePath::new("id"))
+            .map_err(|_| Diagnostic::error("Missing id", "The 'id' attribute is required"))?;
+        let map = config
+            .get(&AttributePath::new("map"))
+            .ok()
+            .map(Vec::<DirMap>::from_block)
+            .transpose()
+            .map_err(|e| Diagnostic::error("Invalid map", e.to_string()))?
+            .unwrap_or_default();
+
+        Ok(Self {
+            id,
+            description: config.get_string(&AttributePath::new("description")).ok(),
+            map: map.iter().map(DirMap::to_property_string).collect(),
+        })
+    }
+
+    fn create_request(&self) -> CreateDirMappingRequest {
+        CreateDirMappingRequest {
+            id: self.id.clone(),
+            description: self.description.clone(),
+            map: self.map.clone(),
+        }
+    }
+
+    /// A description removed from the configuration is cleared through
+    /// `delete`
+    fn update_request(&self) -> UpdateDirMappingRequest {
+        UpdateDirMappingRequest {
+            description: self.description.clone(),
+            map: self.map.clone(),
+            delete: self
+                .description
+                .is_none()
+                .then(|| "description".to_string()),
+        }
+    }
+}
+
+/// Copies what the API reports into state
+fn set_mapping_state(state: &mut DynamicValue, mapping: &DirMapping) {
+    let _ = match &mapping.description {
+        Some(description) => {
+            state.set_string(&AttributePath::new("description"), description.clone())
+        }
+        None => state.set_null(&AttributePath::new("description")),
+    };
+    let map: Vec<DirMap> = mapping.entries().into_iter().map(DirMap::from).collect();
+    let _ = match map.to_block() {
+        Dynamic::List(map) => state.set_list(&AttributePath::new("map"), map),
+        _ => Ok(()),
+    };
+}
+
+/// Manages a cluster-wide directory mapping, which virtiofs shares of VMs
+/// refer to by ID
+#[derive(Default)]
+pub struct MappingDirResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl MappingDirResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn read_back(
+        provider_data: &crate::ProxmoxProviderData,
+        id: &str,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let mapping = provider_data
+            .client
+            .cluster()
+            .mapping()
+            .get_dir(id)
+            .await
+            .map_err(|e| {
+                Diagnostic::error(
+                    "Failed to read directory mapping",
+                    format!("Unable to read directory mapping '{}': {}", id, e),
+                )
+            })?;
+        set_mapping_state(&mut state, &mapping);
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl Resource for MappingDirResource {
+    fn type_name(&self) -> &str {
+        "proxmox_mapping_dir"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages a directory mapping, the cluster-wide ID virtiofs shares of VMs refer to. Needs Proxmox VE 8.4 or newer")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The mapping identifier")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("description", AttributeType::String)
+                    .description("Description of the mapping")
+                    .optional()
+                    .build(),
+            )
+            .block(Vec::<DirMap>::nested_block(
+                "map",
+                "Directory the mapping points at on a node. VMs using the mapping can only run on nodes with an entry",
+            ))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = vec![];
+
+        if let Ok(id) = config.get_string(&AttributePath::new("id")) {
+            let mut chars = id.chars();
+            let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                && id.len() > 1;
+            if !valid {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("id"),
+                    "Invalid id",
+                    "id must start with a letter and only contain letters, digits, '-' and '_'",
+                ));
+            }
+        }
+
+        match config.get(&AttributePath::new("map")) {
+            Ok(Dynamic::List(map)) if !map.is_empty() => {
+                let mut seen = std::collections::HashSet::new();
+                for (index, entry) in map.iter().enumerate() {
+                    let Dynamic::Map(entry) = entry else {
+                        continue;
+                    };
+                    let path = AttributePath::new("map").index(index as i64);
+                    if let Some(Dynamic::String(node)) = entry.get("node") {
+                        if !seen.insert(node.clone()) {
+                            diagnostics.push(Diagnostic::attribute_error(
+                                path.clone().attribute("node"),
+                                "Duplicate node",
+                                format!("Node '{}' has more than one map entry", node),
+                            ));
+                        }
+                    }
+                    if let Some(Dynamic::String(dir)) = entry.get("path") {
+                        if !dir.starts_with('/') {
+                            diagnostics.push(Diagnostic::attribute_error(
+                                path.attribute("path"),
+                                "Invalid path",
+                                format!("path must be absolute, got '{}'", dir),
+                            ));
+                        }
+                    }
+                }
+            }
+            Ok(Dynamic::Unknown) => {}
+            _ => diagnostics.push(Diagnostic::error(
+                "Missing map",
+                "At least one map block is required",
+            )),
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match MappingSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        if let Err(e) = provider_data
+            .client
+            .cluster()
+            .mapping()
+            .create_dir(&settings.create_request())
+            .await
+        {
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics(
+                    "Failed to create directory mapping",
+                    &e,
+                    MAPPING_ATTRIBUTES,
+                ),
+            };
+        }
+
+        match Self::read_back(provider_data, &settings.id, request.planned_state.clone()).await {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            },
+            Err(diag) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![diag],
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let id = match request.current_state.get_string(&AttributePath::new("id")) {
+            Ok(id) => id,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data.client.cluster().mapping().get_dir(&id).await {
+            Ok(mapping) => {
+                let mut new_state = request.current_state.clone();
+                set_mapping_state(&mut new_state, &mapping);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read directory mapping",
+                    format!("Unable to read directory mapping '{}': {}", id, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match MappingSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                    new_identity: None,
+                };
+            }
+        };
+
+        if let Err(e) = provider_data
+            .client
+            .cluster()
+            .mapping()
+            .update_dir(&settings.id, &settings.update_request())
+            .await
+        {
+            return UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics(
+                    "Failed to update directory mapping",
+                    &e,
+                    MAPPING_ATTRIBUTES,
+                ),
+                new_identity: None,
+            };
+        }
+
+        match Self::read_back(provider_data, &settings.id, request.planned_state).await {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            Err(diag) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: vec![diag],
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let id = match request.prior_state.get_string(&AttributePath::new("id")) {
+            Ok(id) => id,
+            Err(_) => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![],
+                }
+            }
+        };
+
+        let diagnostics = match provider_data
+            .client
+            .cluster()
+            .mapping()
+            .delete_dir(&id)
+            .await
+        {
+            Ok(()) => vec![],
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => vec![Diagnostic::error(
+                "Failed to delete directory mapping",
+                format!("API error: {}", e),
+            )],
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for MappingDirResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_mapping_dir_test.rs"]
+mod resource_mapping_dir_test;
@@ -0,0 +1,166 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn map_entry(node: &str, path: &str) -> Dynamic {
+        let mut entry = HashMap::new();
+        entry.insert("node".to_string(), Dynamic::String(node.to_string()));
+        entry.insert("path".to_string(), Dynamic::String(path.to_string()));
+        Dynamic::Map(entry)
+    }
+
+    fn mapping_config(description: Option<&str>, map: Vec<Dynamic>) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), Dynamic::String("share".to_string()));
+        obj.insert(
+            "description".to_string(),
+            description.map_or(Dynamic::Null, |d| Dynamic::String(d.to_string())),
+        );
+        obj.insert("map".to_string(), Dynamic::List(map));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> MappingDirResource {
+        MappingDirResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_map_entries() {
+        let config = mapping_config(
+            None,
+            vec![
+                map_entry("pve1", "/srv/share"),
+                map_entry("pve1", "/mnt/share"),
+                map_entry("pve2", "srv/share"),
+            ],
+        );
+
+        let response = MappingDirResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_mapping_dir".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let summaries: Vec<&str> = response
+            .diagnostics
+            .iter()
+            .map(|d| d.summary.as_str())
+            .collect();
+        assert_eq!(summaries, ["Duplicate node", "Invalid path"]);
+        assert_eq!(
+            response.diagnostics[1].attribute,
+            Some(AttributePath::new("map").index(2).attribute("path"))
+        );
+    }
+
+    #[test]
+    fn test_update_request_clears_removed_description() {
+        let config = mapping_config(None, vec![map_entry("pve1", "/srv/share")]);
+
+        let request = MappingSettings::from_config(&config)
+            .unwrap()
+            .update_request();
+
+        assert_eq!(request.map, vec!["node=pve1,path=/srv/share".to_string()]);
+        assert_eq!(request.delete.as_deref(), Some("description"));
+    }
+
+    #[tokio::test]
+    async fn test_create_reads_back_mapping() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/cluster/mapping/dir")
+            .match_body(Matcher::Json(serde_json::json!({
+                "id": "share",
+                "description": "Shared datasets",
+                "map": ["node=pve1,path=/srv/share", "node=pve2,path=/mnt/share"]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _get = server
+            .mock("GET", "/api2/json/cluster/mapping/dir/share")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"description": "Shared datasets", "digest": "abc",
+                "map": ["node=pve1,path=/srv/share", "node=pve2,path=/mnt/share"]}}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = mapping_config(
+            Some("Shared datasets"),
+            vec![
+                map_entry("pve1", "/srv/share"),
+                map_entry("pve2", "/mnt/share"),
+            ],
+        );
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_mapping_dir".to_string(),
+                    planned_state: config.clone(),
+                    config: config.clone(),
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        create.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(response.new_state, config);
+    }
+
+    #[tokio::test]
+    async fn test_read_drops_missing_mapping() {
+        let mut server = Server::new_async().await;
+        let _get = server
+            .mock("GET", "/api2/json/cluster/mapping/dir/share")
+            .with_status(404)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null, "message": "no such directory mapping 'share'"}"#)
+            .create_async()
+            .await;
+
+        let response = configured_resource(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_mapping_dir".to_string(),
+                    current_state: mapping_config(None, vec![map_entry("pve1", "/srv/share")]),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        assert!(response.new_state.is_none());
+        assert!(response.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+//! Shared handling of the Proxmox `comment` field
+//!
+//! Most auxiliary objects (realms, storage, pools, firewall rules, backup jobs,
+//! HA groups) carry a free-form comment. Proxmox may hand it back with a
+//! trailing newline and drops empty comments entirely, so resources go through
+//! these helpers to keep the value stable across plan and refresh.
+
+use tfplug::schema::{Attribute, AttributeBuilder, AttributeType};
+use tfplug::types::{AttributePath, DynamicValue};
+
+/// Name of the attribute and of the API parameter
+pub(crate) const COMMENT: &str = "comment";
+
+/// Builds the optional `comment` schema attribute
+pub(crate) fn comment_attribute(object: &str) -> Attribute {
+    AttributeBuilder::new(COMMENT, AttributeType::String)
+        .description(&format!("Free-form comment describing the {}", object))
+        .optional()
+        .build()
+}
+
+/// Normalizes a comment the way Proxmox stores it. Trailing whitespace is
+/// dropped and an empty comment is the same as no comment.
+pub(crate) fn normalize_comment(comment: Option<&str>) -> Option<String> {
+    comment
+        .map(str::trim_end)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+}
+
+/// Reads the normalized comment from a config, plan or state value
+pub(crate) fn comment_from_config(config: &DynamicValue) -> Option<String> {
+    normalize_comment(
+        config
+            .get_string(&AttributePath::new(COMMENT))
+            .ok()
+            .as_deref(),
+    )
+}
+
+/// Writes the comment returned by the API into state. When it is equal to the
+/// value already in state after normalization, the user's spelling is kept so
+/// that refresh does not report a diff.
+pub(crate) fn set_comment_state(state: &mut DynamicValue, api_comment: Option<&str>) {
+    let remote = normalize_comment(api_comment);
+    if comment_from_config(state) == remote {
+        return;
+    }
+
+    let path = AttributePath::new(COMMENT);
+    let _ = match remote {
+        Some(comment) => state.set_string(&path, comment),
+        None => state.set_null(&path),
+    };
+}
+
+/// Returns the `delete` parameter needed when a comment was removed. Proxmox
+/// keeps the old value if the field is simply omitted from an update.
+pub(crate) fn comment_delete(prior: &DynamicValue, planned: &DynamicValue) -> Option<String> {
+    if comment_from_config(prior).is_some() && comment_from_config(planned).is_none() {
+        Some(COMMENT.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+#[path = "./comment_test.rs"]
+mod comment_test;
@@ -0,0 +1,71 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use std::collections::HashMap;
+    use tfplug::types::Dynamic;
+
+    fn state(comment: Option<&str>) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("name".to_string(), Dynamic::String("test".to_string()));
+        if let Some(comment) = comment {
+            obj.insert(COMMENT.to_string(), Dynamic::String(comment.to_string()));
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    #[test]
+    fn test_normalize_comment() {
+        assert_eq!(
+            normalize_comment(Some("web tier\n")),
+            Some("web tier".to_string())
+        );
+        assert_eq!(normalize_comment(Some("  ")), None);
+        assert_eq!(normalize_comment(Some("")), None);
+        assert_eq!(normalize_comment(None), None);
+    }
+
+    #[test]
+    fn test_set_comment_state_keeps_equivalent_value() {
+        let mut current = state(Some("managed by terraform"));
+        set_comment_state(&mut current, Some("managed by terraform\n"));
+        assert_eq!(
+            current.get_string(&AttributePath::new(COMMENT)).unwrap(),
+            "managed by terraform"
+        );
+
+        let mut unset = state(None);
+        set_comment_state(&mut unset, None);
+        assert!(unset.get_string(&AttributePath::new(COMMENT)).is_err());
+    }
+
+    #[test]
+    fn test_set_comment_state_picks_up_drift() {
+        let mut current = state(Some("old"));
+        set_comment_state(&mut current, Some("changed in the UI"));
+        assert_eq!(
+            current.get_string(&AttributePath::new(COMMENT)).unwrap(),
+            "changed in the UI"
+        );
+
+        set_comment_state(&mut current, None);
+        assert!(current.get_string(&AttributePath::new(COMMENT)).is_err());
+    }
+
+    #[test]
+    fn test_comment_delete() {
+        assert_eq!(
+            comment_delete(&state(Some("old")), &state(None)),
+            Some("comment".to_string())
+        );
+        assert_eq!(
+            comment_delete(&state(Some("old")), &state(Some(""))),
+            Some("comment".to_string())
+        );
+        assert_eq!(
+            comment_delete(&state(Some("old")), &state(Some("new"))),
+            None
+        );
+        assert_eq!(comment_delete(&state(None), &state(None)), None);
+    }
+}
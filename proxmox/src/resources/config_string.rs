@@ -0,0 +1,111 @@
+//! Semantic comparison of Proxmox property strings
+//!
+//! Disks and network devices are configured through comma separated strings
+//! such as `virtio,bridge=vmbr0,firewall=1` or `local-lvm:10,format=raw`.
+//! Proxmox reorders the parameters, spells sizes and flags its own way and
+//! fills in values the user never set, so comparing the strings directly
+//! reports changes that are not there. These helpers parse both sides into
+//! key-value pairs and compare them by meaning.
+
+use crate::api::common::{parse_bool, ByteSize};
+use crate::api::propstring::PropertyString;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// NIC models that lead a network device string, optionally with the MAC
+pub(crate) const NIC_MODELS: &[&str] = &["virtio", "e1000", "rtl8139", "vmxnet3"];
+
+/// A parsed property string. The leading value without a key (the volume of
+/// a disk, the model of a NIC) is kept apart from the keyed parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ConfigString {
+    pub(crate) positional: Option<String>,
+    pub(crate) params: BTreeMap<String, String>,
+}
+
+impl ConfigString {
+    /// Splits a property string into its positional value and parameters
+    pub(crate) fn parse(value: &str) -> Self {
+        let parsed = PropertyString::parse(value, "");
+        Self {
+            positional: parsed.default_value().map(str::to_string),
+            params: parsed
+                .params()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Parses a network device string. The model becomes the positional value
+    /// and the MAC address, if any, is returned separately.
+    pub(crate) fn parse_network(value: &str) -> (Self, Option<String>) {
+        let mut parsed = Self::parse(value);
+        let mut mac = None;
+        if parsed.positional.is_none() {
+            if let Some(model) = NIC_MODELS
+                .iter()
+                .find(|model| parsed.params.contains_key(**model))
+            {
+                mac = parsed.params.remove(*model).filter(|m| !m.is_empty());
+                parsed.positional = Some(model.to_string());
+            }
+        }
+        if let Some(macaddr) = parsed.params.remove("macaddr") {
+            mac = Some(macaddr);
+        }
+        (parsed, mac)
+    }
+
+    /// Value of a parameter
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.params.get(key).map(String::as_str)
+    }
+
+    /// Whether both strings configure the same thing, regardless of
+    /// parameter order, size units or how flags are spelled
+    pub(crate) fn semantic_eq(&self, other: &Self) -> bool {
+        self.positional == other.positional
+            && self.params.len() == other.params.len()
+            && self
+                .params
+                .iter()
+                .all(|(key, value)| other.get(key).is_some_and(|o| values_eq(key, value, o)))
+    }
+}
+
+/// Renders the canonical form: the positional value first, then the
+/// parameters sorted by key
+impl fmt::Display for ConfigString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rendered = PropertyString::new("");
+        if let Some(positional) = &self.positional {
+            rendered.set("", positional.as_str());
+        }
+        for (key, value) in &self.params {
+            rendered.set(key, value.as_str());
+        }
+        write!(f, "{}", rendered)
+    }
+}
+
+/// Compares two values of the same parameter by meaning
+pub(crate) fn values_eq(key: &str, a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    match key {
+        "size" => size_bytes(a).is_some() && size_bytes(a) == size_bytes(b),
+        "macaddr" => a.eq_ignore_ascii_case(b),
+        _ => parse_bool(a).is_some() && parse_bool(a) == parse_bool(b),
+    }
+}
+
+/// Converts a Proxmox size such as `10G`, `10240M` or `512` to bytes. Values
+/// without a unit are bytes.
+pub(crate) fn size_bytes(size: &str) -> Option<u64> {
+    ByteSize::parse(size).map(|size| size.bytes())
+}
+
+#[cfg(test)]
+#[path = "./config_string_test.rs"]
+mod config_string_test;
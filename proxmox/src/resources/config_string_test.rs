@@ -0,0 +1,78 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_parse_separates_positional_value() {
+        let parsed = ConfigString::parse("local-lvm:vm-100-disk-0,size=10G,ssd,cache=none");
+        assert_eq!(
+            parsed.positional.as_deref(),
+            Some("local-lvm:vm-100-disk-0")
+        );
+        assert_eq!(parsed.get("size"), Some("10G"));
+        assert_eq!(parsed.get("ssd"), Some(""));
+        assert_eq!(parsed.get("cache"), Some("none"));
+
+        let parsed = ConfigString::parse("bridge=vmbr0,tag=10");
+        assert_eq!(parsed.positional, None);
+        assert_eq!(parsed.params.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_network_extracts_model_and_mac() {
+        let (parsed, mac) = ConfigString::parse_network("bridge=vmbr0,virtio=AA:BB:CC:DD:EE:FF");
+        assert_eq!(parsed.positional.as_deref(), Some("virtio"));
+        assert_eq!(mac.as_deref(), Some("AA:BB:CC:DD:EE:FF"));
+        assert_eq!(parsed.to_string(), "virtio,bridge=vmbr0");
+
+        let (parsed, mac) = ConfigString::parse_network("e1000,bridge=vmbr1");
+        assert_eq!(parsed.positional.as_deref(), Some("e1000"));
+        assert_eq!(mac, None);
+    }
+
+    #[test]
+    fn test_display_sorts_parameters() {
+        let parsed = ConfigString::parse("virtio,tag=100,bridge=vmbr0,firewall=1");
+        assert_eq!(parsed.to_string(), "virtio,bridge=vmbr0,firewall=1,tag=100");
+    }
+
+    #[test]
+    fn test_semantic_eq() {
+        let a = ConfigString::parse("local:iso/a.iso,media=cdrom,size=10240M,ssd=on");
+        let b = ConfigString::parse("local:iso/a.iso,ssd=1,size=10G,media=cdrom");
+        assert!(a.semantic_eq(&b));
+
+        let c = ConfigString::parse("local:iso/a.iso,ssd=1,size=11G,media=cdrom");
+        assert!(!a.semantic_eq(&c));
+
+        let d = ConfigString::parse("local:iso/a.iso,ssd=1,size=10G");
+        assert!(!a.semantic_eq(&d));
+    }
+
+    #[test]
+    fn test_size_bytes() {
+        assert_eq!(size_bytes("512"), Some(512));
+        assert_eq!(size_bytes("4K"), Some(4096));
+        assert_eq!(size_bytes("10G"), size_bytes("10240M"));
+        assert_eq!(size_bytes("1T"), size_bytes("1024G"));
+        assert_eq!(size_bytes("1.5G"), size_bytes("1536M"));
+        assert_eq!(size_bytes("10gb"), size_bytes("10G"));
+        assert_eq!(size_bytes("ten"), None);
+        assert_eq!(size_bytes("10X"), None);
+    }
+
+    #[test]
+    fn test_values_eq() {
+        assert!(values_eq("firewall", "1", "on"));
+        assert!(values_eq("discard", "on", "1"));
+        assert!(!values_eq("firewall", "1", "0"));
+        assert!(values_eq(
+            "macaddr",
+            "aa:bb:cc:dd:ee:ff",
+            "AA:BB:CC:DD:EE:FF"
+        ));
+        assert!(!values_eq("bridge", "vmbr0", "VMBR0"));
+        assert!(!values_eq("size", "10G", "10"));
+    }
+}
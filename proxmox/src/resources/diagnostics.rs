@@ -0,0 +1,63 @@
+//! Conversion of API errors into Terraform diagnostics
+//!
+//! When Proxmox rejects individual parameters it names them in the error
+//! response. Reporting each of them against the matching attribute lets
+//! Terraform point at the offending line of configuration instead of
+//! printing the raw response body.
+
+use tfplug::types::{AttributePath, Diagnostic};
+
+use crate::api::ApiError;
+
+/// Builds the diagnostics for a failed API call. Parameters are matched to
+/// the given top-level attributes, with `-` in API names read as `_`.
+/// Resources pass the attributes named after the API parameter they are sent
+/// as. Anything that cannot be matched is reported without an attribute.
+pub(crate) fn api_error_diagnostics(
+    summary: &str,
+    error: &ApiError,
+    attributes: &[&str],
+) -> Vec<Diagnostic> {
+    let parameter_errors = error.parameter_errors();
+    if parameter_errors.is_empty() {
+        let mut diagnostics = vec![Diagnostic::error(summary, format!("API error: {}", error))];
+        diagnostics.extend(task_cancelled_warning(error));
+        return diagnostics;
+    }
+
+    parameter_errors
+        .into_iter()
+        .map(|(param, message)| {
+            let name = param.replace('-', "_");
+            let diagnostic = Diagnostic::error(
+                summary,
+                format!("Invalid value for parameter '{}': {}", param, message),
+            );
+            if attributes.contains(&name.as_str()) {
+                diagnostic.with_attribute(AttributePath::new(&name))
+            } else {
+                diagnostic
+            }
+        })
+        .collect()
+}
+
+/// Warning for a task Terraform was interrupted while waiting for. The task
+/// carries on without it, so the user should look at how it ended before
+/// applying again.
+pub(crate) fn task_cancelled_warning(error: &ApiError) -> Option<Diagnostic> {
+    match error {
+        ApiError::TaskCancelled { upid } => Some(Diagnostic::warning(
+            "Task still running",
+            format!(
+                "Terraform was interrupted before the task finished. It keeps running on the node and its result is not in the state, UPID={}",
+                upid
+            ),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./diagnostics_test.rs"]
+mod diagnostics_test;
@@ -0,0 +1,53 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_parameter_errors_attached_to_attributes() {
+        let error = ApiError::from_response(
+            400,
+            r#"{"data": null, "errors": {"issuer-url": "invalid format", "foo": "unknown"}}"#,
+        );
+
+        let diagnostics =
+            api_error_diagnostics("Failed to create realm", &error, &["realm", "issuer_url"]);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].summary, "Failed to create realm");
+        assert!(diagnostics[0].detail.contains("'foo': unknown"));
+        assert!(diagnostics[0].attribute.is_none());
+        assert!(diagnostics[1]
+            .detail
+            .contains("'issuer-url': invalid format"));
+        assert_eq!(
+            diagnostics[1].attribute,
+            Some(AttributePath::new("issuer_url"))
+        );
+    }
+
+    #[test]
+    fn test_other_errors_reported_once() {
+        let diagnostics =
+            api_error_diagnostics("Failed to create VM", &ApiError::AuthError, &["vmid"]);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].detail, "API error: Authentication failed");
+        assert!(diagnostics[0].attribute.is_none());
+    }
+
+    #[test]
+    fn test_cancelled_task_warns_it_is_still_running() {
+        let error = ApiError::TaskCancelled {
+            upid: "UPID:pve:00001234:00000000:65000000:qmclone:100:root@pam:".to_string(),
+        };
+
+        let diagnostics = api_error_diagnostics("Failed to create VM", &error, &["vmid"]);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1].summary, "Task still running");
+        assert!(diagnostics[1]
+            .detail
+            .ends_with("UPID=UPID:pve:00001234:00000000:65000000:qmclone:100:root@pam:"));
+    }
+}
@@ -1,7 +1,19 @@
 //! Resource implementations
 
 pub mod access;
+pub mod cluster;
+pub(crate) mod comment;
+pub(crate) mod config_string;
+pub(crate) mod diagnostics;
 pub mod nodes;
+pub mod storage;
 
-pub use access::RealmResource;
-pub use nodes::QemuVmResource;
+pub use access::{RealmResource, RealmSyncResource, TfaWebauthnResource};
+pub use cluster::{ClusterJoinResource, ClusterResource, HaResourceResource, MappingDirResource};
+pub use nodes::{
+    CephPoolResource, LxcResource, LxcTemplateResource, NodeAptRepositoriesResource,
+    NodeDiskStorageResource, NodeDnsResource, NodeTimeResource, QemuVmResource,
+    VmAgentExecResource, VmPowerResource, VmQgaPasswordResource, VmTemplateResource,
+    VzdumpBackupResource,
+};
+pub use storage::PbsStorageResource;
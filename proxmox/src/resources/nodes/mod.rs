@@ -1,3 +1,28 @@
+mod resource_ceph_pool;
+mod resource_lxc;
+mod resource_lxc_template;
+mod resource_node_apt_repositories;
+mod resource_node_disk;
+mod resource_node_dns;
+mod resource_node_time;
 mod resource_vm;
+mod resource_vm_agent_exec;
+mod resource_vm_move;
+mod resource_vm_power;
+mod resource_vm_qga_password;
+mod resource_vm_template;
+mod resource_vzdump_backup;
 
+pub use resource_ceph_pool::CephPoolResource;
+pub use resource_lxc::LxcResource;
+pub use resource_lxc_template::LxcTemplateResource;
+pub use resource_node_apt_repositories::NodeAptRepositoriesResource;
+pub use resource_node_disk::NodeDiskStorageResource;
+pub use resource_node_dns::NodeDnsResource;
+pub use resource_node_time::NodeTimeResource;
 pub use resource_vm::QemuVmResource;
+pub use resource_vm_agent_exec::VmAgentExecResource;
+pub use resource_vm_power::VmPowerResource;
+pub use resource_vm_qga_password::VmQgaPasswordResource;
+pub use resource_vm_template::VmTemplateResource;
+pub use resource_vzdump_backup::VzdumpBackupResource;
@@ -0,0 +1,647 @@
+//! Ceph pool resource implementation
+
+use crate::api::nodes::{
+    CephPool, CreateCephPoolRequest, UpdateCephPoolRequest, CEPH_PG_AUTOSCALE_MODES,
+    CEPH_POOL_APPLICATIONS,
+};
+use crate::api::TaskId;
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+/// Creating a pool waits for its placement groups, which can take a while on
+/// a busy cluster.
+const POOL_TASK_TIMEOUT: Duration = Duration::from_secs(600);
+
+const POOL_ATTRIBUTES: &[&str] = &[
+    "name",
+    "size",
+    "min_size",
+    "pg_num",
+    "pg_autoscale_mode",
+    "application",
+];
+
+/// Pool settings taken from the configuration. Unset values are left to
+/// Ceph's defaults.
+#[derive(Debug)]
+struct PoolSettings {
+    node: String,
+    name: String,
+    size: Option<u32>,
+    min_size: Option<u32>,
+    pg_num: Option<u32>,
+    pg_autoscale_mode: Option<String>,
+    application: Option<String>,
+}
+
+impl PoolSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let name = config
+            .get_string(&AttributePath::new("name"))
+            .map_err(|_| Diagnostic::error("Missing name", "The 'name' attribute is required"))?;
+        let number = |name: &str| config.get_u32(&AttributePath::new(name)).ok();
+        let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+
+        Ok(Self {
+            node,
+            name,
+            size: number("size"),
+            min_size: number("min_size"),
+            pg_num: number("pg_num"),
+            pg_autoscale_mode: string("pg_autoscale_mode"),
+            application: string("application"),
+        })
+    }
+
+    fn create_request(&self, add_storage: bool) -> CreateCephPoolRequest {
+        CreateCephPoolRequest {
+            name: self.name.clone(),
+            size: self.size,
+            min_size: self.min_size,
+            pg_num: self.pg_num,
+            pg_autoscale_mode: self.pg_autoscale_mode.clone(),
+            application: self.application.clone(),
+            add_storages: add_storage.then_some(true),
+        }
+    }
+
+    fn update_request(&self) -> UpdateCephPoolRequest {
+        UpdateCephPoolRequest {
+            size: self.size,
+            min_size: self.min_size,
+            pg_num: self.pg_num,
+            pg_autoscale_mode: self.pg_autoscale_mode.clone(),
+            application: self.application.clone(),
+        }
+    }
+}
+
+fn set_pool_state(state: &mut DynamicValue, pool: &CephPool) {
+    for (name, value) in [
+        ("size", pool.size),
+        ("min_size", pool.min_size),
+        ("pg_num", pool.pg_num),
+    ] {
+        let path = AttributePath::new(name);
+        let _ = match value {
+            Some(value) => state.set_number(&path, value as f64),
+            None => state.set_null(&path),
+        };
+    }
+    for (name, value) in [
+        ("pg_autoscale_mode", pool.pg_autoscale_mode.clone()),
+        ("application", pool.application()),
+    ] {
+        let path = AttributePath::new(name);
+        let _ = match value {
+            Some(value) => state.set_string(&path, value),
+            None => state.set_null(&path),
+        };
+    }
+}
+
+/// Manages a replicated Ceph pool in a hyperconverged cluster
+#[derive(Default)]
+pub struct CephPoolResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl CephPoolResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn wait(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        node: &str,
+        task: Result<TaskId, crate::api::ApiError>,
+        timeout: Duration,
+        summary: &str,
+    ) -> Result<(), Vec<Diagnostic>> {
+        let node_api = provider_data.client.nodes().node(node);
+        let result = match task {
+            Ok(upid) => node_api
+                .wait_for_task(&upid, timeout, ctx.cancelled())
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| api_error_diagnostics(summary, &e, POOL_ATTRIBUTES))
+    }
+
+    /// Warns when the pool wants more replicas than there are OSDs to hold
+    /// them. Ceph accepts such pools but leaves their placement groups
+    /// undersized.
+    async fn check_osds(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &PoolSettings,
+    ) -> Option<Diagnostic> {
+        let size = settings.size?;
+        let osds = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .ceph_osds()
+            .await
+            .ok()?
+            .active_osds();
+
+        (size as usize > osds).then(|| {
+            Diagnostic::warning(
+                "Pool size exceeds available OSDs",
+                format!(
+                    "Pool '{}' keeps {} replicas but only {} OSDs are up and in. Its placement groups stay undersized until more OSDs join.",
+                    settings.name, size, osds
+                ),
+            )
+            .with_attribute(AttributePath::new("size"))
+        })
+    }
+
+    async fn read_back(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &PoolSettings,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let pool = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .ceph_pool(&settings.name)
+            .await
+            .map_err(|e| {
+                Diagnostic::error(
+                    "Failed to read Ceph pool",
+                    format!("Unable to read Ceph pool '{}': {}", settings.name, e),
+                )
+            })?;
+        set_pool_state(&mut state, &pool);
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl Resource for CephPoolResource {
+    fn type_name(&self) -> &str {
+        "proxmox_ceph_pool"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages a replicated Ceph pool of a hyperconverged cluster")
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("A node with Ceph installed to manage the pool through")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .description("The pool name")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("size", AttributeType::Number)
+                    .description("Number of replicas per object (1-7). Defaults to 3")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("min_size", AttributeType::Number)
+                    .description("Minimum number of replicas needed to serve I/O (1-7). Defaults to 2")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pg_num", AttributeType::Number)
+                    .description("Number of placement groups. Usually left to the autoscaler")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pg_autoscale_mode", AttributeType::String)
+                    .description("Placement group autoscaler mode: on, off or warn")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("application", AttributeType::String)
+                    .description("What the pool is used for: rbd, cephfs or rgw. Defaults to rbd")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("add_storage", AttributeType::Bool)
+                    .description("Also create an RBD storage named after the pool so VM disks and containers can use it right away. The storage is removed together with the pool")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .block(Timeouts::block(&[
+                Operation::Create,
+                Operation::Update,
+                Operation::Delete,
+            ]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["size", "min_size", "pg_num"]);
+
+        let size = config.get_number(&AttributePath::new("size")).ok();
+        let min_size = config.get_number(&AttributePath::new("min_size")).ok();
+        for (name, value) in [("size", size), ("min_size", min_size)] {
+            if value.is_some_and(|v| !(1.0..=7.0).contains(&v)) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        format!("Invalid {}", name),
+                        format!("{} must be between 1 and 7", name),
+                    )
+                    .with_attribute(AttributePath::new(name)),
+                );
+            }
+        }
+        if let (Some(size), Some(min_size)) = (size, min_size) {
+            if min_size > size {
+                diagnostics.push(
+                    Diagnostic::error("Invalid min_size", "min_size cannot be larger than size")
+                        .with_attribute(AttributePath::new("min_size")),
+                );
+            }
+        }
+
+        for (name, allowed) in [
+            ("pg_autoscale_mode", CEPH_PG_AUTOSCALE_MODES),
+            ("application", CEPH_POOL_APPLICATIONS),
+        ] {
+            if let Ok(value) = config.get_string(&AttributePath::new(name)) {
+                if !allowed.contains(&value.as_str()) {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            format!("Invalid {}", name),
+                            format!("{} must be one of: {}", name, allowed.join(", ")),
+                        )
+                        .with_attribute(AttributePath::new(name)),
+                    );
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match PoolSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+        let add_storage = request
+            .config
+            .get_bool(&AttributePath::new("add_storage"))
+            .unwrap_or(false);
+
+        let mut diagnostics: Vec<Diagnostic> = Self::check_osds(provider_data, &settings)
+            .await
+            .into_iter()
+            .collect();
+
+        let task = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .create_ceph_pool(&settings.create_request(add_storage))
+            .await;
+        if let Err(diags) = Self::wait(
+            &ctx,
+            provider_data,
+            &settings.node,
+            task,
+            Timeouts::new(&request.config).create(&ctx, POOL_TASK_TIMEOUT),
+            "Failed to create Ceph pool",
+        )
+        .await
+        {
+            diagnostics.extend(diags);
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics,
+            };
+        }
+
+        match Self::read_back(provider_data, &settings, request.planned_state.clone()).await {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics,
+            },
+            Err(diag) => {
+                diagnostics.push(diag);
+                CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                }
+            }
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match PoolSettings::from_config(&request.current_state) {
+            Ok(settings) => settings,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .ceph_pool(&settings.name)
+            .await
+        {
+            Ok(pool) => {
+                let mut new_state = request.current_state.clone();
+                set_pool_state(&mut new_state, &pool);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read Ceph pool",
+                    format!("Unable to read Ceph pool '{}': {}", settings.name, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match PoolSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let mut diagnostics: Vec<Diagnostic> = Self::check_osds(provider_data, &settings)
+            .await
+            .into_iter()
+            .collect();
+
+        let task = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .update_ceph_pool(&settings.name, &settings.update_request())
+            .await;
+        if let Err(diags) = Self::wait(
+            &ctx,
+            provider_data,
+            &settings.node,
+            task,
+            Timeouts::new(&request.config).update(&ctx, POOL_TASK_TIMEOUT),
+            "Failed to update Ceph pool",
+        )
+        .await
+        {
+            diagnostics.extend(diags);
+            return UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            };
+        }
+
+        match Self::read_back(provider_data, &settings, request.planned_state).await {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            },
+            Err(diag) => {
+                diagnostics.push(diag);
+                UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics,
+                    new_identity: None,
+                }
+            }
+        }
+    }
+
+    async fn delete(&self, ctx: Context, request: DeleteResourceRequest) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match PoolSettings::from_config(&request.prior_state) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+        let remove_storage = request
+            .prior_state
+            .get_bool(&AttributePath::new("add_storage"))
+            .unwrap_or(false);
+
+        let task = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .delete_ceph_pool(&settings.name, remove_storage)
+            .await;
+        let diagnostics = match task {
+            Err(e) if e.is_not_found() => vec![],
+            task => Self::wait(
+                &ctx,
+                provider_data,
+                &settings.node,
+                task,
+                Timeouts::new(&request.prior_state).delete(&ctx, POOL_TASK_TIMEOUT),
+                "Failed to delete Ceph pool",
+            )
+            .await
+            .err()
+            .unwrap_or_default(),
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for CephPoolResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_ceph_pool_test.rs"]
+mod resource_ceph_pool_test;
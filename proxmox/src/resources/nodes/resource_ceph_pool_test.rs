@@ -0,0 +1,190 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::{ClientCapabilities, Dynamic};
+
+    const UPID: &str = "UPID:pve:00001234:00000000:65000000:cephcreatepool:vm:root@pam:";
+
+    fn pool_config(values: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("name".to_string(), Dynamic::String("vm".to_string()));
+        for name in [
+            "size",
+            "min_size",
+            "pg_num",
+            "pg_autoscale_mode",
+            "application",
+            "add_storage",
+        ] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        for (name, value) in values {
+            obj.insert(name.to_string(), value.clone());
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> CephPoolResource {
+        CephPoolResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    async fn mock_task(server: &mut mockito::ServerGuard) -> mockito::Mock {
+        server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_validate_pool_settings() {
+        let response = CephPoolResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_ceph_pool".to_string(),
+                    config: pool_config(&[
+                        ("size", Dynamic::Number(2.0)),
+                        ("min_size", Dynamic::Number(3.0)),
+                        ("application", Dynamic::String("nfs".to_string())),
+                    ]),
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let summaries: Vec<&str> = response
+            .diagnostics
+            .iter()
+            .map(|d| d.summary.as_str())
+            .collect();
+        assert_eq!(summaries, vec!["Invalid min_size", "Invalid application"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_waits_and_reads_back() {
+        let mut server = Server::new_async().await;
+        let _osds = server
+            .mock("GET", "/api2/json/nodes/pve/ceph/osd")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {"root": {"id": -1, "name": "default", "type": "root", "children": [
+                    {"id": 0, "name": "osd.0", "type": "osd", "status": "up", "in": 1},
+                    {"id": 1, "name": "osd.1", "type": "osd", "status": "up", "in": 1}
+                ]}}}"#,
+            )
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/api2/json/nodes/pve/ceph/pool")
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "vm",
+                "size": 3,
+                "add_storages": true
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let task = mock_task(&mut server).await;
+        let _pools = server
+            .mock("GET", "/api2/json/nodes/pve/ceph/pool")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"pool_name": "vm", "size": 3, "min_size": 2, "pg_num": 32,
+                    "pg_autoscale_mode": "on", "application_metadata": {"rbd": {}}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = pool_config(&[
+            ("size", Dynamic::Number(3.0)),
+            ("add_storage", Dynamic::Bool(true)),
+        ]);
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_ceph_pool".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        create.assert_async().await;
+        task.assert_async().await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "Pool size exceeds available OSDs"
+        );
+        let state = &response.new_state;
+        assert_eq!(
+            state.get_number(&AttributePath::new("min_size")).unwrap(),
+            2.0
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("application"))
+                .unwrap(),
+            "rbd"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_missing_pool_removes_state() {
+        let mut server = Server::new_async().await;
+        let _pools = server
+            .mock("GET", "/api2/json/nodes/pve/ceph/pool")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+
+        let response = configured_resource(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_ceph_pool".to_string(),
+                    current_state: pool_config(&[]),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert!(response.new_state.is_none());
+    }
+}
@@ -0,0 +1,1869 @@
+//! LXC container resource implementation
+
+use crate::api::common::{parse_bool, ByteSize};
+use crate::api::nodes::{
+    CreateLxcRequest, LxcConfig, LxcSettings, UpdateLxcRequest, MAX_DEVICES,
+    MAX_LXC_NETWORK_INTERFACES, MAX_MOUNT_POINTS,
+};
+use crate::api::propstring::PropertyString;
+use crate::resources::comment::normalize_comment;
+use crate::resources::diagnostics::api_error_diagnostics;
+use crate::ssh;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::defaults::StaticDefault;
+use tfplug::model::{TfBlock, TfSchema};
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ModifyPlanRequest,
+    ModifyPlanResponse, ReadResourceRequest, ReadResourceResponse, Resource,
+    ResourceMetadataRequest, ResourceMetadataResponse, ResourceSchemaRequest,
+    ResourceSchemaResponse, ResourceWithConfigure, ResourceWithModifyPlan, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::{IntegerRangeValidator, NumberRangeValidator, StringOneOfValidator};
+
+/// Creating unpacks the template and allocates every volume.
+const CREATE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Resizing, stopping or destroying the container.
+const TASK_TIMEOUT: Duration = Duration::from_secs(300);
+
+const CONTAINER_ATTRIBUTES: &[&str] = &[
+    "vmid",
+    "hostname",
+    "description",
+    "ostemplate",
+    "password",
+    "ssh_public_keys",
+    "unprivileged",
+    "onboot",
+];
+
+const GIB: u64 = 1 << 30;
+
+/// Memory and swap in MB of a container that doesn't set them
+const DEFAULT_MEMORY: u64 = 512;
+
+/// Console modes Proxmox accepts for cmode
+const CONSOLE_MODES: &[&str] = &["tty", "console", "shell"];
+
+/// Root filesystem of the container
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct RootFs {
+    /// Storage to allocate the root filesystem on. Changing it forces a new
+    /// container
+    storage: String,
+    /// Size of the root filesystem, e.g. "8G". Growing it resizes the
+    /// volume, it cannot shrink
+    size: String,
+    /// Volume ID Proxmox allocated, e.g. local-lvm:vm-200-disk-0
+    #[tfplug(computed)]
+    volume: Option<String>,
+}
+
+/// A volume allocated on a storage, an existing volume or a bind mounted
+/// host directory
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct MountPoint {
+    /// Mount point ID (0-255), sent as mp0 to mp255
+    id: u32,
+    /// Path inside the container, e.g. /var/lib/postgresql
+    path: String,
+    /// Storage to allocate a new volume on. Conflicts with volume. Changing
+    /// it allocates a new, empty volume and keeps the old one as an unused
+    /// volume of the container
+    storage: Option<String>,
+    /// Size of the volume allocated on storage, e.g. "16G". Growing it
+    /// resizes the volume, it cannot shrink
+    size: Option<String>,
+    /// An existing volume, or an absolute host directory to bind mount such
+    /// as /srv/data. Conflicts with storage, and holds the allocated volume
+    /// when storage is set
+    #[tfplug(optional, computed)]
+    volume: Option<String>,
+    /// Enable or disable POSIX ACLs. Defaults to the filesystem's setting
+    acl: Option<bool>,
+    /// Enable user quotas inside the container. Not supported by
+    /// unprivileged containers
+    quota: Option<bool>,
+    /// Mount read-only
+    read_only: Option<bool>,
+    /// Include the volume in backups (default: false). Bind mounts are never
+    /// backed up
+    backup: Option<bool>,
+    /// Include the volume in storage replication (default: true)
+    replicate: Option<bool>,
+    /// Mark a bind mount or an existing volume as available on every node,
+    /// so the container can migrate (default: false)
+    shared: Option<bool>,
+}
+
+impl MountPoint {
+    /// Boolean options by attribute name, with the key they map to
+    fn flags(&self) -> [(&'static str, Option<bool>); 6] {
+        [
+            ("acl", self.acl),
+            ("quota", self.quota),
+            ("ro", self.read_only),
+            ("backup", self.backup),
+            ("replicate", self.replicate),
+            ("shared", self.shared),
+        ]
+    }
+
+    fn flags_mut(&mut self) -> [(&'static str, &mut Option<bool>); 6] {
+        [
+            ("acl", &mut self.acl),
+            ("quota", &mut self.quota),
+            ("ro", &mut self.read_only),
+            ("backup", &mut self.backup),
+            ("replicate", &mut self.replicate),
+            ("shared", &mut self.shared),
+        ]
+    }
+
+    fn is_bind_mount(&self) -> bool {
+        self.volume
+            .as_deref()
+            .is_some_and(|volume| volume.starts_with('/'))
+    }
+
+    /// The mpN option. A mount point without a volume allocates one on its
+    /// storage. `size` is recorded with an existing volume, Proxmox drops it
+    /// from the config otherwise.
+    fn option(&self, size: Option<&str>) -> String {
+        let mut mount = PropertyString::new("volume");
+        match (&self.volume, &self.storage, &self.size) {
+            (Some(volume), _, _) => mount.set("volume", volume.clone()),
+            (None, Some(storage), Some(size)) => mount.set("volume", allocation(storage, size)),
+            _ => {}
+        }
+        mount.set("mp", self.path.clone());
+        if let (Some(size), Some(_)) = (size, &self.volume) {
+            mount.set("size", size);
+        }
+        for (key, value) in self.flags() {
+            if let Some(value) = value {
+                mount.set(key, bool_option(value));
+            }
+        }
+        mount.to_string()
+    }
+
+    /// Reads a mpN option. Storage and size are only tracked for mount
+    /// points that allocated their volume, as `prior` shows.
+    fn from_option(id: u32, value: &str, prior: Option<&MountPoint>) -> Self {
+        let mount = PropertyString::parse(value, "volume");
+        let volume = mount.default_value().unwrap_or_default().to_string();
+        let allocated = prior.is_some_and(|prior| prior.storage.is_some());
+        let mut mount_point = Self {
+            id,
+            path: mount.get("mp").unwrap_or_default().to_string(),
+            storage: volume
+                .split_once(':')
+                .filter(|_| allocated)
+                .map(|(storage, _)| storage.to_string()),
+            size: allocated
+                .then(|| read_size(mount.get("size"), prior.and_then(|p| p.size.as_deref())))
+                .flatten(),
+            volume: Some(volume),
+            ..Default::default()
+        };
+        for (key, value) in mount_point.flags_mut() {
+            *value = mount.get(key).and_then(parse_bool);
+        }
+        mount_point
+    }
+}
+
+/// A host device passed through to the container
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Device {
+    /// Device ID (0-255), sent as dev0 to dev255
+    id: u32,
+    /// Device node on the host, e.g. /dev/ttyUSB0
+    path: String,
+    /// Access mode of the device node in the container, e.g. "0660"
+    mode: Option<String>,
+    /// Owner of the device node in the container
+    uid: Option<u32>,
+    /// Group of the device node in the container
+    gid: Option<u32>,
+    /// Pass the device through read-only
+    deny_write: Option<bool>,
+}
+
+impl Device {
+    /// The devN option
+    fn option(&self) -> String {
+        let mut device = PropertyString::new("path");
+        device.set("path", self.path.clone());
+        if let Some(mode) = &self.mode {
+            device.set("mode", mode.clone());
+        }
+        if let Some(uid) = self.uid {
+            device.set("uid", uid.to_string());
+        }
+        if let Some(gid) = self.gid {
+            device.set("gid", gid.to_string());
+        }
+        if let Some(deny_write) = self.deny_write {
+            device.set("deny-write", bool_option(deny_write));
+        }
+        device.to_string()
+    }
+
+    fn from_option(id: u32, value: &str) -> Self {
+        let device = PropertyString::parse(value, "path");
+        Self {
+            id,
+            path: device.default_value().unwrap_or_default().to_string(),
+            mode: device.get("mode").map(str::to_string),
+            uid: device.get("uid").and_then(|uid| uid.parse().ok()),
+            gid: device.get("gid").and_then(|gid| gid.parse().ok()),
+            deny_write: device.get("deny-write").and_then(parse_bool),
+        }
+    }
+}
+
+/// Container features. Proxmox only lets root@pam change them
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Features {
+    /// Allow nesting, e.g. to run Docker or systemd services with their own
+    /// namespaces. This exposes the host's procfs and sysfs to the container
+    nesting: Option<bool>,
+    /// Allow the keyctl() system call, which Docker and systemd use.
+    /// Unprivileged containers only
+    keyctl: Option<bool>,
+    /// Allow FUSE mounts inside the container
+    fuse: Option<bool>,
+}
+
+impl Features {
+    fn flags(&self) -> [(&'static str, Option<bool>); 3] {
+        [
+            ("nesting", self.nesting),
+            ("keyctl", self.keyctl),
+            ("fuse", self.fuse),
+        ]
+    }
+
+    /// The features option
+    fn option(&self) -> String {
+        let mut features = PropertyString::new("");
+        for (key, value) in self.flags() {
+            if let Some(value) = value {
+                features.set(key, bool_option(value));
+            }
+        }
+        features.to_string()
+    }
+
+    fn from_option(value: &str) -> Self {
+        let features = PropertyString::parse(value, "");
+        let flag = |key: &str| features.get(key).and_then(parse_bool);
+        Self {
+            nesting: flag("nesting"),
+            keyctl: flag("keyctl"),
+            fuse: flag("fuse"),
+        }
+    }
+}
+
+/// A veth network interface
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Network {
+    /// Network interface ID (0-31), sent as net0 to net31
+    id: u32,
+    /// Interface name inside the container, e.g. eth0
+    name: String,
+    /// Bridge to attach to, e.g. vmbr0
+    bridge: String,
+    /// IPv4 address in CIDR notation, "dhcp" or "manual"
+    ip: Option<String>,
+    /// IPv4 gateway
+    gw: Option<String>,
+    /// IPv6 address in CIDR notation, "dhcp", "auto" or "manual"
+    ip6: Option<String>,
+    /// IPv6 gateway
+    gw6: Option<String>,
+    /// Apply the container's firewall rules to this interface
+    firewall: Option<bool>,
+    /// VLAN tag
+    tag: Option<u32>,
+    /// MAC address. Proxmox generates one when not set
+    #[tfplug(optional, computed)]
+    hwaddr: Option<String>,
+}
+
+impl Network {
+    /// The netN option
+    fn option(&self) -> String {
+        let mut network = PropertyString::new("");
+        network.set("name", self.name.clone());
+        network.set("bridge", self.bridge.clone());
+        if let Some(hwaddr) = &self.hwaddr {
+            network.set("hwaddr", hwaddr.clone());
+        }
+        for (key, value) in [
+            ("ip", &self.ip),
+            ("gw", &self.gw),
+            ("ip6", &self.ip6),
+            ("gw6", &self.gw6),
+        ] {
+            if let Some(value) = value {
+                network.set(key, value.clone());
+            }
+        }
+        if let Some(firewall) = self.firewall {
+            network.set("firewall", bool_option(firewall));
+        }
+        if let Some(tag) = self.tag {
+            network.set("tag", tag.to_string());
+        }
+        network.to_string()
+    }
+
+    fn from_option(id: u32, value: &str) -> Self {
+        let network = PropertyString::parse(value, "");
+        let string = |key: &str| network.get(key).map(str::to_string);
+        Self {
+            id,
+            name: string("name").unwrap_or_default(),
+            bridge: string("bridge").unwrap_or_default(),
+            ip: string("ip"),
+            gw: string("gw"),
+            ip6: string("ip6"),
+            gw6: string("gw6"),
+            firewall: network.get("firewall").and_then(parse_bool),
+            tag: network.get("tag").and_then(|tag| tag.parse().ok()),
+            hwaddr: string("hwaddr").map(|hwaddr| hwaddr.to_ascii_uppercase()),
+        }
+    }
+}
+
+/// A raw key of the container's config file, for settings Proxmox has no
+/// option for
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct RawConfig {
+    /// Key, e.g. lxc.cgroup2.devices.allow. Keys may repeat
+    key: String,
+    /// Value, e.g. "c 188:* rwm"
+    value: String,
+}
+
+/// Shell script that replaces the lxc.* lines of the container's config
+/// with `raw_config`, at the end of the current config and before any
+/// snapshot or pending section, which are left alone. /etc/pve
+/// is shared by the cluster, so it can run on any node.
+fn raw_config_script(node: &str, vmid: u32, raw_config: &[RawConfig]) -> String {
+    // awk unescapes -v values, so lines are joined with \n and backslashes
+    // doubled
+    let lines: String = raw_config
+        .iter()
+        .map(|raw| format!("{}: {}\\n", raw.key, raw.value.replace('\\', "\\\\")))
+        .collect();
+    format!(
+        "set -e\n\
+         conf=/etc/pve/nodes/{node}/lxc/{vmid}.conf\n\
+         tmp=$(mktemp)\n\
+         trap 'rm -f \"$tmp\"' EXIT\n\
+         awk -v raw={lines} {program} \"$conf\" > \"$tmp\"\n\
+         cat \"$tmp\" > \"$conf\"\n",
+        node = ssh::quote(node),
+        vmid = vmid,
+        lines = ssh::quote(&lines),
+        program = ssh::quote(
+            "BEGIN { main = 1 } \
+             main && /^$/ { blank = blank \"\\n\"; next } \
+             main && /^\\[/ { printf \"%s%s\", raw, blank; main = 0 } \
+             main && /^lxc\\./ { next } \
+             main { printf \"%s\", blank; blank = \"\" } \
+             { print } \
+             END { if (main) printf \"%s%s\", raw, blank }"
+        ),
+    )
+}
+
+/// The planned value when it differs from `prior`
+fn changed<T: Clone + PartialEq>(planned: &Option<T>, prior: &Option<T>) -> Option<T> {
+    planned.clone().filter(|_| planned != prior)
+}
+
+fn bool_option(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+/// "STORAGE:SIZE_IN_GiB", which allocates a new volume
+fn allocation(storage: &str, size: &str) -> String {
+    let bytes = ByteSize::parse(size).map_or(0, |size| size.bytes());
+    format!("{}:{}", storage, bytes as f64 / GIB as f64)
+}
+
+/// The size Proxmox reports, in the spelling of `prior` when they are the
+/// same size
+fn read_size(size: Option<&str>, prior: Option<&str>) -> Option<String> {
+    match (size, prior) {
+        (Some(size), Some(prior)) if ByteSize::parse(size) == ByteSize::parse(prior) => {
+            Some(prior.to_string())
+        }
+        (Some(size), _) => Some(size.to_string()),
+        (None, prior) => prior.map(str::to_string),
+    }
+}
+
+/// Orders blocks read from the container like the blocks in state, so
+/// refresh doesn't reorder the configuration. New ones go last, by id.
+fn order_like<T>(blocks: &mut [T], prior_ids: &[u32], id: fn(&T) -> u32) {
+    blocks.sort_by_key(|block| {
+        let id = id(block);
+        (
+            prior_ids
+                .iter()
+                .position(|prior| *prior == id)
+                .unwrap_or(usize::MAX),
+            id,
+        )
+    });
+}
+
+/// Container settings taken from the configuration or state
+#[derive(Debug, Default)]
+struct ContainerSettings {
+    node: String,
+    vmid: u32,
+    hostname: Option<String>,
+    description: Option<String>,
+    onboot: Option<bool>,
+    rootfs: Option<RootFs>,
+    mount_points: Vec<MountPoint>,
+    devices: Vec<Device>,
+    features: Option<Features>,
+    networks: Vec<Network>,
+    cores: Option<u32>,
+    cpulimit: Option<f64>,
+    cpuunits: Option<u32>,
+    memory: Option<u64>,
+    swap: Option<u64>,
+    cmode: Option<String>,
+    tty: Option<u32>,
+    console: Option<bool>,
+    raw_config: Vec<RawConfig>,
+}
+
+impl ContainerSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let vmid = config
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
+        let block = |name: &str| {
+            config
+                .get(&AttributePath::new(name))
+                .cloned()
+                .unwrap_or(Dynamic::Null)
+        };
+        let invalid = |name: &str, e: tfplug::error::TfplugError| {
+            Diagnostic::attribute_error(
+                AttributePath::new(name),
+                format!("Invalid {}", name),
+                e.to_string(),
+            )
+        };
+
+        Ok(Self {
+            node,
+            vmid,
+            hostname: config.get_string(&AttributePath::new("hostname")).ok(),
+            description: config.get_string(&AttributePath::new("description")).ok(),
+            onboot: config.get_bool(&AttributePath::new("onboot")).ok(),
+            rootfs: Option::<RootFs>::from_block(&block("rootfs"))
+                .map_err(|e| invalid("rootfs", e))?,
+            mount_points: Vec::<MountPoint>::from_block(&block("mountpoint"))
+                .map_err(|e| invalid("mountpoint", e))?,
+            devices: Vec::<Device>::from_block(&block("dev")).map_err(|e| invalid("dev", e))?,
+            features: Option::<Features>::from_block(&block("features"))
+                .map_err(|e| invalid("features", e))?,
+            networks: Vec::<Network>::from_block(&block("network"))
+                .map_err(|e| invalid("network", e))?,
+            cores: config.get_u32(&AttributePath::new("cores")).ok(),
+            cpulimit: config.get_number(&AttributePath::new("cpulimit")).ok(),
+            cpuunits: config.get_u32(&AttributePath::new("cpuunits")).ok(),
+            memory: config.get_u64(&AttributePath::new("memory")).ok(),
+            swap: config.get_u64(&AttributePath::new("swap")).ok(),
+            cmode: config.get_string(&AttributePath::new("cmode")).ok(),
+            tty: config.get_u32(&AttributePath::new("tty")).ok(),
+            console: config.get_bool(&AttributePath::new("console")).ok(),
+            raw_config: Vec::<RawConfig>::from_block(&block("raw_config"))
+                .map_err(|e| invalid("raw_config", e))?,
+        })
+    }
+
+    /// The mpN, devN and netN options, by key. Mount points with an
+    /// existing volume record the size from `sizes`.
+    fn indexed_options(&self, sizes: &Self) -> BTreeMap<String, String> {
+        let mut options = BTreeMap::new();
+        for mount_point in &self.mount_points {
+            let size = sizes
+                .mount_points
+                .iter()
+                .find(|current| current.id == mount_point.id)
+                .and_then(|current| current.size.as_deref());
+            options.insert(format!("mp{}", mount_point.id), mount_point.option(size));
+        }
+        for device in &self.devices {
+            options.insert(format!("dev{}", device.id), device.option());
+        }
+        for network in &self.networks {
+            options.insert(format!("net{}", network.id), network.option());
+        }
+        options
+    }
+
+    fn create_request(&self, config: &DynamicValue) -> Result<CreateLxcRequest, Diagnostic> {
+        let rootfs = self
+            .rootfs
+            .as_ref()
+            .ok_or_else(|| Diagnostic::error("Missing rootfs", "A rootfs block is required"))?;
+        let ostemplate = config
+            .get_string(&AttributePath::new("ostemplate"))
+            .map_err(|_| {
+                Diagnostic::error(
+                    "Missing ostemplate",
+                    "The 'ostemplate' attribute is required",
+                )
+            })?;
+        let ssh_public_keys = config
+            .get_list(&AttributePath::new("ssh_public_keys"))
+            .ok()
+            .map(|keys| {
+                keys.iter()
+                    .filter_map(|key| match key {
+                        Dynamic::String(key) => Some(key.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .filter(|keys| !keys.is_empty());
+
+        Ok(CreateLxcRequest {
+            vmid: self.vmid,
+            ostemplate,
+            rootfs: allocation(&rootfs.storage, &rootfs.size),
+            password: config.get_string(&AttributePath::new("password")).ok(),
+            ssh_public_keys,
+            unprivileged: config.get_bool(&AttributePath::new("unprivileged")).ok(),
+            start: config.get_bool(&AttributePath::new("start")).ok(),
+            settings: LxcSettings {
+                hostname: self.hostname.clone(),
+                description: self.description.clone(),
+                onboot: self.onboot,
+                features: self.features.as_ref().map(Features::option),
+                cores: self.cores,
+                cpulimit: self.cpulimit,
+                cpuunits: self.cpuunits,
+                memory: self.memory,
+                swap: self.swap,
+                cmode: self.cmode.clone(),
+                tty: self.tty,
+                console: self.console,
+                indexed: self.indexed_options(&Self::default()),
+            },
+        })
+    }
+
+    /// Sends only what changed since `prior`. Removed mount points, devices
+    /// and interfaces, and cleared settings, go through `delete`.
+    fn update_request(&self, prior: &Self) -> UpdateLxcRequest {
+        let current = prior.indexed_options(prior);
+        let planned = self.indexed_options(prior);
+        let mut delete: Vec<String> = current
+            .keys()
+            .filter(|key| !planned.contains_key(*key))
+            .cloned()
+            .collect();
+        for (name, cleared) in [
+            (
+                "description",
+                prior.description.is_some() && self.description.is_none(),
+            ),
+            ("onboot", prior.onboot.is_some() && self.onboot.is_none()),
+            (
+                "features",
+                prior.features.is_some() && self.features.is_none(),
+            ),
+            ("cores", prior.cores.is_some() && self.cores.is_none()),
+            (
+                "cpulimit",
+                prior.cpulimit.is_some() && self.cpulimit.is_none(),
+            ),
+            (
+                "cpuunits",
+                prior.cpuunits.is_some() && self.cpuunits.is_none(),
+            ),
+            ("cmode", prior.cmode.is_some() && self.cmode.is_none()),
+            ("tty", prior.tty.is_some() && self.tty.is_none()),
+            ("console", prior.console.is_some() && self.console.is_none()),
+        ] {
+            if cleared {
+                delete.push(name.to_string());
+            }
+        }
+
+        UpdateLxcRequest {
+            settings: LxcSettings {
+                hostname: changed(&self.hostname, &prior.hostname),
+                description: changed(&self.description, &prior.description),
+                onboot: changed(&self.onboot, &prior.onboot),
+                features: self
+                    .features
+                    .as_ref()
+                    .filter(|_| self.features != prior.features)
+                    .map(Features::option),
+                cores: changed(&self.cores, &prior.cores),
+                cpulimit: changed(&self.cpulimit, &prior.cpulimit),
+                cpuunits: changed(&self.cpuunits, &prior.cpuunits),
+                memory: changed(&self.memory, &prior.memory),
+                swap: changed(&self.swap, &prior.swap),
+                cmode: changed(&self.cmode, &prior.cmode),
+                tty: changed(&self.tty, &prior.tty),
+                console: changed(&self.console, &prior.console),
+                indexed: planned
+                    .into_iter()
+                    .filter(|(key, option)| current.get(key) != Some(option))
+                    .collect(),
+            },
+            delete: (!delete.is_empty()).then(|| delete.join(",")),
+            digest: None,
+        }
+    }
+
+    /// Volumes to grow after the update, as (disk, size). Only volumes that
+    /// stay on their storage can grow, a new volume is allocated at its
+    /// size.
+    fn resizes(&self, prior: &Self) -> Vec<(String, ByteSize)> {
+        let grown = |planned: Option<&str>, current: Option<&str>| match (
+            planned.and_then(ByteSize::parse),
+            current.and_then(ByteSize::parse),
+        ) {
+            (Some(planned), Some(current)) if planned > current => Some(planned),
+            _ => None,
+        };
+        let mut resizes = vec![];
+        if let (Some(rootfs), Some(current)) = (&self.rootfs, &prior.rootfs) {
+            if let Some(size) = grown(Some(&rootfs.size), Some(&current.size)) {
+                resizes.push(("rootfs".to_string(), size));
+            }
+        }
+        for mount_point in &self.mount_points {
+            let current = prior.mount_points.iter().find(|current| {
+                current.id == mount_point.id
+                    && mount_point.volume.is_some()
+                    && current.volume == mount_point.volume
+            });
+            if let Some(current) = current {
+                if let Some(size) = grown(mount_point.size.as_deref(), current.size.as_deref()) {
+                    resizes.push((format!("mp{}", mount_point.id), size));
+                }
+            }
+        }
+        resizes
+    }
+}
+
+/// Copies the container config into state. `state` holds the prior blocks,
+/// whose order and size spelling are kept.
+fn set_container_state(state: &mut DynamicValue, config: &LxcConfig) {
+    let prior = |name: &str| {
+        state
+            .get(&AttributePath::new(name))
+            .cloned()
+            .unwrap_or(Dynamic::Null)
+    };
+    let prior_rootfs = Option::<RootFs>::from_block(&prior("rootfs"))
+        .ok()
+        .flatten();
+    let prior_mount_points =
+        Vec::<MountPoint>::from_block(&prior("mountpoint")).unwrap_or_default();
+    let prior_devices = Vec::<Device>::from_block(&prior("dev")).unwrap_or_default();
+    let prior_networks = Vec::<Network>::from_block(&prior("network")).unwrap_or_default();
+
+    let _ = match &config.hostname {
+        Some(hostname) => state.set_string(&AttributePath::new("hostname"), hostname.clone()),
+        None => state.set_null(&AttributePath::new("hostname")),
+    };
+    let description = normalize_comment(config.description.as_deref());
+    let prior_description = state.get_string(&AttributePath::new("description")).ok();
+    if normalize_comment(prior_description.as_deref()) != description {
+        let _ = match description {
+            Some(description) => state.set_string(&AttributePath::new("description"), description),
+            None => state.set_null(&AttributePath::new("description")),
+        };
+    }
+    let _ = match config.onboot {
+        Some(onboot) => state.set_bool(&AttributePath::new("onboot"), onboot),
+        None => state.set_null(&AttributePath::new("onboot")),
+    };
+    let _ = state.set_bool(
+        &AttributePath::new("unprivileged"),
+        config.unprivileged.unwrap_or(false),
+    );
+    for (name, value) in [
+        ("cores", config.cores.map(f64::from)),
+        ("cpulimit", config.cpulimit),
+        ("cpuunits", config.cpuunits.map(f64::from)),
+        ("tty", config.tty.map(f64::from)),
+    ] {
+        let _ = match value {
+            Some(value) => state.set_number(&AttributePath::new(name), value),
+            None => state.set_null(&AttributePath::new(name)),
+        };
+    }
+    // Proxmox writes both when it creates the container
+    for (name, value) in [("memory", config.memory), ("swap", config.swap)] {
+        let _ = state.set_number(
+            &AttributePath::new(name),
+            value.unwrap_or(DEFAULT_MEMORY) as f64,
+        );
+    }
+    let _ = match &config.cmode {
+        Some(cmode) => state.set_string(&AttributePath::new("cmode"), cmode.clone()),
+        None => state.set_null(&AttributePath::new("cmode")),
+    };
+    let _ = match config.console {
+        Some(console) => state.set_bool(&AttributePath::new("console"), console),
+        None => state.set_null(&AttributePath::new("console")),
+    };
+
+    let rootfs = config.rootfs.as_deref().map(|value| {
+        let rootfs = PropertyString::parse(value, "volume");
+        let volume = rootfs.default_value().unwrap_or_default();
+        RootFs {
+            storage: volume
+                .split_once(':')
+                .map_or(volume, |(storage, _)| storage)
+                .to_string(),
+            size: read_size(
+                rootfs.get("size"),
+                prior_rootfs.as_ref().map(|prior| prior.size.as_str()),
+            )
+            .unwrap_or_default(),
+            volume: Some(volume.to_string()),
+        }
+    });
+
+    let mut mount_points: Vec<MountPoint> = config
+        .indexed("mp")
+        .into_iter()
+        .map(|(id, value)| {
+            let prior = prior_mount_points.iter().find(|prior| prior.id == id);
+            MountPoint::from_option(id, value, prior)
+        })
+        .collect();
+    let prior_ids: Vec<u32> = prior_mount_points.iter().map(|m| m.id).collect();
+    order_like(&mut mount_points, &prior_ids, |m| m.id);
+
+    let mut devices: Vec<Device> = config
+        .indexed("dev")
+        .into_iter()
+        .map(|(id, value)| Device::from_option(id, value))
+        .collect();
+    let prior_ids: Vec<u32> = prior_devices.iter().map(|d| d.id).collect();
+    order_like(&mut devices, &prior_ids, |d| d.id);
+
+    let mut networks: Vec<Network> = config
+        .indexed("net")
+        .into_iter()
+        .map(|(id, value)| Network::from_option(id, value))
+        .collect();
+    let prior_ids: Vec<u32> = prior_networks.iter().map(|n| n.id).collect();
+    order_like(&mut networks, &prior_ids, |n| n.id);
+
+    let features = config
+        .features
+        .as_deref()
+        .filter(|features| !features.is_empty())
+        .map(Features::from_option);
+
+    let raw_config: Vec<RawConfig> = config
+        .lxc
+        .iter()
+        .map(|(key, value)| RawConfig {
+            key: key.clone(),
+            value: value.clone(),
+        })
+        .collect();
+
+    for (name, block) in [
+        ("rootfs", rootfs.to_block()),
+        ("mountpoint", mount_points.to_block()),
+        ("dev", devices.to_block()),
+        ("network", networks.to_block()),
+        ("features", features.to_block()),
+        ("raw_config", raw_config.to_block()),
+    ] {
+        if let Dynamic::List(block) = block {
+            let _ = state.set_list(&AttributePath::new(name), block);
+        }
+    }
+}
+
+/// Whether the container will be unprivileged, None while unknown. The API
+/// creates privileged containers by default.
+fn configured_unprivileged(config: &DynamicValue) -> Option<bool> {
+    match config.get(&AttributePath::new("unprivileged")) {
+        Ok(Dynamic::Bool(unprivileged)) => Some(*unprivileged),
+        Ok(Dynamic::Unknown) => None,
+        _ => Some(false),
+    }
+}
+
+/// Ids that are out of range or used twice
+fn check_ids(
+    block: &str,
+    ids: impl Iterator<Item = u32>,
+    max: u32,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut seen = HashSet::new();
+    for (index, id) in ids.enumerate() {
+        let path = AttributePath::new(block)
+            .index(index as i64)
+            .attribute("id");
+        if id >= max {
+            diagnostics.push(Diagnostic::attribute_error(
+                path,
+                format!("Invalid {} id", block),
+                format!("id must be between 0 and {}, got {}", max - 1, id),
+            ));
+        } else if !seen.insert(id) {
+            diagnostics.push(Diagnostic::attribute_error(
+                path,
+                format!("Duplicate {} id", block),
+                format!(
+                    "{} blocks must have unique ids, {} is used twice",
+                    block, id
+                ),
+            ));
+        }
+    }
+}
+
+/// A size that Proxmox can allocate, in whole bytes above zero
+fn check_size(path: AttributePath, size: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if !ByteSize::parse(size).is_some_and(|size| size.bytes() > 0) {
+        diagnostics.push(Diagnostic::attribute_error(
+            path,
+            "Invalid size",
+            format!(
+                "'{}' is not a size, use a number with a unit such as 512M or 8G",
+                size
+            ),
+        ));
+    }
+}
+
+/// Checks what the schema cannot express: the mount point sources, device
+/// paths and the features and mount options unprivileged containers don't
+/// support. Values that are unknown until apply are skipped.
+fn validate_container(config: &DynamicValue) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let unprivileged = configured_unprivileged(config);
+    let unknown = |block: &str, index: usize, attribute: &str| {
+        config.is_unknown_at(
+            &AttributePath::new(block)
+                .index(index as i64)
+                .attribute(attribute),
+        )
+    };
+    let block = |name: &str| {
+        config
+            .get(&AttributePath::new(name))
+            .cloned()
+            .unwrap_or(Dynamic::Null)
+    };
+
+    match block("rootfs") {
+        Dynamic::Unknown => {}
+        rootfs => match Option::<RootFs>::from_block(&rootfs) {
+            Ok(Some(rootfs)) => {
+                if !unknown("rootfs", 0, "size") {
+                    check_size(
+                        AttributePath::new("rootfs").index(0).attribute("size"),
+                        &rootfs.size,
+                        &mut diagnostics,
+                    );
+                }
+            }
+            Ok(None) => diagnostics.push(Diagnostic::attribute_error(
+                AttributePath::new("rootfs"),
+                "Missing rootfs",
+                "A rootfs block is required",
+            )),
+            Err(_) => {}
+        },
+    }
+
+    if let Ok(mount_points) = Vec::<MountPoint>::from_block(&block("mountpoint")) {
+        check_ids(
+            "mountpoint",
+            mount_points.iter().map(|m| m.id),
+            MAX_MOUNT_POINTS,
+            &mut diagnostics,
+        );
+        for (index, mount_point) in mount_points.iter().enumerate() {
+            let path = |attribute: &str| {
+                AttributePath::new("mountpoint")
+                    .index(index as i64)
+                    .attribute(attribute)
+            };
+            if !unknown("mountpoint", index, "path")
+                && (!mount_point.path.starts_with('/') || mount_point.path == "/")
+            {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path("path"),
+                    "Invalid mount point path",
+                    format!(
+                        "path must be an absolute path below /, got '{}'",
+                        mount_point.path
+                    ),
+                ));
+            }
+            let source_unknown =
+                unknown("mountpoint", index, "storage") || unknown("mountpoint", index, "volume");
+            match (&mount_point.storage, &mount_point.volume) {
+                _ if source_unknown => {}
+                (Some(_), Some(_)) => diagnostics.push(Diagnostic::attribute_error(
+                    path("volume"),
+                    "Conflicting mount point source",
+                    "Set either storage to allocate a new volume or volume to mount an existing one, not both",
+                )),
+                (None, None) => diagnostics.push(Diagnostic::attribute_error(
+                    path("storage"),
+                    "Missing mount point source",
+                    "Set storage to allocate a new volume, or volume to mount an existing volume or host directory",
+                )),
+                (Some(_), None) => match &mount_point.size {
+                    Some(size) => check_size(path("size"), size, &mut diagnostics),
+                    None if unknown("mountpoint", index, "size") => {}
+                    None => diagnostics.push(Diagnostic::attribute_error(
+                        path("size"),
+                        "Missing mount point size",
+                        "size is required to allocate a volume on storage",
+                    )),
+                },
+                (None, Some(_)) => {
+                    if mount_point.size.is_some() {
+                        diagnostics.push(Diagnostic::attribute_error(
+                            path("size"),
+                            "Unexpected mount point size",
+                            "size only applies to volumes allocated on storage",
+                        ));
+                    }
+                    if mount_point.is_bind_mount() && unprivileged == Some(true) {
+                        diagnostics.push(Diagnostic::attribute_warning(
+                            path("volume"),
+                            "Bind mount in an unprivileged container",
+                            "The container's root user is mapped to uid 100000 on the host. Files of the host directory show up as owned by nobody unless they are owned by the mapped ids",
+                        ));
+                    }
+                }
+            }
+            if mount_point.quota == Some(true) && unprivileged == Some(true) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path("quota"),
+                    "Quotas need a privileged container",
+                    "Proxmox does not support user quotas in unprivileged containers",
+                ));
+            }
+        }
+    }
+
+    if let Ok(devices) = Vec::<Device>::from_block(&block("dev")) {
+        check_ids(
+            "dev",
+            devices.iter().map(|d| d.id),
+            MAX_DEVICES,
+            &mut diagnostics,
+        );
+        for (index, device) in devices.iter().enumerate() {
+            let path = |attribute: &str| {
+                AttributePath::new("dev")
+                    .index(index as i64)
+                    .attribute(attribute)
+            };
+            if !unknown("dev", index, "path") && !device.path.starts_with("/dev/") {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path("path"),
+                    "Invalid device path",
+                    format!(
+                        "path must be a device node below /dev/, got '{}'",
+                        device.path
+                    ),
+                ));
+            }
+            if let Some(mode) = &device.mode {
+                let octal =
+                    (3..=4).contains(&mode.len()) && mode.chars().all(|c| ('0'..='7').contains(&c));
+                if !octal {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        path("mode"),
+                        "Invalid device mode",
+                        format!(
+                            "mode must be an octal access mode such as 0660, got '{}'",
+                            mode
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Ok(networks) = Vec::<Network>::from_block(&block("network")) {
+        check_ids(
+            "network",
+            networks.iter().map(|n| n.id),
+            MAX_LXC_NETWORK_INTERFACES,
+            &mut diagnostics,
+        );
+    }
+
+    if let Ok(raw_config) = Vec::<RawConfig>::from_block(&block("raw_config")) {
+        for (index, raw) in raw_config.iter().enumerate() {
+            let path = |attribute: &str| {
+                AttributePath::new("raw_config")
+                    .index(index as i64)
+                    .attribute(attribute)
+            };
+            let valid_key = raw.key.starts_with("lxc.")
+                && raw
+                    .key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+            if !unknown("raw_config", index, "key") && !valid_key {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path("key"),
+                    "Invalid raw config key",
+                    format!(
+                        "Only lxc.* keys can be set raw, e.g. lxc.cgroup2.devices.allow, got '{}'. Use the attributes and blocks for the other options",
+                        raw.key
+                    ),
+                ));
+            }
+            if raw.value.contains('\n') {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path("value"),
+                    "Invalid raw config value",
+                    "A value must fit on one line, use several raw_config blocks for repeated keys",
+                ));
+            }
+        }
+    }
+
+    if let Ok(Some(features)) = Option::<Features>::from_block(&block("features")) {
+        let path = |attribute: &str| AttributePath::new("features").index(0).attribute(attribute);
+        if features.keyctl == Some(true) && unprivileged == Some(false) {
+            diagnostics.push(Diagnostic::attribute_error(
+                path("keyctl"),
+                "keyctl needs an unprivileged container",
+                "Proxmox only allows the keyctl feature in unprivileged containers. Set unprivileged = true",
+            ));
+        }
+        if features.nesting == Some(true) && unprivileged == Some(false) {
+            diagnostics.push(Diagnostic::attribute_warning(
+                path("nesting"),
+                "Nesting in a privileged container",
+                "Nesting exposes the host's procfs and sysfs to the container, whose root user is root on the host. Prefer an unprivileged container",
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// Plans `attribute` of the `block` blocks whose configuration leaves it to
+/// Proxmox. A block keeps the value of the prior block with the same id while
+/// `same` holds for the two, and is unknown otherwise. Terraform would match
+/// prior blocks by position and move values to another block when one in
+/// the middle is removed.
+fn plan_assigned(
+    config: &DynamicValue,
+    prior_state: &DynamicValue,
+    planned_state: &mut DynamicValue,
+    block: &str,
+    attribute: &str,
+    same: fn(&HashMap<String, Dynamic>, &HashMap<String, Dynamic>) -> bool,
+) {
+    let path = AttributePath::new(block);
+    let (Ok(configured), Ok(mut blocks)) = (config.get_list(&path), planned_state.get_list(&path))
+    else {
+        return;
+    };
+    let prior_blocks = prior_state.get_list(&path).unwrap_or_default();
+
+    for (planned, configured) in blocks.iter_mut().zip(&configured) {
+        let (Dynamic::Map(planned), Dynamic::Map(configured)) = (planned, configured) else {
+            continue;
+        };
+        if !matches!(configured.get(attribute), None | Some(Dynamic::Null)) {
+            continue;
+        }
+        let prior_value = prior_blocks.iter().find_map(|prior| match prior {
+            Dynamic::Map(prior) if prior.get("id") == planned.get("id") && same(prior, planned) => {
+                prior
+                    .get(attribute)
+                    .filter(|value| **value != Dynamic::Null)
+                    .cloned()
+            }
+            _ => None,
+        });
+        planned.insert(
+            attribute.to_string(),
+            prior_value.unwrap_or(Dynamic::Unknown),
+        );
+    }
+    let _ = planned_state.set_list(&path, blocks);
+}
+
+/// Mount points keep their volume while they stay on the same storage
+fn same_storage(prior: &HashMap<String, Dynamic>, planned: &HashMap<String, Dynamic>) -> bool {
+    prior.get("storage") == planned.get("storage")
+}
+
+/// Errors for volumes planned smaller than they are. Proxmox can only grow
+/// them.
+fn check_shrinking(prior_state: &DynamicValue, planned_state: &DynamicValue) -> Vec<Diagnostic> {
+    let (Ok(prior), Ok(planned)) = (
+        ContainerSettings::from_config(prior_state),
+        ContainerSettings::from_config(planned_state),
+    ) else {
+        return vec![];
+    };
+    let shrunk = |planned: &str, current: &str| {
+        matches!(
+            (ByteSize::parse(planned), ByteSize::parse(current)),
+            (Some(planned), Some(current)) if planned < current
+        )
+    };
+    let error = |path: AttributePath, current: &str, planned: &str| {
+        Diagnostic::attribute_error(
+            path,
+            "Volumes cannot shrink",
+            format!(
+                "The volume is {} and cannot shrink to {}. Grow it, or move the data to a new volume",
+                current, planned
+            ),
+        )
+    };
+
+    let mut diagnostics = vec![];
+    if let (Some(rootfs), Some(current)) = (&planned.rootfs, &prior.rootfs) {
+        if rootfs.storage == current.storage && shrunk(&rootfs.size, &current.size) {
+            diagnostics.push(error(
+                AttributePath::new("rootfs").index(0).attribute("size"),
+                &current.size,
+                &rootfs.size,
+            ));
+        }
+    }
+    for (index, mount_point) in planned.mount_points.iter().enumerate() {
+        let current = prior
+            .mount_points
+            .iter()
+            .find(|current| current.id == mount_point.id && current.storage == mount_point.storage);
+        if let (Some(size), Some(current_size)) = (
+            &mount_point.size,
+            current.and_then(|current| current.size.as_ref()),
+        ) {
+            if shrunk(size, current_size) {
+                diagnostics.push(error(
+                    AttributePath::new("mountpoint")
+                        .index(index as i64)
+                        .attribute("size"),
+                    current_size,
+                    size,
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Manages an LXC container created from a template, with its root
+/// filesystem, mount points, passed through devices, features and network
+/// interfaces. Destroying the resource stops the container and destroys it
+/// with its volumes.
+#[derive(Default)]
+pub struct LxcResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl LxcResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the container back into `state`
+    async fn read_back(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &ContainerSettings,
+        state: &mut DynamicValue,
+    ) -> Result<(), crate::api::ApiError> {
+        let config = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .lxc()
+            .get_config(settings.vmid)
+            .await?;
+        let _ = state.set_string(&AttributePath::new("id"), settings.vmid.to_string());
+        set_container_state(state, &config);
+        Ok(())
+    }
+
+    /// Writes the raw lxc.* keys into the container's config file over SSH
+    async fn write_raw_config(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &ContainerSettings,
+    ) -> Result<(), Diagnostic> {
+        let ssh = provider_data.require_ssh("Setting raw lxc.* keys")?;
+        ssh.execute(&raw_config_script(
+            &settings.node,
+            settings.vmid,
+            &settings.raw_config,
+        ))
+        .await
+        .map_err(|e| {
+            Diagnostic::error(
+                "Failed to write raw config",
+                format!(
+                    "Unable to write the lxc.* keys of container {}: {}",
+                    settings.vmid, e
+                ),
+            )
+        })?;
+        Ok(())
+    }
+
+    fn read_error(settings: &ContainerSettings, e: &crate::api::ApiError) -> Diagnostic {
+        Diagnostic::error(
+            "Failed to read container",
+            format!(
+                "Unable to read the config of container {} on node '{}': {}",
+                settings.vmid, settings.node, e
+            ),
+        )
+    }
+}
+
+#[async_trait]
+impl Resource for LxcResource {
+    fn type_name(&self) -> &str {
+        "proxmox_lxc"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages an LXC container created from a template, with its root filesystem, mount points, passed through devices, features and network interfaces. Destroying the resource stops the container and destroys it together with its volumes")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The VMID of the container")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to create the container on")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The VMID of the container")
+                    .required()
+                    .validator(IntegerRangeValidator::between(100, 999999999))
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ostemplate", AttributeType::String)
+                    .description("The template to create the container from, e.g. local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst or the volid of a proxmox_lxc_template")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("hostname", AttributeType::String)
+                    .description("Hostname of the container. Proxmox names it CT<vmid> when not set")
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("description", AttributeType::String)
+                    .description("Description shown in the container's notes")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("password", AttributeType::String)
+                    .description("Password of root in the container. Only used when the container is created")
+                    .optional()
+                    .sensitive()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ssh_public_keys", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("SSH public keys for root. Only used when the container is created")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("unprivileged", AttributeType::Bool)
+                    .description("Run the container unprivileged, with its ids mapped to unprivileged ids on the host (default: false)")
+                    .optional()
+                    .computed()
+                    .default(StaticDefault::create(Dynamic::Bool(false)))
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("onboot", AttributeType::Bool)
+                    .description("Start the container when the node boots")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("start", AttributeType::Bool)
+                    .description("Start the container once it is created")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cores", AttributeType::Number)
+                    .description("Number of CPU cores the container sees. Defaults to all cores of the node")
+                    .optional()
+                    .validator(IntegerRangeValidator::between(1, 8192))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cpulimit", AttributeType::Number)
+                    .description("CPU time the container may use, in cores, e.g. 1.5. 0 means no limit (default: 0)")
+                    .optional()
+                    .validator(NumberRangeValidator::between(0.0, 8192.0))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cpuunits", AttributeType::Number)
+                    .description("CPU weight of the container relative to other guests (default: 100)")
+                    .optional()
+                    .validator(IntegerRangeValidator::between(0, 500000))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("memory", AttributeType::Number)
+                    .description("Memory in MB (default: 512)")
+                    .optional()
+                    .computed()
+                    .default(StaticDefault::create(Dynamic::Number(DEFAULT_MEMORY as f64)))
+                    .validator(IntegerRangeValidator::between(16, i64::MAX))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("swap", AttributeType::Number)
+                    .description("Swap in MB (default: 512)")
+                    .optional()
+                    .computed()
+                    .default(StaticDefault::create(Dynamic::Number(DEFAULT_MEMORY as f64)))
+                    .validator(IntegerRangeValidator::between(0, i64::MAX))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cmode", AttributeType::String)
+                    .description("Console mode: tty opens one of the ttys, console attaches to /dev/console and shell runs a shell without login (default: tty)")
+                    .optional()
+                    .validator(StringOneOfValidator::create(
+                        CONSOLE_MODES.iter().map(|s| s.to_string()).collect(),
+                    ))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("tty", AttributeType::Number)
+                    .description("Number of ttys available to the container (default: 2)")
+                    .optional()
+                    .validator(IntegerRangeValidator::between(0, 6))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("console", AttributeType::Bool)
+                    .description("Attach a console device, /dev/console, to the container (default: true)")
+                    .optional()
+                    .build(),
+            )
+            .block(Option::<RootFs>::nested_block(
+                "rootfs",
+                "Root filesystem of the container. Required",
+            ))
+            .block(Vec::<MountPoint>::nested_block(
+                "mountpoint",
+                "Mount points: volumes allocated on a storage, existing volumes or bind mounted host directories. Removing one detaches its volume, which stays with the container as an unused volume",
+            ))
+            .block(Vec::<Device>::nested_block(
+                "dev",
+                "Host devices passed through to the container",
+            ))
+            .block(Option::<Features>::nested_block(
+                "features",
+                "Container features. Proxmox only lets root@pam change them",
+            ))
+            .block(Vec::<Network>::nested_block(
+                "network",
+                "Network interfaces of the container",
+            ))
+            .block(Vec::<RawConfig>::nested_block(
+                "raw_config",
+                "Raw lxc.* keys written to the container's config file, in order, for settings Proxmox has no option for. The API cannot set them, so they need the provider's ssh block and a login as root. They take effect when the container starts",
+            ))
+            .block(Timeouts::block(&[
+                Operation::Create,
+                Operation::Update,
+                Operation::Delete,
+            ]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        ValidateResourceConfigResponse {
+            diagnostics: validate_container(&request.config),
+        }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match ContainerSettings::from_config(&request.planned_state) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+        // Fail before creating anything when the raw keys can't be written
+        if !settings.raw_config.is_empty() {
+            if let Err(diag) = provider_data.require_ssh("Setting raw lxc.* keys") {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        }
+        let mut create_request = match settings.create_request(&request.config) {
+            Ok(create_request) => create_request,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        // The raw keys are written before the container first starts
+        let start = !settings.raw_config.is_empty() && create_request.start == Some(true);
+        if start {
+            create_request.start = None;
+        }
+
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        let timeout = Timeouts::new(&request.config).create(&ctx, CREATE_TIMEOUT);
+        let created = match node_api.lxc().create(&create_request).await {
+            Ok(upid) => node_api
+                .wait_for_task(&upid, timeout, ctx.cancelled())
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = created {
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics(
+                    "Failed to create container",
+                    &e,
+                    CONTAINER_ATTRIBUTES,
+                ),
+            };
+        }
+
+        let mut diagnostics = vec![];
+        if !settings.raw_config.is_empty() {
+            match Self::write_raw_config(provider_data, &settings).await {
+                Ok(()) if start => {
+                    let started = match node_api.lxc().start(settings.vmid).await {
+                        Ok(upid) => node_api
+                            .wait_for_task(&upid, timeout, ctx.cancelled())
+                            .await
+                            .map(|_| ()),
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = started {
+                        diagnostics.extend(api_error_diagnostics(
+                            "Failed to start container",
+                            &e,
+                            CONTAINER_ATTRIBUTES,
+                        ));
+                    }
+                }
+                Ok(()) => {}
+                Err(diag) => diagnostics.push(diag),
+            }
+        }
+
+        let mut new_state = request.planned_state;
+        if let Err(e) = Self::read_back(provider_data, &settings, &mut new_state).await {
+            diagnostics.push(Self::read_error(&settings, &e));
+        }
+        CreateResourceResponse {
+            new_state,
+            private: vec![],
+            diagnostics,
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match ContainerSettings::from_config(&request.current_state) {
+            Ok(settings) => settings,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let mut new_state = request.current_state.clone();
+        match Self::read_back(provider_data, &settings, &mut new_state).await {
+            Ok(()) => ReadResourceResponse {
+                new_state: Some(new_state),
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            // A destroyed container is created again
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Self::read_error(&settings, &e)],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = ContainerSettings::from_config(&request.planned_state);
+        let prior = ContainerSettings::from_config(&request.prior_state);
+        let (settings, prior) = match (settings, prior) {
+            (Ok(settings), Ok(prior)) => (settings, prior),
+            (Err(diag), _) | (_, Err(diag)) => {
+                return UpdateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        let lxc_api = node_api.lxc();
+        let mut result = lxc_api
+            .update_config(settings.vmid, &settings.update_request(&prior))
+            .await;
+        let timeout = Timeouts::new(&request.config).update(&ctx, TASK_TIMEOUT);
+        for (disk, size) in settings.resizes(&prior) {
+            if result.is_err() {
+                break;
+            }
+            result = match lxc_api
+                .resize(settings.vmid, &disk, &size.to_string())
+                .await
+            {
+                Ok(upid) => node_api
+                    .wait_for_task(&upid, timeout, ctx.cancelled())
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e),
+            };
+        }
+        let mut diagnostics = match &result {
+            Ok(()) => vec![],
+            Err(e) => api_error_diagnostics("Failed to update container", e, CONTAINER_ATTRIBUTES),
+        };
+        if result.is_ok() && settings.raw_config != prior.raw_config {
+            match Self::write_raw_config(provider_data, &settings).await {
+                Ok(()) => {
+                    let running = lxc_api
+                        .get_status(settings.vmid)
+                        .await
+                        .is_ok_and(|status| status.status == "running");
+                    if running {
+                        diagnostics.push(Diagnostic::warning(
+                            "Container restart required",
+                            format!(
+                                "Container {} is running. The changed lxc.* keys take effect when it restarts",
+                                settings.vmid
+                            ),
+                        ));
+                    }
+                }
+                Err(diag) => diagnostics.push(diag),
+            }
+        }
+
+        // Read back after a failure too, so state shows what was applied
+        let mut new_state = request.planned_state;
+        if let Err(e) = Self::read_back(provider_data, &settings, &mut new_state).await {
+            diagnostics.push(Self::read_error(&settings, &e));
+        }
+        UpdateResourceResponse {
+            new_state,
+            private: vec![],
+            diagnostics,
+            new_identity: None,
+        }
+    }
+
+    async fn delete(&self, ctx: Context, request: DeleteResourceRequest) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match ContainerSettings::from_config(&request.prior_state) {
+            Ok(settings) => settings,
+            Err(_) => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![],
+                }
+            }
+        };
+
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        let lxc_api = node_api.lxc();
+        let timeout = Timeouts::new(&request.prior_state).delete(&ctx, TASK_TIMEOUT);
+        let result = async {
+            if lxc_api.get_status(settings.vmid).await?.status == "running" {
+                let upid = lxc_api.stop(settings.vmid).await?;
+                node_api
+                    .wait_for_task(&upid, timeout, ctx.cancelled())
+                    .await?;
+            }
+            let upid = lxc_api.delete(settings.vmid, true).await?;
+            node_api
+                .wait_for_task(&upid, timeout, ctx.cancelled())
+                .await
+        }
+        .await;
+        let diagnostics = match result {
+            Err(e) if !e.is_not_found() => {
+                api_error_diagnostics("Failed to delete container", &e, CONTAINER_ATTRIBUTES)
+            }
+            _ => vec![],
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithModifyPlan for LxcResource {
+    /// Keeps allocated volumes and generated MAC addresses with the blocks
+    /// they belong to, rejects shrinking volumes and replaces the container
+    /// when its root filesystem moves to another storage
+    async fn modify_plan(&self, _ctx: Context, request: ModifyPlanRequest) -> ModifyPlanResponse {
+        let mut response = ModifyPlanResponse {
+            planned_state: request.proposed_new_state,
+            requires_replace: vec![],
+            planned_private: request.prior_private,
+            diagnostics: vec![],
+        };
+        // Everything is assigned on create and nothing is planned on destroy
+        if response.planned_state.is_null() || request.prior_state.is_null() {
+            return response;
+        }
+
+        plan_assigned(
+            &request.config,
+            &request.prior_state,
+            &mut response.planned_state,
+            "mountpoint",
+            "volume",
+            same_storage,
+        );
+        plan_assigned(
+            &request.config,
+            &request.prior_state,
+            &mut response.planned_state,
+            "network",
+            "hwaddr",
+            |_, _| true,
+        );
+
+        let storage = AttributePath::new("rootfs").index(0).attribute("storage");
+        if let (Ok(prior), Ok(planned)) = (
+            request.prior_state.get_string(&storage),
+            response.planned_state.get_string(&storage),
+        ) {
+            if prior != planned {
+                response.requires_replace.push(storage);
+                return response;
+            }
+        }
+        response.diagnostics = check_shrinking(&request.prior_state, &response.planned_state);
+        response
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for LxcResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_lxc_test.rs"]
+mod resource_lxc_test;
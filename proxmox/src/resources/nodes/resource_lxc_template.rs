@@ -0,0 +1,627 @@
+//! Container template download resource implementation
+
+use crate::api::nodes::{
+    DownloadTemplateRequest, DownloadUrlRequest, StorageContent, CHECKSUM_ALGORITHMS,
+};
+use crate::api::TaskId;
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigValidators,
+    ResourceWithConfigure, UpdateResourceRequest, UpdateResourceResponse,
+    ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, ConfigValidator, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::{
+    AlsoRequiresValidator, ExactlyOneOfValidator, RequiredTogetherValidator, StringOneOfValidator,
+    UrlValidator,
+};
+
+/// Templates are a few hundred MB.
+const DOWNLOAD_TASK_TIMEOUT: Duration = Duration::from_secs(1800);
+
+const TEMPLATE_ATTRIBUTES: &[&str] = &["storage", "template", "url", "checksum"];
+
+/// Where the template is downloaded from
+#[derive(Debug, PartialEq)]
+enum TemplateSource {
+    /// A template from the node's appliance index, like `pveam download`
+    Appliance(String),
+    /// Any URL, stored under `file_name`
+    Url {
+        url: String,
+        file_name: String,
+        checksum: Option<String>,
+        checksum_algorithm: Option<String>,
+    },
+}
+
+/// Template settings taken from the configuration
+#[derive(Debug)]
+struct TemplateSettings {
+    node: String,
+    storage: String,
+    source: TemplateSource,
+}
+
+impl TemplateSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let storage = config
+            .get_string(&AttributePath::new("storage"))
+            .map_err(|_| {
+                Diagnostic::error("Missing storage", "The 'storage' attribute is required")
+            })?;
+        let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+
+        let source = match (string("template"), string("url")) {
+            (Some(template), _) => TemplateSource::Appliance(template),
+            (None, Some(url)) => {
+                let file_name = string("file_name")
+                    .or_else(|| url_file_name(&url))
+                    .ok_or_else(|| {
+                        Diagnostic::attribute_error(
+                            AttributePath::new("file_name"),
+                            "Missing file_name",
+                            format!("'{}' does not end in a file name, set file_name", url),
+                        )
+                    })?;
+                TemplateSource::Url {
+                    url,
+                    file_name,
+                    checksum: string("checksum"),
+                    checksum_algorithm: string("checksum_algorithm"),
+                }
+            }
+            (None, None) => {
+                return Err(Diagnostic::error(
+                    "Missing template",
+                    "One of 'template' or 'url' is required",
+                ))
+            }
+        };
+
+        Ok(Self {
+            node,
+            storage,
+            source,
+        })
+    }
+
+    fn file_name(&self) -> &str {
+        match &self.source {
+            TemplateSource::Appliance(template) => template,
+            TemplateSource::Url { file_name, .. } => file_name,
+        }
+    }
+
+    /// The volume ID the template is stored as, e.g.
+    /// "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst"
+    fn volid(&self) -> String {
+        format!("{}:vztmpl/{}", self.storage, self.file_name())
+    }
+}
+
+/// The last path segment of a URL, without query or fragment
+fn url_file_name(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    let (_, name) = path.rsplit_once('/')?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Copies what the storage reports about the template into state
+fn set_template_state(
+    state: &mut DynamicValue,
+    settings: &TemplateSettings,
+    volume: &StorageContent,
+) {
+    let _ = state.set_string(&AttributePath::new("id"), volume.volid.clone());
+    let _ = state.set_string(&AttributePath::new("volid"), volume.volid.clone());
+    let _ = state.set_string(
+        &AttributePath::new("file_name"),
+        settings.file_name().to_string(),
+    );
+    let _ = match volume.size {
+        Some(size) => state.set_number(&AttributePath::new("size"), size as f64),
+        None => state.set_null(&AttributePath::new("size")),
+    };
+}
+
+/// Downloads a container template into a storage, from the node's
+/// appliance index like `pveam download` or from any URL. Destroying the
+/// resource removes the template from the storage.
+#[derive(Default)]
+pub struct LxcTemplateResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl LxcTemplateResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts the download. Appliance templates are looked up in the index
+    /// first, which is what `pveam update` refreshes.
+    async fn start_download(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &TemplateSettings,
+    ) -> Result<TaskId, Vec<Diagnostic>> {
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        let started = match &settings.source {
+            TemplateSource::Appliance(template) => {
+                let index = node_api.aplinfo().await.map_err(|e| {
+                    vec![Diagnostic::error(
+                        "Failed to read appliance index",
+                        format!(
+                            "Unable to read the appliance index of node '{}': {}",
+                            settings.node, e
+                        ),
+                    )]
+                })?;
+                if !index.iter().any(|entry| &entry.template == template) {
+                    return Err(vec![Diagnostic::attribute_error(
+                        AttributePath::new("template"),
+                        "Unknown template",
+                        format!(
+                            "'{}' is not in the appliance index of node '{}'. Run 'pveam update' on the node to refresh the index, or download it with url",
+                            template, settings.node
+                        ),
+                    )]);
+                }
+                node_api
+                    .download_template(&DownloadTemplateRequest {
+                        storage: settings.storage.clone(),
+                        template: template.clone(),
+                    })
+                    .await
+            }
+            TemplateSource::Url {
+                url,
+                file_name,
+                checksum,
+                checksum_algorithm,
+            } => {
+                node_api
+                    .storage(&settings.storage)
+                    .download_url(&DownloadUrlRequest {
+                        content: "vztmpl".to_string(),
+                        filename: file_name.clone(),
+                        url: url.clone(),
+                        checksum: checksum.clone(),
+                        checksum_algorithm: checksum_algorithm.clone(),
+                    })
+                    .await
+            }
+        };
+        started.map_err(|e| {
+            api_error_diagnostics("Failed to download template", &e, TEMPLATE_ATTRIBUTES)
+        })
+    }
+
+    /// The template's entry in the storage, None if it is gone
+    async fn find_template(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &TemplateSettings,
+        volid: &str,
+    ) -> Result<Option<StorageContent>, crate::api::ApiError> {
+        let templates = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .storage(&settings.storage)
+            .content(Some("vztmpl"))
+            .await?;
+        Ok(templates.into_iter().find(|volume| volume.volid == volid))
+    }
+}
+
+#[async_trait]
+impl Resource for LxcTemplateResource {
+    fn type_name(&self) -> &str {
+        "proxmox_lxc_template"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Downloads a container template into a storage, either from the node's appliance index like 'pveam download' or from a URL. Use volid as the ostemplate of a container. Every argument forces a new resource. Destroying this resource removes the template from the storage")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The volume ID of the template")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to download the template on")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("storage", AttributeType::String)
+                    .description("The storage to download to. It must allow vztmpl content")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("template", AttributeType::String)
+                    .description("A template from the appliance index as listed by 'pveam available', e.g. debian-12-standard_12.7-1_amd64.tar.zst. Conflicts with url")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("url", AttributeType::String)
+                    .description("URL to download the template from. Conflicts with template")
+                    .optional()
+                    .validator(UrlValidator::create(&["http", "https"]))
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("file_name", AttributeType::String)
+                    .description("File name to store a url download as. Defaults to the last part of the URL, or to template")
+                    .optional()
+                    .computed()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("checksum", AttributeType::String)
+                    .description("Expected checksum of a url download")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("checksum_algorithm", AttributeType::String)
+                    .description("Algorithm of checksum: md5, sha1, sha224, sha256, sha384 or sha512")
+                    .optional()
+                    .validator(StringOneOfValidator::create(
+                        CHECKSUM_ALGORITHMS.iter().map(|s| s.to_string()).collect(),
+                    ))
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("volid", AttributeType::String)
+                    .description("The volume ID of the template, e.g. local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("size", AttributeType::Number)
+                    .description("Size of the template in bytes")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create, Operation::Delete]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        // The template is stored under its own name, so it can't contain
+        // a path
+        for name in ["template", "file_name"] {
+            if let Ok(value) = request.config.get_string(&AttributePath::new(name)) {
+                if value.is_empty() || value.contains('/') {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new(name),
+                        format!("Invalid {}", name),
+                        format!("{} must be a file name without '/'", name),
+                    ));
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match TemplateSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        let upid = match Self::start_download(provider_data, &settings).await {
+            Ok(upid) => upid,
+            Err(diagnostics) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                };
+            }
+        };
+        let timeout = Timeouts::new(&request.config).create(&ctx, DOWNLOAD_TASK_TIMEOUT);
+        if let Err(e) = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .wait_for_task(&upid, timeout, ctx.cancelled())
+            .await
+        {
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics(
+                    "Failed to download template",
+                    &e,
+                    TEMPLATE_ATTRIBUTES,
+                ),
+            };
+        }
+
+        let volid = settings.volid();
+        let mut new_state = request.planned_state;
+        match Self::find_template(provider_data, &settings, &volid).await {
+            Ok(Some(volume)) => {
+                set_template_state(&mut new_state, &settings, &volume);
+                CreateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                }
+            }
+            Ok(None) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read template",
+                    format!(
+                        "The download finished but storage '{}' does not list '{}'",
+                        settings.storage, volid
+                    ),
+                )],
+            },
+            Err(e) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read template",
+                    format!(
+                        "Unable to list templates on storage '{}': {}",
+                        settings.storage, e
+                    ),
+                )],
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = TemplateSettings::from_config(&request.current_state);
+        let volid = request
+            .current_state
+            .get_string(&AttributePath::new("volid"));
+        let (settings, volid) = match (settings, volid) {
+            (Ok(settings), Ok(volid)) => (settings, volid),
+            _ => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match Self::find_template(provider_data, &settings, &volid).await {
+            // A template removed from the storage is downloaded again
+            Ok(volume) => {
+                let new_state = volume.map(|volume| {
+                    let mut new_state = request.current_state.clone();
+                    set_template_state(&mut new_state, &settings, &volume);
+                    new_state
+                });
+                ReadResourceResponse {
+                    new_state,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read template",
+                    format!(
+                        "Unable to list templates on storage '{}': {}",
+                        settings.storage, e
+                    ),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        // Every argument forces a new download, only timeouts can change
+        UpdateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: vec![],
+            new_identity: None,
+        }
+    }
+
+    async fn delete(&self, ctx: Context, request: DeleteResourceRequest) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = TemplateSettings::from_config(&request.prior_state);
+        let volid = request.prior_state.get_string(&AttributePath::new("volid"));
+        let (settings, volid) = match (settings, volid) {
+            (Ok(settings), Ok(volid)) => (settings, volid),
+            _ => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![],
+                }
+            }
+        };
+
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        let result = match node_api
+            .storage(&settings.storage)
+            .delete_volume(&volid)
+            .await
+        {
+            Ok(Some(upid)) => node_api
+                .wait_for_task(
+                    &upid,
+                    Timeouts::new(&request.prior_state).delete(&ctx, DOWNLOAD_TASK_TIMEOUT),
+                    ctx.cancelled(),
+                )
+                .await
+                .map(|_| ()),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        let diagnostics = match result {
+            Err(e) if !e.is_not_found() => {
+                api_error_diagnostics("Failed to delete template", &e, TEMPLATE_ATTRIBUTES)
+            }
+            _ => vec![],
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+impl ResourceWithConfigValidators for LxcTemplateResource {
+    fn config_validators(&self) -> Vec<Box<dyn ConfigValidator>> {
+        vec![
+            ExactlyOneOfValidator::create(&["template", "url"]),
+            AlsoRequiresValidator::create("file_name", &["url"]),
+            AlsoRequiresValidator::create("checksum", &["url"]),
+            RequiredTogetherValidator::create(&["checksum", "checksum_algorithm"]),
+        ]
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for LxcTemplateResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+
+    fn as_config_validators(&self) -> Option<&dyn ResourceWithConfigValidators> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_lxc_template_test.rs"]
+mod resource_lxc_template_test;
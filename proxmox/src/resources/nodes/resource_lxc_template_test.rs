@@ -0,0 +1,226 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::Dynamic;
+
+    const TEMPLATE: &str = "debian-12-standard_12.7-1_amd64.tar.zst";
+    const UPID: &str = "UPID:pve:0000A1B2:0012C3D4:66321F20:download:debian-12-standard_12.7-1_amd64.tar.zst:root@pam:";
+
+    fn template_config(settings: &[(&str, &str)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), Dynamic::Unknown);
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("storage".to_string(), Dynamic::String("local".to_string()));
+        for name in [
+            "template",
+            "url",
+            "file_name",
+            "checksum",
+            "checksum_algorithm",
+        ] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        for (name, value) in settings {
+            obj.insert(name.to_string(), Dynamic::String(value.to_string()));
+        }
+        for computed in ["volid", "size"] {
+            obj.insert(computed.to_string(), Dynamic::Unknown);
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> LxcTemplateResource {
+        LxcTemplateResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    async fn mock_download_finished(server: &mut mockito::ServerGuard) -> Vec<mockito::Mock> {
+        let task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+        let content = server
+            .mock("GET", "/api2/json/nodes/pve/storage/local/content")
+            .match_query(Matcher::UrlEncoded("content".into(), "vztmpl".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"volid": "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst", "content": "vztmpl",
+                     "format": "tzst", "size": 126048366},
+                    {"volid": "local:vztmpl/alpine.tar.xz", "content": "vztmpl", "format": "txz", "size": 3000000}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+        vec![task, content]
+    }
+
+    async fn create(
+        resource: &LxcTemplateResource,
+        config: DynamicValue,
+    ) -> CreateResourceResponse {
+        resource
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_lxc_template".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+    }
+
+    #[test]
+    fn test_url_file_name() {
+        assert_eq!(
+            url_file_name("https://example.com/images/alpine.tar.xz?token=1"),
+            Some("alpine.tar.xz".to_string())
+        );
+        assert_eq!(url_file_name("https://example.com/"), None);
+        assert_eq!(url_file_name("https://example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_downloads_appliance_template() {
+        let mut server = Server::new_async().await;
+        let _index = server
+            .mock("GET", "/api2/json/nodes/pve/aplinfo")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": [{{"template": "{}", "section": "system"}}]}}"#,
+                TEMPLATE
+            ))
+            .create_async()
+            .await;
+        let download = server
+            .mock("POST", "/api2/json/nodes/pve/aplinfo")
+            .match_body(Matcher::Json(serde_json::json!({
+                "storage": "local",
+                "template": TEMPLATE
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let _finished = mock_download_finished(&mut server).await;
+
+        let response = create(
+            &configured_resource(&server.url()),
+            template_config(&[("template", TEMPLATE)]),
+        )
+        .await;
+
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        download.assert_async().await;
+        let state = response.new_state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("volid")).unwrap(),
+            "local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("file_name")).unwrap(),
+            TEMPLATE
+        );
+        assert_eq!(
+            state.get_number(&AttributePath::new("size")).unwrap(),
+            126048366.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_template_missing_from_index() {
+        let mut server = Server::new_async().await;
+        let _index = server
+            .mock("GET", "/api2/json/nodes/pve/aplinfo")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": []}"#)
+            .create_async()
+            .await;
+        let download = server
+            .mock("POST", "/api2/json/nodes/pve/aplinfo")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let response = create(
+            &configured_resource(&server.url()),
+            template_config(&[("template", TEMPLATE)]),
+        )
+        .await;
+
+        download.assert_async().await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Unknown template");
+    }
+
+    #[tokio::test]
+    async fn test_create_downloads_url() {
+        let mut server = Server::new_async().await;
+        let download = server
+            .mock("POST", "/api2/json/nodes/pve/storage/local/download-url")
+            .match_body(Matcher::Json(serde_json::json!({
+                "content": "vztmpl",
+                "filename": "alpine.tar.xz",
+                "url": "https://example.com/images/alpine.tar.xz",
+                "checksum": "abc123",
+                "checksum-algorithm": "sha256"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let _finished = mock_download_finished(&mut server).await;
+
+        let response = create(
+            &configured_resource(&server.url()),
+            template_config(&[
+                ("url", "https://example.com/images/alpine.tar.xz"),
+                ("checksum", "abc123"),
+                ("checksum_algorithm", "sha256"),
+            ]),
+        )
+        .await;
+
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        download.assert_async().await;
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("volid"))
+                .unwrap(),
+            "local:vztmpl/alpine.tar.xz"
+        );
+    }
+}
@@ -0,0 +1,547 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+
+    const UPID: &str = "UPID:pve:00001234:00000000:65000000:vzcreate:200:root@pam:";
+
+    fn rootfs() -> RootFs {
+        RootFs {
+            storage: "local-lvm".to_string(),
+            size: "8G".to_string(),
+            volume: None,
+        }
+    }
+
+    fn container_config(
+        unprivileged: bool,
+        mount_points: Vec<MountPoint>,
+        devices: Vec<Device>,
+        features: Option<Features>,
+    ) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), Dynamic::Unknown);
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("vmid".to_string(), Dynamic::Number(200.0));
+        obj.insert(
+            "ostemplate".to_string(),
+            Dynamic::String("local:vztmpl/debian-12-standard_12.7-1_amd64.tar.zst".to_string()),
+        );
+        obj.insert("unprivileged".to_string(), Dynamic::Bool(unprivileged));
+        obj.insert(
+            "ssh_public_keys".to_string(),
+            Dynamic::List(vec![Dynamic::String(
+                "ssh-ed25519 AAAA user@host".to_string(),
+            )]),
+        );
+        for name in [
+            "hostname",
+            "description",
+            "password",
+            "onboot",
+            "start",
+            "cores",
+            "cpulimit",
+            "cpuunits",
+            "cmode",
+            "tty",
+            "console",
+        ] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        obj.insert("memory".to_string(), Dynamic::Number(512.0));
+        obj.insert("swap".to_string(), Dynamic::Number(512.0));
+        obj.insert("raw_config".to_string(), Dynamic::List(vec![]));
+        obj.insert("rootfs".to_string(), Some(rootfs()).to_block());
+        obj.insert("mountpoint".to_string(), mount_points.to_block());
+        obj.insert("dev".to_string(), devices.to_block());
+        obj.insert("features".to_string(), features.to_block());
+        obj.insert("network".to_string(), Dynamic::List(vec![]));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> LxcResource {
+        LxcResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    fn summaries(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.summary.as_str()).collect()
+    }
+
+    #[test]
+    fn test_validate_mount_points_devices_and_features() {
+        let config = container_config(
+            true,
+            vec![
+                MountPoint {
+                    id: 0,
+                    path: "/var/lib/db".to_string(),
+                    storage: Some("local-lvm".to_string()),
+                    size: Some("16G".to_string()),
+                    quota: Some(true),
+                    ..Default::default()
+                },
+                MountPoint {
+                    id: 0,
+                    path: "data".to_string(),
+                    volume: Some("/srv/data".to_string()),
+                    size: Some("1G".to_string()),
+                    ..Default::default()
+                },
+                MountPoint {
+                    id: 2,
+                    path: "/cache".to_string(),
+                    storage: Some("local-lvm".to_string()),
+                    ..Default::default()
+                },
+            ],
+            vec![Device {
+                id: 0,
+                path: "/tmp/ttyUSB0".to_string(),
+                mode: Some("rw".to_string()),
+                ..Default::default()
+            }],
+            Some(Features {
+                nesting: Some(true),
+                keyctl: Some(true),
+                fuse: Some(true),
+            }),
+        );
+
+        let diagnostics = validate_container(&config);
+        assert_eq!(
+            summaries(&diagnostics),
+            vec![
+                "Duplicate mountpoint id",
+                "Quotas need a privileged container",
+                "Invalid mount point path",
+                "Unexpected mount point size",
+                "Bind mount in an unprivileged container",
+                "Missing mount point size",
+                "Invalid device path",
+                "Invalid device mode",
+            ]
+        );
+        assert!(!diagnostics[4].is_error());
+
+        // keyctl is for unprivileged containers, nesting is only a warning
+        let config = container_config(
+            false,
+            vec![],
+            vec![],
+            Some(Features {
+                nesting: Some(true),
+                keyctl: Some(true),
+                fuse: None,
+            }),
+        );
+        let diagnostics = validate_container(&config);
+        assert_eq!(
+            summaries(&diagnostics),
+            vec![
+                "keyctl needs an unprivileged container",
+                "Nesting in a privileged container",
+            ]
+        );
+        assert!(diagnostics[0].is_error() && !diagnostics[1].is_error());
+    }
+
+    #[test]
+    fn test_update_request_and_resizes() {
+        let mount_point = |id: u32, size: &str| MountPoint {
+            id,
+            path: format!("/mnt/{}", id),
+            storage: Some("local-lvm".to_string()),
+            size: Some(size.to_string()),
+            volume: Some(format!("local-lvm:vm-200-disk-{}", id + 1)),
+            ..Default::default()
+        };
+        let prior = ContainerSettings::from_config(&container_config(
+            true,
+            vec![mount_point(0, "8G"), mount_point(1, "4G")],
+            vec![Device {
+                id: 0,
+                path: "/dev/ttyUSB0".to_string(),
+                ..Default::default()
+            }],
+            None,
+        ))
+        .unwrap();
+        let mut grown = mount_point(0, "16G");
+        grown.backup = Some(true);
+        let planned = ContainerSettings::from_config(&container_config(
+            true,
+            vec![
+                grown,
+                MountPoint {
+                    id: 3,
+                    path: "/srv".to_string(),
+                    volume: Some("/srv/share".to_string()),
+                    read_only: Some(true),
+                    ..Default::default()
+                },
+            ],
+            vec![],
+            Some(Features {
+                nesting: Some(true),
+                ..Default::default()
+            }),
+        ))
+        .unwrap();
+
+        let request = planned.update_request(&prior);
+        assert_eq!(
+            request.settings.indexed,
+            BTreeMap::from([
+                (
+                    "mp0".to_string(),
+                    "local-lvm:vm-200-disk-1,mp=/mnt/0,size=8G,backup=1".to_string()
+                ),
+                ("mp3".to_string(), "/srv/share,mp=/srv,ro=1".to_string()),
+            ])
+        );
+        assert_eq!(request.delete.as_deref(), Some("dev0,mp1"));
+        assert_eq!(request.settings.features.as_deref(), Some("nesting=1"));
+        assert_eq!(request.settings.hostname, None);
+
+        assert_eq!(
+            planned.resizes(&prior),
+            vec![("mp0".to_string(), ByteSize(16 * GIB))]
+        );
+    }
+
+    #[test]
+    fn test_set_container_state() {
+        let config: LxcConfig = serde_json::from_value(serde_json::json!({
+            "hostname": "web",
+            "unprivileged": 1,
+            "features": "nesting=1,keyctl=1",
+            "rootfs": "local-lvm:vm-200-disk-0,size=8G",
+            "mp1": "/srv/data,mp=/data,ro=1",
+            "mp0": "local-lvm:vm-200-disk-1,mp=/var/lib/db,size=16G,backup=1",
+            "dev0": "/dev/ttyUSB0,mode=0660,deny-write=1",
+            "net0": "name=eth0,bridge=vmbr0,hwaddr=bc:24:11:00:00:01,ip=dhcp,type=veth"
+        }))
+        .unwrap();
+        let mut state = container_config(
+            false,
+            vec![
+                MountPoint {
+                    id: 1,
+                    path: "/data".to_string(),
+                    volume: Some("/srv/data".to_string()),
+                    ..Default::default()
+                },
+                MountPoint {
+                    id: 0,
+                    path: "/var/lib/db".to_string(),
+                    storage: Some("local-lvm".to_string()),
+                    size: Some("16384M".to_string()),
+                    ..Default::default()
+                },
+            ],
+            vec![],
+            None,
+        );
+
+        set_container_state(&mut state, &config);
+        let settings = ContainerSettings::from_config(&state).unwrap();
+
+        assert_eq!(settings.hostname.as_deref(), Some("web"));
+        assert!(state.get_bool(&AttributePath::new("unprivileged")).unwrap());
+        assert_eq!(
+            settings.rootfs,
+            Some(RootFs {
+                volume: Some("local-lvm:vm-200-disk-0".to_string()),
+                ..rootfs()
+            })
+        );
+        // Blocks keep their order and size spelling
+        assert_eq!(
+            settings.mount_points,
+            vec![
+                MountPoint {
+                    id: 1,
+                    path: "/data".to_string(),
+                    volume: Some("/srv/data".to_string()),
+                    read_only: Some(true),
+                    ..Default::default()
+                },
+                MountPoint {
+                    id: 0,
+                    path: "/var/lib/db".to_string(),
+                    storage: Some("local-lvm".to_string()),
+                    size: Some("16384M".to_string()),
+                    volume: Some("local-lvm:vm-200-disk-1".to_string()),
+                    backup: Some(true),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            settings.devices,
+            vec![Device {
+                id: 0,
+                path: "/dev/ttyUSB0".to_string(),
+                mode: Some("0660".to_string()),
+                deny_write: Some(true),
+                ..Default::default()
+            }]
+        );
+        assert_eq!(
+            settings.features,
+            Some(Features {
+                nesting: Some(true),
+                keyctl: Some(true),
+                fuse: None,
+            })
+        );
+        assert_eq!(
+            settings.networks[0].hwaddr.as_deref(),
+            Some("BC:24:11:00:00:01")
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_volumes_by_id() {
+        let mount_point = |id: u32, volume: Option<&str>| MountPoint {
+            id,
+            path: format!("/mnt/{}", id),
+            storage: Some("local-lvm".to_string()),
+            size: Some("8G".to_string()),
+            volume: volume.map(str::to_string),
+            ..Default::default()
+        };
+        let prior_state = container_config(
+            true,
+            vec![
+                mount_point(0, Some("local-lvm:vm-200-disk-1")),
+                mount_point(1, Some("local-lvm:vm-200-disk-2")),
+            ],
+            vec![],
+            None,
+        );
+        // Removing mp0 must not hand its volume to mp1 by position
+        let config = container_config(true, vec![mount_point(1, None)], vec![], None);
+        let mut planned_state = container_config(
+            true,
+            vec![mount_point(1, Some("local-lvm:vm-200-disk-1"))],
+            vec![],
+            None,
+        );
+
+        plan_assigned(
+            &config,
+            &prior_state,
+            &mut planned_state,
+            "mountpoint",
+            "volume",
+            same_storage,
+        );
+
+        let volume = AttributePath::new("mountpoint")
+            .index(0)
+            .attribute("volume");
+        assert_eq!(
+            planned_state.get_string(&volume).unwrap(),
+            "local-lvm:vm-200-disk-2"
+        );
+
+        let mut shrunk = mount_point(1, Some("local-lvm:vm-200-disk-2"));
+        shrunk.size = Some("4G".to_string());
+        let planned_state = container_config(true, vec![shrunk], vec![], None);
+        let diagnostics = check_shrinking(&prior_state, &planned_state);
+        assert_eq!(summaries(&diagnostics), vec!["Volumes cannot shrink"]);
+    }
+
+    #[tokio::test]
+    async fn test_create_allocates_volumes_and_reads_back() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/nodes/pve/lxc")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "vmid": 200,
+                "rootfs": "local-lvm:8",
+                "ssh-public-keys": "ssh-ed25519 AAAA user@host",
+                "unprivileged": true,
+                "features": "nesting=1,keyctl=1",
+                "mp0": "local-lvm:0.5,mp=/var/lib/db,backup=1",
+                "dev0": "/dev/ttyUSB0,mode=0660"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .create_async()
+            .await;
+        let _task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+        let _config = server
+            .mock("GET", "/api2/json/nodes/pve/lxc/200/config")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {
+                    "hostname": "CT200", "unprivileged": 1, "features": "nesting=1,keyctl=1",
+                    "rootfs": "local-lvm:vm-200-disk-0,size=8G",
+                    "mp0": "local-lvm:vm-200-disk-1,mp=/var/lib/db,backup=1,size=512M",
+                    "dev0": "/dev/ttyUSB0,mode=0660",
+                    "digest": "abc"
+                }}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = container_config(
+            true,
+            vec![MountPoint {
+                id: 0,
+                path: "/var/lib/db".to_string(),
+                storage: Some("local-lvm".to_string()),
+                size: Some("512M".to_string()),
+                backup: Some(true),
+                ..Default::default()
+            }],
+            vec![Device {
+                id: 0,
+                path: "/dev/ttyUSB0".to_string(),
+                mode: Some("0660".to_string()),
+                ..Default::default()
+            }],
+            Some(Features {
+                nesting: Some(true),
+                keyctl: Some(true),
+                fuse: None,
+            }),
+        );
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_lxc".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        create.assert_async().await;
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        let state = response.new_state;
+        assert_eq!(state.get_string(&AttributePath::new("id")).unwrap(), "200");
+        assert_eq!(
+            state.get_string(&AttributePath::new("hostname")).unwrap(),
+            "CT200"
+        );
+        let mount_point = AttributePath::new("mountpoint").index(0);
+        assert_eq!(
+            state
+                .get_string(&mount_point.clone().attribute("volume"))
+                .unwrap(),
+            "local-lvm:vm-200-disk-1"
+        );
+        assert_eq!(
+            state.get_string(&mount_point.attribute("size")).unwrap(),
+            "512M"
+        );
+    }
+
+    #[test]
+    fn test_tuning_update_request() {
+        let mut prior =
+            ContainerSettings::from_config(&container_config(true, vec![], vec![], None)).unwrap();
+        prior.cores = Some(2);
+        prior.cmode = Some("console".to_string());
+        prior.tty = Some(2);
+        let mut planned =
+            ContainerSettings::from_config(&container_config(true, vec![], vec![], None)).unwrap();
+        planned.memory = Some(1024);
+        planned.tty = Some(2);
+        planned.cpulimit = Some(1.5);
+
+        let request = planned.update_request(&prior);
+        assert_eq!(request.settings.memory, Some(1024));
+        assert_eq!(request.settings.swap, None);
+        assert_eq!(request.settings.tty, None);
+        assert_eq!(request.settings.cpulimit, Some(1.5));
+        assert_eq!(request.delete.as_deref(), Some("cores,cmode"));
+    }
+
+    #[test]
+    fn test_raw_config() {
+        let raw = |key: &str, value: &str| RawConfig {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let mut config = container_config(true, vec![], vec![], None);
+        let _ = config.set_list(
+            &AttributePath::new("raw_config"),
+            vec![
+                raw("lxc.cgroup2.devices.allow", "c 188:* rwm").to_dynamic(),
+                raw("memory", "1024").to_dynamic(),
+                raw("lxc.mount.entry", "a\nb").to_dynamic(),
+            ],
+        );
+        let diagnostics = validate_container(&config);
+        assert_eq!(
+            summaries(&diagnostics),
+            vec!["Invalid raw config key", "Invalid raw config value"]
+        );
+
+        let script = raw_config_script(
+            "pve",
+            200,
+            &[
+                raw("lxc.cgroup2.devices.allow", "c 188:* rwm"),
+                raw("lxc.apparmor.profile", "it's"),
+            ],
+        );
+        assert!(script.contains("conf=/etc/pve/nodes/pve/lxc/200.conf\n"));
+        assert!(script.contains(
+            r"awk -v raw='lxc.cgroup2.devices.allow: c 188:* rwm\nlxc.apparmor.profile: it'\''s\n' "
+        ));
+
+        // Raw keys and tuning options are read back from the config
+        let lxc_config: LxcConfig = serde_json::from_value(serde_json::json!({
+            "rootfs": "local-lvm:vm-200-disk-0,size=8G",
+            "cores": 2, "cmode": "shell", "swap": 0,
+            "lxc": [["lxc.cgroup2.devices.allow", "c 188:* rwm"],
+                    ["lxc.cgroup2.devices.allow", "c 189:* rwm"]]
+        }))
+        .unwrap();
+        set_container_state(&mut config, &lxc_config);
+        let settings = ContainerSettings::from_config(&config).unwrap();
+        assert_eq!(settings.cores, Some(2));
+        assert_eq!(settings.cmode.as_deref(), Some("shell"));
+        assert_eq!(settings.memory, Some(512));
+        assert_eq!(settings.swap, Some(0));
+        assert_eq!(settings.tty, None);
+        assert_eq!(
+            settings.raw_config,
+            vec![
+                raw("lxc.cgroup2.devices.allow", "c 188:* rwm"),
+                raw("lxc.cgroup2.devices.allow", "c 189:* rwm"),
+            ]
+        );
+    }
+}
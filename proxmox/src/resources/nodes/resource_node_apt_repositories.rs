@@ -0,0 +1,401 @@
+//! Node APT repositories resource implementation
+
+use crate::api::nodes::{
+    is_standard_repository_handle, AddAptRepositoryRequest, AptRepositories,
+    SetAptRepositoryRequest,
+};
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+
+/// Desired repository states taken from the configuration
+#[derive(Debug)]
+struct RepositorySettings {
+    node: String,
+    /// Handle to whether the repository should be enabled, sorted so the
+    /// changes are made in a stable order
+    repositories: Vec<(String, bool)>,
+}
+
+impl RepositorySettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let mut repositories: Vec<(String, bool)> = config
+            .get_map(&AttributePath::new("repositories"))
+            .map_err(|_| {
+                Diagnostic::error(
+                    "Missing repositories",
+                    "The 'repositories' attribute is required",
+                )
+            })?
+            .into_iter()
+            .filter_map(|(handle, enabled)| match enabled {
+                Dynamic::Bool(enabled) => Some((handle, enabled)),
+                _ => None,
+            })
+            .collect();
+        repositories.sort();
+
+        Ok(Self { node, repositories })
+    }
+}
+
+/// Sets each managed handle to whether the repository is enabled on the
+/// node. Repositories that aren't configured count as disabled.
+fn set_repositories_state(state: &mut DynamicValue, repos: &AptRepositories) {
+    let handles = state
+        .get_map(&AttributePath::new("repositories"))
+        .unwrap_or_default();
+    let _ = state.set_map(
+        &AttributePath::new("repositories"),
+        handles
+            .into_keys()
+            .map(|handle| {
+                let enabled = repos.is_enabled(&handle);
+                (handle, Dynamic::Bool(enabled))
+            })
+            .collect(),
+    );
+}
+
+/// Enables and disables the standard Proxmox APT repositories of a node,
+/// e.g. to switch from the enterprise to the no-subscription repository.
+/// Destroying the resource only removes it from state.
+#[derive(Default)]
+pub struct NodeAptRepositoriesResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeAptRepositoriesResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Brings the repositories to the wanted state and reads them back.
+    /// Enabling a repository that isn't in any file adds it; disabling one
+    /// that isn't configured is left alone.
+    async fn apply(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &RepositorySettings,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let node = provider_data.client.nodes().node(&settings.node);
+        let read_error = |e| {
+            Diagnostic::error(
+                "Failed to read APT repositories",
+                format!(
+                    "Unable to read APT repositories of node '{}': {}",
+                    settings.node, e
+                ),
+            )
+        };
+        let update_error = |handle: &str, e| {
+            Diagnostic::error(
+                "Failed to update APT repository",
+                format!(
+                    "Unable to update the '{}' repository of node '{}': {}",
+                    handle, settings.node, e
+                ),
+            )
+        };
+
+        let repos = node.apt_repositories().await.map_err(read_error)?;
+        for (handle, enabled) in &settings.repositories {
+            if *enabled && !repos.is_configured(handle) {
+                node.add_apt_repository(&AddAptRepositoryRequest {
+                    handle: handle.clone(),
+                })
+                .await
+                .map_err(|e| update_error(handle, e))?;
+                continue;
+            }
+            for (path, index) in repos.entries_to_toggle(handle, *enabled) {
+                node.set_apt_repository(&SetAptRepositoryRequest {
+                    path,
+                    index,
+                    enabled: *enabled,
+                })
+                .await
+                .map_err(|e| update_error(handle, e))?;
+            }
+        }
+
+        let repos = node.apt_repositories().await.map_err(read_error)?;
+        set_repositories_state(&mut state, &repos);
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl Resource for NodeAptRepositoriesResource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_apt_repositories"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Enables and disables the standard Proxmox APT repositories of a node. Destroying this resource leaves the repositories as they are")
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to configure")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("repositories", AttributeType::Map(Box::new(AttributeType::Bool)))
+                    .description("Whether each repository should be enabled, keyed by handle: enterprise, no-subscription, test or ceph-{release}-{enterprise,no-subscription,test}, e.g. ceph-squid-no-subscription. Repositories not listed are left alone")
+                    .required()
+                    .build(),
+            )
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        if let Ok(repositories) = request.config.get_map(&AttributePath::new("repositories")) {
+            let mut handles: Vec<&String> = repositories.keys().collect();
+            handles.sort();
+            for handle in handles {
+                if !is_standard_repository_handle(handle) {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new("repositories").key(handle),
+                        "Invalid repository handle",
+                        format!(
+                            "'{}' is not a standard repository. Use enterprise, no-subscription, test or ceph-{{release}}-{{enterprise,no-subscription,test}}",
+                            handle
+                        ),
+                    ));
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let result = match RepositorySettings::from_config(&request.config) {
+            Ok(settings) => {
+                Self::apply(provider_data, &settings, request.planned_state.clone()).await
+            }
+            Err(diag) => Err(diag),
+        };
+
+        match result {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            },
+            Err(diag) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![diag],
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let node = match request
+            .current_state
+            .get_string(&AttributePath::new("node"))
+        {
+            Ok(node) => node,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .apt_repositories()
+            .await
+        {
+            Ok(repos) => {
+                let mut new_state = request.current_state.clone();
+                set_repositories_state(&mut new_state, &repos);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read APT repositories",
+                    format!("Unable to read APT repositories of node '{}': {}", node, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let result = match RepositorySettings::from_config(&request.config) {
+            Ok(settings) => Self::apply(provider_data, &settings, request.planned_state).await,
+            Err(diag) => Err(diag),
+        };
+
+        match result {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            Err(diag) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: vec![diag],
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // Dropping a repository from the configuration leaves it as it is,
+        // so destroying the whole resource does the same
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for NodeAptRepositoriesResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_node_apt_repositories_test.rs"]
+mod resource_node_apt_repositories_test;
@@ -0,0 +1,199 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn repositories_config(repositories: &[(&str, bool)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert(
+            "repositories".to_string(),
+            Dynamic::Map(
+                repositories
+                    .iter()
+                    .map(|(handle, enabled)| (handle.to_string(), Dynamic::Bool(*enabled)))
+                    .collect(),
+            ),
+        );
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn repositories_body(enterprise: u8, no_subscription: u8) -> String {
+        format!(
+            r#"{{"data": {{
+                "files": [
+                    {{"path": "/etc/apt/sources.list.d/pve-enterprise.list", "repositories": [
+                        {{"Types": ["deb"], "URIs": ["https://enterprise.proxmox.com/debian/pve"],
+                          "Suites": ["bookworm"], "Components": ["pve-enterprise"], "Enabled": {enterprise}}}
+                    ]}},
+                    {{"path": "/etc/apt/sources.list", "repositories": [
+                        {{"Types": ["deb"], "URIs": ["http://deb.debian.org/debian"],
+                          "Suites": ["bookworm"], "Components": ["main"], "Enabled": 1}},
+                        {{"Types": ["deb"], "URIs": ["http://download.proxmox.com/debian/pve"],
+                          "Suites": ["bookworm"], "Components": ["pve-no-subscription"], "Enabled": {no_subscription}}}
+                    ]}}
+                ],
+                "standard-repos": [
+                    {{"handle": "enterprise", "status": {enterprise}}},
+                    {{"handle": "no-subscription", "status": {no_subscription}}},
+                    {{"handle": "test"}}
+                ]
+            }}}}"#
+        )
+    }
+
+    fn configured_resource(url: &str) -> NodeAptRepositoriesResource {
+        NodeAptRepositoriesResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_unknown_handles() {
+        let response = NodeAptRepositoriesResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_node_apt_repositories".to_string(),
+                    config: repositories_config(&[
+                        ("no-subscription", true),
+                        ("pve-enterprise", false),
+                        ("ceph-squid-no-subscription", true),
+                    ]),
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid repository handle");
+        assert!(response.diagnostics[0].detail.contains("'pve-enterprise'"));
+    }
+
+    #[tokio::test]
+    async fn test_create_switches_to_no_subscription() {
+        let mut server = Server::new_async().await;
+        let _before = server
+            .mock("GET", "/api2/json/nodes/pve/apt/repositories")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(repositories_body(1, 0))
+            .expect(1)
+            .create_async()
+            .await;
+        let _after = server
+            .mock("GET", "/api2/json/nodes/pve/apt/repositories")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(repositories_body(0, 1))
+            .create_async()
+            .await;
+        let disable_enterprise = server
+            .mock("POST", "/api2/json/nodes/pve/apt/repositories")
+            .match_body(Matcher::Json(serde_json::json!({
+                "path": "/etc/apt/sources.list.d/pve-enterprise.list",
+                "index": 0,
+                "enabled": false
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let enable_no_subscription = server
+            .mock("POST", "/api2/json/nodes/pve/apt/repositories")
+            .match_body(Matcher::Json(serde_json::json!({
+                "path": "/etc/apt/sources.list",
+                "index": 1,
+                "enabled": true
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let add_test = server
+            .mock("PUT", "/api2/json/nodes/pve/apt/repositories")
+            .with_status(200)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let config = repositories_config(&[
+            ("enterprise", false),
+            ("no-subscription", true),
+            ("test", false),
+        ]);
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_node_apt_repositories".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        disable_enterprise.assert_async().await;
+        enable_no_subscription.assert_async().await;
+        add_test.assert_async().await;
+        let repositories = response
+            .new_state
+            .get_map(&AttributePath::new("repositories"))
+            .unwrap();
+        assert_eq!(repositories["enterprise"], Dynamic::Bool(false));
+        assert_eq!(repositories["no-subscription"], Dynamic::Bool(true));
+        assert_eq!(repositories["test"], Dynamic::Bool(false));
+    }
+
+    #[tokio::test]
+    async fn test_create_adds_missing_repository() {
+        let mut server = Server::new_async().await;
+        let _get = server
+            .mock("GET", "/api2/json/nodes/pve/apt/repositories")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(repositories_body(1, 0))
+            .create_async()
+            .await;
+        let add_test = server
+            .mock("PUT", "/api2/json/nodes/pve/apt/repositories")
+            .match_body(Matcher::Json(serde_json::json!({"handle": "test"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = repositories_config(&[("test", true)]);
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_node_apt_repositories".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        add_test.assert_async().await;
+    }
+}
@@ -0,0 +1,723 @@
+//! Disk initialization resource implementations for LVM, LVM-thin, ZFS and
+//! directory storage
+
+use crate::api::nodes::{
+    CreateDiskStorageRequest, DiskStorageKind, DIRECTORY_FILESYSTEMS, ZFS_COMPRESSION,
+    ZFS_RAID_LEVELS,
+};
+use crate::api::TaskId;
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+/// Formatting and partitioning large disks or building a pool over many of
+/// them takes a while.
+const DISK_TASK_TIMEOUT: Duration = Duration::from_secs(600);
+
+const DISK_ATTRIBUTES: &[&str] = &[
+    "name",
+    "device",
+    "devices",
+    "raidlevel",
+    "ashift",
+    "compression",
+    "filesystem",
+    "add_storage",
+];
+
+/// Fewest devices each ZFS RAID level can be built from
+fn zfs_min_devices(raidlevel: &str) -> usize {
+    match raidlevel {
+        "mirror" => 2,
+        "raidz" => 3,
+        "raidz2" | "raid10" => 4,
+        "raidz3" => 5,
+        _ => 1,
+    }
+}
+
+/// Storage settings taken from the configuration
+#[derive(Debug)]
+struct DiskStorageSettings {
+    node: String,
+    name: String,
+    device: Option<String>,
+    devices: Vec<String>,
+    raidlevel: Option<String>,
+    ashift: Option<u32>,
+    compression: Option<String>,
+    filesystem: Option<String>,
+    add_storage: bool,
+}
+
+impl DiskStorageSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let name = config
+            .get_string(&AttributePath::new("name"))
+            .map_err(|_| Diagnostic::error("Missing name", "The 'name' attribute is required"))?;
+        let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+        let devices = config
+            .get_list(&AttributePath::new("devices"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|device| match device {
+                Dynamic::String(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+
+        Ok(Self {
+            node,
+            name,
+            device: string("device"),
+            devices,
+            raidlevel: string("raidlevel"),
+            ashift: config.get_u32(&AttributePath::new("ashift")).ok(),
+            compression: string("compression"),
+            filesystem: string("filesystem"),
+            add_storage: config
+                .get_bool(&AttributePath::new("add_storage"))
+                .unwrap_or(false),
+        })
+    }
+
+    fn create_request(&self) -> CreateDiskStorageRequest {
+        CreateDiskStorageRequest {
+            name: self.name.clone(),
+            device: self.device.clone(),
+            devices: (!self.devices.is_empty()).then(|| self.devices.join(",")),
+            raidlevel: self.raidlevel.clone(),
+            ashift: self.ashift,
+            compression: self.compression.clone(),
+            filesystem: self.filesystem.clone(),
+            add_storage: self.add_storage.then_some(true),
+        }
+    }
+}
+
+/// Initializes unused disks of a node as storage. The same implementation
+/// backs one resource type per kind of storage.
+pub struct NodeDiskStorageResource {
+    kind: DiskStorageKind,
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeDiskStorageResource {
+    fn with_kind(kind: DiskStorageKind) -> Self {
+        Self {
+            kind,
+            provider_data: None,
+        }
+    }
+
+    /// proxmox_node_disk_lvm
+    pub fn lvm() -> Self {
+        Self::with_kind(DiskStorageKind::Lvm)
+    }
+
+    /// proxmox_node_disk_lvmthin
+    pub fn lvm_thin() -> Self {
+        Self::with_kind(DiskStorageKind::LvmThin)
+    }
+
+    /// proxmox_node_zfs_pool
+    pub fn zfs_pool() -> Self {
+        Self::with_kind(DiskStorageKind::Zfs)
+    }
+
+    /// proxmox_node_disk_directory
+    pub fn directory() -> Self {
+        Self::with_kind(DiskStorageKind::Directory)
+    }
+
+    /// What the storage is called in messages
+    fn label(&self) -> &'static str {
+        match self.kind {
+            DiskStorageKind::Lvm => "LVM volume group",
+            DiskStorageKind::LvmThin => "LVM thin pool",
+            DiskStorageKind::Zfs => "ZFS pool",
+            DiskStorageKind::Directory => "directory storage",
+        }
+    }
+
+    async fn wait(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        node: &str,
+        task: Result<TaskId, crate::api::ApiError>,
+        timeout: Duration,
+        summary: &str,
+    ) -> Result<(), Vec<Diagnostic>> {
+        let node_api = provider_data.client.nodes().node(node);
+        let result = match task {
+            Ok(upid) => node_api
+                .wait_for_task(&upid, timeout, ctx.cancelled())
+                .await
+                .map(|_| ()),
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| api_error_diagnostics(summary, &e, DISK_ATTRIBUTES))
+    }
+
+    /// The state with what the API reports, or None if the storage is gone.
+    /// The API lists what is on the disks, not how it was created, so apart
+    /// from the size and health of ZFS pools only whether it still exists is
+    /// read back.
+    async fn read_back(
+        &self,
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &DiskStorageSettings,
+        mut state: DynamicValue,
+    ) -> Result<Option<DynamicValue>, crate::api::ApiError> {
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        if self.kind != DiskStorageKind::Zfs {
+            let exists = node_api
+                .disk_storage_exists(self.kind, &settings.name)
+                .await?;
+            return Ok(exists.then_some(state));
+        }
+
+        match node_api.zfs_pool(&settings.name).await {
+            Ok(pool) => {
+                let _ = state.set_number(&AttributePath::new("size"), pool.size as f64);
+                let _ = match pool.health {
+                    Some(health) => state.set_string(&AttributePath::new("health"), health),
+                    None => state.set_null(&AttributePath::new("health")),
+                };
+                Ok(Some(state))
+            }
+            Err(e) if e.is_not_found() => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl Resource for NodeDiskStorageResource {
+    fn type_name(&self) -> &str {
+        match self.kind {
+            DiskStorageKind::Lvm => "proxmox_node_disk_lvm",
+            DiskStorageKind::LvmThin => "proxmox_node_disk_lvmthin",
+            DiskStorageKind::Zfs => "proxmox_node_zfs_pool",
+            DiskStorageKind::Directory => "proxmox_node_disk_directory",
+        }
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let description = match self.kind {
+            DiskStorageKind::Lvm => "Initializes an unused disk of a node as an LVM volume group. Every argument but cleanup_disks forces a new resource",
+            DiskStorageKind::LvmThin => "Initializes an unused disk of a node as an LVM thin pool in a volume group of the same name. Every argument but cleanup_disks forces a new resource",
+            DiskStorageKind::Zfs => "Creates a ZFS pool from unused disks of a node. Every argument but cleanup_disks forces a new resource",
+            DiskStorageKind::Directory => "Formats an unused disk of a node and mounts it at /mnt/pve/<name>. Every argument but cleanup_disks forces a new resource",
+        };
+        let name_description = match self.kind {
+            DiskStorageKind::Lvm => "The volume group name, also used for the storage",
+            DiskStorageKind::LvmThin => {
+                "The thin pool and volume group name, also used for the storage"
+            }
+            DiskStorageKind::Zfs => "The pool name, also used for the storage",
+            DiskStorageKind::Directory => {
+                "The storage name. The disk is mounted at /mnt/pve/<name>"
+            }
+        };
+
+        let mut builder = SchemaBuilder::new()
+            .version(0)
+            .description(description)
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node the disks are in")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .description(name_description)
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            );
+
+        builder = match self.kind {
+            DiskStorageKind::Zfs => builder
+                .attribute(
+                    AttributeBuilder::new("devices", AttributeType::List(Box::new(AttributeType::String)))
+                        .description("The disks to build the pool from, e.g. [\"/dev/sdb\", \"/dev/sdc\"]")
+                        .required()
+                        .plan_modifier(RequiresReplace::create())
+                        .build(),
+                )
+                .attribute(
+                    AttributeBuilder::new("raidlevel", AttributeType::String)
+                        .description("RAID level: single, mirror, raid10, raidz, raidz2 or raidz3")
+                        .required()
+                        .plan_modifier(RequiresReplace::create())
+                        .build(),
+                )
+                .attribute(
+                    AttributeBuilder::new("ashift", AttributeType::Number)
+                        .description("Pool sector size exponent (9-16). Defaults to 12")
+                        .optional()
+                        .plan_modifier(RequiresReplace::create())
+                        .build(),
+                )
+                .attribute(
+                    AttributeBuilder::new("compression", AttributeType::String)
+                        .description("Compression algorithm: on, off, gzip, lz4, lzjb, zle or zstd. Defaults to on")
+                        .optional()
+                        .plan_modifier(RequiresReplace::create())
+                        .build(),
+                )
+                .attribute(
+                    AttributeBuilder::new("size", AttributeType::Number)
+                        .description("Size of the pool in bytes")
+                        .computed()
+                        .build(),
+                )
+                .attribute(
+                    AttributeBuilder::new("health", AttributeType::String)
+                        .description("Health of the pool, e.g. ONLINE or DEGRADED")
+                        .computed()
+                        .build(),
+                ),
+            _ => builder.attribute(
+                AttributeBuilder::new("device", AttributeType::String)
+                    .description("The unused disk to initialize, e.g. /dev/sdb")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            ),
+        };
+        if self.kind == DiskStorageKind::Directory {
+            builder = builder.attribute(
+                AttributeBuilder::new("filesystem", AttributeType::String)
+                    .description(
+                        "Filesystem to format the disk with: ext4 or xfs. Defaults to ext4",
+                    )
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            );
+        }
+
+        let schema = builder
+            .attribute(
+                AttributeBuilder::new("add_storage", AttributeType::Bool)
+                    .description("Also add a storage of the same name so VM disks can use it right away. The storage is removed together with the resource")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("cleanup_disks", AttributeType::Bool)
+                    .description("Wipe the disks when the resource is destroyed so they show up as unused again. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create, Operation::Delete]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["ashift"]);
+
+        if let Ok(name) = config.get_string(&AttributePath::new("name")) {
+            let valid = name.len() > 1
+                && name.starts_with(|c: char| c.is_ascii_alphabetic())
+                && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !valid {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("name"),
+                    "Invalid name",
+                    "name must start with a letter, end with a letter or digit and only contain letters, digits, '-', '_' and '.'",
+                ));
+            }
+        }
+
+        if let Ok(device) = config.get_string(&AttributePath::new("device")) {
+            if !device.starts_with("/dev/") {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("device"),
+                    "Invalid device",
+                    format!(
+                        "device must be a device path like /dev/sdb, got '{}'",
+                        device
+                    ),
+                ));
+            }
+        }
+        if let Ok(Dynamic::List(devices)) = config.get(&AttributePath::new("devices")) {
+            for (index, device) in devices.iter().enumerate() {
+                if let Dynamic::String(device) = device {
+                    if !device.starts_with("/dev/") {
+                        diagnostics.push(Diagnostic::attribute_error(
+                            AttributePath::new("devices").index(index as i64),
+                            "Invalid device",
+                            format!(
+                                "devices must be device paths like /dev/sdb, got '{}'",
+                                device
+                            ),
+                        ));
+                    }
+                }
+            }
+            if let Ok(raidlevel) = config.get_string(&AttributePath::new("raidlevel")) {
+                let min = zfs_min_devices(&raidlevel);
+                let known = !devices
+                    .iter()
+                    .any(|device| matches!(device, Dynamic::Unknown));
+                if known && devices.len() < min {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new("devices"),
+                        "Not enough devices",
+                        format!(
+                            "A {} pool needs at least {} devices, got {}",
+                            raidlevel,
+                            min,
+                            devices.len()
+                        ),
+                    ));
+                }
+                if raidlevel == "raid10" && known && devices.len() % 2 != 0 {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new("devices"),
+                        "Invalid devices",
+                        "A raid10 pool needs an even number of devices",
+                    ));
+                }
+            }
+        }
+
+        for (name, allowed) in [
+            ("raidlevel", ZFS_RAID_LEVELS),
+            ("compression", ZFS_COMPRESSION),
+            ("filesystem", DIRECTORY_FILESYSTEMS),
+        ] {
+            if let Ok(value) = config.get_string(&AttributePath::new(name)) {
+                if !allowed.contains(&value.as_str()) {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new(name),
+                        format!("Invalid {}", name),
+                        format!("{} must be one of: {}", name, allowed.join(", ")),
+                    ));
+                }
+            }
+        }
+
+        if let Ok(ashift) = config.get_number(&AttributePath::new("ashift")) {
+            if !(9.0..=16.0).contains(&ashift) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    AttributePath::new("ashift"),
+                    "Invalid ashift",
+                    "ashift must be between 9 and 16",
+                ));
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match DiskStorageSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        let task = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .create_disk_storage(self.kind, &settings.create_request())
+            .await;
+        if let Err(diagnostics) = Self::wait(
+            &ctx,
+            provider_data,
+            &settings.node,
+            task,
+            Timeouts::new(&request.config).create(&ctx, DISK_TASK_TIMEOUT),
+            &format!("Failed to create {}", self.label()),
+        )
+        .await
+        {
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics,
+            };
+        }
+        if settings.add_storage {
+            provider_data.invalidate_storages().await;
+        }
+
+        let missing = || {
+            Diagnostic::error(
+                format!("Failed to read {}", self.label()),
+                format!(
+                    "{} '{}' was created but is not listed on node '{}'",
+                    self.label(),
+                    settings.name,
+                    settings.node
+                ),
+            )
+        };
+        match self
+            .read_back(provider_data, &settings, request.planned_state.clone())
+            .await
+        {
+            Ok(Some(new_state)) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            },
+            Ok(None) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![missing()],
+            },
+            Err(e) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![Diagnostic::error(
+                    format!("Failed to read {}", self.label()),
+                    format!(
+                        "Unable to read {} '{}' on node '{}': {}",
+                        self.label(),
+                        settings.name,
+                        settings.node,
+                        e
+                    ),
+                )],
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match DiskStorageSettings::from_config(&request.current_state) {
+            Ok(settings) => settings,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match self
+            .read_back(provider_data, &settings, request.current_state.clone())
+            .await
+        {
+            Ok(new_state) => ReadResourceResponse {
+                new_state,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    format!("Failed to read {}", self.label()),
+                    format!(
+                        "Unable to read {} '{}' on node '{}': {}",
+                        self.label(),
+                        settings.name,
+                        settings.node,
+                        e
+                    ),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        // Everything but cleanup_disks forces a new resource, and that only
+        // matters on destroy
+        UpdateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: vec![],
+            new_identity: None,
+        }
+    }
+
+    async fn delete(&self, ctx: Context, request: DeleteResourceRequest) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match DiskStorageSettings::from_config(&request.prior_state) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+        let cleanup_disks = request
+            .prior_state
+            .get_bool(&AttributePath::new("cleanup_disks"))
+            .unwrap_or(false);
+
+        let task = provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .delete_disk_storage(
+                self.kind,
+                &settings.name,
+                settings.add_storage,
+                cleanup_disks,
+            )
+            .await;
+        let diagnostics = match task {
+            Err(e) if e.is_not_found() => vec![],
+            task => Self::wait(
+                &ctx,
+                provider_data,
+                &settings.node,
+                task,
+                Timeouts::new(&request.prior_state).delete(&ctx, DISK_TASK_TIMEOUT),
+                &format!("Failed to delete {}", self.label()),
+            )
+            .await
+            .err()
+            .unwrap_or_default(),
+        };
+        if settings.add_storage {
+            provider_data.invalidate_storages().await;
+        }
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for NodeDiskStorageResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_node_disk_test.rs"]
+mod resource_node_disk_test;
@@ -0,0 +1,213 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    const UPID: &str = "UPID:pve:00001234:00000000:65000000:zfscreate:tank:root@pam:";
+
+    fn zfs_config(raidlevel: &str, devices: &[&str]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("name".to_string(), Dynamic::String("tank".to_string()));
+        obj.insert(
+            "raidlevel".to_string(),
+            Dynamic::String(raidlevel.to_string()),
+        );
+        obj.insert(
+            "devices".to_string(),
+            Dynamic::List(
+                devices
+                    .iter()
+                    .map(|d| Dynamic::String(d.to_string()))
+                    .collect(),
+            ),
+        );
+        for name in [
+            "ashift",
+            "compression",
+            "add_storage",
+            "cleanup_disks",
+            "timeouts",
+        ] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        obj.insert("size".to_string(), Dynamic::Unknown);
+        obj.insert("health".to_string(), Dynamic::Unknown);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_zfs(url: &str) -> NodeDiskStorageResource {
+        NodeDiskStorageResource {
+            kind: DiskStorageKind::Zfs,
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    async fn validate(config: DynamicValue) -> Vec<String> {
+        NodeDiskStorageResource::zfs_pool()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_node_zfs_pool".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await
+            .diagnostics
+            .into_iter()
+            .map(|d| d.summary)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_validate_zfs_devices() {
+        assert!(validate(zfs_config("mirror", &["/dev/sdb", "/dev/sdc"]))
+            .await
+            .is_empty());
+        assert_eq!(
+            validate(zfs_config("raidz", &["/dev/sdb", "/dev/sdc"])).await,
+            ["Not enough devices"]
+        );
+        assert_eq!(
+            validate(zfs_config("raid5", &["sdb"])).await,
+            ["Invalid device", "Invalid raidlevel"]
+        );
+    }
+
+    async fn attributes(resource: NodeDiskStorageResource) -> Vec<String> {
+        resource
+            .schema(Context::new(), ResourceSchemaRequest)
+            .await
+            .schema
+            .block
+            .attributes
+            .into_iter()
+            .map(|attr| attr.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_schema_per_kind() {
+        let zfs = attributes(NodeDiskStorageResource::zfs_pool()).await;
+        assert!(zfs.contains(&"devices".to_string()));
+        assert!(!zfs.contains(&"device".to_string()));
+        let directory = attributes(NodeDiskStorageResource::directory()).await;
+        assert!(directory.contains(&"device".to_string()));
+        assert!(directory.contains(&"filesystem".to_string()));
+        let lvm = attributes(NodeDiskStorageResource::lvm()).await;
+        assert!(!lvm.contains(&"filesystem".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_create_zfs_pool_reads_back_size_and_health() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/nodes/pve/disks/zfs")
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "tank",
+                "devices": "/dev/sdb,/dev/sdc",
+                "raidlevel": "mirror"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let _task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+
+        let _list = server
+            .mock("GET", "/api2/json/nodes/pve/disks/zfs")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [{"name": "tank", "size": 996432412672, "free": 996432000000,
+                    "alloc": 412672, "frag": 0, "dedup": 1.0, "health": "ONLINE"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = zfs_config("mirror", &["/dev/sdb", "/dev/sdc"]);
+        let response = configured_zfs(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_node_zfs_pool".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        create.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            response
+                .new_state
+                .get_number(&AttributePath::new("size"))
+                .unwrap(),
+            996432412672.0
+        );
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("health"))
+                .unwrap(),
+            "ONLINE"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_drops_missing_pool() {
+        let mut server = Server::new_async().await;
+        let _list = server
+            .mock("GET", "/api2/json/nodes/pve/disks/zfs")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": [{"name": "rpool", "size": 1000, "health": "ONLINE"}]}"#)
+            .create_async()
+            .await;
+
+        let response = configured_zfs(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_node_zfs_pool".to_string(),
+                    current_state: zfs_config("mirror", &["/dev/sdb", "/dev/sdc"]),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        assert!(response.new_state.is_none());
+        assert!(response.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,398 @@
+//! Node DNS settings resource implementation
+
+use crate::api::nodes::{NodeDns, UpdateNodeDnsRequest, MAX_DNS_SERVERS};
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::ListLengthValidator;
+
+/// Desired DNS settings taken from the configuration
+#[derive(Debug)]
+struct DnsSettings {
+    node: String,
+    search: String,
+    /// None leaves the node's DNS servers untouched
+    servers: Option<Vec<String>>,
+}
+
+impl DnsSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let search = config
+            .get_string(&AttributePath::new("search"))
+            .map_err(|_| {
+                Diagnostic::error("Missing search", "The 'search' attribute is required")
+            })?;
+        let servers = config
+            .get_list(&AttributePath::new("servers"))
+            .ok()
+            .map(|servers| {
+                servers
+                    .into_iter()
+                    .filter_map(|server| match server {
+                        Dynamic::String(s) => Some(s),
+                        _ => None,
+                    })
+                    .collect()
+            });
+
+        Ok(Self {
+            node,
+            search,
+            servers,
+        })
+    }
+
+    fn update_request(&self) -> UpdateNodeDnsRequest {
+        match &self.servers {
+            Some(servers) => UpdateNodeDnsRequest::new(self.search.clone(), servers),
+            None => UpdateNodeDnsRequest {
+                search: self.search.clone(),
+                dns1: None,
+                dns2: None,
+                dns3: None,
+                delete: None,
+            },
+        }
+    }
+}
+
+fn set_dns_state(state: &mut DynamicValue, dns: &NodeDns) {
+    let _ = match &dns.search {
+        Some(search) => state.set_string(&AttributePath::new("search"), search.clone()),
+        None => state.set_null(&AttributePath::new("search")),
+    };
+    let _ = state.set_list(
+        &AttributePath::new("servers"),
+        dns.servers().into_iter().map(Dynamic::String).collect(),
+    );
+}
+
+/// Manages the DNS resolver settings of a node. The settings always exist,
+/// so destroying the resource only removes it from state.
+#[derive(Default)]
+pub struct NodeDnsResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeDnsResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the settings and reads them back so servers is known even
+    /// when it was left to the node
+    async fn apply(
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &DnsSettings,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let node = provider_data.client.nodes().node(&settings.node);
+        node.update_dns(&settings.update_request())
+            .await
+            .map_err(|e| {
+                Diagnostic::error(
+                    "Failed to update DNS settings",
+                    format!(
+                        "Unable to update DNS settings of node '{}': {}",
+                        settings.node, e
+                    ),
+                )
+            })?;
+
+        let dns = node.dns().await.map_err(|e| {
+            Diagnostic::error(
+                "Failed to read DNS settings",
+                format!(
+                    "Unable to read DNS settings of node '{}': {}",
+                    settings.node, e
+                ),
+            )
+        })?;
+        set_dns_state(&mut state, &dns);
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl Resource for NodeDnsResource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_dns"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages the DNS resolver settings of a node. Destroying this resource leaves the settings on the node as they are")
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to configure")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("search", AttributeType::String)
+                    .description("The search domain")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("servers", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Up to three DNS server IP addresses in order of preference. When omitted the servers configured on the node are kept")
+                    .optional()
+                    .computed()
+                    .validator(ListLengthValidator::max(MAX_DNS_SERVERS))
+                    .build(),
+            )
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        if let Ok(servers) = request.config.get_list(&AttributePath::new("servers")) {
+            if servers.len() > MAX_DNS_SERVERS {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Too many DNS servers",
+                        format!("A node supports at most {} DNS servers", MAX_DNS_SERVERS),
+                    )
+                    .with_attribute(AttributePath::new("servers")),
+                );
+            }
+            for server in servers {
+                if let Dynamic::String(address) = server {
+                    if address.parse::<std::net::IpAddr>().is_err() {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                "Invalid DNS server",
+                                format!("'{}' is not an IP address", address),
+                            )
+                            .with_attribute(AttributePath::new("servers")),
+                        );
+                    }
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let result = match DnsSettings::from_config(&request.config) {
+            Ok(settings) => {
+                Self::apply(provider_data, &settings, request.planned_state.clone()).await
+            }
+            Err(diag) => Err(diag),
+        };
+
+        match result {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            },
+            Err(diag) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![diag],
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let node = match request
+            .current_state
+            .get_string(&AttributePath::new("node"))
+        {
+            Ok(node) => node,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data.client.nodes().node(&node).dns().await {
+            Ok(dns) => {
+                let mut new_state = request.current_state.clone();
+                set_dns_state(&mut new_state, &dns);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read DNS settings",
+                    format!("Unable to read DNS settings of node '{}': {}", node, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let result = match DnsSettings::from_config(&request.config) {
+            Ok(settings) => Self::apply(provider_data, &settings, request.planned_state).await,
+            Err(diag) => Err(diag),
+        };
+
+        match result {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            Err(diag) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: vec![diag],
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // A node always has DNS settings, there is nothing to remove
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for NodeDnsResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_node_dns_test.rs"]
+mod resource_node_dns_test;
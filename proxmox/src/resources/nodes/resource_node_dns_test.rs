@@ -0,0 +1,106 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn dns_config(servers: Option<Vec<&str>>) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("search".to_string(), Dynamic::String("lan".to_string()));
+        obj.insert(
+            "servers".to_string(),
+            servers.map_or(Dynamic::Null, |servers| {
+                Dynamic::List(
+                    servers
+                        .into_iter()
+                        .map(|s| Dynamic::String(s.to_string()))
+                        .collect(),
+                )
+            }),
+        );
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> NodeDnsResource {
+        NodeDnsResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_bad_servers() {
+        let response = NodeDnsResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_node_dns".to_string(),
+                    config: dns_config(Some(vec!["1.1.1.1", "8.8.8.8", "9.9.9.9", "dns.lan"])),
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let summaries: Vec<&str> = response
+            .diagnostics
+            .iter()
+            .map(|d| d.summary.as_str())
+            .collect();
+        assert_eq!(
+            summaries,
+            vec!["Too many DNS servers", "Invalid DNS server"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_without_servers_keeps_node_servers() {
+        let mut server = Server::new_async().await;
+        let put = server
+            .mock("PUT", "/api2/json/nodes/pve/dns")
+            .match_body(Matcher::Json(
+                serde_json::from_str(r#"{"search": "lan"}"#).unwrap(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _get = server
+            .mock("GET", "/api2/json/nodes/pve/dns")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"search": "lan", "dns1": "10.0.0.1"}}"#)
+            .create_async()
+            .await;
+
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_node_dns".to_string(),
+                    planned_state: dns_config(None),
+                    config: dns_config(None),
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        put.assert_async().await;
+        assert_eq!(
+            response
+                .new_state
+                .get_list(&AttributePath::new("servers"))
+                .unwrap(),
+            vec![Dynamic::String("10.0.0.1".to_string())]
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Node timezone resource implementation
+
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+
+/// Manages the timezone of a node. A node always has a timezone, so
+/// destroying the resource only removes it from state.
+#[derive(Default)]
+pub struct NodeTimeResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl NodeTimeResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn target(config: &DynamicValue) -> Result<(String, String), Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let timezone = config
+            .get_string(&AttributePath::new("timezone"))
+            .map_err(|_| {
+                Diagnostic::error("Missing timezone", "The 'timezone' attribute is required")
+            })?;
+        Ok((node, timezone))
+    }
+
+    async fn apply(
+        provider_data: &crate::ProxmoxProviderData,
+        config: &DynamicValue,
+    ) -> Result<(), Diagnostic> {
+        let (node, timezone) = Self::target(config)?;
+        provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .set_timezone(&timezone)
+            .await
+            .map_err(|e| {
+                Diagnostic::error(
+                    "Failed to set timezone",
+                    format!(
+                        "Unable to set timezone of node '{}' to '{}': {}",
+                        node, timezone, e
+                    ),
+                )
+            })
+    }
+}
+
+#[async_trait]
+impl Resource for NodeTimeResource {
+    fn type_name(&self) -> &str {
+        "proxmox_node_time"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages the timezone of a node. Destroying this resource leaves the timezone on the node as it is")
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node to configure")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("timezone", AttributeType::String)
+                    .description("The timezone name from the tz database, e.g. Europe/Berlin or UTC")
+                    .required()
+                    .build(),
+            )
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = vec![];
+
+        if let Ok(timezone) = request.config.get_string(&AttributePath::new("timezone")) {
+            if timezone.is_empty() || timezone.chars().any(char::is_whitespace) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid timezone",
+                        format!(
+                            "'{}' is not a timezone name such as Europe/Berlin",
+                            timezone
+                        ),
+                    )
+                    .with_attribute(AttributePath::new("timezone")),
+                );
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let result = match &self.provider_data {
+            Some(provider_data) => Self::apply(provider_data, &request.config).await,
+            None => Err(Diagnostic::error(
+                "Provider not configured",
+                "Provider data was not properly configured",
+            )),
+        };
+
+        CreateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: result.err().into_iter().collect(),
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let node = match request
+            .current_state
+            .get_string(&AttributePath::new("node"))
+        {
+            Ok(node) => node,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data.client.nodes().node(&node).time().await {
+            Ok(time) => {
+                let mut new_state = request.current_state.clone();
+                let _ = new_state.set_string(&AttributePath::new("timezone"), time.timezone);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read time settings",
+                    format!("Unable to read time settings of node '{}': {}", node, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let result = match &self.provider_data {
+            Some(provider_data) => Self::apply(provider_data, &request.config).await,
+            None => Err(Diagnostic::error(
+                "Provider not configured",
+                "Provider data was not properly configured",
+            )),
+        };
+
+        match result {
+            Ok(()) => UpdateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            Err(diag) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: vec![diag],
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // A node always has a timezone, there is nothing to remove
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for NodeTimeResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_node_time_test.rs"]
+mod resource_node_time_test;
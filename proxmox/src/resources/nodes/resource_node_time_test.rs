@@ -0,0 +1,91 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::Dynamic;
+
+    fn time_config(timezone: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert(
+            "timezone".to_string(),
+            Dynamic::String(timezone.to_string()),
+        );
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> NodeTimeResource {
+        NodeTimeResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_sets_timezone_and_read_detects_drift() {
+        let mut server = Server::new_async().await;
+        let put = server
+            .mock("PUT", "/api2/json/nodes/pve/time")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"timezone": "Europe/Berlin"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _get = server
+            .mock("GET", "/api2/json/nodes/pve/time")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"timezone": "UTC", "time": 1700000000}}"#)
+            .create_async()
+            .await;
+
+        let resource = configured_resource(&server.url());
+        let response = resource
+            .update(
+                Context::new(),
+                UpdateResourceRequest {
+                    type_name: "proxmox_node_time".to_string(),
+                    prior_state: time_config("UTC"),
+                    planned_state: time_config("Europe/Berlin"),
+                    config: time_config("Europe/Berlin"),
+                    planned_private: vec![],
+                    provider_meta: None,
+                    planned_identity: None,
+                },
+            )
+            .await;
+        assert!(response.diagnostics.is_empty());
+        put.assert_async().await;
+
+        let response = resource
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_node_time".to_string(),
+                    current_state: time_config("Europe/Berlin"),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: tfplug::types::ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+        assert_eq!(
+            response
+                .new_state
+                .unwrap()
+                .get_string(&AttributePath::new("timezone"))
+                .unwrap(),
+            "UTC"
+        );
+    }
+}
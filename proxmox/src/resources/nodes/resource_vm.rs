@@ -1,20 +1,556 @@
+use crate::api::common::parse_bool;
+use crate::api::nodes::{QemuSettings, MAX_NETWORK_INTERFACES};
+use crate::api::propstring::PropertyString;
+use crate::resources::config_string::{values_eq, ConfigString, NIC_MODELS};
+use crate::resources::diagnostics::{api_error_diagnostics, task_cancelled_warning};
+use crate::ssh;
 use async_trait::async_trait;
+use base64::Engine;
 use std::collections::HashMap;
 use tfplug::context::Context;
 use tfplug::defaults::StaticDefault;
+use tfplug::model::{TfBlock, TfSchema};
+use tfplug::plan_modifier::UseStateForUnknown;
 use tfplug::resource::{
     ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
     CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse,
-    ImportResourceStateRequest, ImportResourceStateResponse, ImportedResource, ReadResourceRequest,
-    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
-    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, ResourceWithImportState,
-    UpdateResourceRequest, UpdateResourceResponse, ValidateResourceConfigRequest,
-    ValidateResourceConfigResponse,
+    ImportResourceStateRequest, ImportResourceStateResponse, ImportedResource, ModifyPlanRequest,
+    ModifyPlanResponse, ReadResourceRequest, ReadResourceResponse, Resource,
+    ResourceMetadataRequest, ResourceMetadataResponse, ResourceSchemaRequest,
+    ResourceSchemaResponse, ResourceWithConfigValidators, ResourceWithConfigure,
+    ResourceWithIdentity, ResourceWithImportState, ResourceWithModifyPlan, ResourceWithMoveState,
+    ResourceWithUpgradeState, StateMover, StateUpgrader, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
 };
 use tfplug::schema::{
-    AttributeBuilder, AttributeType, Block, NestedBlock, NestingMode, SchemaBuilder,
+    AttributeBuilder, AttributeType, Block, ConfigValidator, NestedBlock, NestingMode,
+    SchemaBuilder,
 };
-use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{
+    AttributePath, Diagnostic, Dynamic, DynamicValue, IdentityAttribute, PrivateStateData,
+    ResourceIdentityData, ResourceIdentitySchema,
+};
+use tfplug::validator::{
+    validate_integers, AlsoRequiresValidator, ConflictingValidator, IntegerRangeValidator,
+    StringOneOfValidator,
+};
+
+/// How long the create task may take, including clones, restores and disk
+/// imports.
+const CREATE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1800);
+
+/// How long a reboot that applies pending changes may take.
+const REBOOT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// How often the guest agent is pinged while waiting for it to start
+const AGENT_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Private state key of the config digest Proxmox returned on the last
+/// read. Updates send it so Proxmox rejects them when the VM was changed
+/// outside Terraform since.
+const PRIVATE_CONFIG_DIGEST: &str = "config_digest";
+
+/// Private state key of the CloneLineage of a cloned VM
+const PRIVATE_CLONE_LINEAGE: &str = "clone_lineage";
+
+/// Template a VM was cloned from when it was created
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct CloneLineage {
+    template: String,
+    full_clone: bool,
+}
+
+/// Hotplug features Proxmox enables when the VM sets none
+const DEFAULT_HOTPLUG: &[&str] = &["network", "disk", "usb"];
+
+/// Attributes a running VM picks up when the paired hotplug feature is on
+const HOTPLUG_ATTRIBUTES: &[(&str, &str)] = &[
+    ("network", "network"),
+    ("disk", "disk"),
+    ("attach_existing", "disk"),
+    ("vcpus", "cpu"),
+    ("ipconfig0", "cloudinit"),
+    ("ipconfig1", "cloudinit"),
+    ("ipconfig2", "cloudinit"),
+    ("ipconfig3", "cloudinit"),
+    ("ciuser", "cloudinit"),
+    ("cipassword", "cloudinit"),
+    ("sshkeys", "cloudinit"),
+    ("cloudinit", "cloudinit"),
+];
+
+/// Attributes a running VM only picks up by restarting
+const RESTART_ATTRIBUTES: &[&str] = &[
+    "bios",
+    "machine",
+    "cores",
+    "sockets",
+    "kvm",
+    "nested_virtualization",
+    "scsihw",
+    "efidisk",
+    "serial",
+    "vga",
+    "smbios",
+    "numa_node",
+];
+
+/// Block lists of the schema
+const BLOCK_LISTS: &[&str] = &[
+    "network",
+    "disk",
+    "attach_existing",
+    "cdrom",
+    "cloudinit_drive",
+    "serial",
+    "vga",
+    "cpu",
+    "smbios",
+    "startup",
+    "agent",
+    "cloudinit",
+    "restore_from",
+    "memory",
+    "numa_node",
+    "efidisk",
+];
+
+/// Attributes a failed create keeps from the plan. The others fall back to
+/// their defaults.
+const PLANNED_ATTRIBUTES: &[&str] = &[
+    "target_node",
+    "vmid",
+    "name",
+    "tags",
+    "cores",
+    "start",
+    "kvm",
+    "nested_virtualization",
+    "agent_wait_timeout",
+    "reboot_on_update",
+];
+
+/// Number attributes that map to integer fields in the Proxmox API
+const INTEGER_ATTRIBUTES: &[&str] = &[
+    "vcpus",
+    "additional_wait",
+    "agent_wait_timeout",
+    "clone_wait",
+];
+
+const API_ATTRIBUTES: &[&str] = &[
+    "vmid",
+    "name",
+    "tags",
+    "bios",
+    "machine",
+    "cores",
+    "sockets",
+    "vcpus",
+    "memory",
+    "boot",
+    "bootdisk",
+    "onboot",
+    "scsihw",
+    "agent",
+    "ipconfig0",
+    "ipconfig1",
+    "ipconfig2",
+    "ipconfig3",
+    "ciuser",
+    "cipassword",
+    "ciupgrade",
+    "sshkeys",
+    "description",
+    "tablet",
+    "hookscript",
+    "pool",
+    "protection",
+    "cpu",
+];
+
+/// Serial ports a VM can have, serial0 to serial3
+const MAX_SERIAL_PORTS: u32 = 4;
+
+/// Display types accepted for the vga block
+const VGA_TYPES: &[&str] = &[
+    "std",
+    "cirrus",
+    "vmware",
+    "qxl",
+    "qxl2",
+    "qxl3",
+    "qxl4",
+    "virtio",
+    "virtio-gl",
+    "none",
+    "serial0",
+    "serial1",
+    "serial2",
+    "serial3",
+];
+
+/// Longest smbios1 option Proxmox accepts
+const MAX_SMBIOS_LENGTH: usize = 512;
+
+/// Hugepage sizes accepted by the memory block, in MB
+const HUGEPAGE_SIZES: &[&str] = &["any", "2", "1024"];
+
+/// Guest NUMA nodes a VM can have, numa0 to numa7
+const MAX_NUMA_NODES: u32 = 8;
+
+/// Host memory policies accepted for guest NUMA nodes
+const NUMA_POLICIES: &[&str] = &["preferred", "bind", "interleave"];
+
+/// SMBIOS type 1 system information the guest can read, e.g. for asset tags
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Smbios {
+    /// SMBIOS UUID. Kept from the VM when not set
+    #[tfplug(optional, computed)]
+    uuid: Option<String>,
+    /// System serial number
+    serial: Option<String>,
+    /// System manufacturer
+    manufacturer: Option<String>,
+    /// Product name
+    product: Option<String>,
+    /// Product version
+    version: Option<String>,
+    /// SKU number
+    sku: Option<String>,
+    /// Product family
+    family: Option<String>,
+}
+
+impl Smbios {
+    /// Text fields by their smbios1 key. Proxmox stores them base64 encoded.
+    fn text_fields(&self) -> [(&'static str, &Option<String>); 6] {
+        [
+            ("serial", &self.serial),
+            ("manufacturer", &self.manufacturer),
+            ("product", &self.product),
+            ("version", &self.version),
+            ("sku", &self.sku),
+            ("family", &self.family),
+        ]
+    }
+
+    fn text_fields_mut(&mut self) -> [(&'static str, &mut Option<String>); 6] {
+        [
+            ("serial", &mut self.serial),
+            ("manufacturer", &mut self.manufacturer),
+            ("product", &mut self.product),
+            ("version", &mut self.version),
+            ("sku", &mut self.sku),
+            ("family", &mut self.family),
+        ]
+    }
+}
+
+/// Startup and shutdown ordering when the node boots or shuts down
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Startup {
+    /// Start order. VMs with a lower order start first and shut down last
+    order: Option<f64>,
+    /// Seconds to wait after starting this VM before starting the next one
+    up_delay: Option<f64>,
+    /// Seconds to wait for this VM to shut down before stopping it
+    down_delay: Option<f64>,
+}
+
+impl Startup {
+    /// Settings by attribute name, with the startup option key they map to
+    fn settings(&self) -> [(&'static str, &'static str, Option<f64>); 3] {
+        [
+            ("order", "order", self.order),
+            ("up_delay", "up", self.up_delay),
+            ("down_delay", "down", self.down_delay),
+        ]
+    }
+}
+
+/// QEMU guest agent settings
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Agent {
+    /// Enable the QEMU guest agent (default: true)
+    enabled: Option<bool>,
+    /// Run fstrim in the guest after moving a disk or migrating the VM, so
+    /// thin provisioned clones give back unused space (default: false)
+    trim_cloned_disks: Option<bool>,
+    /// Agent device type, virtio or isa (default: virtio)
+    r#type: Option<String>,
+}
+
+impl Agent {
+    /// Agent device types Proxmox accepts
+    const TYPES: [&'static str; 2] = ["virtio", "isa"];
+
+    fn enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    fn trim_cloned_disks(&self) -> bool {
+        self.trim_cloned_disks.unwrap_or(false)
+    }
+
+    fn agent_type(&self) -> &str {
+        self.r#type.as_deref().unwrap_or("virtio")
+    }
+}
+
+/// A vzdump or Proxmox Backup Server archive to create the VM from. The
+/// drives come from the backup, the other settings override its config.
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct RestoreFrom {
+    /// Volume ID of the backup, e.g. from the proxmox_backups data source
+    volid: String,
+    /// Storage for the restored disks. Defaults to the storages they were
+    /// backed up from
+    storage: Option<String>,
+    /// Give the VM new MAC addresses instead of the backed up ones
+    unique: Option<bool>,
+}
+
+/// Cloud-init settings beyond the flat ci* and ipconfig0 to ipconfig3
+/// attributes
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct CloudInit {
+    /// DNS servers of the guest, in order of preference. Proxmox falls back
+    /// to the servers of the host when neither this nor searchdomain is set
+    nameserver: Option<Vec<String>>,
+    /// DNS search domain of the guest
+    searchdomain: Option<String>,
+    /// Network settings of a network interface, sent as ipconfig0 to
+    /// ipconfig31
+    #[tfplug(block)]
+    ipconfig: Vec<IpConfig>,
+}
+
+impl CloudInit {
+    /// The nameserver option, the servers separated by spaces
+    fn nameserver_config(&self) -> Option<String> {
+        self.nameserver.as_ref().map(|servers| servers.join(" "))
+    }
+
+    /// Takes the DNS settings the block sets from the VM config, so changes
+    /// made outside Terraform show up. The ipconfig entries are left alone.
+    fn read_dns(&mut self, vm_config: &crate::api::nodes::QemuConfig) {
+        if self.nameserver.is_some() {
+            self.nameserver = Some(
+                vm_config
+                    .nameserver
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+        if self.searchdomain.is_some() {
+            self.searchdomain = Some(vm_config.searchdomain.clone().unwrap_or_default());
+        }
+    }
+}
+
+/// Cloud-init network settings of the network block with the same id
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct IpConfig {
+    /// Network interface ID (0-31). A network block with this id must exist
+    id: f64,
+    /// IPv4 address in CIDR notation, or "dhcp"
+    ipv4: Option<String>,
+    /// IPv4 gateway
+    gateway: Option<String>,
+    /// IPv6 address in CIDR notation, "dhcp" or "auto"
+    ipv6: Option<String>,
+    /// IPv6 gateway
+    gateway6: Option<String>,
+}
+
+impl IpConfig {
+    /// Settings by attribute name, with the ipconfig option key they map to
+    fn settings(&self) -> [(&'static str, &Option<String>); 4] {
+        [
+            ("ip", &self.ipv4),
+            ("gw", &self.gateway),
+            ("ip6", &self.ipv6),
+            ("gw6", &self.gateway6),
+        ]
+    }
+
+    /// Builds the ipconfig option, e.g. "ip=10.0.0.5/24,gw=10.0.0.1"
+    fn to_api_string(&self) -> String {
+        let mut ipconfig = PropertyString::new("");
+        for (key, value) in self.settings() {
+            if let Some(value) = value {
+                ipconfig.set(key, value.as_str());
+            }
+        }
+        ipconfig.to_string()
+    }
+
+    /// Parses an ipconfig option as returned by the API
+    fn from_api_string(id: u32, ipconfig: &str) -> Self {
+        let mut parsed = IpConfig {
+            id: id as f64,
+            ..Default::default()
+        };
+        for (key, value) in PropertyString::parse(ipconfig, "").params() {
+            let field = match key {
+                "ip" => &mut parsed.ipv4,
+                "gw" => &mut parsed.gateway,
+                "ip6" => &mut parsed.ipv6,
+                "gw6" => &mut parsed.gateway6,
+                _ => continue,
+            };
+            *field = Some(value.to_string());
+        }
+        parsed
+    }
+}
+
+/// CPU configuration
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Cpu {
+    /// CPU type, e.g. host or x86-64-v2-AES
+    r#type: Option<String>,
+    /// CPU flags to enable (+flag) or disable (-flag), e.g. ["+aes", "-pcid"]
+    flags: Option<Vec<String>>,
+    /// CPU weight against other VMs (1-262144)
+    units: Option<f64>,
+    /// Limit of host CPU time in cores, 0 for no limit (0-128)
+    limit: Option<f64>,
+    /// Host CPUs the VM may run on, e.g. 0-3,8
+    affinity: Option<String>,
+    /// Enable NUMA. Required for numa_node blocks
+    numa: Option<bool>,
+}
+
+impl Cpu {
+    fn flags(&self) -> &[String] {
+        self.flags.as_deref().unwrap_or_default()
+    }
+
+    /// Builds the cpu option, e.g. "host,flags=+aes;-pcid"
+    fn to_api_string(&self) -> Option<String> {
+        let mut parts: Vec<String> = self.r#type.iter().cloned().collect();
+        if !self.flags().is_empty() {
+            parts.push(format!("flags={}", self.flags().join(";")));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(","))
+        }
+    }
+}
+
+/// Memory and ballooning configuration
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct Memory {
+    /// Amount of RAM for the VM in MB
+    #[tfplug(required)]
+    dedicated: Option<f64>,
+    /// Minimum RAM in MB the balloon driver can shrink the VM to. 0 disables ballooning
+    floating: Option<f64>,
+    /// Weight for automatic ballooning against other VMs (0-50000)
+    shares: Option<f64>,
+    /// Back the memory with hugepages: 2, 1024 (MB) or any
+    hugepages: Option<String>,
+    /// Keep hugepages allocated after the VM stops, for faster restarts
+    keep_hugepages: Option<bool>,
+}
+
+/// Guest NUMA node configuration
+#[derive(Debug, Default, PartialEq, TfSchema)]
+struct NumaNode {
+    /// Guest NUMA node ID (0-7)
+    #[tfplug(required)]
+    id: Option<f64>,
+    /// vCPUs of this node as ids and ranges separated by ';', e.g. 0-3
+    #[tfplug(required)]
+    cpus: Option<String>,
+    /// Memory of this node in MB. All nodes must add up to the VM's memory
+    #[tfplug(required)]
+    memory: Option<f64>,
+    /// Host NUMA nodes to take the memory from, e.g. 0-1
+    hostnodes: Option<String>,
+    /// Host memory policy: preferred, bind or interleave. Required with hostnodes
+    policy: Option<String>,
+}
+
+impl NumaNode {
+    /// Builds the numa option as (id, value), e.g.
+    /// (0, "cpus=0-3,hostnodes=0,memory=2048,policy=bind")
+    fn to_api_string(&self) -> Result<(u32, String), String> {
+        let id = self.id.ok_or("NUMA node ID is required")? as u32;
+        let cpus = self.cpus.as_ref().ok_or("NUMA node cpus are required")?;
+
+        let mut parts = vec![format!("cpus={}", cpus)];
+        if let Some(hostnodes) = &self.hostnodes {
+            parts.push(format!("hostnodes={}", hostnodes));
+        }
+        if let Some(memory) = self.memory {
+            parts.push(format!("memory={}", memory as u64));
+        }
+        if let Some(policy) = &self.policy {
+            parts.push(format!("policy={}", policy));
+        }
+        Ok((id, parts.join(",")))
+    }
+
+    /// Parses a numa option as returned by the API
+    fn from_api_string(id: u32, numa: &str) -> Self {
+        let mut parsed = NumaNode {
+            id: Some(id as f64),
+            ..Default::default()
+        };
+        for (key, value) in PropertyString::parse(numa, "").params() {
+            match key {
+                "memory" => parsed.memory = value.parse().ok(),
+                "cpus" => parsed.cpus = Some(value.to_string()),
+                "hostnodes" => parsed.hostnodes = Some(value.to_string()),
+                "policy" => parsed.policy = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        parsed
+    }
+}
+
+/// Settings from the cpu block, as sent to the API
+#[derive(Debug, Default, PartialEq)]
+struct CpuSettings {
+    cpu: Option<String>,
+    cpuunits: Option<u32>,
+    cpulimit: Option<f64>,
+    affinity: Option<String>,
+    numa: Option<bool>,
+}
+
+/// The VM an import ID refers to
+#[derive(Debug, Clone, PartialEq)]
+enum ImportTarget {
+    /// "node/vmid"
+    NodeVmid(String, u32),
+    /// A bare "vmid", looked up in the cluster
+    Vmid(u32),
+    /// "name=<vm name>", looked up in the cluster
+    Name(String),
+}
+
+/// Drive blocks whose storage falls back to the provider's default_storage
+const DEFAULT_STORAGE_BLOCKS: [&str; 3] = ["disk", "efidisk", "cloudinit_drive"];
+
+/// Attribute naming the storage of each drive block, and the content type
+/// the storage must allow for it. Cdroms name it in the ISO's volume ID.
+const DRIVE_STORAGE_CONTENT: [(&str, &str, &str); 4] = [
+    ("disk", "storage", "images"),
+    ("efidisk", "storage", "images"),
+    ("cloudinit_drive", "storage", "images"),
+    ("cdrom", "iso", "iso"),
+];
 
 #[derive(Default)]
 pub struct QemuVmResource {
@@ -26,8 +562,107 @@ impl QemuVmResource {
         Self::default()
     }
 
-    fn normalize_tags(tags: &str) -> String {
-        tags.replace(';', ",")
+    /// Provider data with the provider_meta of the VM's module applied
+    fn provider_data_for(
+        &self,
+        provider_meta: Option<&DynamicValue>,
+    ) -> Option<crate::ProxmoxProviderData> {
+        self.provider_data
+            .as_ref()
+            .map(|data| data.with_provider_meta(provider_meta))
+    }
+
+    /// Builds the tags option from the tags set. Proxmox keeps tags sorted,
+    /// so they are sent that way too.
+    fn tags_config(config: &DynamicValue) -> Option<String> {
+        let mut tags: Vec<String> = config
+            .get_list(&AttributePath::new("tags"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tag| match tag {
+                Dynamic::String(tag) => Some(tag),
+                _ => None,
+            })
+            .collect();
+        if tags.is_empty() {
+            return None;
+        }
+        tags.sort();
+        tags.dedup();
+        Some(tags.join(";"))
+    }
+
+    /// Splits the tags option. Older Proxmox versions separate tags with
+    /// commas or spaces instead of semicolons.
+    fn parse_tags(tags: &str) -> Vec<Dynamic> {
+        let mut tags: Vec<&str> = tags
+            .split([';', ',', ' '])
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags.into_iter()
+            .map(|tag| Dynamic::String(tag.to_string()))
+            .collect()
+    }
+
+    /// Builds the sshkeys option, one public key per line. The request
+    /// percent-encodes it, Proxmox rejects keys sent as they are.
+    fn sshkeys_config(config: &DynamicValue) -> Option<String> {
+        let keys: Vec<String> = config
+            .get_list(&AttributePath::new("sshkeys"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|key| match key {
+                Dynamic::String(key) => Some(key.trim().to_string()),
+                _ => None,
+            })
+            .filter(|key| !key.is_empty())
+            .collect();
+        if keys.is_empty() {
+            return None;
+        }
+        Some(keys.join("\n"))
+    }
+
+    /// Decodes the percent-encoded sshkeys option into its keys
+    fn parse_sshkeys(sshkeys: &str) -> Vec<String> {
+        urlencoding::decode(sshkeys)
+            .map(|keys| keys.into_owned())
+            .unwrap_or_else(|_| sshkeys.to_string())
+            .lines()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn validate_tags(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        for tag in config
+            .get_list(&AttributePath::new("tags"))
+            .unwrap_or_default()
+        {
+            if let Dynamic::String(tag) = tag {
+                let mut chars = tag.chars();
+                let valid = chars
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && chars
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.'));
+                if !valid {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            "Invalid tag",
+                            format!(
+                                "Tag '{}' may only contain letters, digits, _, -, + and ., and can't start with -, + or .",
+                                tag
+                            ),
+                        )
+                        .with_attribute(AttributePath::new("tags")),
+                    );
+                }
+            }
+        }
     }
 
     fn network_blocks_to_string(networks: &[Dynamic]) -> Result<String, String> {
@@ -48,117 +683,101 @@ impl QemuVmResource {
             })
             .unwrap_or("virtio");
 
-        let mut parts = vec![model.to_string()];
+        let mut net = PropertyString::new("model");
+        net.set("model", model);
 
         if let Some(Dynamic::String(bridge)) = net_map.get("bridge") {
-            parts.push(format!("bridge={}", bridge));
+            net.set("bridge", bridge.as_str());
         }
 
         if let Some(Dynamic::Bool(firewall)) = net_map.get("firewall") {
-            parts.push(format!("firewall={}", if *firewall { "1" } else { "0" }));
+            net.set("firewall", if *firewall { "1" } else { "0" });
         }
 
         if let Some(Dynamic::Number(tag)) = net_map.get("tag") {
-            parts.push(format!("tag={}", *tag as i64));
+            net.set("tag", (*tag as i64).to_string());
         }
 
         if let Some(Dynamic::String(macaddr)) = net_map.get("macaddr") {
-            parts.push(format!("macaddr={}", macaddr));
+            if !macaddr.is_empty() {
+                net.set("macaddr", macaddr.as_str());
+            }
         }
 
         if let Some(Dynamic::Number(rate)) = net_map.get("rate") {
-            parts.push(format!("rate={}", rate));
+            net.set("rate", rate.to_string());
         }
 
         if let Some(Dynamic::Number(queues)) = net_map.get("queues") {
-            parts.push(format!("queues={}", *queues as i64));
+            net.set("queues", (*queues as i64).to_string());
         }
 
         if let Some(Dynamic::Bool(link_down)) = net_map.get("link_down") {
             if *link_down {
-                parts.push("link_down=1".to_string());
+                net.set("link_down", "1");
             }
         }
 
         if let Some(Dynamic::Number(mtu)) = net_map.get("mtu") {
-            parts.push(format!("mtu={}", *mtu as i64));
+            net.set("mtu", (*mtu as i64).to_string());
         }
 
-        Ok(parts.join(","))
+        Ok(net.to_string())
     }
 
     fn parse_network_string(net_string: &str, id: u32) -> Dynamic {
         let mut map = std::collections::HashMap::new();
         map.insert("id".to_string(), Dynamic::Number(id as f64));
 
-        // Handle model type with MAC address (e.g., "virtio=BA:88:CB:76:75:D6,bridge=vmbr0")
-        let parts: Vec<&str> = net_string.split(',').collect();
-        let mut model = "virtio";
-        let mut macaddr = None;
-
-        // First check if the first part is model=macaddr
-        if let Some(first_part) = parts.first() {
-            if let Some((key, value)) = first_part.split_once('=') {
-                if key == "virtio" || key == "e1000" || key == "rtl8139" || key == "vmxnet3" {
-                    model = key;
-                    if value.contains(':') {
-                        macaddr = Some(value);
-                    }
-                }
-            } else if first_part == &"virtio"
-                || first_part == &"e1000"
-                || first_part == &"rtl8139"
-                || first_part == &"vmxnet3"
-            {
-                model = first_part;
-            }
-        }
+        // The model may carry the MAC address, e.g. "virtio=BA:88:CB:76:75:D6,bridge=vmbr0"
+        let (net, macaddr) = ConfigString::parse_network(net_string);
+        let model = net
+            .positional
+            .as_deref()
+            .filter(|model| NIC_MODELS.contains(model))
+            .unwrap_or("virtio");
 
-        for part in parts {
-            if let Some((key, value)) = part.split_once('=') {
-                match key {
-                    "bridge" => {
-                        map.insert("bridge".to_string(), Dynamic::String(value.to_string()));
-                    }
-                    "firewall" => {
-                        let firewall = value == "1" || value == "true";
-                        map.insert("firewall".to_string(), Dynamic::Bool(firewall));
-                    }
-                    "tag" => {
-                        if let Ok(tag) = value.parse::<f64>() {
-                            map.insert("tag".to_string(), Dynamic::Number(tag));
-                        }
-                    }
-                    "macaddr" => {
-                        map.insert("macaddr".to_string(), Dynamic::String(value.to_string()));
-                    }
-                    "rate" => {
-                        if let Ok(rate) = value.parse::<f64>() {
-                            map.insert("rate".to_string(), Dynamic::Number(rate));
-                        }
+        for (key, value) in &net.params {
+            let value = value.as_str();
+            match key.as_str() {
+                "bridge" => {
+                    map.insert("bridge".to_string(), Dynamic::String(value.to_string()));
+                }
+                "firewall" => {
+                    let firewall = value == "1" || value == "true";
+                    map.insert("firewall".to_string(), Dynamic::Bool(firewall));
+                }
+                "tag" => {
+                    if let Ok(tag) = value.parse::<f64>() {
+                        map.insert("tag".to_string(), Dynamic::Number(tag));
                     }
-                    "queues" => {
-                        if let Ok(queues) = value.parse::<f64>() {
-                            map.insert("queues".to_string(), Dynamic::Number(queues));
-                        }
+                }
+                "rate" => {
+                    if let Ok(rate) = value.parse::<f64>() {
+                        map.insert("rate".to_string(), Dynamic::Number(rate));
                     }
-                    "link_down" => {
-                        let link_down = value == "1" || value == "true";
-                        map.insert("link_down".to_string(), Dynamic::Bool(link_down));
+                }
+                "queues" => {
+                    if let Ok(queues) = value.parse::<f64>() {
+                        map.insert("queues".to_string(), Dynamic::Number(queues));
                     }
-                    "mtu" => {
-                        if let Ok(mtu) = value.parse::<f64>() {
-                            map.insert("mtu".to_string(), Dynamic::Number(mtu));
-                        }
+                }
+                "link_down" => {
+                    let link_down = value == "1" || value == "true";
+                    map.insert("link_down".to_string(), Dynamic::Bool(link_down));
+                }
+                "mtu" => {
+                    if let Ok(mtu) = value.parse::<f64>() {
+                        map.insert("mtu".to_string(), Dynamic::Number(mtu));
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
 
         map.insert("model".to_string(), Dynamic::String(model.to_string()));
         if let Some(mac) = macaddr {
-            map.insert("macaddr".to_string(), Dynamic::String(mac.to_string()));
+            map.insert("macaddr".to_string(), Dynamic::String(mac));
         }
         Dynamic::Map(map)
     }
@@ -181,10 +800,10 @@ impl QemuVmResource {
         };
         map.insert("type".to_string(), Dynamic::String(disk_type.to_string()));
 
-        let parts: Vec<&str> = disk_string.split(',').collect();
+        let disk = PropertyString::parse(disk_string, "file");
 
-        if let Some(storage_part) = parts.first() {
-            if let Some((storage, path_or_size)) = storage_part.split_once(':') {
+        if let Some(volume) = disk.default_value() {
+            if let Some((storage, path_or_size)) = volume.split_once(':') {
                 map.insert("storage".to_string(), Dynamic::String(storage.to_string()));
 
                 if path_or_size.contains("iso/") {
@@ -195,129 +814,165 @@ impl QemuVmResource {
                     map.insert("size".to_string(), Dynamic::String(size_str));
                 }
             } else {
-                map.insert(
-                    "storage".to_string(),
-                    Dynamic::String(storage_part.to_string()),
-                );
+                map.insert("storage".to_string(), Dynamic::String(volume.to_string()));
             }
         }
 
-        let size_found = map.contains_key("size");
-        if !size_found {
-            for part in &parts {
-                if let Some((key, value)) = part.split_once('=') {
-                    if key == "size" {
-                        map.insert("size".to_string(), Dynamic::String(value.to_string()));
-                        break;
-                    }
+        for (key, value) in disk.params() {
+            match key {
+                "size" => {
+                    map.entry("size".to_string())
+                        .or_insert_with(|| Dynamic::String(value.to_string()));
                 }
-            }
-        }
-
-        for part in parts.iter().skip(1) {
-            if let Some((key, value)) = part.split_once('=') {
-                match key {
-                    "media" => {
-                        map.insert("media".to_string(), Dynamic::String(value.to_string()));
-                    }
-                    "format" => {
-                        map.insert("format".to_string(), Dynamic::String(value.to_string()));
-                    }
-                    "iothread" => {
-                        let iothread = value == "1" || value == "true";
-                        map.insert("iothread".to_string(), Dynamic::Bool(iothread));
-                    }
-                    "ssd" => {
-                        let ssd = value == "1" || value == "true";
-                        map.insert("emulatessd".to_string(), Dynamic::Bool(ssd));
-                    }
-                    "discard" => {
-                        let discard = value == "on" || value == "1";
-                        map.insert("discard".to_string(), Dynamic::Bool(discard));
-                    }
-                    "cache" => {
-                        map.insert("cache".to_string(), Dynamic::String(value.to_string()));
-                    }
-                    "backup" => {
-                        let backup = value == "1" || value == "true";
-                        map.insert("backup".to_string(), Dynamic::Bool(backup));
-                    }
-                    "replicate" => {
-                        let replicate = value == "1" || value == "true";
-                        map.insert("replicate".to_string(), Dynamic::Bool(replicate));
-                    }
-                    _ => {}
+                "media" => {
+                    map.insert("media".to_string(), Dynamic::String(value.to_string()));
                 }
+                "format" => {
+                    map.insert("format".to_string(), Dynamic::String(value.to_string()));
+                }
+                "iothread" => {
+                    let iothread = value == "1" || value == "true";
+                    map.insert("iothread".to_string(), Dynamic::Bool(iothread));
+                }
+                "ssd" => {
+                    let ssd = value == "1" || value == "true";
+                    map.insert("emulatessd".to_string(), Dynamic::Bool(ssd));
+                }
+                "discard" => {
+                    let discard = value == "on" || value == "1";
+                    map.insert("discard".to_string(), Dynamic::Bool(discard));
+                }
+                "cache" => {
+                    map.insert("cache".to_string(), Dynamic::String(value.to_string()));
+                }
+                "backup" => {
+                    let backup = value == "1" || value == "true";
+                    map.insert("backup".to_string(), Dynamic::Bool(backup));
+                }
+                "replicate" => {
+                    let replicate = value == "1" || value == "true";
+                    map.insert("replicate".to_string(), Dynamic::Bool(replicate));
+                }
+                _ => {}
             }
         }
 
         Dynamic::Map(map)
     }
 
-    fn normalize_network_config(net_config: &str, current_config: Option<&str>) -> String {
-        let should_remove_mac = current_config.map(|c| !c.contains(':')).unwrap_or(true);
-
-        let parts: Vec<&str> = net_config.split(',').collect();
-        let mut network_type = None;
-        let mut params = Vec::new();
-
-        for part in parts {
-            if let Some((key, value)) = part.split_once('=') {
-                if key == "virtio" || key == "e1000" || key == "rtl8139" || key == "vmxnet3" {
-                    if value.contains(':') && should_remove_mac {
-                        network_type = Some(key.to_string());
-                    } else {
-                        network_type = Some(part.to_string());
-                    }
-                } else {
-                    params.push((key, value));
+    /// Parses an EFI disk string like "local-lvm:vm-100-disk-1,efitype=4m"
+    /// into the settings of the efidisk block
+    fn parse_efidisk_string(efidisk_string: &str) -> HashMap<String, Dynamic> {
+        let mut efidisk = HashMap::new();
+        let parsed = PropertyString::parse(efidisk_string, "file");
+        if let Some((storage, _)) = parsed.default_value().and_then(|file| file.split_once(':')) {
+            efidisk.insert("storage".to_string(), Dynamic::String(storage.to_string()));
+        }
+
+        for (key, value) in parsed.params() {
+            match key {
+                "format" | "efitype" => {
+                    efidisk.insert(key.to_string(), Dynamic::String(value.to_string()));
                 }
-            } else {
-                // Handle cases where there's no '=' (like standalone virtio)
-                if part == "virtio" || part == "e1000" || part == "rtl8139" || part == "vmxnet3" {
-                    network_type = Some(part.to_string());
-                } else {
-                    params.push((part, ""));
+                "pre-enrolled-keys" => {
+                    let enrolled = value == "1" || value == "true";
+                    efidisk.insert("pre_enrolled_keys".to_string(), Dynamic::Bool(enrolled));
                 }
+                _ => {}
             }
         }
+        efidisk
+    }
 
-        // Sort parameters alphabetically by key
-        params.sort_by(|a, b| a.0.cmp(b.0));
-
-        // Reconstruct the config string
-        let mut result = Vec::new();
-        if let Some(nt) = network_type {
-            result.push(nt);
+    /// Fills the efidisk settings Proxmox leaves out of the disk string
+    fn fill_efidisk_defaults(efidisk: &mut HashMap<String, Dynamic>) {
+        for (key, default) in [
+            ("storage", Dynamic::String(String::new())),
+            ("format", Dynamic::String("raw".to_string())),
+            ("efitype", Dynamic::String("4m".to_string())),
+            ("pre_enrolled_keys", Dynamic::Bool(false)),
+        ] {
+            efidisk.entry(key.to_string()).or_insert(default);
         }
+    }
 
-        for (key, value) in params {
-            if value.is_empty() {
-                result.push(key.to_string());
-            } else {
-                result.push(format!("{}={}", key, value));
+    fn normalize_network_config(net_config: &str, current_config: Option<&str>) -> String {
+        let (mut actual, mac) = ConfigString::parse_network(net_config);
+
+        // Keep the configured spelling when it means the same thing, so that
+        // reordered parameters don't show up as changes. Proxmox assigns a
+        // MAC when none is configured, which doesn't count as a difference.
+        if let Some(current) = current_config {
+            let (configured, configured_mac) = ConfigString::parse_network(current);
+            let mac_matches = match (&configured_mac, &mac) {
+                (None, _) => true,
+                (Some(configured), Some(actual)) => configured.eq_ignore_ascii_case(actual),
+                (Some(_), None) => false,
+            };
+            if mac_matches && configured.semantic_eq(&actual) {
+                return current.to_string();
             }
         }
 
-        result.join(",")
+        let keep_mac = current_config.is_some_and(|c| c.contains(':'));
+        if let (Some(model), Some(mac), true) = (&actual.positional, &mac, keep_mac) {
+            actual.positional = Some(format!("{}={}", model, mac));
+        }
+        actual.to_string()
     }
 
     fn normalize_disk_config(disk_config: &str, current_config: Option<&str>) -> String {
         // Proxmox returns disk configs like "local-lvm:vm-9003-disk-1,size=10G"
         // But Terraform expects "local-lvm:10,format=raw"
+        let Some(current) = current_config else {
+            return disk_config.to_string();
+        };
 
-        if let Some(current) = current_config {
-            // If current config has a simple format like "storage:size,format=raw"
-            // we should return that instead of the detailed Proxmox response
-            if current.contains("format=") && !current.contains("vm-") {
-                return current.to_string();
-            }
+        let actual = ConfigString::parse(disk_config);
+        let configured = ConfigString::parse(current);
+        if configured.semantic_eq(&actual) || Self::allocation_matches(&configured, &actual) {
+            return current.to_string();
         }
 
-        // Otherwise return the disk config as-is
         disk_config.to_string()
     }
 
+    /// Whether a disk configured with the allocation syntax `storage:size`
+    /// is the volume Proxmox created for it. Parameters Proxmox doesn't echo
+    /// back, like `format` on LVM, are not compared.
+    fn allocation_matches(configured: &ConfigString, actual: &ConfigString) -> bool {
+        let Some((storage, size)) = configured
+            .positional
+            .as_deref()
+            .and_then(|volume| volume.split_once(':'))
+            .filter(|(_, size)| !size.is_empty() && size.chars().all(|c| c.is_ascii_digit()))
+        else {
+            return false;
+        };
+        let actual_storage = actual
+            .positional
+            .as_deref()
+            .and_then(|volume| volume.split_once(':'))
+            .map(|(storage, _)| storage);
+        if actual_storage != Some(storage) {
+            return false;
+        }
+
+        // Imported disks take the size of their source image
+        let imported = configured.get("import-from").is_some();
+        let size_matches = imported
+            || actual
+                .get("size")
+                .is_some_and(|actual| values_eq("size", &format!("{}G", size), actual));
+
+        size_matches
+            && configured.params.iter().all(|(key, value)| {
+                actual
+                    .get(key)
+                    .map_or(true, |actual| values_eq(key, value, actual))
+            })
+    }
+
     fn validate_iothread(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
         // Check SCSI disks with iothread
         for i in 0..=30 {
@@ -366,176 +1021,2786 @@ impl QemuVmResource {
         }
     }
 
-    // Block conversion methods for nested block attributes
-    fn disk_block_to_api_string(disk: &Dynamic) -> Result<(String, String), String> {
-        let disk_map = match disk {
-            Dynamic::Map(map) => map,
-            _ => return Err("Disk must be a map".to_string()),
-        };
-
-        let slot = disk_map
-            .get("slot")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .ok_or("Slot is required")?;
+    fn validate_nested_virtualization(
+        &self,
+        config: &DynamicValue,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        if !config
+            .get_bool(&AttributePath::new("nested_virtualization"))
+            .unwrap_or(false)
+        {
+            return;
+        }
 
-        let storage = disk_map
-            .get("storage")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.as_str()),
-                _ => None,
-            })
-            .ok_or("Storage is required")?;
+        if let Ok(false) = config.get_bool(&AttributePath::new("kvm")) {
+            diagnostics.push(Diagnostic::error(
+                "Nested virtualization requires KVM",
+                "nested_virtualization = true cannot be combined with kvm = false",
+            ));
+        }
 
-        let size = disk_map
-            .get("size")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.as_str()),
-                _ => None,
-            })
-            .ok_or("Size is required")?;
+        let cpu: Cpu = Self::single_block_as(config, "cpu").unwrap_or_default();
+        if let Some(flag) = cpu.flags().iter().find(|f| *f == "-vmx" || *f == "-svm") {
+            diagnostics.push(Diagnostic::error(
+                "Conflicting CPU flags",
+                format!(
+                    "CPU flag '{}' disables the extension required for nested virtualization",
+                    flag
+                ),
+            ));
+        }
+    }
 
-        // Convert size format (e.g., "20G" to "20")
-        let size_num = size.trim_end_matches('G').trim_end_matches('g');
-        let mut parts = vec![format!("{}:{}", storage, size_num)];
+    /// Returns the CPU string with the given virtualization flag enabled.
+    /// The 'host' CPU type passes all host flags through, so it is returned unchanged.
+    fn nested_virtualization_cpu(cpu: Option<&str>, flag: &str) -> String {
+        let cpu = match cpu {
+            Some(cpu) if !cpu.is_empty() => cpu,
+            _ => return "host".to_string(),
+        };
 
-        // Add optional attributes
-        if let Some(Dynamic::String(format)) = disk_map.get("format") {
-            if !format.is_empty() {
-                parts.push(format!("format={}", format));
-            }
+        let mut cpu_string = PropertyString::parse(cpu, "cputype");
+        if cpu_string.default_value() == Some("host") {
+            return cpu.to_string();
         }
 
-        if let Some(Dynamic::Bool(true)) = disk_map.get("iothread") {
-            parts.push("iothread=1".to_string());
-        }
+        let wanted = format!("+{}", flag);
+        let flags = match cpu_string.get("flags") {
+            Some(flags) if flags.split(';').any(|f| f == wanted) => return cpu.to_string(),
+            Some(flags) if !flags.is_empty() => format!("{};{}", flags, wanted),
+            _ => wanted,
+        };
+        cpu_string.set("flags", flags);
+        cpu_string.to_string()
+    }
 
-        if let Some(Dynamic::Bool(true)) = disk_map.get("emulatessd") {
-            parts.push("ssd=1".to_string());
-        }
+    /// Config options that only change once the VM reboots
+    async fn pending_keys(
+        client: &crate::api::Client,
+        node: &str,
+        vmid: u32,
+    ) -> Result<Vec<String>, crate::api::ApiError> {
+        let pending = client.nodes().node(node).qemu().pending(vmid).await?;
+        Ok(pending
+            .into_iter()
+            .filter(|change| change.is_pending())
+            .map(|change| change.key)
+            .collect())
+    }
 
-        if let Some(Dynamic::Bool(true)) = disk_map.get("discard") {
-            parts.push("discard=on".to_string());
+    /// Records whether changes wait for a reboot. Terraform rejects a known
+    /// planned value that changes during apply, so only an unknown value is
+    /// filled in and the next refresh picks up the rest.
+    fn fill_pending_changes(state: &mut DynamicValue, pending: bool) {
+        if state
+            .get_bool(&AttributePath::new("pending_changes"))
+            .is_err()
+        {
+            let _ = state.set_bool(&AttributePath::new("pending_changes"), pending);
         }
+    }
 
-        if let Some(Dynamic::Bool(false)) = disk_map.get("backup") {
-            parts.push("backup=0".to_string());
-        }
+    /// Seconds to wait for the guest agent after create, if create starts
+    /// a VM with the agent enabled
+    fn agent_wait_timeout(config: &DynamicValue) -> Option<u64> {
+        let timeout = config
+            .get_u64(&AttributePath::new("agent_wait_timeout"))
+            .ok()
+            .filter(|timeout| *timeout > 0)?;
+        let agent = Self::single_block_as::<Agent>(config, "agent").is_some_and(|a| a.enabled());
+        let start = config
+            .get_bool(&AttributePath::new("start"))
+            .unwrap_or(false);
+        (agent && start).then_some(timeout)
+    }
 
-        if let Some(Dynamic::Bool(false)) = disk_map.get("replicate") {
-            parts.push("replicate=0".to_string());
+    fn validate_agent_wait(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let timeout = config
+            .get_number(&AttributePath::new("agent_wait_timeout"))
+            .unwrap_or(0.0);
+        if timeout < 0.0 {
+            diagnostics.push(
+                Diagnostic::error(
+                    "Invalid agent_wait_timeout",
+                    "agent_wait_timeout must be 0 or more seconds",
+                )
+                .with_attribute(AttributePath::new("agent_wait_timeout")),
+            );
+        } else if timeout > 0.0 && Self::agent_wait_timeout(config).is_none() {
+            diagnostics.push(
+                Diagnostic::warning(
+                    "agent_wait_timeout has no effect",
+                    "The provider only waits for the guest agent when agent is enabled and start is true",
+                )
+                .with_attribute(AttributePath::new("agent_wait_timeout")),
+            );
         }
+    }
 
-        if let Some(Dynamic::Bool(true)) = disk_map.get("readonly") {
-            parts.push("ro=1".to_string());
+    /// Pings the guest agent of a freshly started VM until it answers so
+    /// provisioners don't race the boot
+    async fn wait_for_agent(
+        ctx: &Context,
+        client: &crate::api::Client,
+        node: &str,
+        vmid: u32,
+        timeout: u64,
+    ) -> Result<(), Diagnostic> {
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(timeout);
+        let qemu_api = client.nodes().node(node).qemu();
+        loop {
+            let last_error = match qemu_api.agent_ping(vmid).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            if tokio::time::Instant::now() + AGENT_POLL_INTERVAL > deadline {
+                return Err(Diagnostic::error(
+                    "Guest agent not ready",
+                    format!(
+                        "The guest agent of VM {} did not answer within {} seconds. Make sure the QEMU guest agent is installed in the guest, or raise agent_wait_timeout: {}",
+                        vmid, timeout, last_error
+                    ),
+                ));
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(AGENT_POLL_INTERVAL) => {}
+                _ = ctx.cancelled() => {
+                    return Err(Diagnostic::error(
+                        "Guest agent not ready",
+                        format!(
+                            "Terraform was interrupted before the guest agent of VM {} answered",
+                            vmid
+                        ),
+                    ));
+                }
+            }
         }
+    }
 
-        // IO limits
-        if let Some(Dynamic::Number(n)) = disk_map.get("iops_r_burst") {
-            parts.push(format!("iops_rd_max={}", *n as i64));
-        }
-        if let Some(Dynamic::Number(n)) = disk_map.get("iops_r_concurrent") {
-            parts.push(format!("iops_rd={}", *n as i64));
+    /// Builds the state after create. Values the plan left unknown, like
+    /// generated MAC addresses, are taken from the configuration Proxmox
+    /// recorded for the new VM; everything else stays as planned.
+    async fn created_state(
+        client: &crate::api::Client,
+        node: &str,
+        vmid: u32,
+        planned_state: &DynamicValue,
+        private: &mut PrivateStateData,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> DynamicValue {
+        let mut actual = planned_state.clone();
+        match client.nodes().node(node).qemu().get_config(vmid).await {
+            Ok(vm_config) => {
+                Self::record_config_digest(private, &vm_config);
+                let has_nested_blocks = ["network", "disk", "efidisk"].iter().any(|block| {
+                    planned_state
+                        .get_list(&AttributePath::new(block))
+                        .is_ok_and(|list| !list.is_empty())
+                });
+                if has_nested_blocks {
+                    Self::populate_state_with_nested_blocks(&mut actual, &vm_config, planned_state);
+                } else {
+                    Self::populate_state_from_config(&mut actual, &vm_config, planned_state);
+                }
+                Self::refresh_attach_existing(&mut actual, &vm_config, planned_state);
+            }
+            Err(e) => diagnostics.push(Diagnostic::warning(
+                "Could not read back VM",
+                format!(
+                    "VM {} was created, but reading its configuration failed, so computed values stay empty until the next refresh: {}",
+                    vmid, e
+                ),
+            )),
+        }
+
+        let mut new_state = DynamicValue::new(Self::merge_unknown_values(
+            &planned_state.value,
+            &actual.value,
+        ));
+        Self::fill_pending_changes(&mut new_state, false);
+        new_state
+    }
+
+    /// Keeps the digest of the config just read for the next update
+    fn record_config_digest(
+        private: &mut PrivateStateData,
+        vm_config: &crate::api::nodes::QemuConfig,
+    ) {
+        match &vm_config.digest {
+            Some(digest) => {
+                let _ = private.set(PRIVATE_CONFIG_DIGEST, digest);
+            }
+            None => private.remove_key(PRIVATE_CONFIG_DIGEST),
+        }
+    }
+
+    /// Replaces the unknown parts of `planned` with the matching parts of
+    /// `actual`, or null when nothing matches. Nested blocks are matched by
+    /// their `id` or `slot`, falling back to their position.
+    fn merge_unknown_values(planned: &Dynamic, actual: &Dynamic) -> Dynamic {
+        match (planned, actual) {
+            (Dynamic::Unknown, Dynamic::Unknown) => Dynamic::Null,
+            (Dynamic::Unknown, actual) => actual.clone(),
+            (Dynamic::Map(planned), actual) => Dynamic::Map(
+                planned
+                    .iter()
+                    .map(|(key, value)| {
+                        let actual = match actual {
+                            Dynamic::Map(actual) => actual.get(key).unwrap_or(&Dynamic::Null),
+                            _ => &Dynamic::Null,
+                        };
+                        (key.clone(), Self::merge_unknown_values(value, actual))
+                    })
+                    .collect(),
+            ),
+            (Dynamic::List(planned), actual) => {
+                let actual: &[Dynamic] = match actual {
+                    Dynamic::List(actual) => actual,
+                    _ => &[],
+                };
+                Dynamic::List(
+                    planned
+                        .iter()
+                        .enumerate()
+                        .map(|(index, value)| {
+                            let counterpart = Self::block_key(value)
+                                .and_then(|key| {
+                                    actual
+                                        .iter()
+                                        .find(|candidate| Self::block_key(candidate) == Some(key))
+                                })
+                                .or_else(|| actual.get(index))
+                                .unwrap_or(&Dynamic::Null);
+                            Self::merge_unknown_values(value, counterpart)
+                        })
+                        .collect(),
+                )
+            }
+            (planned, _) => planned.clone(),
+        }
+    }
+
+    /// The attribute identifying a nested block, if it has one
+    fn block_key(block: &Dynamic) -> Option<&Dynamic> {
+        match block {
+            Dynamic::Map(map) => map.get("id").or_else(|| map.get("slot")),
+            _ => None,
+        }
+    }
+
+    /// Plans the MAC address of network blocks that don't set one. An
+    /// interface keeps the MAC Proxmox generated for it, found by id in the
+    /// prior state; a new interface gets an unknown MAC so create or update
+    /// can report the generated one.
+    fn plan_generated_macs(
+        config: &DynamicValue,
+        prior_state: &DynamicValue,
+        planned_state: &mut DynamicValue,
+    ) {
+        let path = AttributePath::new("network");
+        let (Ok(configured), Ok(mut networks)) =
+            (config.get_list(&path), planned_state.get_list(&path))
+        else {
+            return;
+        };
+        let prior_macs: HashMap<u32, String> = prior_state
+            .get_list(&path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|network| match network {
+                Dynamic::Map(map) => match (map.get("id"), map.get("macaddr")) {
+                    (Some(Dynamic::Number(id)), Some(Dynamic::String(mac))) if !mac.is_empty() => {
+                        Some((*id as u32, mac.clone()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        for (network, configured) in networks.iter_mut().zip(&configured) {
+            let unset = match configured {
+                Dynamic::Map(map) => matches!(map.get("macaddr"), None | Some(Dynamic::Null)),
+                _ => false,
+            };
+            if let (Dynamic::Map(network), true) = (network, unset) {
+                let prior_mac = match network.get("id") {
+                    Some(Dynamic::Number(id)) => prior_macs.get(&(*id as u32)),
+                    _ => None,
+                };
+                let mac = prior_mac.map_or(Dynamic::Unknown, |mac| Dynamic::String(mac.clone()));
+                network.insert("macaddr".to_string(), mac);
+            }
+        }
+        let _ = planned_state.set_list(&path, networks);
+    }
+
+    /// Paths of the MAC addresses the configuration leaves out
+    fn omitted_macs(config: &DynamicValue) -> Vec<AttributePath> {
+        let count = config
+            .get_list(&AttributePath::new("network"))
+            .map_or(0, |networks| networks.len());
+        (0..count)
+            .map(|index| {
+                AttributePath::new("network")
+                    .index(index as i64)
+                    .attribute("macaddr")
+            })
+            .filter(|path| matches!(config.get(path), Ok(Dynamic::Null) | Err(_)))
+            .collect()
+    }
+
+    /// Paths of target_node and the drive storages the configuration leaves
+    /// out, which the provider's default_node and default_storage fill in
+    fn omitted_defaults(config: &DynamicValue) -> Vec<AttributePath> {
+        let unset = |path: &AttributePath| matches!(config.get(path), Ok(Dynamic::Null) | Err(_));
+
+        let mut paths = vec![];
+        let node = AttributePath::new("target_node");
+        if unset(&node) {
+            paths.push(node);
+        }
+        for block in DEFAULT_STORAGE_BLOCKS {
+            let count = config
+                .get_list(&AttributePath::new(block))
+                .map_or(0, |drives| drives.len());
+            for index in 0..count {
+                let path = AttributePath::new(block)
+                    .index(index as i64)
+                    .attribute("storage");
+                if unset(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Plans the provider defaults for target_node and drive storages the
+    /// configuration leaves out. Values that already have a prior value keep
+    /// it, so changing a default doesn't move existing VMs or disks.
+    fn plan_provider_defaults(
+        provider_data: Option<&crate::ProxmoxProviderData>,
+        config: &DynamicValue,
+        planned_state: &mut DynamicValue,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        for path in Self::omitted_defaults(config) {
+            if !matches!(
+                planned_state.get(&path),
+                Ok(Dynamic::Null | Dynamic::Unknown) | Err(_)
+            ) {
+                continue;
+            }
+            // The provider isn't configured yet when its own configuration
+            // is unknown, so the default is only known at apply
+            let Some(provider_data) = provider_data else {
+                let _ = planned_state.mark_unknown(&path);
+                continue;
+            };
+            let (default, attribute, setting) = if path == AttributePath::new("target_node") {
+                (&provider_data.default_node, "target_node", "default_node")
+            } else {
+                (&provider_data.default_storage, "storage", "default_storage")
+            };
+            match default {
+                Some(value) => {
+                    let _ = planned_state.set_string(&path, value.clone());
+                }
+                None => diagnostics.push(Diagnostic::attribute_error(
+                    path,
+                    format!("Missing {}", attribute),
+                    format!("Set {} or the provider's {} setting", attribute, setting),
+                )),
+            }
+        }
+    }
+
+    /// String values of `attribute` in the planned blocks that differ from
+    /// the prior state's block at the same index, with their paths
+    fn changed_block_values(
+        prior_state: &DynamicValue,
+        planned_state: &DynamicValue,
+        block: &str,
+        attribute: &str,
+    ) -> Vec<(AttributePath, String)> {
+        let count = planned_state
+            .get_list(&AttributePath::new(block))
+            .map_or(0, |blocks| blocks.len());
+        (0..count)
+            .map(|index| {
+                AttributePath::new(block)
+                    .index(index as i64)
+                    .attribute(attribute)
+            })
+            .filter_map(|path| {
+                let value = planned_state.get_string(&path).ok()?;
+                (prior_state.get_string(&path).ok() != Some(value.clone())).then_some((path, value))
+            })
+            .collect()
+    }
+
+    /// Storages the planned drives use that the prior state didn't already
+    /// use at the same place, with the attribute naming each and the content
+    /// type it needs
+    fn planned_storage_uses(
+        prior_state: &DynamicValue,
+        planned_state: &DynamicValue,
+    ) -> Vec<(AttributePath, String, &'static str)> {
+        let mut uses = vec![];
+        for (block, attribute, content) in DRIVE_STORAGE_CONTENT {
+            for (path, value) in
+                Self::changed_block_values(prior_state, planned_state, block, attribute)
+            {
+                let storage = if attribute == "iso" {
+                    // "none" and physical drives have no storage
+                    match crate::api::nodes::parse_volid(&value) {
+                        Some((storage, _)) => storage.to_string(),
+                        None => continue,
+                    }
+                } else {
+                    value
+                };
+                uses.push((path, storage, content));
+            }
+        }
+        uses
+    }
+
+    /// Checks the storages of new and moved drives against the datacenter's
+    /// storage listing, so a storage that can't hold the drive fails the
+    /// plan at its attribute instead of the apply. A storage missing from
+    /// the listing only gets a warning, as the same apply may add it.
+    async fn check_planned_storages(
+        provider_data: &crate::ProxmoxProviderData,
+        prior_state: &DynamicValue,
+        planned_state: &DynamicValue,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let uses = Self::planned_storage_uses(prior_state, planned_state);
+        if uses.is_empty() {
+            return;
+        }
+        // Without a listing the apply reports any problem itself
+        let Ok(storages) = provider_data.storages().await else {
+            return;
+        };
+        for (path, name, content) in uses {
+            let Some(storage) = storages.iter().find(|storage| storage.storage == name) else {
+                diagnostics.push(Diagnostic::attribute_warning(
+                    path,
+                    "Unknown storage",
+                    format!(
+                        "Storage '{}' is not defined in the datacenter. The apply fails unless it is added first.",
+                        name
+                    ),
+                ));
+                continue;
+            };
+            let Some(allowed) = &storage.content else {
+                continue;
+            };
+            if !allowed.split(',').any(|allowed| allowed.trim() == content) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path,
+                    "Storage does not support content type",
+                    format!(
+                        "Storage '{}' does not allow '{}' content, it allows: {}. Pick another storage or add '{}' to its content types.",
+                        name, content, allowed, content
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// With the provider's check_bridges on, checks that the bridges of new
+    /// and changed network interfaces exist on the target node. Proxmox
+    /// only reports a missing bridge once the VM starts, with an opaque error.
+    async fn check_planned_bridges(
+        provider_data: &crate::ProxmoxProviderData,
+        prior_state: &DynamicValue,
+        planned_state: &DynamicValue,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        if !provider_data.check_bridges {
+            return;
+        }
+        let Ok(node) = planned_state.get_string(&AttributePath::new("target_node")) else {
+            return;
+        };
+        let bridges = Self::changed_block_values(prior_state, planned_state, "network", "bridge");
+        if bridges.is_empty() {
+            return;
+        }
+        // Without a listing the apply reports any problem itself
+        let Ok(interfaces) = provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .network(Some("any_bridge"))
+            .await
+        else {
+            return;
+        };
+        for (path, bridge) in bridges {
+            if interfaces.iter().any(|interface| interface.iface == bridge) {
+                continue;
+            }
+            let available: Vec<&str> = interfaces
+                .iter()
+                .map(|interface| interface.iface.as_str())
+                .collect();
+            diagnostics.push(Diagnostic::attribute_error(
+                path,
+                "Unknown bridge",
+                format!(
+                    "Bridge '{}' does not exist on node '{}'. Bridges on the node: {}",
+                    bridge,
+                    node,
+                    available.join(", ")
+                ),
+            ));
+        }
+    }
+
+    /// Copies the planned provider defaults and kept MAC addresses into a
+    /// configuration that leaves them out, so create and update can read
+    /// everything from the config. Sending the kept MAC stops Proxmox from
+    /// generating a new one when the interface changes.
+    fn fill_from_plan(config: &mut DynamicValue, planned_state: &DynamicValue) {
+        let omitted: Vec<AttributePath> = Self::omitted_defaults(config)
+            .into_iter()
+            .chain(Self::omitted_macs(config))
+            .collect();
+        for path in omitted {
+            if let Ok(Dynamic::String(value)) = planned_state.get(&path) {
+                let _ = config.set_string(&path, value.clone());
+            }
+        }
+    }
+
+    /// Builds the state of an imported VM from everything its configuration
+    /// sets, including the nested blocks, so that a plan right after import
+    /// is clean
+    fn imported_state(
+        node: &str,
+        vmid: u32,
+        vm_config: &crate::api::nodes::QemuConfig,
+    ) -> DynamicValue {
+        let mut state = DynamicValue::new(Dynamic::Map(HashMap::new()));
+        let _ = state.set_string(&AttributePath::new("target_node"), node.to_string());
+        let _ = state.set_number(&AttributePath::new("vmid"), vmid as f64);
+        if let Some(name) = &vm_config.name {
+            let _ = state.set_string(&AttributePath::new("name"), name.clone());
+        }
+
+        for (name, value, _) in Self::config_scalars(vm_config) {
+            let path = AttributePath::new(name);
+            let _ = match value {
+                Some(Dynamic::String(value)) => state.set_string(&path, value),
+                Some(Dynamic::Number(value)) => state.set_number(&path, value),
+                Some(Dynamic::Bool(value)) => state.set_bool(&path, value),
+                _ => continue,
+            };
+        }
+        if let Some(tags) = &vm_config.tags {
+            let _ = state.set_list(&AttributePath::new("tags"), Self::parse_tags(tags));
+        }
+
+        let _ = state.set_list(
+            &AttributePath::new("memory"),
+            vec![Self::parse_memory_settings(vm_config)],
+        );
+        if vm_config.cpu.is_some()
+            || vm_config.cpuunits.is_some()
+            || vm_config.cpulimit.is_some()
+            || vm_config.affinity.is_some()
+            || vm_config.numa.is_some()
+        {
+            let _ = state.set_list(
+                &AttributePath::new("cpu"),
+                vec![Self::parse_cpu_settings(vm_config, &Cpu::default(), false)],
+            );
+        }
+        for (name, value, parse) in [
+            (
+                "smbios",
+                &vm_config.smbios1,
+                Self::parse_smbios_string as fn(&str) -> Dynamic,
+            ),
+            ("startup", &vm_config.startup, Self::parse_startup_string),
+            ("agent", &vm_config.agent, |agent| {
+                Self::parse_agent_string(agent, None)
+            }),
+            ("vga", &vm_config.vga, Self::parse_vga_string),
+        ] {
+            if let Some(value) = value {
+                let _ = state.set_list(&AttributePath::new(name), vec![parse(value)]);
+            }
+        }
+
+        let networks: Vec<Dynamic> = (0..MAX_NETWORK_INTERFACES)
+            .filter_map(|id| {
+                vm_config
+                    .net(id)
+                    .map(|net| Self::parse_network_string(net, id))
+            })
+            .collect();
+        let serials: Vec<Dynamic> = [
+            &vm_config.serial0,
+            &vm_config.serial1,
+            &vm_config.serial2,
+            &vm_config.serial3,
+        ]
+        .into_iter()
+        .zip(0..)
+        .filter_map(|(serial, id)| {
+            serial
+                .as_deref()
+                .map(|serial| Self::parse_serial_string(serial, id))
+        })
+        .collect();
+        let numa_nodes: Vec<Dynamic> = [
+            &vm_config.numa0,
+            &vm_config.numa1,
+            &vm_config.numa2,
+            &vm_config.numa3,
+            &vm_config.numa4,
+            &vm_config.numa5,
+            &vm_config.numa6,
+            &vm_config.numa7,
+        ]
+        .into_iter()
+        .zip(0..)
+        .filter_map(|(node, id)| {
+            node.as_deref()
+                .map(|node| NumaNode::from_api_string(id, node).to_dynamic())
+        })
+        .collect();
+
+        // Drives are split into the block that creates them: CD-ROMs and
+        // cloud-init drives both use media=cdrom
+        let mut disks = Vec::new();
+        let mut cdroms = Vec::new();
+        let mut cloudinit_drives = Vec::new();
+        for slot in crate::api::nodes::DRIVE_SLOTS.iter().copied() {
+            let Some(drive) = vm_config.drive(slot) else {
+                continue;
+            };
+            let parsed = ConfigString::parse(drive);
+            let volume = parsed.positional.clone().unwrap_or_default();
+            let mut block = HashMap::new();
+            block.insert("slot".to_string(), Dynamic::String(slot.to_string()));
+            if parsed.get("media") != Some("cdrom") {
+                disks.push(Self::parse_disk_string(drive, slot));
+            } else if volume.contains("cloudinit") {
+                let storage = volume.split_once(':').map_or("", |(storage, _)| storage);
+                block.insert("storage".to_string(), Dynamic::String(storage.to_string()));
+                cloudinit_drives.push(Dynamic::Map(block));
+            } else {
+                block.insert("iso".to_string(), Dynamic::String(volume));
+                cdroms.push(Dynamic::Map(block));
+            }
+        }
+        let efidisks: Vec<Dynamic> = vm_config
+            .efidisk0
+            .as_deref()
+            .map(|efidisk| {
+                let mut efidisk = Self::parse_efidisk_string(efidisk);
+                Self::fill_efidisk_defaults(&mut efidisk);
+                Dynamic::Map(efidisk)
+            })
+            .into_iter()
+            .collect();
+
+        for (name, blocks) in [
+            ("network", networks),
+            ("disk", disks),
+            ("cdrom", cdroms),
+            ("cloudinit_drive", cloudinit_drives),
+            ("efidisk", efidisks),
+            ("serial", serials),
+            ("numa_node", numa_nodes),
+        ] {
+            if !blocks.is_empty() {
+                let _ = state.set_list(&AttributePath::new(name), blocks);
+            }
+        }
+
+        // Cloud-init settings are only sent on create and update, so import
+        // is the one place they are read from the VM
+        for (name, value) in [
+            ("ciuser", &vm_config.ciuser),
+            ("ipconfig0", &vm_config.ipconfig0),
+            ("ipconfig1", &vm_config.ipconfig1),
+            ("ipconfig2", &vm_config.ipconfig2),
+            ("ipconfig3", &vm_config.ipconfig3),
+        ] {
+            if let Some(value) = value {
+                let _ = state.set_string(&AttributePath::new(name), value.clone());
+            }
+        }
+        if let Some(sshkeys) = &vm_config.sshkeys {
+            let keys = Self::parse_sshkeys(sshkeys)
+                .into_iter()
+                .map(Dynamic::String)
+                .collect();
+            let _ = state.set_list(&AttributePath::new("sshkeys"), keys);
+        }
+        // The flat attributes only cover ipconfig0 to ipconfig3
+        let ipconfig: Vec<IpConfig> = (4..MAX_NETWORK_INTERFACES)
+            .filter_map(|id| {
+                vm_config
+                    .ipconfig(id)
+                    .map(|ipconfig| IpConfig::from_api_string(id, ipconfig))
+            })
+            .collect();
+        let cloudinit = CloudInit {
+            nameserver: vm_config
+                .nameserver
+                .as_deref()
+                .map(|servers| servers.split_whitespace().map(str::to_string).collect()),
+            searchdomain: vm_config.searchdomain.clone(),
+            ipconfig,
+        };
+        if cloudinit != CloudInit::default() {
+            let _ = state.set_list(
+                &AttributePath::new("cloudinit"),
+                vec![cloudinit.to_dynamic()],
+            );
+        }
+        // A refresh reads the flat strings back as well
+        Self::populate_device_strings(&mut state, vm_config, &DynamicValue::null());
+
+        state
+    }
+
+    /// Parses an import ID: "node/vmid", a bare "vmid" or "name=<vm name>"
+    fn parse_import_id(id: &str) -> Result<ImportTarget, Diagnostic> {
+        let invalid = || {
+            Diagnostic::error(
+                "Invalid import ID",
+                format!(
+                    "Import ID '{}' must be in the format 'node/vmid', 'vmid' or 'name=<vm name>'",
+                    id
+                ),
+            )
+        };
+        let vmid = |vmid: &str| {
+            vmid.parse::<u32>()
+                .map_err(|_| Diagnostic::error("Invalid VMID", "VMID must be a valid number"))
+        };
+
+        if let Some(name) = id.strip_prefix("name=") {
+            return match name.trim() {
+                "" => Err(invalid()),
+                name => Ok(ImportTarget::Name(name.to_string())),
+            };
+        }
+        match id.split('/').collect::<Vec<_>>().as_slice() {
+            [node, id] if !node.is_empty() => {
+                Ok(ImportTarget::NodeVmid(node.to_string(), vmid(id)?))
+            }
+            [id] if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) => {
+                Ok(ImportTarget::Vmid(vmid(id)?))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Finds the node and VMID of the VM an import ID names. Only "node/vmid"
+    /// is used as given, the other formats are looked up in the cluster.
+    async fn resolve_import_target(
+        provider_data: &crate::ProxmoxProviderData,
+        target: ImportTarget,
+    ) -> Result<(String, u32), Diagnostic> {
+        let (vmid, name) = match target {
+            ImportTarget::NodeVmid(node, vmid) => return Ok((node, vmid)),
+            ImportTarget::Vmid(vmid) => (Some(vmid), None),
+            ImportTarget::Name(name) => (None, Some(name)),
+        };
+
+        let resources = provider_data.cluster_vms().await.map_err(|e| {
+            Diagnostic::error(
+                "Failed to look up VM",
+                format!("Listing the VMs of the cluster failed: {}", e),
+            )
+        })?;
+        let mut candidates: Vec<(String, u32)> = resources
+            .iter()
+            .filter(|resource| resource.resource_type == "qemu")
+            .filter(|resource| vmid.is_none() || resource.vmid == vmid)
+            .filter(|resource| name.is_none() || resource.name == name)
+            .filter_map(|resource| Some((resource.node.clone()?, resource.vmid?)))
+            .collect();
+        candidates.sort();
+
+        let wanted = match (&vmid, &name) {
+            (Some(vmid), _) => format!("VMID {}", vmid),
+            (_, Some(name)) => format!("name '{}'", name),
+            _ => String::new(),
+        };
+        match candidates.len() {
+            0 => Err(Diagnostic::error(
+                "VM not found",
+                format!("No VM with {} exists in the cluster", wanted),
+            )),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(Diagnostic::error(
+                "Ambiguous VM name",
+                format!(
+                    "More than one VM has the {}. Import one of them by ID instead: {}",
+                    wanted,
+                    candidates
+                        .iter()
+                        .map(|(node, vmid)| format!("{}/{}", node, vmid))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// Resource pool the VM should belong to
+    fn pool(config: &DynamicValue) -> Option<String> {
+        config
+            .get_string(&AttributePath::new("pool"))
+            .ok()
+            .filter(|pool| !pool.is_empty())
+    }
+
+    /// Moves the VM between resource pools. Proxmox refuses to add a guest
+    /// that already belongs to another pool, so it leaves the old one first.
+    async fn reconcile_pool(
+        client: &crate::api::Client,
+        vmid: u32,
+        prior: Option<String>,
+        planned: Option<String>,
+    ) -> Result<(), Diagnostic> {
+        if prior == planned {
+            return Ok(());
+        }
+        let pools = client.pools();
+        if let Some(prior) = prior {
+            pools
+                .update_guests(&prior, &[vmid], true)
+                .await
+                .map_err(|e| {
+                    Diagnostic::error(
+                        "Failed to leave pool",
+                        format!("Removing VM {} from pool '{}': {}", vmid, prior, e),
+                    )
+                    .with_attribute(AttributePath::new("pool"))
+                })?;
+        }
+        if let Some(planned) = planned {
+            pools
+                .update_guests(&planned, &[vmid], false)
+                .await
+                .map_err(|e| {
+                    Diagnostic::error(
+                        "Failed to join pool",
+                        format!("Adding VM {} to pool '{}': {}", vmid, planned, e),
+                    )
+                    .with_attribute(AttributePath::new("pool"))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Whether the cluster resource listing has the VM. None when the
+    /// listing failed, so the caller can't tell.
+    async fn cluster_has_vm(provider_data: &crate::ProxmoxProviderData, vmid: u32) -> Option<bool> {
+        let resources = provider_data.cluster_vms().await.ok()?;
+        Some(
+            resources
+                .iter()
+                .any(|resource| resource.resource_type == "qemu" && resource.vmid == Some(vmid)),
+        )
+    }
+
+    /// Pool the VM currently belongs to, from the cluster resource listing.
+    /// The outer None means the listing failed.
+    async fn current_pool(
+        provider_data: &crate::ProxmoxProviderData,
+        vmid: u32,
+    ) -> Option<Option<String>> {
+        let resources = provider_data.cluster_vms().await.ok()?;
+        Some(
+            resources
+                .iter()
+                .find(|resource| resource.vmid == Some(vmid))
+                .and_then(|resource| resource.pool.clone()),
+        )
+    }
+
+    /// Hotplug features enabled by the VM's hotplug option
+    fn hotplug_features(hotplug: Option<&str>) -> Vec<String> {
+        match hotplug.map(str::trim) {
+            None | Some("1") => DEFAULT_HOTPLUG.iter().map(|f| f.to_string()).collect(),
+            Some("0") => vec![],
+            Some(features) => features
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Planned changes that a running VM with the given hotplug features
+    /// cannot pick up until it restarts
+    fn restart_changes(
+        prior: &DynamicValue,
+        planned: &DynamicValue,
+        hotplug: &[String],
+    ) -> Vec<String> {
+        let root = |state: &DynamicValue| state.get_map(&AttributePath::root()).unwrap_or_default();
+        let (prior_root, planned_root) = (root(prior), root(planned));
+        let changed = |name: &str| {
+            prior_root.get(name).unwrap_or(&Dynamic::Null)
+                != planned_root.get(name).unwrap_or(&Dynamic::Null)
+        };
+        let block_changed = |block: &str, field: &str| {
+            let value = |state: &DynamicValue| {
+                Self::single_block(state, block)
+                    .and_then(|block| block.get(field).cloned())
+                    .unwrap_or(Dynamic::Null)
+            };
+            value(prior) != value(planned)
+        };
+        let hotpluggable = |feature: &str| hotplug.iter().any(|f| f == feature);
+
+        let mut changes: Vec<String> = RESTART_ATTRIBUTES
+            .iter()
+            .filter(|name| changed(name))
+            .map(|name| name.to_string())
+            .collect();
+        changes.extend(
+            HOTPLUG_ATTRIBUTES
+                .iter()
+                .filter(|(name, feature)| changed(name) && !hotpluggable(feature))
+                .map(|(name, _)| name.to_string()),
+        );
+        for field in ["type", "flags", "affinity", "numa"] {
+            if block_changed("cpu", field) {
+                changes.push(format!("cpu.{}", field));
+            }
+        }
+        if block_changed("memory", "dedicated") && !hotpluggable("memory") {
+            changes.push("memory.dedicated".to_string());
+        }
+        for field in ["hugepages", "keep_hugepages"] {
+            if block_changed("memory", field) {
+                changes.push(format!("memory.{}", field));
+            }
+        }
+        changes
+    }
+
+    /// Whether to reboot the VM when an update leaves changes pending
+    fn reboots_on_update(config: &DynamicValue) -> bool {
+        config
+            .get_bool(&AttributePath::new("reboot_on_update"))
+            .unwrap_or(false)
+            && config
+                .get_bool(&AttributePath::new("automatic_reboot"))
+                .unwrap_or(true)
+    }
+
+    /// Checks for changes Proxmox staged until the next reboot after an
+    /// update, and reboots the VM to apply them when reboot_on_update is set.
+    /// The reboot gets the update timeout.
+    async fn apply_pending_changes(
+        ctx: &Context,
+        client: &crate::api::Client,
+        node: &str,
+        vmid: u32,
+        config: &DynamicValue,
+        state: &mut DynamicValue,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let timeout = Timeouts::new(config).update(ctx, REBOOT_TIMEOUT);
+        let reboot = Self::reboots_on_update(config);
+        let mut pending = match Self::pending_keys(client, node, vmid).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                if reboot {
+                    diagnostics.push(Diagnostic::warning(
+                        "Could not check pending changes",
+                        format!("The VM was not rebooted: {}", e),
+                    ));
+                }
+                Self::fill_pending_changes(state, false);
+                return;
+            }
+        };
+
+        if !pending.is_empty() && reboot {
+            let node_api = client.nodes().node(node);
+            let rebooted = match node_api.qemu().reboot(vmid).await {
+                Ok(upid) => node_api
+                    .wait_for_task(&upid, timeout, ctx.cancelled())
+                    .await
+                    .map(|_| ()),
+                Err(e) => Err(e),
+            };
+            match rebooted {
+                Ok(()) => {
+                    pending = Self::pending_keys(client, node, vmid)
+                        .await
+                        .unwrap_or_default();
+                }
+                Err(e) => {
+                    diagnostics.push(Diagnostic::error(
+                        "Failed to reboot VM",
+                        format!(
+                            "The configuration was updated but the reboot to apply {} failed: {}",
+                            pending.join(", "),
+                            e
+                        ),
+                    ));
+                    diagnostics.extend(task_cancelled_warning(&e));
+                }
+            }
+        } else if !pending.is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                "Changes pending reboot",
+                format!(
+                    "Proxmox applies {} on the next reboot. Set reboot_on_update to reboot the VM automatically.",
+                    pending.join(", ")
+                ),
+            ));
+        }
+
+        Self::fill_pending_changes(state, !pending.is_empty());
+    }
+
+    /// Checks the target node exposes vmx/svm and adjusts the CPU string so the
+    /// guest sees the extension. Problems are reported as warnings since the
+    /// VM can still be created without nested support.
+    async fn apply_nested_virtualization(
+        client: &crate::api::Client,
+        node: &str,
+        cpu: &mut Option<String>,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        let status = match client.nodes().node(node).status().await {
+            Ok(status) => status,
+            Err(e) => {
+                diagnostics.push(Diagnostic::warning(
+                    "Could not verify nested virtualization support",
+                    format!("Failed to read CPU information for node '{}': {}", node, e),
+                ));
+                return;
+            }
+        };
+
+        match status
+            .cpuinfo
+            .as_ref()
+            .and_then(|info| info.virtualization_flag())
+        {
+            Some(flag) => *cpu = Some(Self::nested_virtualization_cpu(cpu.as_deref(), flag)),
+            None => diagnostics.push(Diagnostic::warning(
+                "Node CPU does not expose virtualization extensions",
+                format!("Node '{}' does not report the vmx or svm CPU flag. Nested virtualization will not work until VT-x/AMD-V and nested KVM are enabled on the host.", node),
+            )),
+        }
+    }
+
+    /// Reads the attach_existing blocks as (slot, volid, format) tuples
+    fn attach_existing_blocks(config: &DynamicValue) -> Vec<(String, String, Option<String>)> {
+        Self::indexed_attach_existing_blocks(config)
+            .into_iter()
+            .map(|(_, slot, volid, format)| (slot, volid, format))
+            .collect()
+    }
+
+    /// attach_existing blocks with their position in the configuration, for
+    /// diagnostics that point at the block
+    fn indexed_attach_existing_blocks(
+        config: &DynamicValue,
+    ) -> Vec<(usize, String, String, Option<String>)> {
+        config
+            .get_list(&AttributePath::new("attach_existing"))
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, block)| {
+                let map = match block {
+                    Dynamic::Map(map) => map,
+                    _ => return None,
+                };
+                let slot = match map.get("slot") {
+                    Some(Dynamic::String(s)) => s.clone(),
+                    _ => return None,
+                };
+                let volid = match map.get("volid") {
+                    Some(Dynamic::String(s)) => s.clone(),
+                    _ => return None,
+                };
+                let format = match map.get("format") {
+                    Some(Dynamic::String(s)) if !s.is_empty() => Some(s.clone()),
+                    _ => None,
+                };
+                Some((index, slot, volid, format))
+            })
+            .collect()
+    }
+
+    /// Slots claimed by the disk, cdrom and cloudinit_drive blocks
+    fn block_drive_slots(config: &DynamicValue) -> std::collections::HashSet<String> {
+        ["disk", "cdrom", "cloudinit_drive"]
+            .iter()
+            .flat_map(|block| {
+                config
+                    .get_list(&AttributePath::new(block))
+                    .unwrap_or_default()
+            })
+            .filter_map(|block| match block {
+                Dynamic::Map(map) => match map.get("slot") {
+                    Some(Dynamic::String(slot)) => Some(slot.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn validate_attach_existing(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let block_slots = Self::block_drive_slots(config);
+        let mut seen = std::collections::HashSet::new();
+
+        for (index, slot, volid, _) in Self::indexed_attach_existing_blocks(config) {
+            let path = AttributePath::new("attach_existing").index(index as i64);
+            if !crate::api::nodes::DRIVE_SLOTS.contains(&slot.as_str()) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path.clone().attribute("slot"),
+                    "Invalid attach_existing slot",
+                    format!(
+                        "'{}' is not a drive slot. Use an ide, sata, scsi or virtio slot such as scsi1",
+                        slot
+                    ),
+                ));
+            }
+            if crate::api::nodes::parse_volid(&volid).is_none() {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path.clone().attribute("volid"),
+                    "Invalid volid",
+                    format!(
+                        "Volume ID '{}' must be in the form <storage>:<volume>, e.g. local-lvm:vm-100-disk-1",
+                        volid
+                    ),
+                ));
+            }
+            if block_slots.contains(&slot) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path.clone().attribute("slot"),
+                    "Drive slot conflict",
+                    format!(
+                        "Slot '{}' is used by an attach_existing block and by another drive block",
+                        slot
+                    ),
+                ));
+            }
+            if !seen.insert(slot.clone()) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path.attribute("slot"),
+                    "Duplicate attach_existing slot",
+                    format!(
+                        "Slot '{}' is used by more than one attach_existing block",
+                        slot
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Checks that every volume to attach exists on its storage and, when a
+    /// format is given, that the storage reports the same format
+    async fn verify_attach_existing(
+        client: &crate::api::Client,
+        node: &str,
+        config: &DynamicValue,
+    ) -> Result<Vec<(String, String)>, Diagnostic> {
+        let mut attached = vec![];
+
+        for (slot, volid, format) in Self::attach_existing_blocks(config) {
+            let (storage, _) = crate::api::nodes::parse_volid(&volid).ok_or_else(|| {
+                Diagnostic::error(
+                    "Invalid volid",
+                    format!(
+                        "Volume ID '{}' must be in the form <storage>:<volume>",
+                        volid
+                    ),
+                )
+            })?;
+
+            let volume = client
+                .nodes()
+                .node(node)
+                .storage(storage)
+                .volume(&volid)
+                .await
+                .map_err(|e| {
+                    Diagnostic::error(
+                        "Volume not found",
+                        format!(
+                            "Could not find volume '{}' on storage '{}' of node '{}': {}",
+                            volid, storage, node, e
+                        ),
+                    )
+                })?;
+
+            if let Some(expected) = format {
+                let actual = volume.format.unwrap_or_default();
+                if actual != expected {
+                    return Err(Diagnostic::error(
+                        "Volume format mismatch",
+                        format!(
+                            "Volume '{}' has format '{}', but attach_existing for slot '{}' expects '{}'",
+                            volid, actual, slot, expected
+                        ),
+                    ));
+                }
+            }
+
+            attached.push((slot, volid));
+        }
+
+        Ok(attached)
+    }
+
+    /// Slots whose attach_existing block was removed. Proxmox turns a deleted
+    /// drive into an unusedN entry, so the volume itself is kept.
+    fn detached_slots(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let mut keep = Self::block_drive_slots(config);
+        keep.extend(
+            Self::attach_existing_blocks(config)
+                .into_iter()
+                .map(|(slot, _, _)| slot),
+        );
+
+        Self::attach_existing_blocks(prior)
+            .into_iter()
+            .map(|(slot, _, _)| slot)
+            .filter(|slot| !keep.contains(slot))
+            .collect()
+    }
+
+    /// Refreshes attach_existing blocks from the VM config. Blocks whose slot
+    /// is empty are dropped so that the plan re-attaches the volume.
+    fn refresh_attach_existing(
+        state: &mut DynamicValue,
+        vm_config: &crate::api::nodes::QemuConfig,
+        current_state: &DynamicValue,
+    ) {
+        let blocks = match current_state.get_list(&AttributePath::new("attach_existing")) {
+            Ok(blocks) => blocks,
+            Err(_) => return,
+        };
+
+        let refreshed = blocks
+            .into_iter()
+            .filter_map(|block| {
+                let mut map = match block {
+                    Dynamic::Map(map) => map,
+                    _ => return None,
+                };
+                let slot = match map.get("slot") {
+                    Some(Dynamic::String(slot)) => slot.clone(),
+                    _ => return None,
+                };
+                let volid = vm_config.drive(&slot)?.split(',').next()?.to_string();
+                map.insert("volid".to_string(), Dynamic::String(volid));
+                Some(Dynamic::Map(map))
+            })
+            .collect();
+
+        let _ = state.set_list(&AttributePath::new("attach_existing"), refreshed);
+    }
+
+    // Block conversion methods for nested block attributes
+    fn disk_block_to_api_string(disk: &Dynamic) -> Result<(String, String), String> {
+        let disk_map = match disk {
+            Dynamic::Map(map) => map,
+            _ => return Err("Disk must be a map".to_string()),
+        };
+
+        let slot = disk_map
+            .get("slot")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .ok_or("Slot is required")?;
+
+        let storage = disk_map
+            .get("storage")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .ok_or("Storage is required")?;
+
+        let size = disk_map
+            .get("size")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .ok_or("Size is required")?;
+
+        // Convert size format (e.g., "20G" to "20")
+        let size_num = size.trim_end_matches('G').trim_end_matches('g');
+        let mut drive = PropertyString::new("file");
+        match Self::disk_import_source(disk) {
+            // The size comes from the image
+            Some(source) => {
+                drive.set("file", format!("{}:0", storage));
+                drive.set("import-from", source);
+            }
+            None => drive.set("file", format!("{}:{}", storage, size_num)),
+        }
+
+        // Add optional attributes
+        if let Some(Dynamic::String(format)) = disk_map.get("format") {
+            if !format.is_empty() {
+                drive.set("format", format.as_str());
+            }
+        }
+
+        if let Some(Dynamic::Bool(true)) = disk_map.get("iothread") {
+            drive.set("iothread", "1");
+        }
+
+        if let Some(Dynamic::Bool(true)) = disk_map.get("emulatessd") {
+            drive.set("ssd", "1");
+        }
+
+        if let Some(Dynamic::Bool(true)) = disk_map.get("discard") {
+            drive.set("discard", "on");
+        }
+
+        if let Some(Dynamic::Bool(false)) = disk_map.get("backup") {
+            drive.set("backup", "0");
+        }
+
+        if let Some(Dynamic::Bool(false)) = disk_map.get("replicate") {
+            drive.set("replicate", "0");
+        }
+
+        if let Some(Dynamic::Bool(true)) = disk_map.get("readonly") {
+            drive.set("ro", "1");
+        }
+
+        // IO limits
+        if let Some(Dynamic::Number(n)) = disk_map.get("iops_r_burst") {
+            drive.set("iops_rd_max", (*n as i64).to_string());
+        }
+        if let Some(Dynamic::Number(n)) = disk_map.get("iops_r_concurrent") {
+            drive.set("iops_rd", (*n as i64).to_string());
+        }
+        if let Some(Dynamic::Number(n)) = disk_map.get("iops_wr_burst") {
+            drive.set("iops_wr_max", (*n as i64).to_string());
+        }
+        if let Some(Dynamic::Number(n)) = disk_map.get("iops_wr_concurrent") {
+            drive.set("iops_wr", (*n as i64).to_string());
+        }
+
+        // Bandwidth limits
+        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_r_burst") {
+            drive.set("mbps_rd_max", (*n as i64).to_string());
+        }
+        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_r_concurrent") {
+            drive.set("mbps_rd", (*n as i64).to_string());
+        }
+        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_wr_burst") {
+            drive.set("mbps_wr_max", (*n as i64).to_string());
+        }
+        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_wr_concurrent") {
+            drive.set("mbps_wr", (*n as i64).to_string());
+        }
+
+        Ok((slot, drive.to_string()))
+    }
+
+    /// The image a disk block is created from, if any
+    fn disk_import_source(disk: &Dynamic) -> Option<String> {
+        match disk {
+            Dynamic::Map(map) => match map.get("import_from") {
+                Some(Dynamic::String(source)) if !source.is_empty() => Some(source.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Disk blocks importing from a URL, as slot and disk string. The API only
+    /// imports from storage volumes, so these are left out of the create
+    /// request and imported over SSH once the VM exists.
+    fn url_disk_imports(config: &DynamicValue) -> Vec<(String, String, String)> {
+        config
+            .get_list(&AttributePath::new("disk"))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|disk| {
+                let url = Self::disk_import_source(disk)
+                    .filter(|s| s.starts_with("http://") || s.starts_with("https://"))?;
+                let (slot, disk_string) = Self::disk_block_to_api_string(disk).ok()?;
+                Some((slot, url, disk_string))
+            })
+            .collect()
+    }
+
+    /// Shell script that downloads `url` on the node and attaches it to `slot`
+    /// as a new disk. `qm` runs as root, so unlike the API it may import
+    /// from a plain file.
+    fn url_import_script(vmid: u32, slot: &str, url: &str, disk_string: &str) -> String {
+        let import_from = format!("import-from={}", url);
+        let (head, tail) = disk_string
+            .split_once(&import_from)
+            .unwrap_or((disk_string, ""));
+
+        format!(
+            "set -e\n\
+             dir=$(mktemp -d -p /var/tmp)\n\
+             trap 'rm -rf \"$dir\"' EXIT\n\
+             wget -q -O \"$dir/image\" {url}\n\
+             qm set {vmid} --{slot} {head}\"import-from=$dir/image\"{tail}\n",
+            url = ssh::quote(url),
+            vmid = vmid,
+            slot = slot,
+            head = if head.is_empty() {
+                String::new()
+            } else {
+                ssh::quote(head)
+            },
+            tail = if tail.is_empty() {
+                String::new()
+            } else {
+                ssh::quote(tail)
+            },
+        )
+    }
+
+    /// Slots whose import_from differs from the prior state. Images are only
+    /// imported when the VM is created.
+    fn changed_disk_imports(prior: &DynamicValue, planned: &DynamicValue) -> Vec<String> {
+        let imports = |value: &DynamicValue| -> HashMap<String, Option<String>> {
+            value
+                .get_list(&AttributePath::new("disk"))
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|disk| match disk {
+                    Dynamic::Map(map) => match map.get("slot") {
+                        Some(Dynamic::String(slot)) => {
+                            Some((slot.clone(), Self::disk_import_source(disk)))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let prior = imports(prior);
+        let mut changed: Vec<String> = imports(planned)
+            .into_iter()
+            .filter(|(slot, source)| source.is_some() && prior.get(slot) != Some(source))
+            .map(|(slot, _)| slot)
+            .collect();
+        changed.sort();
+        changed
+    }
+
+    fn cdrom_block_to_api_string(cdrom: &Dynamic) -> Result<(String, String), String> {
+        let cdrom_map = match cdrom {
+            Dynamic::Map(map) => map,
+            _ => return Err("CD-ROM must be a map".to_string()),
+        };
+
+        let slot = cdrom_map
+            .get("slot")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .ok_or("Slot is required")?;
+
+        let iso = cdrom_map
+            .get("iso")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .ok_or("ISO is required")?;
+
+        Ok((slot, format!("{},media=cdrom", iso)))
+    }
+
+    /// Drive strings of the cdrom blocks keyed by slot
+    fn cdrom_drives(config: &DynamicValue) -> Vec<(String, String)> {
+        config
+            .get_list(&AttributePath::new("cdrom"))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|cdrom| Self::cdrom_block_to_api_string(cdrom).ok())
+            .collect()
+    }
+
+    /// CD-ROM slots whose block was removed. Ejecting keeps the drive, while
+    /// removing the block deletes it.
+    fn removed_cdrom_slots(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let mut keep = Self::block_drive_slots(config);
+        keep.extend(
+            Self::attach_existing_blocks(config)
+                .into_iter()
+                .map(|(slot, _, _)| slot),
+        );
+
+        Self::cdrom_drives(prior)
+            .into_iter()
+            .map(|(slot, _)| slot)
+            .filter(|slot| !keep.contains(slot))
+            .collect()
+    }
+
+    /// VLAN tags must be 802.1Q VLAN IDs. -1 is the schema's "untagged".
+    fn validate_network(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let networks = config
+            .get_list(&AttributePath::new("network"))
+            .unwrap_or_default();
+        for (index, network) in networks.iter().enumerate() {
+            let Dynamic::Map(network) = network else {
+                continue;
+            };
+            let Some(Dynamic::Number(tag)) = network.get("tag") else {
+                continue;
+            };
+            if *tag == -1.0 || (tag.fract() == 0.0 && (1.0..=4094.0).contains(tag)) {
+                continue;
+            }
+            diagnostics.push(Diagnostic::attribute_error(
+                AttributePath::new("network")
+                    .index(index as i64)
+                    .attribute("tag"),
+                "Invalid VLAN tag",
+                format!(
+                    "VLAN tag must be a whole number from 1 to 4094, got {}",
+                    tag
+                ),
+            ));
+        }
+    }
+
+    fn validate_cdrom(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let cdroms = config
+            .get_list(&AttributePath::new("cdrom"))
+            .unwrap_or_default();
+        let mut seen = std::collections::HashSet::new();
+
+        for (index, cdrom) in cdroms.iter().enumerate() {
+            let cdrom = match cdrom {
+                Dynamic::Map(cdrom) => cdrom,
+                _ => continue,
+            };
+            let path = AttributePath::new("cdrom").index(index as i64);
+            if let Some(Dynamic::String(slot)) = cdrom.get("slot") {
+                if !crate::api::nodes::DRIVE_SLOTS.contains(&slot.as_str())
+                    || slot.starts_with("virtio")
+                {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        path.clone().attribute("slot"),
+                        "Invalid cdrom slot",
+                        format!(
+                            "'{}' cannot hold a CD-ROM. Use an ide, sata or scsi slot such as ide2",
+                            slot
+                        ),
+                    ));
+                } else if !seen.insert(slot.clone()) {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        path.clone().attribute("slot"),
+                        "Duplicate cdrom slot",
+                        format!("Slot '{}' is used by more than one cdrom block", slot),
+                    ));
+                }
+            }
+            if let Some(Dynamic::String(iso)) = cdrom.get("iso") {
+                if iso != "none" && crate::api::nodes::parse_volid(iso).is_none() {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        path.attribute("iso"),
+                        "Invalid cdrom iso",
+                        format!(
+                            "ISO '{}' must be a volume such as local:iso/ubuntu.iso, or \"none\" to eject the disc",
+                            iso
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn cloudinit_drive_block_to_api_string(ci_drive: &Dynamic) -> Result<(String, String), String> {
+        let ci_map = match ci_drive {
+            Dynamic::Map(map) => map,
+            _ => return Err("Cloud-init drive must be a map".to_string()),
+        };
+
+        let slot = ci_map
+            .get("slot")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .ok_or("Slot is required")?;
+
+        let storage = ci_map
+            .get("storage")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .ok_or("Storage is required")?;
+
+        Ok((slot, format!("{}:cloudinit", storage)))
+    }
+
+    fn serial_block_to_api_string(serial: &Dynamic) -> Result<(u32, String), String> {
+        let serial_map = match serial {
+            Dynamic::Map(map) => map,
+            _ => return Err("Serial must be a map".to_string()),
+        };
+
+        let id = serial_map
+            .get("id")
+            .and_then(|v| match v {
+                Dynamic::Number(n) => Some(*n as u32),
+                _ => None,
+            })
+            .ok_or("ID is required")?;
+
+        let type_str = serial_map
+            .get("type")
+            .and_then(|v| match v {
+                Dynamic::String(s) => Some(s.clone()),
+                _ => None,
+            })
+            .ok_or("Type is required")?;
+
+        // A host device is passed as its path, a socket by name
+        if type_str == "device" {
+            return match serial_map.get("device") {
+                Some(Dynamic::String(device)) => Ok((id, device.clone())),
+                _ => Err("Device is required for device serial ports".to_string()),
+            };
         }
-        if let Some(Dynamic::Number(n)) = disk_map.get("iops_wr_burst") {
-            parts.push(format!("iops_wr_max={}", *n as i64));
+
+        Ok((id, type_str))
+    }
+
+    fn parse_serial_string(serial_string: &str, id: u32) -> Dynamic {
+        let mut serial = HashMap::new();
+        serial.insert("id".to_string(), Dynamic::Number(id as f64));
+        if serial_string.starts_with("/dev/") {
+            serial.insert("type".to_string(), Dynamic::String("device".to_string()));
+            serial.insert(
+                "device".to_string(),
+                Dynamic::String(serial_string.to_string()),
+            );
+        } else {
+            serial.insert(
+                "type".to_string(),
+                Dynamic::String(serial_string.to_string()),
+            );
+            serial.insert("device".to_string(), Dynamic::Null);
         }
-        if let Some(Dynamic::Number(n)) = disk_map.get("iops_wr_concurrent") {
-            parts.push(format!("iops_wr={}", *n as i64));
+        Dynamic::Map(serial)
+    }
+
+    fn vga_block_to_api_string(vga: &Dynamic) -> Result<String, String> {
+        let vga_map = match vga {
+            Dynamic::Map(map) => map,
+            _ => return Err("VGA must be a map".to_string()),
+        };
+
+        let vga_type = match vga_map.get("type") {
+            Some(Dynamic::String(vga_type)) => vga_type.clone(),
+            _ => return Err("Type is required".to_string()),
+        };
+
+        let mut parts = vec![vga_type];
+        if let Some(Dynamic::Number(memory)) = vga_map.get("memory") {
+            parts.push(format!("memory={}", *memory as u32));
+        }
+
+        Ok(parts.join(","))
+    }
+
+    fn parse_vga_string(vga_string: &str) -> Dynamic {
+        let mut vga = HashMap::new();
+        vga.insert("memory".to_string(), Dynamic::Null);
+        let parsed = PropertyString::parse(vga_string, "type");
+        if let Some(vga_type) = parsed.default_value() {
+            vga.insert("type".to_string(), Dynamic::String(vga_type.to_string()));
+        }
+        if let Some(Ok(memory)) = parsed.get("memory").map(str::parse::<f64>) {
+            vga.insert("memory".to_string(), Dynamic::Number(memory));
+        }
+        Dynamic::Map(vga)
+    }
+
+    /// Serial ports from the serial blocks, as (id, API value)
+    fn serial_ports(config: &DynamicValue) -> Vec<(u32, String)> {
+        config
+            .get_list(&AttributePath::new("serial"))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|serial| Self::serial_block_to_api_string(serial).ok())
+            .collect()
+    }
+
+    fn vga_config(config: &DynamicValue) -> Option<String> {
+        config
+            .get_list(&AttributePath::new("vga"))
+            .ok()?
+            .first()
+            .and_then(|vga| Self::vga_block_to_api_string(vga).ok())
+    }
+
+    fn validate_console(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let serials = config
+            .get_list(&AttributePath::new("serial"))
+            .unwrap_or_default();
+        let mut ids = std::collections::HashSet::new();
+        for serial in &serials {
+            let serial_map = match serial {
+                Dynamic::Map(map) => map,
+                _ => continue,
+            };
+            if let Some(Dynamic::Number(id)) = serial_map.get("id") {
+                if id.fract() != 0.0 || *id < 0.0 || *id >= MAX_SERIAL_PORTS as f64 {
+                    diagnostics.push(Diagnostic::error(
+                        "Invalid serial port",
+                        format!(
+                            "Serial port id must be between 0 and {}",
+                            MAX_SERIAL_PORTS - 1
+                        ),
+                    ));
+                } else if !ids.insert(*id as u32) {
+                    diagnostics.push(Diagnostic::error(
+                        "Duplicate serial port",
+                        format!("serial{} is configured more than once", *id as u32),
+                    ));
+                }
+            }
+            let device = match serial_map.get("device") {
+                Some(Dynamic::String(device)) => Some(device.as_str()),
+                _ => None,
+            };
+            match (serial_map.get("type"), device) {
+                (Some(Dynamic::String(t)), Some(device)) if t == "device" => {
+                    if !device.starts_with("/dev/") {
+                        diagnostics.push(Diagnostic::error(
+                            "Invalid serial device",
+                            format!("'{}' is not a host device path like /dev/ttyS0", device),
+                        ));
+                    }
+                }
+                (Some(Dynamic::String(t)), None) if t == "device" => {
+                    diagnostics.push(Diagnostic::error(
+                        "Missing serial device",
+                        "Serial ports of type 'device' need the host device path in 'device'",
+                    ));
+                }
+                (Some(Dynamic::String(t)), device) if t == "socket" => {
+                    if device.is_some() {
+                        diagnostics.push(Diagnostic::error(
+                            "Invalid serial device",
+                            "'device' only applies to serial ports of type 'device'",
+                        ));
+                    }
+                }
+                (Some(Dynamic::String(t)), _) => {
+                    diagnostics.push(Diagnostic::error(
+                        "Invalid serial type",
+                        format!("Serial port type must be 'socket' or 'device', not '{}'", t),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let vga = match config
+            .get_list(&AttributePath::new("vga"))
+            .ok()
+            .and_then(|list| list.into_iter().next())
+        {
+            Some(Dynamic::Map(vga)) => vga,
+            _ => return,
+        };
+        if let Some(Dynamic::String(vga_type)) = vga.get("type") {
+            if !VGA_TYPES.contains(&vga_type.as_str()) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid VGA type",
+                    format!("VGA type must be one of: {}", VGA_TYPES.join(", ")),
+                ));
+            } else if let Some(port) = vga_type.strip_prefix("serial") {
+                // Cloud images with a serial console need the port to exist
+                let has_port = port.parse::<u32>().is_ok_and(|port| ids.contains(&port));
+                if !has_port {
+                    diagnostics.push(Diagnostic::error(
+                        "Missing serial port for VGA",
+                        format!(
+                            "VGA type '{}' uses serial port {} as display. Add a serial block with id = {}.",
+                            vga_type, port, port
+                        ),
+                    ));
+                }
+            }
+        }
+        if let Some(Dynamic::Number(memory)) = vga.get("memory") {
+            if !(4.0..=512.0).contains(memory) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid VGA memory",
+                    "VGA memory must be between 4 and 512 MiB",
+                ));
+            }
+        }
+    }
+
+    /// Serial ports and the display that were removed from the
+    /// configuration. Proxmox keeps them unless they are deleted.
+    fn removed_console_devices(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let configured: Vec<u32> = Self::serial_ports(config)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let mut removed: Vec<String> = Self::serial_ports(prior)
+            .into_iter()
+            .filter(|(id, _)| !configured.contains(id))
+            .map(|(id, _)| format!("serial{}", id))
+            .collect();
+        if Self::vga_config(prior).is_some() && Self::vga_config(config).is_none() {
+            removed.push("vga".to_string());
+        }
+        removed
+    }
+
+    /// Parses a Proxmox id list such as "0-3;6" into inclusive ranges
+    fn parse_id_ranges(ids: &str, separator: char) -> Result<Vec<(u32, u32)>, String> {
+        ids.split(separator)
+            .map(|range| {
+                let (start, end) = range.split_once('-').unwrap_or((range, range));
+                let start: u32 = start
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a valid id range", range))?;
+                let end: u32 = end
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a valid id range", range))?;
+                if start > end {
+                    return Err(format!("'{}' is not a valid id range", range));
+                }
+                Ok((start, end))
+            })
+            .collect()
+    }
+
+    /// The numa_node blocks as their models
+    fn numa_node_blocks(config: &DynamicValue) -> Vec<NumaNode> {
+        config
+            .get_list(&AttributePath::new("numa_node"))
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|numa| NumaNode::from_dynamic(numa).ok())
+            .collect()
+    }
+
+    /// Guest NUMA nodes from the numa_node blocks, as (id, API value)
+    fn numa_nodes(config: &DynamicValue) -> Vec<(u32, String)> {
+        Self::numa_node_blocks(config)
+            .iter()
+            .filter_map(|numa| numa.to_api_string().ok())
+            .collect()
+    }
+
+    /// numa0 to numa7 for the create and update requests
+    fn numa_node_fields(config: &DynamicValue) -> [Option<String>; MAX_NUMA_NODES as usize] {
+        let mut fields: [Option<String>; MAX_NUMA_NODES as usize] = Default::default();
+        for (id, numa_string) in Self::numa_nodes(config) {
+            if let Some(field) = fields.get_mut(id as usize) {
+                *field = Some(numa_string);
+            }
+        }
+        fields
+    }
+
+    /// Number of vCPUs the NUMA nodes can be assigned, or None while sockets
+    /// or cores are not known yet
+    fn numa_vcpu_count(config: &DynamicValue) -> Option<u32> {
+        let attributes = config.get_map(&AttributePath::root()).ok()?;
+        let count = |name: &str| match attributes.get(name) {
+            Some(Dynamic::Number(n)) => Some(*n as u32),
+            Some(Dynamic::Unknown) => None,
+            _ => Some(1),
+        };
+        Some(count("sockets")? * count("cores")?)
+    }
+
+    fn validate_numa(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let nodes = Self::numa_node_blocks(config);
+        if nodes.is_empty() {
+            return;
+        }
+
+        if Self::cpu_settings(config).numa != Some(true) {
+            diagnostics.push(
+                Diagnostic::error(
+                    "NUMA not enabled",
+                    "numa_node blocks only take effect with numa = true in the cpu block",
+                )
+                .with_attribute(AttributePath::new("cpu")),
+            );
+        }
+
+        let vcpus = Self::numa_vcpu_count(config);
+        let mut ids = std::collections::HashSet::new();
+        let mut assigned: Vec<(u32, u32)> = vec![];
+        let mut total_memory = Some(0.0);
+        for node in &nodes {
+            if let Some(id) = node.id {
+                if id.fract() != 0.0 || id < 0.0 || id >= MAX_NUMA_NODES as f64 {
+                    diagnostics.push(Diagnostic::error(
+                        "Invalid NUMA node",
+                        format!("NUMA node ID must be between 0 and {}", MAX_NUMA_NODES - 1),
+                    ));
+                } else if !ids.insert(id as u32) {
+                    diagnostics.push(Diagnostic::error(
+                        "Duplicate NUMA node",
+                        format!("numa{} is configured more than once", id as u32),
+                    ));
+                }
+            }
+
+            if let Some(cpus) = &node.cpus {
+                match Self::parse_id_ranges(cpus, ';') {
+                    Ok(ranges) => {
+                        for &(start, end) in &ranges {
+                            if let Some(vcpus) = vcpus {
+                                if end >= vcpus {
+                                    diagnostics.push(Diagnostic::error(
+                                        "NUMA cpus out of range",
+                                        format!(
+                                            "cpus '{}' go beyond the {} vCPUs from sockets * cores (0-{})",
+                                            cpus,
+                                            vcpus,
+                                            vcpus.saturating_sub(1)
+                                        ),
+                                    ));
+                                }
+                            }
+                            if assigned.iter().any(|&(s, e)| start <= e && s <= end) {
+                                diagnostics.push(Diagnostic::error(
+                                    "Overlapping NUMA cpus",
+                                    format!(
+                                        "cpus '{}' are already assigned to another NUMA node",
+                                        cpus
+                                    ),
+                                ));
+                            }
+                        }
+                        assigned.extend(ranges);
+                    }
+                    Err(e) => diagnostics.push(Diagnostic::error(
+                        "Invalid NUMA cpus",
+                        format!("{}. Use ids and ranges separated by ';', e.g. 0-3;6", e),
+                    )),
+                }
+            }
+
+            match node.memory {
+                Some(memory) => total_memory = total_memory.map(|total| total + memory),
+                None => total_memory = None,
+            }
+
+            if let Some(hostnodes) = &node.hostnodes {
+                if let Err(e) = Self::parse_id_ranges(hostnodes, ';') {
+                    diagnostics.push(Diagnostic::error(
+                        "Invalid NUMA hostnodes",
+                        format!("{}. Use ids and ranges separated by ';', e.g. 0-1", e),
+                    ));
+                }
+            }
+            match &node.policy {
+                Some(policy) => {
+                    if !NUMA_POLICIES.contains(&policy.as_str()) {
+                        diagnostics.push(Diagnostic::error(
+                            "Invalid NUMA policy",
+                            format!("policy must be one of: {}", NUMA_POLICIES.join(", ")),
+                        ));
+                    }
+                }
+                // Proxmox refuses host nodes without a policy
+                None if node.hostnodes.is_some() => diagnostics.push(Diagnostic::error(
+                    "Missing NUMA policy",
+                    "hostnodes requires a policy",
+                )),
+                _ => {}
+            }
+        }
+
+        if let (Some(total), Some(memory)) = (total_memory, Self::dedicated_memory(config)) {
+            if total != memory as f64 {
+                diagnostics.push(Diagnostic::error(
+                    "NUMA memory mismatch",
+                    format!(
+                        "The NUMA nodes' memory adds up to {} MB, but the VM has {} MB. Proxmox requires them to match.",
+                        total, memory
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// Guest NUMA nodes that were removed from the configuration
+    fn removed_numa_nodes(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let configured: Vec<u32> = Self::numa_nodes(config)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        Self::numa_nodes(prior)
+            .into_iter()
+            .filter(|(id, _)| !configured.contains(id))
+            .map(|(id, _)| format!("numa{}", id))
+            .collect()
+    }
+
+    /// Cloud-init network settings from the cloudinit block, by network
+    /// interface id
+    fn cloudinit_ipconfigs(config: &DynamicValue) -> Vec<(u32, String)> {
+        Self::single_block_as::<CloudInit>(config, "cloudinit")
+            .unwrap_or_default()
+            .ipconfig
+            .iter()
+            .map(|ipconfig| (ipconfig.id as u32, ipconfig.to_api_string()))
+            .collect()
+    }
+
+    /// ipconfig0 to ipconfig3 from their attributes, followed by the
+    /// entries of the cloudinit block
+    fn ipconfigs(config: &DynamicValue) -> Vec<(u32, String)> {
+        let mut ipconfigs: Vec<(u32, String)> = (0..4)
+            .filter_map(|id| {
+                config
+                    .get_string(&AttributePath::new(&format!("ipconfig{}", id)))
+                    .ok()
+                    .map(|ipconfig| (id, ipconfig))
+            })
+            .collect();
+        ipconfigs.extend(Self::cloudinit_ipconfigs(config));
+        ipconfigs
+    }
+
+    /// ipconfig entries that were removed from the cloudinit block
+    fn removed_ipconfigs(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let configured: Vec<u32> = Self::cloudinit_ipconfigs(config)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        Self::cloudinit_ipconfigs(prior)
+            .into_iter()
+            .filter(|(id, _)| !configured.contains(id))
+            .map(|(id, _)| format!("ipconfig{}", id))
+            .collect()
+    }
+
+    /// DNS options that were removed from the cloudinit block
+    fn removed_cloudinit_dns(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let prior = Self::single_block_as::<CloudInit>(prior, "cloudinit").unwrap_or_default();
+        let config = Self::single_block_as::<CloudInit>(config, "cloudinit").unwrap_or_default();
+        [
+            (
+                "nameserver",
+                prior.nameserver.is_some() && config.nameserver.is_none(),
+            ),
+            (
+                "searchdomain",
+                prior.searchdomain.is_some() && config.searchdomain.is_none(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, removed)| *removed)
+        .map(|(option, _)| option.to_string())
+        .collect()
+    }
+
+    /// ipconfig ids must name a network interface of the VM, and each one
+    /// can only be configured once. The interfaces of a clone or a restored
+    /// VM come from its template or backup, so they are only known when the
+    /// VM is created from scratch.
+    fn validate_cloudinit(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let Some(cloudinit) = Self::single_block(config, "cloudinit") else {
+            return;
+        };
+        if let Some(Dynamic::List(servers)) = cloudinit.get("nameserver") {
+            for (index, server) in servers.iter().enumerate() {
+                if let Dynamic::String(address) = server {
+                    if address.parse::<std::net::IpAddr>().is_err() {
+                        diagnostics.push(Diagnostic::attribute_error(
+                            AttributePath::new("cloudinit")
+                                .index(0)
+                                .attribute("nameserver")
+                                .index(index as i64),
+                            "Invalid DNS server",
+                            format!("'{}' is not an IP address", address),
+                        ));
+                    }
+                }
+            }
+        }
+        let Some(Dynamic::List(ipconfigs)) = cloudinit.get("ipconfig") else {
+            return;
+        };
+        let inherits_networks = !matches!(
+            config
+                .get(&AttributePath::new("clone"))
+                .unwrap_or(&Dynamic::Null),
+            Dynamic::Null
+        ) || !matches!(
+            config
+                .get(&AttributePath::new("restore_from"))
+                .unwrap_or(&Dynamic::Null),
+            Dynamic::Null | Dynamic::List(_)
+        ) || Self::single_block(config, "restore_from").is_some();
+        let network_ids: Option<Vec<f64>> = if inherits_networks {
+            None
+        } else {
+            match config
+                .get(&AttributePath::new("network"))
+                .unwrap_or(&Dynamic::Null)
+            {
+                Dynamic::List(networks) => networks
+                    .iter()
+                    .map(|network| match network {
+                        Dynamic::Map(network) => match network.get("id") {
+                            Some(Dynamic::Number(id)) => Some(*id),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+                    .collect(),
+                Dynamic::Null => Some(vec![]),
+                _ => None,
+            }
+        };
+
+        let mut seen = std::collections::HashSet::new();
+        for (index, ipconfig) in ipconfigs.iter().enumerate() {
+            let Dynamic::Map(ipconfig) = ipconfig else {
+                continue;
+            };
+            let Some(Dynamic::Number(id)) = ipconfig.get("id") else {
+                continue;
+            };
+            let path = AttributePath::new("cloudinit")
+                .index(0)
+                .attribute("ipconfig")
+                .index(index as i64)
+                .attribute("id");
+            if id.fract() != 0.0 || !(0.0..MAX_NETWORK_INTERFACES as f64).contains(id) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path,
+                    "Invalid ipconfig id",
+                    format!(
+                        "ipconfig id must be a whole number from 0 to {}, got {}",
+                        MAX_NETWORK_INTERFACES - 1,
+                        id
+                    ),
+                ));
+                continue;
+            }
+            if !seen.insert(*id as u32) {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path,
+                    "Duplicate ipconfig id",
+                    format!("ipconfig {} is configured more than once", id),
+                ));
+                continue;
+            }
+            let attribute = format!("ipconfig{}", id);
+            if config.get_string(&AttributePath::new(&attribute)).is_ok() {
+                diagnostics.push(Diagnostic::attribute_error(
+                    path,
+                    "Conflicting ipconfig",
+                    format!(
+                        "ipconfig {} is set both by the {} attribute and in the cloudinit block. Use one of them",
+                        id, attribute
+                    ),
+                ));
+                continue;
+            }
+            if let Some(network_ids) = &network_ids {
+                if !network_ids.contains(id) {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        path,
+                        "Unknown network interface",
+                        format!(
+                            "ipconfig {} has no network block with the same id. Add a network block with id = {} or change the ipconfig id",
+                            id, id
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Splits the cpu option into the CPU type and its flags. Other options
+    /// such as hidden or phys-bits are not managed and are skipped.
+    fn parse_cpu_string(cpu: &str) -> (Option<String>, Vec<String>) {
+        let parsed = PropertyString::parse(cpu, "cputype");
+        let flags = parsed
+            .get("flags")
+            .unwrap_or_default()
+            .split(';')
+            .filter(|flag| !flag.is_empty())
+            .map(str::to_string)
+            .collect();
+        (parsed.default_value().map(str::to_string), flags)
+    }
+
+    fn cpu_settings(config: &DynamicValue) -> CpuSettings {
+        let cpu: Cpu = match Self::single_block_as(config, "cpu") {
+            Some(cpu) => cpu,
+            None => return CpuSettings::default(),
+        };
+        CpuSettings {
+            cpu: cpu.to_api_string(),
+            cpuunits: cpu.units.map(|units| units as u32),
+            cpulimit: cpu.limit,
+            affinity: cpu.affinity,
+            numa: cpu.numa,
+        }
+    }
+
+    /// Reads the cpu block back from the VM config. The host type and the
+    /// vmx/svm flag added for nested virtualization are only reported when
+    /// they were configured, so they don't show up as changes.
+    fn parse_cpu_settings(
+        vm_config: &crate::api::nodes::QemuConfig,
+        planned: &Cpu,
+        nested_virtualization: bool,
+    ) -> Dynamic {
+        let (mut cpu_type, mut flags) = vm_config
+            .cpu
+            .as_deref()
+            .map(Self::parse_cpu_string)
+            .unwrap_or_default();
+        if nested_virtualization {
+            flags.retain(|flag| {
+                !matches!(flag.as_str(), "+vmx" | "+svm") || planned.flags().contains(flag)
+            });
+            if planned.r#type.is_none() && cpu_type.as_deref() == Some("host") {
+                cpu_type = None;
+            }
+        } else if cpu_type.is_none() && planned.r#type.is_some() {
+            // Proxmox leaves the default CPU type out of the config
+            cpu_type = Some("x86-64-v2-AES".to_string());
+        }
+
+        Cpu {
+            r#type: cpu_type,
+            flags: (!flags.is_empty() || planned.flags.is_some()).then_some(flags),
+            units: vm_config.cpuunits.map(|units| units as f64),
+            limit: vm_config.cpulimit,
+            affinity: vm_config.affinity.clone(),
+            numa: match (vm_config.numa, planned.numa) {
+                (Some(numa), _) => Some(numa),
+                (None, Some(_)) => Some(false),
+                (None, None) => None,
+            },
+        }
+        .to_dynamic()
+    }
+
+    fn validate_cpu(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let cpu: Cpu = match Self::single_block_as(config, "cpu") {
+            Some(cpu) => cpu,
+            None => return,
+        };
+
+        for flag in cpu.flags() {
+            let valid = match flag.strip_prefix(['+', '-']) {
+                Some(name) => {
+                    !name.is_empty()
+                        && name
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                }
+                None => false,
+            };
+            if !valid {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid CPU flag",
+                        format!(
+                            "CPU flag '{}' must be a flag name prefixed with + to enable or - to disable it, e.g. +aes",
+                            flag
+                        ),
+                    )
+                    .with_attribute(AttributePath::new("cpu")),
+                );
+            }
+        }
+
+        if let Some(units) = cpu.units {
+            if units.fract() != 0.0 || !(1.0..=262144.0).contains(&units) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid CPU units",
+                        "units must be a whole number between 1 and 262144",
+                    )
+                    .with_attribute(AttributePath::new("cpu")),
+                );
+            }
+        }
+        if let Some(limit) = cpu.limit {
+            if !(0.0..=128.0).contains(&limit) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid CPU limit",
+                        "limit must be between 0 (no limit) and 128",
+                    )
+                    .with_attribute(AttributePath::new("cpu")),
+                );
+            }
+        }
+        if let Some(affinity) = &cpu.affinity {
+            if let Err(e) = Self::parse_id_ranges(affinity, ',') {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid CPU affinity",
+                        format!(
+                            "{}. Use host CPU ids and ranges separated by ',', e.g. 0-3,8",
+                            e
+                        ),
+                    )
+                    .with_attribute(AttributePath::new("cpu")),
+                );
+            }
+        }
+    }
+
+    /// CPU options that were removed from the cpu block
+    fn removed_cpu_settings(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let prior = Self::cpu_settings(prior);
+        let settings = Self::cpu_settings(config);
+        [
+            ("cpu", prior.cpu.is_some() && settings.cpu.is_none()),
+            (
+                "cpuunits",
+                prior.cpuunits.is_some() && settings.cpuunits.is_none(),
+            ),
+            (
+                "cpulimit",
+                prior.cpulimit.is_some() && settings.cpulimit.is_none(),
+            ),
+            (
+                "affinity",
+                prior.affinity.is_some() && settings.affinity.is_none(),
+            ),
+            ("numa", prior.numa.is_some() && settings.numa.is_none()),
+        ]
+        .into_iter()
+        .filter(|(_, removed)| *removed)
+        .map(|(key, _)| key.to_string())
+        .collect()
+    }
+
+    /// Builds the smbios1 option from the smbios block. Text fields are
+    /// always base64 encoded so any character can be used. A UUID from
+    /// earlier state is kept when the block doesn't set one, since
+    /// changing it changes the machine identity seen by the guest.
+    fn smbios_config(config: &DynamicValue, prior_uuid: Option<String>) -> Option<String> {
+        let smbios: Smbios = Self::single_block_as(config, "smbios").unwrap_or_default();
+
+        let mut parts: Vec<String> = smbios
+            .uuid
+            .clone()
+            .or(prior_uuid)
+            .map(|uuid| format!("uuid={}", uuid))
+            .into_iter()
+            .collect();
+        for (field, value) in smbios.text_fields() {
+            if let Some(value) = value {
+                parts.push(format!(
+                    "{}={}",
+                    field,
+                    base64::engine::general_purpose::STANDARD.encode(value)
+                ));
+            }
+        }
+        if parts.iter().any(|part| !part.starts_with("uuid=")) {
+            parts.push("base64=1".to_string());
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(","))
+        }
+    }
+
+    /// UUID of the smbios block, known once the VM exists
+    fn smbios_uuid(state: &DynamicValue) -> Option<String> {
+        Self::single_block_as::<Smbios>(state, "smbios")?.uuid
+    }
+
+    /// Parses the smbios1 option, decoding the text fields if Proxmox
+    /// stored them base64 encoded
+    fn parse_smbios_string(smbios_string: &str) -> Dynamic {
+        let values = PropertyString::parse(smbios_string, "");
+        let encoded = values.get("base64") == Some("1");
+
+        let mut smbios = Smbios {
+            uuid: values.get("uuid").map(|uuid| uuid.to_string()),
+            ..Default::default()
+        };
+        for (field, target) in smbios.text_fields_mut() {
+            *target = values.get(field).map(|value| {
+                if encoded {
+                    base64::engine::general_purpose::STANDARD
+                        .decode(value)
+                        .ok()
+                        .and_then(|decoded| String::from_utf8(decoded).ok())
+                        .unwrap_or_else(|| value.to_string())
+                } else {
+                    value.to_string()
+                }
+            });
+        }
+        smbios.to_dynamic()
+    }
+
+    fn validate_smbios(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let smbios: Smbios = match Self::single_block_as(config, "smbios") {
+            Some(smbios) => smbios,
+            None => return,
+        };
+
+        if let Some(uuid) = &smbios.uuid {
+            let groups: Vec<&str> = uuid.split('-').collect();
+            let valid = groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12])
+                && groups
+                    .iter()
+                    .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()));
+            if !valid {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid SMBIOS UUID",
+                        format!(
+                            "'{}' is not a UUID like 5a4f3c2e-1b0d-4e8f-9a7b-6c5d4e3f2a1b",
+                            uuid
+                        ),
+                    )
+                    .with_attribute(AttributePath::new("smbios")),
+                );
+            }
+        }
+
+        if let Some(smbios1) = Self::smbios_config(config, None) {
+            if smbios1.len() > MAX_SMBIOS_LENGTH {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "SMBIOS settings too long",
+                        format!(
+                            "The encoded smbios settings are {} characters, Proxmox accepts at most {}",
+                            smbios1.len(),
+                            MAX_SMBIOS_LENGTH
+                        ),
+                    )
+                    .with_attribute(AttributePath::new("smbios")),
+                );
+            }
+        }
+    }
+
+    /// Builds the startup option, e.g. "order=1,up=30,down=60"
+    fn startup_config(config: &DynamicValue) -> Option<String> {
+        let startup: Startup = Self::single_block_as(config, "startup")?;
+        let parts: Vec<String> = startup
+            .settings()
+            .into_iter()
+            .filter_map(|(_, key, value)| Some(format!("{}={}", key, value? as u64)))
+            .collect();
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(","))
         }
+    }
 
-        // Bandwidth limits
-        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_r_burst") {
-            parts.push(format!("mbps_rd_max={}", *n as i64));
+    fn parse_startup_string(startup_string: &str) -> Dynamic {
+        let values = PropertyString::parse(startup_string, "");
+        let number = |key: &str| values.get(key).and_then(|value| value.parse::<f64>().ok());
+        Startup {
+            order: number("order"),
+            up_delay: number("up"),
+            down_delay: number("down"),
         }
-        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_r_concurrent") {
-            parts.push(format!("mbps_rd={}", *n as i64));
+        .to_dynamic()
+    }
+
+    fn validate_startup(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let startup: Startup = match Self::single_block_as(config, "startup") {
+            Some(startup) => startup,
+            None => return,
+        };
+        for (name, _, value) in startup.settings() {
+            if let Some(value) = value {
+                if value.fract() != 0.0 || value < 0.0 {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            "Invalid startup setting",
+                            format!(
+                                "startup {} must be a whole number of 0 or more, got {}",
+                                name, value
+                            ),
+                        )
+                        .with_attribute(AttributePath::new("startup")),
+                    );
+                }
+            }
         }
-        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_wr_burst") {
-            parts.push(format!("mbps_wr_max={}", *n as i64));
+    }
+
+    /// Builds the agent option, e.g. "1,fstrim_cloned_disks=1,type=isa"
+    fn agent_config(config: &DynamicValue) -> Option<String> {
+        let agent: Agent = Self::single_block_as(config, "agent")?;
+        let mut props = PropertyString::new("enabled");
+        props.set("enabled", if agent.enabled() { "1" } else { "0" });
+        if let Some(trim) = agent.trim_cloned_disks {
+            props.set("fstrim_cloned_disks", if trim { "1" } else { "0" });
         }
-        if let Some(Dynamic::Number(n)) = disk_map.get("mbps_wr_concurrent") {
-            parts.push(format!("mbps_wr={}", *n as i64));
+        if let Some(agent_type) = &agent.r#type {
+            props.set("type", agent_type.as_str());
         }
-
-        Ok((slot, parts.join(",")))
+        Some(props.to_string())
     }
 
-    fn cdrom_block_to_api_string(cdrom: &Dynamic) -> Result<(String, String), String> {
-        let cdrom_map = match cdrom {
-            Dynamic::Map(map) => map,
-            _ => return Err("CD-ROM must be a map".to_string()),
+    /// Parses the agent option. Settings Proxmox leaves out are at their
+    /// default, so where the planned block has the same value, explicit or
+    /// left out, it is kept as planned.
+    fn parse_agent_string(agent_string: &str, planned: Option<&Agent>) -> Dynamic {
+        let values = PropertyString::parse(agent_string, "enabled");
+        let flag = |key: &str| values.get(key).and_then(parse_bool);
+        let read = Agent {
+            enabled: flag("enabled"),
+            trim_cloned_disks: flag("fstrim_cloned_disks"),
+            r#type: values.get("type").map(str::to_string),
+        };
+        let planned = match planned {
+            Some(planned) => planned,
+            None => return read.to_dynamic(),
         };
 
-        let slot = cdrom_map
-            .get("slot")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .ok_or("Slot is required")?;
+        Agent {
+            enabled: match read.enabled.unwrap_or(false) {
+                enabled if enabled == planned.enabled() => planned.enabled,
+                enabled => Some(enabled),
+            },
+            trim_cloned_disks: if read.trim_cloned_disks() == planned.trim_cloned_disks() {
+                planned.trim_cloned_disks
+            } else {
+                read.trim_cloned_disks
+            },
+            r#type: if read.agent_type() == planned.agent_type() {
+                planned.r#type.clone()
+            } else {
+                read.r#type
+            },
+        }
+        .to_dynamic()
+    }
 
-        let iso = cdrom_map
-            .get("iso")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .ok_or("ISO is required")?;
+    fn validate_agent(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let agent_type = match Self::single_block_as::<Agent>(config, "agent") {
+            Some(Agent {
+                r#type: Some(agent_type),
+                ..
+            }) => agent_type,
+            _ => return,
+        };
+        if !Agent::TYPES.contains(&agent_type.as_str()) {
+            diagnostics.push(
+                Diagnostic::error(
+                    "Invalid agent type",
+                    format!(
+                        "Agent type must be one of: {}, got '{}'",
+                        Agent::TYPES.join(", "),
+                        agent_type
+                    ),
+                )
+                .with_attribute(AttributePath::new("agent")),
+            );
+        }
+    }
 
-        Ok((slot, format!("{},media=cdrom", iso)))
+    /// The block of a single-item block list, if one is configured
+    fn single_block(config: &DynamicValue, name: &str) -> Option<HashMap<String, Dynamic>> {
+        match config
+            .get_list(&AttributePath::new(name))
+            .ok()?
+            .into_iter()
+            .next()
+        {
+            Some(Dynamic::Map(block)) => Some(block),
+            _ => None,
+        }
     }
 
-    fn cloudinit_drive_block_to_api_string(ci_drive: &Dynamic) -> Result<(String, String), String> {
-        let ci_map = match ci_drive {
-            Dynamic::Map(map) => map,
-            _ => return Err("Cloud-init drive must be a map".to_string()),
-        };
+    /// The block of a single-item block list as its model, if one is
+    /// configured
+    fn single_block_as<T: TfSchema>(config: &DynamicValue, name: &str) -> Option<T> {
+        T::from_dynamic(&Dynamic::Map(Self::single_block(config, name)?)).ok()
+    }
 
-        let slot = ci_map
-            .get("slot")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .ok_or("Slot is required")?;
+    /// Dedicated memory in MB from the memory block
+    fn dedicated_memory(config: &DynamicValue) -> Option<u64> {
+        Self::single_block_as::<Memory>(config, "memory")?
+            .dedicated
+            .map(|dedicated| dedicated as u64)
+    }
 
-        let storage = ci_map
-            .get("storage")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .ok_or("Storage is required")?;
+    /// Memory settings as the API's (memory, balloon, shares, hugepages,
+    /// keephugepages)
+    #[allow(clippy::type_complexity)]
+    fn memory_settings(
+        config: &DynamicValue,
+    ) -> (
+        Option<u64>,
+        Option<u64>,
+        Option<u32>,
+        Option<String>,
+        Option<bool>,
+    ) {
+        let memory: Memory = match Self::single_block_as(config, "memory") {
+            Some(memory) => memory,
+            None => return (None, None, None, None, None),
+        };
+        (
+            memory.dedicated.map(|dedicated| dedicated as u64),
+            memory.floating.map(|floating| floating as u64),
+            memory.shares.map(|shares| shares as u32),
+            memory.hugepages,
+            memory.keep_hugepages,
+        )
+    }
 
-        Ok((slot, format!("{}:cloudinit", storage)))
+    fn parse_memory_settings(vm_config: &crate::api::nodes::QemuConfig) -> Dynamic {
+        Memory {
+            // Proxmox leaves memory out of the config when it is the default
+            dedicated: Some(vm_config.memory.unwrap_or(512) as f64),
+            floating: vm_config.balloon.map(|balloon| balloon as f64),
+            shares: vm_config.shares.map(|shares| shares as f64),
+            hugepages: vm_config.hugepages.clone(),
+            keep_hugepages: vm_config.keephugepages,
+        }
+        .to_dynamic()
     }
 
-    fn serial_block_to_api_string(serial: &Dynamic) -> Result<(u32, String), String> {
-        let serial_map = match serial {
-            Dynamic::Map(map) => map,
-            _ => return Err("Serial must be a map".to_string()),
+    fn validate_memory(&self, config: &DynamicValue, diagnostics: &mut Vec<Diagnostic>) {
+        let memory: Memory = match Self::single_block_as(config, "memory") {
+            Some(memory) => memory,
+            None => return,
         };
 
-        let id = serial_map
-            .get("id")
-            .and_then(|v| match v {
-                Dynamic::Number(n) => Some(*n as u32),
-                _ => None,
-            })
-            .ok_or("ID is required")?;
+        for (name, value) in [
+            ("dedicated", memory.dedicated),
+            ("floating", memory.floating),
+            ("shares", memory.shares),
+        ] {
+            if let Some(value) = value {
+                if value.fract() != 0.0 || value < 0.0 {
+                    diagnostics.push(Diagnostic::error(
+                        "Invalid memory",
+                        format!("memory {} must be a whole number, got {}", name, value),
+                    ));
+                }
+            }
+        }
 
-        let type_str = serial_map
-            .get("type")
-            .and_then(|v| match v {
-                Dynamic::String(s) => Some(s.clone()),
-                _ => None,
-            })
-            .ok_or("Type is required")?;
+        if let Some(dedicated) = memory.dedicated {
+            if !(16.0..=8388608.0).contains(&dedicated) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid memory",
+                    "Memory must be between 16 MB and 8 TB",
+                ));
+            }
+        }
+        if let (Some(floating), Some(dedicated)) = (memory.floating, memory.dedicated) {
+            if floating > dedicated {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid floating memory",
+                    format!(
+                        "floating ({} MB) can't be more than dedicated ({} MB). Set floating = 0 to disable ballooning.",
+                        floating, dedicated
+                    ),
+                ));
+            }
+        }
+        if let Some(shares) = memory.shares {
+            if shares > 50000.0 {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid memory shares",
+                    "shares must be between 0 and 50000",
+                ));
+            }
+            if memory.floating == Some(0.0) {
+                diagnostics.push(Diagnostic::warning(
+                    "Memory shares have no effect",
+                    "shares only apply to automatic ballooning, which floating = 0 disables",
+                ));
+            }
+        }
 
-        Ok((id, type_str))
+        if let Some(hugepages) = &memory.hugepages {
+            if !HUGEPAGE_SIZES.contains(&hugepages.as_str()) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid hugepages",
+                    format!("hugepages must be one of: {}", HUGEPAGE_SIZES.join(", ")),
+                ));
+            }
+        }
+        if memory.keep_hugepages == Some(true) && memory.hugepages.is_none() {
+            diagnostics.push(Diagnostic::error(
+                "Missing hugepages",
+                "keep_hugepages requires hugepages",
+            ));
+        }
+    }
+
+    /// Memory settings that were removed from the memory block. dedicated is
+    /// left alone, Proxmox falls back to its default when the block goes.
+    fn removed_memory_settings(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let (_, prior_balloon, prior_shares, prior_hugepages, prior_keep) =
+            Self::memory_settings(prior);
+        let (_, balloon, shares, hugepages, keep) = Self::memory_settings(config);
+        [
+            ("balloon", prior_balloon.is_some() && balloon.is_none()),
+            ("shares", prior_shares.is_some() && shares.is_none()),
+            (
+                "hugepages",
+                prior_hugepages.is_some() && hugepages.is_none(),
+            ),
+            ("keephugepages", prior_keep.is_some() && keep.is_none()),
+        ]
+        .into_iter()
+        .filter(|(_, removed)| *removed)
+        .map(|(key, _)| key.to_string())
+        .collect()
     }
 
     fn efidisk_block_to_api_string(efidisk: &Dynamic) -> Result<String, String> {
@@ -553,13 +3818,14 @@ impl QemuVmResource {
             .ok_or("Storage is required")?;
 
         // Default size for EFI disk
-        let mut parts = vec![format!("{}:1", storage)];
+        let mut drive = PropertyString::new("file");
+        drive.set("file", format!("{}:1", storage));
 
         if let Some(Dynamic::String(efitype)) = efidisk_map.get("efitype") {
-            parts.push(format!("efitype={}", efitype));
+            drive.set("efitype", efitype.as_str());
         }
 
-        Ok(parts.join(","))
+        Ok(drive.to_string())
     }
 }
 
@@ -585,13 +3851,14 @@ impl Resource for QemuVmResource {
         _request: ResourceSchemaRequest,
     ) -> ResourceSchemaResponse {
         let schema = SchemaBuilder::new()
-            .version(0)
+            .version(5)
             .description("Manages QEMU/KVM virtual machines in Proxmox VE")
             // Core VM Identity
             .attribute(
                 AttributeBuilder::new("vmid", AttributeType::Number)
                     .description("The VM identifier")
                     .required()
+                    .validator(IntegerRangeValidator::between(100, 999999999))
                     .build(),
             )
             .attribute(
@@ -602,13 +3869,14 @@ impl Resource for QemuVmResource {
             )
             .attribute(
                 AttributeBuilder::new("target_node", AttributeType::String)
-                    .description("The name of the Proxmox node where the VM will be created")
-                    .required()
+                    .description("The name of the Proxmox node where the VM will be created. Defaults to the provider's default_node")
+                    .optional()
+                    .computed()
                     .build(),
             )
             .attribute(
-                AttributeBuilder::new("tags", AttributeType::String)
-                    .description("Tags for the VM (separated by semicolon or comma)")
+                AttributeBuilder::new("tags", AttributeType::Set(Box::new(AttributeType::String)))
+                    .description("Tags for the VM")
                     .optional()
                     .build(),
             )
@@ -636,6 +3904,10 @@ impl Resource for QemuVmResource {
                 AttributeBuilder::new("bios", AttributeType::String)
                     .description("BIOS implementation (seabios or ovmf)")
                     .optional()
+                    .validator(StringOneOfValidator::create(vec![
+                        "seabios".to_string(),
+                        "ovmf".to_string(),
+                    ]))
                     .build(),
             )
             .attribute(
@@ -645,8 +3917,14 @@ impl Resource for QemuVmResource {
                     .build(),
             )
             .attribute(
-                AttributeBuilder::new("cpu_type", AttributeType::String)
-                    .description("CPU type")
+                AttributeBuilder::new("kvm", AttributeType::Bool)
+                    .description("Enable KVM hardware virtualization")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nested_virtualization", AttributeType::Bool)
+                    .description("Expose hardware virtualization extensions to the guest. Uses the 'host' CPU type, or adds the vmx/svm flag when a CPU type is set")
                     .optional()
                     .build(),
             )
@@ -654,12 +3932,14 @@ impl Resource for QemuVmResource {
                 AttributeBuilder::new("cores", AttributeType::Number)
                     .description("Number of CPU cores per socket")
                     .optional()
+                    .validator(IntegerRangeValidator::between(1, 128))
                     .build(),
             )
             .attribute(
                 AttributeBuilder::new("sockets", AttributeType::Number)
                     .description("Number of CPU sockets")
                     .optional()
+                    .validator(IntegerRangeValidator::between(1, 4))
                     .build(),
             )
             .attribute(
@@ -668,18 +3948,6 @@ impl Resource for QemuVmResource {
                     .optional()
                     .build(),
             )
-            .attribute(
-                AttributeBuilder::new("memory", AttributeType::Number)
-                    .description("Amount of RAM for the VM in MB")
-                    .optional()
-                    .build(),
-            )
-            .attribute(
-                AttributeBuilder::new("balloon", AttributeType::Number)
-                    .description("Amount of target RAM for the VM in MB")
-                    .optional()
-                    .build(),
-            )
             // Boot Configuration
             .attribute(
                 AttributeBuilder::new("boot", AttributeType::String)
@@ -708,8 +3976,20 @@ impl Resource for QemuVmResource {
             )
             // Guest Agent & OS Settings
             .attribute(
-                AttributeBuilder::new("agent", AttributeType::Number)
-                    .description("Enable/disable the QEMU guest agent")
+                AttributeBuilder::new("hookscript", AttributeType::String)
+                    .description("Hook script run at VM lifecycle events (e.g., local:snippets/hook.sh)")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pool", AttributeType::String)
+                    .description("Resource pool the VM belongs to")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("agent_wait_timeout", AttributeType::Number)
+                    .description("Seconds to wait after create for the guest agent to answer. Only applies when start and agent are set")
                     .optional()
                     .build(),
             )
@@ -764,8 +4044,8 @@ impl Resource for QemuVmResource {
                     .build(),
             )
             .attribute(
-                AttributeBuilder::new("sshkeys", AttributeType::String)
-                    .description("Cloud-init SSH public keys")
+                AttributeBuilder::new("sshkeys", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Cloud-init SSH public keys, one key per entry")
                     .optional()
                     .build(),
             )
@@ -795,6 +4075,18 @@ impl Resource for QemuVmResource {
                     .optional()
                     .build(),
             )
+            .attribute(
+                AttributeBuilder::new("reboot_on_update", AttributeType::Bool)
+                    .description("Reboot the VM when an update leaves changes pending, unless automatic_reboot is false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("pending_changes", AttributeType::Bool)
+                    .description("Whether configuration changes wait for the next reboot")
+                    .computed()
+                    .build(),
+            )
             .attribute(
                 AttributeBuilder::new("clone_wait", AttributeType::Number)
                     .description("Wait time for clone operation")
@@ -863,8 +4155,8 @@ impl Resource for QemuVmResource {
                         AttributeBuilder::new("macaddr", AttributeType::String)
                             .optional()
                             .computed()
-                            .description("MAC address (computed if not provided)")
-                            .default(StaticDefault::create(Dynamic::String("".to_string())))
+                            .description("MAC address. When left out, Proxmox generates one that the interface keeps from then on")
+                            .plan_modifier(UseStateForUnknown::create())
                             .build(),
                         AttributeBuilder::new("rate", AttributeType::Number)
                             .optional()
@@ -910,8 +4202,9 @@ impl Resource for QemuVmResource {
                             .description("Disk type: scsi, virtio, ide, sata")
                             .build(),
                         AttributeBuilder::new("storage", AttributeType::String)
-                            .required()
-                            .description("Storage pool name")
+                            .optional()
+                            .computed()
+                            .description("Storage pool name. Defaults to the provider's default_storage")
                             .build(),
                         AttributeBuilder::new("size", AttributeType::String)
                             .required()
@@ -921,6 +4214,10 @@ impl Resource for QemuVmResource {
                             .optional()
                             .description("Disk format (raw, qcow2, vmdk)")
                             .build(),
+                        AttributeBuilder::new("import_from", AttributeType::String)
+                            .optional()
+                            .description("qcow2, raw or vmdk image to create the disk from, either a storage volume (e.g. local:import/debian.qcow2) or an http(s) URL. URLs are downloaded on the node over SSH and need the provider's ssh block. Only used when the VM is created; size must match the image")
+                            .build(),
                         // Performance Settings
                         AttributeBuilder::new("discard", AttributeType::Bool)
                             .optional()
@@ -999,6 +4296,34 @@ impl Resource for QemuVmResource {
                 min_items: 0,
                 max_items: 256,
             })
+            // Existing Volume Attachment Block
+            .block(NestedBlock {
+                type_name: "attach_existing".to_string(),
+                block: Block {
+                    version: 0,
+                    attributes: vec![
+                        AttributeBuilder::new("volid", AttributeType::String)
+                            .required()
+                            .description("Volume to attach (e.g., local-lvm:vm-100-disk-1)")
+                            .build(),
+                        AttributeBuilder::new("slot", AttributeType::String)
+                            .required()
+                            .description("Drive slot to attach the volume to (e.g., scsi1)")
+                            .build(),
+                        AttributeBuilder::new("format", AttributeType::String)
+                            .optional()
+                            .description("Expected volume format (raw, qcow2, vmdk). Checked against the storage before attaching")
+                            .build(),
+                    ],
+                    block_types: vec![],
+                    description: "Attaches an existing volume, e.g. a restored or imported disk. Removing the block detaches the volume without deleting it".to_string(),
+                    description_kind: tfplug::schema::StringKind::Plain,
+                    deprecated: false,
+                },
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 32,
+            })
             // CD-ROM Configuration Block
             .block(NestedBlock {
                 type_name: "cdrom".to_string(),
@@ -1011,7 +4336,9 @@ impl Resource for QemuVmResource {
                             .build(),
                         AttributeBuilder::new("iso", AttributeType::String)
                             .required()
-                            .description("ISO image path (e.g., local:iso/ubuntu.iso)")
+                            .description(
+                                "ISO image path (e.g., local:iso/ubuntu.iso), or \"none\" for an empty drive",
+                            )
                             .build(),
                     ],
                     block_types: vec![],
@@ -1034,8 +4361,9 @@ impl Resource for QemuVmResource {
                             .description("Cloud-init drive slot (e.g., ide3)")
                             .build(),
                         AttributeBuilder::new("storage", AttributeType::String)
-                            .required()
-                            .description("Storage pool for cloud-init drive")
+                            .optional()
+                            .computed()
+                            .description("Storage pool for cloud-init drive. Defaults to the provider's default_storage")
                             .build(),
                     ],
                     block_types: vec![],
@@ -1055,11 +4383,15 @@ impl Resource for QemuVmResource {
                     attributes: vec![
                         AttributeBuilder::new("id", AttributeType::Number)
                             .required()
-                            .description("Serial port ID")
+                            .description("Serial port ID (0-3)")
                             .build(),
                         AttributeBuilder::new("type", AttributeType::String)
                             .required()
-                            .description("Serial port type (e.g., socket)")
+                            .description("Serial port type: socket for a console reachable through the API, or device to pass through a host serial device")
+                            .build(),
+                        AttributeBuilder::new("device", AttributeType::String)
+                            .optional()
+                            .description("Host device path for type device, e.g. /dev/ttyS0")
                             .build(),
                     ],
                     block_types: vec![],
@@ -1069,7 +4401,50 @@ impl Resource for QemuVmResource {
                 },
                 nesting: NestingMode::List,
                 min_items: 0,
-                max_items: 4,
+                max_items: MAX_SERIAL_PORTS as i64,
+            })
+            // VGA Block
+            .block(NestedBlock {
+                type_name: "vga".to_string(),
+                block: Block {
+                    version: 0,
+                    attributes: vec![
+                        AttributeBuilder::new("type", AttributeType::String)
+                            .required()
+                            .description("Display type, e.g. std, qxl, virtio or none. serial0-serial3 use that serial port as display, which many cloud images expect")
+                            .build(),
+                        AttributeBuilder::new("memory", AttributeType::Number)
+                            .optional()
+                            .description("Video memory in MiB (4-512)")
+                            .build(),
+                    ],
+                    block_types: vec![],
+                    description: "Display configuration".to_string(),
+                    description_kind: tfplug::schema::StringKind::Plain,
+                    deprecated: false,
+                },
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 1,
+            })
+            // CPU Block
+            .block(Option::<Cpu>::nested_block("cpu", ""))
+            // SMBIOS Block
+            .block(Option::<Smbios>::nested_block("smbios", ""))
+            // Startup Block
+            .block(Option::<Startup>::nested_block("startup", ""))
+            // Guest Agent Block
+            .block(Option::<Agent>::nested_block("agent", ""))
+            // Cloud-Init Block
+            .block(Option::<CloudInit>::nested_block("cloudinit", ""))
+            // Restore Block
+            .block(Option::<RestoreFrom>::nested_block("restore_from", ""))
+            // Memory Block
+            .block(Option::<Memory>::nested_block("memory", ""))
+            // NUMA Node Block
+            .block(NestedBlock {
+                max_items: MAX_NUMA_NODES as i64,
+                ..Vec::<NumaNode>::nested_block("numa_node", "")
             })
             // EFI Disk Block
             .block(NestedBlock {
@@ -1083,8 +4458,9 @@ impl Resource for QemuVmResource {
                             .default(StaticDefault::string("4m"))
                             .build(),
                         AttributeBuilder::new("storage", AttributeType::String)
-                            .required()
-                            .description("Storage pool name")
+                            .optional()
+                            .computed()
+                            .description("Storage pool name. Defaults to the provider's default_storage")
                             .build(),
                         AttributeBuilder::new("format", AttributeType::String)
                             .optional()
@@ -1106,6 +4482,7 @@ impl Resource for QemuVmResource {
                 min_items: 0,
                 max_items: 1,
             })
+            .block(Timeouts::block(&[Operation::Create, Operation::Update]))
             .build();
 
         ResourceSchemaResponse {
@@ -1121,51 +4498,9 @@ impl Resource for QemuVmResource {
     ) -> ValidateResourceConfigResponse {
         let mut diagnostics = vec![];
 
-        if let Ok(vmid) = request.config.get_number(&AttributePath::new("vmid")) {
-            let vmid_int = vmid as u32;
-            if !(100..=999999999).contains(&vmid_int) {
-                diagnostics.push(Diagnostic::error(
-                    "Invalid VMID",
-                    "VMID must be between 100 and 999999999",
-                ));
-            }
-        }
-
-        if let Ok(cores) = request.config.get_number(&AttributePath::new("cores")) {
-            if !(1.0..=128.0).contains(&cores) {
-                diagnostics.push(Diagnostic::error(
-                    "Invalid cores",
-                    "Cores must be between 1 and 128",
-                ));
-            }
-        }
-
-        if let Ok(sockets) = request.config.get_number(&AttributePath::new("sockets")) {
-            if !(1.0..=4.0).contains(&sockets) {
-                diagnostics.push(Diagnostic::error(
-                    "Invalid sockets",
-                    "Sockets must be between 1 and 4",
-                ));
-            }
-        }
-
-        if let Ok(memory) = request.config.get_number(&AttributePath::new("memory")) {
-            if !(16.0..=8388608.0).contains(&memory) {
-                diagnostics.push(Diagnostic::error(
-                    "Invalid memory",
-                    "Memory must be between 16 MB and 8 TB",
-                ));
-            }
-        }
+        diagnostics.extend(validate_integers(&request.config, INTEGER_ATTRIBUTES));
 
         if let Ok(bios) = request.config.get_string(&AttributePath::new("bios")) {
-            if !["seabios", "ovmf"].contains(&bios.as_str()) {
-                diagnostics.push(Diagnostic::error(
-                    "Invalid BIOS",
-                    "BIOS must be either 'seabios' or 'ovmf'",
-                ));
-            }
-
             // Validate OVMF requires efidisk
             if bios == "ovmf" {
                 // Check for efidisk0 string attribute
@@ -1193,17 +4528,37 @@ impl Resource for QemuVmResource {
         // Validate iothread usage
         self.validate_iothread(&request.config, &mut diagnostics);
 
+        self.validate_nested_virtualization(&request.config, &mut diagnostics);
+
+        self.validate_attach_existing(&request.config, &mut diagnostics);
+
+        self.validate_console(&request.config, &mut diagnostics);
+        self.validate_tags(&request.config, &mut diagnostics);
+        self.validate_cdrom(&request.config, &mut diagnostics);
+
+        self.validate_network(&request.config, &mut diagnostics);
+        self.validate_cloudinit(&request.config, &mut diagnostics);
+        self.validate_agent_wait(&request.config, &mut diagnostics);
+        self.validate_cpu(&request.config, &mut diagnostics);
+        self.validate_smbios(&request.config, &mut diagnostics);
+        self.validate_startup(&request.config, &mut diagnostics);
+        self.validate_agent(&request.config, &mut diagnostics);
+        self.validate_memory(&request.config, &mut diagnostics);
+        self.validate_numa(&request.config, &mut diagnostics);
+
         ValidateResourceConfigResponse { diagnostics }
     }
 
     async fn create(
         &self,
-        _ctx: Context,
-        request: CreateResourceRequest,
+        ctx: Context,
+        mut request: CreateResourceRequest,
     ) -> CreateResourceResponse {
         let mut diagnostics = vec![];
+        Self::fill_from_plan(&mut request.config, &request.planned_state);
 
-        let provider_data = match &self.provider_data {
+        let provider_data = self.provider_data_for(request.provider_meta.as_ref());
+        let provider_data = match &provider_data {
             Some(data) => data,
             None => {
                 diagnostics.push(Diagnostic::error(
@@ -1218,8 +4573,69 @@ impl Resource for QemuVmResource {
             }
         };
 
-        match self.extract_vm_config(&request.config) {
-            Ok((node, _vmid, create_request)) => {
+        match self.extract_vm_config(&request.config) {
+            Ok((node, _vmid, mut create_request)) => {
+                if request
+                    .config
+                    .get_bool(&AttributePath::new("nested_virtualization"))
+                    .unwrap_or(false)
+                {
+                    Self::apply_nested_virtualization(
+                        &provider_data.client,
+                        &node,
+                        &mut create_request.settings.cpu,
+                        &mut diagnostics,
+                    )
+                    .await;
+                }
+
+                let url_imports = Self::url_disk_imports(&request.config);
+                let ssh = if url_imports.is_empty() {
+                    None
+                } else {
+                    match provider_data.require_ssh("Importing a disk from a URL") {
+                        Ok(ssh) => Some(ssh),
+                        Err(diag) => {
+                            diagnostics.push(diag);
+                            let mut failed_state = request.planned_state.clone();
+                            Self::populate_all_attributes(
+                                &mut failed_state,
+                                &request.planned_state,
+                            );
+
+                            return CreateResourceResponse {
+                                new_state: failed_state,
+                                private: vec![],
+                                diagnostics,
+                            };
+                        }
+                    }
+                };
+                for (slot, _, _) in &url_imports {
+                    create_request.settings.take_drive(slot);
+                }
+
+                match Self::verify_attach_existing(&provider_data.client, &node, &request.config)
+                    .await
+                {
+                    Ok(attached) => {
+                        for (slot, volid) in attached {
+                            create_request.settings.set_drive(&slot, volid);
+                        }
+                    }
+                    Err(diag) => {
+                        diagnostics.push(diag);
+                        let mut failed_state = request.planned_state.clone();
+                        Self::populate_all_attributes(&mut failed_state, &request.planned_state);
+
+                        return CreateResourceResponse {
+                            new_state: failed_state,
+                            private: vec![],
+                            diagnostics,
+                        };
+                    }
+                }
+
                 match provider_data
                     .client
                     .nodes()
@@ -1228,34 +4644,113 @@ impl Resource for QemuVmResource {
                     .create(create_request.vmid, &create_request)
                     .await
                 {
-                    Ok(_task_id) => {
+                    Ok(task_id) => {
+                        // The VM only exists once the create task has finished
+                        if let Err(e) = provider_data
+                            .client
+                            .nodes()
+                            .node(&node)
+                            .wait_for_task(
+                                &task_id,
+                                Timeouts::new(&request.config).create(&ctx, CREATE_TIMEOUT),
+                                ctx.cancelled(),
+                            )
+                            .await
+                        {
+                            diagnostics.extend(api_error_diagnostics(
+                                "Failed to create VM",
+                                &e,
+                                API_ATTRIBUTES,
+                            ));
+                            let mut failed_state = request.planned_state.clone();
+                            Self::populate_all_attributes(
+                                &mut failed_state,
+                                &request.planned_state,
+                            );
+
+                            return CreateResourceResponse {
+                                new_state: failed_state,
+                                private: vec![],
+                                diagnostics,
+                            };
+                        }
+                        // Listings cached before the VM existed don't have it
+                        provider_data.invalidate_cluster_vms().await;
+
+                        if let Some(ssh) = ssh {
+                            for (slot, url, disk_string) in &url_imports {
+                                let script = Self::url_import_script(
+                                    create_request.vmid,
+                                    slot,
+                                    url,
+                                    disk_string,
+                                );
+                                if let Err(e) = ssh.execute(&script).await {
+                                    diagnostics.push(Diagnostic::error(
+                                        "Failed to import disk",
+                                        format!("Importing {} into {}: {}", url, slot, e),
+                                    ));
+                                }
+                            }
+                        }
+
                         // Wait for VM creation to complete if additional_wait is specified
                         if let Ok(wait_time) = request
                             .config
-                            .get_number(&AttributePath::new("additional_wait"))
+                            .get_u64(&AttributePath::new("additional_wait"))
                         {
-                            if wait_time > 0.0 {
-                                tokio::time::sleep(tokio::time::Duration::from_secs(
-                                    wait_time as u64,
-                                ))
-                                .await;
+                            if wait_time > 0 {
+                                tokio::select! {
+                                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(wait_time)) => {}
+                                    _ = ctx.cancelled() => {}
+                                }
                             }
                         }
 
-                        // For now, just return the planned state
-                        // TODO: Fix the issue where reading the VM config returns different values than what we sent
-                        // This is a temporary workaround - we should properly wait for the task to complete
-                        // and then read the actual VM configuration from the API
-                        CreateResourceResponse {
-                            new_state: request.planned_state.clone(),
+                        if let Some(timeout) = Self::agent_wait_timeout(&request.config) {
+                            if let Err(diag) = Self::wait_for_agent(
+                                &ctx,
+                                &provider_data.client,
+                                &node,
+                                create_request.vmid,
+                                timeout,
+                            )
+                            .await
+                            {
+                                diagnostics.push(diag);
+                            }
+                        }
+
+                        let mut private = PrivateStateData::new();
+                        if let Some(template) = &create_request.clone {
+                            let lineage = CloneLineage {
+                                template: template.clone(),
+                                full_clone: create_request.full.unwrap_or(false),
+                            };
+                            let _ = private.set(PRIVATE_CLONE_LINEAGE, &lineage);
+                        }
+                        let new_state = Self::created_state(
+                            &provider_data.client,
+                            &node,
+                            create_request.vmid,
+                            &request.planned_state,
+                            &mut private,
+                            &mut diagnostics,
+                        )
+                        .await;
+                        let mut response = CreateResourceResponse {
+                            new_state,
                             private: vec![],
                             diagnostics,
-                        }
+                        };
+                        response.set_private_state(&private);
+                        response
                     }
                     Err(e) => {
-                        diagnostics.push(Diagnostic::error(
+                        diagnostics.extend(api_error_diagnostics(
                             "Failed to create VM",
-                            format!("API error: {}", e),
+                            &e,
+                            API_ATTRIBUTES,
                         ));
                         // Return planned state with all attributes populated to avoid "missing attribute" errors
                         let mut failed_state = request.planned_state.clone();
@@ -1305,11 +4800,8 @@ impl Resource for QemuVmResource {
             }
         };
 
-        let vmid = match request
-            .current_state
-            .get_number(&AttributePath::new("vmid"))
-        {
-            Ok(vmid) => vmid as u32,
+        let vmid = match request.current_state.get_u32(&AttributePath::new("vmid")) {
+            Ok(vmid) => vmid,
             Err(_) => {
                 return ReadResourceResponse {
                     new_state: None,
@@ -1321,7 +4813,8 @@ impl Resource for QemuVmResource {
             }
         };
 
-        let provider_data = match &self.provider_data {
+        let provider_data = self.provider_data_for(request.provider_meta.as_ref());
+        let provider_data = match &provider_data {
             Some(data) => data,
             None => {
                 diagnostics.push(Diagnostic::error(
@@ -1338,6 +4831,18 @@ impl Resource for QemuVmResource {
             }
         };
 
+        // The cluster listing is shared by every VM refreshing in this run,
+        // so VMs that are gone drop out without a request each
+        if Self::cluster_has_vm(provider_data, vmid).await == Some(false) {
+            return ReadResourceResponse {
+                new_state: None,
+                diagnostics,
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            };
+        }
+
         match provider_data
             .client
             .nodes()
@@ -1378,34 +4883,50 @@ impl Resource for QemuVmResource {
                     );
                 }
 
-                ReadResourceResponse {
-                    new_state: Some(new_state),
-                    diagnostics,
-                    private: request.private,
-                    deferred: None,
-                    new_identity: None,
+                Self::refresh_attach_existing(&mut new_state, &vm_config, &request.current_state);
+                if let Some(pool) = Self::current_pool(provider_data, vmid).await {
+                    match pool {
+                        Some(pool) => {
+                            let _ = new_state.set_string(&AttributePath::new("pool"), pool);
+                        }
+                        None => {
+                            let _ = new_state.set_null(&AttributePath::new("pool"));
+                        }
+                    }
                 }
-            }
-            Err(crate::api::ApiError::ApiError {
-                status, message, ..
-            }) if status == 404
-                || message.contains("does not exist")
-                || message.contains("not found") =>
-            {
-                ReadResourceResponse {
-                    new_state: None,
+                match Self::pending_keys(&provider_data.client, &node, vmid).await {
+                    Ok(pending) => {
+                        let _ = new_state
+                            .set_bool(&AttributePath::new("pending_changes"), !pending.is_empty());
+                    }
+                    Err(_) => Self::fill_pending_changes(&mut new_state, false),
+                }
+
+                let mut private = request.private_state().unwrap_or_default();
+                Self::record_config_digest(&mut private, &vm_config);
+                let mut response = ReadResourceResponse {
+                    new_state: Some(new_state),
                     diagnostics,
-                    private: request.private,
+                    private: vec![],
                     deferred: None,
                     new_identity: None,
-                }
+                };
+                response.set_private_state(&private);
+                response
             }
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics,
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
             Err(crate::api::ApiError::ServiceUnavailable) => {
                 // When a VM doesn't exist, Proxmox might return ServiceUnavailable
                 // We should check if the VM actually exists by listing VMs
-                match provider_data.client.nodes().node(&node).qemu().list().await {
-                    Ok(vms) => {
-                        if vms.iter().any(|vm| vm.vmid == vmid) {
+                match Self::cluster_has_vm(provider_data, vmid).await {
+                    Some(exists) => {
+                        if exists {
                             // VM exists but service is temporarily unavailable
                             diagnostics.push(Diagnostic::error(
                                 "Failed to read VM",
@@ -1429,7 +4950,7 @@ impl Resource for QemuVmResource {
                             }
                         }
                     }
-                    Err(_) => {
+                    None => {
                         // Can't determine if VM exists, keep current state and report error
                         diagnostics.push(Diagnostic::error(
                             "Failed to read VM",
@@ -1463,12 +4984,14 @@ impl Resource for QemuVmResource {
 
     async fn update(
         &self,
-        _ctx: Context,
-        request: UpdateResourceRequest,
+        ctx: Context,
+        mut request: UpdateResourceRequest,
     ) -> UpdateResourceResponse {
         let mut diagnostics = vec![];
+        Self::fill_from_plan(&mut request.config, &request.planned_state);
 
-        let provider_data = match &self.provider_data {
+        let provider_data = self.provider_data_for(request.provider_meta.as_ref());
+        let provider_data = match &provider_data {
             Some(data) => data,
             None => {
                 diagnostics.push(Diagnostic::error(
@@ -1477,13 +5000,31 @@ impl Resource for QemuVmResource {
                 ));
                 return UpdateResourceResponse {
                     new_state: request.planned_state,
-                    private: vec![],
+                    private: request.planned_private.clone(),
                     diagnostics,
                     new_identity: None,
                 };
             }
         };
 
+        for slot in Self::changed_disk_imports(&request.prior_state, &request.planned_state) {
+            diagnostics.push(Diagnostic::error(
+                "Cannot change disk import",
+                format!(
+                    "import_from of disk {} only applies when the VM is created. Replace the VM to import a different image.",
+                    slot
+                ),
+            ));
+        }
+        if !diagnostics.is_empty() {
+            return UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: request.planned_private.clone(),
+                diagnostics,
+                new_identity: None,
+            };
+        }
+
         let node = match request
             .config
             .get_string(&AttributePath::new("target_node"))
@@ -1493,20 +5034,20 @@ impl Resource for QemuVmResource {
                 diagnostics.push(Diagnostic::error("Missing node", diag.to_string()));
                 return UpdateResourceResponse {
                     new_state: request.prior_state,
-                    private: vec![],
+                    private: request.planned_private.clone(),
                     diagnostics,
                     new_identity: None,
                 };
             }
         };
 
-        let vmid = match request.config.get_number(&AttributePath::new("vmid")) {
-            Ok(vmid) => vmid as u32,
+        let vmid = match request.config.get_u32(&AttributePath::new("vmid")) {
+            Ok(vmid) => vmid,
             Err(diag) => {
                 diagnostics.push(Diagnostic::error("Missing vmid", diag.to_string()));
                 return UpdateResourceResponse {
                     new_state: request.prior_state,
-                    private: vec![],
+                    private: request.planned_private.clone(),
                     diagnostics,
                     new_identity: None,
                 };
@@ -1514,7 +5055,107 @@ impl Resource for QemuVmResource {
         };
 
         match self.build_update_request(&request.config) {
-            Ok(update_request) => {
+            Ok(mut update_request) => {
+                update_request.settings.smbios1 =
+                    Self::smbios_config(&request.config, Self::smbios_uuid(&request.prior_state));
+                let mut private = request.private_state().unwrap_or_default();
+                update_request.digest = private.get(PRIVATE_CONFIG_DIGEST).unwrap_or_default();
+                if request
+                    .config
+                    .get_bool(&AttributePath::new("nested_virtualization"))
+                    .unwrap_or(false)
+                {
+                    Self::apply_nested_virtualization(
+                        &provider_data.client,
+                        &node,
+                        &mut update_request.settings.cpu,
+                        &mut diagnostics,
+                    )
+                    .await;
+                }
+
+                match Self::verify_attach_existing(&provider_data.client, &node, &request.config)
+                    .await
+                {
+                    Ok(attached) => {
+                        for (slot, volid) in attached {
+                            update_request.settings.set_drive(&slot, volid);
+                        }
+                    }
+                    Err(diag) => {
+                        diagnostics.push(diag);
+                        return UpdateResourceResponse {
+                            new_state: request.prior_state,
+                            private: request.planned_private.clone(),
+                            diagnostics,
+                            new_identity: None,
+                        };
+                    }
+                }
+
+                let mut detached = Self::detached_slots(&request.prior_state, &request.config);
+                detached.extend(Self::removed_scalar_options(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                detached.extend(Self::removed_cdrom_slots(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                detached.extend(Self::removed_console_devices(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                if Self::tags_config(&request.prior_state).is_some()
+                    && Self::tags_config(&request.config).is_none()
+                {
+                    detached.push("tags".to_string());
+                }
+                if Self::startup_config(&request.prior_state).is_some()
+                    && Self::startup_config(&request.config).is_none()
+                {
+                    detached.push("startup".to_string());
+                }
+                if Self::agent_config(&request.prior_state).is_some()
+                    && Self::agent_config(&request.config).is_none()
+                {
+                    detached.push("agent".to_string());
+                }
+                if Self::sshkeys_config(&request.prior_state).is_some()
+                    && Self::sshkeys_config(&request.config).is_none()
+                {
+                    detached.push("sshkeys".to_string());
+                }
+                detached.extend(Self::removed_cpu_settings(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                detached.extend(Self::removed_memory_settings(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                detached.extend(Self::removed_numa_nodes(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                detached.extend(Self::removed_ipconfigs(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                detached.extend(Self::removed_cloudinit_dns(
+                    &request.prior_state,
+                    &request.config,
+                ));
+                if !detached.is_empty() {
+                    let mut delete: Vec<String> = update_request
+                        .delete
+                        .take()
+                        .map(|d| d.split(',').map(str::to_string).collect())
+                        .unwrap_or_default();
+                    delete.extend(detached);
+                    update_request.delete = Some(delete.join(","));
+                }
+
                 match provider_data
                     .client
                     .nodes()
@@ -1523,20 +5164,56 @@ impl Resource for QemuVmResource {
                     .update_config(vmid, &update_request)
                     .await
                 {
-                    Ok(_) => UpdateResourceResponse {
-                        new_state: request.planned_state,
-                        private: vec![],
-                        diagnostics,
-                        new_identity: None,
-                    },
+                    Ok(_) => {
+                        let mut new_state = request.planned_state;
+                        if let Err(diag) = Self::reconcile_pool(
+                            &provider_data.client,
+                            vmid,
+                            Self::pool(&request.prior_state),
+                            Self::pool(&request.config),
+                        )
+                        .await
+                        {
+                            diagnostics.push(diag);
+                            return UpdateResourceResponse {
+                                new_state: request.prior_state,
+                                private: request.planned_private.clone(),
+                                diagnostics,
+                                new_identity: None,
+                            };
+                        }
+                        // Cached listings still show the old name and pool
+                        provider_data.invalidate_cluster_vms().await;
+                        Self::apply_pending_changes(
+                            &ctx,
+                            &provider_data.client,
+                            &node,
+                            vmid,
+                            &request.config,
+                            &mut new_state,
+                            &mut diagnostics,
+                        )
+                        .await;
+                        // The update changed the digest, the next read records the new one
+                        private.remove_key(PRIVATE_CONFIG_DIGEST);
+                        let mut response = UpdateResourceResponse {
+                            new_state,
+                            private: vec![],
+                            diagnostics,
+                            new_identity: None,
+                        };
+                        response.set_private_state(&private);
+                        response
+                    }
                     Err(e) => {
-                        diagnostics.push(Diagnostic::error(
+                        diagnostics.extend(api_error_diagnostics(
                             "Failed to update VM",
-                            format!("API error: {}", e),
+                            &e,
+                            API_ATTRIBUTES,
                         ));
                         UpdateResourceResponse {
                             new_state: request.prior_state,
-                            private: vec![],
+                            private: request.planned_private.clone(),
                             diagnostics,
                             new_identity: None,
                         }
@@ -1547,7 +5224,7 @@ impl Resource for QemuVmResource {
                 diagnostics.push(diag);
                 UpdateResourceResponse {
                     new_state: request.prior_state,
-                    private: vec![],
+                    private: request.planned_private.clone(),
                     diagnostics,
                     new_identity: None,
                 }
@@ -1562,7 +5239,8 @@ impl Resource for QemuVmResource {
     ) -> DeleteResourceResponse {
         let mut diagnostics = vec![];
 
-        let provider_data = match &self.provider_data {
+        let provider_data = self.provider_data_for(request.provider_meta.as_ref());
+        let provider_data = match &provider_data {
             Some(data) => data,
             None => {
                 return DeleteResourceResponse { diagnostics };
@@ -1579,8 +5257,8 @@ impl Resource for QemuVmResource {
             }
         };
 
-        let vmid = match request.prior_state.get_number(&AttributePath::new("vmid")) {
-            Ok(vmid) => vmid as u32,
+        let vmid = match request.prior_state.get_u32(&AttributePath::new("vmid")) {
+            Ok(vmid) => vmid,
             Err(_) => {
                 return DeleteResourceResponse { diagnostics };
             }
@@ -1621,7 +5299,10 @@ impl Resource for QemuVmResource {
 
         // Now attempt to delete the VM
         match qemu_api.delete(vmid, false).await {
-            Ok(_) => DeleteResourceResponse { diagnostics },
+            Ok(_) => {
+                provider_data.invalidate_cluster_vms().await;
+                DeleteResourceResponse { diagnostics }
+            }
             Err(e) => {
                 diagnostics.push(Diagnostic::error(
                     "Failed to delete VM",
@@ -1637,269 +5318,139 @@ impl QemuVmResource {
     fn populate_all_attributes(state: &mut DynamicValue, planned_state: &DynamicValue) {
         // This method ensures ALL schema attributes are present in the state
         // Used when creation fails to avoid "missing attribute" errors
-
-        // Required attributes - preserve from planned state
-        if let Ok(node) = planned_state.get_string(&AttributePath::new("target_node")) {
-            let _ = state.set_string(&AttributePath::new("target_node"), node);
-        }
-        if let Ok(vmid) = planned_state.get_number(&AttributePath::new("vmid")) {
-            let _ = state.set_number(&AttributePath::new("vmid"), vmid);
-        }
-        if let Ok(name) = planned_state.get_string(&AttributePath::new("name")) {
-            let _ = state.set_string(&AttributePath::new("name"), name);
-        }
-
-        // Set all optional attributes to their default values
-        // Clone/Template Settings
-        let _ = state.set_string(&AttributePath::new("clone"), String::new());
-        let _ = state.set_bool(&AttributePath::new("full_clone"), false);
-        let _ = state.set_string(&AttributePath::new("os_type"), String::new());
-
-        // Hardware Configuration
-        let _ = state.set_string(&AttributePath::new("bios"), "seabios".to_string());
-        let _ = state.set_string(&AttributePath::new("machine"), String::new());
-        let _ = state.set_string(&AttributePath::new("cpu_type"), String::new());
-        let _ = state.set_number(&AttributePath::new("cores"), 1.0);
-        let _ = state.set_number(&AttributePath::new("sockets"), 1.0);
-        let _ = state.set_number(&AttributePath::new("vcpus"), 0.0);
-        let _ = state.set_number(&AttributePath::new("memory"), 512.0);
-        let _ = state.set_number(&AttributePath::new("balloon"), 0.0);
-
-        // Boot Configuration
-        let _ = state.set_string(&AttributePath::new("boot"), String::new());
-        let _ = state.set_string(&AttributePath::new("bootdisk"), String::new());
-        let _ = state.set_bool(&AttributePath::new("onboot"), false);
-
-        // Storage Configuration
-        let _ = state.set_string(&AttributePath::new("scsihw"), "lsi".to_string());
-
-        // Guest Agent & OS Settings
-        let _ = state.set_number(&AttributePath::new("agent"), 0.0);
-        let _ = state.set_string(&AttributePath::new("qemu_os"), String::new());
-
-        // Cloud-Init Configuration
-        let _ = state.set_string(&AttributePath::new("ipconfig0"), String::new());
-        let _ = state.set_string(&AttributePath::new("ipconfig1"), String::new());
-        let _ = state.set_string(&AttributePath::new("ipconfig2"), String::new());
-        let _ = state.set_string(&AttributePath::new("ipconfig3"), String::new());
-        let _ = state.set_string(&AttributePath::new("ciuser"), String::new());
-        let _ = state.set_string(&AttributePath::new("cipassword"), String::new());
-        let _ = state.set_bool(&AttributePath::new("ciupgrade"), false);
-        let _ = state.set_string(&AttributePath::new("sshkeys"), String::new());
-
-        // Network Settings
-        let _ = state.set_bool(&AttributePath::new("skip_ipv4"), false);
-        let _ = state.set_bool(&AttributePath::new("skip_ipv6"), false);
-
-        // Timing & Behavior Settings
-        let _ = state.set_number(&AttributePath::new("additional_wait"), 0.0);
-        let _ = state.set_bool(&AttributePath::new("automatic_reboot"), true);
-        let _ = state.set_number(&AttributePath::new("clone_wait"), 0.0);
-        let _ = state.set_bool(&AttributePath::new("define_connection_info"), false);
-
-        // Other attributes
-        let _ = state.set_string(&AttributePath::new("description"), String::new());
-        let _ = state.set_bool(&AttributePath::new("start"), false);
-        let _ = state.set_bool(&AttributePath::new("tablet"), true);
-        let _ = state.set_bool(&AttributePath::new("protection"), false);
-        let _ = state.set_string(&AttributePath::new("tags"), String::new());
-
+        let text = |value: &str| Dynamic::String(value.to_string());
+        let defaults = [
+            // Clone/Template Settings
+            ("clone", text("")),
+            ("full_clone", Dynamic::Bool(false)),
+            ("os_type", text("")),
+            // Hardware Configuration
+            ("bios", text("seabios")),
+            ("machine", text("")),
+            ("kvm", Dynamic::Bool(true)),
+            ("nested_virtualization", Dynamic::Bool(false)),
+            ("cores", Dynamic::Number(1.0)),
+            ("sockets", Dynamic::Number(1.0)),
+            ("vcpus", Dynamic::Number(0.0)),
+            // Boot Configuration
+            ("boot", text("")),
+            ("bootdisk", text("")),
+            ("onboot", Dynamic::Bool(false)),
+            // Storage Configuration
+            ("scsihw", text("lsi")),
+            // Guest Agent & OS Settings
+            ("agent_wait_timeout", Dynamic::Number(0.0)),
+            ("qemu_os", text("")),
+            // Cloud-Init Configuration
+            ("ipconfig0", text("")),
+            ("ipconfig1", text("")),
+            ("ipconfig2", text("")),
+            ("ipconfig3", text("")),
+            ("ciuser", text("")),
+            ("cipassword", text("")),
+            ("ciupgrade", Dynamic::Bool(false)),
+            ("sshkeys", Dynamic::List(vec![])),
+            // Network Settings
+            ("skip_ipv4", Dynamic::Bool(false)),
+            ("skip_ipv6", Dynamic::Bool(false)),
+            // Timing & Behavior Settings
+            ("additional_wait", Dynamic::Number(0.0)),
+            ("automatic_reboot", Dynamic::Bool(true)),
+            ("reboot_on_update", Dynamic::Bool(false)),
+            ("pending_changes", Dynamic::Bool(false)),
+            ("clone_wait", Dynamic::Number(0.0)),
+            ("define_connection_info", Dynamic::Bool(false)),
+            // Other attributes
+            ("description", text("")),
+            ("start", Dynamic::Bool(false)),
+            ("tablet", Dynamic::Bool(true)),
+            ("protection", Dynamic::Bool(false)),
+            ("tags", Dynamic::List(vec![])),
+        ];
         // Nested blocks - empty lists with proper structure
-        let _ = state.set_list(&AttributePath::new("network"), Vec::new());
-        let _ = state.set_list(&AttributePath::new("disk"), Vec::new());
-        let _ = state.set_list(&AttributePath::new("cdrom"), Vec::new());
-        let _ = state.set_list(&AttributePath::new("cloudinit_drive"), Vec::new());
-        let _ = state.set_list(&AttributePath::new("serial"), Vec::new());
-        let _ = state.set_list(&AttributePath::new("efidisk"), Vec::new());
+        let blocks = BLOCK_LISTS
+            .iter()
+            .map(|name| (*name, Dynamic::List(vec![])));
+        for (name, default) in defaults.into_iter().chain(blocks) {
+            Self::set_attribute(state, name, default);
+        }
 
         // Now override with any values from planned state
-        if let Ok(tags) = planned_state.get_string(&AttributePath::new("tags")) {
-            let _ = state.set_string(&AttributePath::new("tags"), tags);
-        }
-        if let Ok(cores) = planned_state.get_number(&AttributePath::new("cores")) {
-            let _ = state.set_number(&AttributePath::new("cores"), cores);
-        }
-        if let Ok(memory) = planned_state.get_number(&AttributePath::new("memory")) {
-            let _ = state.set_number(&AttributePath::new("memory"), memory);
-        }
-        if let Ok(start) = planned_state.get_bool(&AttributePath::new("start")) {
-            let _ = state.set_bool(&AttributePath::new("start"), start);
-        }
-        // Copy all block values from planned state
-        if let Ok(network) = planned_state.get_list(&AttributePath::new("network")) {
-            let _ = state.set_list(&AttributePath::new("network"), network);
-        }
-        if let Ok(disk) = planned_state.get_list(&AttributePath::new("disk")) {
-            let _ = state.set_list(&AttributePath::new("disk"), disk);
-        }
-        if let Ok(cdrom) = planned_state.get_list(&AttributePath::new("cdrom")) {
-            let _ = state.set_list(&AttributePath::new("cdrom"), cdrom);
-        }
-        if let Ok(cloudinit_drive) = planned_state.get_list(&AttributePath::new("cloudinit_drive"))
-        {
-            let _ = state.set_list(&AttributePath::new("cloudinit_drive"), cloudinit_drive);
-        }
-        if let Ok(serial) = planned_state.get_list(&AttributePath::new("serial")) {
-            let _ = state.set_list(&AttributePath::new("serial"), serial);
-        }
-        if let Ok(efidisk) = planned_state.get_list(&AttributePath::new("efidisk")) {
-            let _ = state.set_list(&AttributePath::new("efidisk"), efidisk);
+        for name in PLANNED_ATTRIBUTES.iter().chain(BLOCK_LISTS) {
+            if let Ok(value) = planned_state.get(&AttributePath::new(name)) {
+                Self::set_attribute(state, name, value.clone());
+            }
         }
     }
 
-    fn populate_state_from_config(
+    /// Sets a top-level attribute. Null and unknown values are skipped.
+    fn set_attribute(state: &mut DynamicValue, name: &str, value: Dynamic) {
+        let path = AttributePath::new(name);
+        let _ = match value {
+            Dynamic::String(value) => state.set_string(&path, value),
+            Dynamic::Number(value) => state.set_number(&path, value),
+            Dynamic::Bool(value) => state.set_bool(&path, value),
+            Dynamic::List(value) => state.set_list(&path, value),
+            _ => return,
+        };
+    }
+
+    /// Scalar attributes read back from the VM config: the configured value
+    /// and the value Proxmox assumes when the option is unset. Null means the
+    /// option is simply absent.
+    fn config_scalars(
+        vm_config: &crate::api::nodes::QemuConfig,
+    ) -> Vec<(&'static str, Option<Dynamic>, Dynamic)> {
+        let string = |value: &Option<String>| value.clone().map(Dynamic::String);
+        let number = |value: Option<u32>| value.map(|value| Dynamic::Number(value as f64));
+        let bool = |value: Option<bool>| value.map(Dynamic::Bool);
+        let text = |value: &str| Dynamic::String(value.to_string());
+
+        vec![
+            ("cores", number(vm_config.cores), Dynamic::Number(1.0)),
+            ("sockets", number(vm_config.sockets), Dynamic::Number(1.0)),
+            ("vcpus", number(vm_config.vcpus), Dynamic::Null),
+            ("bios", string(&vm_config.bios), text("seabios")),
+            ("machine", string(&vm_config.machine), Dynamic::Null),
+            ("boot", string(&vm_config.boot), text("")),
+            ("bootdisk", string(&vm_config.bootdisk), Dynamic::Null),
+            ("scsihw", string(&vm_config.scsihw), text("lsi")),
+            ("qemu_os", string(&vm_config.ostype), text("other")),
+            ("kvm", bool(vm_config.kvm), Dynamic::Bool(true)),
+            ("onboot", bool(vm_config.onboot), Dynamic::Bool(false)),
+            ("tablet", bool(vm_config.tablet), Dynamic::Bool(true)),
+            (
+                "protection",
+                bool(vm_config.protection),
+                Dynamic::Bool(false),
+            ),
+            ("hookscript", string(&vm_config.hookscript), Dynamic::Null),
+            ("description", string(&vm_config.description), text("")),
+        ]
+    }
+
+    /// Options that are deleted when their attribute is removed, since
+    /// Proxmox has no value that means unset
+    fn removed_scalar_options(prior: &DynamicValue, config: &DynamicValue) -> Vec<String> {
+        let root = |state: &DynamicValue| state.get_map(&AttributePath::root()).unwrap_or_default();
+        let (prior, config) = (root(prior), root(config));
+        ["vcpus", "machine", "bootdisk", "hookscript"]
+            .into_iter()
+            .filter(|name| {
+                !matches!(prior.get(*name), None | Some(Dynamic::Null))
+                    && matches!(config.get(*name), None | Some(Dynamic::Null))
+            })
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Reads back the drive and network strings, in the option order of the
+    /// planned state where it has them
+    fn populate_device_strings(
         state: &mut DynamicValue,
         vm_config: &crate::api::nodes::QemuConfig,
         planned_state: &DynamicValue,
     ) {
-        // Required fields should always be present
-        if let Some(name) = &vm_config.name {
-            let _ = state.set_string(&AttributePath::new("name"), name.clone());
-        }
-
-        // Only populate optional attributes if they exist in VM config or planned state
-        if let Some(cores) = vm_config.cores {
-            let _ = state.set_number(&AttributePath::new("cores"), cores as f64);
-        } else if planned_state
-            .get_number(&AttributePath::new("cores"))
-            .is_ok()
-        {
-            let _ = state.set_number(&AttributePath::new("cores"), 1.0);
-        }
-
-        if let Some(sockets) = vm_config.sockets {
-            let _ = state.set_number(&AttributePath::new("sockets"), sockets as f64);
-        } else if planned_state
-            .get_number(&AttributePath::new("sockets"))
-            .is_ok()
-        {
-            let _ = state.set_number(&AttributePath::new("sockets"), 1.0);
-        }
-
-        if let Some(memory) = vm_config.memory {
-            let _ = state.set_number(&AttributePath::new("memory"), memory as f64);
-        } else if planned_state
-            .get_number(&AttributePath::new("memory"))
-            .is_ok()
-        {
-            let _ = state.set_number(&AttributePath::new("memory"), 512.0);
-        }
-
-        if let Some(ref cpu) = vm_config.cpu {
-            let _ = state.set_string(&AttributePath::new("cpu"), cpu.clone());
-        } else if planned_state.get_string(&AttributePath::new("cpu")).is_ok() {
-            let _ = state.set_string(&AttributePath::new("cpu"), "x86-64-v2-AES".to_string());
-        }
-
-        if let Some(ref bios) = vm_config.bios {
-            let _ = state.set_string(&AttributePath::new("bios"), bios.clone());
-        } else if planned_state
-            .get_string(&AttributePath::new("bios"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("bios"), "seabios".to_string());
-        }
-
-        if let Some(ref boot) = vm_config.boot {
-            // Only set if it was also in planned state
-            if planned_state
-                .get_string(&AttributePath::new("boot"))
-                .is_ok()
-            {
-                let _ = state.set_string(&AttributePath::new("boot"), boot.clone());
-            }
-        } else if planned_state
-            .get_string(&AttributePath::new("boot"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("boot"), String::new());
-        }
-
-        if let Some(ref scsihw) = vm_config.scsihw {
-            let _ = state.set_string(&AttributePath::new("scsihw"), scsihw.clone());
-        } else if planned_state
-            .get_string(&AttributePath::new("scsihw"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("scsihw"), "lsi".to_string());
-        }
-
-        if let Some(ref ostype) = vm_config.ostype {
-            let _ = state.set_string(&AttributePath::new("ostype"), ostype.clone());
-        } else if planned_state
-            .get_string(&AttributePath::new("ostype"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("ostype"), "other".to_string());
-        }
-
-        if let Some(ref agent) = vm_config.agent {
-            let _ = state.set_string(&AttributePath::new("agent"), agent.clone());
-        } else if planned_state
-            .get_string(&AttributePath::new("agent"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("agent"), "0".to_string());
-        }
-
-        if let Some(onboot) = vm_config.onboot {
-            let _ = state.set_bool(&AttributePath::new("onboot"), onboot);
-        } else if planned_state
-            .get_bool(&AttributePath::new("onboot"))
-            .is_ok()
-        {
-            let _ = state.set_bool(&AttributePath::new("onboot"), false);
-        }
-
-        if let Some(tablet) = vm_config.tablet {
-            let _ = state.set_bool(&AttributePath::new("tablet"), tablet);
-        } else if planned_state
-            .get_bool(&AttributePath::new("tablet"))
-            .is_ok()
-        {
-            let _ = state.set_bool(&AttributePath::new("tablet"), true);
-        }
-
-        if let Some(protection) = vm_config.protection {
-            let _ = state.set_bool(&AttributePath::new("protection"), protection);
-        } else if planned_state
-            .get_bool(&AttributePath::new("protection"))
-            .is_ok()
-        {
-            let _ = state.set_bool(&AttributePath::new("protection"), false);
-        }
-
-        if let Some(tags) = &vm_config.tags {
-            // Only set if it was also in planned state
-            if planned_state
-                .get_string(&AttributePath::new("tags"))
-                .is_ok()
-            {
-                let normalized_tags = Self::normalize_tags(tags);
-                let _ = state.set_string(&AttributePath::new("tags"), normalized_tags);
-            }
-        } else if planned_state
-            .get_string(&AttributePath::new("tags"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("tags"), String::new());
-        }
-
-        if let Some(ref description) = vm_config.description {
-            // Only set if it was also in planned state
-            if planned_state
-                .get_string(&AttributePath::new("description"))
-                .is_ok()
-            {
-                let _ = state.set_string(&AttributePath::new("description"), description.clone());
-            }
-        } else if planned_state
-            .get_string(&AttributePath::new("description"))
-            .is_ok()
-        {
-            let _ = state.set_string(&AttributePath::new("description"), String::new());
-        }
-
         // Disk configurations - only populate if in planned state or VM config
         let disk_attrs = vec![
             ("scsi0", &vm_config.scsi0),
@@ -1955,6 +5506,101 @@ impl QemuVmResource {
                 let _ = state.set_string(&AttributePath::new(attr_name), String::new());
             }
         }
+    }
+
+    fn populate_state_from_config(
+        state: &mut DynamicValue,
+        vm_config: &crate::api::nodes::QemuConfig,
+        planned_state: &DynamicValue,
+    ) {
+        // Required fields should always be present
+        if let Some(name) = &vm_config.name {
+            let _ = state.set_string(&AttributePath::new("name"), name.clone());
+        }
+
+        // Only attributes the configuration manages are read back, so options
+        // inherited from a template don't show up as changes
+        let managed = planned_state
+            .get_map(&AttributePath::root())
+            .unwrap_or_default();
+        for (name, value, unset) in Self::config_scalars(vm_config) {
+            if matches!(managed.get(name), None | Some(Dynamic::Null)) {
+                continue;
+            }
+            let path = AttributePath::new(name);
+            let _ = match value.unwrap_or(unset) {
+                Dynamic::String(value) => state.set_string(&path, value),
+                Dynamic::Number(value) => state.set_number(&path, value),
+                Dynamic::Bool(value) => state.set_bool(&path, value),
+                _ => state.set_null(&path),
+            };
+        }
+
+        if planned_state
+            .get_list(&AttributePath::new("memory"))
+            .is_ok_and(|memory| !memory.is_empty())
+        {
+            let _ = state.set_list(
+                &AttributePath::new("memory"),
+                vec![Self::parse_memory_settings(vm_config)],
+            );
+        }
+
+        if Self::single_block(planned_state, "smbios").is_some() {
+            let smbios = vm_config
+                .smbios1
+                .as_deref()
+                .map(Self::parse_smbios_string)
+                .into_iter()
+                .collect();
+            let _ = state.set_list(&AttributePath::new("smbios"), smbios);
+        }
+
+        if Self::single_block(planned_state, "startup").is_some() {
+            let startup = vm_config
+                .startup
+                .as_deref()
+                .map(Self::parse_startup_string)
+                .into_iter()
+                .collect();
+            let _ = state.set_list(&AttributePath::new("startup"), startup);
+        }
+
+        if let Some(planned_agent) = Self::single_block_as::<Agent>(planned_state, "agent") {
+            let agent = vm_config
+                .agent
+                .as_deref()
+                .map(|agent| Self::parse_agent_string(agent, Some(&planned_agent)))
+                .into_iter()
+                .collect();
+            let _ = state.set_list(&AttributePath::new("agent"), agent);
+        }
+
+        if let Some(planned_cpu) = Self::single_block_as::<Cpu>(planned_state, "cpu") {
+            let nested_virtualization = planned_state
+                .get_bool(&AttributePath::new("nested_virtualization"))
+                .unwrap_or(false);
+            let _ = state.set_list(
+                &AttributePath::new("cpu"),
+                vec![Self::parse_cpu_settings(
+                    vm_config,
+                    &planned_cpu,
+                    nested_virtualization,
+                )],
+            );
+        }
+
+        // Only set if it was also in planned state
+        if planned_state.get_list(&AttributePath::new("tags")).is_ok() {
+            let tags = vm_config
+                .tags
+                .as_deref()
+                .map(Self::parse_tags)
+                .unwrap_or_default();
+            let _ = state.set_list(&AttributePath::new("tags"), tags);
+        }
+
+        Self::populate_device_strings(state, vm_config, planned_state);
 
         // Cloud-init attributes - only set if present in planned state
         if let Ok(ciuser) = planned_state.get_string(&AttributePath::new("ciuser")) {
@@ -1965,8 +5611,20 @@ impl QemuVmResource {
             let _ = state.set_string(&AttributePath::new("cipassword"), cipassword);
         }
 
-        if let Ok(sshkeys) = planned_state.get_string(&AttributePath::new("sshkeys")) {
-            let _ = state.set_string(&AttributePath::new("sshkeys"), sshkeys);
+        // Keys are compared trimmed, so whitespace around a configured key
+        // doesn't show up as a change
+        if let Ok(planned) = planned_state.get_list(&AttributePath::new("sshkeys")) {
+            let actual = vm_config
+                .sshkeys
+                .as_deref()
+                .map(Self::parse_sshkeys)
+                .unwrap_or_default();
+            let keys = if Self::sshkeys_config(planned_state) == Some(actual.join("\n")) {
+                planned
+            } else {
+                actual.into_iter().map(Dynamic::String).collect()
+            };
+            let _ = state.set_list(&AttributePath::new("sshkeys"), keys);
         }
 
         if let Ok(ipconfig0) = planned_state.get_string(&AttributePath::new("ipconfig0")) {
@@ -1985,10 +5643,26 @@ impl QemuVmResource {
             let _ = state.set_string(&AttributePath::new("ipconfig3"), ipconfig3);
         }
 
+        if let Some(mut cloudinit) = Self::single_block_as::<CloudInit>(planned_state, "cloudinit")
+        {
+            cloudinit.read_dns(vm_config);
+            let _ = state.set_list(
+                &AttributePath::new("cloudinit"),
+                vec![cloudinit.to_dynamic()],
+            );
+        } else if let Ok(cloudinit) = planned_state.get_list(&AttributePath::new("cloudinit")) {
+            let _ = state.set_list(&AttributePath::new("cloudinit"), cloudinit);
+        }
+
         // Start attribute - preserve from planned state
         if let Ok(start) = planned_state.get_bool(&AttributePath::new("start")) {
             let _ = state.set_bool(&AttributePath::new("start"), start);
         }
+
+        // nested_virtualization only drives cpu/kvm, it has no API counterpart
+        if let Ok(nested) = planned_state.get_bool(&AttributePath::new("nested_virtualization")) {
+            let _ = state.set_bool(&AttributePath::new("nested_virtualization"), nested);
+        }
     }
 
     fn populate_state_with_nested_blocks(
@@ -2015,21 +5689,13 @@ impl QemuVmResource {
             }
 
             // Build network blocks from VM config
-            for i in 0..=3 {
+            for i in 0..MAX_NETWORK_INTERFACES {
                 // Only include networks that were in the planned blocks
                 if !planned_network_ids.contains(&i) {
                     continue;
                 }
 
-                let net_field = match i {
-                    0 => &vm_config.net0,
-                    1 => &vm_config.net1,
-                    2 => &vm_config.net2,
-                    3 => &vm_config.net3,
-                    _ => &None,
-                };
-
-                if let Some(net_config) = net_field {
+                if let Some(net_config) = vm_config.net(i) {
                     // Parse the network string and create a block
                     let net_block = Self::parse_network_string(net_config, i);
                     networks.push(net_block);
@@ -2056,27 +5722,50 @@ impl QemuVmResource {
             }
 
             // Build disk blocks from VM config
-            let disk_configs = vec![
-                ("scsi0", &vm_config.scsi0),
-                ("scsi1", &vm_config.scsi1),
-                ("scsi2", &vm_config.scsi2),
-                ("scsi3", &vm_config.scsi3),
-                ("virtio0", &vm_config.virtio0),
-                ("virtio1", &vm_config.virtio1),
-                ("ide0", &vm_config.ide0),
-                ("ide2", &vm_config.ide2),
-                ("sata0", &vm_config.sata0),
-            ];
-
-            for (slot, disk_field) in disk_configs {
+            for slot in crate::api::nodes::DRIVE_SLOTS.iter().copied() {
                 // Only include disks that were in the planned blocks
                 if !planned_disk_slots.contains(slot) {
                     continue;
                 }
 
-                if let Some(disk_config) = disk_field {
+                if let Some(disk_config) = vm_config.drive(slot) {
                     // Parse the disk string and create a block
-                    let disk_block = Self::parse_disk_string(disk_config, slot);
+                    let mut disk_block = Self::parse_disk_string(disk_config, slot);
+
+                    // Proxmox doesn't record where a disk was imported from
+                    let import_from = planned_disks.iter().find_map(|disk| match disk {
+                        Dynamic::Map(map)
+                            if map.get("slot") == Some(&Dynamic::String(slot.to_string())) =>
+                        {
+                            Self::disk_import_source(disk)
+                        }
+                        _ => None,
+                    });
+                    if let (Dynamic::Map(map), Some(source)) = (&mut disk_block, import_from) {
+                        map.insert("import_from".to_string(), Dynamic::String(source));
+                    }
+
+                    // Keep the planned size when only its unit differs
+                    let planned_size = planned_disks.iter().find_map(|disk| match disk {
+                        Dynamic::Map(map)
+                            if map.get("slot") == Some(&Dynamic::String(slot.to_string())) =>
+                        {
+                            match map.get("size") {
+                                Some(Dynamic::String(size)) => Some(size.clone()),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    });
+                    if let (Dynamic::Map(map), Some(planned)) = (&mut disk_block, planned_size) {
+                        let same_size = matches!(
+                            map.get("size"),
+                            Some(Dynamic::String(actual)) if values_eq("size", &planned, actual)
+                        );
+                        if same_size {
+                            map.insert("size".to_string(), Dynamic::String(planned));
+                        }
+                    }
                     disks.push(disk_block);
                 }
             }
@@ -2085,6 +5774,79 @@ impl QemuVmResource {
             let _ = state.set_list(&AttributePath::new("disk"), disks);
         }
 
+        // Only serial ports managed through serial blocks are read back, like
+        // network blocks, so ports inherited from a template don't show up
+        // as changes
+        if let Ok(planned_serials) = planned_state.get_list(&AttributePath::new("serial")) {
+            let planned_ids: std::collections::HashSet<u32> = planned_serials
+                .iter()
+                .filter_map(|serial| match serial {
+                    Dynamic::Map(map) => match map.get("id") {
+                        Some(Dynamic::Number(id)) => Some(*id as u32),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect();
+            let serials = [
+                &vm_config.serial0,
+                &vm_config.serial1,
+                &vm_config.serial2,
+                &vm_config.serial3,
+            ]
+            .into_iter()
+            .zip(0..)
+            .filter(|(_, id)| planned_ids.contains(id))
+            .filter_map(|(serial, id)| {
+                serial
+                    .as_deref()
+                    .map(|serial| Self::parse_serial_string(serial, id))
+            })
+            .collect();
+            let _ = state.set_list(&AttributePath::new("serial"), serials);
+        }
+
+        if planned_state
+            .get_list(&AttributePath::new("vga"))
+            .is_ok_and(|vga| !vga.is_empty())
+        {
+            let vga = vm_config
+                .vga
+                .as_deref()
+                .map(Self::parse_vga_string)
+                .into_iter()
+                .collect();
+            let _ = state.set_list(&AttributePath::new("vga"), vga);
+        }
+
+        // NUMA nodes are read back the same way as serial ports
+        if let Ok(planned_nodes) = planned_state.get_list(&AttributePath::new("numa_node")) {
+            let planned_ids: std::collections::HashSet<u32> = planned_nodes
+                .iter()
+                .filter_map(|node| NumaNode::from_dynamic(node).ok()?.id)
+                .map(|id| id as u32)
+                .collect();
+            let nodes = [
+                &vm_config.numa0,
+                &vm_config.numa1,
+                &vm_config.numa2,
+                &vm_config.numa3,
+                &vm_config.numa4,
+                &vm_config.numa5,
+                &vm_config.numa6,
+                &vm_config.numa7,
+            ]
+            .into_iter()
+            .zip(0..)
+            .filter(|(_, id)| planned_ids.contains(id))
+            .filter_map(|(node, id)| {
+                node.as_deref()
+                    .map(|node| NumaNode::from_api_string(id, node).to_dynamic())
+            })
+            .collect();
+            let _ = state.set_list(&AttributePath::new("numa_node"), nodes);
+        }
+
         // Handle efidisk block (it's a list with max_items: 1)
         if let Ok(efidisk_list) = planned_state.get_list(&AttributePath::new("efidisk")) {
             if !efidisk_list.is_empty() {
@@ -2092,43 +5854,7 @@ impl QemuVmResource {
                 let mut efidisk = std::collections::HashMap::new();
 
                 if let Some(efidisk_config) = &vm_config.efidisk0 {
-                    // Parse storage and format from config like "local-lvm:1,format=raw,efitype=4m"
-                    let parts: Vec<&str> = efidisk_config.split(',').collect();
-                    if let Some(storage_part) = parts.first() {
-                        if let Some((storage, _)) = storage_part.split_once(':') {
-                            efidisk.insert(
-                                "storage".to_string(),
-                                Dynamic::String(storage.to_string()),
-                            );
-                        }
-                    }
-
-                    for part in parts.iter().skip(1) {
-                        if let Some((key, value)) = part.split_once('=') {
-                            match key {
-                                "format" => {
-                                    efidisk.insert(
-                                        "format".to_string(),
-                                        Dynamic::String(value.to_string()),
-                                    );
-                                }
-                                "efitype" => {
-                                    efidisk.insert(
-                                        "efitype".to_string(),
-                                        Dynamic::String(value.to_string()),
-                                    );
-                                }
-                                "pre-enrolled-keys" => {
-                                    let enrolled = value == "1" || value == "true";
-                                    efidisk.insert(
-                                        "pre_enrolled_keys".to_string(),
-                                        Dynamic::Bool(enrolled),
-                                    );
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
+                    efidisk = Self::parse_efidisk_string(efidisk_config);
                 }
 
                 // Copy all values from planned state first
@@ -2142,56 +5868,138 @@ impl QemuVmResource {
                 }
 
                 // Ensure all required attributes are present with defaults if not in API response
-                if !efidisk.contains_key("storage") {
-                    efidisk.insert("storage".to_string(), Dynamic::String(String::new()));
-                }
-                if !efidisk.contains_key("format") {
-                    efidisk.insert("format".to_string(), Dynamic::String("raw".to_string()));
-                }
-                if !efidisk.contains_key("efitype") {
-                    efidisk.insert("efitype".to_string(), Dynamic::String("4m".to_string()));
-                }
-                if !efidisk.contains_key("pre_enrolled_keys") {
-                    efidisk.insert("pre_enrolled_keys".to_string(), Dynamic::Bool(false));
-                }
+                Self::fill_efidisk_defaults(&mut efidisk);
 
                 // Always set the map
                 efidisk_blocks.push(Dynamic::Map(efidisk));
                 let _ = state.set_list(&AttributePath::new("efidisk"), efidisk_blocks);
             }
         }
+    }
 
-        // Handle cloudinit block
-        if planned_state
-            .get_map(&AttributePath::new("cloudinit"))
-            .is_ok()
-        {
-            let mut cloudinit = std::collections::HashMap::new();
+    /// Settings the create and update requests share, read from the same
+    /// attributes and blocks so the two can't drift apart
+    fn vm_settings(config: &DynamicValue) -> QemuSettings {
+        let nested_virtualization = config
+            .get_bool(&AttributePath::new("nested_virtualization"))
+            .unwrap_or(false);
+        let CpuSettings {
+            mut cpu,
+            cpuunits,
+            cpulimit,
+            affinity,
+            numa,
+        } = Self::cpu_settings(config);
+        let mut kvm = config.get_bool(&AttributePath::new("kvm")).ok();
+        if nested_virtualization {
+            cpu.get_or_insert_with(|| "host".to_string());
+            kvm.get_or_insert(true);
+        }
+        let (memory, balloon, shares, hugepages, keephugepages) = Self::memory_settings(config);
+        let [numa0, numa1, numa2, numa3, numa4, numa5, numa6, numa7] =
+            Self::numa_node_fields(config);
+        let qemu_os = config.get_string(&AttributePath::new("qemu_os")).ok();
+        let os_type = config.get_string(&AttributePath::new("os_type")).ok();
 
-            // Get cloud-init values from planned state since Proxmox doesn't return them
-            if let Ok(ci_map) = planned_state.get_map(&AttributePath::new("cloudinit")) {
-                // Copy all values from planned state
-                for (key, value) in ci_map {
-                    cloudinit.insert(key, value);
-                }
-            }
+        let mut settings = QemuSettings {
+            name: config.get_string(&AttributePath::new("name")).ok(),
+            tags: Self::tags_config(config),
+            description: config.get_string(&AttributePath::new("description")).ok(),
+            ostype: qemu_os.or(os_type),
+            bios: config.get_string(&AttributePath::new("bios")).ok(),
+            machine: config.get_string(&AttributePath::new("machine")).ok(),
+            cpu,
+            cpuunits,
+            cpulimit,
+            affinity,
+            numa,
+            kvm,
+            cores: config.get_u32(&AttributePath::new("cores")).ok(),
+            sockets: config.get_u32(&AttributePath::new("sockets")).ok(),
+            vcpus: config.get_u32(&AttributePath::new("vcpus")).ok(),
+            memory,
+            balloon,
+            shares,
+            hugepages,
+            keephugepages,
+            numa0,
+            numa1,
+            numa2,
+            numa3,
+            numa4,
+            numa5,
+            numa6,
+            numa7,
+            boot: config.get_string(&AttributePath::new("boot")).ok(),
+            bootdisk: config.get_string(&AttributePath::new("bootdisk")).ok(),
+            onboot: config.get_bool(&AttributePath::new("onboot")).ok(),
+            scsihw: config.get_string(&AttributePath::new("scsihw")).ok(),
+            agent: Self::agent_config(config),
+            tablet: config.get_bool(&AttributePath::new("tablet")).ok(),
+            protection: config.get_bool(&AttributePath::new("protection")).ok(),
+            hookscript: config.get_string(&AttributePath::new("hookscript")).ok(),
+            efidisk0: config
+                .get_list(&AttributePath::new("efidisk"))
+                .unwrap_or_default()
+                .first()
+                .and_then(|efidisk| Self::efidisk_block_to_api_string(efidisk).ok()),
+            smbios1: Self::smbios_config(config, None),
+            startup: Self::startup_config(config),
+            vga: Self::vga_config(config),
+            ..Default::default()
+        };
 
-            // Ensure all required attributes are present
-            if !cloudinit.contains_key("user") {
-                cloudinit.insert("user".to_string(), Dynamic::String(String::new()));
+        for disk in config
+            .get_list(&AttributePath::new("disk"))
+            .unwrap_or_default()
+        {
+            if let Ok((slot, disk_string)) = Self::disk_block_to_api_string(&disk) {
+                settings.set_drive(&slot, disk_string);
             }
-            if !cloudinit.contains_key("password") {
-                cloudinit.insert("password".to_string(), Dynamic::String(String::new()));
+        }
+        // Changing the iso swaps or ejects the disc without recreating the VM
+        for (slot, cdrom) in Self::cdrom_drives(config) {
+            settings.set_drive(&slot, cdrom);
+        }
+        for (id, serial_string) in Self::serial_ports(config) {
+            match id {
+                0 => settings.serial0 = Some(serial_string),
+                1 => settings.serial1 = Some(serial_string),
+                2 => settings.serial2 = Some(serial_string),
+                3 => settings.serial3 = Some(serial_string),
+                _ => {}
             }
-            if !cloudinit.contains_key("ssh_keys") {
-                cloudinit.insert("ssh_keys".to_string(), Dynamic::String(String::new()));
+        }
+        for net in config
+            .get_list(&AttributePath::new("network"))
+            .unwrap_or_default()
+        {
+            if let Dynamic::Map(ref net_map) = net {
+                if let Some(Dynamic::Number(id)) = net_map.get("id") {
+                    let id = *id as u32;
+                    if let Ok(net_string) = Self::network_blocks_to_string(&[net]) {
+                        settings.set_net(id, net_string);
+                    }
+                }
             }
-            if !cloudinit.contains_key("ipconfig") {
-                cloudinit.insert("ipconfig".to_string(), Dynamic::List(Vec::new()));
+        }
+        // Fall back to the string attributes for interfaces without a block
+        for id in 0..4 {
+            if settings.net(id).is_none() {
+                if let Ok(net) = config.get_string(&AttributePath::new(&format!("net{}", id))) {
+                    settings.set_net(id, Self::normalize_network_config(&net, Some(&net)));
+                }
             }
-
-            let _ = state.set_map(&AttributePath::new("cloudinit"), cloudinit);
         }
+        for (id, ipconfig) in Self::ipconfigs(config) {
+            settings.set_ipconfig(id, ipconfig);
+        }
+        if let Some(cloudinit) = Self::single_block_as::<CloudInit>(config, "cloudinit") {
+            settings.nameserver = cloudinit.nameserver_config();
+            settings.searchdomain = cloudinit.searchdomain;
+        }
+
+        settings
     }
 
     fn extract_vm_config(
@@ -2209,321 +6017,42 @@ impl QemuVmResource {
             })?;
 
         let vmid = config
-            .get_number(&AttributePath::new("vmid"))
-            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?
-            as u32;
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
 
-        let name = config.get_string(&AttributePath::new("name")).ok();
-        let tags = config.get_string(&AttributePath::new("tags")).ok();
-
-        // Clone/Template Settings
         let clone = config.get_string(&AttributePath::new("clone")).ok();
         let full_clone = config.get_bool(&AttributePath::new("full_clone")).ok();
-        let os_type = config.get_string(&AttributePath::new("os_type")).ok();
-
-        // Hardware Configuration
-        let bios = config.get_string(&AttributePath::new("bios")).ok();
-        let machine = config.get_string(&AttributePath::new("machine")).ok();
-        let cpu_type = config.get_string(&AttributePath::new("cpu_type")).ok();
-        let cores = config
-            .get_number(&AttributePath::new("cores"))
-            .ok()
-            .map(|n| n as u32);
-        let sockets = config
-            .get_number(&AttributePath::new("sockets"))
-            .ok()
-            .map(|n| n as u32);
-        let vcpus = config
-            .get_number(&AttributePath::new("vcpus"))
-            .ok()
-            .map(|n| n as u32);
-        let memory = config
-            .get_number(&AttributePath::new("memory"))
-            .ok()
-            .map(|n| n as u64);
-        let balloon = config
-            .get_number(&AttributePath::new("balloon"))
-            .ok()
-            .map(|n| n as u64);
-
-        // Boot Configuration
-        let boot = config.get_string(&AttributePath::new("boot")).ok();
-        let bootdisk = config.get_string(&AttributePath::new("bootdisk")).ok();
-        let onboot = config.get_bool(&AttributePath::new("onboot")).ok();
-
-        // Storage Configuration
-        let scsihw = config.get_string(&AttributePath::new("scsihw")).ok();
-
-        // Guest Agent & OS Settings
-        let agent = config
-            .get_number(&AttributePath::new("agent"))
-            .ok()
-            .map(|n| n.to_string());
-        let qemu_os = config.get_string(&AttributePath::new("qemu_os")).ok();
-
-        // Cloud-Init Configuration
-        let ipconfig0 = config.get_string(&AttributePath::new("ipconfig0")).ok();
-        let ipconfig1 = config.get_string(&AttributePath::new("ipconfig1")).ok();
-        let ciuser = config.get_string(&AttributePath::new("ciuser")).ok();
-        let cipassword = config.get_string(&AttributePath::new("cipassword")).ok();
-        let ciupgrade = config.get_bool(&AttributePath::new("ciupgrade")).ok();
-        let sshkeys = config.get_string(&AttributePath::new("sshkeys")).ok();
-
-        // Other attributes
-        let start = config.get_bool(&AttributePath::new("start")).ok();
-        let tablet = config.get_bool(&AttributePath::new("tablet")).ok();
-        let protection = config.get_bool(&AttributePath::new("protection")).ok();
-        let description = config.get_string(&AttributePath::new("description")).ok();
-
-        // Handle disk blocks
-        let mut scsi0 = None;
-        let mut scsi1 = None;
-        let mut scsi2 = None;
-        let mut scsi3 = None;
-        let mut virtio0 = None;
-        let mut virtio1 = None;
-        let mut ide0 = None;
-        let mut ide2 = None;
-        let mut ide3 = None;
-        let mut sata0 = None;
-
-        // Process disk blocks
-        if let Ok(disks) = config.get_list(&AttributePath::new("disk")) {
-            for disk in disks {
-                if let Ok((slot, disk_string)) = Self::disk_block_to_api_string(&disk) {
-                    match slot.as_str() {
-                        "scsi0" => scsi0 = Some(disk_string),
-                        "scsi1" => scsi1 = Some(disk_string),
-                        "scsi2" => scsi2 = Some(disk_string),
-                        "scsi3" => scsi3 = Some(disk_string),
-                        "virtio0" => virtio0 = Some(disk_string),
-                        "virtio1" => virtio1 = Some(disk_string),
-                        "ide0" => ide0 = Some(disk_string),
-                        "ide2" => ide2 = Some(disk_string),
-                        "ide3" => ide3 = Some(disk_string),
-                        "sata0" => sata0 = Some(disk_string),
-                        _ => {} // Ignore other slots
-                    }
-                }
-            }
-        }
-
-        // Process cdrom blocks
-        if let Ok(cdroms) = config.get_list(&AttributePath::new("cdrom")) {
-            for cdrom in cdroms {
-                if let Ok((slot, cdrom_string)) = Self::cdrom_block_to_api_string(&cdrom) {
-                    if slot.as_str() == "ide2" {
-                        ide2 = Some(cdrom_string);
-                    }
-                }
-            }
-        }
-
-        // Process cloudinit_drive blocks
-        if let Ok(cloudinit_drives) = config.get_list(&AttributePath::new("cloudinit_drive")) {
-            for ci_drive in cloudinit_drives {
-                if let Ok((slot, ci_string)) = Self::cloudinit_drive_block_to_api_string(&ci_drive)
-                {
-                    if slot.as_str() == "ide3" {
-                        ide3 = Some(ci_string);
-                    }
-                }
-            }
-        }
-
-        // Handle efidisk
-        let mut efidisk0 = None;
-        if let Ok(efidisks) = config.get_list(&AttributePath::new("efidisk")) {
-            if let Some(efidisk) = efidisks.first() {
-                if let Ok(efidisk_string) = Self::efidisk_block_to_api_string(efidisk) {
-                    efidisk0 = Some(efidisk_string);
-                }
-            }
-        }
-
-        // Handle serial blocks
-        let mut serial0 = None;
-        let mut serial1 = None;
-        let mut serial2 = None;
-        let mut serial3 = None;
-        if let Ok(serials) = config.get_list(&AttributePath::new("serial")) {
-            for serial in serials {
-                if let Ok((id, serial_string)) = Self::serial_block_to_api_string(&serial) {
-                    match id {
-                        0 => serial0 = Some(serial_string),
-                        1 => serial1 = Some(serial_string),
-                        2 => serial2 = Some(serial_string),
-                        3 => serial3 = Some(serial_string),
-                        _ => {} // Ignore other IDs
-                    }
-                }
-            }
-        }
-
-        // Handle networks - check for nested blocks first, then fall back to string attributes
-        let mut net0 = None;
-        let mut net1 = None;
-        let mut net2 = None;
-        let mut net3 = None;
+        let restore_from: Option<RestoreFrom> = Self::single_block_as(config, "restore_from");
 
-        // Check for network blocks
-        if let Ok(networks) = config.get_list(&AttributePath::new("network")) {
-            for net in networks {
-                if let Dynamic::Map(ref net_map) = net {
-                    if let Some(Dynamic::Number(id)) = net_map.get("id") {
-                        let id_int = *id as u32;
-                        if let Ok(net_string) = Self::network_blocks_to_string(&[net]) {
-                            match id_int {
-                                0 => net0 = Some(net_string),
-                                1 => net1 = Some(net_string),
-                                2 => net2 = Some(net_string),
-                                3 => net3 = Some(net_string),
-                                _ => {} // Ignore IDs > 3 for now
-                            }
-                        }
-                    }
+        let mut settings = Self::vm_settings(config);
+        // The cloud-init drive is only created with the VM
+        for ci_drive in config
+            .get_list(&AttributePath::new("cloudinit_drive"))
+            .unwrap_or_default()
+        {
+            if let Ok((slot, ci_string)) = Self::cloudinit_drive_block_to_api_string(&ci_drive) {
+                if slot.as_str() == "ide3" {
+                    settings.ide3 = Some(ci_string);
                 }
             }
         }
 
-        // Fall back to string attributes if no network blocks
-        if net0.is_none() {
-            net0 = config
-                .get_string(&AttributePath::new("net0"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-        if net1.is_none() {
-            net1 = config
-                .get_string(&AttributePath::new("net1"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-        if net2.is_none() {
-            net2 = config
-                .get_string(&AttributePath::new("net2"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-        if net3.is_none() {
-            net3 = config
-                .get_string(&AttributePath::new("net3"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-
         let create_request = crate::api::nodes::CreateQemuRequest {
             vmid,
-            clone: clone.clone(),
             full: if clone.is_some() { full_clone } else { None },
-            name,
-            cores,
-            sockets,
-            memory,
-            cpu: cpu_type,
-            bios,
-            boot,
-            bootdisk,
-            scsihw,
-            ostype: qemu_os.clone().or(os_type),
-            agent,
-            onboot,
-            start,
-            tablet,
-            protection,
-            tags,
-            description,
-            scsi0,
-            scsi1,
-            scsi2,
-            scsi3,
-            virtio0,
-            virtio1,
-            ide0,
-            ide2,
-            ide3,
-            sata0,
-            net0,
-            net1,
-            net2,
-            net3,
-            acpi: None,
-            args: None,
-            autostart: None,
-            balloon,
-            cdrom: None,
-            cpulimit: None,
-            cpuunits: None,
-            efidisk0,
-            freeze: None,
-            hookscript: None,
-            hotplug: None,
-            hugepages: None,
-            ide1: None,
-            kvm: None,
-            localtime: None,
-            lock: None,
-            machine,
-            migrate_downtime: None,
-            migrate_speed: None,
-            nameserver: None,
-            numa: None,
-            numa0: None,
-            numa1: None,
-            reboot: None,
-            sata1: None,
-            sata2: None,
-            sata3: None,
-            sata4: None,
-            sata5: None,
-            scsi4: None,
-            scsi5: None,
-            scsi6: None,
-            scsi7: None,
-            searchdomain: None,
-            serial0,
-            serial1,
-            serial2,
-            serial3,
-            shares: None,
-            smbios1: None,
-            smp: None,
-            startup: None,
-            startdate: None,
-            template: None,
-            unused0: None,
-            unused1: None,
-            unused2: None,
-            unused3: None,
-            usb0: None,
-            usb1: None,
-            usb2: None,
-            usb3: None,
-            vcpus,
-            vga: None,
-            virtio2: None,
-            virtio3: None,
-            virtio4: None,
-            virtio5: None,
-            virtio6: None,
-            virtio7: None,
-            virtio8: None,
-            virtio9: None,
-            virtio10: None,
-            virtio11: None,
-            virtio12: None,
-            virtio13: None,
-            virtio14: None,
-            virtio15: None,
-            vmgenid: None,
-            vmstatestorage: None,
-            watchdog: None,
-            ciuser,
-            cipassword,
-            ciupgrade,
-            ipconfig0,
-            ipconfig1,
-            sshkeys,
+            clone,
+            archive: restore_from.as_ref().map(|restore| restore.volid.clone()),
+            storage: restore_from
+                .as_ref()
+                .and_then(|restore| restore.storage.clone()),
+            unique: restore_from.as_ref().and_then(|restore| restore.unique),
+            pool: Self::pool(config),
+            start: config.get_bool(&AttributePath::new("start")).ok(),
+            ciuser: config.get_string(&AttributePath::new("ciuser")).ok(),
+            cipassword: config.get_string(&AttributePath::new("cipassword")).ok(),
+            ciupgrade: config.get_bool(&AttributePath::new("ciupgrade")).ok(),
+            sshkeys: Self::sshkeys_config(config),
+            settings,
         };
 
         Ok((node, vmid, create_request))
@@ -2533,261 +6062,23 @@ impl QemuVmResource {
         &self,
         config: &DynamicValue,
     ) -> Result<crate::api::nodes::UpdateQemuRequest, Diagnostic> {
-        let name = config.get_string(&AttributePath::new("name")).ok();
-        let cores = config
-            .get_number(&AttributePath::new("cores"))
-            .ok()
-            .map(|n| n as u32);
-        let sockets = config
-            .get_number(&AttributePath::new("sockets"))
-            .ok()
-            .map(|n| n as u32);
-        let memory = config
-            .get_number(&AttributePath::new("memory"))
-            .ok()
-            .map(|n| n as u64);
-        let cpu = config.get_string(&AttributePath::new("cpu")).ok();
-        let bios = config.get_string(&AttributePath::new("bios")).ok();
-        let boot = config.get_string(&AttributePath::new("boot")).ok();
-        let scsihw = config.get_string(&AttributePath::new("scsihw")).ok();
-        let ostype = config.get_string(&AttributePath::new("ostype")).ok();
-        let agent = config.get_string(&AttributePath::new("agent")).ok();
-        let onboot = config.get_bool(&AttributePath::new("onboot")).ok();
-        let tablet = config.get_bool(&AttributePath::new("tablet")).ok();
-        let protection = config.get_bool(&AttributePath::new("protection")).ok();
-        let tags = config.get_string(&AttributePath::new("tags")).ok();
-        let description = config.get_string(&AttributePath::new("description")).ok();
-
-        // Handle disks - check for nested blocks first, then fall back to string attributes
-        let mut scsi0 = None;
-        let mut scsi1 = None;
-        let mut scsi2 = None;
-        let mut scsi3 = None;
-        let mut virtio0 = None;
-        let mut virtio1 = None;
-        let mut ide0 = None;
-        let mut ide2 = None;
-        let mut sata0 = None;
-
-        // Check for disk blocks
-        if let Ok(disks) = config.get_list(&AttributePath::new("disk")) {
-            for disk in disks {
-                if let Ok((slot, disk_string)) = Self::disk_block_to_api_string(&disk) {
-                    match slot.as_str() {
-                        "scsi0" => scsi0 = Some(disk_string),
-                        "scsi1" => scsi1 = Some(disk_string),
-                        "scsi2" => scsi2 = Some(disk_string),
-                        "scsi3" => scsi3 = Some(disk_string),
-                        "virtio0" => virtio0 = Some(disk_string),
-                        "virtio1" => virtio1 = Some(disk_string),
-                        "ide0" => ide0 = Some(disk_string),
-                        "ide2" => ide2 = Some(disk_string),
-                        "sata0" => sata0 = Some(disk_string),
-                        _ => {} // Ignore other interfaces for now
-                    }
-                }
-            }
-        }
-
-        // Fall back to string attributes if no disk blocks
-        if scsi0.is_none() {
-            scsi0 = config.get_string(&AttributePath::new("scsi0")).ok();
-        }
-        if scsi1.is_none() {
-            scsi1 = config.get_string(&AttributePath::new("scsi1")).ok();
-        }
-        if scsi2.is_none() {
-            scsi2 = config.get_string(&AttributePath::new("scsi2")).ok();
-        }
-        if scsi3.is_none() {
-            scsi3 = config.get_string(&AttributePath::new("scsi3")).ok();
-        }
-        if virtio0.is_none() {
-            virtio0 = config.get_string(&AttributePath::new("virtio0")).ok();
-        }
-        if virtio1.is_none() {
-            virtio1 = config.get_string(&AttributePath::new("virtio1")).ok();
-        }
-        if ide0.is_none() {
-            ide0 = config.get_string(&AttributePath::new("ide0")).ok();
-        }
-        if ide2.is_none() {
-            ide2 = config.get_string(&AttributePath::new("ide2")).ok();
-        }
-        if sata0.is_none() {
-            sata0 = config.get_string(&AttributePath::new("sata0")).ok();
-        }
-
-        // Handle efidisk - check for nested block first (it's a list), then fall back to string attribute
-        let mut efidisk0 = None;
-        if let Ok(efidisks) = config.get_list(&AttributePath::new("efidisk")) {
-            if let Some(efidisk) = efidisks.first() {
-                if let Ok(efidisk_string) = Self::efidisk_block_to_api_string(efidisk) {
-                    efidisk0 = Some(efidisk_string);
-                }
-            }
-        }
-        if efidisk0.is_none() {
-            efidisk0 = config.get_string(&AttributePath::new("efidisk0")).ok();
-        }
-
-        // Handle networks - check for nested blocks first, then fall back to string attributes
-        let mut net0 = None;
-        let mut net1 = None;
-        let mut net2 = None;
-        let mut net3 = None;
-
-        // Check for network blocks
-        if let Ok(networks) = config.get_list(&AttributePath::new("network")) {
-            for net in networks {
-                if let Dynamic::Map(ref net_map) = net {
-                    if let Some(Dynamic::Number(id)) = net_map.get("id") {
-                        let id_int = *id as u32;
-                        if let Ok(net_string) = Self::network_blocks_to_string(&[net]) {
-                            match id_int {
-                                0 => net0 = Some(net_string),
-                                1 => net1 = Some(net_string),
-                                2 => net2 = Some(net_string),
-                                3 => net3 = Some(net_string),
-                                _ => {} // Ignore IDs > 3 for now
-                            }
-                        }
-                    }
+        let mut settings = Self::vm_settings(config);
+        // Sending import-from again would import the image again
+        for disk in config
+            .get_list(&AttributePath::new("disk"))
+            .unwrap_or_default()
+        {
+            if Self::disk_import_source(&disk).is_some() {
+                if let Ok((slot, _)) = Self::disk_block_to_api_string(&disk) {
+                    settings.take_drive(&slot);
                 }
             }
         }
 
-        // Fall back to string attributes if no network blocks
-        if net0.is_none() {
-            net0 = config
-                .get_string(&AttributePath::new("net0"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-        if net1.is_none() {
-            net1 = config
-                .get_string(&AttributePath::new("net1"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-        if net2.is_none() {
-            net2 = config
-                .get_string(&AttributePath::new("net2"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-        if net3.is_none() {
-            net3 = config
-                .get_string(&AttributePath::new("net3"))
-                .ok()
-                .map(|n| Self::normalize_network_config(&n, Some(&n)));
-        }
-
         Ok(crate::api::nodes::UpdateQemuRequest {
-            name,
-            cores,
-            sockets,
-            memory,
-            cpu,
-            bios,
-            boot,
-            scsihw,
-            ostype,
-            agent,
-            onboot,
-            tablet,
-            protection,
-            tags,
-            description,
-            scsi0,
-            scsi1,
-            scsi2,
-            scsi3,
-            virtio0,
-            virtio1,
-            ide0,
-            ide2,
-            sata0,
-            net0,
-            net1,
-            net2,
-            net3,
-            acpi: None,
-            args: None,
-            autostart: None,
-            balloon: None,
-            bootdisk: None,
-            cdrom: None,
-            cpulimit: None,
-            cpuunits: None,
-            delete: None,
-            digest: None,
-            efidisk0,
-            freeze: None,
-            hookscript: None,
-            hotplug: None,
-            hugepages: None,
-            ide1: None,
-            ide3: None,
-            kvm: None,
-            localtime: None,
-            lock: None,
-            machine: None,
-            migrate_downtime: None,
-            migrate_speed: None,
-            nameserver: None,
-            numa: None,
-            numa0: None,
-            numa1: None,
-            reboot: None,
-            revert: None,
-            sata1: None,
-            sata2: None,
-            sata3: None,
-            sata4: None,
-            sata5: None,
-            scsi4: None,
-            scsi5: None,
-            scsi6: None,
-            scsi7: None,
-            searchdomain: None,
-            serial0: None,
-            serial1: None,
-            serial2: None,
-            serial3: None,
-            shares: None,
-            smbios1: None,
-            smp: None,
-            startup: None,
-            startdate: None,
-            template: None,
-            unused0: None,
-            unused1: None,
-            unused2: None,
-            unused3: None,
-            usb0: None,
-            usb1: None,
-            usb2: None,
-            usb3: None,
-            vcpus: None,
-            vga: None,
-            virtio2: None,
-            virtio3: None,
-            virtio4: None,
-            virtio5: None,
-            virtio6: None,
-            virtio7: None,
-            virtio8: None,
-            virtio9: None,
-            virtio10: None,
-            virtio11: None,
-            virtio12: None,
-            virtio13: None,
-            virtio14: None,
-            virtio15: None,
-            vmgenid: None,
-            vmstatestorage: None,
-            watchdog: None,
+            settings,
+            sshkeys: Self::sshkeys_config(config),
+            ..Default::default()
         })
     }
 }
@@ -2819,6 +6110,340 @@ impl ResourceWithConfigure for QemuVmResource {
 
         ConfigureResourceResponse { diagnostics }
     }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        Some(self)
+    }
+
+    fn as_upgrade_state(&self) -> Option<&dyn ResourceWithUpgradeState> {
+        Some(self)
+    }
+
+    fn as_move_state(&self) -> Option<&dyn ResourceWithMoveState> {
+        Some(self)
+    }
+
+    fn as_config_validators(&self) -> Option<&dyn ResourceWithConfigValidators> {
+        Some(self)
+    }
+
+    fn as_import_state(&self) -> Option<&dyn ResourceWithImportState> {
+        Some(self)
+    }
+
+    fn as_identity(&self) -> Option<&dyn ResourceWithIdentity> {
+        Some(self)
+    }
+}
+
+/// A VM is identified by its VMID, which is unique across the cluster and
+/// stays the same when the VM migrates between nodes
+impl ResourceWithIdentity for QemuVmResource {
+    fn identity_schema(&self) -> ResourceIdentitySchema {
+        ResourceIdentitySchema {
+            version: 0,
+            identity_attributes: vec![IdentityAttribute::new("vmid", AttributeType::Number)
+                .required_for_import()
+                .description("VM ID")],
+        }
+    }
+
+    fn identity_from_state(&self, state: &DynamicValue) -> Option<ResourceIdentityData> {
+        let vmid = state.get_u32(&AttributePath::new("vmid")).ok()?;
+        let mut identity_data = DynamicValue::new(Dynamic::Map(HashMap::new()));
+        identity_data
+            .set_number(&AttributePath::new("vmid"), vmid as f64)
+            .ok()?;
+        Some(ResourceIdentityData { identity_data })
+    }
+}
+
+impl ResourceWithConfigValidators for QemuVmResource {
+    fn config_validators(&self) -> Vec<Box<dyn ConfigValidator>> {
+        vec![
+            // A clone takes its drives from the template
+            ConflictingValidator::create(&["clone", "cdrom"]),
+            // A restore takes its drives from the backup
+            ConflictingValidator::create(&["restore_from", "clone"]),
+            ConflictingValidator::create(&["restore_from", "disk"]),
+            ConflictingValidator::create(&["restore_from", "efidisk"]),
+            ConflictingValidator::create(&["restore_from", "cloudinit_drive"]),
+            ConflictingValidator::create(&["restore_from", "attach_existing"]),
+            ConflictingValidator::create(&["restore_from", "cdrom"]),
+            AlsoRequiresValidator::create("full_clone", &["clone"]),
+        ]
+    }
+}
+
+#[async_trait]
+impl ResourceWithMoveState for QemuVmResource {
+    /// Takes over VMs from the telmate and bpg providers through `moved`
+    /// blocks
+    fn state_movers(&self) -> Vec<StateMover> {
+        super::resource_vm_move::state_movers()
+    }
+}
+
+#[async_trait]
+impl ResourceWithUpgradeState for QemuVmResource {
+    fn state_upgraders(&self) -> Vec<StateUpgrader> {
+        vec![
+            // memory and balloon used to be numbers, which moved into the
+            // memory block as dedicated and floating
+            StateUpgrader::new(0, |mut state| {
+                let root = state.get_map(&AttributePath::root()).unwrap_or_default();
+                let number = |name: &str| match root.get(name) {
+                    Some(Dynamic::Number(n)) => Some(*n),
+                    _ => None,
+                };
+                let (dedicated, floating) = (number("memory"), number("balloon"));
+                let memory = if dedicated.is_some() || floating.is_some() {
+                    let block = HashMap::from([
+                        (
+                            "dedicated".to_string(),
+                            dedicated.map_or(Dynamic::Null, Dynamic::Number),
+                        ),
+                        (
+                            "floating".to_string(),
+                            floating.map_or(Dynamic::Null, Dynamic::Number),
+                        ),
+                    ]);
+                    vec![Dynamic::Map(block)]
+                } else {
+                    vec![]
+                };
+                state
+                    .set_list(&AttributePath::new("memory"), memory)
+                    .map_err(|e| {
+                        vec![Diagnostic::error("Failed to upgrade state", e.to_string())]
+                    })?;
+                Ok(state)
+            }),
+            // cpu_type and numa moved into the cpu block, with the flags of
+            // the cpu_type string split out
+            StateUpgrader::new(1, |mut state| {
+                let root = state.get_map(&AttributePath::root()).unwrap_or_default();
+                let (cpu_type, flags) = match root.get("cpu_type") {
+                    Some(Dynamic::String(cpu_type)) => Self::parse_cpu_string(cpu_type),
+                    _ => (None, vec![]),
+                };
+                let numa = match root.get("numa") {
+                    Some(Dynamic::Bool(numa)) => Some(*numa),
+                    _ => None,
+                };
+                let cpu = if cpu_type.is_some() || !flags.is_empty() || numa.is_some() {
+                    let block = HashMap::from([
+                        (
+                            "type".to_string(),
+                            cpu_type.map_or(Dynamic::Null, Dynamic::String),
+                        ),
+                        (
+                            "flags".to_string(),
+                            if flags.is_empty() {
+                                Dynamic::Null
+                            } else {
+                                Dynamic::List(flags.into_iter().map(Dynamic::String).collect())
+                            },
+                        ),
+                        (
+                            "numa".to_string(),
+                            numa.map_or(Dynamic::Null, Dynamic::Bool),
+                        ),
+                    ]);
+                    vec![Dynamic::Map(block)]
+                } else {
+                    vec![]
+                };
+                state
+                    .set_list(&AttributePath::new("cpu"), cpu)
+                    .map_err(|e| {
+                        vec![Diagnostic::error("Failed to upgrade state", e.to_string())]
+                    })?;
+                Ok(state)
+            }),
+            // tags used to be the tags option as a string
+            StateUpgrader::new(2, |mut state| {
+                let path = AttributePath::new("tags");
+                let Ok(tags) = state.get_string(&path) else {
+                    return Ok(state);
+                };
+                let tags = Self::parse_tags(&tags);
+                let result = if tags.is_empty() {
+                    state.set_null(&path)
+                } else {
+                    state.set_list(&path, tags)
+                };
+                result.map_err(|e| {
+                    vec![Diagnostic::error("Failed to upgrade state", e.to_string())]
+                })?;
+                Ok(state)
+            }),
+            // agent used to be a number, 1 enabling the agent. A VM without
+            // the agent stored 0, so only 1 becomes an agent block.
+            StateUpgrader::new(3, |mut state| {
+                let enabled = state
+                    .get_number(&AttributePath::new("agent"))
+                    .is_ok_and(|agent| agent > 0.0);
+                let agent = if enabled {
+                    vec![Agent {
+                        enabled: Some(true),
+                        ..Default::default()
+                    }
+                    .to_dynamic()]
+                } else {
+                    vec![]
+                };
+                state
+                    .set_list(&AttributePath::new("agent"), agent)
+                    .map_err(|e| {
+                        vec![Diagnostic::error("Failed to upgrade state", e.to_string())]
+                    })?;
+                Ok(state)
+            }),
+            // sshkeys used to be one string with a key per line
+            StateUpgrader::new(4, |mut state| {
+                let path = AttributePath::new("sshkeys");
+                let keys: Vec<Dynamic> = state
+                    .get_string(&path)
+                    .unwrap_or_default()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .map(|key| Dynamic::String(key.to_string()))
+                    .collect();
+                let result = if keys.is_empty() {
+                    state.set_null(&path)
+                } else {
+                    state.set_list(&path, keys)
+                };
+                result.map_err(|e| {
+                    vec![Diagnostic::error("Failed to upgrade state", e.to_string())]
+                })?;
+                Ok(state)
+            }),
+        ]
+    }
+}
+
+#[async_trait]
+impl ResourceWithModifyPlan for QemuVmResource {
+    /// Fills in the provider's default node and storage, keeps generated MAC
+    /// addresses, and warns about
+    /// changes a running VM cannot pick up through hotplug, so they don't sit
+    /// pending until someone reboots the VM
+    async fn modify_plan(&self, _ctx: Context, request: ModifyPlanRequest) -> ModifyPlanResponse {
+        let mut response = ModifyPlanResponse {
+            planned_state: request.proposed_new_state,
+            requires_replace: vec![],
+            planned_private: request.prior_private,
+            diagnostics: vec![],
+        };
+        // Nothing runs before create and nothing is updated on destroy
+        if response.planned_state.is_null() {
+            return response;
+        }
+        let provider_data = self.provider_data_for(request.provider_meta.as_ref());
+        Self::plan_provider_defaults(
+            provider_data.as_ref(),
+            &request.config,
+            &mut response.planned_state,
+            &mut response.diagnostics,
+        );
+        if response.diagnostics.iter().any(Diagnostic::is_error) {
+            return response;
+        }
+        Self::plan_generated_macs(
+            &request.config,
+            &request.prior_state,
+            &mut response.planned_state,
+        );
+        if let Some(provider_data) = &provider_data {
+            Self::check_planned_storages(
+                provider_data,
+                &request.prior_state,
+                &response.planned_state,
+                &mut response.diagnostics,
+            )
+            .await;
+            Self::check_planned_bridges(
+                provider_data,
+                &request.prior_state,
+                &response.planned_state,
+                &mut response.diagnostics,
+            )
+            .await;
+        }
+        if request.prior_state.is_null() || response.diagnostics.iter().any(Diagnostic::is_error) {
+            return response;
+        }
+        // Skip the API calls when no change could need a restart
+        if Self::restart_changes(&request.prior_state, &response.planned_state, &[]).is_empty() {
+            return response;
+        }
+
+        let provider_data = match &provider_data {
+            Some(data) => data,
+            None => return response,
+        };
+        let node = match request
+            .prior_state
+            .get_string(&AttributePath::new("target_node"))
+        {
+            Ok(node) => node,
+            Err(_) => return response,
+        };
+        let vmid = match request.prior_state.get_u32(&AttributePath::new("vmid")) {
+            Ok(vmid) => vmid,
+            Err(_) => return response,
+        };
+
+        let qemu_api = provider_data.client.nodes().node(&node).qemu();
+        match qemu_api.get_status(vmid).await {
+            Ok(status) if status.status == "running" => {}
+            // A stopped VM picks everything up on its next start
+            _ => return response,
+        }
+        let hotplug = match qemu_api.get_config(vmid).await {
+            Ok(vm_config) => vm_config.hotplug,
+            Err(_) => return response,
+        };
+
+        let changes = Self::restart_changes(
+            &request.prior_state,
+            &response.planned_state,
+            &Self::hotplug_features(hotplug.as_deref()),
+        );
+        if changes.is_empty() {
+            return response;
+        }
+
+        let _ = response
+            .planned_state
+            .mark_unknown(&AttributePath::new("pending_changes"));
+        response
+            .diagnostics
+            .push(if Self::reboots_on_update(&request.config) {
+                Diagnostic::warning(
+                    "VM will reboot",
+                    format!(
+                        "VM {} reboots after the update to apply {}",
+                        vmid,
+                        changes.join(", ")
+                    ),
+                )
+            } else {
+                Diagnostic::warning(
+                    "Changes require a VM restart",
+                    format!(
+                        "Running VM {} cannot hotplug {} (hotplug: {}). The changes stay pending until the VM reboots. Set reboot_on_update to reboot it during apply.",
+                        vmid,
+                        changes.join(", "),
+                        hotplug.unwrap_or_else(|| DEFAULT_HOTPLUG.join(","))
+                    ),
+                )
+            });
+        response
+    }
 }
 
 #[async_trait]
@@ -2829,30 +6454,24 @@ impl ResourceWithImportState for QemuVmResource {
         request: ImportResourceStateRequest,
     ) -> ImportResourceStateResponse {
         let mut diagnostics = vec![];
-        let parts: Vec<&str> = request.id.split('/').collect();
-
-        if parts.len() != 2 {
-            diagnostics.push(Diagnostic::error(
-                "Invalid import ID",
-                "Import ID must be in the format 'node/vmid'",
-            ));
-            return ImportResourceStateResponse {
-                imported_resources: vec![],
-                diagnostics,
-                deferred: None,
-            };
-        }
-
-        let node = parts[0];
-        let vmid_str = parts[1];
-
-        let vmid = match vmid_str.parse::<u32>() {
-            Ok(vmid) => vmid,
-            Err(_) => {
-                diagnostics.push(Diagnostic::error(
-                    "Invalid VMID",
-                    "VMID must be a valid number",
-                ));
+        // Import blocks with `identity` send the identity instead of an ID
+        let target = match request
+            .identity
+            .as_ref()
+            .filter(|_| request.id.is_empty())
+            .map(|identity| identity.identity_data.get_u32(&AttributePath::new("vmid")))
+        {
+            Some(Ok(vmid)) => Ok(ImportTarget::Vmid(vmid)),
+            Some(Err(_)) => Err(Diagnostic::error(
+                "Invalid import identity",
+                "The identity of an imported VM must set vmid",
+            )),
+            None => Self::parse_import_id(&request.id),
+        };
+        let target = match target {
+            Ok(target) => target,
+            Err(diag) => {
+                diagnostics.push(diag);
                 return ImportResourceStateResponse {
                     imported_resources: vec![],
                     diagnostics,
@@ -2877,6 +6496,19 @@ impl ResourceWithImportState for QemuVmResource {
             }
         };
 
+        let (node, vmid) = match Self::resolve_import_target(provider_data, target).await {
+            Ok(resolved) => resolved,
+            Err(diag) => {
+                diagnostics.push(diag);
+                return ImportResourceStateResponse {
+                    imported_resources: vec![],
+                    diagnostics,
+                    deferred: None,
+                };
+            }
+        };
+        let node = node.as_str();
+
         let config = match provider_data
             .client
             .nodes()
@@ -2899,37 +6531,9 @@ impl ResourceWithImportState for QemuVmResource {
             }
         };
 
-        // Build state from the fetched configuration
-        let mut state = DynamicValue::new(Dynamic::Map(HashMap::new()));
-        let _ = state.set_string(&AttributePath::new("target_node"), node.to_string());
-        let _ = state.set_number(&AttributePath::new("vmid"), vmid as f64);
-
-        if let Some(name) = &config.name {
-            let _ = state.set_string(&AttributePath::new("name"), name.clone());
-        }
-        if let Some(cores) = config.cores {
-            let _ = state.set_number(&AttributePath::new("cores"), cores as f64);
-        }
-        if let Some(memory) = config.memory {
-            let _ = state.set_number(&AttributePath::new("memory"), memory as f64);
-        }
-        if let Some(sockets) = config.sockets {
-            let _ = state.set_number(&AttributePath::new("sockets"), sockets as f64);
-        }
-        if let Some(cpu) = &config.cpu {
-            let _ = state.set_string(&AttributePath::new("cpu"), cpu.clone());
-        }
-        if let Some(bios) = &config.bios {
-            let _ = state.set_string(&AttributePath::new("bios"), bios.clone());
-        }
-        if let Some(ostype) = &config.ostype {
-            let _ = state.set_string(&AttributePath::new("ostype"), ostype.clone());
-        }
-        if let Some(description) = &config.description {
-            let _ = state.set_string(&AttributePath::new("description"), description.clone());
-        }
-        if let Some(efidisk0) = &config.efidisk0 {
-            let _ = state.set_string(&AttributePath::new("efidisk0"), efidisk0.clone());
+        let mut state = Self::imported_state(node, vmid, &config);
+        if let Some(Some(pool)) = Self::current_pool(provider_data, vmid).await {
+            let _ = state.set_string(&AttributePath::new("pool"), pool);
         }
 
         ImportResourceStateResponse {
@@ -0,0 +1,530 @@
+//! Guest agent exec resource implementation
+
+use crate::api::nodes::{AgentExecRequest, AgentExecStatus};
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ModifyPlanRequest,
+    ModifyPlanResponse, ReadResourceRequest, ReadResourceResponse, Resource,
+    ResourceMetadataRequest, ResourceMetadataResponse, ResourceSchemaRequest,
+    ResourceSchemaResponse, ResourceWithConfigure, ResourceWithModifyPlan, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+/// How long the command may run inside the guest.
+const EXEC_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the guest agent is asked whether the command has exited
+const EXEC_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Arguments that run the command again when they change
+const RERUN_ATTRIBUTES: &[&str] = &[
+    "target_node",
+    "vmid",
+    "command",
+    "input",
+    "ignore_exit_code",
+    "triggers",
+];
+
+/// Arguments that point the resource at another VM
+const REPLACE_ATTRIBUTES: &[&str] = &["target_node", "vmid"];
+
+/// Attributes only known once the command has run
+const OUTPUT_ATTRIBUTES: &[&str] = &["exit_code", "stdout", "stderr"];
+
+/// Runs a command inside a VM through the QEMU guest agent and records its
+/// exit code and output, for bootstrap steps on guests without SSH access.
+/// The command runs on create and again whenever an argument or one of the
+/// triggers changes. Destroying the resource does not undo anything.
+#[derive(Default)]
+pub struct VmAgentExecResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl VmAgentExecResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn target(config: &DynamicValue) -> Result<(String, u32), Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("target_node"))
+            .map_err(|_| {
+                Diagnostic::error(
+                    "Missing target_node",
+                    "The 'target_node' attribute is required",
+                )
+            })?;
+        let vmid = config
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
+        Ok((node, vmid))
+    }
+
+    fn exec_request(config: &DynamicValue) -> AgentExecRequest {
+        let command = config
+            .get_list(&AttributePath::new("command"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|value| match value {
+                Dynamic::String(arg) => Some(arg),
+                _ => None,
+            })
+            .collect();
+        AgentExecRequest {
+            command,
+            input_data: config.get_string(&AttributePath::new("input")).ok(),
+        }
+    }
+
+    /// Polls the guest agent until the command has exited
+    async fn wait_for_exit(
+        ctx: &Context,
+        qemu_api: &crate::api::nodes::QemuApi<'_>,
+        vmid: u32,
+        pid: u64,
+        timeout: Duration,
+    ) -> Result<AgentExecStatus, String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = qemu_api
+                .agent_exec_status(vmid, pid)
+                .await
+                .map_err(|e| format!("Unable to check the command in VM {}: {}", vmid, e))?;
+            if status.has_exited() {
+                return Ok(status);
+            }
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "The command in VM {} did not exit within {} seconds",
+                    vmid,
+                    timeout.as_secs()
+                ));
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(EXEC_POLL_INTERVAL) => {}
+                _ = ctx.cancelled() => {
+                    return Err(format!(
+                        "Terraform was interrupted before the command in VM {} exited, it is still running as PID {}",
+                        vmid, pid
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Runs the command and writes its exit code and output into `state`.
+    /// Fails when the command could not run, was killed, or exited non-zero
+    /// without ignore_exit_code.
+    async fn exec(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        config: &DynamicValue,
+        state: &mut DynamicValue,
+        timeout: Duration,
+    ) -> Result<(), Diagnostic> {
+        let (node, vmid) = Self::target(config)?;
+        let request = Self::exec_request(config);
+        let qemu_api = provider_data.client.nodes().node(&node).qemu();
+
+        let pid = qemu_api.agent_exec(vmid, &request).await.map_err(|e| {
+            Diagnostic::error(
+                "Failed to run command",
+                format!(
+                    "Unable to start '{}' in VM {} on node '{}'. Make sure the QEMU guest agent is running in the guest: {}",
+                    request.command.join(" "),
+                    vmid,
+                    node,
+                    e
+                ),
+            )
+        })?;
+        let status = Self::wait_for_exit(ctx, &qemu_api, vmid, pid, timeout)
+            .await
+            .map_err(|e| Diagnostic::error("Failed to run command", e))?;
+
+        let stdout = status.out_data.clone().unwrap_or_default();
+        let stderr = status.err_data.clone().unwrap_or_default();
+        let _ = state.set_string(&AttributePath::new("stdout"), stdout);
+        let _ = state.set_string(&AttributePath::new("stderr"), stderr.clone());
+
+        let exit_code = match (status.exitcode, status.signal) {
+            (Some(exit_code), _) => exit_code,
+            (None, Some(signal)) => {
+                return Err(Diagnostic::error(
+                    "Command was killed",
+                    format!(
+                        "'{}' in VM {} was ended by signal {}",
+                        request.command.join(" "),
+                        vmid,
+                        signal
+                    ),
+                ))
+            }
+            (None, None) => 0,
+        };
+        let _ = state.set_number(&AttributePath::new("exit_code"), exit_code as f64);
+
+        let ignore_exit_code = config
+            .get_bool(&AttributePath::new("ignore_exit_code"))
+            .unwrap_or(false);
+        if exit_code != 0 && !ignore_exit_code {
+            return Err(Diagnostic::error(
+                "Command failed",
+                format!(
+                    "'{}' in VM {} exited with code {}: {}",
+                    request.command.join(" "),
+                    vmid,
+                    exit_code,
+                    stderr.trim()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Resource for VmAgentExecResource {
+    fn type_name(&self) -> &str {
+        "proxmox_vm_agent_exec"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Runs a command inside a QEMU VM through the guest agent and records its exit code and output. The command runs again whenever an argument or one of the triggers changes. Destroying this resource does not undo anything the command did")
+            .attribute(
+                AttributeBuilder::new("target_node", AttributeType::String)
+                    .description("The name of the Proxmox node hosting the VM")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The identifier of the VM. The QEMU guest agent must be running inside it")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("command", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("The program to run followed by its arguments, e.g. [\"sh\", \"-c\", \"systemctl restart app\"]. No shell is involved unless the command starts one")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("input", AttributeType::String)
+                    .description("Data passed to the command on stdin")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ignore_exit_code", AttributeType::Bool)
+                    .description("Record a non-zero exit code instead of failing. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("triggers", AttributeType::Map(Box::new(AttributeType::String)))
+                    .description("Arbitrary values that run the command again when they change")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("exit_code", AttributeType::Number)
+                    .description("Exit code of the last run")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("stdout", AttributeType::String)
+                    .description("Standard output of the last run. The guest agent truncates long output")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("stderr", AttributeType::String)
+                    .description("Standard error of the last run. The guest agent truncates long output")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create, Operation::Update]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = validate_integers(&request.config, &["vmid"]);
+
+        if let Ok(vmid) = request.config.get_number(&AttributePath::new("vmid")) {
+            if !(100.0..=999999999.0).contains(&vmid) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid VMID",
+                    "VMID must be between 100 and 999999999",
+                ));
+            }
+        }
+        if let Ok(command) = request.config.get_list(&AttributePath::new("command")) {
+            if command.is_empty() {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid command",
+                        "command needs at least the program to run",
+                    )
+                    .with_attribute(AttributePath::new("command")),
+                );
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, EXEC_TIMEOUT);
+        let mut new_state = request.planned_state;
+        let result = Self::exec(
+            &ctx,
+            provider_data,
+            &request.config,
+            &mut new_state,
+            timeout,
+        )
+        .await;
+
+        CreateResourceResponse {
+            new_state,
+            private: vec![],
+            diagnostics: result.err().into_iter().collect(),
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let (node, vmid) = match Self::target(&request.current_state) {
+            Ok(target) => target,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        // The output of a past run can't be read back. It only goes away
+        // with its VM.
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .qemu()
+            .get_status(vmid)
+            .await
+        {
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            _ => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).update(&ctx, EXEC_TIMEOUT);
+        let mut new_state = request.planned_state;
+        let result = Self::exec(
+            &ctx,
+            provider_data,
+            &request.config,
+            &mut new_state,
+            timeout,
+        )
+        .await;
+
+        UpdateResourceResponse {
+            new_state,
+            private: vec![],
+            diagnostics: result.err().into_iter().collect(),
+            new_identity: None,
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // A command that ran can't be taken back, removing the resource only
+        // drops it from state
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for VmAgentExecResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ResourceWithModifyPlan for VmAgentExecResource {
+    /// Plans the outputs as unknown whenever the command is going to run,
+    /// and replaces the resource when it points at another VM
+    async fn modify_plan(&self, _ctx: Context, request: ModifyPlanRequest) -> ModifyPlanResponse {
+        let mut response = ModifyPlanResponse {
+            planned_state: request.proposed_new_state,
+            requires_replace: vec![],
+            planned_private: request.prior_private,
+            diagnostics: vec![],
+        };
+        if response.planned_state.is_null() {
+            return response;
+        }
+
+        let changed = |name: &str| {
+            let path = AttributePath::new(name);
+            request.prior_state.get(&path).ok() != response.planned_state.get(&path).ok()
+        };
+        let reruns =
+            request.prior_state.is_null() || RERUN_ATTRIBUTES.iter().any(|name| changed(name));
+        let requires_replace = if request.prior_state.is_null() {
+            vec![]
+        } else {
+            REPLACE_ATTRIBUTES
+                .iter()
+                .filter(|name| changed(name))
+                .map(|name| AttributePath::new(name))
+                .collect()
+        };
+
+        response.requires_replace = requires_replace;
+        if reruns {
+            for name in OUTPUT_ATTRIBUTES {
+                let _ = response
+                    .planned_state
+                    .mark_unknown(&AttributePath::new(name));
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_vm_agent_exec_test.rs"]
+mod resource_vm_agent_exec_test;
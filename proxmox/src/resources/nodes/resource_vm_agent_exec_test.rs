@@ -0,0 +1,177 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+
+    fn exec_config(values: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert(
+            "target_node".to_string(),
+            Dynamic::String("pve".to_string()),
+        );
+        obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+        obj.insert(
+            "command".to_string(),
+            Dynamic::List(vec![
+                Dynamic::String("sh".to_string()),
+                Dynamic::String("-c".to_string()),
+                Dynamic::String("cloud-init status --wait".to_string()),
+            ]),
+        );
+        for name in ["input", "ignore_exit_code", "triggers"] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        for name in OUTPUT_ATTRIBUTES {
+            obj.insert(name.to_string(), Dynamic::Unknown);
+        }
+        for (name, value) in values {
+            obj.insert(name.to_string(), value.clone());
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> VmAgentExecResource {
+        VmAgentExecResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    async fn mock_exec(server: &mut Server, status: &str) -> (mockito::Mock, mockito::Mock) {
+        let exec = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/100/agent/exec")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"command": ["sh", "-c", "cloud-init status --wait"]}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"pid": 77}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let status = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/100/agent/exec-status")
+            .match_query(Matcher::UrlEncoded("pid".to_string(), "77".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": {}}}"#, status))
+            .create_async()
+            .await;
+        (exec, status)
+    }
+
+    async fn create(server: &Server, config: DynamicValue) -> CreateResourceResponse {
+        configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vm_agent_exec".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_create_records_exit_code_and_output() {
+        let mut server = Server::new_async().await;
+        let (exec, status) = mock_exec(
+            &mut server,
+            r#"{"exited": 1, "exitcode": 0, "out-data": "status: done\n"}"#,
+        )
+        .await;
+
+        let response = create(&server, exec_config(&[])).await;
+
+        exec.assert_async().await;
+        status.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        let state = &response.new_state;
+        assert_eq!(
+            state.get_number(&AttributePath::new("exit_code")).unwrap(),
+            0.0
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("stdout")).unwrap(),
+            "status: done\n"
+        );
+        assert_eq!(state.get_string(&AttributePath::new("stderr")).unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_create_fails_on_non_zero_exit_code() {
+        let mut server = Server::new_async().await;
+        let _mocks = mock_exec(
+            &mut server,
+            r#"{"exited": 1, "exitcode": 1, "err-data": "status: error\n"}"#,
+        )
+        .await;
+
+        let response = create(&server, exec_config(&[])).await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Command failed");
+        assert!(response.diagnostics[0].detail.contains("status: error"));
+    }
+
+    #[tokio::test]
+    async fn test_create_ignores_exit_code_when_asked() {
+        let mut server = Server::new_async().await;
+        let _mocks = mock_exec(&mut server, r#"{"exited": 1, "exitcode": 3}"#).await;
+
+        let response = create(
+            &server,
+            exec_config(&[("ignore_exit_code", Dynamic::Bool(true))]),
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            response
+                .new_state
+                .get_number(&AttributePath::new("exit_code"))
+                .unwrap(),
+            3.0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_plan_reruns_changed_command() {
+        let mut prior = exec_config(&[]);
+        for name in OUTPUT_ATTRIBUTES {
+            prior
+                .set_string(&AttributePath::new(name), String::new())
+                .unwrap();
+        }
+        let mut proposed = prior.clone();
+        proposed
+            .set_string(&AttributePath::new("input"), "hello".to_string())
+            .unwrap();
+
+        let response = VmAgentExecResource::new()
+            .modify_plan(
+                Context::new(),
+                ModifyPlanRequest {
+                    type_name: "proxmox_vm_agent_exec".to_string(),
+                    config: proposed.clone(),
+                    prior_state: prior,
+                    proposed_new_state: proposed,
+                    prior_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        for name in OUTPUT_ATTRIBUTES {
+            assert!(response
+                .planned_state
+                .is_unknown_at(&AttributePath::new(name)));
+        }
+        assert!(response.requires_replace.is_empty());
+    }
+}
@@ -0,0 +1,794 @@
+//! State translators for VMs managed by other Proxmox providers
+//!
+//! A `moved` block from a `telmate/proxmox` `proxmox_vm_qemu` or a
+//! `bpg/proxmox` `proxmox_virtual_environment_vm` hands over the VM without
+//! an import. Only what identifies the VM and what the configuration sets has
+//! to be right here: the refresh that follows the move reads everything else
+//! back from the API.
+
+use crate::api::propstring::PropertyString;
+use std::collections::HashMap;
+use tfplug::resource::StateMover;
+use tfplug::types::{Diagnostic, Dynamic, DynamicValue};
+
+type Object = HashMap<String, Dynamic>;
+
+/// Disk buses in the order Proxmox lists them
+const BUSES: [&str; 4] = ["ide", "sata", "scsi", "virtio"];
+
+/// Attributes the telmate provider names like proxmox_qemu_vm
+const TELMATE_ATTRIBUTES: &[&str] = &[
+    "vmid",
+    "name",
+    "target_node",
+    "clone",
+    "full_clone",
+    "bios",
+    "machine",
+    "kvm",
+    "onboot",
+    "boot",
+    "bootdisk",
+    "qemu_os",
+    "os_type",
+    "scsihw",
+    "hookscript",
+    "pool",
+    "cores",
+    "sockets",
+    "vcpus",
+    "ciuser",
+    "cipassword",
+    "ciupgrade",
+    "ipconfig0",
+    "ipconfig1",
+    "ipconfig2",
+    "ipconfig3",
+    "skip_ipv4",
+    "skip_ipv6",
+    "additional_wait",
+    "automatic_reboot",
+    "clone_wait",
+    "define_connection_info",
+    "tablet",
+    "protection",
+];
+
+/// Disk attributes the telmate provider names like proxmox_qemu_vm
+const TELMATE_DISK_ATTRIBUTES: &[&str] = &[
+    "storage",
+    "size",
+    "format",
+    "iops_r_burst",
+    "iops_r_burst_length",
+    "iops_r_concurrent",
+    "iops_wr_burst",
+    "iops_wr_burst_length",
+    "iops_wr_concurrent",
+    "mbps_r_burst",
+    "mbps_r_concurrent",
+    "mbps_wr_burst",
+    "mbps_wr_concurrent",
+];
+
+/// Disk flags, either booleans or the 0/1 and on/off of older telmate
+/// releases
+const DISK_FLAGS: &[(&str, &str)] = &[
+    ("discard", "discard"),
+    ("emulatessd", "emulatessd"),
+    ("ssd", "emulatessd"),
+    ("iothread", "iothread"),
+    ("backup", "backup"),
+    ("replicate", "replicate"),
+    ("readonly", "readonly"),
+];
+
+/// bpg disk speed limits and their proxmox_qemu_vm names
+const BPG_SPEED: &[(&str, &str)] = &[
+    ("iops_read", "iops_r_concurrent"),
+    ("iops_read_burstable", "iops_r_burst"),
+    ("iops_write", "iops_wr_concurrent"),
+    ("iops_write_burstable", "iops_wr_burst"),
+    ("read", "mbps_r_concurrent"),
+    ("read_burstable", "mbps_r_burst"),
+    ("write", "mbps_wr_concurrent"),
+    ("write_burstable", "mbps_wr_burst"),
+];
+
+/// The resources proxmox_qemu_vm can take state from
+pub(super) fn state_movers() -> Vec<StateMover> {
+    vec![
+        StateMover::new("telmate/proxmox", "proxmox_vm_qemu", |_, state| {
+            translate(state, from_telmate)
+        }),
+        StateMover::new(
+            "bpg/proxmox",
+            "proxmox_virtual_environment_vm",
+            |_, state| translate(state, from_bpg),
+        ),
+    ]
+}
+
+fn translate(
+    state: DynamicValue,
+    from: fn(&Object) -> Object,
+) -> Result<DynamicValue, Vec<Diagnostic>> {
+    let source = match &state.value {
+        Dynamic::Map(source) => source,
+        _ => {
+            return Err(vec![Diagnostic::error(
+                "Unable to move resource state",
+                "The source state is not an object",
+            )])
+        }
+    };
+
+    let target = from(source);
+    for required in ["vmid", "target_node"] {
+        if !matches!(target.get(required), Some(value) if *value != Dynamic::Null) {
+            return Err(vec![Diagnostic::error(
+                "Unable to move resource state",
+                format!(
+                    "The source state has no {}, so the VM cannot be identified. Import it instead.",
+                    required
+                ),
+            )]);
+        }
+    }
+    Ok(DynamicValue::new(Dynamic::Map(target)))
+}
+
+/// Maps `proxmox_vm_qemu` state of telmate/proxmox 2.x and 3.x
+fn from_telmate(source: &Object) -> Object {
+    let mut target = Object::new();
+    for name in TELMATE_ATTRIBUTES {
+        copy(source, name, &mut target, name);
+    }
+    copy(source, "desc", &mut target, "description");
+    copy(source, "description", &mut target, "description");
+    copy(source, "agent_timeout", &mut target, "agent_wait_timeout");
+    if number(source, "agent").is_some_and(|agent| agent > 0.0) {
+        let block = Object::from([("enabled".to_string(), Dynamic::Bool(true))]);
+        target.insert("agent".to_string(), blocks(vec![block]));
+    }
+
+    if let Some(tags) = string(source, "tags") {
+        target.insert("tags".to_string(), string_list(tags.split([';', ','])));
+    }
+    // telmate keeps all keys in one string, one per line
+    if let Some(sshkeys) = string(source, "sshkeys").filter(|keys| !keys.trim().is_empty()) {
+        target.insert("sshkeys".to_string(), string_list(sshkeys.lines()));
+    }
+    match string(source, "vm_state") {
+        Some(vm_state) => {
+            target.insert("start".to_string(), Dynamic::Bool(vm_state == "running"));
+        }
+        None => copy(source, "oncreate", &mut target, "start"),
+    }
+
+    if let Some(memory) = number(source, "memory") {
+        let mut block = Object::from([("dedicated".to_string(), Dynamic::Number(memory))]);
+        copy(source, "balloon", &mut block, "floating");
+        target.insert("memory".to_string(), blocks(vec![block]));
+    }
+
+    // 3.x groups the CPU settings in a block, before that they were flat
+    let mut cpu = Object::new();
+    match source.get("cpu") {
+        Some(Dynamic::String(cpu_type)) => {
+            cpu.insert("type".to_string(), Dynamic::String(cpu_type.clone()));
+        }
+        _ => {
+            if let Some(block) = first_block(source, "cpu") {
+                for (from, to) in [
+                    ("cores", "cores"),
+                    ("sockets", "sockets"),
+                    ("vcores", "vcpus"),
+                ] {
+                    copy(block, from, &mut target, to);
+                }
+                for name in ["type", "limit", "units", "affinity", "numa"] {
+                    copy(block, name, &mut cpu, name);
+                }
+            }
+        }
+    }
+    copy(source, "cpu_type", &mut cpu, "type");
+    copy(source, "numa", &mut cpu, "numa");
+    if !cpu.is_empty() {
+        target.insert("cpu".to_string(), blocks(vec![cpu]));
+    }
+
+    let networks = block_list(source, "network")
+        .into_iter()
+        .enumerate()
+        .map(|(index, network)| {
+            let id = number(network, "id").unwrap_or(index as f64);
+            let mut block = Object::from([("id".to_string(), Dynamic::Number(id))]);
+            for name in [
+                "model",
+                "bridge",
+                "firewall",
+                "macaddr",
+                "rate",
+                "queues",
+                "link_down",
+                "mtu",
+            ] {
+                copy(network, name, &mut block, name);
+            }
+            // -1 was the "no VLAN" marker
+            if let Some(tag) = number(network, "tag").filter(|tag| *tag > 0.0) {
+                block.insert("tag".to_string(), Dynamic::Number(tag));
+            }
+            block
+        })
+        .collect();
+    insert_blocks(&mut target, "network", networks);
+
+    let mut drives = TelmateDrives::default();
+    if let Some(disks) = first_block(source, "disks") {
+        for bus in BUSES {
+            let Some(slots) = first_block(disks, bus) else {
+                continue;
+            };
+            let mut names: Vec<&String> = slots.keys().collect();
+            names.sort();
+            for slot in names {
+                if let Some(drive) = first_block(slots, slot) {
+                    drives.add_slot(bus, slot, drive);
+                }
+            }
+        }
+    }
+    let mut next_slot: HashMap<String, usize> = HashMap::new();
+    for disk in block_list(source, "disk") {
+        drives.add_disk(disk, &mut next_slot);
+    }
+    insert_blocks(&mut target, "disk", drives.disks);
+    insert_blocks(&mut target, "cdrom", drives.cdroms);
+    insert_blocks(&mut target, "cloudinit_drive", drives.cloudinit);
+
+    let serials = block_list(source, "serial")
+        .into_iter()
+        .map(|serial| {
+            let mut block = Object::new();
+            copy(serial, "id", &mut block, "id");
+            match string(serial, "type") {
+                Some(device) if device != "socket" => {
+                    block.insert("type".to_string(), Dynamic::String("device".to_string()));
+                    block.insert("device".to_string(), Dynamic::String(device.to_string()));
+                }
+                _ => {
+                    block.insert("type".to_string(), Dynamic::String("socket".to_string()));
+                }
+            }
+            block
+        })
+        .collect();
+    insert_blocks(&mut target, "serial", serials);
+
+    copy_block(
+        source,
+        "vga",
+        &mut target,
+        "vga",
+        &[("type", "type"), ("memory", "memory")],
+    );
+    copy_block(
+        source,
+        "smbios",
+        &mut target,
+        "smbios",
+        &[
+            ("uuid", "uuid"),
+            ("serial", "serial"),
+            ("manufacturer", "manufacturer"),
+            ("product", "product"),
+            ("version", "version"),
+            ("sku", "sku"),
+            ("family", "family"),
+        ],
+    );
+    copy_block(
+        source,
+        "efidisk",
+        &mut target,
+        "efidisk",
+        &[("efitype", "efitype"), ("storage", "storage")],
+    );
+
+    // startup is a property string such as "order=1,up=30,down=60"
+    if let Some(startup) = string(source, "startup").filter(|s| !s.is_empty()) {
+        let mut block = Object::new();
+        for (key, value) in PropertyString::parse(startup, "").params() {
+            let name = match key {
+                "order" => "order",
+                "up" => "up_delay",
+                "down" => "down_delay",
+                _ => continue,
+            };
+            if let Ok(value) = value.trim().parse::<f64>() {
+                block.insert(name.to_string(), Dynamic::Number(value));
+            }
+        }
+        if !block.is_empty() {
+            target.insert("startup".to_string(), blocks(vec![block]));
+        }
+    }
+
+    target
+}
+
+/// Drives collected from either telmate disk layout
+#[derive(Default)]
+struct TelmateDrives {
+    disks: Vec<Object>,
+    cdroms: Vec<Object>,
+    cloudinit: Vec<Object>,
+}
+
+impl TelmateDrives {
+    /// A slot of the 3.x `disks` block, e.g. `scsi { scsi0 { disk { ... } } }`
+    fn add_slot(&mut self, bus: &str, slot: &str, drive: &Object) {
+        if let Some(disk) = first_block(drive, "disk") {
+            self.disks.push(telmate_disk(bus, slot, disk));
+        } else if let Some(cdrom) = first_block(drive, "cdrom") {
+            self.cdroms.push(cdrom_block(slot, string(cdrom, "iso")));
+        } else if let Some(cloudinit) = first_block(drive, "cloudinit") {
+            self.cloudinit.push(cloudinit_block(slot, cloudinit));
+        }
+    }
+
+    /// An entry of the flat `disk` list. 3.x names the slot ("scsi0") and
+    /// the kind of drive in `type`, 2.x has the bus in `type` and numbers
+    /// the disks per bus.
+    fn add_disk(&mut self, disk: &Object, next_slot: &mut HashMap<String, usize>) {
+        let kind = string(disk, "type").unwrap_or("disk");
+        if let Some(slot) = string(disk, "slot").filter(|slot| bus_of(slot).is_some()) {
+            match kind {
+                "cdrom" => self.cdroms.push(cdrom_block(slot, string(disk, "iso"))),
+                "cloudinit" => self.cloudinit.push(cloudinit_block(slot, disk)),
+                _ => self
+                    .disks
+                    .push(telmate_disk(bus_of(slot).unwrap_or(kind), slot, disk)),
+            }
+            return;
+        }
+
+        let counter = next_slot.entry(kind.to_string()).or_default();
+        let index = number(disk, "slot").map_or(*counter, |index| index as usize);
+        *counter = index + 1;
+        self.disks
+            .push(telmate_disk(kind, &format!("{}{}", kind, index), disk));
+    }
+}
+
+fn telmate_disk(bus: &str, slot: &str, disk: &Object) -> Object {
+    let mut block = Object::from([
+        ("slot".to_string(), Dynamic::String(slot.to_string())),
+        ("type".to_string(), Dynamic::String(bus.to_string())),
+    ]);
+    for name in TELMATE_DISK_ATTRIBUTES {
+        copy(disk, name, &mut block, name);
+    }
+    for (from, to) in DISK_FLAGS {
+        if let Some(flag) = disk.get(*from).and_then(flag) {
+            block.insert(to.to_string(), Dynamic::Bool(flag));
+        }
+    }
+    block
+}
+
+/// Maps `proxmox_virtual_environment_vm` state of bpg/proxmox
+fn from_bpg(source: &Object) -> Object {
+    let mut target = Object::new();
+    for (from, to) in [
+        ("vm_id", "vmid"),
+        ("name", "name"),
+        ("node_name", "target_node"),
+        ("description", "description"),
+        ("bios", "bios"),
+        ("machine", "machine"),
+        ("on_boot", "onboot"),
+        ("started", "start"),
+        ("tablet_device", "tablet"),
+        ("protection", "protection"),
+        ("pool_id", "pool"),
+        ("hook_script_file_id", "hookscript"),
+        ("scsi_hardware", "scsihw"),
+    ] {
+        copy(source, from, &mut target, to);
+    }
+    if let Some(Dynamic::List(tags)) = source.get("tags") {
+        target.insert("tags".to_string(), Dynamic::List(tags.clone()));
+    }
+    if let Some(Dynamic::List(order)) = source.get("boot_order") {
+        let order: Vec<&str> = order
+            .iter()
+            .filter_map(|device| match device {
+                Dynamic::String(device) => Some(device.as_str()),
+                _ => None,
+            })
+            .collect();
+        if !order.is_empty() {
+            target.insert(
+                "boot".to_string(),
+                Dynamic::String(format!("order={}", order.join(";"))),
+            );
+        }
+    }
+
+    // Without enabled the agent block is only there because bpg always
+    // keeps it in state
+    if let Some(agent) = first_block(source, "agent") {
+        if agent.get("enabled").and_then(flag).unwrap_or(false) {
+            let mut block = Object::from([("enabled".to_string(), Dynamic::Bool(true))]);
+            copy(agent, "trim", &mut block, "trim_cloned_disks");
+            copy(agent, "type", &mut block, "type");
+            target.insert("agent".to_string(), blocks(vec![block]));
+        }
+    }
+    if let Some(os) = first_block(source, "operating_system") {
+        copy(os, "type", &mut target, "qemu_os");
+    }
+    if let Some(clone) = first_block(source, "clone") {
+        copy(clone, "full", &mut target, "full_clone");
+    }
+
+    if let Some(cpu) = first_block(source, "cpu") {
+        copy(cpu, "cores", &mut target, "cores");
+        copy(cpu, "sockets", &mut target, "sockets");
+        if let Some(vcpus) = number(cpu, "hotplugged").filter(|n| *n > 0.0) {
+            target.insert("vcpus".to_string(), Dynamic::Number(vcpus));
+        }
+        let mut block = Object::new();
+        for name in ["type", "units", "limit", "affinity", "numa"] {
+            copy(cpu, name, &mut block, name);
+        }
+        if let Some(Dynamic::List(flags)) = cpu.get("flags") {
+            if !flags.is_empty() {
+                block.insert("flags".to_string(), Dynamic::List(flags.clone()));
+            }
+        }
+        target.insert("cpu".to_string(), blocks(vec![block]));
+    }
+
+    copy_block(
+        source,
+        "memory",
+        &mut target,
+        "memory",
+        &[
+            ("dedicated", "dedicated"),
+            ("floating", "floating"),
+            ("hugepages", "hugepages"),
+            ("keep_hugepages", "keep_hugepages"),
+        ],
+    );
+
+    let disks = block_list(source, "disk")
+        .into_iter()
+        .filter_map(|disk| {
+            let slot = string(disk, "interface")?;
+            let mut block = Object::from([
+                ("slot".to_string(), Dynamic::String(slot.to_string())),
+                (
+                    "type".to_string(),
+                    Dynamic::String(bus_of(slot).unwrap_or("scsi").to_string()),
+                ),
+            ]);
+            copy(disk, "datastore_id", &mut block, "storage");
+            copy(disk, "file_format", &mut block, "format");
+            copy(disk, "import_from", &mut block, "import_from");
+            // bpg sizes are whole GiB
+            if let Some(size) = number(disk, "size") {
+                block.insert("size".to_string(), Dynamic::String(format!("{}G", size)));
+            }
+            for (from, to) in [
+                ("discard", "discard"),
+                ("ssd", "emulatessd"),
+                ("iothread", "iothread"),
+                ("backup", "backup"),
+                ("replicate", "replicate"),
+            ] {
+                if let Some(flag) = disk.get(from).and_then(flag) {
+                    block.insert(to.to_string(), Dynamic::Bool(flag));
+                }
+            }
+            if let Some(speed) = first_block(disk, "speed") {
+                for (from, to) in BPG_SPEED {
+                    // 0 is bpg's "no limit"
+                    if let Some(limit) = number(speed, from).filter(|n| *n > 0.0) {
+                        block.insert(to.to_string(), Dynamic::Number(limit));
+                    }
+                }
+            }
+            Some(block)
+        })
+        .collect();
+    insert_blocks(&mut target, "disk", disks);
+
+    let networks = block_list(source, "network_device")
+        .into_iter()
+        .enumerate()
+        .filter(|(_, network)| network.get("enabled").and_then(flag).unwrap_or(true))
+        .map(|(index, network)| {
+            let mut block = Object::from([("id".to_string(), Dynamic::Number(index as f64))]);
+            for (from, to) in [
+                ("bridge", "bridge"),
+                ("model", "model"),
+                ("mac_address", "macaddr"),
+                ("firewall", "firewall"),
+                ("disconnected", "link_down"),
+            ] {
+                copy(network, from, &mut block, to);
+            }
+            for (from, to) in [
+                ("vlan_id", "tag"),
+                ("rate_limit", "rate"),
+                ("queues", "queues"),
+                ("mtu", "mtu"),
+            ] {
+                if let Some(value) = number(network, from).filter(|n| *n > 0.0) {
+                    block.insert(to.to_string(), Dynamic::Number(value));
+                }
+            }
+            block
+        })
+        .collect();
+    insert_blocks(&mut target, "network", networks);
+
+    let cdroms = block_list(source, "cdrom")
+        .into_iter()
+        .filter(|cdrom| cdrom.get("enabled").and_then(flag).unwrap_or(true))
+        .map(|cdrom| {
+            cdrom_block(
+                string(cdrom, "interface").unwrap_or("ide3"),
+                string(cdrom, "file_id"),
+            )
+        })
+        .collect();
+    insert_blocks(&mut target, "cdrom", cdroms);
+
+    if let Some(init) = first_block(source, "initialization") {
+        let mut drive = Object::from([(
+            "slot".to_string(),
+            Dynamic::String(string(init, "interface").unwrap_or("ide2").to_string()),
+        )]);
+        copy(init, "datastore_id", &mut drive, "storage");
+        target.insert("cloudinit_drive".to_string(), blocks(vec![drive]));
+        copy(init, "upgrade", &mut target, "ciupgrade");
+
+        if let Some(account) = first_block(init, "user_account") {
+            copy(account, "username", &mut target, "ciuser");
+            copy(account, "password", &mut target, "cipassword");
+            if let Some(Dynamic::List(keys)) = account.get("keys") {
+                let keys = string_list(keys.iter().filter_map(|key| match key {
+                    Dynamic::String(key) => Some(key.as_str()),
+                    _ => None,
+                }));
+                if !matches!(&keys, Dynamic::List(keys) if keys.is_empty()) {
+                    target.insert("sshkeys".to_string(), keys);
+                }
+            }
+        }
+
+        for (index, ip_config) in block_list(init, "ip_config").into_iter().enumerate() {
+            let mut parts = vec![];
+            for (family, ip, gw) in [("ipv4", "ip", "gw"), ("ipv6", "ip6", "gw6")] {
+                if let Some(config) = first_block(ip_config, family) {
+                    if let Some(address) = string(config, "address") {
+                        parts.push(format!("{}={}", ip, address));
+                    }
+                    if let Some(gateway) = string(config, "gateway").filter(|g| !g.is_empty()) {
+                        parts.push(format!("{}={}", gw, gateway));
+                    }
+                }
+            }
+            if !parts.is_empty() {
+                target.insert(
+                    format!("ipconfig{}", index),
+                    Dynamic::String(parts.join(",")),
+                );
+            }
+        }
+    }
+
+    let serials = block_list(source, "serial_device")
+        .into_iter()
+        .enumerate()
+        .map(|(index, serial)| {
+            let mut block = Object::from([("id".to_string(), Dynamic::Number(index as f64))]);
+            match string(serial, "device") {
+                Some(device) if device != "socket" => {
+                    block.insert("type".to_string(), Dynamic::String("device".to_string()));
+                    block.insert("device".to_string(), Dynamic::String(device.to_string()));
+                }
+                _ => {
+                    block.insert("type".to_string(), Dynamic::String("socket".to_string()));
+                }
+            }
+            block
+        })
+        .collect();
+    insert_blocks(&mut target, "serial", serials);
+
+    copy_block(
+        source,
+        "vga",
+        &mut target,
+        "vga",
+        &[("type", "type"), ("memory", "memory")],
+    );
+    copy_block(
+        source,
+        "efi_disk",
+        &mut target,
+        "efidisk",
+        &[
+            ("type", "efitype"),
+            ("datastore_id", "storage"),
+            ("file_format", "format"),
+            ("pre_enrolled_keys", "pre_enrolled_keys"),
+        ],
+    );
+    copy_block(
+        source,
+        "startup",
+        &mut target,
+        "startup",
+        &[
+            ("order", "order"),
+            ("up_delay", "up_delay"),
+            ("down_delay", "down_delay"),
+        ],
+    );
+    copy_block(
+        source,
+        "smbios",
+        &mut target,
+        "smbios",
+        &[
+            ("uuid", "uuid"),
+            ("serial", "serial"),
+            ("manufacturer", "manufacturer"),
+            ("product", "product"),
+            ("version", "version"),
+            ("sku", "sku"),
+            ("family", "family"),
+        ],
+    );
+
+    target
+}
+
+fn cdrom_block(slot: &str, iso: Option<&str>) -> Object {
+    Object::from([
+        ("slot".to_string(), Dynamic::String(slot.to_string())),
+        (
+            "iso".to_string(),
+            Dynamic::String(
+                iso.filter(|iso| !iso.is_empty())
+                    .unwrap_or("none")
+                    .to_string(),
+            ),
+        ),
+    ])
+}
+
+fn cloudinit_block(slot: &str, drive: &Object) -> Object {
+    let mut block = Object::from([("slot".to_string(), Dynamic::String(slot.to_string()))]);
+    copy(drive, "storage", &mut block, "storage");
+    block
+}
+
+/// The bus of a slot name such as "virtio1"
+fn bus_of(slot: &str) -> Option<&'static str> {
+    BUSES.into_iter().find(|bus| {
+        slot.strip_prefix(bus)
+            .is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Copies a value unless it is null
+fn copy(source: &Object, from: &str, target: &mut Object, to: &str) {
+    match source.get(from) {
+        None | Some(Dynamic::Null) => {}
+        Some(value) => {
+            target.insert(to.to_string(), value.clone());
+        }
+    }
+}
+
+/// Copies the first block of `from` into a single `to` block, renaming the
+/// given attributes
+fn copy_block(
+    source: &Object,
+    from: &str,
+    target: &mut Object,
+    to: &str,
+    attributes: &[(&str, &str)],
+) {
+    let Some(block) = first_block(source, from) else {
+        return;
+    };
+    let mut copied = Object::new();
+    for (from, to) in attributes {
+        copy(block, from, &mut copied, to);
+    }
+    if !copied.is_empty() {
+        target.insert(to.to_string(), blocks(vec![copied]));
+    }
+}
+
+fn string<'a>(source: &'a Object, name: &str) -> Option<&'a str> {
+    match source.get(name) {
+        Some(Dynamic::String(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn number(source: &Object, name: &str) -> Option<f64> {
+    match source.get(name) {
+        Some(Dynamic::Number(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+/// Reads a flag stored as bool, 0/1 or on/off
+fn flag(value: &Dynamic) -> Option<bool> {
+    match value {
+        Dynamic::Bool(value) => Some(*value),
+        Dynamic::Number(value) => Some(*value != 0.0),
+        Dynamic::String(value) => match value.as_str() {
+            "1" | "on" | "true" => Some(true),
+            "0" | "off" | "ignore" | "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The objects of a list (or single object) of nested blocks
+fn block_list<'a>(source: &'a Object, name: &str) -> Vec<&'a Object> {
+    match source.get(name) {
+        Some(Dynamic::List(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Dynamic::Map(item) => Some(item),
+                _ => None,
+            })
+            .collect(),
+        Some(Dynamic::Map(item)) => vec![item],
+        _ => vec![],
+    }
+}
+
+fn first_block<'a>(source: &'a Object, name: &str) -> Option<&'a Object> {
+    block_list(source, name).into_iter().next()
+}
+
+fn blocks(items: Vec<Object>) -> Dynamic {
+    Dynamic::List(items.into_iter().map(Dynamic::Map).collect())
+}
+
+fn insert_blocks(target: &mut Object, name: &str, items: Vec<Object>) {
+    if !items.is_empty() {
+        target.insert(name.to_string(), blocks(items));
+    }
+}
+
+fn string_list<'a>(values: impl Iterator<Item = &'a str>) -> Dynamic {
+    Dynamic::List(
+        values
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| Dynamic::String(value.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+#[path = "./resource_vm_move_test.rs"]
+mod resource_vm_move_test;
@@ -0,0 +1,263 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::resources::nodes::QemuVmResource;
+    use tfplug::context::Context;
+    use tfplug::resource::{MoveResourceStateRequest, ResourceWithMoveState};
+    use tfplug::types::{AttributePath, RawState};
+
+    fn object(json: &str) -> Object {
+        match DynamicValue::decode_json(json.as_bytes()).unwrap().value {
+            Dynamic::Map(object) => object,
+            other => panic!("expected an object, got {:?}", other),
+        }
+    }
+
+    fn block<'a>(target: &'a Object, name: &str, index: usize) -> &'a Object {
+        block_list(target, name)[index]
+    }
+
+    fn text(value: &str) -> Dynamic {
+        Dynamic::String(value.to_string())
+    }
+
+    #[test]
+    fn test_from_telmate_v3() {
+        let target = from_telmate(&object(
+            r#"{
+                "vmid": 120, "name": "web", "target_node": "pve1", "tags": "web;prod",
+                "vm_state": "running", "memory": 4096, "balloon": 1024, "desc": "frontend", "agent": 1,
+                "cpu": [{"cores": 4, "sockets": 1, "vcores": 0, "type": "host", "numa": true}],
+                "startup": "order=2,up=30", "ipconfig0": "ip=dhcp",
+                "sshkeys": "ssh-ed25519 AAAA a\n\nssh-ed25519 BBBB b\n",
+                "network": [{"id": 0, "model": "virtio", "bridge": "vmbr0", "tag": -1, "macaddr": "BC:24:11:00:00:01"}],
+                "disks": [{
+                    "ide": [{"ide2": [{"cloudinit": [{"storage": "local-lvm"}]}]}],
+                    "scsi": [{
+                        "scsi0": [{"disk": [{"storage": "local-lvm", "size": "32G", "discard": true, "iothread": true}]}],
+                        "scsi1": [{"cdrom": [{"iso": "local:iso/debian.iso"}]}]
+                    }]
+                }],
+                "serial": [{"id": 0, "type": "socket"}, {"id": 1, "type": "/dev/ttyS0"}]
+            }"#,
+        ));
+
+        assert_eq!(target["vmid"], Dynamic::Number(120.0));
+        assert_eq!(target["target_node"], text("pve1"));
+        assert_eq!(target["description"], text("frontend"));
+        assert_eq!(
+            target["tags"],
+            Dynamic::List(vec![text("web"), text("prod")])
+        );
+        assert_eq!(
+            target["sshkeys"],
+            Dynamic::List(vec![text("ssh-ed25519 AAAA a"), text("ssh-ed25519 BBBB b")])
+        );
+        assert_eq!(target["start"], Dynamic::Bool(true));
+        assert_eq!(block(&target, "agent", 0)["enabled"], Dynamic::Bool(true));
+        assert_eq!(target["cores"], Dynamic::Number(4.0));
+        assert_eq!(block(&target, "cpu", 0)["type"], text("host"));
+        assert_eq!(block(&target, "cpu", 0)["numa"], Dynamic::Bool(true));
+        assert_eq!(
+            block(&target, "memory", 0)["dedicated"],
+            Dynamic::Number(4096.0)
+        );
+        assert_eq!(
+            block(&target, "memory", 0)["floating"],
+            Dynamic::Number(1024.0)
+        );
+        assert_eq!(block(&target, "startup", 0)["order"], Dynamic::Number(2.0));
+        assert_eq!(
+            block(&target, "startup", 0)["up_delay"],
+            Dynamic::Number(30.0)
+        );
+
+        let network = block(&target, "network", 0);
+        assert_eq!(network["bridge"], text("vmbr0"));
+        assert!(!network.contains_key("tag"));
+
+        let disk = block(&target, "disk", 0);
+        assert_eq!(disk["slot"], text("scsi0"));
+        assert_eq!(disk["type"], text("scsi"));
+        assert_eq!(disk["size"], text("32G"));
+        assert_eq!(disk["discard"], Dynamic::Bool(true));
+        assert_eq!(block(&target, "cdrom", 0)["slot"], text("scsi1"));
+        assert_eq!(
+            block(&target, "cdrom", 0)["iso"],
+            text("local:iso/debian.iso")
+        );
+        assert_eq!(block(&target, "cloudinit_drive", 0)["slot"], text("ide2"));
+
+        assert_eq!(block(&target, "serial", 0)["type"], text("socket"));
+        assert_eq!(block(&target, "serial", 1)["type"], text("device"));
+        assert_eq!(block(&target, "serial", 1)["device"], text("/dev/ttyS0"));
+    }
+
+    #[test]
+    fn test_from_telmate_v2_flat_disks() {
+        let target = from_telmate(&object(
+            r#"{
+                "vmid": 121, "target_node": "pve1", "cpu": "kvm64", "oncreate": false, "agent": 0,
+                "disk": [
+                    {"type": "scsi", "storage": "local-lvm", "size": "10G", "discard": "on", "ssd": 1},
+                    {"type": "scsi", "storage": "local-lvm", "size": "20G", "iothread": 0},
+                    {"type": "virtio", "slot": 3, "storage": "ceph", "size": "5G"}
+                ],
+                "network": [{"model": "e1000", "bridge": "vmbr1", "tag": 20}]
+            }"#,
+        ));
+
+        assert_eq!(block(&target, "cpu", 0)["type"], text("kvm64"));
+        assert_eq!(target["start"], Dynamic::Bool(false));
+        assert!(!target.contains_key("agent"));
+        let slots: Vec<&Dynamic> = block_list(&target, "disk")
+            .iter()
+            .map(|disk| &disk["slot"])
+            .collect();
+        assert_eq!(
+            slots,
+            vec![&text("scsi0"), &text("scsi1"), &text("virtio3")]
+        );
+        assert_eq!(block(&target, "disk", 0)["discard"], Dynamic::Bool(true));
+        assert_eq!(block(&target, "disk", 0)["emulatessd"], Dynamic::Bool(true));
+        assert_eq!(block(&target, "disk", 1)["iothread"], Dynamic::Bool(false));
+        assert_eq!(block(&target, "network", 0)["id"], Dynamic::Number(0.0));
+        assert_eq!(block(&target, "network", 0)["tag"], Dynamic::Number(20.0));
+    }
+
+    #[test]
+    fn test_from_bpg() {
+        let target = from_bpg(&object(
+            r#"{
+                "vm_id": 130, "name": "db", "node_name": "pve2", "tags": ["db"], "started": true,
+                "on_boot": true, "boot_order": ["scsi0", "net0"],
+                "agent": [{"enabled": true, "trim": true, "type": "isa", "timeout": "15m"}], "operating_system": [{"type": "l26"}],
+                "cpu": [{"cores": 2, "sockets": 1, "type": "x86-64-v2-AES", "flags": [], "hotplugged": 0}],
+                "memory": [{"dedicated": 2048, "floating": 0, "shared": 0}],
+                "disk": [{"interface": "virtio0", "datastore_id": "local-zfs", "size": 40, "ssd": false,
+                          "discard": "ignore", "speed": [{"iops_read": 0, "read": 100}]}],
+                "network_device": [{"bridge": "vmbr0", "model": "virtio", "mac_address": "BC:24:11:00:00:02",
+                                    "vlan_id": 0, "rate_limit": 0, "mtu": 0, "queues": 0, "disconnected": false, "enabled": true}],
+                "initialization": [{
+                    "datastore_id": "local-lvm", "interface": "ide2",
+                    "user_account": [{"username": "debian", "password": "secret", "keys": ["ssh-ed25519 AAAA a", "ssh-ed25519 BBBB b"]}],
+                    "ip_config": [
+                        {"ipv4": [{"address": "10.0.0.5/24", "gateway": "10.0.0.1"}], "ipv6": []},
+                        {"ipv4": [{"address": "dhcp", "gateway": ""}]}
+                    ]
+                }],
+                "cdrom": [{"enabled": true, "file_id": "none", "interface": "ide3"}],
+                "efi_disk": [{"datastore_id": "local-lvm", "type": "4m", "pre_enrolled_keys": false}]
+            }"#,
+        ));
+
+        assert_eq!(target["vmid"], Dynamic::Number(130.0));
+        assert_eq!(target["target_node"], text("pve2"));
+        assert_eq!(target["start"], Dynamic::Bool(true));
+        assert_eq!(target["boot"], text("order=scsi0;net0"));
+        let agent = block(&target, "agent", 0);
+        assert_eq!(agent["enabled"], Dynamic::Bool(true));
+        assert_eq!(agent["trim_cloned_disks"], Dynamic::Bool(true));
+        assert_eq!(agent["type"], text("isa"));
+        assert!(!agent.contains_key("timeout"));
+        assert_eq!(target["qemu_os"], text("l26"));
+        assert!(!target.contains_key("vcpus"));
+        assert!(!block(&target, "cpu", 0).contains_key("flags"));
+        assert_eq!(
+            block(&target, "memory", 0)["dedicated"],
+            Dynamic::Number(2048.0)
+        );
+
+        let disk = block(&target, "disk", 0);
+        assert_eq!(disk["slot"], text("virtio0"));
+        assert_eq!(disk["type"], text("virtio"));
+        assert_eq!(disk["storage"], text("local-zfs"));
+        assert_eq!(disk["size"], text("40G"));
+        assert_eq!(disk["discard"], Dynamic::Bool(false));
+        assert_eq!(disk["mbps_r_concurrent"], Dynamic::Number(100.0));
+        assert!(!disk.contains_key("iops_r_concurrent"));
+
+        let network = block(&target, "network", 0);
+        assert_eq!(network["macaddr"], text("BC:24:11:00:00:02"));
+        assert!(!network.contains_key("tag"));
+        assert!(!network.contains_key("mtu"));
+
+        assert_eq!(target["ciuser"], text("debian"));
+        assert_eq!(
+            target["sshkeys"],
+            Dynamic::List(vec![text("ssh-ed25519 AAAA a"), text("ssh-ed25519 BBBB b")])
+        );
+        assert_eq!(target["ipconfig0"], text("ip=10.0.0.5/24,gw=10.0.0.1"));
+        assert_eq!(target["ipconfig1"], text("ip=dhcp"));
+        assert_eq!(
+            block(&target, "cloudinit_drive", 0)["storage"],
+            text("local-lvm")
+        );
+        assert_eq!(block(&target, "cdrom", 0)["iso"], text("none"));
+        assert_eq!(block(&target, "efidisk", 0)["efitype"], text("4m"));
+    }
+
+    async fn move_from(
+        provider: &str,
+        type_name: &str,
+        json: &str,
+    ) -> tfplug::resource::MoveResourceStateResponse {
+        QemuVmResource::new()
+            .move_state(
+                Context::new(),
+                MoveResourceStateRequest {
+                    source_provider_address: provider.to_string(),
+                    source_type_name: type_name.to_string(),
+                    source_schema_version: 0,
+                    source_state: RawState {
+                        json: Some(json.as_bytes().to_vec()),
+                        flatmap: None,
+                    },
+                    target_type_name: "proxmox_qemu_vm".to_string(),
+                    source_private: vec![],
+                },
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_move_state_conforms_to_schema() {
+        let response = move_from(
+            "registry.terraform.io/telmate/proxmox",
+            "proxmox_vm_qemu",
+            r#"{"id": "pve1/qemu/120", "vmid": 120, "name": "web", "target_node": "pve1", "hastate": "started"}"#,
+        )
+        .await;
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.target_state;
+        assert_eq!(
+            state.get_number(&AttributePath::new("vmid")).unwrap(),
+            120.0
+        );
+        let Dynamic::Map(values) = &state.value else {
+            panic!("state must be an object");
+        };
+        assert!(!values.contains_key("hastate"));
+        assert_eq!(values["pool"], Dynamic::Null);
+        assert_eq!(values["disk"], Dynamic::List(vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_move_state_requires_vm_identity() {
+        let response = move_from(
+            "registry.terraform.io/bpg/proxmox",
+            "proxmox_virtual_environment_vm",
+            r#"{"vm_id": 130, "name": "db"}"#,
+        )
+        .await;
+
+        assert!(response.target_state.is_null());
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "Unable to move resource state"
+        );
+        assert!(response.diagnostics[0].detail.contains("target_node"));
+    }
+}
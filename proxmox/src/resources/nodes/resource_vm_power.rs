@@ -0,0 +1,460 @@
+//! VM power action resource implementation
+
+use crate::resources::diagnostics::task_cancelled_warning;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ModifyPlanRequest,
+    ModifyPlanResponse, ReadResourceRequest, ReadResourceResponse, Resource,
+    ResourceMetadataRequest, ResourceMetadataResponse, ResourceSchemaRequest,
+    ResourceSchemaResponse, ResourceWithConfigure, ResourceWithModifyPlan, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::{validate_integers, StringOneOfValidator};
+
+/// How long an action may take until the VM reaches the status it leads to.
+const POWER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the VM status is checked while waiting for an action
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Actions the resource can run
+const POWER_ACTIONS: &[&str] = &["start", "shutdown", "reboot", "reset"];
+
+/// Arguments that run the action again when they change
+const RERUN_ATTRIBUTES: &[&str] = &["target_node", "vmid", "action", "triggers"];
+
+/// Arguments that point the resource at another VM
+const REPLACE_ATTRIBUTES: &[&str] = &["target_node", "vmid"];
+
+/// Runs a power action on an existing VM and waits until the VM reaches
+/// the status the action leads to. The action runs on create and again
+/// whenever an argument or one of the triggers changes, so rolling restarts
+/// can be driven by Terraform without touching the VM definition.
+/// Destroying the resource leaves the VM as it is.
+#[derive(Default)]
+pub struct VmPowerResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl VmPowerResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn target(config: &DynamicValue) -> Result<(String, u32, String), Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("target_node"))
+            .map_err(|_| {
+                Diagnostic::error(
+                    "Missing target_node",
+                    "The 'target_node' attribute is required",
+                )
+            })?;
+        let vmid = config
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
+        let action = config
+            .get_string(&AttributePath::new("action"))
+            .map_err(|_| {
+                Diagnostic::error("Missing action", "The 'action' attribute is required")
+            })?;
+        Ok((node, vmid, action))
+    }
+
+    /// Status Proxmox reports once `action` has finished
+    fn expected_status(action: &str) -> &'static str {
+        match action {
+            "shutdown" => "stopped",
+            _ => "running",
+        }
+    }
+
+    /// Runs the configured action, waits for its task and then for the VM
+    /// to report the expected status. Returns that status.
+    async fn power(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        config: &DynamicValue,
+        timeout: Duration,
+    ) -> Result<String, Vec<Diagnostic>> {
+        let (node, vmid, action) = Self::target(config).map_err(|diag| vec![diag])?;
+        let node_api = provider_data.client.nodes().node(&node);
+        let qemu_api = node_api.qemu();
+        let deadline = Instant::now() + timeout;
+
+        let started = match action.as_str() {
+            "start" => qemu_api.start(vmid).await,
+            "shutdown" => qemu_api.shutdown(vmid).await,
+            "reboot" => qemu_api.reboot(vmid).await,
+            "reset" => qemu_api.reset(vmid).await,
+            _ => {
+                return Err(vec![Diagnostic::error(
+                    "Invalid action",
+                    format!("action must be one of: {}", POWER_ACTIONS.join(", ")),
+                )])
+            }
+        };
+        let failed = |e: crate::api::ApiError| {
+            Diagnostic::error(
+                "Failed to change VM power state",
+                format!("Unable to {} VM {} on node '{}': {}", action, vmid, node, e),
+            )
+        };
+        let upid = started.map_err(|e| vec![failed(e)])?;
+        node_api
+            .wait_for_task(&upid, timeout, ctx.cancelled())
+            .await
+            .map_err(|e| {
+                let warning = task_cancelled_warning(&e);
+                std::iter::once(failed(e))
+                    .chain(warning)
+                    .collect::<Vec<_>>()
+            })?;
+
+        let expected = Self::expected_status(&action);
+        loop {
+            if let Ok(status) = qemu_api.get_status(vmid).await {
+                if status.status == expected {
+                    return Ok(status.status);
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(vec![Diagnostic::error(
+                    "Timed out waiting for VM",
+                    format!(
+                        "VM {} on node '{}' was not {} within {} seconds after {}",
+                        vmid,
+                        node,
+                        expected,
+                        timeout.as_secs(),
+                        action
+                    ),
+                )]);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(STATUS_POLL_INTERVAL) => {}
+                _ = ctx.cancelled() => {
+                    return Err(vec![Diagnostic::error(
+                        "Stopped waiting for VM",
+                        format!(
+                            "Terraform was interrupted before VM {} on node '{}' was {} after {}",
+                            vmid, node, expected, action
+                        ),
+                    )]);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Resource for VmPowerResource {
+    fn type_name(&self) -> &str {
+        "proxmox_vm_power"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Starts, shuts down, reboots or resets an existing QEMU VM and waits for it to reach the resulting status. The action runs again whenever an argument or one of the triggers changes. Destroying this resource leaves the VM as it is")
+            .attribute(
+                AttributeBuilder::new("target_node", AttributeType::String)
+                    .description("The name of the Proxmox node hosting the VM")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The identifier of the VM")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("action", AttributeType::String)
+                    .description("The action to run: start, shutdown, reboot or reset")
+                    .required()
+                    .validator(StringOneOfValidator::create(
+                        POWER_ACTIONS.iter().map(|action| action.to_string()).collect(),
+                    ))
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("triggers", AttributeType::Map(Box::new(AttributeType::String)))
+                    .description("Arbitrary values that run the action again when they change")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("status", AttributeType::String)
+                    .description("Status of the VM once the last action finished")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create, Operation::Update]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = validate_integers(&request.config, &["vmid"]);
+
+        if let Ok(vmid) = request.config.get_number(&AttributePath::new("vmid")) {
+            if !(100.0..=999999999.0).contains(&vmid) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid VMID",
+                    "VMID must be between 100 and 999999999",
+                ));
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, POWER_TIMEOUT);
+        match Self::power(&ctx, provider_data, &request.config, timeout).await {
+            Ok(status) => {
+                let mut new_state = request.planned_state;
+                let _ = new_state.set_string(&AttributePath::new("status"), status);
+                CreateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                }
+            }
+            Err(diagnostics) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics,
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let (node, vmid, _) = match Self::target(&request.current_state) {
+            Ok(target) => target,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        // An action has nothing to read back. It only goes away with its VM.
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .qemu()
+            .get_status(vmid)
+            .await
+        {
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            _ => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).update(&ctx, POWER_TIMEOUT);
+        match Self::power(&ctx, provider_data, &request.config, timeout).await {
+            Ok(status) => {
+                let mut new_state = request.planned_state;
+                let _ = new_state.set_string(&AttributePath::new("status"), status);
+                UpdateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                    new_identity: None,
+                }
+            }
+            Err(diagnostics) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // The VM keeps its power state, removing the resource only drops it
+        // from state
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for VmPowerResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ResourceWithModifyPlan for VmPowerResource {
+    /// Plans status as unknown whenever the action is going to run, and
+    /// replaces the resource when it points at another VM
+    async fn modify_plan(&self, _ctx: Context, request: ModifyPlanRequest) -> ModifyPlanResponse {
+        let mut response = ModifyPlanResponse {
+            planned_state: request.proposed_new_state,
+            requires_replace: vec![],
+            planned_private: request.prior_private,
+            diagnostics: vec![],
+        };
+        if response.planned_state.is_null() {
+            return response;
+        }
+
+        let changed = |name: &str| {
+            let path = AttributePath::new(name);
+            request.prior_state.get(&path).ok() != response.planned_state.get(&path).ok()
+        };
+        let reruns =
+            request.prior_state.is_null() || RERUN_ATTRIBUTES.iter().any(|name| changed(name));
+        let requires_replace = if request.prior_state.is_null() {
+            vec![]
+        } else {
+            REPLACE_ATTRIBUTES
+                .iter()
+                .filter(|name| changed(name))
+                .map(|name| AttributePath::new(name))
+                .collect()
+        };
+
+        response.requires_replace = requires_replace;
+        if reruns {
+            let _ = response
+                .planned_state
+                .mark_unknown(&AttributePath::new("status"));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_vm_power_test.rs"]
+mod resource_vm_power_test;
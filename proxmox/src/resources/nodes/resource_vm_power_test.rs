@@ -0,0 +1,221 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::Dynamic;
+
+    const UPID: &str = "UPID:pve:00001234:00000000:65000000:qmreboot:100:root@pam:";
+
+    fn power_config(action: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert(
+            "target_node".to_string(),
+            Dynamic::String("pve".to_string()),
+        );
+        obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+        obj.insert("action".to_string(), Dynamic::String(action.to_string()));
+        obj.insert("triggers".to_string(), Dynamic::Null);
+        obj.insert("status".to_string(), Dynamic::Unknown);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> VmPowerResource {
+        VmPowerResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    async fn mock_task(server: &mut Server, exitstatus: &str) -> mockito::Mock {
+        server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "{}"}}}}"#,
+                UPID, exitstatus
+            ))
+            .create_async()
+            .await
+    }
+
+    async fn create(server: &Server, config: DynamicValue) -> CreateResourceResponse {
+        configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vm_power".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+    }
+
+    #[test]
+    fn test_expected_status() {
+        assert_eq!(VmPowerResource::expected_status("start"), "running");
+        assert_eq!(VmPowerResource::expected_status("reboot"), "running");
+        assert_eq!(VmPowerResource::expected_status("reset"), "running");
+        assert_eq!(VmPowerResource::expected_status("shutdown"), "stopped");
+    }
+
+    #[tokio::test]
+    async fn test_create_waits_for_status() {
+        let mut server = Server::new_async().await;
+        let reboot = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/100/status/reboot")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let task = mock_task(&mut server, "OK").await;
+        let status = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/100/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"status": "running"}}"#)
+            .create_async()
+            .await;
+
+        let response = create(&server, power_config("reboot")).await;
+
+        reboot.assert_async().await;
+        task.assert_async().await;
+        status.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("status"))
+                .unwrap(),
+            "running"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_reports_failed_task() {
+        let mut server = Server::new_async().await;
+        let _shutdown = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/100/status/shutdown")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .create_async()
+            .await;
+        let _task = mock_task(&mut server, "VM quit/powerdown failed").await;
+
+        let response = create(&server, power_config("shutdown")).await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "Failed to change VM power state"
+        );
+        assert!(response.diagnostics[0].detail.contains("powerdown failed"));
+    }
+
+    #[tokio::test]
+    async fn test_read_removes_missing_vm() {
+        let mut server = Server::new_async().await;
+        let _status = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/100/status/current")
+            .with_status(404)
+            .with_body(r#"{"data": null}"#)
+            .create_async()
+            .await;
+
+        let response = configured_resource(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_vm_power".to_string(),
+                    current_state: power_config("start"),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: tfplug::types::ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        assert!(response.new_state.is_none());
+        assert!(response.diagnostics.is_empty());
+    }
+
+    async fn modify_plan(prior_state: DynamicValue, proposed: DynamicValue) -> ModifyPlanResponse {
+        VmPowerResource::new()
+            .modify_plan(
+                Context::new(),
+                ModifyPlanRequest {
+                    type_name: "proxmox_vm_power".to_string(),
+                    config: proposed.clone(),
+                    prior_state,
+                    proposed_new_state: proposed,
+                    prior_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+    }
+
+    fn applied(action: &str) -> DynamicValue {
+        let mut state = power_config(action);
+        state
+            .set_string(&AttributePath::new("status"), "running".to_string())
+            .unwrap();
+        state
+    }
+
+    #[tokio::test]
+    async fn test_modify_plan_reruns_changed_action() {
+        let mut proposed = applied("shutdown");
+        proposed
+            .set_string(&AttributePath::new("status"), "running".to_string())
+            .unwrap();
+
+        let response = modify_plan(applied("start"), proposed).await;
+
+        assert!(response
+            .planned_state
+            .is_unknown_at(&AttributePath::new("status")));
+        assert!(response.requires_replace.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_modify_plan_keeps_status_without_changes() {
+        let response = modify_plan(applied("start"), applied("start")).await;
+
+        assert_eq!(
+            response
+                .planned_state
+                .get_string(&AttributePath::new("status"))
+                .unwrap(),
+            "running"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_plan_replaces_for_another_vm() {
+        let mut proposed = applied("start");
+        proposed
+            .set_number(&AttributePath::new("vmid"), 101.0)
+            .unwrap();
+
+        let response = modify_plan(applied("start"), proposed).await;
+
+        assert_eq!(response.requires_replace, vec![AttributePath::new("vmid")]);
+    }
+}
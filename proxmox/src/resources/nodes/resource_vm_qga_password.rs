@@ -0,0 +1,395 @@
+//! Guest agent password resource implementation
+
+use crate::api::nodes::AgentSetUserPasswordRequest;
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ModifyPlanRequest,
+    ModifyPlanResponse, ReadResourceRequest, ReadResourceResponse, Resource,
+    ResourceMetadataRequest, ResourceMetadataResponse, ResourceSchemaRequest,
+    ResourceSchemaResponse, ResourceWithConfigure, ResourceWithModifyPlan, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+/// Arguments that point the resource at another VM
+const REPLACE_ATTRIBUTES: &[&str] = &["target_node", "vmid"];
+
+/// Sets the password of a user inside a VM through the QEMU guest agent,
+/// e.g. to hand out a console password after cloud-init has run. The
+/// password is set on create and again whenever an argument or one of the
+/// triggers changes, so changing a trigger rotates it. Destroying the
+/// resource leaves the password in place.
+#[derive(Default)]
+pub struct VmQgaPasswordResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl VmQgaPasswordResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn target(config: &DynamicValue) -> Result<(String, u32), Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("target_node"))
+            .map_err(|_| {
+                Diagnostic::error(
+                    "Missing target_node",
+                    "The 'target_node' attribute is required",
+                )
+            })?;
+        let vmid = config
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
+        Ok((node, vmid))
+    }
+
+    fn password_request(config: &DynamicValue) -> Result<AgentSetUserPasswordRequest, Diagnostic> {
+        let username = config
+            .get_string(&AttributePath::new("username"))
+            .map_err(|_| {
+                Diagnostic::error("Missing username", "The 'username' attribute is required")
+            })?;
+        let password = config
+            .get_string(&AttributePath::new("password"))
+            .map_err(|_| {
+                Diagnostic::error("Missing password", "The 'password' attribute is required")
+            })?;
+        Ok(AgentSetUserPasswordRequest {
+            username,
+            password,
+            crypted: config.get_bool(&AttributePath::new("crypted")).ok(),
+        })
+    }
+
+    /// Sets the configured password inside the guest
+    async fn set_password(
+        provider_data: &crate::ProxmoxProviderData,
+        config: &DynamicValue,
+    ) -> Result<(), Diagnostic> {
+        let (node, vmid) = Self::target(config)?;
+        let request = Self::password_request(config)?;
+
+        provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .qemu()
+            .agent_set_user_password(vmid, &request)
+            .await
+            .map_err(|e| {
+                Diagnostic::error(
+                    "Failed to set password",
+                    format!(
+                        "Unable to set the password of '{}' in VM {} on node '{}'. Make sure the QEMU guest agent is running in the guest and the user exists: {}",
+                        request.username, vmid, node, e
+                    ),
+                )
+            })
+    }
+}
+
+#[async_trait]
+impl Resource for VmQgaPasswordResource {
+    fn type_name(&self) -> &str {
+        "proxmox_vm_qga_password"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Sets the password of a user inside a QEMU VM through the guest agent. The password is set again whenever an argument or one of the triggers changes, which rotates it. Destroying this resource leaves the password in place")
+            .attribute(
+                AttributeBuilder::new("target_node", AttributeType::String)
+                    .description("The name of the Proxmox node hosting the VM")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The identifier of the VM. The QEMU guest agent must be running inside it")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("username", AttributeType::String)
+                    .description("The user inside the guest whose password is set. The user must already exist")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("password", AttributeType::String)
+                    .description("The new password, or its crypt(3) hash when crypted is true")
+                    .required()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("crypted", AttributeType::Bool)
+                    .description("The password is already hashed, e.g. with mkpasswd, and is set as is. Defaults to false")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("triggers", AttributeType::Map(Box::new(AttributeType::String)))
+                    .description("Arbitrary values that set the password again when they change, e.g. a rotation date")
+                    .optional()
+                    .build(),
+            )
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = validate_integers(&request.config, &["vmid"]);
+
+        if let Ok(vmid) = request.config.get_number(&AttributePath::new("vmid")) {
+            if !(100.0..=999999999.0).contains(&vmid) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid VMID",
+                    "VMID must be between 100 and 999999999",
+                ));
+            }
+        }
+        if let Ok(password) = request.config.get_string(&AttributePath::new("password")) {
+            if password.len() < 5 {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid password",
+                        "The guest agent needs a password of at least 5 characters",
+                    )
+                    .with_attribute(AttributePath::new("password")),
+                );
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let result = Self::set_password(provider_data, &request.config).await;
+
+        CreateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: result.err().into_iter().collect(),
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let (node, vmid) = match Self::target(&request.current_state) {
+            Ok(target) => target,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        // The guest agent can't read a password back. It only goes away
+        // with its VM.
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .qemu()
+            .get_status(vmid)
+            .await
+        {
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            _ => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        match Self::set_password(provider_data, &request.config).await {
+            Ok(()) => UpdateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            // The guest still has the old password
+            Err(diagnostic) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: vec![diagnostic],
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // There is no previous password to go back to, removing the resource
+        // only drops it from state
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for VmQgaPasswordResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ResourceWithModifyPlan for VmQgaPasswordResource {
+    /// Replaces the resource when it points at another VM. Any other change
+    /// sets the password again in place.
+    async fn modify_plan(&self, _ctx: Context, request: ModifyPlanRequest) -> ModifyPlanResponse {
+        let mut response = ModifyPlanResponse {
+            planned_state: request.proposed_new_state,
+            requires_replace: vec![],
+            planned_private: request.prior_private,
+            diagnostics: vec![],
+        };
+        if response.planned_state.is_null() || request.prior_state.is_null() {
+            return response;
+        }
+
+        response.requires_replace = REPLACE_ATTRIBUTES
+            .iter()
+            .filter(|name| {
+                let path = AttributePath::new(name);
+                request.prior_state.get(&path).ok() != response.planned_state.get(&path).ok()
+            })
+            .map(|name| AttributePath::new(name))
+            .collect();
+        response
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_vm_qga_password_test.rs"]
+mod resource_vm_qga_password_test;
@@ -0,0 +1,161 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::Dynamic;
+
+    fn password_config(password: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert(
+            "target_node".to_string(),
+            Dynamic::String("pve".to_string()),
+        );
+        obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+        obj.insert("username".to_string(), Dynamic::String("admin".to_string()));
+        obj.insert(
+            "password".to_string(),
+            Dynamic::String(password.to_string()),
+        );
+        obj.insert("crypted".to_string(), Dynamic::Null);
+        obj.insert("triggers".to_string(), Dynamic::Null);
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> VmQgaPasswordResource {
+        VmQgaPasswordResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_short_password() {
+        let response = VmQgaPasswordResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_vm_qga_password".to_string(),
+                    config: password_config("abc"),
+                    client_capabilities: tfplug::types::ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid password");
+    }
+
+    #[tokio::test]
+    async fn test_create_sets_password() {
+        let mut server = Server::new_async().await;
+        let set = server
+            .mock(
+                "POST",
+                "/api2/json/nodes/pve/qemu/100/agent/set-user-password",
+            )
+            .match_body(Matcher::Json(serde_json::json!({
+                "username": "admin",
+                "password": "correct-horse"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"result": {}}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let config = password_config("correct-horse");
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vm_qga_password".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        set.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_keeps_prior_state_when_agent_fails() {
+        let mut server = Server::new_async().await;
+        let _set = server
+            .mock(
+                "POST",
+                "/api2/json/nodes/pve/qemu/100/agent/set-user-password",
+            )
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null, "message": "QEMU guest agent is not running"}"#)
+            .create_async()
+            .await;
+
+        let prior = password_config("correct-horse");
+        let planned = password_config("battery-staple");
+        let response = configured_resource(&server.url())
+            .update(
+                Context::new(),
+                UpdateResourceRequest {
+                    type_name: "proxmox_vm_qga_password".to_string(),
+                    prior_state: prior,
+                    planned_state: planned.clone(),
+                    config: planned,
+                    planned_private: vec![],
+                    provider_meta: None,
+                    planned_identity: None,
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Failed to set password");
+        assert_eq!(
+            response
+                .new_state
+                .get_string(&AttributePath::new("password"))
+                .unwrap(),
+            "correct-horse"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_plan_replaces_for_another_vm() {
+        let mut proposed = password_config("correct-horse");
+        proposed
+            .set_number(&AttributePath::new("vmid"), 101.0)
+            .unwrap();
+        proposed
+            .set_string(
+                &AttributePath::new("password"),
+                "battery-staple".to_string(),
+            )
+            .unwrap();
+
+        let response = VmQgaPasswordResource::new()
+            .modify_plan(
+                Context::new(),
+                ModifyPlanRequest {
+                    type_name: "proxmox_vm_qga_password".to_string(),
+                    config: proposed.clone(),
+                    prior_state: password_config("correct-horse"),
+                    proposed_new_state: proposed,
+                    prior_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert_eq!(response.requires_replace, vec![AttributePath::new("vmid")]);
+    }
+}
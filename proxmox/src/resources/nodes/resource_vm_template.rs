@@ -0,0 +1,457 @@
+//! VM template resource implementation
+
+use crate::resources::diagnostics::task_cancelled_warning;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+/// How long to wait for a VM to stop, and then for the conversion task,
+/// before giving up.
+const STOP_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Default)]
+pub struct VmTemplateResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl VmTemplateResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn target(config: &DynamicValue) -> Result<(String, u32), Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("target_node"))
+            .map_err(|_| {
+                Diagnostic::error(
+                    "Missing target_node",
+                    "The 'target_node' attribute is required",
+                )
+            })?;
+
+        let vmid = config
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
+
+        Ok((node, vmid))
+    }
+
+    /// Stops the VM and waits until Proxmox reports it as stopped
+    async fn stop_and_wait(
+        ctx: &Context,
+        qemu_api: &crate::api::nodes::QemuApi<'_>,
+        vmid: u32,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        qemu_api
+            .stop(vmid)
+            .await
+            .map_err(|e| format!("Failed to stop VM {}: {}", vmid, e))?;
+
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+                _ = ctx.cancelled() => {
+                    return Err(format!(
+                        "Terraform was interrupted before VM {} stopped",
+                        vmid
+                    ));
+                }
+            }
+            if let Ok(status) = qemu_api.get_status(vmid).await {
+                if status.status == "stopped" {
+                    return Ok(());
+                }
+            }
+        }
+
+        Err(format!(
+            "VM {} did not stop within {} seconds",
+            vmid,
+            timeout.as_secs()
+        ))
+    }
+}
+
+#[async_trait]
+impl Resource for VmTemplateResource {
+    fn type_name(&self) -> &str {
+        "proxmox_vm_template"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Converts an existing QEMU VM into a template. The conversion is one-way: destroying this resource only removes it from state")
+            .attribute(
+                AttributeBuilder::new("target_node", AttributeType::String)
+                    .description("The name of the Proxmox node hosting the VM")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The identifier of the VM to convert")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("force_stop", AttributeType::Bool)
+                    .description("Stop the VM if it is running before converting it. When false, converting a running VM is an error")
+                    .optional()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let mut diagnostics = validate_integers(&request.config, &["vmid"]);
+
+        if let Ok(vmid) = request.config.get_number(&AttributePath::new("vmid")) {
+            if !(100.0..=999999999.0).contains(&vmid) {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid VMID",
+                    "VMID must be between 100 and 999999999",
+                ));
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let mut diagnostics = vec![];
+
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                ));
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                };
+            }
+        };
+
+        let (node, vmid) = match Self::target(&request.config) {
+            Ok(target) => target,
+            Err(diag) => {
+                diagnostics.push(diag);
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                };
+            }
+        };
+
+        let qemu_api = provider_data.client.nodes().node(&node).qemu();
+
+        // Adopt VMs that are already templates instead of failing
+        match qemu_api.get_config(vmid).await {
+            Ok(config) if config.template == Some(true) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                };
+            }
+            Ok(_) => {}
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(
+                    "Failed to read VM",
+                    format!("Error fetching VM {}: {}", vmid, e),
+                ));
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                };
+            }
+        }
+
+        match qemu_api.get_status(vmid).await {
+            Ok(status) if status.status != "stopped" => {
+                let force_stop = request
+                    .config
+                    .get_bool(&AttributePath::new("force_stop"))
+                    .unwrap_or(false);
+
+                if !force_stop {
+                    diagnostics.push(Diagnostic::error(
+                        "VM is running",
+                        format!("VM {} on node '{}' is {}. Stop it first or set force_stop = true to convert it into a template", vmid, node, status.status),
+                    ));
+                    return CreateResourceResponse {
+                        new_state: request.planned_state,
+                        private: vec![],
+                        diagnostics,
+                    };
+                }
+
+                if let Err(e) = Self::stop_and_wait(
+                    &ctx,
+                    &qemu_api,
+                    vmid,
+                    Timeouts::new(&request.config).create(&ctx, STOP_TIMEOUT),
+                )
+                .await
+                {
+                    diagnostics.push(Diagnostic::error("Failed to stop VM", e));
+                    return CreateResourceResponse {
+                        new_state: request.planned_state,
+                        private: vec![],
+                        diagnostics,
+                    };
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(
+                    "Could not check VM status",
+                    format!(
+                        "Refusing to convert VM {} without knowing whether it is running: {}",
+                        vmid, e
+                    ),
+                ));
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics,
+                };
+            }
+        }
+
+        // Older Proxmox versions convert synchronously and return no task
+        let converted = match qemu_api.template(vmid).await {
+            Ok(Some(upid)) => provider_data
+                .client
+                .nodes()
+                .node(&node)
+                .wait_for_task(
+                    &upid,
+                    Timeouts::new(&request.config).create(&ctx, STOP_TIMEOUT),
+                    ctx.cancelled(),
+                )
+                .await
+                .map(|_| ()),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        match converted {
+            // Cached listings still show it as a VM
+            Ok(()) => provider_data.invalidate_cluster_vms().await,
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(
+                    "Failed to convert VM to template",
+                    format!("API error: {}", e),
+                ));
+                diagnostics.extend(task_cancelled_warning(&e));
+            }
+        }
+
+        CreateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics,
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let mut diagnostics = vec![];
+
+        let (node, vmid) = match Self::target(&request.current_state) {
+            Ok(target) => target,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics,
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    "Provider not configured",
+                    "Provider data was not properly configured",
+                ));
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    private: request.private,
+                    diagnostics,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&node)
+            .qemu()
+            .get_config(vmid)
+            .await
+        {
+            // A VM that is no longer a template has to be converted again
+            Ok(config) if config.template != Some(true) => ReadResourceResponse {
+                new_state: None,
+                diagnostics,
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Ok(_) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics,
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics,
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(
+                    "Failed to read VM template",
+                    format!("API error: {}", e),
+                ));
+                ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics,
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let mut diagnostics = vec![];
+
+        // Templates are immutable, only force_stop may change in place
+        let prior = Self::target(&request.prior_state).ok();
+        let planned = Self::target(&request.planned_state).ok();
+        if prior != planned {
+            diagnostics.push(Diagnostic::error(
+                "VM templates are immutable",
+                "Changing target_node or vmid requires replacing the proxmox_vm_template resource",
+            ));
+            return UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics,
+                new_identity: None,
+            };
+        }
+
+        UpdateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics,
+            new_identity: None,
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // Proxmox cannot turn a template back into a VM, so there is nothing
+        // to undo here. The template goes away with its VM.
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for VmTemplateResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_vm_template_test.rs"]
+mod resource_vm_template_test;
@@ -0,0 +1,236 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::{ClientCapabilities, Dynamic};
+
+    fn template_config(force_stop: Option<bool>) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert(
+            "target_node".to_string(),
+            Dynamic::String("pve".to_string()),
+        );
+        obj.insert("vmid".to_string(), Dynamic::Number(9000.0));
+        if let Some(force_stop) = force_stop {
+            obj.insert("force_stop".to_string(), Dynamic::Bool(force_stop));
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> VmTemplateResource {
+        VmTemplateResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[test]
+    fn test_resource_type_name() {
+        let resource = VmTemplateResource::new();
+        assert_eq!(resource.type_name(), "proxmox_vm_template");
+    }
+
+    #[tokio::test]
+    async fn test_validate_invalid_vmid() {
+        let resource = VmTemplateResource::new();
+        let mut config = template_config(None);
+        config
+            .set_number(&AttributePath::new("vmid"), 50.0)
+            .unwrap();
+
+        let response = resource
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_vm_template".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert!(response.diagnostics[0].summary.contains("Invalid VMID"));
+    }
+
+    #[tokio::test]
+    async fn test_create_refuses_running_vm() {
+        let mut server = Server::new_async().await;
+        let _config = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/9000/config")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"name": "golden", "template": 0}}"#)
+            .create_async()
+            .await;
+        let _status = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/9000/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"status": "running"}}"#)
+            .create_async()
+            .await;
+        let template = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/9000/template")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let resource = configured_resource(&server.url());
+        let response = resource
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vm_template".to_string(),
+                    planned_state: template_config(None),
+                    config: template_config(None),
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "VM is running");
+        template.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_converts_stopped_vm() {
+        let mut server = Server::new_async().await;
+        let _config = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/9000/config")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"name": "golden"}}"#)
+            .create_async()
+            .await;
+        let _status = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/9000/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"status": "stopped"}}"#)
+            .create_async()
+            .await;
+        let template = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/9000/template")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let resource = configured_resource(&server.url());
+        let response = resource
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vm_template".to_string(),
+                    planned_state: template_config(None),
+                    config: template_config(None),
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        template.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_reports_failed_conversion_task() {
+        let upid = "UPID:pve:00001234:00000000:5F000000:qmtemplate:9000:root@pam:";
+        let mut server = Server::new_async().await;
+        let _config = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/9000/config")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"name": "golden"}}"#)
+            .create_async()
+            .await;
+        let _status = server
+            .mock("GET", "/api2/json/nodes/pve/qemu/9000/status/current")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"status": "stopped"}}"#)
+            .create_async()
+            .await;
+        let _template = server
+            .mock("POST", "/api2/json/nodes/pve/qemu/9000/template")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, upid))
+            .create_async()
+            .await;
+        let task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "disk is locked"}}}}"#,
+                upid
+            ))
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let resource = configured_resource(&server.url());
+        let response = resource
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vm_template".to_string(),
+                    planned_state: template_config(None),
+                    config: template_config(None),
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        task.assert_async().await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "Failed to convert VM to template"
+        );
+        assert!(response.diagnostics[0].detail.contains("disk is locked"));
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_vmid_change() {
+        let resource = VmTemplateResource::new();
+        let mut planned = template_config(None);
+        planned
+            .set_number(&AttributePath::new("vmid"), 9001.0)
+            .unwrap();
+
+        let response = resource
+            .update(
+                Context::new(),
+                UpdateResourceRequest {
+                    type_name: "proxmox_vm_template".to_string(),
+                    prior_state: template_config(None),
+                    planned_state: planned.clone(),
+                    config: planned,
+                    planned_private: vec![],
+                    provider_meta: None,
+                    planned_identity: None,
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert!(response.diagnostics[0].summary.contains("immutable"));
+    }
+}
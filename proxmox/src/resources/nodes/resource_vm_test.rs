@@ -1,9 +1,67 @@
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)]
 mod tests {
     use super::super::*;
     use tfplug::context::Context;
-    use tfplug::resource::{Resource, ValidateResourceConfigRequest};
+    use tfplug::resource::{
+        Resource, ResourceSchemaRequest, ResourceWithConfigValidators,
+        ValidateResourceConfigRequest,
+    };
     use tfplug::types::{ClientCapabilities, Dynamic, DynamicValue};
+    use tfplug::validator::{run_config_validators, validate_config};
+
+    /// Validates a config the way the framework does: the schema validators
+    /// first, then the config validators and the resource's own checks
+    async fn validate_all(config: DynamicValue) -> Vec<Diagnostic> {
+        let resource = QemuVmResource::new();
+        let schema = resource
+            .schema(Context::new(), ResourceSchemaRequest)
+            .await
+            .schema;
+        let mut diagnostics = validate_config(&schema.block, &config);
+        diagnostics.extend(run_config_validators(
+            &resource.config_validators(),
+            &config,
+        ));
+        diagnostics.extend(
+            resource
+                .validate(
+                    Context::new(),
+                    ValidateResourceConfigRequest {
+                        type_name: "proxmox_qemu_vm".to_string(),
+                        config,
+                        client_capabilities: ClientCapabilities {
+                            deferral_allowed: false,
+                            write_only_attributes_allowed: false,
+                        },
+                    },
+                )
+                .await
+                .diagnostics,
+        );
+        diagnostics
+    }
+
+    fn memory_block(dedicated: f64, floating: Option<f64>) -> Dynamic {
+        let mut block = std::collections::HashMap::new();
+        block.insert("dedicated".to_string(), Dynamic::Number(dedicated));
+        block.insert(
+            "floating".to_string(),
+            floating.map_or(Dynamic::Null, Dynamic::Number),
+        );
+        Dynamic::List(vec![Dynamic::Map(block)])
+    }
+
+    fn cpu_block(settings: &[(&str, Dynamic)]) -> Vec<Dynamic> {
+        let mut block: std::collections::HashMap<String, Dynamic> = settings
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        for name in ["type", "flags", "units", "limit", "affinity", "numa"] {
+            block.entry(name.to_string()).or_insert(Dynamic::Null);
+        }
+        vec![Dynamic::Map(block)]
+    }
 
     fn create_test_dynamic_value() -> DynamicValue {
         let mut obj = std::collections::HashMap::new();
@@ -13,7 +71,7 @@ mod tests {
         );
         obj.insert("vmid".to_string(), Dynamic::Number(100.0));
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-        obj.insert("memory".to_string(), Dynamic::Number(2048.0));
+        obj.insert("memory".to_string(), memory_block(2048.0, None));
         obj.insert("cores".to_string(), Dynamic::Number(2.0));
         obj.insert("sockets".to_string(), Dynamic::Number(1.0));
         DynamicValue::new(Dynamic::Map(obj))
@@ -27,7 +85,7 @@ mod tests {
         );
         obj.insert("vmid".to_string(), Dynamic::Number(100.0));
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-        obj.insert("memory".to_string(), Dynamic::Number(2048.0));
+        obj.insert("memory".to_string(), memory_block(2048.0, None));
         obj.insert("cores".to_string(), Dynamic::Number(2.0));
         obj.insert("sockets".to_string(), Dynamic::Number(1.0));
 
@@ -62,7 +120,7 @@ mod tests {
         );
         obj.insert("vmid".to_string(), Dynamic::Number(100.0));
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-        obj.insert("memory".to_string(), Dynamic::Number(2048.0));
+        obj.insert("memory".to_string(), memory_block(2048.0, None));
         obj.insert("cores".to_string(), Dynamic::Number(2.0));
         obj.insert("sockets".to_string(), Dynamic::Number(1.0));
 
@@ -106,7 +164,7 @@ mod tests {
         );
         obj.insert("vmid".to_string(), Dynamic::Number(100.0));
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-        obj.insert("memory".to_string(), Dynamic::Number(2048.0));
+        obj.insert("memory".to_string(), memory_block(2048.0, None));
         obj.insert("cores".to_string(), Dynamic::Number(2.0));
         obj.insert("sockets".to_string(), Dynamic::Number(1.0));
         obj.insert("bios".to_string(), Dynamic::String("ovmf".to_string()));
@@ -135,7 +193,7 @@ mod tests {
         );
         obj.insert("vmid".to_string(), Dynamic::Number(100.0));
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-        obj.insert("memory".to_string(), Dynamic::Number(2048.0));
+        obj.insert("memory".to_string(), memory_block(2048.0, None));
         obj.insert("cores".to_string(), Dynamic::Number(2.0));
         obj.insert("sockets".to_string(), Dynamic::Number(1.0));
 
@@ -191,7 +249,10 @@ mod tests {
         );
         obj.insert(
             "tags".to_string(),
-            Dynamic::String("production,web".to_string()),
+            Dynamic::List(vec![
+                Dynamic::String("production".to_string()),
+                Dynamic::String("web".to_string()),
+            ]),
         );
 
         // Clone/Template Settings
@@ -209,14 +270,16 @@ mod tests {
         obj.insert("bios".to_string(), Dynamic::String("ovmf".to_string()));
         obj.insert("machine".to_string(), Dynamic::String("q35".to_string()));
         obj.insert(
-            "cpu_type".to_string(),
-            Dynamic::String("x86-64-v2-AES".to_string()),
+            "cpu".to_string(),
+            Dynamic::List(cpu_block(&[(
+                "type",
+                Dynamic::String("x86-64-v2-AES".to_string()),
+            )])),
         );
         obj.insert("cores".to_string(), Dynamic::Number(2.0));
         obj.insert("sockets".to_string(), Dynamic::Number(1.0));
         obj.insert("vcpus".to_string(), Dynamic::Number(2.0));
-        obj.insert("memory".to_string(), Dynamic::Number(4096.0));
-        obj.insert("balloon".to_string(), Dynamic::Number(2048.0));
+        obj.insert("memory".to_string(), memory_block(4096.0, Some(2048.0)));
 
         // Boot Configuration
         obj.insert("boot".to_string(), Dynamic::String("c".to_string()));
@@ -230,7 +293,10 @@ mod tests {
         );
 
         // Guest Agent & OS Settings
-        obj.insert("agent".to_string(), Dynamic::Number(1.0));
+        obj.insert(
+            "agent".to_string(),
+            Dynamic::List(vec![Agent::default().to_dynamic()]),
+        );
         obj.insert("qemu_os".to_string(), Dynamic::String("l26".to_string()));
 
         // Cloud-Init Configuration
@@ -246,7 +312,7 @@ mod tests {
         obj.insert("ciupgrade".to_string(), Dynamic::Bool(true));
         obj.insert(
             "sshkeys".to_string(),
-            Dynamic::String("ssh-rsa AAAAB3NzaC1...".to_string()),
+            Dynamic::List(vec![Dynamic::String("ssh-rsa AAAAB3NzaC1...".to_string())]),
         );
 
         // Network Settings
@@ -351,7 +417,7 @@ mod tests {
             net_config,
             Some("virtio,bridge=vmbr0,tag=100,firewall=1"),
         );
-        assert_eq!(normalized, "virtio,bridge=vmbr0,firewall=1,tag=100");
+        assert_eq!(normalized, "virtio,bridge=vmbr0,tag=100,firewall=1");
 
         // Test with MAC address that should be kept
         let net_config = "virtio=BA:88:CB:76:75:D6,tag=100,bridge=vmbr0,firewall=1";
@@ -361,7 +427,74 @@ mod tests {
         );
         assert_eq!(
             normalized,
-            "virtio=BA:88:CB:76:75:D6,bridge=vmbr0,firewall=1,tag=100"
+            "virtio=BA:88:CB:76:75:D6,bridge=vmbr0,tag=100,firewall=1"
+        );
+    }
+
+    #[test]
+    fn test_normalize_network_config_reports_changed_configuration() {
+        let net_config = "virtio=BA:88:CB:76:75:D6,tag=100,bridge=vmbr0,firewall=1";
+
+        // A different VLAN tag is a change, reported without the assigned MAC
+        let normalized = QemuVmResource::normalize_network_config(
+            net_config,
+            Some("virtio,bridge=vmbr0,tag=101,firewall=1"),
+        );
+        assert_eq!(normalized, "virtio,bridge=vmbr0,firewall=1,tag=100");
+    }
+
+    #[test]
+    fn test_normalize_network_config_keeps_equivalent_configuration() {
+        let net_config = "virtio=BA:88:CB:76:75:D6,bridge=vmbr0,firewall=1,tag=100";
+
+        // Reordered parameters and flag spellings are not a change
+        let configured = "virtio,tag=100,firewall=on,bridge=vmbr0";
+        assert_eq!(
+            QemuVmResource::normalize_network_config(net_config, Some(configured)),
+            configured
+        );
+
+        let configured = "bridge=vmbr0,virtio=ba:88:cb:76:75:d6,firewall=1,tag=100";
+        assert_eq!(
+            QemuVmResource::normalize_network_config(net_config, Some(configured)),
+            configured
+        );
+
+        // A different MAC is
+        let configured = "virtio=BA:88:CB:76:75:D7,bridge=vmbr0,firewall=1,tag=100";
+        assert_eq!(
+            QemuVmResource::normalize_network_config(net_config, Some(configured)),
+            net_config
+        );
+    }
+
+    #[test]
+    fn test_normalize_disk_config_compares_semantically() {
+        let disk_config = "local-lvm:vm-100-disk-0,iothread=1,size=10G";
+
+        let configured = "local-lvm:10,iothread=on,format=raw";
+        assert_eq!(
+            QemuVmResource::normalize_disk_config(disk_config, Some(configured)),
+            configured
+        );
+
+        let configured = "local-lvm:vm-100-disk-0,size=10240M,iothread=1";
+        assert_eq!(
+            QemuVmResource::normalize_disk_config(disk_config, Some(configured)),
+            configured
+        );
+
+        // Resized or moved disks are reported as they are
+        for configured in ["local-lvm:20,format=raw", "ceph:10,format=raw"] {
+            assert_eq!(
+                QemuVmResource::normalize_disk_config(disk_config, Some(configured)),
+                disk_config
+            );
+        }
+
+        assert_eq!(
+            QemuVmResource::normalize_disk_config(disk_config, None),
+            disk_config
         );
     }
 
@@ -389,9 +522,6 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_invalid_vmid_too_low() {
-        let resource = QemuVmResource::new();
-        let ctx = Context::new();
-
         let mut obj = std::collections::HashMap::new();
         obj.insert(
             "target_node".to_string(),
@@ -400,25 +530,14 @@ mod tests {
         obj.insert("vmid".to_string(), Dynamic::Number(50.0)); // Invalid: < 100
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
 
-        let request = ValidateResourceConfigRequest {
-            type_name: "proxmox_qemu_vm".to_string(),
-            config: DynamicValue::new(Dynamic::Map(obj)),
-            client_capabilities: ClientCapabilities {
-                deferral_allowed: false,
-                write_only_attributes_allowed: false,
-            },
-        };
-
-        let response = resource.validate(ctx, request).await;
-        assert_eq!(response.diagnostics.len(), 1);
-        assert!(response.diagnostics[0].summary.contains("Invalid VMID"));
+        let diagnostics = validate_all(DynamicValue::new(Dynamic::Map(obj))).await;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].summary, "Value out of range");
+        assert_eq!(diagnostics[0].attribute, Some(AttributePath::new("vmid")));
     }
 
     #[tokio::test]
     async fn test_validate_invalid_cores() {
-        let resource = QemuVmResource::new();
-        let ctx = Context::new();
-
         let mut obj = std::collections::HashMap::new();
         obj.insert(
             "target_node".to_string(),
@@ -428,18 +547,29 @@ mod tests {
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
         obj.insert("cores".to_string(), Dynamic::Number(200.0)); // Invalid: > 128
 
-        let request = ValidateResourceConfigRequest {
-            type_name: "proxmox_qemu_vm".to_string(),
-            config: DynamicValue::new(Dynamic::Map(obj)),
-            client_capabilities: ClientCapabilities {
-                deferral_allowed: false,
-                write_only_attributes_allowed: false,
-            },
-        };
+        let diagnostics = validate_all(DynamicValue::new(Dynamic::Map(obj))).await;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].summary, "Value out of range");
+        assert_eq!(diagnostics[0].attribute, Some(AttributePath::new("cores")));
+    }
 
-        let response = resource.validate(ctx, request).await;
-        assert_eq!(response.diagnostics.len(), 1);
-        assert!(response.diagnostics[0].summary.contains("Invalid cores"));
+    #[tokio::test]
+    async fn test_validate_fractional_integers() {
+        let mut obj = std::collections::HashMap::new();
+        obj.insert(
+            "target_node".to_string(),
+            Dynamic::String("pve".to_string()),
+        );
+        obj.insert("vmid".to_string(), Dynamic::Number(100.5));
+        obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
+        obj.insert("cores".to_string(), Dynamic::Number(2.5));
+
+        let diagnostics = validate_all(DynamicValue::new(Dynamic::Map(obj))).await;
+        let invalid: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.summary == "Invalid integer")
+            .collect();
+        assert_eq!(invalid.len(), 2);
     }
 
     #[tokio::test]
@@ -454,7 +584,7 @@ mod tests {
         );
         obj.insert("vmid".to_string(), Dynamic::Number(100.0));
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-        obj.insert("memory".to_string(), Dynamic::Number(10.0)); // Invalid: < 16
+        obj.insert("memory".to_string(), memory_block(10.0, None)); // Invalid: < 16
 
         let request = ValidateResourceConfigRequest {
             type_name: "proxmox_qemu_vm".to_string(),
@@ -472,9 +602,6 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_invalid_bios() {
-        let resource = QemuVmResource::new();
-        let ctx = Context::new();
-
         let mut obj = std::collections::HashMap::new();
         obj.insert(
             "target_node".to_string(),
@@ -484,18 +611,10 @@ mod tests {
         obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
         obj.insert("bios".to_string(), Dynamic::String("invalid".to_string())); // Invalid
 
-        let request = ValidateResourceConfigRequest {
-            type_name: "proxmox_qemu_vm".to_string(),
-            config: DynamicValue::new(Dynamic::Map(obj)),
-            client_capabilities: ClientCapabilities {
-                deferral_allowed: false,
-                write_only_attributes_allowed: false,
-            },
-        };
-
-        let response = resource.validate(ctx, request).await;
-        assert_eq!(response.diagnostics.len(), 1);
-        assert!(response.diagnostics[0].summary.contains("Invalid BIOS"));
+        let diagnostics = validate_all(DynamicValue::new(Dynamic::Map(obj))).await;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].summary, "Invalid value");
+        assert_eq!(diagnostics[0].attribute, Some(AttributePath::new("bios")));
     }
 
     #[test]
@@ -669,6 +788,225 @@ mod tests {
         assert!(response.diagnostics.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_validate_network_vlan_tags() {
+        let mut config = create_test_dynamic_value_with_network_blocks();
+        for (index, tag) in [(0, 0.0), (1, 4095.5)] {
+            config
+                .set_number(
+                    &AttributePath::new("network").index(index).attribute("tag"),
+                    tag,
+                )
+                .unwrap();
+        }
+
+        let paths: Vec<String> = validation_diagnostics(config)
+            .await
+            .iter()
+            .filter(|d| d.summary == "Invalid VLAN tag")
+            .filter_map(|d| d.attribute.as_ref().map(|path| path.to_string()))
+            .collect();
+        assert_eq!(paths, vec!["network[0].tag", "network[1].tag"]);
+    }
+
+    fn set_ipconfigs(config: &mut DynamicValue, ids: &[f64]) {
+        let ipconfig = ids
+            .iter()
+            .map(|id| {
+                Dynamic::Map(HashMap::from([
+                    ("id".to_string(), Dynamic::Number(*id)),
+                    ("ipv4".to_string(), Dynamic::String("dhcp".to_string())),
+                ]))
+            })
+            .collect();
+        config
+            .set_list(
+                &AttributePath::new("cloudinit"),
+                vec![Dynamic::Map(HashMap::from([(
+                    "ipconfig".to_string(),
+                    Dynamic::List(ipconfig),
+                )]))],
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_cloudinit_ipconfig_ids() {
+        let mut config = create_test_dynamic_value_with_network_blocks();
+        config
+            .set_string(&AttributePath::new("ipconfig0"), "ip=dhcp".to_string())
+            .unwrap();
+        set_ipconfigs(&mut config, &[0.0, 1.0, 1.0, 5.0, 32.0]);
+
+        let diagnostics: Vec<(String, String)> = validation_diagnostics(config)
+            .await
+            .iter()
+            .filter_map(|d| {
+                d.attribute
+                    .as_ref()
+                    .map(|path| (path.to_string(), d.summary.clone()))
+            })
+            .filter(|(path, _)| path.starts_with("cloudinit"))
+            .collect();
+        let expected = [
+            (0, "Conflicting ipconfig"),
+            (2, "Duplicate ipconfig id"),
+            (3, "Unknown network interface"),
+            (4, "Invalid ipconfig id"),
+        ];
+        assert_eq!(
+            diagnostics,
+            expected
+                .iter()
+                .map(|(index, summary)| (
+                    format!("cloudinit[0].ipconfig[{}].id", index),
+                    summary.to_string()
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_cloudinit_trusts_interfaces_of_clones() {
+        let mut config = create_test_dynamic_value_with_network_blocks();
+        config
+            .set_string(&AttributePath::new("clone"), "ubuntu-template".to_string())
+            .unwrap();
+        set_ipconfigs(&mut config, &[7.0]);
+
+        assert!(!validation_diagnostics(config)
+            .await
+            .iter()
+            .any(|d| d.summary == "Unknown network interface"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_cloudinit_trusts_interfaces_of_restored_vms() {
+        let mut config = create_test_dynamic_value();
+        config_with_restore_from(&mut config);
+        set_ipconfigs(&mut config, &[1.0]);
+
+        assert!(!validation_diagnostics(config)
+            .await
+            .iter()
+            .any(|d| d.summary == "Unknown network interface"));
+    }
+
+    #[test]
+    fn test_ipconfig_api_string() {
+        let ipconfig = IpConfig {
+            id: 5.0,
+            ipv4: Some("10.0.5.2/24".to_string()),
+            gateway: Some("10.0.5.1".to_string()),
+            ipv6: Some("auto".to_string()),
+            gateway6: None,
+        };
+        let api_string = ipconfig.to_api_string();
+
+        assert_eq!(api_string, "ip=10.0.5.2/24,gw=10.0.5.1,ip6=auto");
+        assert_eq!(IpConfig::from_api_string(5, &api_string), ipconfig);
+    }
+
+    #[test]
+    fn test_cloudinit_ipconfigs_and_removed_ones() {
+        let mut prior = create_test_dynamic_value_with_network_blocks();
+        set_ipconfigs(&mut prior, &[4.0, 6.0]);
+        let mut config = create_test_dynamic_value_with_network_blocks();
+        config
+            .set_string(&AttributePath::new("ipconfig1"), "ip=dhcp".to_string())
+            .unwrap();
+        set_ipconfigs(&mut config, &[6.0]);
+
+        assert_eq!(
+            QemuVmResource::ipconfigs(&config),
+            vec![(1, "ip=dhcp".to_string()), (6, "ip=dhcp".to_string())]
+        );
+        assert_eq!(
+            QemuVmResource::removed_ipconfigs(&prior, &config),
+            vec!["ipconfig4".to_string()]
+        );
+    }
+
+    fn set_cloudinit_dns(config: &mut DynamicValue, servers: &[&str], domain: Option<&str>) {
+        let mut cloudinit = HashMap::from([(
+            "nameserver".to_string(),
+            Dynamic::List(
+                servers
+                    .iter()
+                    .map(|server| Dynamic::String(server.to_string()))
+                    .collect(),
+            ),
+        )]);
+        if let Some(domain) = domain {
+            cloudinit.insert(
+                "searchdomain".to_string(),
+                Dynamic::String(domain.to_string()),
+            );
+        }
+        config
+            .set_list(
+                &AttributePath::new("cloudinit"),
+                vec![Dynamic::Map(cloudinit)],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cloudinit_dns_settings() {
+        let mut prior = create_test_dynamic_value_with_network_blocks();
+        set_cloudinit_dns(&mut prior, &["10.0.0.53", "1.1.1.1"], Some("lab.example"));
+        let mut config = create_test_dynamic_value_with_network_blocks();
+        set_cloudinit_dns(&mut config, &["10.0.0.53"], None);
+
+        let settings = QemuVmResource::vm_settings(&prior);
+        assert_eq!(settings.nameserver.as_deref(), Some("10.0.0.53 1.1.1.1"));
+        assert_eq!(settings.searchdomain.as_deref(), Some("lab.example"));
+        assert_eq!(
+            QemuVmResource::removed_cloudinit_dns(&prior, &config),
+            vec!["searchdomain".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_cloudinit_dns_read_back() {
+        let mut planned = create_test_dynamic_value_with_network_blocks();
+        set_cloudinit_dns(&mut planned, &["10.0.0.53"], None);
+        let vm_config = crate::api::nodes::QemuConfig {
+            nameserver: Some("10.0.0.54  9.9.9.9".to_string()),
+            searchdomain: Some("lab.example".to_string()),
+            ..Default::default()
+        };
+
+        let mut state = planned.clone();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &planned);
+
+        // The search domain isn't managed, so only the servers are read back
+        let cloudinit: CloudInit = QemuVmResource::single_block_as(&state, "cloudinit").unwrap();
+        assert_eq!(
+            cloudinit.nameserver,
+            Some(vec!["10.0.0.54".to_string(), "9.9.9.9".to_string()])
+        );
+        assert_eq!(cloudinit.searchdomain, None);
+
+        let imported = QemuVmResource::imported_state("pve", 100, &vm_config);
+        let cloudinit: CloudInit = QemuVmResource::single_block_as(&imported, "cloudinit").unwrap();
+        assert_eq!(cloudinit.searchdomain.as_deref(), Some("lab.example"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_cloudinit_nameservers() {
+        let mut config = create_test_dynamic_value_with_network_blocks();
+        set_cloudinit_dns(&mut config, &["10.0.0.53", "dns.example"], None);
+
+        let paths: Vec<String> = validation_diagnostics(config)
+            .await
+            .iter()
+            .filter(|d| d.summary == "Invalid DNS server")
+            .filter_map(|d| d.attribute.as_ref().map(|path| path.to_string()))
+            .collect();
+        assert_eq!(paths, vec!["cloudinit[0].nameserver[1]"]);
+    }
+
     #[tokio::test]
     async fn test_validate_disk_blocks() {
         let resource = QemuVmResource::new();
@@ -753,10 +1091,10 @@ mod tests {
         assert_eq!(node, "pve");
         assert_eq!(vmid, 100);
         assert_eq!(create_request.vmid, 100);
-        assert_eq!(create_request.name, Some("test-vm".to_string()));
-        assert_eq!(create_request.memory, Some(2048));
-        assert_eq!(create_request.cores, Some(2));
-        assert_eq!(create_request.sockets, Some(1));
+        assert_eq!(create_request.settings.name, Some("test-vm".to_string()));
+        assert_eq!(create_request.settings.memory, Some(2048));
+        assert_eq!(create_request.settings.cores, Some(2));
+        assert_eq!(create_request.settings.sockets, Some(1));
     }
 
     #[test]
@@ -806,6 +1144,78 @@ mod tests {
         assert!(disk_string.contains("iothread=1"));
     }
 
+    fn import_disk(slot: &str, source: &str) -> Dynamic {
+        let mut disk = std::collections::HashMap::new();
+        disk.insert("slot".to_string(), Dynamic::String(slot.to_string()));
+        disk.insert("type".to_string(), Dynamic::String("scsi".to_string()));
+        disk.insert(
+            "storage".to_string(),
+            Dynamic::String("local-lvm".to_string()),
+        );
+        disk.insert("size".to_string(), Dynamic::String("2G".to_string()));
+        disk.insert("format".to_string(), Dynamic::String("qcow2".to_string()));
+        disk.insert(
+            "import_from".to_string(),
+            Dynamic::String(source.to_string()),
+        );
+        Dynamic::Map(disk)
+    }
+
+    fn config_with_disks(disks: Vec<Dynamic>) -> DynamicValue {
+        let mut obj = std::collections::HashMap::new();
+        obj.insert("disk".to_string(), Dynamic::List(disks));
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    #[test]
+    fn test_disk_block_with_import_from() {
+        let disk = import_disk("scsi0", "local:import/debian-12.qcow2");
+        let (_, disk_string) = QemuVmResource::disk_block_to_api_string(&disk).unwrap();
+        assert_eq!(
+            disk_string,
+            "local-lvm:0,import-from=local:import/debian-12.qcow2,format=qcow2"
+        );
+
+        let config = config_with_disks(vec![
+            disk,
+            import_disk("scsi1", "https://example.com/debian 12.qcow2"),
+        ]);
+        let imports = QemuVmResource::url_disk_imports(&config);
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].0, "scsi1");
+        assert_eq!(imports[0].1, "https://example.com/debian 12.qcow2");
+    }
+
+    #[test]
+    fn test_url_import_script() {
+        let url = "https://example.com/debian 12.qcow2";
+        let disk_string = format!("local-lvm:0,import-from={},format=qcow2", url);
+        let script = QemuVmResource::url_import_script(100, "scsi1", url, &disk_string);
+
+        assert!(
+            script.contains("wget -q -O \"$dir/image\" 'https://example.com/debian 12.qcow2'\n")
+        );
+        assert!(script
+            .contains("qm set 100 --scsi1 local-lvm:0,\"import-from=$dir/image\",format=qcow2\n"));
+        assert!(script.contains("trap 'rm -rf \"$dir\"' EXIT"));
+    }
+
+    #[test]
+    fn test_changed_disk_imports() {
+        let prior = config_with_disks(vec![import_disk("scsi0", "local:import/a.qcow2")]);
+
+        assert!(QemuVmResource::changed_disk_imports(&prior, &prior).is_empty());
+
+        let planned = config_with_disks(vec![
+            import_disk("scsi0", "local:import/b.qcow2"),
+            import_disk("scsi1", "local:import/c.qcow2"),
+        ]);
+        assert_eq!(
+            QemuVmResource::changed_disk_imports(&prior, &planned),
+            vec!["scsi0", "scsi1"]
+        );
+    }
+
     #[test]
     fn test_parse_network_string_to_block() {
         let net_string = "virtio,bridge=vmbr0,firewall=1,tag=100";
@@ -931,11 +1341,11 @@ mod tests {
         assert_eq!(node, "pve");
         assert_eq!(vmid, 100);
         assert_eq!(
-            create_request.net0,
+            create_request.settings.net0,
             Some("virtio,bridge=vmbr0,firewall=1,tag=100".to_string())
         );
         assert_eq!(
-            create_request.net1,
+            create_request.settings.net1,
             Some("e1000,bridge=vmbr1,firewall=0,tag=200".to_string())
         );
     }
@@ -950,11 +1360,11 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         assert_eq!(
-            create_request.scsi0,
+            create_request.settings.scsi0,
             Some("local-lvm:10,format=raw,iothread=1,ssd=1,discard=on".to_string())
         );
         assert_eq!(
-            create_request.virtio0,
+            create_request.settings.virtio0,
             Some("local-lvm:20,format=qcow2".to_string())
         );
     }
@@ -969,7 +1379,7 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         assert_eq!(
-            create_request.efidisk0,
+            create_request.settings.efidisk0,
             Some("local-lvm:1,efitype=4m".to_string())
         );
     }
@@ -984,11 +1394,11 @@ mod tests {
 
         let update_request = result.unwrap();
         assert_eq!(
-            update_request.net0,
+            update_request.settings.net0,
             Some("virtio,bridge=vmbr0,firewall=1,tag=100".to_string())
         );
         assert_eq!(
-            update_request.net1,
+            update_request.settings.net1,
             Some("e1000,bridge=vmbr1,firewall=0,tag=200".to_string())
         );
     }
@@ -1211,10 +1621,13 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         assert_eq!(
-            create_request.net0,
+            create_request.settings.net0,
             Some("virtio,bridge=vmbr0,firewall=1".to_string())
         );
-        assert_eq!(create_request.net1, Some("e1000,bridge=vmbr1".to_string()));
+        assert_eq!(
+            create_request.settings.net1,
+            Some("e1000,bridge=vmbr1".to_string())
+        );
     }
 
     #[test]
@@ -1269,14 +1682,17 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         assert_eq!(
-            create_request.scsi0,
+            create_request.settings.scsi0,
             Some("local-lvm:10,format=raw".to_string())
         );
         assert_eq!(
-            create_request.ide2,
+            create_request.settings.ide2,
             Some("local:iso/ubuntu-22.04.iso,media=cdrom".to_string())
         );
-        assert_eq!(create_request.ide3, Some("local-lvm:cloudinit".to_string()));
+        assert_eq!(
+            create_request.settings.ide3,
+            Some("local-lvm:cloudinit".to_string())
+        );
     }
 
     #[test]
@@ -1322,18 +1738,21 @@ mod tests {
         // Only attributes present in planned state should be populated with defaults
         assert!(state.get_number(&AttributePath::new("cores")).is_ok());
         assert!(state.get_number(&AttributePath::new("sockets")).is_ok());
-        assert!(state.get_number(&AttributePath::new("memory")).is_ok());
+        assert_eq!(
+            state.get_list(&AttributePath::new("memory")).unwrap(),
+            vec![QemuVmResource::parse_memory_settings(&vm_config)]
+        );
 
         // These attributes are not in the planned state, so they should not be set
-        assert!(state.get_string(&AttributePath::new("cpu")).is_err());
+        assert!(state.get_list(&AttributePath::new("cpu")).is_err());
         assert!(state.get_string(&AttributePath::new("bios")).is_err());
         assert!(state.get_string(&AttributePath::new("scsihw")).is_err());
         assert!(state.get_string(&AttributePath::new("ostype")).is_err());
-        assert!(state.get_string(&AttributePath::new("agent")).is_err());
+        assert!(state.get_list(&AttributePath::new("agent")).is_err());
         assert!(state.get_bool(&AttributePath::new("onboot")).is_err());
         assert!(state.get_bool(&AttributePath::new("tablet")).is_err());
         assert!(state.get_bool(&AttributePath::new("protection")).is_err());
-        assert!(state.get_string(&AttributePath::new("tags")).is_err());
+        assert!(state.get_list(&AttributePath::new("tags")).is_err());
         assert!(state
             .get_string(&AttributePath::new("description"))
             .is_err());
@@ -1357,7 +1776,7 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         // IDE2 should not contain format=cdrom
-        if let Some(ide2) = &create_request.ide2 {
+        if let Some(ide2) = &create_request.settings.ide2 {
             assert!(!ide2.contains("format=cdrom"));
             assert!(!ide2.contains("format=cloudinit"));
         }
@@ -1396,7 +1815,7 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         // Cloud-init disk should not have format
-        if let Some(ide2) = &create_request.ide2 {
+        if let Some(ide2) = &create_request.settings.ide2 {
             assert!(!ide2.contains("format="));
         }
     }
@@ -1432,7 +1851,7 @@ mod tests {
 
         let (_, _, create_request) = result.unwrap();
         // EFI disk on LVM should not have format
-        if let Some(efidisk0) = &create_request.efidisk0 {
+        if let Some(efidisk0) = &create_request.settings.efidisk0 {
             assert!(!efidisk0.contains("format="));
         }
     }
@@ -1454,7 +1873,10 @@ mod tests {
         let mut planned_state = create_test_dynamic_value_with_network_blocks();
         // Add the attributes we want to test to the planned state
         planned_state
-            .set_string(&AttributePath::new("cpu"), "x86-64-v2-AES".to_string())
+            .set_list(
+                &AttributePath::new("cpu"),
+                cpu_block(&[("type", Dynamic::String("x86-64-v2-AES".to_string()))]),
+            )
             .unwrap();
         planned_state
             .set_string(&AttributePath::new("bios"), "seabios".to_string())
@@ -1474,10 +1896,7 @@ mod tests {
             "test-vm"
         );
         assert_eq!(state.get_number(&AttributePath::new("cores")).unwrap(), 2.0);
-        assert_eq!(
-            state.get_number(&AttributePath::new("memory")).unwrap(),
-            2048.0
-        );
+        assert_eq!(QemuVmResource::dedicated_memory(&state), Some(2048));
 
         // Verify zero values for optional attributes that were in planned state
         assert_eq!(
@@ -1485,18 +1904,17 @@ mod tests {
             1.0
         );
         assert_eq!(
-            state.get_string(&AttributePath::new("cpu")).unwrap(),
+            state
+                .get_string(&AttributePath::new("cpu").index(0).attribute("type"))
+                .unwrap(),
             "x86-64-v2-AES"
         );
         assert_eq!(
             state.get_string(&AttributePath::new("bios")).unwrap(),
             "seabios"
         );
-        assert_eq!(
-            state.get_bool(&AttributePath::new("onboot")).unwrap(),
-            false
-        );
-        assert_eq!(state.get_bool(&AttributePath::new("tablet")).unwrap(), true);
+        assert!(!state.get_bool(&AttributePath::new("onboot")).unwrap());
+        assert!(state.get_bool(&AttributePath::new("tablet")).unwrap());
 
         // Verify network blocks are populated
         let networks = state.get_list(&AttributePath::new("network")).unwrap();
@@ -1505,4 +1923,1737 @@ mod tests {
         // Verify disk list is not set since we didn't plan disk blocks
         assert!(state.get_list(&AttributePath::new("disk")).is_err());
     }
+
+    #[tokio::test]
+    async fn test_validate_nested_virtualization_requires_kvm() {
+        let resource = QemuVmResource::new();
+        let ctx = Context::new();
+
+        let mut config = create_test_dynamic_value();
+        config
+            .set_bool(&AttributePath::new("nested_virtualization"), true)
+            .unwrap();
+        config.set_bool(&AttributePath::new("kvm"), false).unwrap();
+
+        let request = ValidateResourceConfigRequest {
+            type_name: "proxmox_qemu_vm".to_string(),
+            config,
+            client_capabilities: ClientCapabilities {
+                deferral_allowed: false,
+                write_only_attributes_allowed: false,
+            },
+        };
+
+        let response = resource.validate(ctx, request).await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert!(response.diagnostics[0]
+            .summary
+            .contains("Nested virtualization requires KVM"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_nested_virtualization_conflicting_flags() {
+        let resource = QemuVmResource::new();
+        let ctx = Context::new();
+
+        let mut config = create_test_dynamic_value();
+        config
+            .set_bool(&AttributePath::new("nested_virtualization"), true)
+            .unwrap();
+        config
+            .set_list(
+                &AttributePath::new("cpu"),
+                cpu_block(&[
+                    ("type", Dynamic::String("x86-64-v2-AES".to_string())),
+                    (
+                        "flags",
+                        Dynamic::List(vec![Dynamic::String("-vmx".to_string())]),
+                    ),
+                ]),
+            )
+            .unwrap();
+
+        let request = ValidateResourceConfigRequest {
+            type_name: "proxmox_qemu_vm".to_string(),
+            config,
+            client_capabilities: ClientCapabilities {
+                deferral_allowed: false,
+                write_only_attributes_allowed: false,
+            },
+        };
+
+        let response = resource.validate(ctx, request).await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert!(response.diagnostics[0]
+            .summary
+            .contains("Conflicting CPU flags"));
+    }
+
+    #[test]
+    fn test_extract_vm_config_with_nested_virtualization() {
+        let resource = QemuVmResource::new();
+        let mut config = create_test_dynamic_value();
+        config
+            .set_bool(&AttributePath::new("nested_virtualization"), true)
+            .unwrap();
+
+        let (_, _, create_request) = resource.extract_vm_config(&config).unwrap();
+        assert_eq!(create_request.settings.cpu, Some("host".to_string()));
+        assert_eq!(create_request.settings.kvm, Some(true));
+
+        let update_request = resource.build_update_request(&config).unwrap();
+        assert_eq!(update_request.settings.cpu, Some("host".to_string()));
+        assert_eq!(update_request.settings.kvm, Some(true));
+    }
+
+    #[test]
+    fn test_nested_virtualization_cpu() {
+        assert_eq!(
+            QemuVmResource::nested_virtualization_cpu(None, "vmx"),
+            "host"
+        );
+        assert_eq!(
+            QemuVmResource::nested_virtualization_cpu(Some("host"), "vmx"),
+            "host"
+        );
+        assert_eq!(
+            QemuVmResource::nested_virtualization_cpu(Some("x86-64-v2-AES"), "vmx"),
+            "x86-64-v2-AES,flags=+vmx"
+        );
+        assert_eq!(
+            QemuVmResource::nested_virtualization_cpu(Some("cputype=EPYC,flags=+pcid"), "svm"),
+            "EPYC,flags=+pcid;+svm"
+        );
+        assert_eq!(
+            QemuVmResource::nested_virtualization_cpu(Some("EPYC,flags=+svm"), "svm"),
+            "EPYC,flags=+svm"
+        );
+    }
+
+    fn attach_existing_block(slot: &str, volid: &str, format: Option<&str>) -> Dynamic {
+        let mut block = std::collections::HashMap::new();
+        block.insert("slot".to_string(), Dynamic::String(slot.to_string()));
+        block.insert("volid".to_string(), Dynamic::String(volid.to_string()));
+        if let Some(format) = format {
+            block.insert("format".to_string(), Dynamic::String(format.to_string()));
+        }
+        Dynamic::Map(block)
+    }
+
+    fn config_with_attach_existing(blocks: Vec<Dynamic>) -> DynamicValue {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("attach_existing"), blocks)
+            .unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_validate_attach_existing() {
+        let resource = QemuVmResource::new();
+
+        let mut config = config_with_attach_existing(vec![
+            attach_existing_block("scsi1", "local-lvm:vm-100-disk-1", None),
+            attach_existing_block("scsi1", "local-lvm:vm-100-disk-2", None),
+            attach_existing_block("unused0", "vm-100-disk-3", None),
+        ]);
+        let mut disk = std::collections::HashMap::new();
+        disk.insert("slot".to_string(), Dynamic::String("scsi1".to_string()));
+        config
+            .set_list(&AttributePath::new("disk"), vec![Dynamic::Map(disk)])
+            .unwrap();
+
+        let response = resource
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_qemu_vm".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        let summaries: Vec<&str> = response
+            .diagnostics
+            .iter()
+            .map(|d| d.summary.as_str())
+            .collect();
+        assert!(summaries.contains(&"Invalid attach_existing slot"));
+        assert!(summaries.contains(&"Invalid volid"));
+        assert!(summaries.contains(&"Drive slot conflict"));
+        assert!(summaries.contains(&"Duplicate attach_existing slot"));
+    }
+
+    #[test]
+    fn test_detached_slots() {
+        let prior = config_with_attach_existing(vec![
+            attach_existing_block("scsi1", "local-lvm:vm-100-disk-1", None),
+            attach_existing_block("scsi2", "local-lvm:vm-100-disk-2", None),
+        ]);
+        let config = config_with_attach_existing(vec![attach_existing_block(
+            "scsi1",
+            "local-lvm:vm-100-disk-1",
+            None,
+        )]);
+
+        assert_eq!(
+            QemuVmResource::detached_slots(&prior, &config),
+            vec!["scsi2".to_string()]
+        );
+        assert!(QemuVmResource::detached_slots(&config, &prior).is_empty());
+    }
+
+    #[test]
+    fn test_refresh_attach_existing() {
+        let current = config_with_attach_existing(vec![
+            attach_existing_block("scsi1", "local-lvm:vm-100-disk-1", Some("raw")),
+            attach_existing_block("scsi2", "local-lvm:vm-100-disk-2", None),
+        ]);
+        let vm_config: crate::api::nodes::QemuConfig = serde_json::from_str(
+            r#"{"scsi1": "local-lvm:vm-100-disk-1,iothread=1,size=10G", "unused0": "local-lvm:vm-100-disk-2"}"#,
+        )
+        .unwrap();
+
+        let mut state = current.clone();
+        QemuVmResource::refresh_attach_existing(&mut state, &vm_config, &current);
+
+        let blocks = state
+            .get_list(&AttributePath::new("attach_existing"))
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        if let Dynamic::Map(block) = &blocks[0] {
+            assert_eq!(
+                block.get("volid"),
+                Some(&Dynamic::String("local-lvm:vm-100-disk-1".to_string()))
+            );
+            assert_eq!(
+                block.get("format"),
+                Some(&Dynamic::String("raw".to_string()))
+            );
+        } else {
+            panic!("attach_existing block should be a map");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_attach_existing_format_mismatch() {
+        let mut server = mockito::Server::new_async().await;
+        let _volume = server
+            .mock(
+                "GET",
+                "/api2/json/nodes/pve/storage/local/content/local%3A100%2Fvm-100-disk-1.qcow2",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"format": "qcow2", "size": 1073741824}}"#)
+            .create_async()
+            .await;
+
+        let client = crate::api::test_helpers::create_test_client(&server.url());
+
+        let config = config_with_attach_existing(vec![attach_existing_block(
+            "virtio1",
+            "local:100/vm-100-disk-1.qcow2",
+            Some("qcow2"),
+        )]);
+        let attached = QemuVmResource::verify_attach_existing(&client, "pve", &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            attached,
+            vec![(
+                "virtio1".to_string(),
+                "local:100/vm-100-disk-1.qcow2".to_string()
+            )]
+        );
+
+        let config = config_with_attach_existing(vec![attach_existing_block(
+            "virtio1",
+            "local:100/vm-100-disk-1.qcow2",
+            Some("raw"),
+        )]);
+        let err = QemuVmResource::verify_attach_existing(&client, "pve", &config)
+            .await
+            .unwrap_err();
+        assert_eq!(err.summary, "Volume format mismatch");
+    }
+
+    fn serial_block(id: f64, serial_type: &str, device: Option<&str>) -> Dynamic {
+        let mut block = std::collections::HashMap::new();
+        block.insert("id".to_string(), Dynamic::Number(id));
+        block.insert("type".to_string(), Dynamic::String(serial_type.to_string()));
+        if let Some(device) = device {
+            block.insert("device".to_string(), Dynamic::String(device.to_string()));
+        }
+        Dynamic::Map(block)
+    }
+
+    fn vga_block(vga_type: &str, memory: Option<f64>) -> Dynamic {
+        let mut block = std::collections::HashMap::new();
+        block.insert("type".to_string(), Dynamic::String(vga_type.to_string()));
+        if let Some(memory) = memory {
+            block.insert("memory".to_string(), Dynamic::Number(memory));
+        }
+        Dynamic::Map(block)
+    }
+
+    fn config_with_console(serials: Vec<Dynamic>, vga: Option<Dynamic>) -> DynamicValue {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("serial"), serials)
+            .unwrap();
+        config
+            .set_list(&AttributePath::new("vga"), vga.into_iter().collect())
+            .unwrap();
+        config
+    }
+
+    async fn validation_summaries(config: DynamicValue) -> Vec<String> {
+        validation_diagnostics(config)
+            .await
+            .into_iter()
+            .map(|d| d.summary)
+            .collect()
+    }
+
+    async fn validation_diagnostics(config: DynamicValue) -> Vec<Diagnostic> {
+        QemuVmResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_qemu_vm".to_string(),
+                    config,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await
+            .diagnostics
+    }
+
+    #[tokio::test]
+    async fn test_validate_serial_and_vga() {
+        let valid = config_with_console(
+            vec![
+                serial_block(0.0, "socket", None),
+                serial_block(1.0, "device", Some("/dev/ttyS1")),
+            ],
+            Some(vga_block("serial0", None)),
+        );
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let invalid = config_with_console(
+            vec![
+                serial_block(1.0, "socket", None),
+                serial_block(1.0, "device", Some("ttyS1")),
+                serial_block(4.0, "pty", None),
+            ],
+            Some(vga_block("serial0", Some(1024.0))),
+        );
+        let summaries = validation_summaries(invalid).await;
+        for expected in [
+            "Duplicate serial port",
+            "Invalid serial device",
+            "Invalid serial port",
+            "Invalid serial type",
+            "Missing serial port for VGA",
+            "Invalid VGA memory",
+        ] {
+            assert!(summaries.iter().any(|s| s == expected), "{}", expected);
+        }
+    }
+
+    #[test]
+    fn test_serial_and_vga_round_trip() {
+        let config = config_with_console(
+            vec![
+                serial_block(0.0, "socket", None),
+                serial_block(2.0, "device", Some("/dev/ttyUSB0")),
+            ],
+            Some(vga_block("qxl", Some(32.0))),
+        );
+
+        let request = QemuVmResource::new().build_update_request(&config).unwrap();
+        assert_eq!(request.settings.serial0.as_deref(), Some("socket"));
+        assert_eq!(request.settings.serial2.as_deref(), Some("/dev/ttyUSB0"));
+        assert_eq!(request.settings.vga.as_deref(), Some("qxl,memory=32"));
+
+        let mut vm_config: crate::api::nodes::QemuConfig = serde_json::from_value(
+            serde_json::json!({"serial0": "socket", "serial2": "/dev/ttyUSB0", "serial3": "socket", "vga": "qxl,memory=32"}),
+        )
+        .unwrap();
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_with_nested_blocks(&mut state, &vm_config, &config);
+        let serials = state.get_list(&AttributePath::new("serial")).unwrap();
+        assert_eq!(serials.len(), 2);
+        assert_eq!(
+            serials[1],
+            QemuVmResource::parse_serial_string("/dev/ttyUSB0", 2)
+        );
+        let vga = state.get_list(&AttributePath::new("vga")).unwrap();
+        assert_eq!(
+            vga,
+            vec![QemuVmResource::parse_vga_string("type=qxl,memory=32")]
+        );
+
+        vm_config.vga = None;
+        QemuVmResource::populate_state_with_nested_blocks(&mut state, &vm_config, &config);
+        assert!(state
+            .get_list(&AttributePath::new("vga"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_removed_console_devices() {
+        let prior = config_with_console(
+            vec![
+                serial_block(0.0, "socket", None),
+                serial_block(1.0, "socket", None),
+            ],
+            Some(vga_block("serial0", None)),
+        );
+        let config = config_with_console(vec![serial_block(0.0, "socket", None)], None);
+
+        assert_eq!(
+            QemuVmResource::removed_console_devices(&prior, &config),
+            vec!["serial1".to_string(), "vga".to_string()]
+        );
+        assert!(QemuVmResource::removed_console_devices(&config, &prior).is_empty());
+    }
+
+    fn numa_block(id: f64, cpus: &str, memory: f64, hostnodes: Option<&str>) -> Dynamic {
+        let mut block = std::collections::HashMap::new();
+        block.insert("id".to_string(), Dynamic::Number(id));
+        block.insert("cpus".to_string(), Dynamic::String(cpus.to_string()));
+        block.insert("memory".to_string(), Dynamic::Number(memory));
+        if let Some(hostnodes) = hostnodes {
+            block.insert(
+                "hostnodes".to_string(),
+                Dynamic::String(hostnodes.to_string()),
+            );
+            block.insert("policy".to_string(), Dynamic::String("bind".to_string()));
+        }
+        Dynamic::Map(block)
+    }
+
+    fn config_with_numa(nodes: Vec<Dynamic>) -> DynamicValue {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(
+                &AttributePath::new("cpu"),
+                cpu_block(&[("numa", Dynamic::Bool(true))]),
+            )
+            .unwrap();
+        config
+            .set_number(&AttributePath::new("sockets"), 2.0)
+            .unwrap();
+        config
+            .set_number(&AttributePath::new("cores"), 2.0)
+            .unwrap();
+        config
+            .set_list(
+                &AttributePath::new("memory"),
+                vec![Dynamic::Map(
+                    [("dedicated".to_string(), Dynamic::Number(4096.0))].into(),
+                )],
+            )
+            .unwrap();
+        config
+            .set_list(&AttributePath::new("numa_node"), nodes)
+            .unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_validate_numa_nodes() {
+        let valid = config_with_numa(vec![
+            numa_block(0.0, "0-1", 2048.0, Some("0")),
+            numa_block(1.0, "2;3", 2048.0, None),
+        ]);
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let mut invalid = config_with_numa(vec![
+            numa_block(0.0, "0-2", 2048.0, None),
+            numa_block(0.0, "2-4", 1024.0, None),
+            numa_block(8.0, "x", 512.0, None),
+        ]);
+        invalid
+            .set_list(
+                &AttributePath::new("cpu"),
+                cpu_block(&[("numa", Dynamic::Bool(false))]),
+            )
+            .unwrap();
+        let summaries = validation_summaries(invalid).await;
+        for expected in [
+            "NUMA not enabled",
+            "Duplicate NUMA node",
+            "Invalid NUMA node",
+            "Overlapping NUMA cpus",
+            "NUMA cpus out of range",
+            "Invalid NUMA cpus",
+            "NUMA memory mismatch",
+        ] {
+            assert!(summaries.iter().any(|s| s == expected), "{}", expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_numa_skips_unknown_topology() {
+        let mut config = config_with_numa(vec![numa_block(0.0, "0-7", 4096.0, None)]);
+        config.mark_unknown(&AttributePath::new("cores")).unwrap();
+        assert!(validation_summaries(config).await.is_empty());
+    }
+
+    #[test]
+    fn test_numa_nodes_round_trip() {
+        let config = config_with_numa(vec![
+            numa_block(0.0, "0-1", 2048.0, Some("0")),
+            numa_block(3.0, "2-3", 2048.0, None),
+        ]);
+
+        let request = QemuVmResource::new().build_update_request(&config).unwrap();
+        assert_eq!(request.settings.numa, Some(true));
+        assert_eq!(
+            request.settings.numa0.as_deref(),
+            Some("cpus=0-1,hostnodes=0,memory=2048,policy=bind")
+        );
+        assert!(request.settings.numa1.is_none());
+        assert_eq!(
+            request.settings.numa3.as_deref(),
+            Some("cpus=2-3,memory=2048")
+        );
+
+        let vm_config: crate::api::nodes::QemuConfig = serde_json::from_value(serde_json::json!({
+            "numa": 1,
+            "numa0": "cpus=0-1,hostnodes=0,memory=2048,policy=bind",
+            "numa1": "cpus=4,memory=512",
+            "numa3": "cpus=2-3,memory=2048"
+        }))
+        .unwrap();
+        assert_eq!(vm_config.numa, Some(true));
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_with_nested_blocks(&mut state, &vm_config, &config);
+        assert_eq!(
+            state.get_list(&AttributePath::new("numa_node")).unwrap(),
+            vec![
+                NumaNode::from_api_string(0, "cpus=0-1,hostnodes=0,memory=2048,policy=bind")
+                    .to_dynamic(),
+                NumaNode::from_api_string(3, "cpus=2-3,memory=2048").to_dynamic(),
+            ]
+        );
+
+        let prior = config;
+        let config = config_with_numa(vec![numa_block(0.0, "0-3", 4096.0, None)]);
+        assert_eq!(
+            QemuVmResource::removed_numa_nodes(&prior, &config),
+            vec!["numa3".to_string()]
+        );
+    }
+
+    /// State as the provider stored it before the schema had a version
+    const BASELINE_STATE: &str = r#"{
+        "vmid": 100, "name": "web", "target_node": "pve1", "tags": "web;prod",
+        "clone": null, "full_clone": null, "os_type": "cloud-init", "bios": "seabios",
+        "machine": null, "cpu_type": "x86-64-v2-AES,flags=+aes", "cores": 2, "sockets": 1,
+        "vcpus": null, "memory": 2048, "balloon": 1024, "boot": "order=scsi0",
+        "bootdisk": null, "onboot": true, "scsihw": "virtio-scsi-single", "agent": 1,
+        "qemu_os": "l26", "ipconfig0": "ip=dhcp", "ipconfig1": null, "ipconfig2": null,
+        "ipconfig3": null, "ciuser": "debian", "cipassword": null, "ciupgrade": null,
+        "sshkeys": "ssh-ed25519 AAAA admin", "skip_ipv4": null, "skip_ipv6": null,
+        "additional_wait": null, "automatic_reboot": null, "clone_wait": null,
+        "define_connection_info": null, "description": null, "start": true,
+        "tablet": true, "protection": false,
+        "network": [{"id": 0, "model": "virtio", "bridge": "vmbr0", "firewall": false,
+            "tag": null, "macaddr": "BC:24:11:00:00:01", "rate": null, "queues": null,
+            "link_down": false, "mtu": null}],
+        "disk": [{"slot": "scsi0", "type": "disk", "storage": "local-lvm", "size": "32G",
+            "format": "raw", "discard": true, "emulatessd": null, "iothread": true,
+            "backup": null, "replicate": null, "readonly": null}],
+        "cdrom": [], "cloudinit_drive": [{"slot": "ide2", "storage": "local-lvm"}],
+        "serial": [{"id": 0, "type": "socket"}], "efidisk": []
+    }"#;
+
+    async fn upgrade_from(version: i64, json: &str) -> DynamicValue {
+        let response = QemuVmResource::new()
+            .upgrade_state(
+                Context::new(),
+                tfplug::resource::UpgradeResourceStateRequest {
+                    type_name: "proxmox_qemu_vm".to_string(),
+                    version,
+                    raw_state: tfplug::types::RawState {
+                        json: Some(json.as_bytes().to_vec()),
+                        flatmap: None,
+                    },
+                },
+            )
+            .await;
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        response.upgraded_state
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_state_moves_memory_into_block() {
+        let state = upgrade_from(0, BASELINE_STATE).await;
+        let memory = state.get_list(&AttributePath::new("memory")).unwrap();
+        assert_eq!(memory.len(), 1);
+        let Dynamic::Map(memory) = &memory[0] else {
+            panic!("memory must be a block");
+        };
+        assert_eq!(memory["dedicated"], Dynamic::Number(2048.0));
+        assert_eq!(memory["floating"], Dynamic::Number(1024.0));
+        assert_eq!(memory["hugepages"], Dynamic::Null);
+        assert!(!state
+            .get_map(&AttributePath::root())
+            .unwrap()
+            .contains_key("balloon"));
+        assert_eq!(
+            state.get_string(&AttributePath::new("name")).unwrap(),
+            "web"
+        );
+
+        let unset = r#"{"vmid": 100, "name": "web", "memory": null, "balloon": null}"#;
+        let state = upgrade_from(0, unset).await;
+        assert_eq!(
+            state.get_list(&AttributePath::new("memory")).unwrap(),
+            vec![]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_state_moves_cpu_type_into_block() {
+        let state = upgrade_from(0, BASELINE_STATE).await;
+        let cpu = state.get_list(&AttributePath::new("cpu")).unwrap();
+        assert_eq!(cpu.len(), 1);
+        let Dynamic::Map(cpu) = &cpu[0] else {
+            panic!("cpu must be a block");
+        };
+        assert_eq!(cpu["type"], Dynamic::String("x86-64-v2-AES".to_string()));
+        assert_eq!(
+            cpu["flags"],
+            Dynamic::List(vec![Dynamic::String("+aes".to_string())])
+        );
+        assert_eq!(cpu["numa"], Dynamic::Null);
+
+        let numa = r#"{"vmid": 100, "name": "web", "cpu_type": "host", "numa": true}"#;
+        let state = upgrade_from(1, numa).await;
+        let cpu = AttributePath::new("cpu").index(0);
+        assert_eq!(
+            state.get_string(&cpu.clone().attribute("type")).unwrap(),
+            "host"
+        );
+        assert!(state.get_bool(&cpu.attribute("numa")).unwrap());
+        assert!(!state
+            .get_map(&AttributePath::root())
+            .unwrap()
+            .contains_key("cpu_type"));
+
+        let unset = r#"{"vmid": 100, "name": "web", "cpu_type": null}"#;
+        let state = upgrade_from(1, unset).await;
+        assert_eq!(state.get_list(&AttributePath::new("cpu")).unwrap(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_state_splits_tags() {
+        let state = upgrade_from(0, BASELINE_STATE).await;
+        assert_eq!(
+            state.get_list(&AttributePath::new("tags")).unwrap(),
+            vec![
+                Dynamic::String("prod".to_string()),
+                Dynamic::String("web".to_string())
+            ]
+        );
+
+        let commas = r#"{"vmid": 100, "name": "web", "tags": "web,db"}"#;
+        let state = upgrade_from(2, commas).await;
+        assert_eq!(
+            state.get_list(&AttributePath::new("tags")).unwrap(),
+            vec![
+                Dynamic::String("db".to_string()),
+                Dynamic::String("web".to_string())
+            ]
+        );
+
+        let empty = r#"{"vmid": 100, "name": "web", "tags": ""}"#;
+        let state = upgrade_from(2, empty).await;
+        assert_eq!(
+            state.get_map(&AttributePath::root()).unwrap()["tags"],
+            Dynamic::Null
+        );
+    }
+
+    fn config_with_memory(settings: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut block: std::collections::HashMap<String, Dynamic> = settings
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        block
+            .entry("dedicated".to_string())
+            .or_insert(Dynamic::Number(4096.0));
+        for name in ["floating", "shares", "hugepages", "keep_hugepages"] {
+            block.entry(name.to_string()).or_insert(Dynamic::Null);
+        }
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("memory"), vec![Dynamic::Map(block)])
+            .unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_validate_memory_block() {
+        let valid = config_with_memory(&[
+            ("floating", Dynamic::Number(1024.0)),
+            ("shares", Dynamic::Number(500.0)),
+            ("hugepages", Dynamic::String("2".to_string())),
+            ("keep_hugepages", Dynamic::Bool(true)),
+        ]);
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let invalid = config_with_memory(&[
+            ("floating", Dynamic::Number(8192.0)),
+            ("shares", Dynamic::Number(60000.0)),
+            ("hugepages", Dynamic::String("4".to_string())),
+        ]);
+        let summaries = validation_summaries(invalid).await;
+        for expected in [
+            "Invalid floating memory",
+            "Invalid memory shares",
+            "Invalid hugepages",
+        ] {
+            assert!(summaries.iter().any(|s| s == expected), "{}", expected);
+        }
+
+        let keep_only = config_with_memory(&[("keep_hugepages", Dynamic::Bool(true))]);
+        assert_eq!(
+            validation_summaries(keep_only).await,
+            vec!["Missing hugepages".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_memory_block_round_trip() {
+        let config = config_with_memory(&[
+            ("floating", Dynamic::Number(0.0)),
+            ("hugepages", Dynamic::String("1024".to_string())),
+            ("keep_hugepages", Dynamic::Bool(true)),
+        ]);
+
+        let request = QemuVmResource::new().build_update_request(&config).unwrap();
+        assert_eq!(request.settings.memory, Some(4096));
+        assert_eq!(request.settings.balloon, Some(0));
+        assert_eq!(request.settings.hugepages.as_deref(), Some("1024"));
+        assert_eq!(request.settings.keephugepages, Some(true));
+        assert!(request.settings.shares.is_none());
+
+        let vm_config: crate::api::nodes::QemuConfig = serde_json::from_value(serde_json::json!({
+            "memory": "4096",
+            "balloon": 0,
+            "hugepages": "1024",
+            "keephugepages": 1
+        }))
+        .unwrap();
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &config);
+        assert_eq!(
+            state.get_list(&AttributePath::new("memory")).unwrap(),
+            config.get_list(&AttributePath::new("memory")).unwrap()
+        );
+
+        let prior = config_with_memory(&[
+            ("floating", Dynamic::Number(1024.0)),
+            ("shares", Dynamic::Number(500.0)),
+            ("hugepages", Dynamic::String("2".to_string())),
+        ]);
+        let config = config_with_memory(&[("hugepages", Dynamic::String("2".to_string()))]);
+        assert_eq!(
+            QemuVmResource::removed_memory_settings(&prior, &config),
+            vec!["balloon".to_string(), "shares".to_string()]
+        );
+    }
+
+    fn config_with_cpu(settings: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("cpu"), cpu_block(settings))
+            .unwrap();
+        config
+    }
+
+    fn cpu_flags(flags: &[&str]) -> Dynamic {
+        Dynamic::List(
+            flags
+                .iter()
+                .map(|flag| Dynamic::String(flag.to_string()))
+                .collect(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_validate_cpu_block() {
+        let valid = config_with_cpu(&[
+            ("type", Dynamic::String("host".to_string())),
+            ("flags", cpu_flags(&["+aes", "-pcid", "+md-clear"])),
+            ("units", Dynamic::Number(1024.0)),
+            ("limit", Dynamic::Number(1.5)),
+            ("affinity", Dynamic::String("0-3,8".to_string())),
+        ]);
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let invalid = config_with_cpu(&[
+            ("flags", cpu_flags(&["aes", "+"])),
+            ("units", Dynamic::Number(0.0)),
+            ("limit", Dynamic::Number(200.0)),
+            ("affinity", Dynamic::String("0-3;8".to_string())),
+        ]);
+        let summaries = validation_summaries(invalid).await;
+        assert_eq!(
+            summaries,
+            vec![
+                "Invalid CPU flag".to_string(),
+                "Invalid CPU flag".to_string(),
+                "Invalid CPU units".to_string(),
+                "Invalid CPU limit".to_string(),
+                "Invalid CPU affinity".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cpu_block_round_trip() {
+        let config = config_with_cpu(&[
+            ("type", Dynamic::String("EPYC".to_string())),
+            ("flags", cpu_flags(&["+aes", "-pcid"])),
+            ("units", Dynamic::Number(2048.0)),
+            ("affinity", Dynamic::String("0-3".to_string())),
+            ("numa", Dynamic::Bool(false)),
+        ]);
+
+        let request = QemuVmResource::new().build_update_request(&config).unwrap();
+        assert_eq!(
+            request.settings.cpu.as_deref(),
+            Some("EPYC,flags=+aes;-pcid")
+        );
+        assert_eq!(request.settings.cpuunits, Some(2048));
+        assert_eq!(request.settings.affinity.as_deref(), Some("0-3"));
+        assert_eq!(request.settings.numa, Some(false));
+        assert!(request.settings.cpulimit.is_none());
+
+        // Proxmox may return the type with its key and leaves false flags out
+        let vm_config: crate::api::nodes::QemuConfig = serde_json::from_value(serde_json::json!({
+            "cpu": "cputype=EPYC,flags=+aes;-pcid,hidden=1",
+            "cpuunits": "2048",
+            "affinity": "0-3"
+        }))
+        .unwrap();
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &config);
+        assert_eq!(
+            state.get_list(&AttributePath::new("cpu")).unwrap(),
+            config.get_list(&AttributePath::new("cpu")).unwrap()
+        );
+
+        let prior = config;
+        let config = config_with_cpu(&[("type", Dynamic::String("EPYC".to_string()))]);
+        assert_eq!(
+            QemuVmResource::removed_cpu_settings(&prior, &config),
+            vec![
+                "cpuunits".to_string(),
+                "affinity".to_string(),
+                "numa".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cpu_read_back_hides_nested_virtualization() {
+        let mut config = config_with_cpu(&[("flags", cpu_flags(&["+aes"]))]);
+        config
+            .set_bool(&AttributePath::new("nested_virtualization"), true)
+            .unwrap();
+
+        let vm_config = crate::api::nodes::QemuConfig {
+            cpu: Some("host,flags=+aes;+vmx".to_string()),
+            ..Default::default()
+        };
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &config);
+        assert_eq!(
+            state.get_list(&AttributePath::new("cpu")).unwrap(),
+            config.get_list(&AttributePath::new("cpu")).unwrap()
+        );
+    }
+
+    fn config_with_smbios(settings: &[(&str, &str)]) -> DynamicValue {
+        let mut block: std::collections::HashMap<String, Dynamic> = settings
+            .iter()
+            .map(|(name, value)| (name.to_string(), Dynamic::String(value.to_string())))
+            .collect();
+        for name in [
+            "uuid",
+            "serial",
+            "manufacturer",
+            "product",
+            "version",
+            "sku",
+            "family",
+        ] {
+            block.entry(name.to_string()).or_insert(Dynamic::Null);
+        }
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("smbios"), vec![Dynamic::Map(block)])
+            .unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_validate_smbios_block() {
+        let valid = config_with_smbios(&[
+            ("uuid", "5a4f3c2e-1b0d-4e8f-9a7b-6c5d4e3f2a1b"),
+            ("serial", "ASSET-0042"),
+        ]);
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let long_serial = "x".repeat(400);
+        let invalid = config_with_smbios(&[("uuid", "not-a-uuid"), ("serial", &long_serial)]);
+        assert_eq!(
+            validation_summaries(invalid).await,
+            vec![
+                "Invalid SMBIOS UUID".to_string(),
+                "SMBIOS settings too long".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_smbios_block_round_trip() {
+        let config =
+            config_with_smbios(&[("serial", "ASSET 42, rack=3"), ("manufacturer", "Acme")]);
+
+        assert_eq!(
+            QemuVmResource::smbios_config(&config, None).as_deref(),
+            Some("serial=QVNTRVQgNDIsIHJhY2s9Mw==,manufacturer=QWNtZQ==,base64=1")
+        );
+        let uuid = "5a4f3c2e-1b0d-4e8f-9a7b-6c5d4e3f2a1b";
+        let smbios1 = QemuVmResource::smbios_config(&config, Some(uuid.to_string())).unwrap();
+        assert!(smbios1.starts_with("uuid=5a4f3c2e-1b0d-4e8f-9a7b-6c5d4e3f2a1b,serial="));
+
+        let vm_config = crate::api::nodes::QemuConfig {
+            smbios1: Some(smbios1),
+            ..Default::default()
+        };
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &config);
+        let expected = config_with_smbios(&[
+            ("uuid", uuid),
+            ("serial", "ASSET 42, rack=3"),
+            ("manufacturer", "Acme"),
+        ]);
+        assert_eq!(
+            state.get_list(&AttributePath::new("smbios")).unwrap(),
+            expected.get_list(&AttributePath::new("smbios")).unwrap()
+        );
+
+        // Values set outside of base64 mode are read as they are
+        assert_eq!(
+            QemuVmResource::parse_smbios_string("uuid=abc,product=pve"),
+            QemuVmResource::parse_smbios_string("product=cHZl,uuid=abc,base64=1")
+        );
+
+        // Dropping the block keeps the machine's UUID
+        let without = create_test_dynamic_value();
+        assert_eq!(
+            QemuVmResource::smbios_config(&without, Some(uuid.to_string())).as_deref(),
+            Some("uuid=5a4f3c2e-1b0d-4e8f-9a7b-6c5d4e3f2a1b")
+        );
+    }
+
+    fn config_with_startup(settings: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut block: std::collections::HashMap<String, Dynamic> = settings
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        for name in ["order", "up_delay", "down_delay"] {
+            block.entry(name.to_string()).or_insert(Dynamic::Null);
+        }
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("startup"), vec![Dynamic::Map(block)])
+            .unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_validate_startup_block() {
+        let valid = config_with_startup(&[("order", Dynamic::Number(1.0))]);
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let invalid = config_with_startup(&[
+            ("order", Dynamic::Number(-1.0)),
+            ("up_delay", Dynamic::Number(2.5)),
+        ]);
+        assert_eq!(
+            validation_summaries(invalid).await,
+            vec![
+                "Invalid startup setting".to_string(),
+                "Invalid startup setting".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_startup_block_round_trip() {
+        let config = config_with_startup(&[
+            ("order", Dynamic::Number(2.0)),
+            ("down_delay", Dynamic::Number(60.0)),
+        ]);
+
+        let request = QemuVmResource::new().build_update_request(&config).unwrap();
+        assert_eq!(request.settings.startup.as_deref(), Some("order=2,down=60"));
+
+        // Read back doesn't depend on the order Proxmox returns the keys in
+        let vm_config = crate::api::nodes::QemuConfig {
+            startup: Some("down=60,order=2".to_string()),
+            ..Default::default()
+        };
+        let mut state = create_test_dynamic_value();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &config);
+        assert_eq!(
+            state.get_list(&AttributePath::new("startup")).unwrap(),
+            config.get_list(&AttributePath::new("startup")).unwrap()
+        );
+
+        let empty = config_with_startup(&[]);
+        assert!(QemuVmResource::startup_config(&empty).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_validate_tags() {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(
+                &AttributePath::new("tags"),
+                vec![
+                    Dynamic::String("k8s_node-1.2".to_string()),
+                    Dynamic::String("-bad".to_string()),
+                    Dynamic::String("has space".to_string()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            validation_summaries(config).await,
+            vec!["Invalid tag".to_string(), "Invalid tag".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_tags_round_trip() {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(
+                &AttributePath::new("tags"),
+                vec![
+                    Dynamic::String("web".to_string()),
+                    Dynamic::String("production".to_string()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            QemuVmResource::tags_config(&config).as_deref(),
+            Some("production;web")
+        );
+
+        config
+            .set_list(&AttributePath::new("tags"), vec![])
+            .unwrap();
+        assert!(QemuVmResource::tags_config(&config).is_none());
+
+        assert_eq!(
+            QemuVmResource::parse_tags("web,production web;;test"),
+            vec![
+                Dynamic::String("production".to_string()),
+                Dynamic::String("test".to_string()),
+                Dynamic::String("web".to_string()),
+            ]
+        );
+    }
+
+    fn config_with_cdroms(cdroms: &[(&str, &str)]) -> DynamicValue {
+        let blocks = cdroms
+            .iter()
+            .map(|(slot, iso)| {
+                let mut block = std::collections::HashMap::new();
+                block.insert("slot".to_string(), Dynamic::String(slot.to_string()));
+                block.insert("iso".to_string(), Dynamic::String(iso.to_string()));
+                Dynamic::Map(block)
+            })
+            .collect();
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&AttributePath::new("cdrom"), blocks)
+            .unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_validate_cdrom() {
+        let valid = config_with_cdroms(&[("ide2", "local:iso/ubuntu.iso"), ("sata1", "none")]);
+        assert!(validation_summaries(valid).await.is_empty());
+
+        let invalid = config_with_cdroms(&[
+            ("virtio1", "local:iso/ubuntu.iso"),
+            ("ide2", "ubuntu.iso"),
+            ("ide2", "none"),
+        ]);
+        assert_eq!(
+            validation_summaries(invalid).await,
+            vec![
+                "Invalid cdrom slot".to_string(),
+                "Invalid cdrom iso".to_string(),
+                "Duplicate cdrom slot".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_cdrom_points_at_block_attribute() {
+        let invalid = config_with_cdroms(&[("ide2", "none"), ("virtio1", "ubuntu.iso")]);
+        let paths: Vec<String> = validation_diagnostics(invalid)
+            .await
+            .iter()
+            .filter_map(|d| d.attribute.as_ref().map(|path| path.to_string()))
+            .collect();
+        assert_eq!(paths, vec!["cdrom[1].slot", "cdrom[1].iso"]);
+    }
+
+    #[tokio::test]
+    async fn test_config_validators_reject_cdrom_with_clone_and_lone_full_clone() {
+        let mut config = config_with_cdroms(&[("ide2", "none")]);
+        config
+            .set_string(&AttributePath::new("clone"), "ubuntu-template".to_string())
+            .unwrap();
+        let diagnostics = validate_all(config).await;
+        let conflict = diagnostics
+            .iter()
+            .find(|d| d.summary == "Conflicting attributes")
+            .unwrap();
+        assert_eq!(conflict.attribute.as_ref().unwrap().to_string(), "cdrom");
+
+        let mut config = create_test_dynamic_value();
+        config
+            .set_bool(&AttributePath::new("full_clone"), true)
+            .unwrap();
+        let diagnostics = validate_all(config).await;
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.detail == "\"clone\" must be set when \"full_clone\" is set"));
+    }
+
+    fn config_with_restore_from(config: &mut DynamicValue) {
+        let mut block = std::collections::HashMap::new();
+        block.insert(
+            "volid".to_string(),
+            Dynamic::String("pbs:backup/vm/100/2024-05-01T12:00:00Z".to_string()),
+        );
+        block.insert(
+            "storage".to_string(),
+            Dynamic::String("local-lvm".to_string()),
+        );
+        block.insert("unique".to_string(), Dynamic::Bool(true));
+        config
+            .set_list(
+                &AttributePath::new("restore_from"),
+                vec![Dynamic::Map(block)],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_extract_vm_config_restores_archive() {
+        let mut config = create_test_dynamic_value();
+        config_with_restore_from(&mut config);
+
+        let (_, _, create_request) = QemuVmResource::new().extract_vm_config(&config).unwrap();
+        assert_eq!(
+            create_request.archive.as_deref(),
+            Some("pbs:backup/vm/100/2024-05-01T12:00:00Z")
+        );
+        assert_eq!(create_request.storage.as_deref(), Some("local-lvm"));
+        assert_eq!(create_request.unique, Some(true));
+        assert_eq!(create_request.settings.memory, Some(2048));
+
+        let (_, _, create_request) = QemuVmResource::new()
+            .extract_vm_config(&create_test_dynamic_value())
+            .unwrap();
+        assert_eq!(create_request.archive, None);
+        assert_eq!(create_request.storage, None);
+    }
+
+    #[tokio::test]
+    async fn test_config_validators_reject_drives_with_restore_from() {
+        let mut config = create_test_dynamic_value_with_disk_blocks();
+        config_with_restore_from(&mut config);
+        let diagnostics = validate_all(config).await;
+        let conflict = diagnostics
+            .iter()
+            .find(|d| d.summary == "Conflicting attributes")
+            .unwrap();
+        assert_eq!(conflict.attribute.as_ref().unwrap().to_string(), "disk");
+    }
+
+    #[test]
+    fn test_create_and_update_share_settings() {
+        let resource = QemuVmResource::new();
+        let mut config = create_test_dynamic_value_with_disk_blocks();
+        config
+            .set_string(&AttributePath::new("qemu_os"), "l26".to_string())
+            .unwrap();
+
+        let (_, _, create_request) = resource.extract_vm_config(&config).unwrap();
+        let update_request = resource.build_update_request(&config).unwrap();
+
+        assert_eq!(update_request.settings.ostype.as_deref(), Some("l26"));
+        assert_eq!(
+            serde_json::to_value(&update_request.settings).unwrap(),
+            serde_json::to_value(&create_request.settings).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_update_ejects_cdrom() {
+        let resource = QemuVmResource::new();
+        let config = config_with_cdroms(&[("ide2", "none"), ("sata1", "local:iso/virtio.iso")]);
+
+        let update_request = resource.build_update_request(&config).unwrap();
+        assert_eq!(
+            update_request.settings.ide2,
+            Some("none,media=cdrom".to_string())
+        );
+        assert_eq!(
+            update_request.settings.sata1,
+            Some("local:iso/virtio.iso,media=cdrom".to_string())
+        );
+    }
+
+    #[test]
+    fn test_removed_cdrom_slots() {
+        let prior = config_with_cdroms(&[("ide2", "local:iso/ubuntu.iso"), ("sata1", "none")]);
+        let config = config_with_cdroms(&[("ide2", "none")]);
+
+        assert_eq!(
+            QemuVmResource::removed_cdrom_slots(&prior, &config),
+            vec!["sata1".to_string()]
+        );
+        assert!(QemuVmResource::removed_cdrom_slots(&config, &prior).is_empty());
+    }
+
+    #[test]
+    fn test_hotplug_features() {
+        assert_eq!(
+            QemuVmResource::hotplug_features(None),
+            vec!["network", "disk", "usb"]
+        );
+        assert_eq!(
+            QemuVmResource::hotplug_features(Some("1")),
+            vec!["network", "disk", "usb"]
+        );
+        assert!(QemuVmResource::hotplug_features(Some("0")).is_empty());
+        assert_eq!(
+            QemuVmResource::hotplug_features(Some("disk, memory,cpu")),
+            vec!["disk", "memory", "cpu"]
+        );
+    }
+
+    #[test]
+    fn test_restart_changes() {
+        let prior = config_with_memory(&[("dedicated", Dynamic::Number(2048.0))]);
+        let mut planned = config_with_memory(&[
+            ("dedicated", Dynamic::Number(4096.0)),
+            ("floating", Dynamic::Number(1024.0)),
+        ]);
+        planned
+            .set_number(&AttributePath::new("cores"), 4.0)
+            .unwrap();
+        planned
+            .set_list(
+                &AttributePath::new("sshkeys"),
+                vec![Dynamic::String("ssh-ed25519 AAAA".to_string())],
+            )
+            .unwrap();
+        planned
+            .set_string(&AttributePath::new("description"), "web".to_string())
+            .unwrap();
+
+        let defaults = QemuVmResource::hotplug_features(None);
+        assert_eq!(
+            QemuVmResource::restart_changes(&prior, &planned, &defaults),
+            vec!["cores", "sshkeys", "memory.dedicated"]
+        );
+
+        let hotplug = QemuVmResource::hotplug_features(Some("memory,cloudinit"));
+        assert_eq!(
+            QemuVmResource::restart_changes(&prior, &planned, &hotplug),
+            vec!["cores"]
+        );
+        assert!(QemuVmResource::restart_changes(&prior, &prior, &[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_agent_wait() {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_number(&AttributePath::new("agent_wait_timeout"), 120.0)
+            .unwrap();
+        assert_eq!(
+            validation_summaries(config.clone()).await,
+            vec!["agent_wait_timeout has no effect".to_string()]
+        );
+        assert_eq!(QemuVmResource::agent_wait_timeout(&config), None);
+
+        config.set_bool(&AttributePath::new("start"), true).unwrap();
+        let disabled = Agent {
+            enabled: Some(false),
+            ..Default::default()
+        };
+        config
+            .set_list(&AttributePath::new("agent"), vec![disabled.to_dynamic()])
+            .unwrap();
+        assert_eq!(QemuVmResource::agent_wait_timeout(&config), None);
+
+        config
+            .set_list(
+                &AttributePath::new("agent"),
+                vec![Agent::default().to_dynamic()],
+            )
+            .unwrap();
+        assert!(validation_summaries(config.clone()).await.is_empty());
+        assert_eq!(QemuVmResource::agent_wait_timeout(&config), Some(120));
+    }
+
+    #[tokio::test]
+    async fn test_agent_block() {
+        let mut config = create_test_dynamic_value();
+        let agent = Agent {
+            trim_cloned_disks: Some(true),
+            r#type: Some("isa".to_string()),
+            ..Default::default()
+        };
+        config
+            .set_list(&AttributePath::new("agent"), vec![agent.to_dynamic()])
+            .unwrap();
+        assert_eq!(
+            QemuVmResource::agent_config(&config).as_deref(),
+            Some("1,fstrim_cloned_disks=1,type=isa")
+        );
+        assert!(validation_summaries(config.clone()).await.is_empty());
+
+        let agent = Agent {
+            enabled: Some(false),
+            r#type: Some("usb".to_string()),
+            ..Default::default()
+        };
+        config
+            .set_list(&AttributePath::new("agent"), vec![agent.to_dynamic()])
+            .unwrap();
+        assert_eq!(
+            QemuVmResource::agent_config(&config).as_deref(),
+            Some("0,type=usb")
+        );
+        assert_eq!(
+            validation_summaries(config).await,
+            vec!["Invalid agent type".to_string()]
+        );
+        assert_eq!(
+            QemuVmResource::agent_config(&create_test_dynamic_value()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_agent_string_keeps_planned_defaults() {
+        let planned = Agent {
+            trim_cloned_disks: Some(false),
+            r#type: Some("virtio".to_string()),
+            ..Default::default()
+        };
+        // Proxmox leaves out the settings at their default
+        assert_eq!(
+            QemuVmResource::parse_agent_string("1", Some(&planned)),
+            planned.to_dynamic()
+        );
+        assert_eq!(
+            QemuVmResource::parse_agent_string("enabled=1,type=virtio", Some(&planned)),
+            planned.to_dynamic()
+        );
+
+        // Changes made outside Terraform show up as drift
+        assert_eq!(
+            QemuVmResource::parse_agent_string("0,fstrim_cloned_disks=1,type=isa", Some(&planned)),
+            Agent {
+                enabled: Some(false),
+                trim_cloned_disks: Some(true),
+                r#type: Some("isa".to_string()),
+            }
+            .to_dynamic()
+        );
+
+        // Without a planned block, e.g. on import, the option is read as is
+        assert_eq!(
+            QemuVmResource::parse_agent_string("enabled=1", None),
+            Agent {
+                enabled: Some(true),
+                ..Default::default()
+            }
+            .to_dynamic()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_state_from_numeric_agent() {
+        for (agent, expected) in [
+            (
+                "1",
+                vec![Agent {
+                    enabled: Some(true),
+                    ..Default::default()
+                }
+                .to_dynamic()],
+            ),
+            ("0", vec![]),
+            ("null", vec![]),
+        ] {
+            let json = format!(r#"{{"vmid": 100, "name": "web", "agent": {}}}"#, agent);
+            let state = upgrade_from(3, &json).await;
+            assert_eq!(
+                state.get_list(&AttributePath::new("agent")).unwrap(),
+                expected
+            );
+            assert_eq!(
+                state.get_string(&AttributePath::new("name")).unwrap(),
+                "web"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_state_splits_sshkeys() {
+        for (sshkeys, expected) in [
+            (
+                r#""ssh-ed25519 AAAA a\n\nssh-rsa BBBB b\n""#,
+                Dynamic::List(vec![
+                    Dynamic::String("ssh-ed25519 AAAA a".to_string()),
+                    Dynamic::String("ssh-rsa BBBB b".to_string()),
+                ]),
+            ),
+            (r#""""#, Dynamic::Null),
+            ("null", Dynamic::Null),
+        ] {
+            let json = format!(
+                r#"{{"vmid": 100, "name": "web", "agent": [], "sshkeys": {}}}"#,
+                sshkeys
+            );
+            let state = upgrade_from(4, &json).await;
+            assert_eq!(
+                state.get(&AttributePath::new("sshkeys")).unwrap(),
+                &expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_sshkeys_join_and_split() {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(
+                &AttributePath::new("sshkeys"),
+                vec![
+                    Dynamic::String(" ssh-ed25519 AAAA a@example.com\n".to_string()),
+                    Dynamic::String(String::new()),
+                    Dynamic::String("ssh-rsa BBBB+b/c= b@example.com".to_string()),
+                ],
+            )
+            .unwrap();
+        let joined = QemuVmResource::sshkeys_config(&config).unwrap();
+        assert_eq!(
+            joined,
+            "ssh-ed25519 AAAA a@example.com\nssh-rsa BBBB+b/c= b@example.com"
+        );
+
+        // Proxmox returns the option percent-encoded, usually with a
+        // trailing newline
+        let stored = format!("{}%0A", urlencoding::encode(&joined));
+        assert_eq!(
+            QemuVmResource::parse_sshkeys(&stored),
+            vec![
+                "ssh-ed25519 AAAA a@example.com",
+                "ssh-rsa BBBB+b/c= b@example.com"
+            ]
+        );
+
+        config
+            .set_list(&AttributePath::new("sshkeys"), vec![])
+            .unwrap();
+        assert_eq!(QemuVmResource::sshkeys_config(&config), None);
+    }
+
+    #[test]
+    fn test_sshkeys_read_back() {
+        let mut planned = create_test_dynamic_value();
+        let configured = vec![Dynamic::String("ssh-rsa AAAAB3NzaC1... ".to_string())];
+        planned
+            .set_list(&AttributePath::new("sshkeys"), configured.clone())
+            .unwrap();
+        let read_back = |sshkeys: &str| {
+            let vm_config: crate::api::nodes::QemuConfig =
+                serde_json::from_value(serde_json::json!({"name": "test-vm", "sshkeys": sshkeys}))
+                    .unwrap();
+            let mut state = planned.clone();
+            QemuVmResource::populate_state_from_config(&mut state, &vm_config, &planned);
+            state.get_list(&AttributePath::new("sshkeys")).unwrap()
+        };
+
+        // The configured keys stay as written when only whitespace differs
+        assert_eq!(read_back("ssh-rsa%20AAAAB3NzaC1...%0A"), configured);
+        assert_eq!(
+            read_back("ssh-rsa%20AAAAB3NzaC1...%0Assh-ed25519%20CCCC%0A"),
+            vec![
+                Dynamic::String("ssh-rsa AAAAB3NzaC1...".to_string()),
+                Dynamic::String("ssh-ed25519 CCCC".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_back_reports_drift_of_managed_options() {
+        let mut config = create_test_dynamic_value();
+        config
+            .set_number(&AttributePath::new("vcpus"), 4.0)
+            .unwrap();
+        config
+            .set_string(&AttributePath::new("machine"), "q35".to_string())
+            .unwrap();
+        let agent = Agent {
+            enabled: Some(false),
+            ..Default::default()
+        };
+        config
+            .set_list(&AttributePath::new("agent"), vec![agent.to_dynamic()])
+            .unwrap();
+        config
+            .set_string(&AttributePath::new("qemu_os"), "l26".to_string())
+            .unwrap();
+        config
+            .set_string(
+                &AttributePath::new("hookscript"),
+                "local:snippets/hook.sh".to_string(),
+            )
+            .unwrap();
+
+        let vm_config = crate::api::nodes::QemuConfig {
+            name: Some("test-vm".to_string()),
+            vcpus: Some(2),
+            agent: Some("enabled=1,fstrim_cloned_disks=1".to_string()),
+            ostype: Some("win11".to_string()),
+            hookscript: Some("local:snippets/hook.sh".to_string()),
+            bootdisk: Some("scsi0".to_string()),
+            ..Default::default()
+        };
+        let mut state = config.clone();
+        QemuVmResource::populate_state_from_config(&mut state, &vm_config, &config);
+
+        assert_eq!(state.get_number(&AttributePath::new("vcpus")).unwrap(), 2.0);
+        assert!(state.get_string(&AttributePath::new("machine")).is_err());
+        assert_eq!(
+            state.get_list(&AttributePath::new("agent")).unwrap(),
+            vec![Agent {
+                enabled: Some(true),
+                trim_cloned_disks: Some(true),
+                r#type: None,
+            }
+            .to_dynamic()]
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("qemu_os")).unwrap(),
+            "win11"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("hookscript")).unwrap(),
+            "local:snippets/hook.sh"
+        );
+        // bootdisk isn't managed by the configuration
+        assert!(state.get_string(&AttributePath::new("bootdisk")).is_err());
+    }
+
+    #[test]
+    fn test_removed_scalar_options() {
+        let mut prior = create_test_dynamic_value();
+        prior.set_number(&AttributePath::new("vcpus"), 4.0).unwrap();
+        prior
+            .set_string(&AttributePath::new("machine"), "q35".to_string())
+            .unwrap();
+        let mut config = create_test_dynamic_value();
+        config
+            .set_string(&AttributePath::new("machine"), "pc".to_string())
+            .unwrap();
+
+        assert_eq!(
+            QemuVmResource::removed_scalar_options(&prior, &config),
+            vec!["vcpus".to_string()]
+        );
+        assert!(QemuVmResource::removed_scalar_options(&config, &prior).is_empty());
+    }
+
+    #[test]
+    fn test_merge_unknown_values() {
+        let block = |slot: &str, size: Dynamic| {
+            let mut map = HashMap::new();
+            map.insert("slot".to_string(), Dynamic::String(slot.to_string()));
+            map.insert("size".to_string(), size);
+            Dynamic::Map(map)
+        };
+        let mut planned = HashMap::new();
+        planned.insert("name".to_string(), Dynamic::String("planned".to_string()));
+        planned.insert("pending_changes".to_string(), Dynamic::Unknown);
+        planned.insert(
+            "disk".to_string(),
+            Dynamic::List(vec![
+                block("scsi1", Dynamic::Unknown),
+                block("scsi0", Dynamic::String("10G".to_string())),
+            ]),
+        );
+        let mut actual = HashMap::new();
+        actual.insert("name".to_string(), Dynamic::String("actual".to_string()));
+        actual.insert("pending_changes".to_string(), Dynamic::Unknown);
+        actual.insert(
+            "disk".to_string(),
+            Dynamic::List(vec![
+                block("scsi0", Dynamic::String("20G".to_string())),
+                block("scsi1", Dynamic::String("32G".to_string())),
+            ]),
+        );
+
+        let merged =
+            QemuVmResource::merge_unknown_values(&Dynamic::Map(planned), &Dynamic::Map(actual));
+
+        let Dynamic::Map(merged) = merged else {
+            panic!("expected a map");
+        };
+        assert_eq!(
+            merged.get("name"),
+            Some(&Dynamic::String("planned".to_string()))
+        );
+        assert_eq!(merged.get("pending_changes"), Some(&Dynamic::Null));
+        assert_eq!(
+            merged.get("disk"),
+            Some(&Dynamic::List(vec![
+                block("scsi1", Dynamic::String("32G".to_string())),
+                block("scsi0", Dynamic::String("10G".to_string())),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_plan_generated_macs() {
+        let network = |macaddr: Option<Dynamic>| {
+            let mut map = HashMap::new();
+            map.insert("bridge".to_string(), Dynamic::String("vmbr0".to_string()));
+            if let Some(macaddr) = macaddr {
+                map.insert("macaddr".to_string(), macaddr);
+            }
+            Dynamic::Map(map)
+        };
+        let fixed = Dynamic::String("BC:24:11:AA:BB:CC".to_string());
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(
+                &AttributePath::new("network"),
+                vec![network(None), network(Some(fixed.clone()))],
+            )
+            .unwrap();
+        let mut planned = config.clone();
+
+        QemuVmResource::plan_generated_macs(&config, &DynamicValue::null(), &mut planned);
+
+        assert_eq!(
+            planned.get_list(&AttributePath::new("network")).unwrap(),
+            vec![network(Some(Dynamic::Unknown)), network(Some(fixed))]
+        );
+    }
+
+    #[test]
+    fn test_plan_keeps_generated_macs_by_interface_id() {
+        let network = |id: f64, macaddr: Option<&str>| {
+            let mut map = HashMap::new();
+            map.insert("id".to_string(), Dynamic::Number(id));
+            map.insert("bridge".to_string(), Dynamic::String("vmbr0".to_string()));
+            if let Some(macaddr) = macaddr {
+                map.insert("macaddr".to_string(), Dynamic::String(macaddr.to_string()));
+            }
+            Dynamic::Map(map)
+        };
+        let path = AttributePath::new("network");
+        let mut prior = create_test_dynamic_value();
+        prior
+            .set_list(&path, vec![network(1.0, Some("BC:24:11:00:00:01"))])
+            .unwrap();
+        // A new interface 0 goes in front of the existing interface 1
+        let mut config = create_test_dynamic_value();
+        config
+            .set_list(&path, vec![network(0.0, None), network(1.0, None)])
+            .unwrap();
+        let mut planned = config.clone();
+
+        QemuVmResource::plan_generated_macs(&config, &prior, &mut planned);
+
+        let planned_networks = planned.get_list(&path).unwrap();
+        let Dynamic::Map(new) = &planned_networks[0] else {
+            panic!("network block is not an object");
+        };
+        assert_eq!(new.get("macaddr"), Some(&Dynamic::Unknown));
+        assert_eq!(planned_networks[1], network(1.0, Some("BC:24:11:00:00:01")));
+
+        QemuVmResource::fill_from_plan(&mut config, &planned);
+        let net1 =
+            QemuVmResource::network_blocks_to_string(&[config.get_list(&path).unwrap().remove(1)])
+                .unwrap();
+        assert!(net1.contains("macaddr=BC:24:11:00:00:01"));
+    }
+
+    #[test]
+    fn test_parse_import_id() {
+        assert_eq!(
+            QemuVmResource::parse_import_id("pve/100").unwrap(),
+            ImportTarget::NodeVmid("pve".to_string(), 100)
+        );
+        assert_eq!(
+            QemuVmResource::parse_import_id("100").unwrap(),
+            ImportTarget::Vmid(100)
+        );
+        assert_eq!(
+            QemuVmResource::parse_import_id("name=web/01").unwrap(),
+            ImportTarget::Name("web/01".to_string())
+        );
+
+        for (id, summary) in [
+            ("pve/abc", "Invalid VMID"),
+            ("web", "Invalid import ID"),
+            ("name=", "Invalid import ID"),
+            ("/100", "Invalid import ID"),
+            ("pve/100/1", "Invalid import ID"),
+        ] {
+            let diag = QemuVmResource::parse_import_id(id).unwrap_err();
+            assert_eq!(diag.summary, summary, "{}", id);
+        }
+    }
 }
@@ -0,0 +1,484 @@
+//! One-off vzdump backup resource implementation
+
+use crate::api::nodes::{StorageContent, VzdumpRequest, VZDUMP_COMPRESSION, VZDUMP_MODES};
+use crate::resources::diagnostics::{api_error_diagnostics, task_cancelled_warning};
+use async_trait::async_trait;
+use std::time::Duration;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::timeouts::{Operation, Timeouts};
+use tfplug::types::{AttributePath, Diagnostic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+/// Backing up large disks takes a while.
+const BACKUP_TASK_TIMEOUT: Duration = Duration::from_secs(3600);
+
+const BACKUP_ATTRIBUTES: &[&str] = &["vmid", "storage", "mode", "compress", "protected"];
+
+/// Backup settings taken from the configuration
+#[derive(Debug)]
+struct BackupSettings {
+    node: String,
+    vmid: u32,
+    storage: String,
+    mode: Option<String>,
+    compress: Option<String>,
+    notes_template: Option<String>,
+    protected: Option<bool>,
+}
+
+impl BackupSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let node = config
+            .get_string(&AttributePath::new("node"))
+            .map_err(|_| Diagnostic::error("Missing node", "The 'node' attribute is required"))?;
+        let vmid = config
+            .get_u32(&AttributePath::new("vmid"))
+            .map_err(|_| Diagnostic::error("Missing vmid", "The 'vmid' attribute is required"))?;
+        let storage = config
+            .get_string(&AttributePath::new("storage"))
+            .map_err(|_| {
+                Diagnostic::error("Missing storage", "The 'storage' attribute is required")
+            })?;
+        let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+
+        Ok(Self {
+            node,
+            vmid,
+            storage,
+            mode: string("mode"),
+            compress: string("compress"),
+            notes_template: string("notes_template"),
+            protected: config.get_bool(&AttributePath::new("protected")).ok(),
+        })
+    }
+
+    fn vzdump_request(&self) -> VzdumpRequest {
+        VzdumpRequest {
+            vmid: self.vmid,
+            storage: self.storage.clone(),
+            mode: self.mode.clone(),
+            compress: self.compress.clone(),
+            notes_template: self.notes_template.clone(),
+            protected: self.protected,
+        }
+    }
+}
+
+/// Copies what the storage reports about the backup into state
+fn set_backup_state(state: &mut DynamicValue, backup: &StorageContent) {
+    let _ = state.set_string(&AttributePath::new("id"), backup.volid.clone());
+    let _ = state.set_string(&AttributePath::new("volid"), backup.volid.clone());
+    let _ = match backup.size {
+        Some(size) => state.set_number(&AttributePath::new("size"), size as f64),
+        None => state.set_null(&AttributePath::new("size")),
+    };
+    let _ = match backup.ctime {
+        Some(ctime) => state.set_number(&AttributePath::new("ctime"), ctime as f64),
+        None => state.set_null(&AttributePath::new("ctime")),
+    };
+    let _ = match &backup.notes {
+        Some(notes) => state.set_string(&AttributePath::new("notes"), notes.clone()),
+        None => state.set_null(&AttributePath::new("notes")),
+    };
+}
+
+/// Backs up a guest once with vzdump and records the backup volume, e.g.
+/// before an upgrade. Changing any argument or trigger takes a new backup.
+/// Destroying the resource leaves the backup on the storage.
+#[derive(Default)]
+pub struct VzdumpBackupResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl VzdumpBackupResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs the backup and finds the volume it wrote. vzdump does not
+    /// return the volume ID, so it is the guest's newest backup on the
+    /// storage created after the task started.
+    async fn backup(
+        ctx: &Context,
+        provider_data: &crate::ProxmoxProviderData,
+        settings: &BackupSettings,
+        timeout: Duration,
+    ) -> Result<StorageContent, Vec<Diagnostic>> {
+        let node_api = provider_data.client.nodes().node(&settings.node);
+        let upid = node_api
+            .vzdump(&settings.vzdump_request())
+            .await
+            .map_err(|e| api_error_diagnostics("Failed to back up guest", &e, BACKUP_ATTRIBUTES))?;
+        node_api
+            .wait_for_task(&upid, timeout, ctx.cancelled())
+            .await
+            .map_err(|e| {
+                let error = Diagnostic::error(
+                    "Failed to back up guest",
+                    format!("Backup of guest {} failed: {}", settings.vmid, e),
+                );
+                std::iter::once(error)
+                    .chain(task_cancelled_warning(&e))
+                    .collect::<Vec<_>>()
+            })?;
+
+        let started = upid.start_time().unwrap_or_default();
+        let backups = node_api
+            .storage(&settings.storage)
+            .backups(Some(settings.vmid))
+            .await
+            .map_err(|e| {
+                vec![Diagnostic::error(
+                    "Failed to read backup",
+                    format!(
+                        "Unable to list backups on storage '{}': {}",
+                        settings.storage, e
+                    ),
+                )]
+            })?;
+        backups
+            .into_iter()
+            .filter(|backup| backup.ctime.is_some_and(|ctime| ctime >= started))
+            .max_by_key(|backup| backup.ctime)
+            .ok_or_else(|| {
+                vec![Diagnostic::error(
+                    "Failed to read backup",
+                    format!(
+                        "The backup of guest {} finished but storage '{}' lists no backup of it created since task {} started",
+                        settings.vmid, settings.storage, upid.0
+                    ),
+                )]
+            })
+    }
+}
+
+#[async_trait]
+impl Resource for VzdumpBackupResource {
+    fn type_name(&self) -> &str {
+        "proxmox_vzdump_backup"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Backs up a VM or container once with vzdump and waits for the backup to finish. Changing any argument or trigger takes a new backup. Destroying this resource leaves the backup on the storage")
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .description("The volume ID of the backup")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("node", AttributeType::String)
+                    .description("The node the guest is on")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("vmid", AttributeType::Number)
+                    .description("The ID of the guest to back up")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("storage", AttributeType::String)
+                    .description("The backup storage to write to")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("mode", AttributeType::String)
+                    .description("snapshot backs up the running guest, suspend pauses it and stop shuts it down for the backup. Defaults to snapshot")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("compress", AttributeType::String)
+                    .description("Compression for file based storages: 0, 1, gzip, lz4 or zstd. 0 turns it off. Ignored by Proxmox Backup Server")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("notes_template", AttributeType::String)
+                    .description("Notes for the backup. {{cluster}}, {{guestname}}, {{node}} and {{vmid}} are replaced")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("protected", AttributeType::Bool)
+                    .description("Protect the backup from pruning and removal")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("triggers", AttributeType::Map(Box::new(AttributeType::String)))
+                    .description("Arbitrary values that take a new backup when they change")
+                    .optional()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("volid", AttributeType::String)
+                    .description("The volume ID of the backup, e.g. to restore from")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("size", AttributeType::Number)
+                    .description("Size of the backup in bytes")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("ctime", AttributeType::Number)
+                    .description("When the backup was created, as a unix timestamp")
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("notes", AttributeType::String)
+                    .description("The notes of the backup with the template filled in")
+                    .computed()
+                    .build(),
+            )
+            .block(Timeouts::block(&[Operation::Create]))
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["vmid"]);
+
+        for (name, allowed) in [("mode", VZDUMP_MODES), ("compress", VZDUMP_COMPRESSION)] {
+            if let Ok(value) = config.get_string(&AttributePath::new(name)) {
+                if !allowed.contains(&value.as_str()) {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new(name),
+                        format!("Invalid {}", name),
+                        format!("{} must be one of: {}", name, allowed.join(", ")),
+                    ));
+                }
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match BackupSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        let timeout = Timeouts::new(&request.config).create(&ctx, BACKUP_TASK_TIMEOUT);
+        match Self::backup(&ctx, provider_data, &settings, timeout).await {
+            Ok(backup) => {
+                let mut new_state = request.planned_state;
+                set_backup_state(&mut new_state, &backup);
+                CreateResourceResponse {
+                    new_state,
+                    private: vec![],
+                    diagnostics: vec![],
+                }
+            }
+            Err(diagnostics) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics,
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = BackupSettings::from_config(&request.current_state);
+        let volid = request
+            .current_state
+            .get_string(&AttributePath::new("volid"));
+        let (settings, volid) = match (settings, volid) {
+            (Ok(settings), Ok(volid)) => (settings, volid),
+            _ => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data
+            .client
+            .nodes()
+            .node(&settings.node)
+            .storage(&settings.storage)
+            .backups(Some(settings.vmid))
+            .await
+        {
+            // A backup that was pruned or removed is gone
+            Ok(backups) => {
+                let new_state = backups
+                    .iter()
+                    .find(|backup| backup.volid == volid)
+                    .map(|backup| {
+                        let mut new_state = request.current_state.clone();
+                        set_backup_state(&mut new_state, backup);
+                        new_state
+                    });
+                ReadResourceResponse {
+                    new_state,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read backup",
+                    format!(
+                        "Unable to list backups on storage '{}': {}",
+                        settings.storage, e
+                    ),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        // Every argument takes a new backup, only timeouts can change
+        UpdateResourceResponse {
+            new_state: request.planned_state,
+            private: vec![],
+            diagnostics: vec![],
+            new_identity: None,
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        _request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        // The backup stays on the storage until it is pruned or removed
+        DeleteResourceResponse {
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for VzdumpBackupResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_vzdump_backup_test.rs"]
+mod resource_vzdump_backup_test;
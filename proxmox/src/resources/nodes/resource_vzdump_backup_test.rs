@@ -0,0 +1,140 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::{ClientCapabilities, Dynamic};
+
+    const UPID: &str = "UPID:pve:0000A1B2:0012C3D4:66321F20:vzdump:100:root@pam:";
+
+    fn backup_config(mode: &str) -> DynamicValue {
+        let mut obj = HashMap::new();
+        obj.insert("id".to_string(), Dynamic::Unknown);
+        obj.insert("node".to_string(), Dynamic::String("pve".to_string()));
+        obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+        obj.insert("storage".to_string(), Dynamic::String("local".to_string()));
+        obj.insert("mode".to_string(), Dynamic::String(mode.to_string()));
+        obj.insert("compress".to_string(), Dynamic::String("zstd".to_string()));
+        obj.insert("notes_template".to_string(), Dynamic::Null);
+        obj.insert("protected".to_string(), Dynamic::Null);
+        obj.insert("triggers".to_string(), Dynamic::Null);
+        for computed in ["volid", "size", "ctime", "notes"] {
+            obj.insert(computed.to_string(), Dynamic::Unknown);
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> VzdumpBackupResource {
+        VzdumpBackupResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_unknown_mode() {
+        let response = VzdumpBackupResource::new()
+            .validate(
+                Context::new(),
+                ValidateResourceConfigRequest {
+                    type_name: "proxmox_vzdump_backup".to_string(),
+                    config: backup_config("live"),
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid mode");
+    }
+
+    #[tokio::test]
+    async fn test_create_records_new_backup() {
+        let mut server = Server::new_async().await;
+        let vzdump = server
+            .mock("POST", "/api2/json/nodes/pve/vzdump")
+            .match_body(Matcher::Json(serde_json::json!({
+                "vmid": 100,
+                "storage": "local",
+                "mode": "snapshot",
+                "compress": "zstd"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"data": "{}"}}"#, UPID))
+            .expect(1)
+            .create_async()
+            .await;
+        let _task = server
+            .mock(
+                "GET",
+                Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"data": {{"upid": "{}", "status": "stopped", "exitstatus": "OK"}}}}"#,
+                UPID
+            ))
+            .create_async()
+            .await;
+        // The task started at 0x66321F20 = 1714560800, the first backup is
+        // from before that
+        let _backups = server
+            .mock("GET", "/api2/json/nodes/pve/storage/local/content")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("content".into(), "backup".into()),
+                Matcher::UrlEncoded("vmid".into(), "100".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": [
+                    {"volid": "local:backup/vzdump-qemu-100-2024_04_30-10_00_00.vma.zst", "content": "backup",
+                     "format": "vma.zst", "size": 1000, "ctime": 1714471200, "vmid": 100},
+                    {"volid": "local:backup/vzdump-qemu-100-2024_05_01-11_00_05.vma.zst", "content": "backup",
+                     "format": "vma.zst", "size": 2000, "ctime": 1714560900, "vmid": 100, "notes": "web01"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let config = backup_config("snapshot");
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_vzdump_backup".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        assert!(
+            response.diagnostics.is_empty(),
+            "{:?}",
+            response.diagnostics
+        );
+        vzdump.assert_async().await;
+        let state = response.new_state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("volid")).unwrap(),
+            "local:backup/vzdump-qemu-100-2024_05_01-11_00_05.vma.zst"
+        );
+        assert_eq!(
+            state.get_number(&AttributePath::new("size")).unwrap(),
+            2000.0
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("notes")).unwrap(),
+            "web01"
+        );
+    }
+}
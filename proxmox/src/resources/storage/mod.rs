@@ -0,0 +1,2 @@
+pub mod resource_storage_pbs;
+pub use resource_storage_pbs::PbsStorageResource;
@@ -0,0 +1,620 @@
+//! Proxmox Backup Server storage resource implementation
+
+use crate::api::storage::{
+    CreateStorageRequest, StorageConfig, StorageSettings, UpdateStorageRequest,
+};
+use crate::resources::diagnostics::api_error_diagnostics;
+use async_trait::async_trait;
+use tfplug::context::Context;
+use tfplug::plan_modifier::RequiresReplace;
+use tfplug::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure, UpdateResourceRequest,
+    UpdateResourceResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+};
+use tfplug::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+use tfplug::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
+use tfplug::validator::validate_integers;
+
+const PBS_ATTRIBUTES: &[&str] = &[
+    "storage",
+    "server",
+    "port",
+    "datastore",
+    "namespace",
+    "username",
+    "password",
+    "fingerprint",
+    "encryption_key",
+    "nodes",
+    "disable",
+];
+
+/// PBS storages only ever hold backups
+const PBS_CONTENT: &str = "backup";
+
+/// Storage settings taken from the configuration
+#[derive(Debug)]
+struct PbsSettings {
+    storage: String,
+    server: Option<String>,
+    port: Option<u32>,
+    datastore: Option<String>,
+    namespace: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    fingerprint: Option<String>,
+    encryption_key: Option<String>,
+    nodes: Option<Vec<String>>,
+    disable: Option<bool>,
+}
+
+impl PbsSettings {
+    fn from_config(config: &DynamicValue) -> Result<Self, Diagnostic> {
+        let storage = config
+            .get_string(&AttributePath::new("storage"))
+            .map_err(|_| {
+                Diagnostic::error("Missing storage", "The 'storage' attribute is required")
+            })?;
+        let string = |name: &str| config.get_string(&AttributePath::new(name)).ok();
+        let nodes = config
+            .get_list(&AttributePath::new("nodes"))
+            .ok()
+            .map(|nodes| {
+                nodes
+                    .into_iter()
+                    .filter_map(|node| match node {
+                        Dynamic::String(s) => Some(s),
+                        _ => None,
+                    })
+                    .collect()
+            });
+
+        Ok(Self {
+            storage,
+            server: string("server"),
+            port: config.get_u32(&AttributePath::new("port")).ok(),
+            datastore: string("datastore"),
+            namespace: string("namespace"),
+            username: string("username"),
+            password: string("password"),
+            fingerprint: string("fingerprint"),
+            encryption_key: string("encryption_key"),
+            nodes,
+            disable: config.get_bool(&AttributePath::new("disable")).ok(),
+        })
+    }
+
+    fn settings(&self) -> StorageSettings {
+        StorageSettings {
+            content: Some(PBS_CONTENT.to_string()),
+            nodes: self.nodes.as_ref().map(|nodes| nodes.join(",")),
+            disable: self.disable,
+            server: self.server.clone(),
+            port: self.port,
+            datastore: self.datastore.clone(),
+            namespace: self.namespace.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+            fingerprint: self.fingerprint.clone(),
+            encryption_key: self.encryption_key.clone(),
+        }
+    }
+
+    fn create_request(&self) -> CreateStorageRequest {
+        CreateStorageRequest {
+            storage: self.storage.clone(),
+            storage_type: "pbs".to_string(),
+            settings: self.settings(),
+        }
+    }
+
+    /// The encryption key is only sent when it changed, since PVE replaces
+    /// the stored key every time it is given one
+    fn update_request(&self, prior_state: &DynamicValue) -> UpdateStorageRequest {
+        let mut settings = self.settings();
+        let prior_key = prior_state
+            .get_string(&AttributePath::new("encryption_key"))
+            .ok();
+        if settings.encryption_key == prior_key {
+            settings.encryption_key = None;
+        }
+
+        let delete = PbsSettings::from_config(prior_state)
+            .map(|prior| {
+                let configured = self.optional_settings();
+                prior
+                    .optional_settings()
+                    .into_iter()
+                    .filter(|param| !configured.contains(param))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        UpdateStorageRequest {
+            settings,
+            delete: (!delete.is_empty()).then(|| delete.join(",")),
+        }
+    }
+
+    /// API parameters of the optional settings that are set. Removing one
+    /// from the configuration has to clear it through `delete`.
+    fn optional_settings(&self) -> Vec<&'static str> {
+        [
+            ("port", self.port.is_some()),
+            ("namespace", self.namespace.is_some()),
+            ("fingerprint", self.fingerprint.is_some()),
+            ("encryption-key", self.encryption_key.is_some()),
+            ("nodes", self.nodes.is_some()),
+            ("disable", self.disable.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(param, set)| set.then_some(param))
+        .collect()
+    }
+}
+
+/// Copies what the API reports into state. The password and encryption key
+/// are never returned, so they keep their configured values.
+fn set_pbs_state(state: &mut DynamicValue, storage: &StorageConfig) {
+    for (name, value) in [
+        ("server", &storage.server),
+        ("datastore", &storage.datastore),
+        ("namespace", &storage.namespace),
+        ("username", &storage.username),
+        ("fingerprint", &storage.fingerprint),
+    ] {
+        let path = AttributePath::new(name);
+        let _ = match value {
+            Some(value) => state.set_string(&path, value.clone()),
+            None => state.set_null(&path),
+        };
+    }
+    let _ = match storage.port {
+        Some(port) => state.set_number(&AttributePath::new("port"), port as f64),
+        None => state.set_null(&AttributePath::new("port")),
+    };
+    let _ = match storage.nodes {
+        Some(_) => state.set_list(
+            &AttributePath::new("nodes"),
+            storage
+                .node_list()
+                .into_iter()
+                .map(Dynamic::String)
+                .collect(),
+        ),
+        None => state.set_null(&AttributePath::new("nodes")),
+    };
+    let _ = match storage.disable {
+        Some(disable) => state.set_bool(&AttributePath::new("disable"), disable),
+        None => state.set_null(&AttributePath::new("disable")),
+    };
+}
+
+/// Manages a Proxmox Backup Server datastore as a cluster-wide backup storage
+#[derive(Default)]
+pub struct PbsStorageResource {
+    provider_data: Option<crate::ProxmoxProviderData>,
+}
+
+impl PbsStorageResource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn read_back(
+        provider_data: &crate::ProxmoxProviderData,
+        storage: &str,
+        mut state: DynamicValue,
+    ) -> Result<DynamicValue, Diagnostic> {
+        let config = provider_data
+            .client
+            .storage()
+            .get(storage)
+            .await
+            .map_err(|e| {
+                Diagnostic::error(
+                    "Failed to read storage",
+                    format!("Unable to read storage '{}': {}", storage, e),
+                )
+            })?;
+        set_pbs_state(&mut state, &config);
+        Ok(state)
+    }
+}
+
+#[async_trait]
+impl Resource for PbsStorageResource {
+    fn type_name(&self) -> &str {
+        "proxmox_storage_pbs"
+    }
+
+    async fn metadata(
+        &self,
+        _ctx: Context,
+        _request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        ResourceMetadataResponse {
+            type_name: self.type_name().to_string(),
+        }
+    }
+
+    async fn schema(
+        &self,
+        _ctx: Context,
+        _request: ResourceSchemaRequest,
+    ) -> ResourceSchemaResponse {
+        let schema = SchemaBuilder::new()
+            .version(0)
+            .description("Manages a Proxmox Backup Server storage for backups")
+            .attribute(
+                AttributeBuilder::new("storage", AttributeType::String)
+                    .description("The storage identifier")
+                    .required()
+                    .plan_modifier(RequiresReplace::create())
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("server", AttributeType::String)
+                    .description("Host name or IP address of the backup server")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("port", AttributeType::Number)
+                    .description("Port of the backup server API. Defaults to 8007")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("datastore", AttributeType::String)
+                    .description("Datastore on the backup server to store backups in")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("namespace", AttributeType::String)
+                    .description("Namespace within the datastore. Defaults to the root namespace")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("username", AttributeType::String)
+                    .description("User or API token to log in as, e.g. backup@pbs or backup@pbs!pve")
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("password", AttributeType::String)
+                    .description("Password of the user or secret of the API token")
+                    .required()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("fingerprint", AttributeType::String)
+                    .description("SHA-256 fingerprint of the backup server certificate. Needed unless the certificate is trusted by the nodes")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("encryption_key", AttributeType::String)
+                    .description("Client-side encryption key as JSON, or 'autogen' to have one generated. Keep a copy, backups cannot be restored without it")
+                    .optional()
+                    .sensitive()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("nodes", AttributeType::List(Box::new(AttributeType::String)))
+                    .description("Nodes the storage is available on. Defaults to all nodes")
+                    .optional()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("disable", AttributeType::Bool)
+                    .description("Disable the storage")
+                    .optional()
+                    .build(),
+            )
+            .build();
+
+        ResourceSchemaResponse {
+            schema,
+            diagnostics: vec![],
+        }
+    }
+
+    async fn validate(
+        &self,
+        _ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        let config = &request.config;
+        let mut diagnostics = validate_integers(config, &["port"]);
+
+        if let Ok(port) = config.get_number(&AttributePath::new("port")) {
+            if !(1.0..=65535.0).contains(&port) {
+                diagnostics.push(
+                    Diagnostic::error("Invalid port", "port must be between 1 and 65535")
+                        .with_attribute(AttributePath::new("port")),
+                );
+            }
+        }
+
+        ValidateResourceConfigResponse { diagnostics }
+    }
+
+    async fn create(
+        &self,
+        _ctx: Context,
+        request: CreateResourceRequest,
+    ) -> CreateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let settings = match PbsSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return CreateResourceResponse {
+                    new_state: request.planned_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                };
+            }
+        };
+
+        if let Err(e) = provider_data
+            .client
+            .storage()
+            .create(&settings.create_request())
+            .await
+        {
+            return CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics("Failed to create storage", &e, PBS_ATTRIBUTES),
+            };
+        }
+        provider_data.invalidate_storages().await;
+
+        match Self::read_back(
+            provider_data,
+            &settings.storage,
+            request.planned_state.clone(),
+        )
+        .await
+        {
+            Ok(new_state) => CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            },
+            Err(diag) => CreateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![diag],
+            },
+        }
+    }
+
+    async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return ReadResourceResponse {
+                    new_state: Some(request.current_state),
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        let storage = match request
+            .current_state
+            .get_string(&AttributePath::new("storage"))
+        {
+            Ok(storage) => storage,
+            Err(_) => {
+                return ReadResourceResponse {
+                    new_state: None,
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                };
+            }
+        };
+
+        match provider_data.client.storage().get(&storage).await {
+            Ok(config) => {
+                let mut new_state = request.current_state.clone();
+                set_pbs_state(&mut new_state, &config);
+                ReadResourceResponse {
+                    new_state: Some(new_state),
+                    diagnostics: vec![],
+                    private: request.private,
+                    deferred: None,
+                    new_identity: None,
+                }
+            }
+            Err(e) if e.is_not_found() => ReadResourceResponse {
+                new_state: None,
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+            Err(e) => ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![Diagnostic::error(
+                    "Failed to read storage",
+                    format!("Unable to read storage '{}': {}", storage, e),
+                )],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn update(
+        &self,
+        _ctx: Context,
+        request: UpdateResourceRequest,
+    ) -> UpdateResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                    new_identity: None,
+                };
+            }
+        };
+
+        let settings = match PbsSettings::from_config(&request.config) {
+            Ok(settings) => settings,
+            Err(diag) => {
+                return UpdateResourceResponse {
+                    new_state: request.prior_state,
+                    private: vec![],
+                    diagnostics: vec![diag],
+                    new_identity: None,
+                };
+            }
+        };
+
+        if let Err(e) = provider_data
+            .client
+            .storage()
+            .update(
+                &settings.storage,
+                &settings.update_request(&request.prior_state),
+            )
+            .await
+        {
+            return UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: api_error_diagnostics("Failed to update storage", &e, PBS_ATTRIBUTES),
+                new_identity: None,
+            };
+        }
+        provider_data.invalidate_storages().await;
+
+        match Self::read_back(provider_data, &settings.storage, request.planned_state).await {
+            Ok(new_state) => UpdateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            },
+            Err(diag) => UpdateResourceResponse {
+                new_state: request.prior_state,
+                private: vec![],
+                diagnostics: vec![diag],
+                new_identity: None,
+            },
+        }
+    }
+
+    async fn delete(
+        &self,
+        _ctx: Context,
+        request: DeleteResourceRequest,
+    ) -> DeleteResourceResponse {
+        let provider_data = match &self.provider_data {
+            Some(data) => data,
+            None => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![Diagnostic::error(
+                        "Provider not configured",
+                        "Provider data was not properly configured",
+                    )],
+                };
+            }
+        };
+
+        let storage = match request
+            .prior_state
+            .get_string(&AttributePath::new("storage"))
+        {
+            Ok(storage) => storage,
+            Err(_) => {
+                return DeleteResourceResponse {
+                    diagnostics: vec![],
+                }
+            }
+        };
+
+        let diagnostics = match provider_data.client.storage().delete(&storage).await {
+            Ok(()) => {
+                provider_data.invalidate_storages().await;
+                vec![]
+            }
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => vec![Diagnostic::error(
+                "Failed to delete storage",
+                format!("API error: {}", e),
+            )],
+        };
+
+        DeleteResourceResponse { diagnostics }
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for PbsStorageResource {
+    async fn configure(
+        &mut self,
+        _ctx: Context,
+        request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let mut diagnostics = vec![];
+
+        if let Some(data) = request.provider_data {
+            if let Some(provider_data) = data.downcast_ref::<crate::ProxmoxProviderData>() {
+                self.provider_data = Some(provider_data.clone());
+            } else {
+                diagnostics.push(Diagnostic::error(
+                    "Invalid provider data",
+                    "Failed to extract ProxmoxProviderData from provider data",
+                ));
+            }
+        } else {
+            diagnostics.push(Diagnostic::error(
+                "No provider data",
+                "No provider data was provided to the resource",
+            ));
+        }
+
+        ConfigureResourceResponse { diagnostics }
+    }
+}
+
+#[cfg(test)]
+#[path = "./resource_storage_pbs_test.rs"]
+mod resource_storage_pbs_test;
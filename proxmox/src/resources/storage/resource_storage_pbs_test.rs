@@ -0,0 +1,168 @@
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::super::*;
+    use crate::api::test_helpers::provider_data;
+    use mockito::{Matcher, Server};
+    use std::collections::HashMap;
+    use tfplug::types::ClientCapabilities;
+
+    fn pbs_config(values: &[(&str, Dynamic)]) -> DynamicValue {
+        let mut obj = HashMap::new();
+        for (name, value) in [
+            ("storage", "backup"),
+            ("server", "pbs.example.com"),
+            ("datastore", "store1"),
+            ("username", "backup@pbs!pve"),
+            ("password", "secret"),
+        ] {
+            obj.insert(name.to_string(), Dynamic::String(value.to_string()));
+        }
+        for name in [
+            "port",
+            "namespace",
+            "fingerprint",
+            "encryption_key",
+            "nodes",
+            "disable",
+        ] {
+            obj.insert(name.to_string(), Dynamic::Null);
+        }
+        for (name, value) in values {
+            obj.insert(name.to_string(), value.clone());
+        }
+        DynamicValue::new(Dynamic::Map(obj))
+    }
+
+    fn configured_resource(url: &str) -> PbsStorageResource {
+        PbsStorageResource {
+            provider_data: Some(provider_data(url)),
+        }
+    }
+
+    const STORAGE_BODY: &str = r#"{"data": {"storage": "backup", "type": "pbs",
+        "content": "backup", "server": "pbs.example.com", "datastore": "store1",
+        "username": "backup@pbs!pve", "fingerprint": "aa:bb", "digest": "abc"}}"#;
+
+    #[test]
+    fn test_update_request_clears_removed_settings() {
+        let prior = pbs_config(&[
+            ("namespace", Dynamic::String("prod".to_string())),
+            ("encryption_key", Dynamic::String("autogen".to_string())),
+            ("port", Dynamic::Number(8007.0)),
+        ]);
+        let config = pbs_config(&[("encryption_key", Dynamic::String("autogen".to_string()))]);
+
+        let request = PbsSettings::from_config(&config)
+            .unwrap()
+            .update_request(&prior);
+
+        assert_eq!(request.delete.as_deref(), Some("port,namespace"));
+        assert!(request.settings.encryption_key.is_none());
+        assert_eq!(request.settings.password.as_deref(), Some("secret"));
+        assert_eq!(request.settings.content.as_deref(), Some("backup"));
+    }
+
+    #[tokio::test]
+    async fn test_create_sends_secrets_and_reads_back() {
+        let mut server = Server::new_async().await;
+        let create = server
+            .mock("POST", "/api2/json/storage")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "storage": "backup",
+                "type": "pbs",
+                "content": "backup",
+                "password": "secret",
+                "encryption-key": "autogen",
+                "nodes": "pve1,pve2"
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"storage": "backup", "type": "pbs"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let _get = server
+            .mock("GET", "/api2/json/storage/backup")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(STORAGE_BODY)
+            .create_async()
+            .await;
+
+        let config = pbs_config(&[
+            ("encryption_key", Dynamic::String("autogen".to_string())),
+            (
+                "nodes",
+                Dynamic::List(vec![
+                    Dynamic::String("pve1".to_string()),
+                    Dynamic::String("pve2".to_string()),
+                ]),
+            ),
+        ]);
+        let response = configured_resource(&server.url())
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: "proxmox_storage_pbs".to_string(),
+                    planned_state: config.clone(),
+                    config,
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await;
+
+        create.assert_async().await;
+        assert!(response.diagnostics.is_empty());
+        let state = &response.new_state;
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("fingerprint"))
+                .unwrap(),
+            "aa:bb"
+        );
+        assert_eq!(
+            state.get_string(&AttributePath::new("password")).unwrap(),
+            "secret"
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("encryption_key"))
+                .unwrap(),
+            "autogen"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_missing_storage_removes_state() {
+        let mut server = Server::new_async().await;
+        let _get = server
+            .mock("GET", "/api2/json/storage/backup")
+            .with_status(500)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": null, "message": "storage 'backup' does not exist\n"}"#)
+            .create_async()
+            .await;
+
+        let response = configured_resource(&server.url())
+            .read(
+                Context::new(),
+                ReadResourceRequest {
+                    type_name: "proxmox_storage_pbs".to_string(),
+                    current_state: pbs_config(&[]),
+                    private: vec![],
+                    provider_meta: None,
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                    current_identity: None,
+                },
+            )
+            .await;
+
+        assert!(response.diagnostics.is_empty());
+        assert!(response.new_state.is_none());
+    }
+}
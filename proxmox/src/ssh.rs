@@ -0,0 +1,152 @@
+//! Shell access to Proxmox nodes over SSH
+//!
+//! Some operations have no API equivalent, such as `qm importdisk` or editing
+//! files under `/etc/pve`. Resources that need them run commands through
+//! [`SshClient`], which only exists when the provider has an `ssh` block.
+//! Commands go through the system `ssh` binary, so host keys, agents and jump
+//! hosts from `~/.ssh/config` keep working.
+
+use std::io::Write;
+use std::process::Stdio;
+use tempfile::NamedTempFile;
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Debug, Error)]
+pub enum SshError {
+    #[error("Failed to write SSH private key: {0}")]
+    KeyFile(std::io::Error),
+
+    #[error("Failed to run ssh: {0}")]
+    Spawn(std::io::Error),
+
+    #[error("Command `{command}` failed on {host} (exit code {code:?}): {stderr}")]
+    CommandFailed {
+        host: String,
+        command: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SshConfig {
+    pub host: String,
+    pub user: String,
+    /// Private key contents. When unset, the keys ssh finds on its own are
+    /// used.
+    pub private_key: Option<String>,
+    /// Whether to offer keys from the running SSH agent
+    pub agent: bool,
+}
+
+pub struct SshClient {
+    config: SshConfig,
+    /// Holds `private_key` for the lifetime of the client. Removed on drop.
+    key_file: Option<NamedTempFile>,
+}
+
+impl SshClient {
+    pub fn new(config: SshConfig) -> Result<Self, SshError> {
+        let key_file = match &config.private_key {
+            Some(key) => Some(write_key_file(key).map_err(SshError::KeyFile)?),
+            None => None,
+        };
+
+        Ok(Self { config, key_file })
+    }
+
+    /// Host commands run on unless another one is given
+    pub fn host(&self) -> &str {
+        &self.config.host
+    }
+
+    /// Runs a command on the configured host and returns its standard output
+    pub async fn execute(&self, command: &str) -> Result<String, SshError> {
+        self.execute_on(&self.config.host, command).await
+    }
+
+    /// Runs a command on a specific host, e.g. the node a guest lives on in a
+    /// cluster, with the configured credentials
+    pub async fn execute_on(&self, host: &str, command: &str) -> Result<String, SshError> {
+        tracing::debug!("Running `{}` on {} over SSH", command, host);
+
+        let output = Command::new("ssh")
+            .args(self.args(host, command))
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(SshError::Spawn)?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(SshError::CommandFailed {
+                host: host.to_string(),
+                command: command.to_string(),
+                code: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            })
+        }
+    }
+
+    fn args(&self, host: &str, command: &str) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "StrictHostKeyChecking=accept-new",
+            "-o",
+            "ConnectTimeout=10",
+            "-l",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        args.push(self.config.user.clone());
+
+        if let Some(key_file) = &self.key_file {
+            args.push("-i".to_string());
+            args.push(key_file.path().display().to_string());
+            args.push("-o".to_string());
+            args.push("IdentitiesOnly=yes".to_string());
+        }
+        if !self.config.agent {
+            args.push("-o".to_string());
+            args.push("IdentityAgent=none".to_string());
+        }
+
+        args.push(host.to_string());
+        args.push("--".to_string());
+        args.push(command.to_string());
+        args
+    }
+}
+
+/// Quotes an argument for the remote POSIX shell
+pub fn quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// ssh refuses keys that are readable by others or lack a final newline.
+/// Temporary files are created with owner-only permissions.
+fn write_key_file(key: &str) -> std::io::Result<NamedTempFile> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(key.trim_end().as_bytes())?;
+    file.write_all(b"\n")?;
+    file.flush()?;
+    Ok(file)
+}
+
+#[cfg(test)]
+#[path = "./ssh_test.rs"]
+mod ssh_test;
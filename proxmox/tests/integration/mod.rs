@@ -1,2 +1,3 @@
 pub mod access_realm_test;
+pub mod node_dns_test;
 pub mod qemu;
@@ -0,0 +1,72 @@
+//! Acceptance tests for proxmox_node_dns, run through the plugin protocol
+
+use mockito::{Matcher, Server};
+use proxmox::ProxmoxProvider;
+use serde_json::json;
+use tfplug::testing::{check, TestCase, TestStep};
+use tfplug::types::{AttributePath, Dynamic};
+
+fn provider_config(server: &Server) -> serde_json::Value {
+    json!({
+        "endpoint": server.url(),
+        "api_token": "test@pve!test=secret123",
+        "insecure": true,
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn node_dns_keeps_node_servers_without_drift() {
+    let mut server = Server::new_async().await;
+    let put = server
+        .mock("PUT", "/api2/json/nodes/pve/dns")
+        .match_body(Matcher::Json(json!({"search": "lan"})))
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": null}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let _get = server
+        .mock("GET", "/api2/json/nodes/pve/dns")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": {"search": "lan", "dns1": "10.0.0.1"}}"#)
+        .create_async()
+        .await;
+
+    TestCase::new(ProxmoxProvider::new())
+        .provider_config(provider_config(&server))
+        .step(
+            TestStep::new()
+                .resource(
+                    "proxmox_node_dns.pve",
+                    json!({"node": "pve", "search": "lan"}),
+                )
+                .check(check::attribute_equals(
+                    "proxmox_node_dns.pve",
+                    AttributePath::new("servers").index(0),
+                    Dynamic::String("10.0.0.1".to_string()),
+                )),
+        )
+        .run()
+        .await;
+
+    // Planning again after the apply must not write the settings a second time
+    put.assert_async().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn node_dns_rejects_invalid_servers() {
+    let server = Server::new_async().await;
+
+    TestCase::new(ProxmoxProvider::new())
+        .provider_config(provider_config(&server))
+        .step(
+            TestStep::new()
+                .resource(
+                    "proxmox_node_dns.pve",
+                    json!({"node": "pve", "search": "lan", "servers": ["dns.example.com"]}),
+                )
+                .expect_error("Invalid DNS server"),
+        )
+        .run()
+        .await;
+}
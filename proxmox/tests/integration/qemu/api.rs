@@ -1,6 +1,6 @@
 //! Integration tests for Proxmox QEMU/VM operations
 
-use proxmox::api::nodes::{CreateQemuRequest, UpdateQemuRequest};
+use proxmox::api::nodes::{CreateQemuRequest, QemuSettings, UpdateQemuRequest};
 use proxmox::api::Client;
 use std::sync::Arc;
 
@@ -90,12 +90,15 @@ async fn test_qemu_vm_lifecycle() {
     println!("Creating VM with ID {}", test_vmid);
     let create_request = CreateQemuRequest {
         vmid: test_vmid,
-        name: Some("test-vm-integration".to_string()),
-        memory: Some(1024),
-        cores: Some(1),
-        sockets: Some(1),
-        cpu: Some("host".to_string()),
-        ostype: Some("l26".to_string()),
+        settings: QemuSettings {
+            name: Some("test-vm-integration".to_string()),
+            memory: Some(1024),
+            cores: Some(1),
+            sockets: Some(1),
+            cpu: Some("host".to_string()),
+            ostype: Some("l26".to_string()),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -146,8 +149,11 @@ async fn test_qemu_vm_lifecycle() {
 
     // Step 3: Update VM
     let update_request = UpdateQemuRequest {
-        memory: Some(2048),
-        cores: Some(2),
+        settings: QemuSettings {
+            memory: Some(2048),
+            cores: Some(2),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
@@ -293,9 +299,12 @@ async fn test_qemu_vm_status() {
     println!("Creating test VM with ID {}", test_vmid);
     let create_request = CreateQemuRequest {
         vmid: test_vmid,
-        name: Some("test-vm-status".to_string()),
-        memory: Some(512),
-        cores: Some(1),
+        settings: QemuSettings {
+            name: Some("test-vm-status".to_string()),
+            memory: Some(512),
+            cores: Some(1),
+            ..Default::default()
+        },
         ..Default::default()
     };
 
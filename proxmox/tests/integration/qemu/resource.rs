@@ -8,16 +8,39 @@ use std::sync::Arc;
 use tfplug::context::Context;
 use tfplug::resource::{
     ConfigureResourceRequest, CreateResourceRequest, DeleteResourceRequest,
-    ImportResourceStateRequest, ReadResourceRequest, Resource, ResourceMetadataRequest,
-    ResourceSchemaRequest, ResourceWithConfigure, ResourceWithImportState, UpdateResourceRequest,
+    ImportResourceStateRequest, ModifyPlanRequest, ReadResourceRequest, Resource,
+    ResourceMetadataRequest, ResourceSchemaRequest, ResourceWithConfigure, ResourceWithIdentity,
+    ResourceWithImportState, UpdateResourceRequest,
+};
+use tfplug::types::{
+    AttributePath, ClientCapabilities, Dynamic, DynamicValue, PrivateStateData,
+    ResourceIdentityData,
 };
-use tfplug::types::{AttributePath, ClientCapabilities, Dynamic, DynamicValue};
 
 fn create_test_provider_data(server_url: &str) -> ProxmoxProviderData {
     let client = Client::new(server_url, "test@pam!test=secret", true).unwrap();
-    ProxmoxProviderData {
-        client: Arc::new(client),
-    }
+    ProxmoxProviderData::new(client)
+}
+
+fn memory_block(dedicated: f64) -> Dynamic {
+    let mut block = HashMap::new();
+    block.insert("dedicated".to_string(), Dynamic::Number(dedicated));
+    Dynamic::List(vec![Dynamic::Map(block)])
+}
+
+async fn mock_create_task(server: &mut mockito::ServerGuard) -> mockito::Mock {
+    server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data": {"upid": "UPID:pve:00001234:00000000:5F000000:qmcreate:100:root@pam:", "status": "stopped", "exitstatus": "OK"}}"#,
+        )
+        .create_async()
+        .await
 }
 
 fn create_test_dynamic_value() -> DynamicValue {
@@ -28,7 +51,7 @@ fn create_test_dynamic_value() -> DynamicValue {
     );
     obj.insert("vmid".to_string(), Dynamic::Number(100.0));
     obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-    obj.insert("memory".to_string(), Dynamic::Number(2048.0));
+    obj.insert("memory".to_string(), memory_block(2048.0));
     obj.insert("cores".to_string(), Dynamic::Number(2.0));
     obj.insert("sockets".to_string(), Dynamic::Number(1.0));
     DynamicValue::new(Dynamic::Map(obj))
@@ -52,14 +75,27 @@ async fn test_resource_schema() {
     let response = resource.schema(ctx, request).await;
 
     assert!(response.diagnostics.is_empty());
-    assert_eq!(response.schema.version, 0);
+    assert_eq!(response.schema.version, 5);
 
     let attrs = &response.schema.block.attributes;
-    assert!(attrs.iter().any(|a| a.name == "target_node" && a.required));
+    assert!(attrs
+        .iter()
+        .any(|a| a.name == "target_node" && a.optional && a.computed));
     assert!(attrs.iter().any(|a| a.name == "vmid" && a.required));
     assert!(attrs.iter().any(|a| a.name == "name" && a.required));
     assert!(attrs.iter().any(|a| a.name == "cores" && !a.required));
-    assert!(attrs.iter().any(|a| a.name == "memory" && !a.required));
+    assert!(response
+        .schema
+        .block
+        .block_types
+        .iter()
+        .any(|b| b.type_name == "memory" && b.max_items == 1));
+    assert!(response
+        .schema
+        .block
+        .block_types
+        .iter()
+        .any(|b| b.type_name == "agent" && b.max_items == 1));
     assert!(attrs.iter().any(|a| a.name == "cipassword" && a.sensitive));
 }
 
@@ -85,6 +121,7 @@ async fn test_create_without_provider_data() {
 #[tokio::test]
 async fn test_create_successful() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -144,6 +181,236 @@ async fn test_create_successful() {
     assert!(response.diagnostics.is_empty());
 }
 
+#[tokio::test]
+async fn test_create_waits_for_guest_agent() {
+    let mut server = Server::new_async().await;
+    let _create = server
+        .mock("POST", "/api2/json/nodes/pve/qemu")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": "UPID:pve:00001234:00000000:5F000000:qmcreate:100:root@pam:"}"#)
+        .create_async()
+        .await;
+    let task = mock_create_task(&mut server).await;
+    let _config = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": {"name": "test-vm", "cores": 2, "memory": 2048, "sockets": 1}}"#)
+        .create_async()
+        .await;
+    let ping = server
+        .mock("POST", "/api2/json/nodes/pve/qemu/100/agent/ping")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": {"result": {}}}"#)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let mut config = create_test_dynamic_value();
+    let agent = HashMap::from([("enabled".to_string(), Dynamic::Bool(true))]);
+    config
+        .set_list(&AttributePath::new("agent"), vec![Dynamic::Map(agent)])
+        .unwrap();
+    config.set_bool(&AttributePath::new("start"), true).unwrap();
+    config
+        .set_number(&AttributePath::new("agent_wait_timeout"), 60.0)
+        .unwrap();
+
+    let request = CreateResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        config: config.clone(),
+        planned_state: config,
+        planned_private: vec![],
+        provider_meta: Some(DynamicValue::null()),
+    };
+    let response = resource.create(Context::new(), request).await;
+
+    task.assert_async().await;
+    ping.assert_async().await;
+    assert!(response.diagnostics.is_empty());
+}
+
+#[tokio::test]
+async fn test_create_reads_back_generated_values() {
+    let mut server = Server::new_async().await;
+    let _create = server
+        .mock("POST", "/api2/json/nodes/pve/qemu")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": "UPID:pve:00001234:00000000:5F000000:qmcreate:100:root@pam:"}"#)
+        .create_async()
+        .await;
+    let task = mock_create_task(&mut server).await;
+    let config_mock = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": {
+                    "name": "test-vm",
+                    "cores": 4,
+                    "memory": 2048,
+                    "sockets": 1,
+                    "net0": "virtio=BC:24:11:AA:BB:CC,bridge=vmbr0",
+                    "net1": "e1000=DE:AD:BE:EF:00:01,bridge=vmbr1"
+                }
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let network = |id: f64, bridge: &str, macaddr: Dynamic| {
+        let mut block = HashMap::new();
+        block.insert("id".to_string(), Dynamic::Number(id));
+        block.insert("model".to_string(), Dynamic::String("virtio".to_string()));
+        block.insert("bridge".to_string(), Dynamic::String(bridge.to_string()));
+        block.insert("macaddr".to_string(), macaddr);
+        Dynamic::Map(block)
+    };
+    let mut config = create_test_dynamic_value();
+    config
+        .set_list(
+            &AttributePath::new("network"),
+            vec![
+                network(
+                    1.0,
+                    "vmbr1",
+                    Dynamic::String("DE:AD:BE:EF:00:01".to_string()),
+                ),
+                network(0.0, "vmbr0", Dynamic::Null),
+            ],
+        )
+        .unwrap();
+    let mut planned_state = config.clone();
+    planned_state
+        .set_list(
+            &AttributePath::new("network"),
+            vec![
+                network(
+                    1.0,
+                    "vmbr1",
+                    Dynamic::String("DE:AD:BE:EF:00:01".to_string()),
+                ),
+                network(0.0, "vmbr0", Dynamic::Unknown),
+            ],
+        )
+        .unwrap();
+    planned_state
+        .mark_unknown(&AttributePath::new("pending_changes"))
+        .unwrap();
+
+    let request = CreateResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        config,
+        planned_state,
+        planned_private: vec![],
+        provider_meta: Some(DynamicValue::null()),
+    };
+    let response = resource.create(Context::new(), request).await;
+
+    task.assert_async().await;
+    config_mock.assert_async().await;
+    assert!(response.diagnostics.is_empty());
+
+    let networks = response
+        .new_state
+        .get_list(&AttributePath::new("network"))
+        .unwrap();
+    assert_eq!(
+        networks[1],
+        network(
+            0.0,
+            "vmbr0",
+            Dynamic::String("BC:24:11:AA:BB:CC".to_string())
+        )
+    );
+    assert_eq!(
+        networks[0],
+        network(
+            1.0,
+            "vmbr1",
+            Dynamic::String("DE:AD:BE:EF:00:01".to_string())
+        )
+    );
+    // Known values stay as planned until the next refresh
+    assert_eq!(
+        response
+            .new_state
+            .get_number(&AttributePath::new("cores"))
+            .unwrap(),
+        2.0
+    );
+    assert!(!response
+        .new_state
+        .get_bool(&AttributePath::new("pending_changes"))
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_create_reports_failed_task() {
+    let mut server = Server::new_async().await;
+    let _create = server
+        .mock("POST", "/api2/json/nodes/pve/qemu")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": "UPID:pve:00001234:00000000:5F000000:qmcreate:100:root@pam:"}"#)
+        .create_async()
+        .await;
+    let _task = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data": {"upid": "UPID:pve:00001234:00000000:5F000000:qmcreate:100:root@pam:", "status": "stopped", "exitstatus": "storage 'local-lvm' does not exist"}}"#,
+        )
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let request = CreateResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        config: create_test_dynamic_value(),
+        planned_state: create_test_dynamic_value(),
+        planned_private: vec![],
+        provider_meta: Some(DynamicValue::null()),
+    };
+    let response = resource.create(Context::new(), request).await;
+
+    assert_eq!(response.diagnostics.len(), 1);
+    assert_eq!(response.diagnostics[0].summary, "Failed to create VM");
+    assert!(response.diagnostics[0]
+        .detail
+        .contains("storage 'local-lvm' does not exist"));
+}
+
 #[tokio::test]
 async fn test_read_without_provider_data() {
     let resource = QemuVmResource::new();
@@ -223,7 +490,9 @@ async fn test_read_successful() {
         4.0
     );
     assert_eq!(
-        new_state.get_number(&AttributePath::new("memory")).unwrap(),
+        new_state
+            .get_number(&AttributePath::new("memory").index(0).attribute("dedicated"))
+            .unwrap(),
         4096.0
     );
     assert_eq!(
@@ -276,6 +545,48 @@ async fn test_read_vm_not_found() {
     assert!(response.new_state.is_none());
 }
 
+#[tokio::test]
+async fn test_read_drops_vm_missing_from_cluster_listing() {
+    let mut server = Server::new_async().await;
+    let _resources = server
+        .mock("GET", "/api2/json/cluster/resources")
+        .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": [{"id": "qemu/101", "type": "qemu", "node": "pve", "vmid": 101}]}"#)
+        .create_async()
+        .await;
+    let config = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let provider_data = create_test_provider_data(&server.url());
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let request = ReadResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        current_state: create_test_dynamic_value(),
+        private: vec![],
+        provider_meta: Some(DynamicValue::null()),
+        client_capabilities: ClientCapabilities {
+            deferral_allowed: false,
+            write_only_attributes_allowed: false,
+        },
+        current_identity: None,
+    };
+
+    let response = resource.read(Context::new(), request).await;
+    assert!(response.diagnostics.is_empty());
+    assert!(response.new_state.is_none());
+    config.assert_async().await;
+}
+
 #[tokio::test]
 async fn test_read_normalizes_tags() {
     let mut server = Server::new_async().await;
@@ -309,7 +620,13 @@ async fn test_read_normalizes_tags() {
     let mut current_state = create_test_dynamic_value();
     // Add tags to current state so they will be populated
     current_state
-        .set_string(&AttributePath::new("tags"), "web,production".to_string())
+        .set_list(
+            &AttributePath::new("tags"),
+            vec![
+                Dynamic::String("web".to_string()),
+                Dynamic::String("production".to_string()),
+            ],
+        )
         .unwrap();
 
     let request = ReadResourceRequest {
@@ -329,8 +646,15 @@ async fn test_read_normalizes_tags() {
     assert!(response.new_state.is_some());
 
     let new_state = response.new_state.unwrap();
-    let tags = new_state.get_string(&AttributePath::new("tags")).unwrap();
-    assert_eq!(tags, "web,production,test");
+    let tags = new_state.get_list(&AttributePath::new("tags")).unwrap();
+    assert_eq!(
+        tags,
+        vec![
+            Dynamic::String("production".to_string()),
+            Dynamic::String("test".to_string()),
+            Dynamic::String("web".to_string()),
+        ]
+    );
     mock.assert_async().await;
 }
 
@@ -376,128 +700,768 @@ async fn test_read_preserves_boot_order() {
         current_identity: None,
     };
 
-    let response = resource.read(ctx, request).await;
-    assert!(response.diagnostics.is_empty());
-    assert!(response.new_state.is_some());
+    let response = resource.read(ctx, request).await;
+    assert!(response.diagnostics.is_empty());
+    assert!(response.new_state.is_some());
+
+    let new_state = response.new_state.unwrap();
+    // Boot should not be set if it wasn't in the current state
+    assert!(new_state.get_string(&AttributePath::new("boot")).is_err());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_read_normalizes_network_macs() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": {
+                    "vmid": 100,
+                    "name": "test-vm",
+                    "cores": 2,
+                    "sockets": 1,
+                    "memory": 2048,
+                    "net0": "virtio=BA:88:CB:76:75:D6,bridge=vmbr0,firewall=0,tag=30"
+                }
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let provider_data = create_test_provider_data(&server.url());
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let ctx = Context::new();
+    let request = ReadResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        current_state: create_test_dynamic_value(),
+        private: vec![],
+        client_capabilities: ClientCapabilities {
+            deferral_allowed: false,
+            write_only_attributes_allowed: false,
+        },
+        provider_meta: None,
+        current_identity: None,
+    };
+
+    let response = resource.read(ctx, request).await;
+    assert!(response.diagnostics.is_empty());
+    assert!(response.new_state.is_some());
+
+    let new_state = response.new_state.unwrap();
+    let net0 = new_state.get_string(&AttributePath::new("net0")).unwrap();
+    // When current config doesn't have a MAC, it should be stripped from the response
+    assert_eq!(net0, "virtio,bridge=vmbr0,firewall=0,tag=30");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_read_keeps_network_option_order_of_state() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": {
+                    "vmid": 100,
+                    "name": "test-vm",
+                    "cores": 2,
+                    "sockets": 1,
+                    "memory": 2048,
+                    "net0": "virtio=BA:88:CB:76:75:D6,bridge=vmbr0,firewall=0,tag=30"
+                }
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let provider_data = create_test_provider_data(&server.url());
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let mut current_state = create_test_dynamic_value();
+    current_state
+        .set_string(
+            &AttributePath::new("net0"),
+            "virtio,tag=30,bridge=vmbr0,firewall=0".to_string(),
+        )
+        .unwrap();
+
+    let ctx = Context::new();
+    let request = ReadResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        current_state,
+        private: vec![],
+        client_capabilities: ClientCapabilities {
+            deferral_allowed: false,
+            write_only_attributes_allowed: false,
+        },
+        provider_meta: None,
+        current_identity: None,
+    };
+
+    let response = resource.read(ctx, request).await;
+    assert!(response.diagnostics.is_empty());
+    assert!(response.new_state.is_some());
+
+    let new_state = response.new_state.unwrap();
+    let net0 = new_state.get_string(&AttributePath::new("net0")).unwrap();
+    // When current config doesn't have a MAC, the generated one is not a change
+    assert_eq!(net0, "virtio,tag=30,bridge=vmbr0,firewall=0");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_update_successful() {
+    let mut server = Server::new_async().await;
+    let _m = server
+        .mock("POST", "/api2/json/nodes/pve/qemu/100/config")
+        .match_header("content-type", "application/json")
+        .match_body(Matcher::Json(serde_json::json!({
+            "name": "test-vm",
+            "memory": 4096,
+            "cores": 4,
+            "sockets": 1
+        })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": null
+            }"#,
+        )
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let provider_data = create_test_provider_data(&server.url());
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let ctx = Context::new();
+
+    let mut updated_obj = std::collections::HashMap::new();
+    updated_obj.insert(
+        "target_node".to_string(),
+        Dynamic::String("pve".to_string()),
+    );
+    updated_obj.insert("vmid".to_string(), Dynamic::Number(100.0));
+    updated_obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
+    updated_obj.insert("memory".to_string(), memory_block(4096.0));
+    updated_obj.insert("cores".to_string(), Dynamic::Number(4.0));
+    updated_obj.insert("sockets".to_string(), Dynamic::Number(1.0));
+
+    let request = UpdateResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        config: DynamicValue::new(Dynamic::Map(updated_obj.clone())),
+        planned_state: DynamicValue::new(Dynamic::Map(updated_obj.clone())),
+        prior_state: create_test_dynamic_value(),
+        planned_private: vec![],
+        provider_meta: Some(DynamicValue::null()),
+        planned_identity: None,
+    };
+
+    let response = resource.update(ctx, request).await;
+    if !response.diagnostics.is_empty() {
+        for diag in &response.diagnostics {
+            eprintln!("Diagnostic: {} - {}", diag.summary, diag.detail);
+        }
+    }
+    assert!(response.diagnostics.is_empty());
+}
+
+#[tokio::test]
+async fn test_update_sends_digest_of_last_read() {
+    let mut server = Server::new_async().await;
+    let _read = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data": {"name": "test-vm", "cores": 2, "memory": 2048, "digest": "abc123"}}"#,
+        )
+        .create_async()
+        .await;
+    let update = server
+        .mock("POST", "/api2/json/nodes/pve/qemu/100/config")
+        .match_body(Matcher::PartialJson(
+            serde_json::json!({"digest": "abc123"}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": null}"#)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let read = resource
+        .read(
+            Context::new(),
+            ReadResourceRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                current_state: create_test_dynamic_value(),
+                private: vec![],
+                provider_meta: None,
+                client_capabilities: ClientCapabilities {
+                    deferral_allowed: false,
+                    write_only_attributes_allowed: false,
+                },
+                current_identity: None,
+            },
+        )
+        .await;
+    let private = PrivateStateData::from_private(&read.private).unwrap();
+    assert_eq!(
+        private.get::<String>("config_digest").unwrap(),
+        Some("abc123".to_string())
+    );
+
+    let mut updated = create_test_dynamic_value();
+    updated
+        .set_number(&AttributePath::new("cores"), 4.0)
+        .unwrap();
+    let response = resource
+        .update(
+            Context::new(),
+            UpdateResourceRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                config: updated.clone(),
+                planned_state: updated,
+                prior_state: create_test_dynamic_value(),
+                planned_private: read.private,
+                provider_meta: None,
+                planned_identity: None,
+            },
+        )
+        .await;
+    assert!(response.diagnostics.is_empty());
+    update.assert_async().await;
+
+    // The update changed the digest, so it's dropped until the next read
+    let private = PrivateStateData::from_private(&response.private).unwrap();
+    assert_eq!(private.get::<String>("config_digest").unwrap(), None);
+}
+
+async fn update_with_pending_memory(
+    server: &mut mockito::ServerGuard,
+    reboot_on_update: bool,
+) -> tfplug::resource::UpdateResourceResponse {
+    let _config = server
+        .mock("POST", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": null}"#)
+        .create_async()
+        .await;
+    let _pending = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/pending")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": [{"key": "memory", "value": 2048, "pending": 4096}]}"#)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let mut updated = create_test_dynamic_value();
+    updated
+        .set_list(
+            &AttributePath::new("memory"),
+            match memory_block(4096.0) {
+                Dynamic::List(blocks) => blocks,
+                _ => unreachable!(),
+            },
+        )
+        .unwrap();
+    updated
+        .set_bool(&AttributePath::new("reboot_on_update"), reboot_on_update)
+        .unwrap();
+
+    let request = UpdateResourceRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        config: updated.clone(),
+        planned_state: updated,
+        prior_state: create_test_dynamic_value(),
+        planned_private: vec![],
+        provider_meta: Some(DynamicValue::null()),
+        planned_identity: None,
+    };
+    resource.update(Context::new(), request).await
+}
+
+#[tokio::test]
+async fn test_update_warns_about_pending_changes() {
+    let mut server = Server::new_async().await;
+    let reboot = server
+        .mock("POST", "/api2/json/nodes/pve/qemu/100/status/reboot")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let response = update_with_pending_memory(&mut server, false).await;
+
+    reboot.assert_async().await;
+    assert_eq!(response.diagnostics.len(), 1);
+    assert_eq!(response.diagnostics[0].summary, "Changes pending reboot");
+    assert!(response.diagnostics[0].detail.contains("memory"));
+    assert!(response
+        .new_state
+        .get_bool(&AttributePath::new("pending_changes"))
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_update_reboots_to_apply_pending_changes() {
+    let mut server = Server::new_async().await;
+    let reboot = server
+        .mock("POST", "/api2/json/nodes/pve/qemu/100/status/reboot")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": "UPID:pve:00001234:00000000:5F000000:qmreboot:100:root@pam:"}"#)
+        .create_async()
+        .await;
+    let task = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api2/json/nodes/pve/tasks/UPID.*/status$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data": {"upid": "UPID:pve:00001234:00000000:5F000000:qmreboot:100:root@pam:", "status": "stopped", "exitstatus": "OK"}}"#,
+        )
+        .create_async()
+        .await;
+
+    let response = update_with_pending_memory(&mut server, true).await;
+
+    reboot.assert_async().await;
+    task.assert_async().await;
+    assert!(response.diagnostics.is_empty());
+}
+
+#[tokio::test]
+async fn test_modify_plan_warns_about_restart_changes() {
+    let mut server = Server::new_async().await;
+    let _status = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/status/current")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": {"status": "running"}}"#)
+        .create_async()
+        .await;
+    let _config = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": {"hotplug": "network,disk,cpu"}}"#)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let mut planned = create_test_dynamic_value();
+    planned
+        .set_number(&AttributePath::new("sockets"), 2.0)
+        .unwrap();
+    planned
+        .set_number(&AttributePath::new("vcpus"), 4.0)
+        .unwrap();
+
+    let plan_modifier = resource.as_modify_plan().unwrap();
+    let response = plan_modifier
+        .modify_plan(
+            Context::new(),
+            ModifyPlanRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                config: planned.clone(),
+                prior_state: create_test_dynamic_value(),
+                proposed_new_state: planned,
+                prior_private: vec![],
+                provider_meta: None,
+            },
+        )
+        .await;
+
+    assert_eq!(response.diagnostics.len(), 1);
+    assert_eq!(
+        response.diagnostics[0].summary,
+        "Changes require a VM restart"
+    );
+    assert!(response.diagnostics[0].detail.contains("sockets"));
+    assert!(!response.diagnostics[0].detail.contains("vcpus"));
+    assert!(response
+        .planned_state
+        .get_bool(&AttributePath::new("pending_changes"))
+        .is_err());
+}
+
+/// Plans creating a VM that leaves out target_node and the storage of its
+/// first disk, with the given provider defaults
+async fn plan_with_provider_defaults(
+    default_node: Option<&str>,
+    default_storage: Option<&str>,
+    provider_meta: Option<DynamicValue>,
+) -> tfplug::resource::ModifyPlanResponse {
+    let mut resource = QemuVmResource::new();
+    let provider_data = create_test_provider_data("http://localhost:8006").with_defaults(
+        default_node.map(str::to_string),
+        default_storage.map(str::to_string),
+    );
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let disk = |storage: Dynamic| {
+        Dynamic::Map(HashMap::from([
+            ("slot".to_string(), Dynamic::String("scsi0".to_string())),
+            ("storage".to_string(), storage),
+            ("size".to_string(), Dynamic::String("10G".to_string())),
+        ]))
+    };
+    let mut config = create_test_dynamic_value();
+    config.set_null(&AttributePath::new("target_node")).unwrap();
+    config
+        .set_list(
+            &AttributePath::new("disk"),
+            vec![
+                disk(Dynamic::Null),
+                disk(Dynamic::String("fast".to_string())),
+            ],
+        )
+        .unwrap();
+
+    let plan_modifier = resource.as_modify_plan().unwrap();
+    plan_modifier
+        .modify_plan(
+            Context::new(),
+            ModifyPlanRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                config: config.clone(),
+                prior_state: DynamicValue::null(),
+                proposed_new_state: config,
+                prior_private: vec![],
+                provider_meta,
+            },
+        )
+        .await
+}
+
+#[tokio::test]
+async fn test_modify_plan_fills_in_provider_defaults() {
+    let response = plan_with_provider_defaults(Some("pve2"), Some("local-lvm"), None).await;
+    assert!(response.diagnostics.is_empty());
+
+    let planned = &response.planned_state;
+    assert_eq!(
+        planned
+            .get_string(&AttributePath::new("target_node"))
+            .unwrap(),
+        "pve2"
+    );
+    let storage = |index| {
+        planned
+            .get_string(&AttributePath::new("disk").index(index).attribute("storage"))
+            .unwrap()
+    };
+    assert_eq!(storage(0), "local-lvm");
+    assert_eq!(storage(1), "fast");
+}
+
+#[tokio::test]
+async fn test_modify_plan_requires_node_and_storage_without_defaults() {
+    let response = plan_with_provider_defaults(None, None, None).await;
+
+    let summaries: Vec<&str> = response
+        .diagnostics
+        .iter()
+        .map(|diag| diag.summary.as_str())
+        .collect();
+    assert_eq!(summaries, ["Missing target_node", "Missing storage"]);
+    assert_eq!(
+        response.diagnostics[1].attribute,
+        Some(AttributePath::new("disk").index(0).attribute("storage"))
+    );
+}
+
+#[tokio::test]
+async fn test_modify_plan_prefers_module_default_node() {
+    let meta = DynamicValue::new(Dynamic::Map(HashMap::from([
+        (
+            "module_name".to_string(),
+            Dynamic::String("web".to_string()),
+        ),
+        (
+            "default_node".to_string(),
+            Dynamic::String("pve3".to_string()),
+        ),
+    ])));
+    let response = plan_with_provider_defaults(Some("pve2"), Some("local-lvm"), Some(meta)).await;
+    assert!(response.diagnostics.is_empty());
+
+    assert_eq!(
+        response
+            .planned_state
+            .get_string(&AttributePath::new("target_node"))
+            .unwrap(),
+        "pve3"
+    );
+}
+
+#[tokio::test]
+async fn test_modify_plan_checks_drive_storage_content() {
+    let mut server = Server::new_async().await;
+    let storages = server
+        .mock("GET", "/api2/json/storage")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data": [
+                {"storage": "local-lvm", "type": "lvmthin", "content": "images,rootdir"},
+                {"storage": "local", "type": "dir", "content": "iso,vztmpl,backup"}
+            ]}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let provider_data = create_test_provider_data(&server.url())
+        .with_cluster_cache(std::time::Duration::from_secs(60));
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let disk = |slot: &str, storage: &str| {
+        Dynamic::Map(HashMap::from([
+            ("slot".to_string(), Dynamic::String(slot.to_string())),
+            ("storage".to_string(), Dynamic::String(storage.to_string())),
+            ("size".to_string(), Dynamic::String("10G".to_string())),
+        ]))
+    };
+    let mut config = create_test_dynamic_value();
+    config
+        .set_list(
+            &AttributePath::new("disk"),
+            vec![disk("scsi0", "local-lvm"), disk("scsi1", "local")],
+        )
+        .unwrap();
+    config
+        .set_list(
+            &AttributePath::new("cdrom"),
+            vec![Dynamic::Map(HashMap::from([
+                ("slot".to_string(), Dynamic::String("ide2".to_string())),
+                (
+                    "iso".to_string(),
+                    Dynamic::String("nas:iso/debian.iso".to_string()),
+                ),
+            ]))],
+        )
+        .unwrap();
+
+    let response = resource
+        .as_modify_plan()
+        .unwrap()
+        .modify_plan(
+            Context::new(),
+            ModifyPlanRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                config: config.clone(),
+                prior_state: DynamicValue::null(),
+                proposed_new_state: config,
+                prior_private: vec![],
+                provider_meta: None,
+            },
+        )
+        .await;
 
-    let new_state = response.new_state.unwrap();
-    // Boot should not be set if it wasn't in the current state
-    assert!(new_state.get_string(&AttributePath::new("boot")).is_err());
-    mock.assert_async().await;
+    storages.assert_async().await;
+    assert_eq!(response.diagnostics.len(), 2);
+    let error = &response.diagnostics[0];
+    assert!(error.is_error());
+    assert_eq!(
+        error.attribute,
+        Some(AttributePath::new("disk").index(1).attribute("storage"))
+    );
+    assert!(error.detail.contains("does not allow 'images' content"));
+    let warning = &response.diagnostics[1];
+    assert!(!warning.is_error());
+    assert_eq!(
+        warning.attribute,
+        Some(AttributePath::new("cdrom").index(0).attribute("iso"))
+    );
+    assert!(warning.detail.contains("Storage 'nas'"));
 }
 
 #[tokio::test]
-async fn test_read_normalizes_network_macs() {
+async fn test_modify_plan_checks_network_bridges() {
     let mut server = Server::new_async().await;
-    let mock = server
-        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+    let bridges = server
+        .mock("GET", "/api2/json/nodes/pve/network")
+        .match_query(Matcher::UrlEncoded("type".into(), "any_bridge".into()))
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"{
-                "data": {
-                    "vmid": 100,
-                    "name": "test-vm",
-                    "cores": 2,
-                    "sockets": 1,
-                    "memory": 2048,
-                    "net0": "virtio=BA:88:CB:76:75:D6,bridge=vmbr0,firewall=0,tag=30"
-                }
-            }"#,
-        )
+        .with_body(r#"{"data": [{"iface": "vmbr0", "type": "bridge"}, {"iface": "vmbr1", "type": "bridge"}]}"#)
+        .expect(1)
         .create_async()
         .await;
 
     let mut resource = QemuVmResource::new();
-    let provider_data = create_test_provider_data(&server.url());
+    let provider_data = create_test_provider_data(&server.url()).with_bridge_checks(true);
     let configure_request = ConfigureResourceRequest {
         provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
     };
     let _ = resource.configure(Context::new(), configure_request).await;
 
-    let ctx = Context::new();
-    let request = ReadResourceRequest {
-        type_name: "proxmox_qemu_vm".to_string(),
-        current_state: create_test_dynamic_value(),
-        private: vec![],
-        client_capabilities: ClientCapabilities {
-            deferral_allowed: false,
-            write_only_attributes_allowed: false,
-        },
-        provider_meta: None,
-        current_identity: None,
+    let network = |id: f64, bridge: &str| {
+        Dynamic::Map(HashMap::from([
+            ("id".to_string(), Dynamic::Number(id)),
+            ("bridge".to_string(), Dynamic::String(bridge.to_string())),
+        ]))
     };
+    let mut config = create_test_dynamic_value();
+    config
+        .set_list(
+            &AttributePath::new("network"),
+            vec![network(0.0, "vmbr0"), network(1.0, "vmbr00")],
+        )
+        .unwrap();
 
-    let response = resource.read(ctx, request).await;
-    assert!(response.diagnostics.is_empty());
-    assert!(response.new_state.is_some());
+    let response = resource
+        .as_modify_plan()
+        .unwrap()
+        .modify_plan(
+            Context::new(),
+            ModifyPlanRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                config: config.clone(),
+                prior_state: DynamicValue::null(),
+                proposed_new_state: config,
+                prior_private: vec![],
+                provider_meta: None,
+            },
+        )
+        .await;
 
-    let new_state = response.new_state.unwrap();
-    let net0 = new_state.get_string(&AttributePath::new("net0")).unwrap();
-    // When current config doesn't have a MAC, it should be stripped from the response
-    assert_eq!(net0, "virtio,bridge=vmbr0,firewall=0,tag=30");
-    mock.assert_async().await;
+    bridges.assert_async().await;
+    let errors: Vec<_> = response
+        .diagnostics
+        .iter()
+        .filter(|d| d.summary == "Unknown bridge")
+        .collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].attribute,
+        Some(AttributePath::new("network").index(1).attribute("bridge"))
+    );
+    assert!(errors[0]
+        .detail
+        .contains("Bridges on the node: vmbr0, vmbr1"));
 }
 
 #[tokio::test]
-async fn test_update_successful() {
+async fn test_update_moves_vm_between_pools() {
     let mut server = Server::new_async().await;
-    let _m = server
+    let _config = server
         .mock("POST", "/api2/json/nodes/pve/qemu/100/config")
-        .match_header("content-type", "application/json")
-        .match_body(Matcher::Json(serde_json::json!({
-            "name": "test-vm",
-            "memory": 4096,
-            "cores": 4,
-            "sockets": 1
-        })))
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(
-            r#"{
-                "data": null
-            }"#,
-        )
+        .with_body(r#"{"data": null}"#)
+        .create_async()
+        .await;
+    let leave = server
+        .mock("PUT", "/api2/json/pools/dev")
+        .match_body(Matcher::Json(
+            serde_json::json!({"vms": "100", "delete": true}),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": null}"#)
+        .create_async()
+        .await;
+    let join = server
+        .mock("PUT", "/api2/json/pools/prod")
+        .match_body(Matcher::Json(serde_json::json!({"vms": "100"})))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": null}"#)
         .create_async()
         .await;
 
     let mut resource = QemuVmResource::new();
-    let provider_data = create_test_provider_data(&server.url());
     let configure_request = ConfigureResourceRequest {
-        provider_data: Some(Arc::new(provider_data) as Arc<dyn Any + Send + Sync>),
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
     };
     let _ = resource.configure(Context::new(), configure_request).await;
 
-    let ctx = Context::new();
-
-    let mut updated_obj = std::collections::HashMap::new();
-    updated_obj.insert(
-        "target_node".to_string(),
-        Dynamic::String("pve".to_string()),
-    );
-    updated_obj.insert("vmid".to_string(), Dynamic::Number(100.0));
-    updated_obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-    updated_obj.insert("memory".to_string(), Dynamic::Number(4096.0));
-    updated_obj.insert("cores".to_string(), Dynamic::Number(4.0));
-    updated_obj.insert("sockets".to_string(), Dynamic::Number(1.0));
+    let mut prior = create_test_dynamic_value();
+    prior
+        .set_string(&AttributePath::new("pool"), "dev".to_string())
+        .unwrap();
+    let mut planned = create_test_dynamic_value();
+    planned
+        .set_string(&AttributePath::new("pool"), "prod".to_string())
+        .unwrap();
 
     let request = UpdateResourceRequest {
         type_name: "proxmox_qemu_vm".to_string(),
-        config: DynamicValue::new(Dynamic::Map(updated_obj.clone())),
-        planned_state: DynamicValue::new(Dynamic::Map(updated_obj.clone())),
-        prior_state: create_test_dynamic_value(),
+        config: planned.clone(),
+        planned_state: planned,
+        prior_state: prior,
         planned_private: vec![],
         provider_meta: Some(DynamicValue::null()),
         planned_identity: None,
     };
+    let response = resource.update(Context::new(), request).await;
 
-    let response = resource.update(ctx, request).await;
-    if !response.diagnostics.is_empty() {
-        for diag in &response.diagnostics {
-            eprintln!("Diagnostic: {} - {}", diag.summary, diag.detail);
-        }
-    }
+    leave.assert_async().await;
+    join.assert_async().await;
     assert!(response.diagnostics.is_empty());
+    assert_eq!(
+        response
+            .new_state
+            .get_string(&AttributePath::new("pool"))
+            .unwrap(),
+        "prod"
+    );
 }
 
 #[tokio::test]
@@ -683,6 +1647,268 @@ async fn test_import_state() {
     );
 }
 
+#[tokio::test]
+async fn test_import_state_populates_nested_blocks() {
+    let mut server = Server::new_async().await;
+    let _config = server
+        .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "data": {
+                    "name": "imported-vm",
+                    "cores": 2,
+                    "memory": 2048,
+                    "sockets": 1,
+                    "ostype": "l26",
+                    "tags": "db;prod",
+                    "scsihw": "virtio-scsi-single",
+                    "net0": "virtio=BC:24:11:AA:BB:CC,bridge=vmbr0,firewall=1",
+                    "scsi0": "local-lvm:vm-100-disk-0,iothread=1,size=32G",
+                    "scsi5": "local-lvm:vm-100-disk-2,size=8G",
+                    "ide2": "local:iso/debian.iso,media=cdrom,size=600M",
+                    "ide3": "local-lvm:vm-100-cloudinit,media=cdrom",
+                    "efidisk0": "local-lvm:vm-100-disk-1,efitype=4m,pre-enrolled-keys=1,size=4M",
+                    "serial0": "socket",
+                    "ciuser": "debian",
+                    "ipconfig0": "ip=dhcp",
+                    "sshkeys": "ssh-ed25519%20AAAAC3Nza%20admin%40example%0A"
+                }
+            }"#,
+        )
+        .expect_at_least(2)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let request = ImportResourceStateRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        id: "pve/100".to_string(),
+        client_capabilities: ClientCapabilities {
+            deferral_allowed: false,
+            write_only_attributes_allowed: false,
+        },
+        identity: None,
+    };
+    let response = resource.import_state(Context::new(), request).await;
+    assert!(response.diagnostics.is_empty());
+    let state = &response.imported_resources[0].state;
+
+    assert_eq!(
+        state.get_string(&AttributePath::new("qemu_os")).unwrap(),
+        "l26"
+    );
+    assert!(state.get_string(&AttributePath::new("ostype")).is_err());
+    assert_eq!(
+        state.get_list(&AttributePath::new("sshkeys")).unwrap(),
+        vec![Dynamic::String(
+            "ssh-ed25519 AAAAC3Nza admin@example".to_string()
+        )]
+    );
+    assert_eq!(
+        state.get_string(&AttributePath::new("ipconfig0")).unwrap(),
+        "ip=dhcp"
+    );
+    assert_eq!(
+        state.get_list(&AttributePath::new("tags")).unwrap().len(),
+        2
+    );
+
+    let networks = state.get_list(&AttributePath::new("network")).unwrap();
+    assert_eq!(networks.len(), 1);
+    let disks = state.get_list(&AttributePath::new("disk")).unwrap();
+    let slots: Vec<_> = disks
+        .iter()
+        .map(|disk| match disk {
+            Dynamic::Map(map) => map.get("slot").cloned(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        slots,
+        vec![
+            Some(Dynamic::String("scsi0".to_string())),
+            Some(Dynamic::String("scsi5".to_string()))
+        ]
+    );
+    let cdrom = &state.get_list(&AttributePath::new("cdrom")).unwrap()[0];
+    let Dynamic::Map(cdrom) = cdrom else {
+        panic!("expected a cdrom block");
+    };
+    assert_eq!(
+        cdrom.get("iso"),
+        Some(&Dynamic::String("local:iso/debian.iso".to_string()))
+    );
+    let cloudinit = &state
+        .get_list(&AttributePath::new("cloudinit_drive"))
+        .unwrap()[0];
+    let Dynamic::Map(cloudinit) = cloudinit else {
+        panic!("expected a cloudinit_drive block");
+    };
+    assert_eq!(
+        cloudinit.get("storage"),
+        Some(&Dynamic::String("local-lvm".to_string()))
+    );
+    let efidisk = &state.get_list(&AttributePath::new("efidisk")).unwrap()[0];
+    let Dynamic::Map(efidisk) = efidisk else {
+        panic!("expected an efidisk block");
+    };
+    assert_eq!(efidisk.get("pre_enrolled_keys"), Some(&Dynamic::Bool(true)));
+    assert_eq!(
+        state.get_list(&AttributePath::new("serial")).unwrap().len(),
+        1
+    );
+
+    // Refreshing the imported state reports no differences
+    let read = resource
+        .read(
+            Context::new(),
+            ReadResourceRequest {
+                type_name: "proxmox_qemu_vm".to_string(),
+                current_state: state.clone(),
+                private: vec![],
+                provider_meta: Some(DynamicValue::null()),
+                client_capabilities: ClientCapabilities {
+                    deferral_allowed: false,
+                    write_only_attributes_allowed: false,
+                },
+                current_identity: None,
+            },
+        )
+        .await;
+    let mut refreshed = read.new_state.unwrap();
+    refreshed
+        .set_null(&AttributePath::new("pending_changes"))
+        .unwrap();
+    let mut expected = state.clone();
+    expected
+        .set_null(&AttributePath::new("pending_changes"))
+        .unwrap();
+    assert_eq!(refreshed, expected);
+}
+
+async fn import_with_cluster_vms(
+    id: &str,
+    identity: Option<ResourceIdentityData>,
+) -> tfplug::resource::ImportResourceStateResponse {
+    let mut server = Server::new_async().await;
+    let _resources = server
+        .mock("GET", "/api2/json/cluster/resources")
+        .match_query(Matcher::UrlEncoded("type".into(), "vm".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data": [
+                {"id": "qemu/100", "type": "qemu", "node": "pve1", "vmid": 100, "name": "web"},
+                {"id": "qemu/101", "type": "qemu", "node": "pve2", "vmid": 101, "name": "db"},
+                {"id": "qemu/102", "type": "qemu", "node": "pve1", "vmid": 102, "name": "db"},
+                {"id": "lxc/103", "type": "lxc", "node": "pve1", "vmid": 103, "name": "web"}
+            ]}"#,
+        )
+        .create_async()
+        .await;
+    let _config = server
+        .mock(
+            "GET",
+            Matcher::Regex(r"^/api2/json/nodes/pve\d/qemu/\d+/config$".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data": {"name": "web", "cores": 2, "memory": 2048}}"#)
+        .create_async()
+        .await;
+
+    let mut resource = QemuVmResource::new();
+    let configure_request = ConfigureResourceRequest {
+        provider_data: Some(
+            Arc::new(create_test_provider_data(&server.url())) as Arc<dyn Any + Send + Sync>
+        ),
+    };
+    let _ = resource.configure(Context::new(), configure_request).await;
+
+    let request = ImportResourceStateRequest {
+        type_name: "proxmox_qemu_vm".to_string(),
+        id: id.to_string(),
+        client_capabilities: ClientCapabilities {
+            deferral_allowed: false,
+            write_only_attributes_allowed: false,
+        },
+        identity,
+    };
+    resource.import_state(Context::new(), request).await
+}
+
+#[tokio::test]
+async fn test_import_state_resolves_vmid_and_name() {
+    for id in ["100", "name=web"] {
+        let response = import_with_cluster_vms(id, None).await;
+        assert!(response.diagnostics.is_empty(), "{}", id);
+        let state = &response.imported_resources[0].state;
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("target_node"))
+                .unwrap(),
+            "pve1"
+        );
+        assert_eq!(
+            state.get_number(&AttributePath::new("vmid")).unwrap(),
+            100.0
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_import_state_resolves_vmid_from_identity() {
+    let mut identity_data = DynamicValue::new(Dynamic::Map(HashMap::new()));
+    identity_data
+        .set_number(&AttributePath::new("vmid"), 100.0)
+        .unwrap();
+    let response = import_with_cluster_vms("", Some(ResourceIdentityData { identity_data })).await;
+    assert!(response.diagnostics.is_empty());
+
+    let imported = &response.imported_resources[0];
+    assert_eq!(
+        imported
+            .state
+            .get_string(&AttributePath::new("target_node"))
+            .unwrap(),
+        "pve1"
+    );
+    // The server derives the identity it answers with from the imported state
+    let identity = QemuVmResource::new()
+        .identity_from_state(&imported.state)
+        .unwrap();
+    assert_eq!(
+        identity
+            .identity_data
+            .get_number(&AttributePath::new("vmid"))
+            .unwrap(),
+        100.0
+    );
+}
+
+#[tokio::test]
+async fn test_import_state_reports_ambiguous_and_missing_vms() {
+    let response = import_with_cluster_vms("name=db", None).await;
+    assert!(response.imported_resources.is_empty());
+    assert_eq!(response.diagnostics[0].summary, "Ambiguous VM name");
+    assert!(response.diagnostics[0]
+        .detail
+        .contains("pve1/102, pve2/101"));
+
+    let response = import_with_cluster_vms("103", None).await;
+    assert!(response.imported_resources.is_empty());
+    assert_eq!(response.diagnostics[0].summary, "VM not found");
+}
+
 #[tokio::test]
 async fn test_import_state_invalid_id() {
     let resource = QemuVmResource::new();
@@ -721,6 +1947,7 @@ async fn test_configure_resource() {
 #[tokio::test]
 async fn test_create_populates_network_interfaces() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -796,6 +2023,7 @@ async fn test_create_populates_network_interfaces() {
 #[tokio::test]
 async fn test_create_vm_with_network_blocks() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -934,6 +2162,7 @@ async fn test_create_vm_with_network_blocks() {
 #[tokio::test]
 async fn test_create_vm_with_disk_blocks() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -1113,6 +2342,7 @@ async fn test_create_vm_with_disk_blocks() {
 #[tokio::test]
 async fn test_create_vm_with_efidisk_block() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -1219,21 +2449,17 @@ async fn test_create_vm_with_efidisk_block() {
     }
 }
 
-// TODO: Enable this test when cloud-init fields are added to CreateQemuRequest
-#[ignore]
 #[tokio::test]
 async fn test_create_vm_with_cloudinit_blocks() {
     let mut server = Server::new_async().await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
-        .match_body(Matcher::JsonString(
+        .match_body(Matcher::PartialJsonString(
             r#"{
               "vmid": 100,
-              "name": "test-vm",
-              "memory": 2048,
-              "cores": 2,
-              "sockets": 1
+              "ipconfig0": "ip=192.168.1.100/24,gw=192.168.1.1",
+              "ipconfig5": "ip=10.0.0.100/24"
             }"#
             .to_string(),
         ))
@@ -1244,8 +2470,10 @@ async fn test_create_vm_with_cloudinit_blocks() {
                 "data": "UPID:pve:00001234:00000000:5F000000:qmcreate:100:root@pam:"
             }"#,
         )
+        .expect(1)
         .create_async()
         .await;
+    let _task = mock_create_task(&mut server).await;
 
     let _m2 = server
         .mock("GET", "/api2/json/nodes/pve/qemu/100/config")
@@ -1259,10 +2487,8 @@ async fn test_create_vm_with_cloudinit_blocks() {
                     "memory": 2048,
                     "sockets": 1,
                     "vmid": 100,
-                    "ciuser": "ubuntu",
-                    "sshkeys": "ssh-rsa AAAAB3... user@example.com",
                     "ipconfig0": "ip=192.168.1.100/24,gw=192.168.1.1",
-                    "ipconfig1": "ip=10.0.0.100/24"
+                    "ipconfig5": "ip=10.0.0.100/24"
                 }
             }"#,
         )
@@ -1278,17 +2504,6 @@ async fn test_create_vm_with_cloudinit_blocks() {
 
     let mut config = create_test_dynamic_value();
 
-    let mut cloudinit = HashMap::new();
-    cloudinit.insert("user".to_string(), Dynamic::String("ubuntu".to_string()));
-    cloudinit.insert(
-        "password".to_string(),
-        Dynamic::String("secret123".to_string()),
-    );
-    cloudinit.insert(
-        "ssh_keys".to_string(),
-        Dynamic::String("ssh-rsa AAAAB3... user@example.com".to_string()),
-    );
-
     let mut ipconfig0 = HashMap::new();
     ipconfig0.insert("id".to_string(), Dynamic::Number(0.0));
     ipconfig0.insert(
@@ -1300,20 +2515,24 @@ async fn test_create_vm_with_cloudinit_blocks() {
         Dynamic::String("192.168.1.1".to_string()),
     );
 
-    let mut ipconfig1 = HashMap::new();
-    ipconfig1.insert("id".to_string(), Dynamic::Number(1.0));
-    ipconfig1.insert(
+    let mut ipconfig5 = HashMap::new();
+    ipconfig5.insert("id".to_string(), Dynamic::Number(5.0));
+    ipconfig5.insert(
         "ipv4".to_string(),
         Dynamic::String("10.0.0.100/24".to_string()),
     );
 
+    let mut cloudinit = HashMap::new();
     cloudinit.insert(
         "ipconfig".to_string(),
-        Dynamic::List(vec![Dynamic::Map(ipconfig0), Dynamic::Map(ipconfig1)]),
+        Dynamic::List(vec![Dynamic::Map(ipconfig0), Dynamic::Map(ipconfig5)]),
     );
 
     config
-        .set_map(&AttributePath::new("cloudinit"), cloudinit)
+        .set_list(
+            &AttributePath::new("cloudinit"),
+            vec![Dynamic::Map(cloudinit)],
+        )
         .unwrap();
 
     let ctx = Context::new();
@@ -1332,29 +2551,20 @@ async fn test_create_vm_with_cloudinit_blocks() {
         }
     }
     assert!(response.diagnostics.is_empty());
+    _m1.assert_async().await;
 
-    let cloudinit_block = response
+    let cloudinit_blocks = response
         .new_state
-        .get_map(&AttributePath::new("cloudinit"))
+        .get_list(&AttributePath::new("cloudinit"))
         .unwrap();
-    assert_eq!(
-        cloudinit_block.get("user").unwrap(),
-        &Dynamic::String("ubuntu".to_string())
-    );
-    assert_eq!(
-        cloudinit_block.get("password").unwrap(),
-        &Dynamic::String("secret123".to_string())
-    );
-    assert_eq!(
-        cloudinit_block.get("ssh_keys").unwrap(),
-        &Dynamic::String("ssh-rsa AAAAB3... user@example.com".to_string())
-    );
-
-    match cloudinit_block.get("ipconfig").unwrap() {
-        Dynamic::List(ipconfigs) => {
-            assert_eq!(ipconfigs.len(), 2);
-        }
-        _ => panic!("Expected ipconfig to be a list"),
+    match &cloudinit_blocks[..] {
+        [Dynamic::Map(cloudinit_block)] => match cloudinit_block.get("ipconfig").unwrap() {
+            Dynamic::List(ipconfigs) => {
+                assert_eq!(ipconfigs.len(), 2);
+            }
+            _ => panic!("Expected ipconfig to be a list"),
+        },
+        _ => panic!("Expected a single cloudinit block"),
     }
 }
 
@@ -1402,7 +2612,7 @@ async fn test_update_vm_with_nested_blocks() {
     );
     updated_obj.insert("vmid".to_string(), Dynamic::Number(100.0));
     updated_obj.insert("name".to_string(), Dynamic::String("test-vm".to_string()));
-    updated_obj.insert("memory".to_string(), Dynamic::Number(4096.0));
+    updated_obj.insert("memory".to_string(), memory_block(4096.0));
     updated_obj.insert("cores".to_string(), Dynamic::Number(4.0));
     updated_obj.insert("sockets".to_string(), Dynamic::Number(1.0));
     updated_obj.insert(
@@ -1554,6 +2764,7 @@ async fn test_read_vm_with_nested_blocks() {
 #[tokio::test]
 async fn test_mixed_blocks_and_string_attributes() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -1700,6 +2911,7 @@ async fn test_mixed_blocks_and_string_attributes() {
 #[tokio::test]
 async fn test_vm_creation_with_mac_address_specified() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
@@ -1803,6 +3015,7 @@ async fn test_vm_creation_with_mac_address_specified() {
 #[tokio::test]
 async fn test_disk_path_transformation() {
     let mut server = Server::new_async().await;
+    let _task = mock_create_task(&mut server).await;
     let _m1 = server
         .mock("POST", "/api2/json/nodes/pve/qemu")
         .match_header("content-type", "application/json")
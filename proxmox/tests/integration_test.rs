@@ -1 +1,3 @@
+#![allow(clippy::disallowed_methods)] // Allow unwrap() in tests for clarity
+
 mod integration;
@@ -0,0 +1,220 @@
+//! Derive macros for tfplug
+//!
+//! `#[derive(TfSchema)]` turns a struct with named fields into a schema
+//! block and generates the conversions between the struct and the Dynamic
+//! values Terraform sends. Doc comments become descriptions.
+//!
+//! ```ignore
+//! /// Startup and shutdown ordering
+//! #[derive(TfSchema)]
+//! struct Startup {
+//!     /// Start order
+//!     order: Option<f64>,
+//!     #[tfplug(optional, computed)]
+//!     uuid: Option<String>,
+//!     #[tfplug(block)]
+//!     disk: Vec<Disk>,
+//! }
+//! ```
+//!
+//! Field options, inside `#[tfplug(...)]`:
+//! - `required`, `optional`, `computed`, `sensitive`: attribute flags. Without
+//!   any of the first three, `Option` fields are optional and all others
+//!   required.
+//! - `block`: the field is a nested block, `Option<T>` for at most one block
+//!   and `Vec<T>` for a list of blocks, where T derives TfSchema as well.
+//! - `rename = "name"`: the attribute name, when it differs from the field.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, LitStr};
+
+#[proc_macro_derive(TfSchema, attributes(tfplug))]
+pub fn derive_tf_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Options of one field
+#[derive(Default)]
+struct FieldOptions {
+    required: bool,
+    optional: bool,
+    computed: bool,
+    sensitive: bool,
+    block: bool,
+    rename: Option<String>,
+}
+
+impl FieldOptions {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = FieldOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("tfplug")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("required") {
+                    options.required = true;
+                } else if meta.path.is_ident("optional") {
+                    options.optional = true;
+                } else if meta.path.is_ident("computed") {
+                    options.computed = true;
+                } else if meta.path.is_ident("sensitive") {
+                    options.sensitive = true;
+                } else if meta.path.is_ident("block") {
+                    options.block = true;
+                } else if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else {
+                    return Err(meta.error("unknown tfplug option"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+/// The doc comment of an item as a single line
+fn description(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value().trim().to_string()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "TfSchema needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "TfSchema can only be derived for structs",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "TfSchema can't be derived for generic structs",
+        ));
+    }
+
+    let block_description = description(&input.attrs);
+    let mut attributes = Vec::new();
+    let mut block_types = Vec::new();
+    let mut reads = Vec::new();
+    let mut writes = Vec::new();
+
+    for field in fields {
+        let options = FieldOptions::parse(&field.attrs)?;
+        let ident = field.ident.as_ref().ok_or_else(|| {
+            syn::Error::new_spanned(field, "TfSchema needs a struct with named fields")
+        })?;
+        let ty = &field.ty;
+        let attribute_name = options
+            .rename
+            .clone()
+            .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+        let field_description = description(&field.attrs);
+
+        if options.block {
+            block_types.push(quote! {
+                <#ty as ::tfplug::model::TfBlock>::nested_block(#attribute_name, #field_description)
+            });
+            reads.push(quote! {
+                #ident: ::tfplug::model::block_field(object, #attribute_name)?
+            });
+            writes.push(quote! {
+                (#attribute_name.to_string(), ::tfplug::model::TfBlock::to_block(&self.#ident))
+            });
+            continue;
+        }
+
+        let flags = if options.required || options.optional || options.computed {
+            let required = options.required.then(|| quote! { .required() });
+            let optional = options.optional.then(|| quote! { .optional() });
+            let computed = options.computed.then(|| quote! { .computed() });
+            quote! { #required #optional #computed }
+        } else {
+            quote! {
+                ;
+                let builder = if <#ty as ::tfplug::model::TfValue>::OPTIONAL {
+                    builder.optional()
+                } else {
+                    builder.required()
+                }
+            }
+        };
+        let sensitive = options.sensitive.then(|| quote! { .sensitive() });
+        attributes.push(quote! {
+            {
+                let builder = ::tfplug::schema::AttributeBuilder::new(
+                    #attribute_name,
+                    <#ty as ::tfplug::model::TfValue>::attribute_type(),
+                )
+                .description(#field_description)
+                #sensitive
+                #flags;
+                builder.build()
+            }
+        });
+        reads.push(quote! {
+            #ident: ::tfplug::model::field(object, #attribute_name)?
+        });
+        writes.push(quote! {
+            (#attribute_name.to_string(), ::tfplug::model::TfValue::to_dynamic(&self.#ident))
+        });
+    }
+
+    Ok(quote! {
+        impl ::tfplug::model::TfSchema for #name {
+            fn block() -> ::tfplug::schema::Block {
+                ::tfplug::schema::Block {
+                    version: 0,
+                    attributes: vec![#(#attributes),*],
+                    block_types: vec![#(#block_types),*],
+                    description: #block_description.to_string(),
+                    description_kind: ::tfplug::schema::StringKind::Plain,
+                    deprecated: false,
+                }
+            }
+
+            fn from_dynamic(value: &::tfplug::types::Dynamic) -> ::tfplug::Result<Self> {
+                let object = ::tfplug::model::object(value)?;
+                Ok(Self {
+                    #(#reads),*
+                })
+            }
+
+            fn to_dynamic(&self) -> ::tfplug::types::Dynamic {
+                ::tfplug::types::Dynamic::Map(::std::collections::HashMap::from([
+                    #(#writes),*
+                ]))
+            }
+        }
+    })
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::disallowed_methods)] // Allow unwrap() in examples for clarity
+
 use tfplug::{
     defaults::StaticDefault,
     plan_modifier::RequiresReplace,
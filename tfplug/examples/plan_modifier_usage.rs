@@ -7,6 +7,8 @@
 //! - Complex attribute paths
 //! - Combining multiple plan modifiers
 
+#![allow(clippy::disallowed_methods)] // Allow unwrap() in examples for clarity
+
 use tfplug::defaults::StaticDefault;
 use tfplug::plan_modifier::{
     NormalizeCase, PreventUpdate, RequiresReplace, RequiresReplaceIf, SetDefault,
@@ -92,7 +94,7 @@ impl PlanModifier for NormalizePort {
         if let Dynamic::Number(port) = &request.plan_value.value {
             let port_int = *port as i32;
 
-            if port_int < 1 || port_int > 65535 {
+            if !(1..=65535).contains(&port_int) {
                 // Clamp to valid range
                 let normalized = port_int.clamp(1, 65535);
                 response.plan_value = DynamicValue::new(Dynamic::Number(normalized as f64));
@@ -9,6 +9,8 @@
 //! - Complex schema definitions with validators
 //! - Concurrent operations
 
+#![allow(clippy::disallowed_methods)] // Allow unwrap() in examples for clarity
+
 use std::any::Any;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -29,9 +31,9 @@ use tfplug::resource::{
     ImportResourceStateRequest, ImportResourceStateResponse, ModifyPlanRequest, ModifyPlanResponse,
     ReadResourceRequest, ReadResourceResponse, Resource, ResourceMetadataRequest,
     ResourceMetadataResponse, ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigure,
-    ResourceWithImportState, ResourceWithModifyPlan, ResourceWithUpgradeState,
-    UpdateResourceRequest, UpdateResourceResponse, UpgradeResourceStateRequest,
-    UpgradeResourceStateResponse, ValidateResourceConfigRequest, ValidateResourceConfigResponse,
+    ResourceWithImportState, ResourceWithModifyPlan, ResourceWithUpgradeState, StateUpgrader,
+    UpdateResourceRequest, UpdateResourceResponse, ValidateResourceConfigRequest,
+    ValidateResourceConfigResponse,
 };
 use tfplug::schema::AttributeType;
 use tfplug::types::{
@@ -371,6 +373,7 @@ impl Resource for ServerResource {
         _request: ResourceSchemaRequest,
     ) -> ResourceSchemaResponse {
         let schema = SchemaBuilder::new()
+            .version(1)
             .attribute(
                 AttributeBuilder::new("name", AttributeType::String)
                     .required()
@@ -691,6 +694,10 @@ impl ResourceWithConfigure for ServerResource {
             diagnostics: vec![],
         }
     }
+
+    fn as_upgrade_state(&self) -> Option<&dyn ResourceWithUpgradeState> {
+        Some(self)
+    }
 }
 
 #[async_trait::async_trait]
@@ -745,49 +752,17 @@ impl ResourceWithModifyPlan for ServerResource {
 
 #[async_trait::async_trait]
 impl ResourceWithUpgradeState for ServerResource {
-    async fn upgrade_state(
-        &self,
-        _ctx: Context,
-        request: UpgradeResourceStateRequest,
-    ) -> UpgradeResourceStateResponse {
-        let diagnostics = vec![];
-
-        // Example: upgrade from version 0 to version 1
+    fn state_upgraders(&self) -> Vec<StateUpgrader> {
         // Version 0 had "instance_size", version 1 has "size"
-        if request.version == 0 {
-            // For this example, we'll create a new upgraded state
-            let mut upgraded = DynamicValue::new(Dynamic::Map(HashMap::new()));
-
-            // In a real implementation, you would parse request.raw_state and migrate fields
-            // For this example, we'll just show the pattern with expected fields
-            upgraded
-                .set_string(&AttributePath::new("size"), "medium".to_string())
-                .unwrap();
-            upgraded
-                .set_string(&AttributePath::new("id"), "srv-12345".to_string())
-                .unwrap();
-            upgraded
-                .set_string(&AttributePath::new("name"), "upgraded-server".to_string())
-                .unwrap();
-            upgraded
-                .set_string(&AttributePath::new("status"), "running".to_string())
-                .unwrap();
-            upgraded
-                .set_string(&AttributePath::new("ip_address"), "10.0.0.1".to_string())
-                .unwrap();
-
-            return UpgradeResourceStateResponse {
-                upgraded_state: upgraded,
-                diagnostics,
-            };
-        }
-
-        // For any other version, return as-is
-        // In a real implementation, you would parse the raw state
-        UpgradeResourceStateResponse {
-            upgraded_state: DynamicValue::unknown(),
-            diagnostics,
-        }
+        vec![StateUpgrader::new(0, |mut state| {
+            let size = state
+                .get_string(&AttributePath::new("instance_size"))
+                .unwrap_or_else(|_| "medium".to_string());
+            state
+                .set_string(&AttributePath::new("size"), size)
+                .map_err(|e| vec![Diagnostic::error("Failed to upgrade state", e.to_string())])?;
+            Ok(state)
+        })]
     }
 }
 
@@ -1,5 +1,7 @@
 //! Example showing how to use the new tfplug Provider API
 
+#![allow(clippy::disallowed_methods)] // Allow unwrap() in examples for clarity
+
 use async_trait::async_trait;
 use std::any::Any;
 use std::collections::HashMap;
@@ -828,7 +830,7 @@ async fn main() {
         .await
         .into_iter()
         .filter_map(|r| r.ok())
-        .filter_map(|r| r)
+        .flatten()
         .collect();
 
     println!("\nCreated {} users concurrently", results.len());
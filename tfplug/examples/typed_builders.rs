@@ -6,6 +6,8 @@
 //! - Working with DynamicValue for configuration and state
 //! - Type-safe access to values using AttributePath
 
+#![allow(clippy::disallowed_methods)] // Allow unwrap() in examples for clarity
+
 use std::collections::HashMap;
 use tfplug::{
     defaults::StaticDefault, plan_modifier::RequiresReplace, AttributeBuilder, AttributeType,
@@ -16,11 +16,28 @@ use tokio::time;
 #[derive(Clone)]
 pub struct Context {
     inner: Arc<ContextInner>,
+    log: Arc<LogScope>,
+}
+
+/// Module and fields added to lines logged through the context
+#[derive(Clone)]
+struct LogScope {
+    module: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for LogScope {
+    fn default() -> Self {
+        Self {
+            module: crate::tflog::PROVIDER_MODULE.to_string(),
+            fields: serde_json::Map::new(),
+        }
+    }
 }
 
 struct ContextInner {
     deadline: Option<Instant>,
-    values: RwLock<HashMap<String, Box<dyn Any + Send + Sync>>>,
+    values: Arc<RwLock<HashMap<String, Box<dyn Any + Send + Sync>>>>,
     done: watch::Receiver<bool>,
     _done_tx: watch::Sender<bool>,
 }
@@ -32,10 +49,11 @@ impl Context {
         Self {
             inner: Arc::new(ContextInner {
                 deadline: None,
-                values: RwLock::new(HashMap::new()),
+                values: Arc::new(RwLock::new(HashMap::new())),
                 done: done_rx,
                 _done_tx: done_tx,
             }),
+            log: Arc::new(LogScope::default()),
         }
     }
 
@@ -53,10 +71,11 @@ impl Context {
         Self {
             inner: Arc::new(ContextInner {
                 deadline: Some(deadline),
-                values: RwLock::new(HashMap::new()),
+                values: Arc::new(RwLock::new(HashMap::new())),
                 done: done_rx,
                 _done_tx: done_tx,
             }),
+            log: self.log,
         }
     }
 
@@ -92,6 +111,74 @@ impl Context {
     pub fn cancel(&self) {
         let _ = self.inner._done_tx.send(true);
     }
+
+    /// Completes once the context is cancelled. Select on it next to long
+    /// waits so they stop when Terraform is interrupted.
+    pub async fn cancelled(&self) {
+        let mut done = self.done();
+        if done.wait_for(|done| *done).await.is_err() {
+            // The sender lives as long as the context, so this only happens
+            // while it is being dropped
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Returns a context that is also cancelled when `parent` is, like a Go
+    /// context derived from a parent. It keeps the deadline, values and
+    /// cancellation of this context.
+    pub fn with_parent(self, parent: &Context) -> Self {
+        let (done_tx, done_rx) = watch::channel(self.is_cancelled() || parent.is_cancelled());
+
+        let done_tx_clone = done_tx.clone();
+        let parent = parent.clone();
+        let own = self.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = parent.cancelled() => {
+                    let _ = done_tx_clone.send(true);
+                }
+                _ = own.cancelled() => {
+                    let _ = done_tx_clone.send(true);
+                }
+                // Nothing is left to cancel once the context is dropped
+                _ = done_tx_clone.closed() => {}
+            }
+        });
+
+        Self {
+            inner: Arc::new(ContextInner {
+                deadline: self.inner.deadline,
+                values: self.inner.values.clone(),
+                done: done_rx,
+                _done_tx: done_tx,
+            }),
+            log: self.log,
+        }
+    }
+
+    /// Adds a field to every line logged through the returned context,
+    /// like tflog.SetField
+    pub fn with_log_field(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        Arc::make_mut(&mut self.log)
+            .fields
+            .insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Logs through the returned context go to the `provider.<name>`
+    /// module, which TF_LOG_PROVIDER_<NAME> filters separately
+    pub fn with_log_subsystem(mut self, name: &str) -> Self {
+        Arc::make_mut(&mut self.log).module = format!("{}.{}", crate::tflog::PROVIDER_MODULE, name);
+        self
+    }
+
+    pub(crate) fn log_module(&self) -> &str {
+        &self.log.module
+    }
+
+    pub(crate) fn log_fields(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.log.fields
+    }
 }
 
 impl Default for Context {
@@ -136,6 +223,41 @@ mod tests {
         assert!(ctx.is_cancelled());
     }
 
+    #[tokio::test]
+    async fn context_cancelled_with_parent() {
+        let parent = Context::new();
+        let ctx = Context::new().with_parent(&parent);
+
+        assert!(!ctx.is_cancelled());
+
+        parent.cancel();
+        time::timeout(Duration::from_secs(1), ctx.cancelled())
+            .await
+            .expect("parent cancellation reaches the child");
+
+        assert!(ctx.is_cancelled());
+        assert!(Context::new().with_parent(&parent).is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn context_with_parent_keeps_own_values_and_cancellation() {
+        let parent = Context::new();
+        let ctx = Context::new()
+            .with_timeout(Duration::from_millis(50))
+            .with_value("api_key", "secret123".to_string())
+            .await
+            .with_parent(&parent);
+
+        let value: Option<String> = ctx.get_value("api_key").await;
+        assert_eq!(value, Some("secret123".to_string()));
+        assert!(ctx.deadline().is_some());
+
+        time::timeout(Duration::from_secs(1), ctx.cancelled())
+            .await
+            .expect("the own timeout still cancels the context");
+        assert!(!parent.is_cancelled());
+    }
+
     #[tokio::test]
     async fn context_deadline() {
         let ctx = Context::new();
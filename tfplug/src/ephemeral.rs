@@ -1,9 +1,15 @@
 //! Ephemeral resource trait for temporary resources
+//!
+//! Open, renew and close are each served by a fresh instance, so anything
+//! renew or close needs must be carried in the `private` bytes returned by
+//! open and renew. Terraform hands them back but never stores them.
 
 use crate::context::Context;
 use crate::schema::Schema;
 use crate::types::{ClientCapabilities, Deferred, Diagnostic, DynamicValue};
 use async_trait::async_trait;
+use std::any::Any;
+use std::sync::Arc;
 
 /// EphemeralResource trait for resources with temporary lifecycle
 #[async_trait]
@@ -51,7 +57,26 @@ pub trait EphemeralResource: Send + Sync {
     ) -> CloseEphemeralResourceResponse;
 }
 
+/// EphemeralResourceWithConfigure receives provider data before open,
+/// renew and close are called
+#[async_trait]
+pub trait EphemeralResourceWithConfigure: EphemeralResource {
+    async fn configure(
+        &mut self,
+        ctx: Context,
+        request: ConfigureEphemeralResourceRequest,
+    ) -> ConfigureEphemeralResourceResponse;
+}
+
 // Request/Response Types
+pub struct ConfigureEphemeralResourceRequest {
+    pub provider_data: Option<Arc<dyn Any + Send + Sync>>,
+}
+
+pub struct ConfigureEphemeralResourceResponse {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 pub struct EphemeralResourceMetadataRequest;
 
 pub struct EphemeralResourceMetadataResponse {
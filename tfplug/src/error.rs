@@ -33,6 +33,15 @@ pub enum TfplugError {
     #[error("Type mismatch: expected {expected}, got {actual}")]
     TypeMismatch { expected: String, actual: String },
 
+    #[error("Attribute not found: {0}")]
+    AttributeNotFound(String),
+
+    #[error("Value is null: {0}")]
+    NullValue(String),
+
+    #[error("Value is unknown: {0}")]
+    UnknownValue(String),
+
     #[error("Validation failed: {0}")]
     ValidationFailed(String),
 
@@ -1,6 +1,7 @@
 //! Function trait for provider functions
 
 use crate::context::Context;
+use crate::schema::AttributeType;
 use crate::types::{Diagnostic, DynamicValue, FunctionError};
 use async_trait::async_trait;
 
@@ -69,3 +70,154 @@ pub struct CallFunctionResponse {
     pub result: Option<DynamicValue>,
     pub error: Option<FunctionError>,
 }
+
+impl Parameter {
+    /// Create a parameter of the given type. Null and unknown arguments are
+    /// rejected by Terraform unless allowed.
+    pub fn new(name: &str, type_: AttributeType) -> Self {
+        Self {
+            name: name.to_string(),
+            type_: type_.type_json().to_string().into_bytes(),
+            allow_null_value: false,
+            allow_unknown_values: false,
+            description: String::new(),
+        }
+    }
+
+    /// Set description
+    pub fn description(mut self, desc: &str) -> Self {
+        self.description = desc.to_string();
+        self
+    }
+
+    /// Pass null arguments to the function instead of failing the call
+    pub fn allow_null(mut self) -> Self {
+        self.allow_null_value = true;
+        self
+    }
+
+    /// Pass unknown arguments to the function instead of returning an
+    /// unknown result without calling it
+    pub fn allow_unknown(mut self) -> Self {
+        self.allow_unknown_values = true;
+        self
+    }
+}
+
+/// FunctionBuilder provides fluent API for building function definitions
+pub struct FunctionBuilder {
+    definition: FunctionDefinition,
+}
+
+impl FunctionBuilder {
+    /// Create a function returning the given type
+    pub fn new(return_type: AttributeType) -> Self {
+        Self {
+            definition: FunctionDefinition {
+                parameters: Vec::new(),
+                variadic_parameter: None,
+                return_type: ReturnType {
+                    type_: return_type.type_json().to_string().into_bytes(),
+                },
+                summary: String::new(),
+                description: String::new(),
+                deprecation_message: None,
+            },
+        }
+    }
+
+    /// Set the one line summary
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.definition.summary = summary.to_string();
+        self
+    }
+
+    /// Set description
+    pub fn description(mut self, desc: &str) -> Self {
+        self.definition.description = desc.to_string();
+        self
+    }
+
+    /// Add the next positional parameter
+    pub fn parameter(mut self, parameter: Parameter) -> Self {
+        self.definition.parameters.push(parameter);
+        self
+    }
+
+    /// Accept any number of trailing arguments of this parameter
+    pub fn variadic_parameter(mut self, parameter: Parameter) -> Self {
+        self.definition.variadic_parameter = Some(parameter);
+        self
+    }
+
+    /// Mark as deprecated
+    pub fn deprecated(mut self, message: &str) -> Self {
+        self.definition.deprecation_message = Some(message.to_string());
+        self
+    }
+
+    /// Finalize the definition
+    pub fn build(self) -> FunctionDefinition {
+        self.definition
+    }
+}
+
+impl CallFunctionResponse {
+    /// Successful call returning `result`
+    pub fn result(result: DynamicValue) -> Self {
+        Self {
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// Failed call. `argument` is the index of the offending argument, if any.
+    pub fn error(text: impl Into<String>, argument: Option<i64>) -> Self {
+        Self {
+            result: None,
+            error: Some(FunctionError {
+                text: text.into(),
+                function_argument: argument,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn function_builder_encodes_types() {
+        let definition = FunctionBuilder::new(AttributeType::Number)
+            .summary("Parse a size")
+            .parameter(Parameter::new("size", AttributeType::String).allow_null())
+            .variadic_parameter(Parameter::new(
+                "rest",
+                AttributeType::List(Box::new(AttributeType::Bool)),
+            ))
+            .build();
+
+        assert_eq!(definition.return_type.type_, br#""number""#.to_vec());
+        assert_eq!(definition.summary, "Parse a size");
+        assert_eq!(definition.parameters.len(), 1);
+        assert_eq!(definition.parameters[0].type_, br#""string""#.to_vec());
+        assert!(definition.parameters[0].allow_null_value);
+        assert!(!definition.parameters[0].allow_unknown_values);
+        assert_eq!(
+            definition.variadic_parameter.unwrap().type_,
+            br#"["list","bool"]"#.to_vec()
+        );
+        assert!(definition.deprecation_message.is_none());
+    }
+
+    #[test]
+    fn call_function_response_carries_argument_index() {
+        let response = CallFunctionResponse::error("bad size", Some(0));
+        assert!(response.result.is_none());
+        let error = response.error.unwrap();
+        assert_eq!(error.text, "bad size");
+        assert_eq!(error.function_argument, Some(0));
+    }
+}
@@ -7,6 +7,7 @@ use crate::context::Context;
 use crate::proto;
 use crate::provider::Provider;
 use crate::types::DynamicValue;
+use crate::validator::{run_config_validators, validate_config};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tonic::{Request, Response, Status};
@@ -19,6 +20,8 @@ pub struct GrpcProviderServer<P: Provider> {
     provider: Arc<RwLock<P>>,
     provider_data: Arc<RwLock<Option<Arc<dyn std::any::Any + Send + Sync>>>>,
     configured: Arc<RwLock<bool>>,
+    /// Cancelled by StopProvider, and with it every RPC still running
+    stop: Context,
 }
 
 impl<P: Provider + 'static> GrpcProviderServer<P> {
@@ -27,8 +30,42 @@ impl<P: Provider + 'static> GrpcProviderServer<P> {
             provider: Arc::new(RwLock::new(provider)),
             provider_data: Arc::new(RwLock::new(None)),
             configured: Arc::new(RwLock::new(false)),
+            stop: Context::new(),
         }
     }
+
+    /// Context for one RPC. Like terraform-plugin-framework, every line the
+    /// provider logs while serving it carries the RPC name and a request ID,
+    /// and it is cancelled when Terraform stops the provider.
+    fn request_context(&self, rpc: &str) -> Context {
+        Context::new()
+            .with_parent(&self.stop)
+            .with_log_field("tf_rpc", rpc)
+            .with_log_field("tf_req_id", uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Creates the named ephemeral resource and hands it the provider data
+    async fn ephemeral_resource(
+        &self,
+        ctx: &Context,
+        type_name: &str,
+    ) -> GrpcResult<Box<dyn crate::ephemeral::EphemeralResourceWithConfigure>> {
+        let provider = self.provider.read().await;
+        let ephemeral_resources = provider.ephemeral_resources();
+        let factory = ephemeral_resources.get(type_name).ok_or_else(|| {
+            Status::not_found(format!("ephemeral resource type '{}' not found", type_name))
+        })?;
+
+        let mut ephemeral_resource = factory();
+        let provider_data = self.provider_data.read().await.clone();
+        let _ = ephemeral_resource
+            .configure(
+                ctx.clone(),
+                crate::ephemeral::ConfigureEphemeralResourceRequest { provider_data },
+            )
+            .await;
+        Ok(ephemeral_resource)
+    }
 }
 
 #[tonic::async_trait]
@@ -40,7 +77,7 @@ where
         &self,
         _request: Request<proto::get_metadata::Request>,
     ) -> std::result::Result<Response<proto::get_metadata::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("GetMetadata");
         let provider = self.provider.read().await;
 
         let provider_response = provider
@@ -74,7 +111,17 @@ where
                 });
         }
 
-        // TODO: Handle functions and ephemeral resources when those traits are implemented
+        for (name, _) in provider.functions() {
+            response
+                .functions
+                .push(proto::get_metadata::FunctionMetadata { name });
+        }
+
+        for (name, _) in provider.ephemeral_resources() {
+            response
+                .ephemeral_resources
+                .push(proto::get_metadata::EphemeralResourceMetadata { type_name: name });
+        }
 
         Ok(Response::new(response))
     }
@@ -83,7 +130,7 @@ where
         &self,
         _request: Request<proto::get_provider_schema::Request>,
     ) -> std::result::Result<Response<proto::get_provider_schema::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("GetProviderSchema");
         let provider = self.provider.read().await;
 
         let provider_schema_response = provider
@@ -102,11 +149,12 @@ where
             ephemeral_resource_schemas: std::collections::HashMap::new(),
             functions: std::collections::HashMap::new(),
             diagnostics: convert_diagnostics(&provider_schema_response.diagnostics),
-            server_capabilities: Some(proto::ServerCapabilities {
-                plan_destroy: false,
-                get_provider_schema_optional: false,
-                move_resource_state: false,
-            }),
+            server_capabilities: Some(convert_server_capabilities(
+                &provider
+                    .metadata(ctx.clone(), crate::provider::ProviderMetadataRequest)
+                    .await
+                    .server_capabilities,
+            )),
         };
 
         for (name, factory) in provider.resources() {
@@ -129,6 +177,25 @@ where
                 .insert(name.clone(), convert_schema(&schema_response.schema));
         }
 
+        for (name, factory) in provider.ephemeral_resources() {
+            let ephemeral_resource = factory();
+            let schema_response = ephemeral_resource
+                .schema(
+                    ctx.clone(),
+                    crate::ephemeral::EphemeralResourceSchemaRequest,
+                )
+                .await;
+            response
+                .ephemeral_resource_schemas
+                .insert(name.clone(), convert_schema(&schema_response.schema));
+        }
+
+        let (functions, diagnostics) = function_definitions(&*provider, &ctx).await;
+        response.functions = functions;
+        response
+            .diagnostics
+            .extend(convert_diagnostics(&diagnostics));
+
         Ok(Response::new(response))
     }
 
@@ -136,7 +203,7 @@ where
         &self,
         request: Request<proto::validate_provider_config::Request>,
     ) -> std::result::Result<Response<proto::validate_provider_config::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ValidateProviderConfig");
         let provider = self.provider.read().await;
         let req = request.into_inner();
 
@@ -167,7 +234,7 @@ where
         &self,
         request: Request<proto::configure_provider::Request>,
     ) -> std::result::Result<Response<proto::configure_provider::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ConfigureProvider");
         let mut provider = self.provider.write().await;
         let req = request.into_inner();
 
@@ -204,7 +271,10 @@ where
         &self,
         _request: Request<proto::stop_provider::Request>,
     ) -> std::result::Result<Response<proto::stop_provider::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("StopProvider");
+        // Long waits in other RPCs select on their context, so they return
+        // instead of running on after Terraform gave up on them
+        self.stop.cancel();
         let provider = self.provider.read().await;
 
         let response = provider
@@ -220,9 +290,10 @@ where
         &self,
         request: Request<proto::validate_resource_config::Request>,
     ) -> std::result::Result<Response<proto::validate_resource_config::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ValidateResourceConfig");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
 
         let resources = provider.resources();
         let factory = resources.get(&req.type_name).ok_or_else(|| {
@@ -255,6 +326,17 @@ where
             }
         };
 
+        let schema_response = resource
+            .schema(ctx.clone(), crate::resource::ResourceSchemaRequest)
+            .await;
+        let mut diagnostics = validate_config(&schema_response.schema.block, &config);
+        if let Some(validators) = resource.as_config_validators() {
+            diagnostics.extend(run_config_validators(
+                &validators.config_validators(),
+                &config,
+            ));
+        }
+
         let response = resource
             .validate(
                 ctx,
@@ -265,9 +347,10 @@ where
                 },
             )
             .await;
+        diagnostics.extend(response.diagnostics);
 
         Ok(Response::new(proto::validate_resource_config::Response {
-            diagnostics: convert_diagnostics(&response.diagnostics),
+            diagnostics: convert_diagnostics(&diagnostics),
         }))
     }
 
@@ -275,9 +358,10 @@ where
         &self,
         request: Request<proto::upgrade_resource_state::Request>,
     ) -> std::result::Result<Response<proto::upgrade_resource_state::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("UpgradeResourceState");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
 
         let resources = provider.resources();
         let factory = resources.get(&req.type_name).ok_or_else(|| {
@@ -302,56 +386,54 @@ where
             .raw_state
             .ok_or_else(|| Status::invalid_argument("raw_state is required"))?;
 
+        // State of the current version needs no upgrader, but may predate
+        // attributes added without a version bump, so it is still shaped to
+        // the schema
         if req.version == schema_response.schema.version {
-            let state = if !raw_state.json.is_empty() {
-                DynamicValue::decode_json(&raw_state.json).map_err(|e| {
-                    Status::invalid_argument(format!("failed to decode state: {}", e))
-                })?
-            } else {
-                return Err(Status::invalid_argument("raw state must have json"));
-            };
-
+            let response = crate::upgrade::upgrade_state(
+                &[],
+                &schema_response.schema,
+                req.version,
+                &convert_raw_state_from_proto(&raw_state),
+            );
             return Ok(Response::new(proto::upgrade_resource_state::Response {
-                upgraded_state: Some(convert_dynamic_value_to_proto(&state)?),
-                diagnostics: vec![],
+                upgraded_state: Some(convert_dynamic_value_to_proto(&response.upgraded_state)?),
+                diagnostics: convert_diagnostics(&response.diagnostics),
             }));
         }
 
-        {
-            return Err(Status::unimplemented(format!(
+        let upgradeable = resource.as_upgrade_state().ok_or_else(|| {
+            Status::unimplemented(format!(
                 "resource '{}' schema version changed from {} to {} but ResourceWithUpgradeState not implemented",
                 req.type_name, req.version, schema_response.schema.version
-            )));
-        }
+            ))
+        })?;
 
-        /* TODO: Enable when we have proper downcasting
-        if let Some(upgradeable) = resource.as_any().downcast_ref::<dyn ResourceWithUpgradeState>() {
-            let response = upgradeable
-                .upgrade_state(
-                    ctx,
-                    crate::resource::UpgradeResourceStateRequest {
-                        type_name: req.type_name,
-                        version: req.version,
-                        raw_state: convert_raw_state_from_proto(&raw_state),
-                    },
-                )
-                .await;
+        let response = upgradeable
+            .upgrade_state(
+                ctx,
+                crate::resource::UpgradeResourceStateRequest {
+                    type_name: req.type_name,
+                    version: req.version,
+                    raw_state: convert_raw_state_from_proto(&raw_state),
+                },
+            )
+            .await;
 
-            Ok(Response::new(proto::upgrade_resource_state::Response {
-                upgraded_state: Some(convert_dynamic_value_to_proto(&response.upgraded_state)?),
-                diagnostics: convert_diagnostics(&response.diagnostics),
-            }))
-        }
-        */
+        Ok(Response::new(proto::upgrade_resource_state::Response {
+            upgraded_state: Some(convert_dynamic_value_to_proto(&response.upgraded_state)?),
+            diagnostics: convert_diagnostics(&response.diagnostics),
+        }))
     }
 
     async fn read_resource(
         &self,
         request: Request<proto::read_resource::Request>,
     ) -> std::result::Result<Response<proto::read_resource::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ReadResource");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
 
         let resources = provider.resources();
         let factory = resources.get(&req.type_name).ok_or_else(|| {
@@ -392,7 +474,11 @@ where
                         .map(convert_dynamic_value_from_proto)
                         .transpose()?,
                     client_capabilities: convert_client_capabilities(&req.client_capabilities),
-                    current_identity: None, // TODO: Handle identity when implemented
+                    current_identity: req
+                        .current_identity
+                        .as_ref()
+                        .map(convert_identity_from_proto)
+                        .transpose()?,
                 },
             )
             .await;
@@ -404,9 +490,13 @@ where
                 .map(convert_dynamic_value_to_proto)
                 .transpose()?,
             diagnostics: convert_diagnostics(&response.diagnostics),
+            new_identity: response_identity(
+                &*resource,
+                response.new_identity,
+                response.new_state.as_ref(),
+            )?,
             private: response.private,
             deferred: response.deferred.as_ref().map(convert_deferred),
-            new_identity: None, // TODO: Handle identity when implemented
         }))
     }
 
@@ -414,9 +504,10 @@ where
         &self,
         request: Request<proto::plan_resource_change::Request>,
     ) -> std::result::Result<Response<proto::plan_resource_change::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("PlanResourceChange");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
 
         let resources = provider.resources();
         let factory = resources.get(&req.type_name).ok_or_else(|| {
@@ -433,12 +524,12 @@ where
             )
             .await;
 
-        let _config = convert_dynamic_value_from_proto(
+        let config = convert_dynamic_value_from_proto(
             &req.config
                 .ok_or_else(|| Status::invalid_argument("config is required"))?,
         )?;
 
-        let _prior_state = convert_dynamic_value_from_proto(
+        let prior_state = convert_dynamic_value_from_proto(
             &req.prior_state
                 .ok_or_else(|| Status::invalid_argument("prior_state is required"))?,
         )?;
@@ -448,25 +539,27 @@ where
                 .ok_or_else(|| Status::invalid_argument("proposed_new_state is required"))?,
         )?;
 
-        let planned_state = proposed_new_state.clone();
-        let requires_replace = vec![];
-        let planned_private = req.prior_private.clone();
-        let diagnostics = vec![];
+        let mut planned_state = proposed_new_state.clone();
+        let mut requires_replace = vec![];
+        let mut planned_private = req.prior_private.clone();
+        let mut diagnostics = vec![];
 
         // If resource implements ModifyPlan, call it
-        // TODO: Implement proper downcasting when we have a better type system
-        /* TODO: Enable when we have proper downcasting
-        if let Some(plan_modifier) = resource.as_any().downcast_ref::<dyn ResourceWithModifyPlan>() {
+        if let Some(plan_modifier) = resource.as_modify_plan() {
             let modify_response = plan_modifier
                 .modify_plan(
                     ctx,
                     crate::resource::ModifyPlanRequest {
                         type_name: req.type_name.clone(),
-                        config: config.clone(),
+                        config,
                         prior_state,
-                        proposed_new_state: proposed_new_state.clone(),
+                        proposed_new_state,
                         prior_private: req.prior_private.clone(),
-                        provider_meta: req.provider_meta.as_ref().map(convert_dynamic_value_from_proto).transpose()?,
+                        provider_meta: req
+                            .provider_meta
+                            .as_ref()
+                            .map(convert_dynamic_value_from_proto)
+                            .transpose()?,
                     },
                 )
                 .await;
@@ -476,7 +569,14 @@ where
             planned_private = modify_response.planned_private;
             diagnostics = modify_response.diagnostics;
         }
-        */
+
+        // Identity stays the same for the life of an object, a new one is
+        // only known once it's created
+        let planned_identity = if planned_state.is_null() {
+            None
+        } else {
+            req.prior_identity
+        };
 
         Ok(Response::new(proto::plan_resource_change::Response {
             planned_state: Some(convert_dynamic_value_to_proto(&planned_state)?),
@@ -485,7 +585,7 @@ where
             diagnostics: convert_diagnostics(&diagnostics),
             legacy_type_system: false,
             deferred: None,
-            planned_identity: None, // TODO: Handle identity when implemented
+            planned_identity,
         }))
     }
 
@@ -493,9 +593,10 @@ where
         &self,
         request: Request<proto::apply_resource_change::Request>,
     ) -> std::result::Result<Response<proto::apply_resource_change::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ApplyResourceChange");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
 
         let resources = provider.resources();
         let factory = resources.get(&req.type_name).ok_or_else(|| {
@@ -551,10 +652,14 @@ where
 
             proto::apply_resource_change::Response {
                 new_state: Some(convert_dynamic_value_to_proto(&create_response.new_state)?),
+                new_identity: response_identity(
+                    &*resource,
+                    None,
+                    Some(&create_response.new_state),
+                )?,
                 private: create_response.private,
                 diagnostics: convert_diagnostics(&create_response.diagnostics),
                 legacy_type_system: false,
-                new_identity: None, // TODO: Handle identity when implemented
             }
         } else if !is_create && is_delete {
             let delete_response = resource
@@ -578,7 +683,7 @@ where
                 private: vec![],
                 diagnostics: convert_diagnostics(&delete_response.diagnostics),
                 legacy_type_system: false,
-                new_identity: None, // TODO: Handle identity when implemented
+                new_identity: None,
             }
         } else if !is_create && !is_delete {
             let update_response = resource
@@ -595,17 +700,25 @@ where
                             .as_ref()
                             .map(convert_dynamic_value_from_proto)
                             .transpose()?,
-                        planned_identity: None, // TODO: Handle identity when implemented
+                        planned_identity: req
+                            .planned_identity
+                            .as_ref()
+                            .map(convert_identity_from_proto)
+                            .transpose()?,
                     },
                 )
                 .await;
 
             proto::apply_resource_change::Response {
                 new_state: Some(convert_dynamic_value_to_proto(&update_response.new_state)?),
+                new_identity: response_identity(
+                    &*resource,
+                    update_response.new_identity,
+                    Some(&update_response.new_state),
+                )?,
                 private: update_response.private,
                 diagnostics: convert_diagnostics(&update_response.diagnostics),
                 legacy_type_system: false,
-                new_identity: None, // TODO: Handle identity when implemented
             }
         } else {
             return Err(Status::invalid_argument(
@@ -620,9 +733,10 @@ where
         &self,
         request: Request<proto::import_resource_state::Request>,
     ) -> std::result::Result<Response<proto::import_resource_state::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ImportResourceState");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
 
         let resources = provider.resources();
         let factory = resources.get(&req.type_name).ok_or_else(|| {
@@ -639,62 +753,117 @@ where
             )
             .await;
 
-        /* TODO: Enable when we have proper downcasting
-        if let Some(importable) = resource.as_any().downcast_ref::<dyn ResourceWithImportState>() {
-            let response = importable
-                .import_state(
-                    ctx,
-                    crate::resource::ImportResourceStateRequest {
-                        type_name: req.type_name,
-                        id: req.id,
-                        client_capabilities: convert_client_capabilities(&req.client_capabilities),
-                        identity: None, // TODO: Handle identity when implemented
-                    },
-                )
-                .await;
+        let importable = resource.as_import_state().ok_or_else(|| {
+            Status::unimplemented(format!(
+                "resource '{}' does not implement import",
+                req.type_name
+            ))
+        })?;
 
-            let imported_resources = response.imported_resources.iter()
-                .map(|r| {
-                    Ok(proto::import_resource_state::ImportedResource {
-                        type_name: r.type_name.clone(),
-                        state: Some(convert_dynamic_value_to_proto(&r.state)?),
-                        private: r.private.clone(),
-                        identity: None, // TODO: Handle identity when implemented
-                    })
+        let response = importable
+            .import_state(
+                ctx,
+                crate::resource::ImportResourceStateRequest {
+                    type_name: req.type_name,
+                    id: req.id,
+                    client_capabilities: convert_client_capabilities(&req.client_capabilities),
+                    identity: req
+                        .identity
+                        .as_ref()
+                        .map(convert_identity_from_proto)
+                        .transpose()?,
+                },
+            )
+            .await;
+
+        let imported_resources = response
+            .imported_resources
+            .into_iter()
+            .map(|r| {
+                Ok(proto::import_resource_state::ImportedResource {
+                    state: Some(convert_dynamic_value_to_proto(&r.state)?),
+                    identity: response_identity(&*resource, r.identity, Some(&r.state))?,
+                    type_name: r.type_name,
+                    private: r.private,
                 })
-                .collect::<Result<Vec<_>, Status>>()?;
+            })
+            .collect::<GrpcResult<Vec<_>>>()?;
 
-            Ok(Response::new(proto::import_resource_state::Response {
-                imported_resources,
-                diagnostics: convert_diagnostics(&response.diagnostics),
-                deferred: response.deferred.as_ref().map(convert_deferred),
-            }))
-        } else {
-        */
-        return Err(Status::unimplemented(format!(
-            "resource '{}' does not implement import",
-            req.type_name
-        )));
-        /*
-        }
-        */
+        Ok(Response::new(proto::import_resource_state::Response {
+            imported_resources,
+            diagnostics: convert_diagnostics(&response.diagnostics),
+            deferred: response.deferred.as_ref().map(convert_deferred),
+        }))
     }
 
     async fn move_resource_state(
         &self,
-        _request: Request<proto::move_resource_state::Request>,
+        request: Request<proto::move_resource_state::Request>,
     ) -> std::result::Result<Response<proto::move_resource_state::Response>, Status> {
-        // TODO: Implement when ResourceWithMoveState trait is available
-        Err(Status::unimplemented("move_resource_state not implemented"))
+        let ctx = self.request_context("MoveResourceState");
+        let provider = self.provider.read().await;
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.target_type_name.as_str());
+
+        let resources = provider.resources();
+        let factory = resources.get(&req.target_type_name).ok_or_else(|| {
+            Status::not_found(format!(
+                "resource type '{}' not found",
+                req.target_type_name
+            ))
+        })?;
+
+        let mut resource = factory();
+
+        let provider_data = self.provider_data.read().await.clone();
+        let _ = resource
+            .configure(
+                ctx.clone(),
+                crate::resource::ConfigureResourceRequest { provider_data },
+            )
+            .await;
+
+        let movable = resource.as_move_state().ok_or_else(|| {
+            Status::unimplemented(format!(
+                "resource '{}' does not implement ResourceWithMoveState",
+                req.target_type_name
+            ))
+        })?;
+
+        let source_state = req
+            .source_state
+            .ok_or_else(|| Status::invalid_argument("source_state is required"))?;
+
+        let response = movable
+            .move_state(
+                ctx,
+                crate::resource::MoveResourceStateRequest {
+                    source_provider_address: req.source_provider_address,
+                    source_type_name: req.source_type_name,
+                    source_schema_version: req.source_schema_version,
+                    source_state: convert_raw_state_from_proto(&source_state),
+                    target_type_name: req.target_type_name,
+                    source_private: req.source_private,
+                },
+            )
+            .await;
+
+        Ok(Response::new(proto::move_resource_state::Response {
+            target_state: Some(convert_dynamic_value_to_proto(&response.target_state)?),
+            diagnostics: convert_diagnostics(&response.diagnostics),
+            target_private: response.target_private,
+            target_identity: response_identity(&*resource, None, Some(&response.target_state))?,
+        }))
     }
 
     async fn read_data_source(
         &self,
         request: Request<proto::read_data_source::Request>,
     ) -> std::result::Result<Response<proto::read_data_source::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ReadDataSource");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_data_source_type", req.type_name.as_str());
 
         let data_sources = provider.data_sources();
         let factory = data_sources.get(&req.type_name).ok_or_else(|| {
@@ -747,9 +916,10 @@ where
         &self,
         request: Request<proto::validate_data_resource_config::Request>,
     ) -> std::result::Result<Response<proto::validate_data_resource_config::Response>, Status> {
-        let ctx = Context::new();
+        let ctx = self.request_context("ValidateDataResourceConfig");
         let provider = self.provider.read().await;
         let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_data_source_type", req.type_name.as_str());
 
         let data_sources = provider.data_sources();
         let factory = data_sources.get(&req.type_name).ok_or_else(|| {
@@ -784,6 +954,11 @@ where
             }
         };
 
+        let schema_response = data_source
+            .schema(ctx.clone(), crate::data_source::DataSourceSchemaRequest)
+            .await;
+        let mut diagnostics = validate_config(&schema_response.schema.block, &config);
+
         let response = data_source
             .validate(
                 ctx,
@@ -793,10 +968,11 @@ where
                 },
             )
             .await;
+        diagnostics.extend(response.diagnostics);
 
         Ok(Response::new(
             proto::validate_data_resource_config::Response {
-                diagnostics: convert_diagnostics(&response.diagnostics),
+                diagnostics: convert_diagnostics(&diagnostics),
             },
         ))
     }
@@ -805,80 +981,268 @@ where
         &self,
         _request: Request<proto::get_functions::Request>,
     ) -> std::result::Result<Response<proto::get_functions::Response>, Status> {
-        // TODO: Implement when ProviderWithFunctions trait is available
+        let ctx = self.request_context("GetFunctions");
+        let provider = self.provider.read().await;
+
+        let (functions, diagnostics) = function_definitions(&*provider, &ctx).await;
+
         Ok(Response::new(proto::get_functions::Response {
-            functions: std::collections::HashMap::new(),
-            diagnostics: vec![],
+            functions,
+            diagnostics: convert_diagnostics(&diagnostics),
         }))
     }
 
     async fn call_function(
         &self,
-        _request: Request<proto::call_function::Request>,
+        request: Request<proto::call_function::Request>,
     ) -> std::result::Result<Response<proto::call_function::Response>, Status> {
-        // TODO: Implement when Function trait is available
-        Err(Status::unimplemented("call_function not implemented"))
+        let ctx = self.request_context("CallFunction");
+        let provider = self.provider.read().await;
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_function_name", req.name.as_str());
+
+        let functions = provider.functions();
+        let factory = functions
+            .get(&req.name)
+            .ok_or_else(|| Status::not_found(format!("function '{}' not found", req.name)))?;
+        let function = factory();
+
+        let arguments = req
+            .arguments
+            .iter()
+            .map(convert_dynamic_value_from_proto)
+            .collect::<GrpcResult<Vec<_>>>()?;
+
+        let response = function
+            .call(
+                ctx,
+                crate::function::CallFunctionRequest {
+                    name: req.name,
+                    arguments,
+                },
+            )
+            .await;
+
+        Ok(Response::new(proto::call_function::Response {
+            result: response
+                .result
+                .as_ref()
+                .map(convert_dynamic_value_to_proto)
+                .transpose()?,
+            error: response.error.map(|error| proto::FunctionError {
+                text: error.text,
+                function_argument: error.function_argument,
+            }),
+        }))
     }
 
     async fn validate_ephemeral_resource_config(
         &self,
-        _request: Request<proto::validate_ephemeral_resource_config::Request>,
+        request: Request<proto::validate_ephemeral_resource_config::Request>,
     ) -> std::result::Result<Response<proto::validate_ephemeral_resource_config::Response>, Status>
     {
-        // TODO: Implement when EphemeralResource trait is available
-        Err(Status::unimplemented(
-            "validate_ephemeral_resource_config not implemented",
+        let ctx = self.request_context("ValidateEphemeralResourceConfig");
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_ephemeral_resource_type", req.type_name.as_str());
+        let ephemeral_resource = self.ephemeral_resource(&ctx, &req.type_name).await?;
+
+        let config = convert_dynamic_value_from_proto(
+            &req.config
+                .ok_or_else(|| Status::invalid_argument("config is required"))?,
+        )?;
+
+        let schema_response = ephemeral_resource
+            .schema(
+                ctx.clone(),
+                crate::ephemeral::EphemeralResourceSchemaRequest,
+            )
+            .await;
+        let mut diagnostics = validate_config(&schema_response.schema.block, &config);
+
+        let response = ephemeral_resource
+            .validate(
+                ctx,
+                crate::ephemeral::ValidateEphemeralResourceConfigRequest {
+                    type_name: req.type_name,
+                    config,
+                },
+            )
+            .await;
+        diagnostics.extend(response.diagnostics);
+
+        Ok(Response::new(
+            proto::validate_ephemeral_resource_config::Response {
+                diagnostics: convert_diagnostics(&diagnostics),
+            },
         ))
     }
 
     async fn open_ephemeral_resource(
         &self,
-        _request: Request<proto::open_ephemeral_resource::Request>,
+        request: Request<proto::open_ephemeral_resource::Request>,
     ) -> std::result::Result<Response<proto::open_ephemeral_resource::Response>, Status> {
-        // TODO: Implement when EphemeralResource trait is available
-        Err(Status::unimplemented(
-            "open_ephemeral_resource not implemented",
-        ))
+        let ctx = self.request_context("OpenEphemeralResource");
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_ephemeral_resource_type", req.type_name.as_str());
+        let ephemeral_resource = self.ephemeral_resource(&ctx, &req.type_name).await?;
+
+        let config = convert_dynamic_value_from_proto(
+            &req.config
+                .ok_or_else(|| Status::invalid_argument("config is required"))?,
+        )?;
+
+        let response = ephemeral_resource
+            .open(
+                ctx,
+                crate::ephemeral::OpenEphemeralResourceRequest {
+                    type_name: req.type_name,
+                    config,
+                    client_capabilities: convert_client_capabilities(&req.client_capabilities),
+                },
+            )
+            .await;
+
+        Ok(Response::new(proto::open_ephemeral_resource::Response {
+            diagnostics: convert_diagnostics(&response.diagnostics),
+            renew_at: response.renew_at.map(prost_types::Timestamp::from),
+            result: Some(convert_dynamic_value_to_proto(&response.result)?),
+            private: response.private,
+            deferred: response.deferred.as_ref().map(convert_deferred),
+        }))
     }
 
     async fn renew_ephemeral_resource(
         &self,
-        _request: Request<proto::renew_ephemeral_resource::Request>,
+        request: Request<proto::renew_ephemeral_resource::Request>,
     ) -> std::result::Result<Response<proto::renew_ephemeral_resource::Response>, Status> {
-        // TODO: Implement when EphemeralResource trait is available
-        Err(Status::unimplemented(
-            "renew_ephemeral_resource not implemented",
-        ))
+        let ctx = self.request_context("RenewEphemeralResource");
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_ephemeral_resource_type", req.type_name.as_str());
+        let ephemeral_resource = self.ephemeral_resource(&ctx, &req.type_name).await?;
+
+        let response = ephemeral_resource
+            .renew(
+                ctx,
+                crate::ephemeral::RenewEphemeralResourceRequest {
+                    type_name: req.type_name,
+                    private: req.private,
+                },
+            )
+            .await;
+
+        Ok(Response::new(proto::renew_ephemeral_resource::Response {
+            diagnostics: convert_diagnostics(&response.diagnostics),
+            renew_at: response.renew_at.map(prost_types::Timestamp::from),
+            private: response.private,
+        }))
     }
 
     async fn close_ephemeral_resource(
         &self,
-        _request: Request<proto::close_ephemeral_resource::Request>,
+        request: Request<proto::close_ephemeral_resource::Request>,
     ) -> std::result::Result<Response<proto::close_ephemeral_resource::Response>, Status> {
-        // TODO: Implement when EphemeralResource trait is available
-        Err(Status::unimplemented(
-            "close_ephemeral_resource not implemented",
-        ))
+        let ctx = self.request_context("CloseEphemeralResource");
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_ephemeral_resource_type", req.type_name.as_str());
+        let ephemeral_resource = self.ephemeral_resource(&ctx, &req.type_name).await?;
+
+        let response = ephemeral_resource
+            .close(
+                ctx,
+                crate::ephemeral::CloseEphemeralResourceRequest {
+                    type_name: req.type_name,
+                    private: req.private,
+                },
+            )
+            .await;
+
+        Ok(Response::new(proto::close_ephemeral_resource::Response {
+            diagnostics: convert_diagnostics(&response.diagnostics),
+        }))
     }
 
     async fn upgrade_resource_identity(
         &self,
-        _request: Request<proto::upgrade_resource_identity::Request>,
+        request: Request<proto::upgrade_resource_identity::Request>,
     ) -> std::result::Result<Response<proto::upgrade_resource_identity::Response>, Status> {
-        // TODO: Implement when ResourceWithUpgradeIdentity trait is available
-        Err(Status::unimplemented(
-            "upgrade_resource_identity not implemented",
-        ))
+        let ctx = self.request_context("UpgradeResourceIdentity");
+        let provider = self.provider.read().await;
+        let req = request.into_inner();
+        let ctx = ctx.with_log_field("tf_resource_type", req.type_name.as_str());
+
+        let resources = provider.resources();
+        let factory = resources.get(&req.type_name).ok_or_else(|| {
+            Status::not_found(format!("resource type '{}' not found", req.type_name))
+        })?;
+
+        let mut resource = factory();
+
+        let provider_data = self.provider_data.read().await.clone();
+        let _ = resource
+            .configure(
+                ctx.clone(),
+                crate::resource::ConfigureResourceRequest { provider_data },
+            )
+            .await;
+
+        let raw_identity = convert_raw_state_from_proto(&req.raw_identity.unwrap_or_default());
+
+        let response = if let Some(upgrader) = resource.as_upgrade_identity() {
+            upgrader
+                .upgrade_identity(
+                    ctx,
+                    crate::resource::UpgradeResourceIdentityRequest {
+                        type_name: req.type_name,
+                        version: req.version,
+                        raw_identity,
+                    },
+                )
+                .await
+        } else {
+            let identity = resource.as_identity().ok_or_else(|| {
+                Status::unimplemented(format!(
+                    "resource '{}' does not implement ResourceWithIdentity",
+                    req.type_name
+                ))
+            })?;
+            upgrade_identity_passthrough(
+                &req.type_name,
+                req.version,
+                identity.identity_schema().version,
+                &raw_identity,
+            )
+        };
+
+        Ok(Response::new(proto::upgrade_resource_identity::Response {
+            upgraded_identity: response
+                .upgraded_identity
+                .as_ref()
+                .map(convert_identity_to_proto)
+                .transpose()?,
+            diagnostics: convert_diagnostics(&response.diagnostics),
+        }))
     }
 
     async fn get_resource_identity_schemas(
         &self,
         _request: Request<proto::get_resource_identity_schemas::Request>,
     ) -> std::result::Result<Response<proto::get_resource_identity_schemas::Response>, Status> {
-        // TODO: Implement when ResourceWithIdentity trait is available
+        let provider = self.provider.read().await;
+
+        let mut identity_schemas = std::collections::HashMap::new();
+        for (name, factory) in provider.resources() {
+            let resource = factory();
+            if let Some(identity) = resource.as_identity() {
+                identity_schemas.insert(
+                    name.clone(),
+                    convert_identity_schema(&identity.identity_schema()),
+                );
+            }
+        }
+
         Ok(Response::new(
             proto::get_resource_identity_schemas::Response {
-                identity_schemas: std::collections::HashMap::new(),
+                identity_schemas,
                 diagnostics: vec![],
             },
         ))
@@ -887,6 +1251,105 @@ where
 
 // Conversion functions
 
+/// Stored identity of the current version used as is, for resources that
+/// don't implement ResourceWithUpgradeIdentity
+fn upgrade_identity_passthrough(
+    type_name: &str,
+    version: i64,
+    current_version: i64,
+    raw_identity: &crate::types::RawState,
+) -> crate::resource::UpgradeResourceIdentityResponse {
+    let mut diagnostics = vec![];
+    let mut upgraded_identity = None;
+
+    if version != current_version {
+        diagnostics.push(crate::types::Diagnostic::error(
+            "Unable to upgrade resource identity",
+            format!(
+                "resource '{}' identity version changed from {} to {} but ResourceWithUpgradeIdentity not implemented",
+                type_name, version, current_version
+            ),
+        ));
+    } else if let Some(json) = &raw_identity.json {
+        match DynamicValue::decode_json(json) {
+            Ok(identity_data) => {
+                upgraded_identity = Some(crate::types::ResourceIdentityData { identity_data })
+            }
+            Err(e) => diagnostics.push(crate::types::Diagnostic::error(
+                "Unable to read resource identity",
+                format!(
+                    "Stored identity of '{}' is not valid JSON: {}",
+                    type_name, e
+                ),
+            )),
+        }
+    }
+
+    crate::resource::UpgradeResourceIdentityResponse {
+        upgraded_identity,
+        diagnostics,
+    }
+}
+
+/// Identity to send with a response: the one the resource set, or else the
+/// one it derives from the new state
+#[allow(clippy::result_large_err)]
+fn response_identity(
+    resource: &dyn crate::resource::ResourceWithConfigure,
+    identity: Option<crate::types::ResourceIdentityData>,
+    state: Option<&DynamicValue>,
+) -> GrpcResult<Option<proto::ResourceIdentityData>> {
+    let identity = identity.or_else(|| {
+        let state = state.filter(|state| !state.is_null())?;
+        resource.as_identity()?.identity_from_state(state)
+    });
+    identity.as_ref().map(convert_identity_to_proto).transpose()
+}
+
+fn convert_identity_schema(
+    schema: &crate::types::ResourceIdentitySchema,
+) -> proto::ResourceIdentitySchema {
+    proto::ResourceIdentitySchema {
+        version: schema.version,
+        identity_attributes: schema
+            .identity_attributes
+            .iter()
+            .map(
+                |attribute| proto::resource_identity_schema::IdentityAttribute {
+                    name: attribute.name.clone(),
+                    r#type: attribute.type_.clone(),
+                    required_for_import: attribute.required_for_import,
+                    optional_for_import: attribute.optional_for_import,
+                    description: attribute.description.clone(),
+                },
+            )
+            .collect(),
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn convert_identity_from_proto(
+    identity: &proto::ResourceIdentityData,
+) -> GrpcResult<crate::types::ResourceIdentityData> {
+    Ok(crate::types::ResourceIdentityData {
+        identity_data: identity
+            .identity_data
+            .as_ref()
+            .map(convert_dynamic_value_from_proto)
+            .transpose()?
+            .unwrap_or_else(DynamicValue::null),
+    })
+}
+
+#[allow(clippy::result_large_err)]
+fn convert_identity_to_proto(
+    identity: &crate::types::ResourceIdentityData,
+) -> GrpcResult<proto::ResourceIdentityData> {
+    Ok(proto::ResourceIdentityData {
+        identity_data: Some(convert_dynamic_value_to_proto(&identity.identity_data)?),
+    })
+}
+
 fn convert_server_capabilities(
     caps: &crate::types::ServerCapabilities,
 ) -> proto::ServerCapabilities {
@@ -911,6 +1374,60 @@ fn convert_client_capabilities(
         })
 }
 
+/// Collects the definitions of all provider functions keyed by name
+async fn function_definitions<P: Provider>(
+    provider: &P,
+    ctx: &Context,
+) -> (
+    std::collections::HashMap<String, proto::Function>,
+    Vec<crate::types::Diagnostic>,
+) {
+    let mut functions = std::collections::HashMap::new();
+    let mut diagnostics = vec![];
+    for (name, factory) in provider.functions() {
+        let response = factory()
+            .definition(ctx.clone(), crate::function::FunctionDefinitionRequest)
+            .await;
+        diagnostics.extend(response.diagnostics);
+        functions.insert(name, convert_function(&response.definition));
+    }
+    (functions, diagnostics)
+}
+
+fn convert_function(definition: &crate::function::FunctionDefinition) -> proto::Function {
+    proto::Function {
+        parameters: definition
+            .parameters
+            .iter()
+            .map(convert_function_parameter)
+            .collect(),
+        variadic_parameter: definition
+            .variadic_parameter
+            .as_ref()
+            .map(convert_function_parameter),
+        r#return: Some(proto::function::Return {
+            r#type: definition.return_type.type_.clone(),
+        }),
+        summary: definition.summary.clone(),
+        description: definition.description.clone(),
+        description_kind: proto::StringKind::Plain as i32,
+        deprecation_message: definition.deprecation_message.clone().unwrap_or_default(),
+    }
+}
+
+fn convert_function_parameter(
+    parameter: &crate::function::Parameter,
+) -> proto::function::Parameter {
+    proto::function::Parameter {
+        name: parameter.name.clone(),
+        r#type: parameter.type_.clone(),
+        allow_null_value: parameter.allow_null_value,
+        allow_unknown_values: parameter.allow_unknown_values,
+        description: parameter.description.clone(),
+        description_kind: proto::StringKind::Plain as i32,
+    }
+}
+
 fn convert_schema(schema: &crate::schema::Schema) -> proto::Schema {
     proto::Schema {
         version: schema.version,
@@ -930,44 +1447,11 @@ fn convert_block(block: &crate::schema::Block) -> proto::schema::Block {
 }
 
 fn convert_attribute(attr: &crate::schema::Attribute) -> proto::schema::Attribute {
-    use crate::schema::AttributeType;
-
-    // Convert the attribute type to proto bytes
-    let type_bytes = match &attr.r#type {
-        AttributeType::String => b"\"string\"".to_vec(),
-        AttributeType::Number => b"\"number\"".to_vec(),
-        AttributeType::Bool => b"\"bool\"".to_vec(),
-        AttributeType::List(inner) => {
-            let inner_type = match inner.as_ref() {
-                AttributeType::String => "\"string\"",
-                AttributeType::Number => "\"number\"",
-                AttributeType::Bool => "\"bool\"",
-                _ => "\"dynamic\"", // For complex types
-            };
-            format!("[\"list\", {}]", inner_type).into_bytes()
-        }
-        AttributeType::Set(inner) => {
-            let inner_type = match inner.as_ref() {
-                AttributeType::String => "\"string\"",
-                AttributeType::Number => "\"number\"",
-                AttributeType::Bool => "\"bool\"",
-                _ => "\"dynamic\"", // For complex types
-            };
-            format!("[\"set\", {}]", inner_type).into_bytes()
-        }
-        AttributeType::Map(inner) => {
-            let inner_type = match inner.as_ref() {
-                AttributeType::String => "\"string\"",
-                AttributeType::Number => "\"number\"",
-                AttributeType::Bool => "\"bool\"",
-                _ => "\"dynamic\"", // For complex types
-            };
-            format!("[\"map\", {}]", inner_type).into_bytes()
-        }
-        AttributeType::Object(_) => {
-            // For objects, we'll use dynamic type for now
-            b"\"dynamic\"".to_vec()
-        }
+    // Nested attributes describe their type through nested_type only,
+    // Terraform rejects attributes that set both
+    let type_bytes = match attr.nested_type {
+        Some(_) => Vec::new(),
+        None => attr.r#type.type_json().to_string().into_bytes(),
     };
 
     proto::schema::Attribute {
@@ -1050,7 +1534,13 @@ fn convert_diagnostic(diag: &crate::types::Diagnostic) -> proto::Diagnostic {
         severity: convert_diagnostic_severity(diag.severity) as i32,
         summary: diag.summary.clone(),
         detail: diag.detail.clone(),
-        attribute: diag.attribute.as_ref().map(convert_attribute_path),
+        // An empty path means the diagnostic is about the whole resource,
+        // which Terraform expresses by leaving the attribute unset
+        attribute: diag
+            .attribute
+            .as_ref()
+            .filter(|path| !path.steps.is_empty())
+            .map(convert_attribute_path),
     }
 }
 
@@ -1127,7 +1617,6 @@ fn convert_dynamic_value_to_proto(val: &DynamicValue) -> GrpcResult<proto::Dynam
     })
 }
 
-#[allow(dead_code)]
 fn convert_raw_state_from_proto(proto_state: &proto::RawState) -> crate::types::RawState {
     crate::types::RawState {
         json: if proto_state.json.is_empty() {
@@ -2,7 +2,7 @@
 
 use crate::context::Context;
 use crate::resource::{ImportResourceStateRequest, ImportResourceStateResponse, ImportedResource};
-use crate::types::{AttributePath, Dynamic, DynamicValue};
+use crate::types::{AttributePath, Diagnostic, Dynamic, DynamicValue};
 use std::collections::HashMap;
 
 /// Sets the import ID to a specific attribute in state
@@ -21,15 +21,15 @@ pub fn import_state_passthrough_id(
     let mut state = DynamicValue::new(Dynamic::Map(HashMap::new()));
 
     if let Err(e) = state.set_string(&attr_path, request.id.clone()) {
-        response.diagnostics.push(crate::types::Diagnostic {
-            severity: crate::types::DiagnosticSeverity::Error,
-            summary: format!("Failed to set import ID: {}", e),
-            detail: format!(
-                "Could not set attribute '{:?}' to value '{}'",
-                attr_path, request.id
-            ),
-            attribute: Some(attr_path),
-        });
+        let detail = format!(
+            "Could not set attribute '{}' to value '{}'",
+            attr_path, request.id
+        );
+        response.diagnostics.push(Diagnostic::attribute_error(
+            attr_path,
+            format!("Failed to set import ID: {}", e),
+            detail,
+        ));
         return;
     }
 
@@ -62,43 +62,43 @@ pub fn import_state_passthrough_with_identity(
             Ok(value) => {
                 // Set the string value in state
                 if let Err(e) = state.set_string(&state_attr_path, value) {
-                    response.diagnostics.push(crate::types::Diagnostic {
-                        severity: crate::types::DiagnosticSeverity::Error,
-                        summary: format!("Failed to copy identity value: {}", e),
-                        detail: format!(
-                            "Could not copy from identity '{:?}' to state '{:?}'",
-                            identity_attr_path, state_attr_path
-                        ),
-                        attribute: Some(state_attr_path),
-                    });
+                    let detail = format!(
+                        "Could not copy from identity '{}' to state '{}'",
+                        identity_attr_path, state_attr_path
+                    );
+                    response.diagnostics.push(Diagnostic::attribute_error(
+                        state_attr_path,
+                        format!("Failed to copy identity value: {}", e),
+                        detail,
+                    ));
                     return;
                 }
             }
             Err(e) => {
-                response.diagnostics.push(crate::types::Diagnostic {
-                    severity: crate::types::DiagnosticSeverity::Error,
-                    summary: format!("Failed to read identity value: {}", e),
-                    detail: format!(
-                        "Could not read attribute '{:?}' from identity",
-                        identity_attr_path
-                    ),
-                    attribute: Some(identity_attr_path),
-                });
+                let detail = format!(
+                    "Could not read attribute '{}' from identity",
+                    identity_attr_path
+                );
+                response.diagnostics.push(Diagnostic::attribute_error(
+                    identity_attr_path,
+                    format!("Failed to read identity value: {}", e),
+                    detail,
+                ));
                 return;
             }
         }
     } else {
         // No identity provided, fall back to ID passthrough
         if let Err(e) = state.set_string(&state_attr_path, request.id.clone()) {
-            response.diagnostics.push(crate::types::Diagnostic {
-                severity: crate::types::DiagnosticSeverity::Error,
-                summary: format!("Failed to set import ID: {}", e),
-                detail: format!(
-                    "No identity provided, could not set attribute '{:?}' to ID '{}'",
-                    state_attr_path, request.id
-                ),
-                attribute: Some(state_attr_path),
-            });
+            let detail = format!(
+                "No identity provided, could not set attribute '{}' to ID '{}'",
+                state_attr_path, request.id
+            );
+            response.diagnostics.push(Diagnostic::attribute_error(
+                state_attr_path,
+                format!("Failed to set import ID: {}", e),
+                detail,
+            ));
             return;
         }
     }
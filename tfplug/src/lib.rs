@@ -3,6 +3,9 @@
 //! A framework for building Terraform providers in Rust, implementing the
 //! Terraform Plugin Protocol v6.9.
 
+// Lets code generated by tfplug-derive name the crate from inside it
+extern crate self as tfplug;
+
 // Core modules
 pub mod context;
 pub mod error;
@@ -19,7 +22,15 @@ pub mod resource;
 // Helper modules
 pub mod defaults;
 pub mod import;
+pub mod model;
+pub mod mux;
 pub mod plan_modifier;
+pub mod schema_json;
+pub mod state_move;
+pub mod testing;
+pub mod tflog;
+pub mod timeouts;
+pub mod upgrade;
 pub mod validator;
 
 // Framework implementation modules - to be implemented
@@ -35,7 +46,7 @@ pub use import::{import_state_passthrough_id, import_state_passthrough_with_iden
 pub use provider::{Provider, ProviderMetadataRequest, ProviderMetadataResponse};
 pub use resource::{Resource, ResourceWithConfigure, ResourceWithModifyPlan};
 pub use schema::{AttributeBuilder, AttributeType, Schema, SchemaBuilder};
-pub use server::{serve, serve_default, LogLevel, ServerConfig};
+pub use server::{serve, serve_debug, serve_default, LogLevel, ServerConfig, Transport};
 pub use types::{Dynamic, DynamicValue, PrivateStateData};
 
 // Convenience macro for main function
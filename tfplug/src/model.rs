@@ -0,0 +1,491 @@
+//! Typed models of schema blocks
+//!
+//! Instead of reading every attribute with `get_string(&AttributePath::new(..))`,
+//! a block can be described by a struct deriving TfSchema. The derive builds
+//! the schema block from the fields and converts between the struct and the
+//! Dynamic values in config, plan and state:
+//!
+//! ```ignore
+//! /// Startup and shutdown ordering
+//! #[derive(TfSchema)]
+//! struct Startup {
+//!     /// Start order
+//!     order: Option<f64>,
+//! }
+//!
+//! let startup = Startup::from_dynamic(&value)?;
+//! ```
+//!
+//! Field types implement TfValue for attributes and TfBlock for nested
+//! blocks. Null and unknown values read as None for Option fields and fail
+//! with NullValue or UnknownValue otherwise.
+
+use crate::error::{Result, TfplugError};
+use crate::schema::{AttributeType, Block, NestedBlock, NestingMode, Schema};
+use crate::types::{Dynamic, DynamicValue};
+use std::collections::HashMap;
+
+pub use tfplug_derive::TfSchema;
+
+/// A struct describing a schema block, usually derived
+pub trait TfSchema: Sized {
+    /// The block with an attribute or nested block per field
+    fn block() -> Block;
+
+    /// Reads the struct from an object value
+    fn from_dynamic(value: &Dynamic) -> Result<Self>;
+
+    /// The struct as an object value with every field set
+    fn to_dynamic(&self) -> Dynamic;
+
+    /// A version 0 schema with the block at its root
+    fn schema() -> Schema {
+        Schema {
+            version: 0,
+            block: Self::block(),
+        }
+    }
+
+    /// Reads the struct from config, plan or state
+    fn from_value(value: &DynamicValue) -> Result<Self> {
+        Self::from_dynamic(&value.value)
+    }
+
+    /// The struct as a config, plan or state value
+    fn to_value(&self) -> DynamicValue {
+        DynamicValue::new(self.to_dynamic())
+    }
+}
+
+/// A Rust type an attribute value converts to
+pub trait TfValue: Sized {
+    /// Whether null is a valid value, which makes the attribute optional
+    /// unless the field says otherwise
+    const OPTIONAL: bool = false;
+
+    /// The attribute type in the schema
+    fn attribute_type() -> AttributeType;
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self>;
+
+    fn to_dynamic(&self) -> Dynamic;
+}
+
+/// A field holding nested blocks
+pub trait TfBlock: Sized {
+    /// The nested block declaration for a field named `name`
+    fn nested_block(name: &str, description: &str) -> NestedBlock;
+
+    fn from_block(value: &Dynamic) -> Result<Self>;
+
+    fn to_block(&self) -> Dynamic;
+}
+
+/// The error for a value that isn't a known `expected`
+fn mismatch(expected: &str, value: &Dynamic) -> TfplugError {
+    match value {
+        Dynamic::Null => TfplugError::NullValue(String::new()),
+        Dynamic::Unknown => TfplugError::UnknownValue(String::new()),
+        other => TfplugError::TypeMismatch {
+            expected: expected.to_string(),
+            actual: match other {
+                Dynamic::Bool(_) => "bool",
+                Dynamic::Number(_) => "number",
+                Dynamic::String(_) => "string",
+                Dynamic::List(_) => "list",
+                _ => "object",
+            }
+            .to_string(),
+        },
+    }
+}
+
+/// Prefixes the path of an error with the attribute it came from
+fn at_attribute(name: &str, error: TfplugError) -> TfplugError {
+    let path = |inner: String| {
+        if inner.is_empty() || inner.starts_with('[') {
+            format!("{}{}", name, inner)
+        } else {
+            format!("{}.{}", name, inner)
+        }
+    };
+    match error {
+        TfplugError::NullValue(inner) => TfplugError::NullValue(path(inner)),
+        TfplugError::UnknownValue(inner) => TfplugError::UnknownValue(path(inner)),
+        TfplugError::AttributeNotFound(inner) => TfplugError::AttributeNotFound(path(inner)),
+        TfplugError::TypeMismatch { expected, actual } => TfplugError::TypeMismatch {
+            expected: format!("{} for {}", expected, name),
+            actual,
+        },
+        other => other,
+    }
+}
+
+/// The attributes of an object value. Used by the derive.
+pub fn object(value: &Dynamic) -> Result<&HashMap<String, Dynamic>> {
+    match value {
+        Dynamic::Map(object) => Ok(object),
+        other => Err(mismatch("object", other)),
+    }
+}
+
+/// Reads the attribute `name` of an object. A missing attribute reads like
+/// null. Used by the derive.
+pub fn field<T: TfValue>(object: &HashMap<String, Dynamic>, name: &str) -> Result<T> {
+    T::from_dynamic(object.get(name).unwrap_or(&Dynamic::Null)).map_err(|e| at_attribute(name, e))
+}
+
+/// Reads the nested blocks `name` of an object. Used by the derive.
+pub fn block_field<T: TfBlock>(object: &HashMap<String, Dynamic>, name: &str) -> Result<T> {
+    T::from_block(object.get(name).unwrap_or(&Dynamic::Null)).map_err(|e| at_attribute(name, e))
+}
+
+impl TfValue for String {
+    fn attribute_type() -> AttributeType {
+        AttributeType::String
+    }
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self> {
+        match value {
+            Dynamic::String(s) => Ok(s.clone()),
+            other => Err(mismatch("string", other)),
+        }
+    }
+
+    fn to_dynamic(&self) -> Dynamic {
+        Dynamic::String(self.clone())
+    }
+}
+
+impl TfValue for bool {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Bool
+    }
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self> {
+        match value {
+            Dynamic::Bool(b) => Ok(*b),
+            other => Err(mismatch("bool", other)),
+        }
+    }
+
+    fn to_dynamic(&self) -> Dynamic {
+        Dynamic::Bool(*self)
+    }
+}
+
+impl TfValue for f64 {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Number
+    }
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self> {
+        match value {
+            Dynamic::Number(n) => Ok(*n),
+            other => Err(mismatch("number", other)),
+        }
+    }
+
+    fn to_dynamic(&self) -> Dynamic {
+        Dynamic::Number(*self)
+    }
+}
+
+/// Whole numbers reject fractional values instead of truncating them, like
+/// DynamicValue::get_i64
+macro_rules! whole_number {
+    ($($ty:ty => $name:literal),*) => {
+        $(
+            impl TfValue for $ty {
+                fn attribute_type() -> AttributeType {
+                    AttributeType::Number
+                }
+
+                fn from_dynamic(value: &Dynamic) -> Result<Self> {
+                    let n = f64::from_dynamic(value)?;
+                    if n.fract() != 0.0 || n < <$ty>::MIN as f64 || n > <$ty>::MAX as f64 {
+                        return Err(TfplugError::TypeMismatch {
+                            expected: $name.to_string(),
+                            actual: n.to_string(),
+                        });
+                    }
+                    Ok(n as $ty)
+                }
+
+                fn to_dynamic(&self) -> Dynamic {
+                    Dynamic::Number(*self as f64)
+                }
+            }
+        )*
+    };
+}
+
+whole_number!(
+    i64 => "integer",
+    u32 => "unsigned 32-bit integer",
+    u64 => "unsigned integer"
+);
+
+impl<T: TfValue> TfValue for Option<T> {
+    const OPTIONAL: bool = true;
+
+    fn attribute_type() -> AttributeType {
+        T::attribute_type()
+    }
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self> {
+        match value {
+            Dynamic::Null | Dynamic::Unknown => Ok(None),
+            value => T::from_dynamic(value).map(Some),
+        }
+    }
+
+    fn to_dynamic(&self) -> Dynamic {
+        self.as_ref().map_or(Dynamic::Null, T::to_dynamic)
+    }
+}
+
+impl<T: TfValue> TfValue for Vec<T> {
+    fn attribute_type() -> AttributeType {
+        AttributeType::List(Box::new(T::attribute_type()))
+    }
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self> {
+        match value {
+            Dynamic::List(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    T::from_dynamic(item).map_err(|e| at_attribute(&format!("[{}]", index), e))
+                })
+                .collect(),
+            other => Err(mismatch("list", other)),
+        }
+    }
+
+    fn to_dynamic(&self) -> Dynamic {
+        Dynamic::List(self.iter().map(T::to_dynamic).collect())
+    }
+}
+
+impl<T: TfValue> TfValue for HashMap<String, T> {
+    fn attribute_type() -> AttributeType {
+        AttributeType::Map(Box::new(T::attribute_type()))
+    }
+
+    fn from_dynamic(value: &Dynamic) -> Result<Self> {
+        object(value)?
+            .iter()
+            .map(|(key, item)| {
+                T::from_dynamic(item)
+                    .map(|item| (key.clone(), item))
+                    .map_err(|e| at_attribute(&format!("[\"{}\"]", key), e))
+            })
+            .collect()
+    }
+
+    fn to_dynamic(&self) -> Dynamic {
+        Dynamic::Map(
+            self.iter()
+                .map(|(key, item)| (key.clone(), item.to_dynamic()))
+                .collect(),
+        )
+    }
+}
+
+fn list_block<T: TfSchema>(name: &str, description: &str, max_items: i64) -> NestedBlock {
+    let mut block = T::block();
+    if !description.is_empty() {
+        block.description = description.to_string();
+    }
+    NestedBlock {
+        type_name: name.to_string(),
+        block,
+        nesting: NestingMode::List,
+        min_items: 0,
+        max_items,
+    }
+}
+
+/// Blocks read from a block list, which is null or unknown before they are
+/// known
+fn blocks<T: TfSchema>(value: &Dynamic) -> Result<Vec<T>> {
+    match value {
+        Dynamic::Null | Dynamic::Unknown => Ok(vec![]),
+        Dynamic::List(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                T::from_dynamic(item).map_err(|e| at_attribute(&format!("[{}]", index), e))
+            })
+            .collect(),
+        other => Err(mismatch("list of blocks", other)),
+    }
+}
+
+/// At most one block
+impl<T: TfSchema> TfBlock for Option<T> {
+    fn nested_block(name: &str, description: &str) -> NestedBlock {
+        list_block::<T>(name, description, 1)
+    }
+
+    fn from_block(value: &Dynamic) -> Result<Self> {
+        Ok(blocks(value)?.into_iter().next())
+    }
+
+    fn to_block(&self) -> Dynamic {
+        Dynamic::List(self.iter().map(T::to_dynamic).collect())
+    }
+}
+
+/// Any number of blocks
+impl<T: TfSchema> TfBlock for Vec<T> {
+    fn nested_block(name: &str, description: &str) -> NestedBlock {
+        list_block::<T>(name, description, 0)
+    }
+
+    fn from_block(value: &Dynamic) -> Result<Self> {
+        blocks(value)
+    }
+
+    fn to_block(&self) -> Dynamic {
+        Dynamic::List(self.iter().map(T::to_dynamic).collect())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::types::AttributePath;
+
+    /// A network card
+    #[derive(Debug, PartialEq, TfSchema)]
+    struct Network {
+        /// Bridge to attach to
+        bridge: String,
+        /// VLAN tag
+        tag: Option<u32>,
+        #[tfplug(optional, computed, rename = "macaddr")]
+        mac: Option<String>,
+    }
+
+    /// A virtual machine
+    #[derive(Debug, PartialEq, TfSchema)]
+    struct Vm {
+        name: String,
+        #[tfplug(sensitive)]
+        password: Option<String>,
+        tags: Vec<String>,
+        /// Network cards
+        #[tfplug(block)]
+        network: Vec<Network>,
+        #[tfplug(block)]
+        startup: Option<Startup>,
+    }
+
+    /// Startup ordering
+    #[derive(Debug, PartialEq, TfSchema)]
+    struct Startup {
+        order: Option<i64>,
+    }
+
+    #[test]
+    fn derive_builds_the_schema_block() {
+        let block = Vm::block();
+        assert_eq!(block.description, "A virtual machine");
+
+        let attribute = |name: &str| {
+            block
+                .attributes
+                .iter()
+                .find(|attribute| attribute.name == name)
+                .unwrap()
+        };
+        assert!(attribute("name").required);
+        assert!(attribute("password").optional && attribute("password").sensitive);
+        assert_eq!(
+            attribute("tags").r#type,
+            AttributeType::List(Box::new(AttributeType::String))
+        );
+
+        let network = &block.block_types[0];
+        assert_eq!(network.type_name, "network");
+        assert_eq!(network.max_items, 0);
+        assert_eq!(network.block.description, "Network cards");
+        let mac = &network.block.attributes[2];
+        assert_eq!(mac.name, "macaddr");
+        assert!(mac.optional && mac.computed);
+        assert_eq!(
+            network.block.attributes[0].description,
+            "Bridge to attach to"
+        );
+        assert_eq!(block.block_types[1].max_items, 1);
+    }
+
+    #[test]
+    fn derive_converts_to_and_from_dynamic() {
+        let value = DynamicValue::decode_json(
+            br#"{"name": "web", "password": null, "tags": ["a"],
+                 "network": [{"bridge": "vmbr0", "tag": 20, "macaddr": "__unknown__"}],
+                 "startup": []}"#,
+        )
+        .unwrap();
+
+        let vm = Vm::from_value(&value).unwrap();
+        assert_eq!(
+            vm,
+            Vm {
+                name: "web".to_string(),
+                password: None,
+                tags: vec!["a".to_string()],
+                network: vec![Network {
+                    bridge: "vmbr0".to_string(),
+                    tag: Some(20),
+                    mac: None,
+                }],
+                startup: None,
+            }
+        );
+
+        let value = vm.to_value();
+        assert_eq!(
+            value
+                .get_string(&AttributePath::new("network").index(0).attribute("bridge"))
+                .unwrap(),
+            "vmbr0"
+        );
+        assert!(value.is_null_at(&AttributePath::new("network").index(0).attribute("macaddr")));
+        assert_eq!(
+            value.get_list(&AttributePath::new("startup")).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn derive_reports_the_failing_attribute() {
+        let missing = DynamicValue::decode_json(br#"{"tags": []}"#).unwrap();
+        assert!(matches!(
+            Vm::from_value(&missing),
+            Err(TfplugError::NullValue(path)) if path == "name"
+        ));
+
+        let fractional = DynamicValue::decode_json(
+            br#"{"name": "web", "tags": [], "network": [{"bridge": "vmbr0", "tag": 1.5}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Vm::from_value(&fractional),
+            Err(TfplugError::TypeMismatch { .. })
+        ));
+
+        let unknown = DynamicValue::decode_json(
+            br#"{"name": "web", "tags": [], "network": [{"bridge": "__unknown__"}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Vm::from_value(&unknown),
+            Err(TfplugError::UnknownValue(path)) if path == "network[0].bridge"
+        ));
+    }
+}
@@ -0,0 +1,923 @@
+//! Provider multiplexing
+//!
+//! MuxProvider serves several providers from one plugin binary as if they
+//! were a single provider. Resources, data sources, functions and ephemeral
+//! resources are routed by type name to the provider that declares them.
+//!
+//! Every provider is configured from the same provider block, whose schema
+//! is the union of their schemas. Each keeps its own provider data: the
+//! resources of a provider are configured with what that provider returned
+//! from configure, never with another provider's data.
+//!
+//! ```ignore
+//! let provider = MuxProvider::new("proxmox")
+//!     .with(ClusterProvider::new())
+//!     .with(NodeProvider::new());
+//! serve_default(provider).await
+//! ```
+
+use crate::context::Context;
+use crate::data_source::{
+    ConfigureDataSourceRequest, ConfigureDataSourceResponse, DataSource, DataSourceMetadataRequest,
+    DataSourceMetadataResponse, DataSourceSchemaRequest, DataSourceSchemaResponse,
+    DataSourceWithConfigure, ReadDataSourceRequest, ReadDataSourceResponse,
+    ValidateDataSourceConfigRequest, ValidateDataSourceConfigResponse,
+};
+use crate::ephemeral::{
+    CloseEphemeralResourceRequest, CloseEphemeralResourceResponse,
+    ConfigureEphemeralResourceRequest, ConfigureEphemeralResourceResponse, EphemeralResource,
+    EphemeralResourceMetadataRequest, EphemeralResourceMetadataResponse,
+    EphemeralResourceSchemaRequest, EphemeralResourceSchemaResponse,
+    EphemeralResourceWithConfigure, OpenEphemeralResourceRequest, OpenEphemeralResourceResponse,
+    RenewEphemeralResourceRequest, RenewEphemeralResourceResponse,
+    ValidateEphemeralResourceConfigRequest, ValidateEphemeralResourceConfigResponse,
+};
+use crate::provider::{
+    ConfigureProviderRequest, ConfigureProviderResponse, DataSourceFactory,
+    EphemeralResourceFactory, FunctionFactory, Provider, ProviderMetaSchemaRequest,
+    ProviderMetaSchemaResponse, ProviderMetadataRequest, ProviderMetadataResponse,
+    ProviderSchemaRequest, ProviderSchemaResponse, ResourceFactory, StopProviderRequest,
+    StopProviderResponse, ValidateProviderConfigRequest, ValidateProviderConfigResponse,
+};
+use crate::resource::{
+    ConfigureResourceRequest, ConfigureResourceResponse, CreateResourceRequest,
+    CreateResourceResponse, DeleteResourceRequest, DeleteResourceResponse, ReadResourceRequest,
+    ReadResourceResponse, Resource, ResourceMetadataRequest, ResourceMetadataResponse,
+    ResourceSchemaRequest, ResourceSchemaResponse, ResourceWithConfigValidators,
+    ResourceWithConfigure, ResourceWithIdentity, ResourceWithImportState, ResourceWithModifyPlan,
+    ResourceWithMoveState, ResourceWithUpgradeIdentity, ResourceWithUpgradeState,
+    UpdateResourceRequest, UpdateResourceResponse, ValidateResourceConfigRequest,
+    ValidateResourceConfigResponse,
+};
+use crate::schema::{Schema, SchemaBuilder};
+use crate::types::{Diagnostic, ServerCapabilities};
+use async_trait::async_trait;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type ProviderData = Option<Arc<dyn Any + Send + Sync>>;
+
+/// Several providers served as one
+pub struct MuxProvider {
+    type_name: String,
+    providers: Vec<Box<dyn Provider>>,
+    /// What each provider returned from configure, by position
+    provider_data: Vec<ProviderData>,
+}
+
+impl MuxProvider {
+    /// A mux served under `type_name`, which is also the name functions are
+    /// called under: `provider::<type_name>::<function>`
+    pub fn new(type_name: &str) -> Self {
+        Self {
+            type_name: type_name.to_string(),
+            providers: vec![],
+            provider_data: vec![],
+        }
+    }
+
+    /// Adds a provider. When two providers declare the same type name, the
+    /// one added first serves it and GetProviderSchema reports an error.
+    pub fn with<P: Provider + 'static>(mut self, provider: P) -> Self {
+        self.providers.push(Box::new(provider));
+        self.provider_data.push(None);
+        self
+    }
+
+    /// Type names declared by more than one provider
+    fn duplicate_type_names(&self) -> Vec<String> {
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for provider in &self.providers {
+            let names = provider
+                .resources()
+                .into_keys()
+                .chain(
+                    provider
+                        .data_sources()
+                        .into_keys()
+                        .map(|n| format!("data.{}", n)),
+                )
+                .chain(
+                    provider
+                        .functions()
+                        .into_keys()
+                        .map(|n| format!("function.{}", n)),
+                )
+                .chain(
+                    provider
+                        .ephemeral_resources()
+                        .into_keys()
+                        .map(|n| format!("ephemeral.{}", n)),
+                );
+            for name in names {
+                *seen.entry(name).or_default() += 1;
+            }
+        }
+        let mut duplicates: Vec<String> = seen
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(name, _)| name)
+            .collect();
+        duplicates.sort();
+        duplicates
+    }
+
+    /// Each factory of each provider, keyed by type name and paired with the
+    /// data of the provider that declared it. The first provider wins.
+    fn routed<F>(
+        &self,
+        factories: impl Fn(&dyn Provider) -> HashMap<String, F>,
+    ) -> HashMap<String, (F, ProviderData)> {
+        let mut routed = HashMap::new();
+        for (provider, data) in self.providers.iter().zip(&self.provider_data) {
+            for (name, factory) in factories(provider.as_ref()) {
+                routed.entry(name).or_insert((factory, data.clone()));
+            }
+        }
+        routed
+    }
+}
+
+/// Adds the attributes and blocks of `schema` that `merged` doesn't have
+/// yet. A name declared with a different type is reported.
+fn merge_schema(merged: &mut Schema, schema: Schema, diagnostics: &mut Vec<Diagnostic>) {
+    for attribute in schema.block.attributes {
+        match merged
+            .block
+            .attributes
+            .iter()
+            .find(|a| a.name == attribute.name)
+        {
+            Some(existing) if existing.r#type != attribute.r#type => {
+                diagnostics.push(Diagnostic::error(
+                    "Conflicting provider schemas",
+                    format!(
+                        "Provider attribute '{}' is declared as {:?} and as {:?}",
+                        attribute.name, existing.r#type, attribute.r#type
+                    ),
+                ));
+            }
+            Some(_) => {}
+            None => merged.block.attributes.push(attribute),
+        }
+    }
+    for nested in schema.block.block_types {
+        if !merged
+            .block
+            .block_types
+            .iter()
+            .any(|b| b.type_name == nested.type_name)
+        {
+            merged.block.block_types.push(nested);
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for MuxProvider {
+    fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    async fn metadata(
+        &self,
+        ctx: Context,
+        _request: ProviderMetadataRequest,
+    ) -> ProviderMetadataResponse {
+        let mut capabilities = ServerCapabilities {
+            plan_destroy: false,
+            get_provider_schema_optional: !self.providers.is_empty(),
+            move_resource_state: false,
+        };
+        for provider in &self.providers {
+            let response = provider
+                .metadata(ctx.clone(), ProviderMetadataRequest)
+                .await;
+            let caps = response.server_capabilities;
+            capabilities.plan_destroy |= caps.plan_destroy;
+            capabilities.get_provider_schema_optional &= caps.get_provider_schema_optional;
+            capabilities.move_resource_state |= caps.move_resource_state;
+        }
+
+        ProviderMetadataResponse {
+            type_name: self.type_name.clone(),
+            server_capabilities: capabilities,
+        }
+    }
+
+    async fn schema(
+        &self,
+        ctx: Context,
+        _request: ProviderSchemaRequest,
+    ) -> ProviderSchemaResponse {
+        let mut schema = SchemaBuilder::new().build();
+        let mut diagnostics = vec![];
+
+        for provider in &self.providers {
+            let response = provider.schema(ctx.clone(), ProviderSchemaRequest).await;
+            diagnostics.extend(response.diagnostics);
+            merge_schema(&mut schema, response.schema, &mut diagnostics);
+        }
+        for name in self.duplicate_type_names() {
+            diagnostics.push(Diagnostic::error(
+                "Duplicate type name",
+                format!("'{}' is declared by more than one provider", name),
+            ));
+        }
+
+        ProviderSchemaResponse {
+            schema,
+            diagnostics,
+        }
+    }
+
+    async fn meta_schema(
+        &self,
+        ctx: Context,
+        _request: ProviderMetaSchemaRequest,
+    ) -> ProviderMetaSchemaResponse {
+        let mut merged: Option<Schema> = None;
+        let mut diagnostics = vec![];
+
+        for provider in &self.providers {
+            let response = provider
+                .meta_schema(ctx.clone(), ProviderMetaSchemaRequest)
+                .await;
+            diagnostics.extend(response.diagnostics);
+            match (&mut merged, response.schema) {
+                (Some(merged), Some(schema)) => merge_schema(merged, schema, &mut diagnostics),
+                (None, schema) => merged = schema,
+                (Some(_), None) => {}
+            }
+        }
+
+        ProviderMetaSchemaResponse {
+            schema: merged,
+            diagnostics,
+        }
+    }
+
+    async fn configure(
+        &mut self,
+        ctx: Context,
+        request: ConfigureProviderRequest,
+    ) -> ConfigureProviderResponse {
+        let mut diagnostics = vec![];
+
+        for (provider, data) in self.providers.iter_mut().zip(&mut self.provider_data) {
+            let response = provider
+                .configure(
+                    ctx.clone(),
+                    ConfigureProviderRequest {
+                        terraform_version: request.terraform_version.clone(),
+                        config: request.config.clone(),
+                        client_capabilities: request.client_capabilities.clone(),
+                    },
+                )
+                .await;
+            diagnostics.extend(response.diagnostics);
+            *data = response.provider_data;
+        }
+
+        // Resources get their own provider's data from the routed factories
+        ConfigureProviderResponse {
+            diagnostics,
+            provider_data: None,
+        }
+    }
+
+    async fn validate(
+        &self,
+        ctx: Context,
+        request: ValidateProviderConfigRequest,
+    ) -> ValidateProviderConfigResponse {
+        let mut diagnostics = vec![];
+
+        for provider in &self.providers {
+            let response = provider
+                .validate(
+                    ctx.clone(),
+                    ValidateProviderConfigRequest {
+                        config: request.config.clone(),
+                        client_capabilities: request.client_capabilities.clone(),
+                    },
+                )
+                .await;
+            diagnostics.extend(response.diagnostics);
+        }
+
+        ValidateProviderConfigResponse { diagnostics }
+    }
+
+    async fn stop(&self, ctx: Context, _request: StopProviderRequest) -> StopProviderResponse {
+        let mut errors = vec![];
+
+        for provider in &self.providers {
+            if let Some(error) = provider.stop(ctx.clone(), StopProviderRequest).await.error {
+                errors.push(error);
+            }
+        }
+
+        StopProviderResponse {
+            error: (!errors.is_empty()).then(|| errors.join("; ")),
+        }
+    }
+
+    fn resources(&self) -> HashMap<String, ResourceFactory> {
+        self.routed(|provider| provider.resources())
+            .into_iter()
+            .map(|(name, (factory, provider_data))| {
+                let factory: ResourceFactory = Box::new(move || {
+                    Box::new(RoutedResource {
+                        inner: factory(),
+                        provider_data: provider_data.clone(),
+                    })
+                });
+                (name, factory)
+            })
+            .collect()
+    }
+
+    fn data_sources(&self) -> HashMap<String, DataSourceFactory> {
+        self.routed(|provider| provider.data_sources())
+            .into_iter()
+            .map(|(name, (factory, provider_data))| {
+                let factory: DataSourceFactory = Box::new(move || {
+                    Box::new(RoutedDataSource {
+                        inner: factory(),
+                        provider_data: provider_data.clone(),
+                    })
+                });
+                (name, factory)
+            })
+            .collect()
+    }
+
+    fn functions(&self) -> HashMap<String, FunctionFactory> {
+        self.routed(|provider| provider.functions())
+            .into_iter()
+            .map(|(name, (factory, _))| (name, factory))
+            .collect()
+    }
+
+    fn ephemeral_resources(&self) -> HashMap<String, EphemeralResourceFactory> {
+        self.routed(|provider| provider.ephemeral_resources())
+            .into_iter()
+            .map(|(name, (factory, provider_data))| {
+                let factory: EphemeralResourceFactory = Box::new(move || {
+                    Box::new(RoutedEphemeralResource {
+                        inner: factory(),
+                        provider_data: provider_data.clone(),
+                    })
+                });
+                (name, factory)
+            })
+            .collect()
+    }
+}
+
+/// A resource configured with the data of the provider that declared it
+struct RoutedResource {
+    inner: Box<dyn ResourceWithConfigure>,
+    provider_data: ProviderData,
+}
+
+#[async_trait]
+impl Resource for RoutedResource {
+    fn type_name(&self) -> &str {
+        self.inner.type_name()
+    }
+
+    async fn metadata(
+        &self,
+        ctx: Context,
+        request: ResourceMetadataRequest,
+    ) -> ResourceMetadataResponse {
+        self.inner.metadata(ctx, request).await
+    }
+
+    async fn schema(&self, ctx: Context, request: ResourceSchemaRequest) -> ResourceSchemaResponse {
+        self.inner.schema(ctx, request).await
+    }
+
+    async fn validate(
+        &self,
+        ctx: Context,
+        request: ValidateResourceConfigRequest,
+    ) -> ValidateResourceConfigResponse {
+        self.inner.validate(ctx, request).await
+    }
+
+    async fn create(&self, ctx: Context, request: CreateResourceRequest) -> CreateResourceResponse {
+        self.inner.create(ctx, request).await
+    }
+
+    async fn read(&self, ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+        self.inner.read(ctx, request).await
+    }
+
+    async fn update(&self, ctx: Context, request: UpdateResourceRequest) -> UpdateResourceResponse {
+        self.inner.update(ctx, request).await
+    }
+
+    async fn delete(&self, ctx: Context, request: DeleteResourceRequest) -> DeleteResourceResponse {
+        self.inner.delete(ctx, request).await
+    }
+}
+
+#[async_trait]
+impl ResourceWithConfigure for RoutedResource {
+    async fn configure(
+        &mut self,
+        ctx: Context,
+        _request: ConfigureResourceRequest,
+    ) -> ConfigureResourceResponse {
+        let provider_data = self.provider_data.clone();
+        self.inner
+            .configure(ctx, ConfigureResourceRequest { provider_data })
+            .await
+    }
+
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        self.inner.as_modify_plan()
+    }
+
+    fn as_upgrade_state(&self) -> Option<&dyn ResourceWithUpgradeState> {
+        self.inner.as_upgrade_state()
+    }
+
+    fn as_move_state(&self) -> Option<&dyn ResourceWithMoveState> {
+        self.inner.as_move_state()
+    }
+
+    fn as_config_validators(&self) -> Option<&dyn ResourceWithConfigValidators> {
+        self.inner.as_config_validators()
+    }
+
+    fn as_import_state(&self) -> Option<&dyn ResourceWithImportState> {
+        self.inner.as_import_state()
+    }
+
+    fn as_identity(&self) -> Option<&dyn ResourceWithIdentity> {
+        self.inner.as_identity()
+    }
+
+    fn as_upgrade_identity(&self) -> Option<&dyn ResourceWithUpgradeIdentity> {
+        self.inner.as_upgrade_identity()
+    }
+}
+
+/// A data source configured with the data of the provider that declared it
+struct RoutedDataSource {
+    inner: Box<dyn DataSourceWithConfigure>,
+    provider_data: ProviderData,
+}
+
+#[async_trait]
+impl DataSource for RoutedDataSource {
+    fn type_name(&self) -> &str {
+        self.inner.type_name()
+    }
+
+    async fn metadata(
+        &self,
+        ctx: Context,
+        request: DataSourceMetadataRequest,
+    ) -> DataSourceMetadataResponse {
+        self.inner.metadata(ctx, request).await
+    }
+
+    async fn schema(
+        &self,
+        ctx: Context,
+        request: DataSourceSchemaRequest,
+    ) -> DataSourceSchemaResponse {
+        self.inner.schema(ctx, request).await
+    }
+
+    async fn validate(
+        &self,
+        ctx: Context,
+        request: ValidateDataSourceConfigRequest,
+    ) -> ValidateDataSourceConfigResponse {
+        self.inner.validate(ctx, request).await
+    }
+
+    async fn read(&self, ctx: Context, request: ReadDataSourceRequest) -> ReadDataSourceResponse {
+        self.inner.read(ctx, request).await
+    }
+}
+
+#[async_trait]
+impl DataSourceWithConfigure for RoutedDataSource {
+    async fn configure(
+        &mut self,
+        ctx: Context,
+        _request: ConfigureDataSourceRequest,
+    ) -> ConfigureDataSourceResponse {
+        let provider_data = self.provider_data.clone();
+        self.inner
+            .configure(ctx, ConfigureDataSourceRequest { provider_data })
+            .await
+    }
+}
+
+/// An ephemeral resource configured with the data of the provider that
+/// declared it
+struct RoutedEphemeralResource {
+    inner: Box<dyn EphemeralResourceWithConfigure>,
+    provider_data: ProviderData,
+}
+
+#[async_trait]
+impl EphemeralResource for RoutedEphemeralResource {
+    async fn metadata(
+        &self,
+        ctx: Context,
+        request: EphemeralResourceMetadataRequest,
+    ) -> EphemeralResourceMetadataResponse {
+        self.inner.metadata(ctx, request).await
+    }
+
+    async fn schema(
+        &self,
+        ctx: Context,
+        request: EphemeralResourceSchemaRequest,
+    ) -> EphemeralResourceSchemaResponse {
+        self.inner.schema(ctx, request).await
+    }
+
+    async fn validate(
+        &self,
+        ctx: Context,
+        request: ValidateEphemeralResourceConfigRequest,
+    ) -> ValidateEphemeralResourceConfigResponse {
+        self.inner.validate(ctx, request).await
+    }
+
+    async fn open(
+        &self,
+        ctx: Context,
+        request: OpenEphemeralResourceRequest,
+    ) -> OpenEphemeralResourceResponse {
+        self.inner.open(ctx, request).await
+    }
+
+    async fn renew(
+        &self,
+        ctx: Context,
+        request: RenewEphemeralResourceRequest,
+    ) -> RenewEphemeralResourceResponse {
+        self.inner.renew(ctx, request).await
+    }
+
+    async fn close(
+        &self,
+        ctx: Context,
+        request: CloseEphemeralResourceRequest,
+    ) -> CloseEphemeralResourceResponse {
+        self.inner.close(ctx, request).await
+    }
+}
+
+#[async_trait]
+impl EphemeralResourceWithConfigure for RoutedEphemeralResource {
+    async fn configure(
+        &mut self,
+        ctx: Context,
+        _request: ConfigureEphemeralResourceRequest,
+    ) -> ConfigureEphemeralResourceResponse {
+        let provider_data = self.provider_data.clone();
+        self.inner
+            .configure(ctx, ConfigureEphemeralResourceRequest { provider_data })
+            .await
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::schema::{AttributeBuilder, AttributeType};
+    use crate::types::{AttributePath, ClientCapabilities, DynamicValue};
+
+    /// Provider with one provider attribute and one resource, whose
+    /// provider data is its own name
+    struct NamedProvider {
+        name: &'static str,
+        resource: &'static str,
+    }
+
+    /// Resource that writes the provider data it was configured with to
+    /// the `provider` attribute
+    struct EchoResource {
+        type_name: String,
+        provider_data: ProviderData,
+    }
+
+    #[async_trait]
+    impl Resource for EchoResource {
+        fn type_name(&self) -> &str {
+            &self.type_name
+        }
+
+        async fn metadata(
+            &self,
+            _ctx: Context,
+            _request: ResourceMetadataRequest,
+        ) -> ResourceMetadataResponse {
+            ResourceMetadataResponse {
+                type_name: self.type_name.clone(),
+            }
+        }
+
+        async fn schema(
+            &self,
+            _ctx: Context,
+            _request: ResourceSchemaRequest,
+        ) -> ResourceSchemaResponse {
+            ResourceSchemaResponse {
+                schema: SchemaBuilder::new().build(),
+                diagnostics: vec![],
+            }
+        }
+
+        async fn validate(
+            &self,
+            _ctx: Context,
+            _request: ValidateResourceConfigRequest,
+        ) -> ValidateResourceConfigResponse {
+            ValidateResourceConfigResponse {
+                diagnostics: vec![],
+            }
+        }
+
+        async fn create(
+            &self,
+            _ctx: Context,
+            request: CreateResourceRequest,
+        ) -> CreateResourceResponse {
+            let mut new_state = request.planned_state;
+            let provider = self
+                .provider_data
+                .as_ref()
+                .and_then(|data| data.downcast_ref::<String>())
+                .cloned()
+                .unwrap_or_default();
+            new_state
+                .set_string(&AttributePath::new("provider"), provider)
+                .unwrap();
+            CreateResourceResponse {
+                new_state,
+                private: vec![],
+                diagnostics: vec![],
+            }
+        }
+
+        async fn read(&self, _ctx: Context, request: ReadResourceRequest) -> ReadResourceResponse {
+            ReadResourceResponse {
+                new_state: Some(request.current_state),
+                diagnostics: vec![],
+                private: request.private,
+                deferred: None,
+                new_identity: None,
+            }
+        }
+
+        async fn update(
+            &self,
+            _ctx: Context,
+            request: UpdateResourceRequest,
+        ) -> UpdateResourceResponse {
+            UpdateResourceResponse {
+                new_state: request.planned_state,
+                private: vec![],
+                diagnostics: vec![],
+                new_identity: None,
+            }
+        }
+
+        async fn delete(
+            &self,
+            _ctx: Context,
+            _request: DeleteResourceRequest,
+        ) -> DeleteResourceResponse {
+            DeleteResourceResponse {
+                diagnostics: vec![],
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ResourceWithConfigure for EchoResource {
+        async fn configure(
+            &mut self,
+            _ctx: Context,
+            request: ConfigureResourceRequest,
+        ) -> ConfigureResourceResponse {
+            self.provider_data = request.provider_data;
+            ConfigureResourceResponse {
+                diagnostics: vec![],
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for NamedProvider {
+        fn type_name(&self) -> &str {
+            self.name
+        }
+
+        async fn metadata(
+            &self,
+            _ctx: Context,
+            _request: ProviderMetadataRequest,
+        ) -> ProviderMetadataResponse {
+            ProviderMetadataResponse {
+                type_name: self.name.to_string(),
+                server_capabilities: ServerCapabilities {
+                    plan_destroy: false,
+                    get_provider_schema_optional: false,
+                    move_resource_state: self.name == "node",
+                },
+            }
+        }
+
+        async fn schema(
+            &self,
+            _ctx: Context,
+            _request: ProviderSchemaRequest,
+        ) -> ProviderSchemaResponse {
+            ProviderSchemaResponse {
+                schema: SchemaBuilder::new()
+                    .attribute(
+                        AttributeBuilder::new("endpoint", AttributeType::String)
+                            .optional()
+                            .build(),
+                    )
+                    .attribute(
+                        AttributeBuilder::new(
+                            &format!("{}_option", self.name),
+                            AttributeType::Bool,
+                        )
+                        .optional()
+                        .build(),
+                    )
+                    .build(),
+                diagnostics: vec![],
+            }
+        }
+
+        async fn meta_schema(
+            &self,
+            _ctx: Context,
+            _request: ProviderMetaSchemaRequest,
+        ) -> ProviderMetaSchemaResponse {
+            ProviderMetaSchemaResponse {
+                schema: None,
+                diagnostics: vec![],
+            }
+        }
+
+        async fn configure(
+            &mut self,
+            _ctx: Context,
+            _request: ConfigureProviderRequest,
+        ) -> ConfigureProviderResponse {
+            ConfigureProviderResponse {
+                diagnostics: vec![],
+                provider_data: Some(Arc::new(self.name.to_string())),
+            }
+        }
+
+        async fn validate(
+            &self,
+            _ctx: Context,
+            _request: ValidateProviderConfigRequest,
+        ) -> ValidateProviderConfigResponse {
+            ValidateProviderConfigResponse {
+                diagnostics: vec![],
+            }
+        }
+
+        async fn stop(&self, _ctx: Context, _request: StopProviderRequest) -> StopProviderResponse {
+            StopProviderResponse { error: None }
+        }
+
+        fn resources(&self) -> HashMap<String, ResourceFactory> {
+            let type_name = self.resource.to_string();
+            let factory: ResourceFactory = Box::new(move || {
+                Box::new(EchoResource {
+                    type_name: type_name.clone(),
+                    provider_data: None,
+                })
+            });
+            HashMap::from([(self.resource.to_string(), factory)])
+        }
+
+        fn data_sources(&self) -> HashMap<String, DataSourceFactory> {
+            HashMap::new()
+        }
+    }
+
+    fn mux() -> MuxProvider {
+        MuxProvider::new("proxmox")
+            .with(NamedProvider {
+                name: "cluster",
+                resource: "proxmox_pool",
+            })
+            .with(NamedProvider {
+                name: "node",
+                resource: "proxmox_node_dns",
+            })
+    }
+
+    async fn configure(mux: &mut MuxProvider) {
+        let response = mux
+            .configure(
+                Context::new(),
+                ConfigureProviderRequest {
+                    terraform_version: "1.9.0".to_string(),
+                    config: DynamicValue::null(),
+                    client_capabilities: ClientCapabilities {
+                        deferral_allowed: false,
+                        write_only_attributes_allowed: false,
+                    },
+                },
+            )
+            .await;
+        assert!(response.diagnostics.is_empty());
+    }
+
+    async fn created_by(mux: &MuxProvider, type_name: &str) -> String {
+        let mut resource = mux.resources().remove(type_name).unwrap()();
+        resource
+            .configure(
+                Context::new(),
+                ConfigureResourceRequest {
+                    provider_data: None,
+                },
+            )
+            .await;
+        resource
+            .create(
+                Context::new(),
+                CreateResourceRequest {
+                    type_name: type_name.to_string(),
+                    planned_state: DynamicValue::null(),
+                    config: DynamicValue::null(),
+                    planned_private: vec![],
+                    provider_meta: None,
+                },
+            )
+            .await
+            .new_state
+            .get_string(&AttributePath::new("provider"))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn resources_get_their_own_provider_data() {
+        let mut mux = mux();
+        configure(&mut mux).await;
+
+        assert_eq!(created_by(&mux, "proxmox_pool").await, "cluster");
+        assert_eq!(created_by(&mux, "proxmox_node_dns").await, "node");
+    }
+
+    #[tokio::test]
+    async fn schema_is_the_union_of_provider_schemas() {
+        let mux = mux();
+        let response = mux.schema(Context::new(), ProviderSchemaRequest).await;
+
+        assert!(response.diagnostics.is_empty());
+        let mut names: Vec<&str> = response
+            .schema
+            .block
+            .attributes
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["cluster_option", "endpoint", "node_option"]);
+
+        let metadata = mux.metadata(Context::new(), ProviderMetadataRequest).await;
+        assert_eq!(metadata.type_name, "proxmox");
+        assert!(metadata.server_capabilities.move_resource_state);
+    }
+
+    #[tokio::test]
+    async fn duplicate_type_names_are_reported() {
+        let mut mux = mux().with(NamedProvider {
+            name: "pbs",
+            resource: "proxmox_pool",
+        });
+        configure(&mut mux).await;
+
+        let response = mux.schema(Context::new(), ProviderSchemaRequest).await;
+        assert_eq!(response.diagnostics.len(), 1);
+        assert!(response.diagnostics[0].detail.contains("'proxmox_pool'"));
+        assert_eq!(created_by(&mux, "proxmox_pool").await, "cluster");
+    }
+}
@@ -60,6 +60,18 @@ pub trait Provider: Send + Sync {
     /// Return data source factories - these create new instances on each call
     /// CRITICAL: Factories MUST return DataSourceWithConfigure trait objects
     fn data_sources(&self) -> HashMap<String, DataSourceFactory>;
+
+    /// Return function factories keyed by function name, e.g. "parse_volid".
+    /// Terraform calls them as `provider::<type_name>::<name>(...)`.
+    fn functions(&self) -> HashMap<String, FunctionFactory> {
+        HashMap::new()
+    }
+
+    /// Return ephemeral resource factories keyed by type name. Ephemeral
+    /// resources produce values that are never written to state or plan.
+    fn ephemeral_resources(&self) -> HashMap<String, EphemeralResourceFactory> {
+        HashMap::new()
+    }
 }
 
 /// Factory type for creating resources
@@ -136,26 +148,12 @@ pub struct StopProviderResponse {
     pub error: Option<String>,
 }
 
-/// Optional trait for providers with functions
-#[async_trait]
-pub trait ProviderWithFunctions: Provider {
-    /// Return function factories
-    fn functions(&self) -> HashMap<String, FunctionFactory>;
-}
-
 /// Factory type for creating functions
 pub type FunctionFactory = Box<dyn Fn() -> Box<dyn crate::function::Function> + Send + Sync>;
 
-/// Optional trait for providers with ephemeral resources
-#[async_trait]
-pub trait ProviderWithEphemeralResources: Provider {
-    /// Return ephemeral resource factories
-    fn ephemeral_resources(&self) -> HashMap<String, EphemeralResourceFactory>;
-}
-
 /// Factory type for creating ephemeral resources
 pub type EphemeralResourceFactory =
-    Box<dyn Fn() -> Box<dyn crate::ephemeral::EphemeralResource> + Send + Sync>;
+    Box<dyn Fn() -> Box<dyn crate::ephemeral::EphemeralResourceWithConfigure> + Send + Sync>;
 
 #[cfg(test)]
 mod tests {
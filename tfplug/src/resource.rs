@@ -4,11 +4,13 @@
 //! can implement for additional functionality.
 
 use crate::context::Context;
-use crate::schema::Schema;
+use crate::schema::{ConfigValidator, Schema};
+pub use crate::state_move::StateMover;
 use crate::types::{
-    AttributePath, ClientCapabilities, Deferred, Diagnostic, DynamicValue, RawState,
-    ResourceIdentityData,
+    AttributePath, ClientCapabilities, Deferred, Diagnostic, DynamicValue, PrivateStateData,
+    RawState, ResourceIdentityData, ResourceIdentitySchema,
 };
+pub use crate::upgrade::StateUpgrader;
 use async_trait::async_trait;
 use std::any::Any;
 use std::sync::Arc;
@@ -88,12 +90,30 @@ pub struct CreateResourceRequest {
     pub provider_meta: Option<DynamicValue>,
 }
 
+impl CreateResourceRequest {
+    /// The `planned_private` bytes decoded as PrivateStateData
+    pub fn private_state(&self) -> crate::Result<PrivateStateData> {
+        PrivateStateData::from_private(&self.planned_private)
+    }
+}
+
 pub struct CreateResourceResponse {
     pub new_state: DynamicValue,
     pub private: Vec<u8>,
     pub diagnostics: Vec<Diagnostic>,
 }
 
+impl CreateResourceResponse {
+    /// Encodes `state` into `private`, adding an error diagnostic if it
+    /// can't be encoded
+    pub fn set_private_state(&mut self, state: &PrivateStateData) {
+        match state.encode() {
+            Ok(private) => self.private = private,
+            Err(e) => self.diagnostics.push(private_state_error(e)),
+        }
+    }
+}
+
 pub struct ReadResourceRequest {
     pub type_name: String,
     pub current_state: DynamicValue,
@@ -103,6 +123,13 @@ pub struct ReadResourceRequest {
     pub current_identity: Option<ResourceIdentityData>,
 }
 
+impl ReadResourceRequest {
+    /// The `private` bytes decoded as PrivateStateData
+    pub fn private_state(&self) -> crate::Result<PrivateStateData> {
+        PrivateStateData::from_private(&self.private)
+    }
+}
+
 pub struct ReadResourceResponse {
     pub new_state: Option<DynamicValue>,
     pub diagnostics: Vec<Diagnostic>,
@@ -111,6 +138,17 @@ pub struct ReadResourceResponse {
     pub new_identity: Option<ResourceIdentityData>,
 }
 
+impl ReadResourceResponse {
+    /// Encodes `state` into `private`, adding an error diagnostic if it
+    /// can't be encoded
+    pub fn set_private_state(&mut self, state: &PrivateStateData) {
+        match state.encode() {
+            Ok(private) => self.private = private,
+            Err(e) => self.diagnostics.push(private_state_error(e)),
+        }
+    }
+}
+
 pub struct UpdateResourceRequest {
     pub type_name: String,
     pub prior_state: DynamicValue,
@@ -121,6 +159,13 @@ pub struct UpdateResourceRequest {
     pub planned_identity: Option<ResourceIdentityData>,
 }
 
+impl UpdateResourceRequest {
+    /// The `planned_private` bytes decoded as PrivateStateData
+    pub fn private_state(&self) -> crate::Result<PrivateStateData> {
+        PrivateStateData::from_private(&self.planned_private)
+    }
+}
+
 pub struct UpdateResourceResponse {
     pub new_state: DynamicValue,
     pub private: Vec<u8>,
@@ -128,6 +173,17 @@ pub struct UpdateResourceResponse {
     pub new_identity: Option<ResourceIdentityData>,
 }
 
+impl UpdateResourceResponse {
+    /// Encodes `state` into `private`, adding an error diagnostic if it
+    /// can't be encoded
+    pub fn set_private_state(&mut self, state: &PrivateStateData) {
+        match state.encode() {
+            Ok(private) => self.private = private,
+            Err(e) => self.diagnostics.push(private_state_error(e)),
+        }
+    }
+}
+
 pub struct DeleteResourceRequest {
     pub type_name: String,
     pub prior_state: DynamicValue,
@@ -135,6 +191,13 @@ pub struct DeleteResourceRequest {
     pub provider_meta: Option<DynamicValue>,
 }
 
+impl DeleteResourceRequest {
+    /// The `planned_private` bytes decoded as PrivateStateData
+    pub fn private_state(&self) -> crate::Result<PrivateStateData> {
+        PrivateStateData::from_private(&self.planned_private)
+    }
+}
+
 pub struct DeleteResourceResponse {
     pub diagnostics: Vec<Diagnostic>,
 }
@@ -149,6 +212,49 @@ pub trait ResourceWithConfigure: Resource {
         ctx: Context,
         request: ConfigureResourceRequest,
     ) -> ConfigureResourceResponse;
+
+    /// Returns the resource as a plan modifier if it implements
+    /// ResourceWithModifyPlan. Override with `Some(self)` to opt in, since
+    /// factories hand out ResourceWithConfigure trait objects.
+    fn as_modify_plan(&self) -> Option<&dyn ResourceWithModifyPlan> {
+        None
+    }
+
+    /// Returns the resource as a state upgrader if it implements
+    /// ResourceWithUpgradeState. Override with `Some(self)` to opt in.
+    fn as_upgrade_state(&self) -> Option<&dyn ResourceWithUpgradeState> {
+        None
+    }
+
+    /// Returns the resource as a state mover if it implements
+    /// ResourceWithMoveState. Override with `Some(self)` to opt in.
+    fn as_move_state(&self) -> Option<&dyn ResourceWithMoveState> {
+        None
+    }
+
+    /// Returns the resource as a config validator source if it implements
+    /// ResourceWithConfigValidators. Override with `Some(self)` to opt in.
+    fn as_config_validators(&self) -> Option<&dyn ResourceWithConfigValidators> {
+        None
+    }
+
+    /// Returns the resource as an importer if it implements
+    /// ResourceWithImportState. Override with `Some(self)` to opt in.
+    fn as_import_state(&self) -> Option<&dyn ResourceWithImportState> {
+        None
+    }
+
+    /// Returns the resource as an identity source if it implements
+    /// ResourceWithIdentity. Override with `Some(self)` to opt in.
+    fn as_identity(&self) -> Option<&dyn ResourceWithIdentity> {
+        None
+    }
+
+    /// Returns the resource as an identity upgrader if it implements
+    /// ResourceWithUpgradeIdentity. Override with `Some(self)` to opt in.
+    fn as_upgrade_identity(&self) -> Option<&dyn ResourceWithUpgradeIdentity> {
+        None
+    }
 }
 
 pub struct ConfigureResourceRequest {
@@ -161,6 +267,14 @@ pub struct ConfigureResourceResponse {
     pub diagnostics: Vec<Diagnostic>,
 }
 
+/// Optional interface for declaring rules across attributes, such as two
+/// attributes that conflict, as reusable validators instead of code in
+/// validate(). The framework runs them after the schema validators and
+/// before validate().
+pub trait ResourceWithConfigValidators: Resource {
+    fn config_validators(&self) -> Vec<Box<dyn ConfigValidator>>;
+}
+
 /// Optional interface for customizing planning behavior
 /// The framework handles most planning logic internally:
 /// 1. Marks computed attributes as unknown
@@ -191,6 +305,13 @@ pub struct ModifyPlanRequest {
     pub provider_meta: Option<DynamicValue>,
 }
 
+impl ModifyPlanRequest {
+    /// The `prior_private` bytes decoded as PrivateStateData
+    pub fn private_state(&self) -> crate::Result<PrivateStateData> {
+        PrivateStateData::from_private(&self.prior_private)
+    }
+}
+
 pub struct ModifyPlanResponse {
     pub planned_state: DynamicValue,
     pub requires_replace: Vec<AttributePath>,
@@ -198,6 +319,24 @@ pub struct ModifyPlanResponse {
     pub diagnostics: Vec<Diagnostic>,
 }
 
+impl ModifyPlanResponse {
+    /// Encodes `state` into `planned_private`, adding an error diagnostic if it
+    /// can't be encoded
+    pub fn set_private_state(&mut self, state: &PrivateStateData) {
+        match state.encode() {
+            Ok(private) => self.planned_private = private,
+            Err(e) => self.diagnostics.push(private_state_error(e)),
+        }
+    }
+}
+
+fn private_state_error(error: crate::TfplugError) -> Diagnostic {
+    Diagnostic::error(
+        "Failed to encode private state",
+        format!("The resource's private state could not be saved: {}", error),
+    )
+}
+
 /// Optional interface for handling state upgrades between schema versions
 /// If not implemented, the framework behavior is:
 /// - If stored version matches current version: return state as-is
@@ -208,11 +347,26 @@ pub struct ModifyPlanResponse {
 /// The framework automatically handles version checking
 #[async_trait]
 pub trait ResourceWithUpgradeState: Resource {
+    /// One upgrader per prior schema version, each moving state to the next
+    /// version. Keep the old ones when adding a new version so state of any
+    /// age can still be upgraded.
+    fn state_upgraders(&self) -> Vec<StateUpgrader>;
+
+    /// Upgrades stored state to the current schema version. The default
+    /// chains the state_upgraders; override for full control.
     async fn upgrade_state(
         &self,
         ctx: Context,
         request: UpgradeResourceStateRequest,
-    ) -> UpgradeResourceStateResponse;
+    ) -> UpgradeResourceStateResponse {
+        let schema = self.schema(ctx, ResourceSchemaRequest).await.schema;
+        crate::upgrade::upgrade_state(
+            &self.state_upgraders(),
+            &schema,
+            request.version,
+            &request.raw_state,
+        )
+    }
 }
 
 pub struct UpgradeResourceStateRequest {
@@ -226,6 +380,41 @@ pub struct UpgradeResourceStateResponse {
     pub diagnostics: Vec<Diagnostic>,
 }
 
+/// Optional interface for taking over state of another resource type, used
+/// by `moved` blocks whose `from` is a different type or provider
+#[async_trait]
+pub trait ResourceWithMoveState: Resource {
+    /// One mover per source resource type this resource understands
+    fn state_movers(&self) -> Vec<StateMover>;
+
+    /// Translates the source state. The default uses the first matching
+    /// state_mover; override for full control.
+    async fn move_state(
+        &self,
+        ctx: Context,
+        request: MoveResourceStateRequest,
+    ) -> MoveResourceStateResponse {
+        let schema = self.schema(ctx, ResourceSchemaRequest).await.schema;
+        crate::state_move::move_state(&self.state_movers(), &schema, &request)
+    }
+}
+
+pub struct MoveResourceStateRequest {
+    /// Provider address of the source, e.g. "registry.terraform.io/telmate/proxmox"
+    pub source_provider_address: String,
+    pub source_type_name: String,
+    pub source_schema_version: i64,
+    pub source_state: RawState,
+    pub target_type_name: String,
+    pub source_private: Vec<u8>,
+}
+
+pub struct MoveResourceStateResponse {
+    pub target_state: DynamicValue,
+    pub target_private: Vec<u8>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 /// Optional interface for import functionality
 #[async_trait]
 pub trait ResourceWithImportState: Resource {
@@ -257,3 +446,40 @@ pub struct ImportedResource {
     pub private: Vec<u8>,
     pub identity: Option<ResourceIdentityData>,
 }
+
+/// Optional interface for resource identity (Terraform 1.12+), the values
+/// that identify a remote object independently of the rest of its state.
+/// Terraform uses it for import blocks with `identity` and to spot the same
+/// object managed twice.
+pub trait ResourceWithIdentity: Resource {
+    fn identity_schema(&self) -> ResourceIdentitySchema;
+
+    /// Identity of the object a state describes. The framework sends it
+    /// with read, apply, import and move responses that don't set an
+    /// identity themselves, so it must not change for the same object.
+    fn identity_from_state(&self, state: &DynamicValue) -> Option<ResourceIdentityData>;
+}
+
+/// Optional interface for upgrading identity stored with an older identity
+/// schema version. Without it stored identity of the current version is
+/// used as is and other versions are rejected.
+#[async_trait]
+pub trait ResourceWithUpgradeIdentity: Resource {
+    async fn upgrade_identity(
+        &self,
+        ctx: Context,
+        request: UpgradeResourceIdentityRequest,
+    ) -> UpgradeResourceIdentityResponse;
+}
+
+pub struct UpgradeResourceIdentityRequest {
+    pub type_name: String,
+    pub version: i64,
+    /// Identity as stored, JSON encoded
+    pub raw_identity: RawState,
+}
+
+pub struct UpgradeResourceIdentityResponse {
+    pub upgraded_identity: Option<ResourceIdentityData>,
+    pub diagnostics: Vec<Diagnostic>,
+}
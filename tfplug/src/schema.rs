@@ -3,7 +3,7 @@
 //! This module provides the schema system for defining resource and data source
 //! schemas, including attribute types, blocks, and validation.
 
-use crate::types::{AttributePath, Diagnostic};
+use crate::types::{AttributePath, Diagnostic, Dynamic};
 use std::collections::HashMap;
 
 /// AttributeType defines the type system for Terraform attributes
@@ -128,6 +128,33 @@ pub struct NestedType {
     pub nesting: ObjectNestingMode,
 }
 
+impl NestedType {
+    /// Create a nested type from its attributes
+    pub fn new(nesting: ObjectNestingMode, attributes: Vec<Attribute>) -> Self {
+        Self {
+            attributes,
+            nesting,
+        }
+    }
+
+    /// Type of the attribute value: an object of the nested attributes,
+    /// wrapped in a list, set or map depending on the nesting mode
+    pub fn value_type(&self) -> AttributeType {
+        let object = AttributeType::Object(
+            self.attributes
+                .iter()
+                .map(|attribute| (attribute.name.clone(), attribute.r#type.clone()))
+                .collect(),
+        );
+        match self.nesting {
+            ObjectNestingMode::Invalid | ObjectNestingMode::Single => object,
+            ObjectNestingMode::List => AttributeType::List(Box::new(object)),
+            ObjectNestingMode::Set => AttributeType::Set(Box::new(object)),
+            ObjectNestingMode::Map => AttributeType::Map(Box::new(object)),
+        }
+    }
+}
+
 /// ObjectNestingMode for nested attribute objects
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ObjectNestingMode {
@@ -138,6 +165,176 @@ pub enum ObjectNestingMode {
     Map,
 }
 
+static NULL: Dynamic = Dynamic::Null;
+
+impl AttributeType {
+    /// Encodes the type using Terraform's JSON type constraint syntax,
+    /// e.g. `["list", ["object", {"name": "string"}]]`
+    pub fn type_json(&self) -> serde_json::Value {
+        use serde_json::{Map, Value};
+
+        let collection = |kind: &str, inner: &AttributeType| {
+            Value::Array(vec![Value::from(kind), inner.type_json()])
+        };
+
+        match self {
+            AttributeType::String => Value::from("string"),
+            AttributeType::Number => Value::from("number"),
+            AttributeType::Bool => Value::from("bool"),
+            AttributeType::List(inner) => collection("list", inner),
+            AttributeType::Set(inner) => collection("set", inner),
+            AttributeType::Map(inner) => collection("map", inner),
+            AttributeType::Object(fields) => {
+                let fields: Map<String, Value> = fields
+                    .iter()
+                    .map(|(name, field_type)| (name.clone(), field_type.type_json()))
+                    .collect();
+                Value::Array(vec![Value::from("object"), Value::Object(fields)])
+            }
+        }
+    }
+
+    /// Whether two values of this type are equal. Set elements are compared
+    /// regardless of order, so reordering a set is not a change.
+    pub fn values_equal(&self, a: &Dynamic, b: &Dynamic) -> bool {
+        match (self, a, b) {
+            (AttributeType::List(inner), Dynamic::List(a), Dynamic::List(b)) => {
+                ordered_eq(a, b, |a, b| inner.values_equal(a, b))
+            }
+            (AttributeType::Set(inner), Dynamic::List(a), Dynamic::List(b)) => {
+                unordered_eq(a, b, |a, b| inner.values_equal(a, b))
+            }
+            (AttributeType::Map(inner), Dynamic::Map(a), Dynamic::Map(b)) => {
+                keyed_eq(a, b, |a, b| inner.values_equal(a, b))
+            }
+            (AttributeType::Object(fields), Dynamic::Map(a), Dynamic::Map(b)) => {
+                fields.iter().all(|(name, field_type)| {
+                    field_type
+                        .values_equal(a.get(name).unwrap_or(&NULL), b.get(name).unwrap_or(&NULL))
+                })
+            }
+            _ => a == b,
+        }
+    }
+}
+
+impl Attribute {
+    /// Whether two values of this attribute are equal, ignoring the order of
+    /// sets including set nested attributes
+    pub fn values_equal(&self, a: &Dynamic, b: &Dynamic) -> bool {
+        let nested = match &self.nested_type {
+            Some(nested) => nested,
+            None => return self.r#type.values_equal(a, b),
+        };
+        let object_eq = |a: &Dynamic, b: &Dynamic| objects_equal(&nested.attributes, &[], a, b);
+        match (nested.nesting, a, b) {
+            (ObjectNestingMode::List, Dynamic::List(a), Dynamic::List(b)) => {
+                ordered_eq(a, b, object_eq)
+            }
+            (ObjectNestingMode::Set, Dynamic::List(a), Dynamic::List(b)) => {
+                unordered_eq(a, b, object_eq)
+            }
+            (ObjectNestingMode::Map, Dynamic::Map(a), Dynamic::Map(b)) => keyed_eq(a, b, object_eq),
+            (ObjectNestingMode::Single | ObjectNestingMode::Invalid, a, b) => object_eq(a, b),
+            _ => a == b,
+        }
+    }
+}
+
+impl Block {
+    /// Whether two values of this block are equal. Set nested blocks match
+    /// regardless of order, and a missing list, set or map of blocks equals
+    /// an empty one.
+    pub fn values_equal(&self, a: &Dynamic, b: &Dynamic) -> bool {
+        objects_equal(&self.attributes, &self.block_types, a, b)
+    }
+}
+
+impl NestedBlock {
+    fn values_equal(&self, a: &Dynamic, b: &Dynamic) -> bool {
+        let empty = |value: &Dynamic| match value {
+            Dynamic::Null => true,
+            Dynamic::List(items) => items.is_empty(),
+            Dynamic::Map(items) => self.nesting == NestingMode::Map && items.is_empty(),
+            _ => false,
+        };
+        let block_eq = |a: &Dynamic, b: &Dynamic| self.block.values_equal(a, b);
+        match (self.nesting, a, b) {
+            (NestingMode::List | NestingMode::Set | NestingMode::Map, a, b)
+                if empty(a) && empty(b) =>
+            {
+                true
+            }
+            (NestingMode::List, Dynamic::List(a), Dynamic::List(b)) => ordered_eq(a, b, block_eq),
+            (NestingMode::Set, Dynamic::List(a), Dynamic::List(b)) => unordered_eq(a, b, block_eq),
+            (NestingMode::Map, Dynamic::Map(a), Dynamic::Map(b)) => keyed_eq(a, b, block_eq),
+            (NestingMode::Single | NestingMode::Group | NestingMode::Invalid, a, b) => {
+                block_eq(a, b)
+            }
+            _ => a == b,
+        }
+    }
+}
+
+fn objects_equal(
+    attributes: &[Attribute],
+    block_types: &[NestedBlock],
+    a: &Dynamic,
+    b: &Dynamic,
+) -> bool {
+    let (a, b) = match (a, b) {
+        (Dynamic::Map(a), Dynamic::Map(b)) => (a, b),
+        (a, b) => return a == b,
+    };
+    attributes.iter().all(|attribute| {
+        attribute.values_equal(
+            a.get(&attribute.name).unwrap_or(&NULL),
+            b.get(&attribute.name).unwrap_or(&NULL),
+        )
+    }) && block_types.iter().all(|nested| {
+        nested.values_equal(
+            a.get(&nested.type_name).unwrap_or(&NULL),
+            b.get(&nested.type_name).unwrap_or(&NULL),
+        )
+    })
+}
+
+fn ordered_eq(a: &[Dynamic], b: &[Dynamic], eq: impl Fn(&Dynamic, &Dynamic) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| eq(a, b))
+}
+
+/// Compares two lists as multisets: every element of `a` must pair up with a
+/// distinct equal element of `b`
+fn unordered_eq(a: &[Dynamic], b: &[Dynamic], eq: impl Fn(&Dynamic, &Dynamic) -> bool) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut matched = vec![false; b.len()];
+    a.iter().all(|item| {
+        let found = b
+            .iter()
+            .enumerate()
+            .position(|(index, other)| !matched[index] && eq(item, other));
+        match found {
+            Some(index) => {
+                matched[index] = true;
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+fn keyed_eq(
+    a: &HashMap<String, Dynamic>,
+    b: &HashMap<String, Dynamic>,
+    eq: impl Fn(&Dynamic, &Dynamic) -> bool,
+) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(key, value)| b.get(key).is_some_and(|other| eq(value, other)))
+}
+
 /// StringKind represents the format of string values
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum StringKind {
@@ -158,6 +355,10 @@ pub trait Validator: Send + Sync {
 pub struct ValidatorRequest {
     pub config_value: crate::types::DynamicValue,
     pub path: AttributePath,
+    /// Object holding the attribute: the whole config for top level
+    /// attributes, the block instance for attributes of a nested block.
+    /// Lets validators look at related attributes.
+    pub parent: crate::types::DynamicValue,
 }
 
 /// Response from validators
@@ -165,6 +366,25 @@ pub struct ValidatorResponse {
     pub diagnostics: Vec<Diagnostic>,
 }
 
+/// ConfigValidator checks a whole config, for rules that span several
+/// attributes or blocks such as two attributes that can't be set together
+pub trait ConfigValidator: Send + Sync {
+    /// Human-readable description
+    fn description(&self) -> String;
+    /// Perform validation
+    fn validate(&self, request: ConfigValidatorRequest) -> ConfigValidatorResponse;
+}
+
+/// Request for config validators
+pub struct ConfigValidatorRequest {
+    pub config: crate::types::DynamicValue,
+}
+
+/// Response from config validators
+pub struct ConfigValidatorResponse {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 /// PlanModifier modifies planned values during planning
 /// Common uses: RequiresReplace, UseStateForUnknown
 pub trait PlanModifier: Send + Sync {
@@ -235,6 +455,32 @@ impl AttributeBuilder {
         }
     }
 
+    /// Create a nested attribute holding one object,
+    /// e.g. `cpu = { type = "host" }`
+    pub fn single_nested(name: &str, attributes: Vec<Attribute>) -> Self {
+        Self::nested(name, ObjectNestingMode::Single, attributes)
+    }
+
+    /// Create a nested attribute holding a list of objects
+    pub fn list_nested(name: &str, attributes: Vec<Attribute>) -> Self {
+        Self::nested(name, ObjectNestingMode::List, attributes)
+    }
+
+    /// Create a nested attribute holding a set of objects
+    pub fn set_nested(name: &str, attributes: Vec<Attribute>) -> Self {
+        Self::nested(name, ObjectNestingMode::Set, attributes)
+    }
+
+    /// Create a nested attribute holding a map of objects
+    pub fn map_nested(name: &str, attributes: Vec<Attribute>) -> Self {
+        Self::nested(name, ObjectNestingMode::Map, attributes)
+    }
+
+    fn nested(name: &str, nesting: ObjectNestingMode, attributes: Vec<Attribute>) -> Self {
+        Self::new(name, AttributeType::Object(HashMap::new()))
+            .nested_type(NestedType::new(nesting, attributes))
+    }
+
     /// Set description
     pub fn description(mut self, desc: &str) -> Self {
         self.attribute.description = desc.to_string();
@@ -279,6 +525,12 @@ impl AttributeBuilder {
         self
     }
 
+    /// Add several validators at once
+    pub fn validators(mut self, validators: Vec<Box<dyn Validator>>) -> Self {
+        self.attribute.validators.extend(validators);
+        self
+    }
+
     /// Add plan modifier
     pub fn plan_modifier(mut self, modifier: Box<dyn PlanModifier>) -> Self {
         self.attribute.plan_modifiers.push(modifier);
@@ -291,8 +543,10 @@ impl AttributeBuilder {
         self
     }
 
-    /// Set nested type
+    /// Set nested type. The attribute type follows from the nested
+    /// attributes.
     pub fn nested_type(mut self, nested: NestedType) -> Self {
+        self.attribute.r#type = nested.value_type();
         self.attribute.nested_type = Some(nested);
         self
     }
@@ -436,4 +690,162 @@ mod tests {
             panic!("Expected Object type");
         }
     }
+
+    #[test]
+    fn nested_attribute_builders() {
+        let attr = AttributeBuilder::list_nested(
+            "network",
+            vec![
+                AttributeBuilder::new("bridge", AttributeType::String)
+                    .required()
+                    .build(),
+                AttributeBuilder::new("tag", AttributeType::Number)
+                    .optional()
+                    .build(),
+            ],
+        )
+        .optional()
+        .build();
+
+        let nested = attr.nested_type.as_ref().expect("nested type");
+        assert_eq!(nested.nesting, ObjectNestingMode::List);
+        assert_eq!(nested.attributes.len(), 2);
+        assert_eq!(
+            attr.r#type,
+            AttributeType::List(Box::new(AttributeType::Object(HashMap::from([
+                ("bridge".to_string(), AttributeType::String),
+                ("tag".to_string(), AttributeType::Number),
+            ]))))
+        );
+
+        let single = AttributeBuilder::single_nested(
+            "cpu",
+            vec![AttributeBuilder::new("type", AttributeType::String)
+                .optional()
+                .build()],
+        )
+        .build();
+        assert_eq!(
+            single.r#type,
+            AttributeType::Object(HashMap::from([("type".to_string(), AttributeType::String)]))
+        );
+
+        let map = AttributeBuilder::map_nested("disks", vec![]).build();
+        assert!(matches!(map.r#type, AttributeType::Map(_)));
+        let set = AttributeBuilder::set_nested("rules", vec![]).build();
+        assert!(matches!(set.r#type, AttributeType::Set(_)));
+    }
+
+    fn rule(action: &str, port: f64) -> Dynamic {
+        Dynamic::Map(HashMap::from([
+            ("action".to_string(), Dynamic::String(action.to_string())),
+            ("port".to_string(), Dynamic::Number(port)),
+        ]))
+    }
+
+    fn rules_block(nesting: NestingMode) -> Block {
+        SchemaBuilder::new()
+            .attribute(
+                AttributeBuilder::new("tags", AttributeType::Set(Box::new(AttributeType::String)))
+                    .optional()
+                    .build(),
+            )
+            .block(NestedBlock {
+                type_name: "rule".to_string(),
+                block: SchemaBuilder::new()
+                    .attribute(
+                        AttributeBuilder::new("action", AttributeType::String)
+                            .required()
+                            .build(),
+                    )
+                    .attribute(
+                        AttributeBuilder::new("port", AttributeType::Number)
+                            .required()
+                            .build(),
+                    )
+                    .build()
+                    .block,
+                nesting,
+                min_items: 0,
+                max_items: 0,
+            })
+            .build()
+            .block
+    }
+
+    fn with_rules(rules: Dynamic, tags: &[&str]) -> Dynamic {
+        Dynamic::Map(HashMap::from([
+            ("rule".to_string(), rules),
+            (
+                "tags".to_string(),
+                Dynamic::List(
+                    tags.iter()
+                        .map(|tag| Dynamic::String(tag.to_string()))
+                        .collect(),
+                ),
+            ),
+        ]))
+    }
+
+    #[test]
+    fn set_nested_blocks_compare_regardless_of_order() {
+        let block = rules_block(NestingMode::Set);
+        let a = with_rules(
+            Dynamic::List(vec![rule("ACCEPT", 22.0), rule("DROP", 80.0)]),
+            &["web", "prod"],
+        );
+        let reordered = with_rules(
+            Dynamic::List(vec![rule("DROP", 80.0), rule("ACCEPT", 22.0)]),
+            &["prod", "web"],
+        );
+        let changed = with_rules(
+            Dynamic::List(vec![rule("DROP", 80.0), rule("ACCEPT", 443.0)]),
+            &["prod", "web"],
+        );
+        let duplicated = with_rules(
+            Dynamic::List(vec![rule("ACCEPT", 22.0), rule("ACCEPT", 22.0)]),
+            &["web", "prod"],
+        );
+
+        assert!(block.values_equal(&a, &reordered));
+        assert!(!block.values_equal(&a, &changed));
+        assert!(!block.values_equal(&a, &duplicated));
+        assert!(!rules_block(NestingMode::List).values_equal(&a, &reordered));
+    }
+
+    #[test]
+    fn map_nested_blocks_compare_by_key() {
+        let block = rules_block(NestingMode::Map);
+        let rules = |entries: &[(&str, Dynamic)]| {
+            Dynamic::Map(
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.clone()))
+                    .collect(),
+            )
+        };
+
+        let a = with_rules(
+            rules(&[("ssh", rule("ACCEPT", 22.0)), ("web", rule("ACCEPT", 80.0))]),
+            &[],
+        );
+        let same = with_rules(
+            rules(&[("web", rule("ACCEPT", 80.0)), ("ssh", rule("ACCEPT", 22.0))]),
+            &[],
+        );
+        let renamed = with_rules(
+            rules(&[
+                ("ssh", rule("ACCEPT", 22.0)),
+                ("http", rule("ACCEPT", 80.0)),
+            ]),
+            &[],
+        );
+
+        assert!(block.values_equal(&a, &same));
+        assert!(!block.values_equal(&a, &renamed));
+        assert!(block.values_equal(
+            &with_rules(Dynamic::Null, &[]),
+            &with_rules(rules(&[]), &[])
+        ));
+    }
 }
@@ -0,0 +1,349 @@
+//! Provider schema export
+//!
+//! Encodes the schemas of a provider in the format `terraform providers
+//! schema -json` prints, so documentation generators such as tfplugdocs can
+//! read them straight from the provider binary instead of going through a
+//! Terraform working directory.
+
+use crate::context::Context;
+use crate::error::{Result, TfplugError};
+use crate::provider::Provider;
+use crate::schema::{Attribute, Block, NestedBlock, NestedType, Schema, StringKind};
+use serde_json::{Map, Value};
+
+/// Version of the `terraform providers schema -json` format produced
+const FORMAT_VERSION: &str = "1.0";
+
+/// Schemas of the provider, its resources, data sources, ephemeral resources
+/// and functions, keyed by the provider source `address`. Fails if any of
+/// them report an error instead of a schema.
+pub async fn provider_schemas_json<P: Provider>(provider: &P, address: &str) -> Result<Value> {
+    let ctx = Context::new();
+    let mut diagnostics = vec![];
+
+    let response = provider
+        .schema(ctx.clone(), crate::provider::ProviderSchemaRequest)
+        .await;
+    diagnostics.extend(response.diagnostics);
+    let provider_schema = schema_json(&response.schema);
+
+    let mut resource_schemas = Map::new();
+    for (name, factory) in provider.resources() {
+        let response = factory()
+            .schema(ctx.clone(), crate::resource::ResourceSchemaRequest)
+            .await;
+        diagnostics.extend(response.diagnostics);
+        resource_schemas.insert(name, schema_json(&response.schema));
+    }
+
+    let mut data_source_schemas = Map::new();
+    for (name, factory) in provider.data_sources() {
+        let response = factory()
+            .schema(ctx.clone(), crate::data_source::DataSourceSchemaRequest)
+            .await;
+        diagnostics.extend(response.diagnostics);
+        data_source_schemas.insert(name, schema_json(&response.schema));
+    }
+
+    let mut ephemeral_resource_schemas = Map::new();
+    for (name, factory) in provider.ephemeral_resources() {
+        let response = factory()
+            .schema(
+                ctx.clone(),
+                crate::ephemeral::EphemeralResourceSchemaRequest,
+            )
+            .await;
+        diagnostics.extend(response.diagnostics);
+        ephemeral_resource_schemas.insert(name, schema_json(&response.schema));
+    }
+
+    let mut functions = Map::new();
+    for (name, factory) in provider.functions() {
+        let response = factory()
+            .definition(ctx.clone(), crate::function::FunctionDefinitionRequest)
+            .await;
+        diagnostics.extend(response.diagnostics);
+        functions.insert(name, function_json(&response.definition));
+    }
+
+    if let Some(error) = diagnostics.iter().find(|diag| diag.is_error()) {
+        return Err(TfplugError::InvalidConfiguration(format!(
+            "{}: {}",
+            error.summary, error.detail
+        )));
+    }
+
+    let schemas = object([
+        ("provider", provider_schema),
+        ("resource_schemas", Value::Object(resource_schemas)),
+        ("data_source_schemas", Value::Object(data_source_schemas)),
+        (
+            "ephemeral_resource_schemas",
+            Value::Object(ephemeral_resource_schemas),
+        ),
+        ("functions", Value::Object(functions)),
+    ]);
+    let mut provider_schemas = Map::new();
+    provider_schemas.insert(address.to_string(), schemas);
+
+    Ok(object([
+        ("format_version", Value::from(FORMAT_VERSION)),
+        ("provider_schemas", Value::Object(provider_schemas)),
+    ]))
+}
+
+fn object<const N: usize>(fields: [(&str, Value); N]) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+fn schema_json(schema: &Schema) -> Value {
+    object([
+        ("version", Value::from(schema.version)),
+        ("block", block_json(&schema.block)),
+    ])
+}
+
+/// Like Terraform, leaves out empty and false fields
+fn block_json(block: &Block) -> Value {
+    let mut object = Map::new();
+    if !block.attributes.is_empty() {
+        object.insert("attributes".to_string(), attributes_json(&block.attributes));
+    }
+    if !block.block_types.is_empty() {
+        let block_types = block
+            .block_types
+            .iter()
+            .map(|nested| (nested.type_name.clone(), nested_block_json(nested)))
+            .collect();
+        object.insert("block_types".to_string(), Value::Object(block_types));
+    }
+    insert_description(&mut object, &block.description, block.description_kind);
+    if block.deprecated {
+        object.insert("deprecated".to_string(), Value::Bool(true));
+    }
+    Value::Object(object)
+}
+
+fn attributes_json(attributes: &[Attribute]) -> Value {
+    Value::Object(
+        attributes
+            .iter()
+            .map(|attr| (attr.name.clone(), attribute_json(attr)))
+            .collect(),
+    )
+}
+
+fn attribute_json(attr: &Attribute) -> Value {
+    let mut object = Map::new();
+    // Nested attributes describe their type through nested_type only
+    match &attr.nested_type {
+        Some(nested) => {
+            object.insert("nested_type".to_string(), nested_type_json(nested));
+        }
+        None => {
+            object.insert("type".to_string(), attr.r#type.type_json());
+        }
+    }
+    insert_description(&mut object, &attr.description, StringKind::Plain);
+    for (flag, set) in [
+        ("deprecated", attr.deprecated),
+        ("required", attr.required),
+        ("optional", attr.optional),
+        ("computed", attr.computed),
+        ("sensitive", attr.sensitive),
+    ] {
+        if set {
+            object.insert(flag.to_string(), Value::Bool(true));
+        }
+    }
+    Value::Object(object)
+}
+
+fn nested_type_json(nested: &NestedType) -> Value {
+    use crate::schema::ObjectNestingMode;
+
+    let nesting_mode = match nested.nesting {
+        ObjectNestingMode::Invalid => "invalid",
+        ObjectNestingMode::Single => "single",
+        ObjectNestingMode::List => "list",
+        ObjectNestingMode::Set => "set",
+        ObjectNestingMode::Map => "map",
+    };
+    object([
+        ("attributes", attributes_json(&nested.attributes)),
+        ("nesting_mode", Value::from(nesting_mode)),
+    ])
+}
+
+fn nested_block_json(nested: &NestedBlock) -> Value {
+    use crate::schema::NestingMode;
+
+    let nesting_mode = match nested.nesting {
+        NestingMode::Invalid => "invalid",
+        NestingMode::Single => "single",
+        NestingMode::List => "list",
+        NestingMode::Set => "set",
+        NestingMode::Map => "map",
+        NestingMode::Group => "group",
+    };
+    let mut object = Map::new();
+    object.insert("nesting_mode".to_string(), Value::from(nesting_mode));
+    object.insert("block".to_string(), block_json(&nested.block));
+    if nested.min_items > 0 {
+        object.insert("min_items".to_string(), Value::from(nested.min_items));
+    }
+    if nested.max_items > 0 {
+        object.insert("max_items".to_string(), Value::from(nested.max_items));
+    }
+    Value::Object(object)
+}
+
+fn function_json(definition: &crate::function::FunctionDefinition) -> Value {
+    let mut object = Map::new();
+    if !definition.summary.is_empty() {
+        object.insert(
+            "summary".to_string(),
+            Value::from(definition.summary.clone()),
+        );
+    }
+    if !definition.description.is_empty() {
+        object.insert(
+            "description".to_string(),
+            Value::from(definition.description.clone()),
+        );
+    }
+    if let Some(message) = &definition.deprecation_message {
+        object.insert(
+            "deprecation_message".to_string(),
+            Value::from(message.clone()),
+        );
+    }
+    object.insert(
+        "return_type".to_string(),
+        type_from_bytes(&definition.return_type.type_),
+    );
+    if !definition.parameters.is_empty() {
+        object.insert(
+            "parameters".to_string(),
+            Value::Array(definition.parameters.iter().map(parameter_json).collect()),
+        );
+    }
+    if let Some(parameter) = &definition.variadic_parameter {
+        object.insert("variadic_parameter".to_string(), parameter_json(parameter));
+    }
+    Value::Object(object)
+}
+
+fn parameter_json(parameter: &crate::function::Parameter) -> Value {
+    let mut object = Map::new();
+    object.insert("name".to_string(), Value::from(parameter.name.clone()));
+    if !parameter.description.is_empty() {
+        object.insert(
+            "description".to_string(),
+            Value::from(parameter.description.clone()),
+        );
+    }
+    if parameter.allow_null_value {
+        object.insert("is_nullable".to_string(), Value::Bool(true));
+    }
+    object.insert("type".to_string(), type_from_bytes(&parameter.type_));
+    Value::Object(object)
+}
+
+/// Function types are kept as their encoded JSON type constraint
+fn type_from_bytes(bytes: &[u8]) -> Value {
+    serde_json::from_slice(bytes).unwrap_or(Value::Null)
+}
+
+fn insert_description(object: &mut Map<String, Value>, description: &str, kind: StringKind) {
+    if description.is_empty() {
+        return;
+    }
+    object.insert("description".to_string(), Value::from(description));
+    let kind = match kind {
+        StringKind::Plain => "plain",
+        StringKind::Markdown => "markdown",
+    };
+    object.insert("description_kind".to_string(), Value::from(kind));
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::schema::{AttributeBuilder, AttributeType, NestingMode, SchemaBuilder};
+    use serde_json::json;
+
+    #[test]
+    fn block_json_matches_terraform_schema_format() {
+        let schema = SchemaBuilder::new()
+            .version(1)
+            .description("A VM")
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .required()
+                    .description("Name of the VM")
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("tags", AttributeType::List(Box::new(AttributeType::String)))
+                    .optional()
+                    .computed()
+                    .build(),
+            )
+            .block(NestedBlock {
+                type_name: "disk".to_string(),
+                block: SchemaBuilder::new()
+                    .attribute(
+                        AttributeBuilder::new("size", AttributeType::Number)
+                            .required()
+                            .build(),
+                    )
+                    .build()
+                    .block,
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 0,
+            })
+            .build();
+
+        assert_eq!(
+            schema_json(&schema),
+            json!({
+                "version": 1,
+                "block": {
+                    "attributes": {
+                        "name": {
+                            "type": "string",
+                            "description": "Name of the VM",
+                            "description_kind": "plain",
+                            "required": true,
+                        },
+                        "tags": {
+                            "type": ["list", "string"],
+                            "optional": true,
+                            "computed": true,
+                        },
+                    },
+                    "block_types": {
+                        "disk": {
+                            "nesting_mode": "list",
+                            "block": {
+                                "attributes": {
+                                    "size": {"type": "number", "required": true},
+                                },
+                            },
+                        },
+                    },
+                    "description": "A VM",
+                    "description_kind": "plain",
+                },
+            })
+        );
+    }
+}
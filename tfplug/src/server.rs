@@ -1,15 +1,44 @@
 //! Server module for running Terraform providers
 //!
-//! This module provides functionality to start a Terraform provider server
-//! with TLS support and proper protocol handshake.
+//! This module starts a Terraform provider server and performs the
+//! go-plugin handshake. Terraform launches the provider with its own client
+//! certificate in PLUGIN_CLIENT_CERT (AutoMTLS); the server answers with a
+//! freshly generated self-signed certificate in the handshake line, so no
+//! certificate files have to ship with the provider.
 
 use crate::error::{Result, TfplugError};
 use crate::grpc::GrpcProviderServer;
 use crate::proto::provider_server::ProviderServer;
 use crate::provider::Provider;
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::{DigitallySignedStruct, DistinguishedName, SignatureScheme};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
-use tonic::transport::{Identity, Server, ServerTlsConfig};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::server::{Connected, Router};
+use tonic::transport::Server;
+
+/// Environment variable and value go-plugin uses to tell a plugin apart from
+/// a binary run by hand
+const MAGIC_COOKIE_KEY: &str = "TF_PLUGIN_MAGIC_COOKIE";
+const MAGIC_COOKIE_VALUE: &str = "d602bf8f470bc67ca7faa0386276bbdd4330efaf76d1a219cb4d6991ca9872b2";
+
+/// Version of the go-plugin handshake itself
+const CORE_PROTOCOL_VERSION: u32 = 1;
+/// Terraform plugin protocol version served by this framework
+const PROTOCOL_VERSION: u32 = 6;
 
 /// Log level for the server
 #[derive(Debug, Clone, Copy)]
@@ -21,13 +50,37 @@ pub enum LogLevel {
     Error,
 }
 
+/// How Terraform connects to the provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// A unix socket in PLUGIN_UNIX_SOCKET_DIR or the temp directory
+    #[cfg(unix)]
+    Unix,
+    /// A TCP port on 127.0.0.1
+    Tcp,
+}
+
+impl Default for Transport {
+    #[cfg(unix)]
+    fn default() -> Self {
+        Transport::Unix
+    }
+
+    #[cfg(not(unix))]
+    fn default() -> Self {
+        Transport::Tcp
+    }
+}
+
 /// Server configuration for running a Terraform provider
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
-    /// Path to TLS certificate file
-    pub cert_path: PathBuf,
-    /// Path to TLS key file
-    pub key_path: PathBuf,
+    /// Path to a TLS certificate file to serve instead of a generated one
+    pub cert_path: Option<PathBuf>,
+    /// Path to the key of `cert_path`
+    pub key_path: Option<PathBuf>,
+    /// Transport Terraform connects over
+    pub transport: Transport,
     /// Maximum message size in bytes
     pub max_message_size: usize,
     /// Whether to enable logging
@@ -36,17 +89,23 @@ pub struct ServerConfig {
     pub log_level: LogLevel,
     /// Timeout for graceful shutdown
     pub shutdown_timeout: Duration,
+    /// Source address of the provider, e.g.
+    /// "registry.terraform.io/mrdvince/proxmox", used to key the schemas
+    /// printed by `--schema-json`
+    pub provider_address: Option<String>,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
-            cert_path: PathBuf::from("./certs/localhost.pem"),
-            key_path: PathBuf::from("./certs/localhost-key.pem"),
+            cert_path: None,
+            key_path: None,
+            transport: Transport::default(),
             max_message_size: 256 << 20, // 256MB
             enable_logging: true,
             log_level: LogLevel::Info,
             shutdown_timeout: Duration::from_secs(30),
+            provider_address: None,
         }
     }
 }
@@ -59,13 +118,19 @@ impl ServerConfig {
 
     /// Set the certificate path
     pub fn with_cert_path(mut self, path: PathBuf) -> Self {
-        self.cert_path = path;
+        self.cert_path = Some(path);
         self
     }
 
     /// Set the key path
     pub fn with_key_path(mut self, path: PathBuf) -> Self {
-        self.key_path = path;
+        self.key_path = Some(path);
+        self
+    }
+
+    /// Set the transport
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
         self
     }
 
@@ -92,52 +157,481 @@ impl ServerConfig {
         self.shutdown_timeout = timeout;
         self
     }
+
+    /// Set the provider source address
+    pub fn with_provider_address(mut self, address: &str) -> Self {
+        self.provider_address = Some(address.to_string());
+        self
+    }
 }
 
-/// Main entry point for running a provider
+/// Main entry point for running a provider. Started with `--schema-json`,
+/// prints the provider schemas for documentation tooling and exits instead.
 pub async fn serve<P: Provider + 'static>(provider: P, config: ServerConfig) -> Result<()> {
     // Initialize logging if enabled
     if config.enable_logging {
         // Logging initialization would go here
     }
 
+    if std::env::args().any(|arg| arg == "--schema-json") {
+        return print_schema_json(&provider, &config).await;
+    }
+
+    check_handshake_env()?;
+
     // Create the gRPC server
     let grpc_server = GrpcProviderServer::new(provider);
     let provider_service = ProviderServer::new(grpc_server)
         .max_decoding_message_size(config.max_message_size)
         .max_encoding_message_size(config.max_message_size);
+    let router = Server::builder().add_service(provider_service);
+
+    let client_cert = std::env::var("PLUGIN_CLIENT_CERT").ok();
+    let identity = server_identity(&config).await?;
+    let acceptor = tls_acceptor(&identity, client_cert.as_deref())?;
+
+    match config.transport {
+        #[cfg(unix)]
+        Transport::Unix => {
+            let dir = std::env::var_os("PLUGIN_UNIX_SOCKET_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(std::env::temp_dir);
+            let path = dir.join(format!("plugin{}", uuid::Uuid::new_v4().simple()));
+            let listener = tokio::net::UnixListener::bind(&path)?;
+
+            println!(
+                "{}",
+                handshake_line("unix", &path.display().to_string(), &identity.cert)
+            );
+
+            let incoming = tokio_stream::wrappers::UnixListenerStream::new(listener);
+            let result = serve_tls(router, acceptor, incoming).await;
+            let _ = std::fs::remove_file(&path);
+            result
+        }
+        Transport::Tcp => {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+            let addr = listener.local_addr()?;
+
+            println!(
+                "{}",
+                handshake_line("tcp", &addr.to_string(), &identity.cert)
+            );
+
+            let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+            serve_tls(router, acceptor, incoming).await
+        }
+    }
+}
+
+/// Prints the schemas of `provider` as `terraform providers schema -json`
+/// does. Without a configured source address the provider is keyed the way
+/// Terraform resolves an unqualified provider name.
+async fn print_schema_json<P: Provider>(provider: &P, config: &ServerConfig) -> Result<()> {
+    let address = config
+        .provider_address
+        .clone()
+        .unwrap_or_else(|| format!("registry.terraform.io/hashicorp/{}", provider.type_name()));
+    let schemas = crate::schema_json::provider_schemas_json(provider, &address).await?;
+    println!(
+        "{}",
+        serde_json::to_string(&schemas).map_err(|e| TfplugError::EncodingError(e.to_string()))?
+    );
+    Ok(())
+}
+
+/// Fails unless the process was started by Terraform, in the way go-plugin
+/// refuses to run plugins started by hand
+fn check_handshake_env() -> Result<()> {
+    if std::env::var(MAGIC_COOKIE_KEY).as_deref() != Ok(MAGIC_COOKIE_VALUE) {
+        return Err(TfplugError::ServerError(
+            "This binary is a plugin. These are not meant to be executed directly. \
+             Please execute the program that consumes these plugins, which will \
+             load any plugins automatically. To debug the provider, start it with --debug."
+                .to_string(),
+        ));
+    }
+
+    if let Ok(versions) = std::env::var("PLUGIN_PROTOCOL_VERSIONS") {
+        let supported = versions
+            .split(',')
+            .any(|version| version.trim() == PROTOCOL_VERSION.to_string());
+        if !supported {
+            return Err(TfplugError::ServerError(format!(
+                "Terraform asked for plugin protocol versions {} but this provider only speaks version {}",
+                versions, PROTOCOL_VERSION
+            )));
+        }
+    }
+
+    Ok(())
+}
 
-    // Load TLS configuration
-    let cert = tokio::fs::read(&config.cert_path)
+/// The line go-plugin reads from stdout to find the server. The last field
+/// is the server certificate Terraform should trust, as unpadded base64 DER.
+fn handshake_line(network: &str, addr: &str, cert: &CertificateDer<'_>) -> String {
+    format!(
+        "{}|{}|{}|{}|grpc|{}",
+        CORE_PROTOCOL_VERSION,
+        PROTOCOL_VERSION,
+        network,
+        addr,
+        STANDARD_NO_PAD.encode(cert.as_ref())
+    )
+}
+
+/// Certificate and key the server presents
+struct ServerIdentity {
+    cert: CertificateDer<'static>,
+    key: PrivateKeyDer<'static>,
+}
+
+/// Reads the configured certificate files, or generates a certificate when
+/// none are configured
+async fn server_identity(config: &ServerConfig) -> Result<ServerIdentity> {
+    let (Some(cert_path), Some(key_path)) = (&config.cert_path, &config.key_path) else {
+        return generate_identity();
+    };
+
+    let cert = tokio::fs::read(cert_path)
         .await
         .map_err(|e| TfplugError::TlsError(format!("Failed to read certificate: {}", e)))?;
-
-    let key = tokio::fs::read(&config.key_path)
+    let key = tokio::fs::read(key_path)
         .await
         .map_err(|e| TfplugError::TlsError(format!("Failed to read key: {}", e)))?;
 
-    let identity = Identity::from_pem(cert, key);
-    let tls_config = ServerTlsConfig::new().identity(identity);
+    Ok(ServerIdentity {
+        cert: CertificateDer::from_pem_slice(&cert)
+            .map_err(|e| TfplugError::TlsError(format!("Invalid certificate: {}", e)))?,
+        key: PrivateKeyDer::from_pem_slice(&key)
+            .map_err(|e| TfplugError::TlsError(format!("Invalid key: {}", e)))?,
+    })
+}
 
-    // Create a TCP listener
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
-    let actual_addr = listener.local_addr()?;
+/// Generates a self-signed certificate for localhost. Terraform trusts it
+/// directly, so unlike go-plugin's own certificates it isn't marked as a CA,
+/// which keeps it acceptable to webpki-based clients as well.
+fn generate_identity() -> Result<ServerIdentity> {
+    let tls_error =
+        |e: rcgen::Error| TfplugError::TlsError(format!("Failed to generate certificate: {}", e));
+
+    let key_pair = rcgen::KeyPair::generate().map_err(tls_error)?;
+    let mut params =
+        rcgen::CertificateParams::new(vec!["localhost".to_string()]).map_err(tls_error)?;
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, "localhost");
+    params.key_usages = vec![
+        rcgen::KeyUsagePurpose::DigitalSignature,
+        rcgen::KeyUsagePurpose::KeyEncipherment,
+    ];
+    params.extended_key_usages = vec![
+        rcgen::ExtendedKeyUsagePurpose::ServerAuth,
+        rcgen::ExtendedKeyUsagePurpose::ClientAuth,
+    ];
+    let cert = params.self_signed(&key_pair).map_err(tls_error)?;
+
+    Ok(ServerIdentity {
+        cert: cert.der().clone(),
+        key: PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_pair.serialize_der())),
+    })
+}
+
+/// Builds the TLS acceptor. With a client certificate from Terraform only
+/// that exact certificate is accepted from clients.
+fn tls_acceptor(identity: &ServerIdentity, client_cert: Option<&str>) -> Result<TlsAcceptor> {
+    let provider = CryptoProvider::get_default()
+        .cloned()
+        .unwrap_or_else(|| Arc::new(rustls::crypto::aws_lc_rs::default_provider()));
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| TfplugError::TlsError(e.to_string()))?;
 
-    println!("1|6|tcp|{}|grpc", actual_addr);
+    let builder = match client_cert {
+        Some(pem) => {
+            let expected = CertificateDer::from_pem_slice(pem.as_bytes())
+                .map_err(|e| TfplugError::TlsError(format!("Invalid PLUGIN_CLIENT_CERT: {}", e)))?;
+            builder.with_client_cert_verifier(Arc::new(PluginClientVerifier {
+                expected: expected.into_owned(),
+                provider,
+            }))
+        }
+        None => builder.with_no_client_auth(),
+    };
 
-    // Create the server with TLS
-    let server = Server::builder()
-        .tls_config(tls_config)?
-        .add_service(provider_service);
+    let mut tls_config = builder
+        .with_single_cert(vec![identity.cert.clone()], identity.key.clone_key())
+        .map_err(|e| TfplugError::TlsError(e.to_string()))?;
+    tls_config.alpn_protocols = vec![b"h2".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(tls_config)))
+}
+
+/// Accepts only the certificate Terraform passed in PLUGIN_CLIENT_CERT.
+/// go-plugin marks that certificate as a CA, which webpki refuses as a
+/// client certificate, so it is compared as is rather than chain verified.
+#[derive(Debug)]
+struct PluginClientVerifier {
+    expected: CertificateDer<'static>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ClientCertVerifier for PluginClientVerifier {
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _now: UnixTime,
+    ) -> std::result::Result<ClientCertVerified, rustls::Error> {
+        if end_entity.as_ref() == self.expected.as_ref() {
+            Ok(ClientCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(
+                rustls::CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// Serves the router over TLS connections accepted from `incoming`. Each
+/// TLS handshake runs in its own task so a stalled client can't block the
+/// listener.
+async fn serve_tls<S, I>(router: Router, acceptor: TlsAcceptor, mut incoming: I) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Connected + Unpin + Send + 'static,
+    I: Stream<Item = std::io::Result<S>> + Unpin + Send + 'static,
+{
+    let (sender, receiver) = tokio::sync::mpsc::channel::<std::io::Result<TlsStream<S>>>(16);
+    tokio::spawn(async move {
+        while let Some(stream) = incoming.next().await {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::warn!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            let acceptor = acceptor.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match acceptor.accept(stream).await {
+                    Ok(stream) => {
+                        let _ = sender.send(Ok(stream)).await;
+                    }
+                    Err(e) => tracing::warn!("TLS handshake failed: {}", e),
+                }
+            });
+        }
+    });
+
+    router
+        .serve_with_incoming(ReceiverStream::new(receiver))
+        .await?;
+    Ok(())
+}
+
+/// Runs the provider for debugging. Instead of being started by Terraform,
+/// the provider is started by hand, for example under a debugger, and
+/// prints the TF_REATTACH_PROVIDERS value that makes Terraform attach to it.
+/// Terraform connects without the plugin handshake or TLS, so no
+/// certificates are needed. Serves until interrupted with Ctrl-C.
+///
+/// `address` is the provider's full source address, e.g.
+/// "registry.terraform.io/mrdvince/proxmox".
+pub async fn serve_debug<P: Provider + 'static>(
+    provider: P,
+    address: &str,
+    config: ServerConfig,
+) -> Result<()> {
+    let grpc_server = GrpcProviderServer::new(provider);
+    let provider_service = ProviderServer::new(grpc_server)
+        .max_decoding_message_size(config.max_message_size)
+        .max_encoding_message_size(config.max_message_size);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let reattach = reattach_config(address, &listener.local_addr()?.to_string())?;
+
+    println!("Provider started. To attach Terraform, set TF_REATTACH_PROVIDERS:");
+    println!();
+    println!("\tTF_REATTACH_PROVIDERS='{}'", reattach);
+    println!();
 
-    // Run the server with the listener
     let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
-    server.serve_with_incoming(incoming).await?;
+    Server::builder()
+        .add_service(provider_service)
+        .serve_with_incoming_shutdown(incoming, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
 
     Ok(())
 }
 
+/// One entry of TF_REATTACH_PROVIDERS, in go-plugin's field names
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ReattachConfig<'a> {
+    protocol: &'a str,
+    protocol_version: u32,
+    pid: u32,
+    test: bool,
+    addr: ReattachAddr<'a>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ReattachAddr<'a> {
+    network: &'a str,
+    string: &'a str,
+}
+
+/// The TF_REATTACH_PROVIDERS value for a provider listening on `addr`
+fn reattach_config(address: &str, addr: &str) -> Result<String> {
+    let config = ReattachConfig {
+        protocol: "grpc",
+        protocol_version: 6,
+        pid: std::process::id(),
+        test: true,
+        addr: ReattachAddr {
+            network: "tcp",
+            string: addr,
+        },
+    };
+    serde_json::to_string(&HashMap::from([(address, config)]))
+        .map_err(|e| TfplugError::EncodingError(e.to_string()))
+}
+
 /// Convenience function to run a provider with default configuration
 pub async fn serve_default<P: Provider + 'static>(provider: P) -> Result<()> {
     serve(provider, ServerConfig::default()).await
 }
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reattach_config_names_the_provider_address() {
+        let config: serde_json::Value = serde_json::from_str(
+            &reattach_config("registry.terraform.io/mrdvince/proxmox", "127.0.0.1:4321").unwrap(),
+        )
+        .unwrap();
+
+        let provider = &config["registry.terraform.io/mrdvince/proxmox"];
+        assert_eq!(provider["Protocol"], "grpc");
+        assert_eq!(provider["ProtocolVersion"], 6);
+        assert_eq!(provider["Pid"], std::process::id());
+        assert_eq!(provider["Addr"]["Network"], "tcp");
+        assert_eq!(provider["Addr"]["String"], "127.0.0.1:4321");
+    }
+
+    #[test]
+    fn handshake_line_carries_the_server_certificate() {
+        let identity = generate_identity().unwrap();
+        let line = handshake_line("unix", "/tmp/plugin123", &identity.cert);
+
+        let fields: Vec<&str> = line.split('|').collect();
+        assert_eq!(fields[..5], ["1", "6", "unix", "/tmp/plugin123", "grpc"]);
+        assert!(!fields[5].ends_with('='));
+        assert_eq!(
+            STANDARD_NO_PAD.decode(fields[5]).unwrap(),
+            identity.cert.as_ref()
+        );
+    }
+
+    /// Runs a TLS handshake against an acceptor expecting Terraform's
+    /// `client_cert`, with a client presenting `presented`
+    async fn handshake(client_cert: &ServerIdentity, presented: &ServerIdentity) -> bool {
+        let server = generate_identity().unwrap();
+        let client_pem = format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+            base64::engine::general_purpose::STANDARD.encode(client_cert.cert.as_ref())
+        );
+        let acceptor = tls_acceptor(&server, Some(&client_pem)).unwrap();
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(server.cert.clone()).unwrap();
+        let client_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_client_auth_cert(vec![presented.cert.clone()], presented.key.clone_key())
+        .unwrap();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(client_config));
+
+        let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+        let server_name = rustls::pki_types::ServerName::try_from("localhost").unwrap();
+        let (accepted, _) = tokio::join!(
+            acceptor.accept(server_io),
+            connector.connect(server_name, client_io)
+        );
+        accepted.is_ok()
+    }
+
+    /// A client certificate like the ones go-plugin generates, marked as a CA
+    fn terraform_identity() -> ServerIdentity {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let mut params = rcgen::CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        params.key_usages = vec![
+            rcgen::KeyUsagePurpose::DigitalSignature,
+            rcgen::KeyUsagePurpose::KeyCertSign,
+        ];
+        params.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::ClientAuth];
+        let cert = params.self_signed(&key_pair).unwrap();
+        ServerIdentity {
+            cert: cert.der().clone(),
+            key: PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_pair.serialize_der())),
+        }
+    }
+
+    #[tokio::test]
+    async fn tls_accepts_only_terraforms_client_certificate() {
+        let terraform = terraform_identity();
+        let other = generate_identity().unwrap();
+
+        assert!(handshake(&terraform, &terraform).await);
+        assert!(!handshake(&terraform, &other).await);
+    }
+}
@@ -0,0 +1,205 @@
+//! State move helpers
+//!
+//! A `moved` block may change the resource type, including to a type of
+//! another provider. Terraform then asks the target resource to translate the
+//! source state. A resource lists the sources it understands with one
+//! StateMover each; the first mover matching the source provider and type
+//! produces the new state, which is shaped to the target schema.
+
+use crate::resource::{MoveResourceStateRequest, MoveResourceStateResponse};
+use crate::schema::Schema;
+use crate::types::{Diagnostic, DynamicValue};
+
+type MoveFn = dyn Fn(i64, DynamicValue) -> Result<DynamicValue, Vec<Diagnostic>> + Send + Sync;
+
+/// Translates state of one source resource type into the target resource
+pub struct StateMover {
+    /// Source provider, e.g. "telmate/proxmox". Matches any registry host.
+    pub source_provider: String,
+    /// Source resource type, e.g. "proxmox_vm_qemu"
+    pub source_type_name: String,
+    translate: Box<MoveFn>,
+}
+
+impl StateMover {
+    /// Create a mover for `source_type_name` of `source_provider`. The
+    /// function receives the source schema version and the decoded source
+    /// state and returns state of the target resource. Attributes it leaves
+    /// out are set to null.
+    pub fn new<F>(source_provider: &str, source_type_name: &str, translate: F) -> Self
+    where
+        F: Fn(i64, DynamicValue) -> Result<DynamicValue, Vec<Diagnostic>> + Send + Sync + 'static,
+    {
+        Self {
+            source_provider: source_provider.to_string(),
+            source_type_name: source_type_name.to_string(),
+            translate: Box::new(translate),
+        }
+    }
+
+    /// Whether this mover handles state of the given provider address, such
+    /// as "registry.terraform.io/telmate/proxmox", and resource type
+    pub fn matches(&self, provider_address: &str, type_name: &str) -> bool {
+        let provider_matches = provider_address == self.source_provider
+            || provider_address
+                .strip_suffix(self.source_provider.as_str())
+                .is_some_and(|host| host.ends_with('/'));
+        provider_matches && type_name == self.source_type_name
+    }
+}
+
+/// Moves source state into the target resource using the first matching
+/// mover
+pub fn move_state(
+    movers: &[StateMover],
+    schema: &Schema,
+    request: &MoveResourceStateRequest,
+) -> MoveResourceStateResponse {
+    let moved = run_mover(movers, request).and_then(|state| {
+        crate::upgrade::conform_to_block(&schema.block, state).map_err(|d| vec![d])
+    });
+    match moved {
+        Ok(state) => MoveResourceStateResponse {
+            target_state: state,
+            target_private: vec![],
+            diagnostics: vec![],
+        },
+        Err(diagnostics) => MoveResourceStateResponse {
+            target_state: DynamicValue::null(),
+            target_private: vec![],
+            diagnostics,
+        },
+    }
+}
+
+fn run_mover(
+    movers: &[StateMover],
+    request: &MoveResourceStateRequest,
+) -> Result<DynamicValue, Vec<Diagnostic>> {
+    let mover = movers
+        .iter()
+        .find(|m| m.matches(&request.source_provider_address, &request.source_type_name))
+        .ok_or_else(|| {
+            vec![Diagnostic::error(
+                "Unable to move resource state",
+                format!(
+                    "{} cannot take state from {} of provider {}. Use import instead.",
+                    request.target_type_name,
+                    request.source_type_name,
+                    request.source_provider_address
+                ),
+            )]
+        })?;
+
+    let json = request.source_state.json.as_ref().ok_or_else(|| {
+        vec![Diagnostic::error(
+            "Unsupported state format",
+            "Only JSON state can be moved",
+        )]
+    })?;
+    let state = DynamicValue::decode_json(json).map_err(|e| {
+        vec![Diagnostic::error(
+            "Failed to decode state",
+            format!("Could not decode the source state: {}", e),
+        )]
+    })?;
+
+    (mover.translate)(request.source_schema_version, state)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+    use crate::types::{AttributePath, RawState};
+
+    fn schema() -> Schema {
+        SchemaBuilder::new()
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("memory", AttributeType::Number)
+                    .optional()
+                    .build(),
+            )
+            .build()
+    }
+
+    fn movers() -> Vec<StateMover> {
+        vec![StateMover::new(
+            "example/legacy",
+            "legacy_vm",
+            |_, source| {
+                let mut state = DynamicValue::null();
+                let name = source
+                    .get_string(&AttributePath::new("hostname"))
+                    .map_err(|e| vec![Diagnostic::error("Missing hostname", e.to_string())])?;
+                let _ = state.set_string(&AttributePath::new("name"), name);
+                Ok(state)
+            },
+        )]
+    }
+
+    fn request(provider: &str, type_name: &str) -> MoveResourceStateRequest {
+        MoveResourceStateRequest {
+            source_provider_address: provider.to_string(),
+            source_type_name: type_name.to_string(),
+            source_schema_version: 0,
+            source_state: RawState {
+                json: Some(br#"{"hostname":"web","legacy":true}"#.to_vec()),
+                flatmap: None,
+            },
+            target_type_name: "vm".to_string(),
+            source_private: vec![],
+        }
+    }
+
+    #[test]
+    fn mover_matches_provider_on_any_host() {
+        let mover = &movers()[0];
+        assert!(mover.matches("registry.terraform.io/example/legacy", "legacy_vm"));
+        assert!(mover.matches("example/legacy", "legacy_vm"));
+        assert!(!mover.matches("registry.terraform.io/other-example/legacy", "legacy_vm"));
+        assert!(!mover.matches("registry.terraform.io/example/legacy", "legacy_lxc"));
+    }
+
+    #[test]
+    fn move_state_translates_and_conforms_to_schema() {
+        let response = move_state(
+            &movers(),
+            &schema(),
+            &request("registry.terraform.io/example/legacy", "legacy_vm"),
+        );
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.target_state;
+        assert_eq!(
+            state.get_string(&AttributePath::new("name")).unwrap(),
+            "web"
+        );
+        let crate::types::Dynamic::Map(values) = &state.value else {
+            panic!("state must be an object");
+        };
+        assert_eq!(values.get("memory"), Some(&crate::types::Dynamic::Null));
+        assert!(!values.contains_key("legacy"));
+    }
+
+    #[test]
+    fn move_state_rejects_unknown_sources() {
+        let response = move_state(
+            &movers(),
+            &schema(),
+            &request("registry.terraform.io/example/legacy", "legacy_lxc"),
+        );
+
+        assert!(response.target_state.is_null());
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "Unable to move resource state"
+        );
+    }
+}
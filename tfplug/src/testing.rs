@@ -0,0 +1,803 @@
+//! Acceptance testing
+//!
+//! Runs a provider in-process and drives it through the plugin protocol the
+//! way `terraform apply` does. Every step validates, refreshes, plans and
+//! applies its configuration, runs its checks against the resulting state
+//! and makes sure that planning again shows no changes. Once the last step
+//! has run, or a step failed, everything that was created is destroyed.
+//!
+//! ```ignore
+//! TestCase::new(ProxmoxProvider::new())
+//!     .provider_config(json!({"endpoint": server.url(), "api_token": "..."}))
+//!     .step(
+//!         TestStep::new()
+//!             .resource("proxmox_node_dns.pve", json!({"node": "pve", "search": "example.com"}))
+//!             .check(check::attribute_equals(
+//!                 "proxmox_node_dns.pve",
+//!                 AttributePath::new("search"),
+//!                 Dynamic::String("example.com".to_string()),
+//!             )),
+//!     )
+//!     .run()
+//!     .await;
+//! ```
+//!
+//! Configuration is given as literal JSON values addressed as `type.name`.
+//! There is no expression language, so one resource can't refer to another,
+//! and resources are applied in the order they are listed.
+
+use crate::context::Context;
+use crate::grpc::GrpcProviderServer;
+use crate::proto::{self, provider_server::Provider as _};
+use crate::provider::{Provider, ProviderSchemaRequest};
+use crate::schema::{Block, NestingMode, Schema};
+use crate::types::{Dynamic, DynamicValue};
+use crate::upgrade::conform_to_block;
+use regex::Regex;
+use std::collections::HashMap;
+use tonic::Request;
+
+/// Checks the state after a step or after destroy, returning why it failed
+pub type Check = Box<dyn Fn(&TestState) -> Result<(), String> + Send + Sync>;
+
+/// State of the resources and data sources of a test case, by address.
+/// Data sources are addressed as `data.type.name`.
+#[derive(Debug, Clone, Default)]
+pub struct TestState {
+    values: HashMap<String, DynamicValue>,
+}
+
+impl TestState {
+    pub fn get(&self, address: &str) -> Option<&DynamicValue> {
+        self.values.get(address)
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+}
+
+/// One configuration to apply, with the checks to run after applying it
+#[derive(Default)]
+pub struct TestStep {
+    resources: Vec<(String, serde_json::Value)>,
+    data_sources: Vec<(String, serde_json::Value)>,
+    checks: Vec<Check>,
+    expect_error: Option<String>,
+    expect_non_empty_plan: bool,
+}
+
+impl TestStep {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a resource such as `proxmox_pool.test` with its configuration.
+    /// Resources of earlier steps that a step leaves out are destroyed.
+    pub fn resource(mut self, address: &str, config: serde_json::Value) -> Self {
+        self.resources.push((address.to_string(), config));
+        self
+    }
+
+    /// Adds a data source, read before the resources of the step are applied
+    pub fn data_source(mut self, address: &str, config: serde_json::Value) -> Self {
+        self.data_sources.push((address.to_string(), config));
+        self
+    }
+
+    pub fn check(mut self, check: Check) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// The step must fail with an error whose summary or detail matches the
+    /// pattern. Checks are skipped for such a step.
+    pub fn expect_error(mut self, pattern: &str) -> Self {
+        self.expect_error = Some(pattern.to_string());
+        self
+    }
+
+    /// Allows the plan after applying to still show changes, for resources
+    /// that are expected to drift
+    pub fn expect_non_empty_plan(mut self) -> Self {
+        self.expect_non_empty_plan = true;
+        self
+    }
+}
+
+/// A provider and the steps to run against it
+pub struct TestCase<P: Provider + 'static> {
+    provider: P,
+    provider_config: serde_json::Value,
+    steps: Vec<TestStep>,
+    check_destroy: Vec<Check>,
+}
+
+impl<P: Provider + 'static> TestCase<P> {
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            provider_config: serde_json::Value::Object(Default::default()),
+            steps: vec![],
+            check_destroy: vec![],
+        }
+    }
+
+    /// Configuration of the provider block
+    pub fn provider_config(mut self, config: serde_json::Value) -> Self {
+        self.provider_config = config;
+        self
+    }
+
+    pub fn step(mut self, step: TestStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Runs once everything has been destroyed, with the state as it was
+    /// before destroying
+    pub fn check_destroy(mut self, check: Check) -> Self {
+        self.check_destroy.push(check);
+        self
+    }
+
+    /// Runs the test case, panicking with the reason if it fails
+    pub async fn run(self) {
+        if let Err(e) = self.try_run().await {
+            panic!("{}", e);
+        }
+    }
+
+    /// Runs the test case and returns why it failed
+    pub async fn try_run(self) -> Result<(), String> {
+        let mut runner = Runner::new(self.provider).await;
+        let mut result = runner.configure(&self.provider_config).await;
+
+        if result.is_ok() {
+            for (index, step) in self.steps.iter().enumerate() {
+                result = runner
+                    .run_step(step)
+                    .await
+                    .map_err(|e| format!("Step {}: {}", index + 1, e));
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+
+        let state = runner.state.clone();
+        let destroyed = runner.destroy_all().await;
+        result?;
+        destroyed.map_err(|e| format!("Destroy: {}", e))?;
+        for check in &self.check_destroy {
+            check(&state).map_err(|e| format!("Destroy check: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// What planning a resource came up with
+struct Plan {
+    planned_state: DynamicValue,
+    requires_replace: bool,
+    planned_private: Vec<u8>,
+}
+
+struct Runner<P: Provider + 'static> {
+    server: GrpcProviderServer<P>,
+    resource_schemas: HashMap<String, Schema>,
+    data_source_schemas: HashMap<String, Schema>,
+    provider_schema: Schema,
+    state: TestState,
+    private: HashMap<String, Vec<u8>>,
+    /// Resource addresses in the order they were created, to destroy them
+    /// in reverse
+    created: Vec<String>,
+}
+
+impl<P: Provider + 'static> Runner<P> {
+    /// Collects the schemas before the provider moves into the server, so
+    /// that configuration can be shaped like Terraform would send it
+    async fn new(provider: P) -> Self {
+        let ctx = Context::new();
+        let provider_schema = provider
+            .schema(ctx.clone(), ProviderSchemaRequest)
+            .await
+            .schema;
+
+        let mut resource_schemas = HashMap::new();
+        for (type_name, factory) in provider.resources() {
+            let response = factory()
+                .schema(ctx.clone(), crate::resource::ResourceSchemaRequest)
+                .await;
+            resource_schemas.insert(type_name, response.schema);
+        }
+        let mut data_source_schemas = HashMap::new();
+        for (type_name, factory) in provider.data_sources() {
+            let response = factory()
+                .schema(ctx.clone(), crate::data_source::DataSourceSchemaRequest)
+                .await;
+            data_source_schemas.insert(type_name, response.schema);
+        }
+
+        Self {
+            server: GrpcProviderServer::new(provider),
+            resource_schemas,
+            data_source_schemas,
+            provider_schema,
+            state: TestState::default(),
+            private: HashMap::new(),
+            created: vec![],
+        }
+    }
+
+    async fn configure(&self, config: &serde_json::Value) -> Result<(), String> {
+        let config = encode(&shape_config(&self.provider_schema, config)?)?;
+
+        let response = self
+            .server
+            .validate_provider_config(Request::new(proto::validate_provider_config::Request {
+                config: Some(config.clone()),
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors("Provider configuration is invalid", &response.diagnostics)?;
+
+        let response = self
+            .server
+            .configure_provider(Request::new(proto::configure_provider::Request {
+                terraform_version: TERRAFORM_VERSION.to_string(),
+                config: Some(config),
+                client_capabilities: client_capabilities(),
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors("Failed to configure the provider", &response.diagnostics)
+    }
+
+    async fn run_step(&mut self, step: &TestStep) -> Result<(), String> {
+        let pattern = step
+            .expect_error
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid expect_error pattern: {}", e))?;
+
+        match (pattern, self.apply_step(step).await) {
+            (None, Err(e)) => Err(e),
+            (Some(pattern), Err(e)) if pattern.is_match(&e) => Ok(()),
+            (Some(pattern), Err(e)) => Err(format!(
+                "Expected an error matching /{}/, got: {}",
+                pattern, e
+            )),
+            (Some(pattern), Ok(())) => Err(format!(
+                "Expected an error matching /{}/, but the step succeeded",
+                pattern
+            )),
+            (None, Ok(())) => {
+                for check in &step.checks {
+                    check(&self.state).map_err(|e| format!("Check failed: {}", e))?;
+                }
+                if !step.expect_non_empty_plan {
+                    self.verify_empty_plan(step).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn apply_step(&mut self, step: &TestStep) -> Result<(), String> {
+        let removed: Vec<String> = self
+            .created
+            .iter()
+            .filter(|address| !step.resources.iter().any(|(a, _)| a == *address))
+            .cloned()
+            .collect();
+        for address in removed.iter().rev() {
+            self.destroy(address).await?;
+        }
+
+        for (address, config) in &step.data_sources {
+            self.read_data_source(address, config).await?;
+        }
+        for (address, config) in &step.resources {
+            self.apply_resource(address, config).await?;
+        }
+        Ok(())
+    }
+
+    async fn read_data_source(
+        &mut self,
+        address: &str,
+        config: &serde_json::Value,
+    ) -> Result<(), String> {
+        let (type_name, _) = split_address(address)?;
+        let schema = self
+            .data_source_schemas
+            .get(type_name)
+            .ok_or_else(|| format!("The provider has no data source '{}'", type_name))?;
+        let config = encode(&shape_config(schema, config)?)?;
+
+        let response = self
+            .server
+            .validate_data_resource_config(Request::new(
+                proto::validate_data_resource_config::Request {
+                    type_name: type_name.to_string(),
+                    config: Some(config.clone()),
+                },
+            ))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors(address, &response.diagnostics)?;
+
+        let response = self
+            .server
+            .read_data_source(Request::new(proto::read_data_source::Request {
+                type_name: type_name.to_string(),
+                config: Some(config),
+                provider_meta: None,
+                client_capabilities: client_capabilities(),
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors(address, &response.diagnostics)?;
+
+        let state = decode(response.state)?;
+        self.state.values.insert(format!("data.{}", address), state);
+        Ok(())
+    }
+
+    async fn apply_resource(
+        &mut self,
+        address: &str,
+        config: &serde_json::Value,
+    ) -> Result<(), String> {
+        let (type_name, _) = split_address(address)?;
+        let schema = self
+            .resource_schemas
+            .get(type_name)
+            .ok_or_else(|| format!("The provider has no resource '{}'", type_name))?;
+        let config = shape_config(schema, config)?;
+
+        let response = self
+            .server
+            .validate_resource_config(Request::new(proto::validate_resource_config::Request {
+                type_name: type_name.to_string(),
+                config: Some(encode(&config)?),
+                client_capabilities: client_capabilities(),
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors(address, &response.diagnostics)?;
+
+        let mut prior = self.refresh(address).await?;
+        let mut plan = self.plan(address, prior.as_ref(), &config).await?;
+        if prior.as_ref() == Some(&plan.planned_state) {
+            return Ok(());
+        }
+        if prior.is_some() && plan.requires_replace {
+            self.destroy(address).await?;
+            prior = None;
+            plan = self.plan(address, None, &config).await?;
+        }
+
+        let response = self
+            .server
+            .apply_resource_change(Request::new(proto::apply_resource_change::Request {
+                type_name: type_name.to_string(),
+                prior_state: Some(encode(prior.as_ref().unwrap_or(&DynamicValue::null()))?),
+                planned_state: Some(encode(&plan.planned_state)?),
+                config: Some(encode(&config)?),
+                planned_private: plan.planned_private,
+                provider_meta: None,
+                planned_identity: None,
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+
+        // Keep whatever was created, even by a failed apply, so that it is
+        // destroyed at the end
+        let new_state = decode(response.new_state)?;
+        if new_state.is_null() {
+            self.forget(address);
+        } else {
+            self.remember(address, new_state, response.private);
+        }
+        errors(address, &response.diagnostics)
+    }
+
+    /// Reads the resource at `address` as Terraform does before planning.
+    /// Returns None when it isn't in state or no longer exists.
+    async fn refresh(&mut self, address: &str) -> Result<Option<DynamicValue>, String> {
+        let current = match self.state.values.get(address) {
+            Some(current) => current.clone(),
+            None => return Ok(None),
+        };
+        let (type_name, _) = split_address(address)?;
+
+        let response = self
+            .server
+            .read_resource(Request::new(proto::read_resource::Request {
+                type_name: type_name.to_string(),
+                current_state: Some(encode(&current)?),
+                private: self.private.get(address).cloned().unwrap_or_default(),
+                provider_meta: None,
+                client_capabilities: client_capabilities(),
+                current_identity: None,
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors(address, &response.diagnostics)?;
+
+        let new_state = decode(response.new_state)?;
+        if new_state.is_null() {
+            self.forget(address);
+            return Ok(None);
+        }
+        self.remember(address, new_state.clone(), response.private);
+        Ok(Some(new_state))
+    }
+
+    async fn plan(
+        &self,
+        address: &str,
+        prior: Option<&DynamicValue>,
+        config: &DynamicValue,
+    ) -> Result<Plan, String> {
+        let (type_name, _) = split_address(address)?;
+        let schema = self
+            .resource_schemas
+            .get(type_name)
+            .ok_or_else(|| format!("The provider has no resource '{}'", type_name))?;
+        let proposed = DynamicValue::new(proposed_new(
+            &schema.block,
+            prior.map(|prior| &prior.value),
+            &config.value,
+        ));
+
+        let response = self
+            .server
+            .plan_resource_change(Request::new(proto::plan_resource_change::Request {
+                type_name: type_name.to_string(),
+                prior_state: Some(encode(prior.unwrap_or(&DynamicValue::null()))?),
+                proposed_new_state: Some(encode(&proposed)?),
+                config: Some(encode(config)?),
+                prior_private: self.private.get(address).cloned().unwrap_or_default(),
+                provider_meta: None,
+                client_capabilities: client_capabilities(),
+                prior_identity: None,
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors(address, &response.diagnostics)?;
+        Ok(Plan {
+            planned_state: decode(response.planned_state)?,
+            requires_replace: !response.requires_replace.is_empty(),
+            planned_private: response.planned_private,
+        })
+    }
+
+    /// Plans every resource of the step again and fails if anything would
+    /// change, which usually means read and apply disagree
+    async fn verify_empty_plan(&mut self, step: &TestStep) -> Result<(), String> {
+        for (address, config) in &step.resources {
+            let (type_name, _) = split_address(address)?;
+            let schema = self
+                .resource_schemas
+                .get(type_name)
+                .ok_or_else(|| format!("The provider has no resource '{}'", type_name))?;
+            let config = shape_config(schema, config)?;
+
+            let prior = self.refresh(address).await?;
+            let plan = self.plan(address, prior.as_ref(), &config).await?;
+            let prior = match prior {
+                Some(prior) => prior,
+                None => {
+                    return Err(format!(
+                        "After applying, the plan is not empty: {} no longer exists",
+                        address
+                    ))
+                }
+            };
+            if plan.requires_replace || plan.planned_state != prior {
+                return Err(format!(
+                    "After applying, the plan is not empty: {} would change {}",
+                    address,
+                    changed_attributes(&prior.value, &plan.planned_state.value).join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    async fn destroy(&mut self, address: &str) -> Result<(), String> {
+        let prior = match self.state.values.get(address) {
+            Some(prior) => prior.clone(),
+            None => return Ok(()),
+        };
+        let (type_name, _) = split_address(address)?;
+
+        let response = self
+            .server
+            .apply_resource_change(Request::new(proto::apply_resource_change::Request {
+                type_name: type_name.to_string(),
+                prior_state: Some(encode(&prior)?),
+                planned_state: Some(encode(&DynamicValue::null())?),
+                config: Some(encode(&DynamicValue::null())?),
+                planned_private: self.private.get(address).cloned().unwrap_or_default(),
+                provider_meta: None,
+                planned_identity: None,
+            }))
+            .await
+            .map_err(|e| e.message().to_string())?
+            .into_inner();
+        errors(address, &response.diagnostics)?;
+
+        self.forget(address);
+        Ok(())
+    }
+
+    /// Destroys every resource, newest first. Keeps going after a failure
+    /// so that as little as possible is left behind.
+    async fn destroy_all(&mut self) -> Result<(), String> {
+        let mut failures = vec![];
+        for address in self.created.clone().iter().rev() {
+            if let Err(e) = self.destroy(address).await {
+                failures.push(e);
+            }
+        }
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("\n"))
+        }
+    }
+
+    fn remember(&mut self, address: &str, state: DynamicValue, private: Vec<u8>) {
+        if !self.created.iter().any(|a| a == address) {
+            self.created.push(address.to_string());
+        }
+        self.state.values.insert(address.to_string(), state);
+        self.private.insert(address.to_string(), private);
+    }
+
+    fn forget(&mut self, address: &str) {
+        self.created.retain(|a| a != address);
+        self.state.values.remove(address);
+        self.private.remove(address);
+    }
+}
+
+/// Terraform version reported to the provider
+const TERRAFORM_VERSION: &str = "1.9.0";
+
+fn client_capabilities() -> Option<proto::ClientCapabilities> {
+    Some(proto::ClientCapabilities {
+        deferral_allowed: false,
+        write_only_attributes_allowed: false,
+    })
+}
+
+fn split_address(address: &str) -> Result<(&str, &str), String> {
+    address
+        .split_once('.')
+        .filter(|(type_name, name)| !type_name.is_empty() && !name.is_empty())
+        .ok_or_else(|| format!("Address '{}' must be in the form type.name", address))
+}
+
+/// Turns JSON configuration into the object Terraform would send, with
+/// every attribute of the schema present
+fn shape_config(schema: &Schema, config: &serde_json::Value) -> Result<DynamicValue, String> {
+    let json = serde_json::to_vec(config).map_err(|e| e.to_string())?;
+    let config = DynamicValue::decode_json(&json).map_err(|e| e.to_string())?;
+    conform_to_block(&schema.block, config).map_err(|d| d.detail)
+}
+
+/// The proposed new state Terraform sends when planning: the configuration,
+/// with computed attributes the configuration leaves null taken from the
+/// prior state
+fn proposed_new(block: &Block, prior: Option<&Dynamic>, config: &Dynamic) -> Dynamic {
+    let (prior, config) = match (prior, config) {
+        (Some(Dynamic::Map(prior)), Dynamic::Map(config)) => (prior, config),
+        _ => return config.clone(),
+    };
+
+    let mut proposed = config.clone();
+    for attribute in &block.attributes {
+        if attribute.computed && matches!(config.get(&attribute.name), None | Some(Dynamic::Null)) {
+            if let Some(value) = prior.get(&attribute.name) {
+                proposed.insert(attribute.name.clone(), value.clone());
+            }
+        }
+    }
+    for nested in &block.block_types {
+        let (prior, config) = match (prior.get(&nested.type_name), config.get(&nested.type_name)) {
+            (Some(prior), Some(config)) => (prior, config),
+            _ => continue,
+        };
+        let value = match (nested.nesting, prior, config) {
+            (NestingMode::List, Dynamic::List(prior), Dynamic::List(config)) => Dynamic::List(
+                config
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| proposed_new(&nested.block, prior.get(i), item))
+                    .collect(),
+            ),
+            (NestingMode::Single | NestingMode::Group, prior, config) => {
+                proposed_new(&nested.block, Some(prior), config)
+            }
+            (_, _, config) => config.clone(),
+        };
+        proposed.insert(nested.type_name.clone(), value);
+    }
+    Dynamic::Map(proposed)
+}
+
+/// Top-level attributes that differ between two objects
+fn changed_attributes(prior: &Dynamic, planned: &Dynamic) -> Vec<String> {
+    let (prior, planned) = match (prior, planned) {
+        (Dynamic::Map(prior), Dynamic::Map(planned)) => (prior, planned),
+        _ => return vec!["the whole object".to_string()],
+    };
+    let mut changed: Vec<String> = planned
+        .iter()
+        .filter(|(name, value)| prior.get(*name) != Some(value))
+        .map(|(name, _)| name.clone())
+        .collect();
+    changed.sort();
+    changed
+}
+
+fn encode(value: &DynamicValue) -> Result<proto::DynamicValue, String> {
+    Ok(proto::DynamicValue {
+        msgpack: value.encode_msgpack().map_err(|e| e.to_string())?,
+        json: vec![],
+    })
+}
+
+fn decode(value: Option<proto::DynamicValue>) -> Result<DynamicValue, String> {
+    match value {
+        Some(value) if !value.msgpack.is_empty() => {
+            DynamicValue::decode_msgpack(&value.msgpack).map_err(|e| e.to_string())
+        }
+        _ => Ok(DynamicValue::null()),
+    }
+}
+
+/// Fails with the error diagnostics, if there are any
+fn errors(context: &str, diagnostics: &[proto::Diagnostic]) -> Result<(), String> {
+    let errors: Vec<String> = diagnostics
+        .iter()
+        .filter(|d| d.severity == proto::diagnostic::Severity::Error as i32)
+        .map(|d| format!("{}: {}: {}", context, d.summary, d.detail))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// Ready-made checks
+pub mod check {
+    use super::{Check, TestState};
+    use crate::types::{AttributePath, Dynamic, DynamicValue};
+
+    fn value<'a>(state: &'a TestState, address: &str) -> Result<&'a DynamicValue, String> {
+        state
+            .get(address)
+            .ok_or_else(|| format!("{} is not in state", address))
+    }
+
+    /// The resource or data source is in state
+    pub fn exists(address: &str) -> Check {
+        let address = address.to_string();
+        Box::new(move |state| value(state, &address).map(|_| ()))
+    }
+
+    /// The attribute at `path` equals `expected`
+    pub fn attribute_equals(address: &str, path: AttributePath, expected: Dynamic) -> Check {
+        let address = address.to_string();
+        Box::new(move |state| {
+            let actual = value(state, &address)?
+                .get(&path)
+                .map_err(|e| format!("{}.{}: {}", address, path, e))?;
+            if *actual == expected {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{}.{} is {:?}, expected {:?}",
+                    address, path, actual, expected
+                ))
+            }
+        })
+    }
+
+    /// The attribute at `path` has a value
+    pub fn attribute_set(address: &str, path: AttributePath) -> Check {
+        let address = address.to_string();
+        Box::new(move |state| match value(state, &address)?.get(&path) {
+            Ok(Dynamic::Null) | Err(_) => Err(format!("{}.{} is not set", address, path)),
+            Ok(_) => Ok(()),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::schema::{AttributeBuilder, AttributeType, SchemaBuilder};
+
+    fn schema() -> Schema {
+        SchemaBuilder::new()
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("id", AttributeType::String)
+                    .computed()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("comment", AttributeType::String)
+                    .optional()
+                    .build(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn shape_config_adds_missing_attributes() {
+        let config = shape_config(&schema(), &serde_json::json!({"name": "a"})).unwrap();
+        let Dynamic::Map(values) = config.value else {
+            panic!("config must be an object");
+        };
+        assert_eq!(values["name"], Dynamic::String("a".to_string()));
+        assert_eq!(values["id"], Dynamic::Null);
+        assert_eq!(values["comment"], Dynamic::Null);
+    }
+
+    #[test]
+    fn proposed_new_keeps_computed_values() {
+        let schema = schema();
+        let prior = shape_config(
+            &schema,
+            &serde_json::json!({"name": "a", "id": "1", "comment": "x"}),
+        )
+        .unwrap();
+        let config = shape_config(&schema, &serde_json::json!({"name": "b"})).unwrap();
+
+        let Dynamic::Map(proposed) = proposed_new(&schema.block, Some(&prior.value), &config.value)
+        else {
+            panic!("proposed state must be an object");
+        };
+        assert_eq!(proposed["name"], Dynamic::String("b".to_string()));
+        assert_eq!(proposed["id"], Dynamic::String("1".to_string()));
+        assert_eq!(proposed["comment"], Dynamic::Null);
+        assert_eq!(
+            changed_attributes(&prior.value, &Dynamic::Map(proposed)),
+            vec!["comment", "name"]
+        );
+    }
+
+    #[test]
+    fn split_address_requires_type_and_name() {
+        assert_eq!(
+            split_address("proxmox_pool.test").unwrap(),
+            ("proxmox_pool", "test")
+        );
+        assert!(split_address("proxmox_pool").is_err());
+        assert!(split_address(".test").is_err());
+    }
+}
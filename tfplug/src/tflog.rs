@@ -0,0 +1,349 @@
+//! Structured logging in the format Terraform reads from providers
+//!
+//! Terraform reads provider stderr line by line. Lines in the JSON format of
+//! terraform-plugin-log (`@level`, `@message`, `@module`, `@timestamp` plus
+//! any fields) keep their level and fields in Terraform's log, anything else
+//! is logged as plain debug output. TfLogLayer writes tracing events in that
+//! format and filters them with the same environment variables as
+//! terraform-plugin-log:
+//!
+//! - `TF_LOG_PROVIDER` for the provider, `TF_LOG_PROVIDER_<SUBSYSTEM>` for a
+//!   subsystem of it
+//! - `TF_LOG_SDK_FRAMEWORK` and `TF_LOG_SDK` for tfplug itself
+//! - `TF_LOG` for all of them
+//!
+//! Providers log through a Context so the fields set on it, such as the
+//! RPC and resource type tfplug adds to every request, end up in each line:
+//!
+//! ```ignore
+//! let ctx = ctx.with_log_field("vmid", 100);
+//! tflog::debug(&ctx, "waiting for the guest agent");
+//! ```
+
+use crate::context::Context;
+use serde_json::{Map, Value};
+use std::fmt::Display;
+use std::io::Write;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Module of provider log lines
+pub const PROVIDER_MODULE: &str = "provider";
+/// Module of log lines from tfplug itself
+pub const FRAMEWORK_MODULE: &str = "sdk.framework";
+
+/// Event fields carrying the module and Context fields of lines logged
+/// through a Context
+const MODULE_FIELD: &str = "tf_module";
+const FIELDS_FIELD: &str = "tf_fields";
+
+/// Installs TfLogLayer writing to stderr as the global tracing subscriber
+pub fn init() {
+    let _ = tracing_subscriber::registry()
+        .with(TfLogLayer::new(LogLevels::from_env(), std::io::stderr))
+        .try_init();
+}
+
+pub fn trace(ctx: &Context, message: impl Display) {
+    log(ctx, Level::TRACE, message);
+}
+
+pub fn debug(ctx: &Context, message: impl Display) {
+    log(ctx, Level::DEBUG, message);
+}
+
+pub fn info(ctx: &Context, message: impl Display) {
+    log(ctx, Level::INFO, message);
+}
+
+pub fn warn(ctx: &Context, message: impl Display) {
+    log(ctx, Level::WARN, message);
+}
+
+pub fn error(ctx: &Context, message: impl Display) {
+    log(ctx, Level::ERROR, message);
+}
+
+fn log(ctx: &Context, level: Level, message: impl Display) {
+    let module = ctx.log_module();
+    let fields = Value::Object(ctx.log_fields().clone()).to_string();
+    // tracing needs the level at compile time, anything left is ERROR
+    match level {
+        Level::TRACE => {
+            tracing::trace!(tf_module = %module, tf_fields = %fields, "{}", message)
+        }
+        Level::DEBUG => {
+            tracing::debug!(tf_module = %module, tf_fields = %fields, "{}", message)
+        }
+        Level::INFO => tracing::info!(tf_module = %module, tf_fields = %fields, "{}", message),
+        Level::WARN => tracing::warn!(tf_module = %module, tf_fields = %fields, "{}", message),
+        _ => {
+            tracing::error!(tf_module = %module, tf_fields = %fields, "{}", message)
+        }
+    }
+}
+
+/// Log level filters per module, read from the TF_LOG variables
+#[derive(Debug, Clone)]
+pub struct LogLevels {
+    lookup: fn(&str) -> Option<String>,
+}
+
+impl LogLevels {
+    /// Levels from the process environment
+    pub fn from_env() -> Self {
+        Self {
+            lookup: |name| std::env::var(name).ok(),
+        }
+    }
+
+    /// Levels from `lookup` instead of the environment
+    pub fn from_lookup(lookup: fn(&str) -> Option<String>) -> Self {
+        Self { lookup }
+    }
+
+    /// The most verbose level logged for `module`, None when it's off.
+    /// Without any variable set everything is logged, Terraform filters by
+    /// its own TF_LOG.
+    pub fn level(&self, module: &str) -> Option<Level> {
+        let variables: Vec<String> = if module == FRAMEWORK_MODULE {
+            vec!["TF_LOG_SDK_FRAMEWORK".to_string(), "TF_LOG_SDK".to_string()]
+        } else {
+            let mut variables = vec!["TF_LOG_PROVIDER".to_string()];
+            if let Some(subsystem) = module.strip_prefix("provider.") {
+                variables.insert(0, format!("TF_LOG_PROVIDER_{}", subsystem.to_uppercase()));
+            }
+            variables
+        };
+
+        let setting = variables
+            .iter()
+            .map(String::as_str)
+            .chain(["TF_LOG"])
+            .find_map(|name| (self.lookup)(name).filter(|value| !value.is_empty()));
+        match setting.map(|value| value.to_uppercase()).as_deref() {
+            None | Some("TRACE") | Some("JSON") => Some(Level::TRACE),
+            Some("DEBUG") => Some(Level::DEBUG),
+            Some("INFO") => Some(Level::INFO),
+            Some("WARN") => Some(Level::WARN),
+            Some("ERROR") => Some(Level::ERROR),
+            Some("OFF") => None,
+            // Unknown levels log everything rather than hiding output
+            Some(_) => Some(Level::TRACE),
+        }
+    }
+}
+
+/// A tracing layer writing events as terraform-plugin-log JSON lines
+pub struct TfLogLayer<W> {
+    levels: LogLevels,
+    make_writer: W,
+}
+
+impl<W> TfLogLayer<W>
+where
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    pub fn new(levels: LogLevels, make_writer: W) -> Self {
+        Self {
+            levels,
+            make_writer,
+        }
+    }
+}
+
+/// Collects the fields of an event
+#[derive(Default)]
+struct JsonVisitor {
+    message: String,
+    module: Option<String>,
+    context_fields: Option<String>,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn record(&mut self, field: &Field, value: Value) {
+        match (field.name(), value) {
+            ("message", Value::String(message)) => self.message = message,
+            (MODULE_FIELD, Value::String(module)) => self.module = Some(module),
+            (FIELDS_FIELD, Value::String(fields)) => self.context_fields = Some(fields),
+            (name, value) => {
+                self.fields.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record(field, Value::String(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, Value::String(value.to_string()));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, Value::Bool(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, Value::from(value));
+    }
+}
+
+/// Module of an event logged without a Context
+fn target_module(target: &str) -> &'static str {
+    if target == "tfplug" || target.starts_with("tfplug::") {
+        FRAMEWORK_MODULE
+    } else {
+        PROVIDER_MODULE
+    }
+}
+
+impl<S, W> Layer<S> for TfLogLayer<W>
+where
+    S: Subscriber,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: LayerContext<'_, S>) {
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let module = visitor
+            .module
+            .take()
+            .unwrap_or_else(|| target_module(metadata.target()).to_string());
+        // Lower levels are more verbose in tracing
+        match self.levels.level(&module) {
+            Some(max) if *metadata.level() <= max => {}
+            _ => return,
+        }
+
+        let mut line = Map::new();
+        line.insert(
+            "@level".to_string(),
+            Value::String(metadata.level().as_str().to_lowercase()),
+        );
+        line.insert("@message".to_string(), Value::String(visitor.message));
+        line.insert("@module".to_string(), Value::String(module));
+        line.insert(
+            "@timestamp".to_string(),
+            Value::String(
+                chrono::Local::now()
+                    .format("%Y-%m-%dT%H:%M:%S%.6f%:z")
+                    .to_string(),
+            ),
+        );
+        if let Some(Ok(Value::Object(fields))) = visitor
+            .context_fields
+            .as_deref()
+            .map(serde_json::from_str::<Value>)
+        {
+            line.extend(fields);
+        }
+        line.extend(visitor.fields);
+
+        let mut writer = self.make_writer.make_writer();
+        let _ = writeln!(writer, "{}", Value::Object(line));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Lines logged by `log` with the levels of `lookup`
+    fn logged(lookup: fn(&str) -> Option<String>, log: impl FnOnce()) -> Vec<Value> {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::registry()
+            .with(TfLogLayer::new(LogLevels::from_lookup(lookup), move || {
+                writer.clone()
+            }));
+        tracing::subscriber::with_default(subscriber, log);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn context_loggers_write_terraform_plugin_log_lines() {
+        let lines = logged(
+            |_| None,
+            || {
+                let ctx = Context::new()
+                    .with_log_field("tf_resource_type", "proxmox_qemu_vm")
+                    .with_log_subsystem("qemu")
+                    .with_log_field("vmid", 100);
+                warn(&ctx, "agent not running");
+            },
+        );
+
+        let line = &lines[0];
+        assert_eq!(line["@level"], "warn");
+        assert_eq!(line["@message"], "agent not running");
+        assert_eq!(line["@module"], "provider.qemu");
+        assert_eq!(line["tf_resource_type"], "proxmox_qemu_vm");
+        assert_eq!(line["vmid"], 100);
+        assert!(line["@timestamp"].is_string());
+    }
+
+    #[test]
+    fn levels_follow_the_tf_log_variables() {
+        let lines = logged(
+            |name| match name {
+                "TF_LOG" => Some("TRACE".to_string()),
+                "TF_LOG_PROVIDER" => Some("INFO".to_string()),
+                "TF_LOG_PROVIDER_QEMU" => Some("off".to_string()),
+                _ => None,
+            },
+            || {
+                let ctx = Context::new();
+                debug(&ctx, "hidden by TF_LOG_PROVIDER");
+                info(&ctx, "provider info");
+                error(
+                    &ctx.clone().with_log_subsystem("qemu"),
+                    "hidden, qemu is off",
+                );
+                tracing::trace!(target: "tfplug::grpc", count = 3, "framework trace");
+            },
+        );
+
+        let messages: Vec<&Value> = lines.iter().map(|line| &line["@message"]).collect();
+        assert_eq!(messages, ["provider info", "framework trace"]);
+        assert_eq!(lines[1]["@module"], "sdk.framework");
+        assert_eq!(lines[1]["count"], 3);
+    }
+}
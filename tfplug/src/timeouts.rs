@@ -0,0 +1,248 @@
+//! Operation timeouts
+//!
+//! Resources with long running operations accept a standard `timeouts` block
+//! such as `timeouts { create = "30m" }`. Add it to the schema with
+//! `Timeouts::block` and read it back in the operation:
+//!
+//! ```ignore
+//! let timeout = Timeouts::new(&request.config).create(&ctx, Duration::from_secs(1800));
+//! ```
+//!
+//! The duration passed in is the resource's default for the operation. It
+//! applies unless the `timeouts` block sets the operation.
+
+use crate::context::Context;
+use crate::schema::{AttributeBuilder, AttributeType, Block, NestedBlock, NestingMode, StringKind};
+use crate::types::{AttributePath, DynamicValue};
+use crate::validator::DurationValidator;
+use std::time::{Duration, Instant};
+
+/// Name of the block holding the timeouts
+pub const BLOCK_NAME: &str = "timeouts";
+
+/// Operation a timeout applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Create,
+    Read,
+    Update,
+    Delete,
+}
+
+impl Operation {
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Create => "create",
+            Operation::Read => "read",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+        }
+    }
+}
+
+/// Timeouts configured in the `timeouts` block of a resource
+#[derive(Debug, Clone, Default)]
+pub struct Timeouts {
+    create: Option<Duration>,
+    read: Option<Duration>,
+    update: Option<Duration>,
+    delete: Option<Duration>,
+}
+
+impl Timeouts {
+    /// The `timeouts` block for a schema, with one attribute per operation
+    pub fn block(operations: &[Operation]) -> NestedBlock {
+        let attributes = operations
+            .iter()
+            .map(|operation| {
+                AttributeBuilder::new(operation.name(), AttributeType::String)
+                    .description(&format!(
+                        "How long to wait for {} to finish, as a duration such as \"30s\", \"10m\" or \"1h30m\"",
+                        operation.name()
+                    ))
+                    .optional()
+                    .validator(DurationValidator::create())
+                    .build()
+            })
+            .collect();
+
+        NestedBlock {
+            type_name: BLOCK_NAME.to_string(),
+            block: Block {
+                version: 0,
+                attributes,
+                block_types: vec![],
+                description: "Timeouts for long running operations".to_string(),
+                description_kind: StringKind::Plain,
+                deprecated: false,
+            },
+            nesting: NestingMode::Single,
+            min_items: 0,
+            max_items: 0,
+        }
+    }
+
+    /// Reads the `timeouts` block of a config, plan or state. Values that
+    /// are unset, unknown or invalid fall back to the operation default.
+    pub fn new(value: &DynamicValue) -> Self {
+        let get = |operation: Operation| {
+            value
+                .get_string(&AttributePath::new(BLOCK_NAME).attribute(operation.name()))
+                .ok()
+                .and_then(|timeout| parse_duration(&timeout).ok())
+        };
+        Self {
+            create: get(Operation::Create),
+            read: get(Operation::Read),
+            update: get(Operation::Update),
+            delete: get(Operation::Delete),
+        }
+    }
+
+    /// Timeout for create, or `default` when not configured
+    pub fn create(&self, ctx: &Context, default: Duration) -> Duration {
+        within_deadline(ctx, self.create.unwrap_or(default))
+    }
+
+    /// Timeout for read, or `default` when not configured
+    pub fn read(&self, ctx: &Context, default: Duration) -> Duration {
+        within_deadline(ctx, self.read.unwrap_or(default))
+    }
+
+    /// Timeout for update, or `default` when not configured
+    pub fn update(&self, ctx: &Context, default: Duration) -> Duration {
+        within_deadline(ctx, self.update.unwrap_or(default))
+    }
+
+    /// Timeout for delete, or `default` when not configured
+    pub fn delete(&self, ctx: &Context, default: Duration) -> Duration {
+        within_deadline(ctx, self.delete.unwrap_or(default))
+    }
+}
+
+/// Caps a timeout to the time left before the deadline of the context
+fn within_deadline(ctx: &Context, timeout: Duration) -> Duration {
+    match ctx.deadline() {
+        Some(deadline) => timeout.min(deadline.saturating_duration_since(Instant::now())),
+        None => timeout,
+    }
+}
+
+/// Parses a duration as written in Terraform configurations: a sequence of
+/// numbers with a unit of h, m, s or ms, such as "1h30m" or "1.5s"
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || {
+        format!(
+            "\"{}\" is not a duration, use a number with a unit of h, m, s or ms such as \"10m\" or \"1h30m\"",
+            value
+        )
+    };
+
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_end].parse().map_err(|_| invalid())?;
+        rest = &rest[number_end..];
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds = match &rest[..unit_end] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit_end..];
+        total += number * seconds;
+    }
+
+    Duration::try_from_secs_f64(total).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::types::Dynamic;
+    use std::collections::HashMap;
+
+    fn config(timeouts: Dynamic) -> DynamicValue {
+        DynamicValue::new(Dynamic::Map(HashMap::from([(
+            BLOCK_NAME.to_string(),
+            timeouts,
+        )])))
+    }
+
+    #[test]
+    fn parse_duration_accepts_terraform_durations() {
+        assert_eq!(parse_duration("45s").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("1.5s").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+
+        for invalid in [
+            "",
+            "10",
+            "m",
+            "10x",
+            "1h-5m",
+            "1..5s",
+            "99999999999999999999999h",
+        ] {
+            assert!(parse_duration(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn timeouts_fall_back_to_defaults() {
+        let ctx = Context::new();
+        let default = Duration::from_secs(60);
+        let timeouts = Timeouts::new(&config(Dynamic::Map(HashMap::from([
+            ("create".to_string(), Dynamic::String("30m".to_string())),
+            ("update".to_string(), Dynamic::Null),
+            ("delete".to_string(), Dynamic::Unknown),
+        ]))));
+
+        assert_eq!(timeouts.create(&ctx, default), Duration::from_secs(1800));
+        assert_eq!(timeouts.update(&ctx, default), default);
+        assert_eq!(timeouts.delete(&ctx, default), default);
+        assert_eq!(timeouts.read(&ctx, default), default);
+
+        let unset = Timeouts::new(&config(Dynamic::Null));
+        assert_eq!(unset.create(&ctx, default), default);
+    }
+
+    #[tokio::test]
+    async fn timeouts_stay_within_the_context_deadline() {
+        let ctx = Context::new().with_timeout(Duration::from_secs(5));
+        let timeouts = Timeouts::new(&DynamicValue::null());
+
+        assert!(timeouts.create(&ctx, Duration::from_secs(600)) <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn block_has_one_optional_attribute_per_operation() {
+        let block = Timeouts::block(&[Operation::Create, Operation::Delete]);
+
+        assert_eq!(block.type_name, "timeouts");
+        assert_eq!(block.nesting, NestingMode::Single);
+        let names: Vec<&str> = block
+            .block
+            .attributes
+            .iter()
+            .map(|attribute| attribute.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["create", "delete"]);
+        assert!(block.block.attributes.iter().all(|a| a.optional));
+    }
+}
@@ -28,6 +28,24 @@ pub enum Dynamic {
     Unknown,
 }
 
+/// msgpack extension types cty encodes unknown values with. Type 0 is a
+/// plain unknown, type 12 an unknown carrying refinements, such as a prefix
+/// or not being null, which the framework doesn't track.
+const MSGPACK_UNKNOWN_EXT: i8 = 0;
+const MSGPACK_REFINED_UNKNOWN_EXT: i8 = 12;
+
+/// Payload of an msgpack extension value, serialized as raw bytes
+struct ExtData<'a>(&'a [u8]);
+
+impl Serialize for ExtData<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
 impl Serialize for Dynamic {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
@@ -40,7 +58,15 @@ impl Serialize for Dynamic {
             Dynamic::String(s) => serializer.serialize_str(s),
             Dynamic::List(l) => l.serialize(serializer),
             Dynamic::Map(m) => m.serialize(serializer),
-            Dynamic::Unknown => serializer.serialize_str("__unknown__"),
+            // JSON has no unknown, a placeholder string keeps it round
+            // tripping
+            Dynamic::Unknown if serializer.is_human_readable() => {
+                serializer.serialize_str("__unknown__")
+            }
+            Dynamic::Unknown => serializer.serialize_newtype_struct(
+                rmp_serde::MSGPACK_EXT_STRUCT_NAME,
+                &(MSGPACK_UNKNOWN_EXT, ExtData(&[0])),
+            ),
         }
     }
 }
@@ -53,7 +79,9 @@ impl<'de> Deserialize<'de> for Dynamic {
         use serde::de::{self, Visitor};
         use std::fmt;
 
-        struct DynamicVisitor;
+        struct DynamicVisitor {
+            human_readable: bool,
+        }
 
         impl<'de> Visitor<'de> for DynamicVisitor {
             type Value = Dynamic;
@@ -101,7 +129,7 @@ impl<'de> Deserialize<'de> for Dynamic {
             where
                 E: de::Error,
             {
-                if value == "__unknown__" {
+                if self.human_readable && value == "__unknown__" {
                     Ok(Dynamic::Unknown)
                 } else {
                     Ok(Dynamic::String(value.to_string()))
@@ -112,13 +140,52 @@ impl<'de> Deserialize<'de> for Dynamic {
             where
                 E: de::Error,
             {
-                if value == "__unknown__" {
+                if self.human_readable && value == "__unknown__" {
                     Ok(Dynamic::Unknown)
                 } else {
                     Ok(Dynamic::String(value))
                 }
             }
 
+            /// msgpack extension values arrive as a newtype holding the
+            /// extension type and its payload
+            fn visit_newtype_struct<D>(
+                self,
+                deserializer: D,
+            ) -> std::result::Result<Dynamic, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct ExtVisitor;
+
+                impl<'de> Visitor<'de> for ExtVisitor {
+                    type Value = i8;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("an msgpack extension value")
+                    }
+
+                    fn visit_seq<V>(self, mut seq: V) -> std::result::Result<i8, V::Error>
+                    where
+                        V: de::SeqAccess<'de>,
+                    {
+                        let ext_type = seq
+                            .next_element::<i8>()?
+                            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                        seq.next_element::<de::IgnoredAny>()?;
+                        Ok(ext_type)
+                    }
+                }
+
+                match deserializer.deserialize_any(ExtVisitor)? {
+                    MSGPACK_UNKNOWN_EXT | MSGPACK_REFINED_UNKNOWN_EXT => Ok(Dynamic::Unknown),
+                    other => Err(de::Error::custom(format!(
+                        "unsupported msgpack extension type {}",
+                        other
+                    ))),
+                }
+            }
+
             fn visit_seq<V>(self, mut seq: V) -> std::result::Result<Dynamic, V::Error>
             where
                 V: de::SeqAccess<'de>,
@@ -142,7 +209,8 @@ impl<'de> Deserialize<'de> for Dynamic {
             }
         }
 
-        deserializer.deserialize_any(DynamicVisitor)
+        let human_readable = deserializer.is_human_readable();
+        deserializer.deserialize_any(DynamicVisitor { human_readable })
     }
 }
 
@@ -227,9 +295,11 @@ impl DynamicValue {
     }
 
     /// Type-safe accessors - ALWAYS use these instead of pattern matching
-    /// These handle path navigation and type checking
+    /// These handle path navigation and type checking. A missing attribute,
+    /// a null and an unknown value fail with AttributeNotFound, NullValue
+    /// and UnknownValue respectively, so callers can tell them apart.
     pub fn get_string(&self, path: &AttributePath) -> Result<String> {
-        let value = self.navigate_path(path)?;
+        let value = self.known(path)?;
         match value {
             Dynamic::String(s) => Ok(s.clone()),
             _ => Err(TfplugError::TypeMismatch {
@@ -240,7 +310,7 @@ impl DynamicValue {
     }
 
     pub fn get_number(&self, path: &AttributePath) -> Result<f64> {
-        let value = self.navigate_path(path)?;
+        let value = self.known(path)?;
         match value {
             Dynamic::Number(n) => Ok(*n),
             _ => Err(TfplugError::TypeMismatch {
@@ -250,8 +320,37 @@ impl DynamicValue {
         }
     }
 
+    /// Reads a number that must be a whole number. Fractional values are
+    /// rejected instead of being silently truncated.
+    pub fn get_i64(&self, path: &AttributePath) -> Result<i64> {
+        let n = self.get_number(path)?;
+        if n.fract() != 0.0 || !(i64::MIN as f64..=i64::MAX as f64).contains(&n) {
+            return Err(TfplugError::TypeMismatch {
+                expected: "integer".to_string(),
+                actual: n.to_string(),
+            });
+        }
+        Ok(n as i64)
+    }
+
+    pub fn get_u32(&self, path: &AttributePath) -> Result<u32> {
+        let n = self.get_i64(path)?;
+        u32::try_from(n).map_err(|_| TfplugError::TypeMismatch {
+            expected: "unsigned 32-bit integer".to_string(),
+            actual: n.to_string(),
+        })
+    }
+
+    pub fn get_u64(&self, path: &AttributePath) -> Result<u64> {
+        let n = self.get_i64(path)?;
+        u64::try_from(n).map_err(|_| TfplugError::TypeMismatch {
+            expected: "unsigned integer".to_string(),
+            actual: n.to_string(),
+        })
+    }
+
     pub fn get_bool(&self, path: &AttributePath) -> Result<bool> {
-        let value = self.navigate_path(path)?;
+        let value = self.known(path)?;
         match value {
             Dynamic::Bool(b) => Ok(*b),
             _ => Err(TfplugError::TypeMismatch {
@@ -262,7 +361,7 @@ impl DynamicValue {
     }
 
     pub fn get_list(&self, path: &AttributePath) -> Result<Vec<Dynamic>> {
-        let value = self.navigate_path(path)?;
+        let value = self.known(path)?;
         match value {
             Dynamic::List(l) => Ok(l.clone()),
             _ => Err(TfplugError::TypeMismatch {
@@ -273,7 +372,7 @@ impl DynamicValue {
     }
 
     pub fn get_map(&self, path: &AttributePath) -> Result<HashMap<String, Dynamic>> {
-        let value = self.navigate_path(path)?;
+        let value = self.known(path)?;
         match value {
             Dynamic::Map(m) => Ok(m.clone()),
             _ => Err(TfplugError::TypeMismatch {
@@ -292,6 +391,10 @@ impl DynamicValue {
         self.set_value(path, Dynamic::Number(value))
     }
 
+    pub fn set_i64(&mut self, path: &AttributePath, value: i64) -> Result<()> {
+        self.set_value(path, Dynamic::Number(value as f64))
+    }
+
     pub fn set_bool(&mut self, path: &AttributePath, value: bool) -> Result<()> {
         self.set_value(path, Dynamic::Bool(value))
     }
@@ -304,6 +407,11 @@ impl DynamicValue {
         self.set_value(path, Dynamic::Map(value))
     }
 
+    /// Clears an attribute, e.g. when the remote object no longer has a value
+    pub fn set_null(&mut self, path: &AttributePath) -> Result<()> {
+        self.set_value(path, Dynamic::Null)
+    }
+
     /// Helpers for handling unknown values during planning
     pub fn is_null(&self) -> bool {
         matches!(self.value, Dynamic::Null)
@@ -313,27 +421,55 @@ impl DynamicValue {
         matches!(self.value, Dynamic::Unknown)
     }
 
+    /// Whether the value at `path` is null. Missing attributes are not null.
+    pub fn is_null_at(&self, path: &AttributePath) -> bool {
+        matches!(self.navigate_path(path), Ok(Dynamic::Null))
+    }
+
+    /// Whether the value at `path`, or an object or collection containing
+    /// it, is unknown
+    pub fn is_unknown_at(&self, path: &AttributePath) -> bool {
+        matches!(self.navigate_path(path), Ok(Dynamic::Unknown))
+    }
+
     /// Mark computed values as unknown during planning
     pub fn mark_unknown(&mut self, path: &AttributePath) -> Result<()> {
         self.set_value(path, Dynamic::Unknown)
     }
 
     // Private helper methods
+    /// The value at `path`, whatever its type
+    pub fn get(&self, path: &AttributePath) -> Result<&Dynamic> {
+        self.navigate_path(path)
+    }
+
+    /// The value at `path`, which must be neither null nor unknown
+    fn known(&self, path: &AttributePath) -> Result<&Dynamic> {
+        match self.navigate_path(path)? {
+            Dynamic::Null => Err(TfplugError::NullValue(path.to_string())),
+            Dynamic::Unknown => Err(TfplugError::UnknownValue(path.to_string())),
+            value => Ok(value),
+        }
+    }
+
+    /// Walks `path`. Anything below a null or unknown value is null or
+    /// unknown as well.
     fn navigate_path<'a>(&'a self, path: &AttributePath) -> Result<&'a Dynamic> {
         let mut current = &self.value;
 
         for step in &path.steps {
             current = match (current, step) {
-                (Dynamic::Map(m), AttributePathStep::AttributeName(name)) => {
-                    m.get(name).ok_or_else(|| {
-                        TfplugError::Custom(format!("attribute '{}' not found", name))
-                    })?
-                }
+                (Dynamic::Null | Dynamic::Unknown, _) => return Ok(current),
+                (
+                    Dynamic::Map(m),
+                    AttributePathStep::AttributeName(key)
+                    | AttributePathStep::ElementKeyString(key),
+                ) => m
+                    .get(key)
+                    .ok_or_else(|| TfplugError::AttributeNotFound(path.to_string()))?,
                 (Dynamic::List(l), AttributePathStep::ElementKeyInt(idx)) => {
-                    let idx = *idx as usize;
-                    l.get(idx).ok_or_else(|| {
-                        TfplugError::Custom(format!("list index {} out of bounds", idx))
-                    })?
+                    l.get(*idx as usize)
+                        .ok_or_else(|| TfplugError::AttributeNotFound(path.to_string()))?
                 }
                 _ => return Err(TfplugError::Custom("invalid path navigation".to_string())),
             };
@@ -360,7 +496,11 @@ impl DynamicValue {
             if idx == last_idx {
                 // Set the final value
                 match (current, step) {
-                    (Dynamic::Map(m), AttributePathStep::AttributeName(name)) => {
+                    (
+                        Dynamic::Map(m),
+                        AttributePathStep::AttributeName(name)
+                        | AttributePathStep::ElementKeyString(name),
+                    ) => {
                         m.insert(name.clone(), new_value);
                         return Ok(());
                     }
@@ -380,7 +520,11 @@ impl DynamicValue {
             } else {
                 // Navigate to the next level
                 current = match (current, step) {
-                    (Dynamic::Map(m), AttributePathStep::AttributeName(name)) => {
+                    (
+                        Dynamic::Map(m),
+                        AttributePathStep::AttributeName(name)
+                        | AttributePathStep::ElementKeyString(name),
+                    ) => {
                         m.entry(name.clone()).or_insert_with(|| {
                             // Determine what to insert based on next step
                             if let Some(next_step) = path.steps.get(idx + 1) {
@@ -466,6 +610,22 @@ impl AttributePath {
     }
 }
 
+/// Renders the path the way it is written in configuration, such as
+/// `disk[0].size` or `tags["env"]`
+impl std::fmt::Display for AttributePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            match step {
+                AttributePathStep::AttributeName(name) if i == 0 => write!(f, "{}", name)?,
+                AttributePathStep::AttributeName(name) => write!(f, ".{}", name)?,
+                AttributePathStep::ElementKeyString(key) => write!(f, "[{:?}]", key)?,
+                AttributePathStep::ElementKeyInt(idx) => write!(f, "[{}]", idx)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Individual step in an AttributePath
 #[derive(Debug, Clone, PartialEq)]
 pub enum AttributePathStep {
@@ -520,6 +680,42 @@ impl PrivateStateData {
         })?;
         Ok(Self { data })
     }
+
+    /// Decodes the private bytes of a request. Terraform sends none for new
+    /// resources and for resources whose provider never set any, so empty
+    /// bytes are empty private state.
+    pub fn from_private(data: &[u8]) -> Result<Self> {
+        if data.is_empty() {
+            Ok(Self::new())
+        } else {
+            Self::decode(data)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Typed value stored under `key` by set, None when the key isn't set
+    pub fn get<T: serde::de::DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.get_key(key)
+            .map(|value| {
+                serde_json::from_slice(value).map_err(|e| {
+                    TfplugError::DecodingError(format!("private state key '{}': {}", key, e))
+                })
+            })
+            .transpose()
+    }
+
+    /// Stores a value under `key` as JSON, the format terraform-plugin-framework
+    /// uses for private state values
+    pub fn set<T: Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        let value = serde_json::to_vec(value).map_err(|e| {
+            TfplugError::EncodingError(format!("private state key '{}': {}", key, e))
+        })?;
+        self.set_key(key, value);
+        Ok(())
+    }
 }
 
 impl Default for PrivateStateData {
@@ -563,10 +759,39 @@ impl Diagnostic {
         }
     }
 
+    /// An error about the attribute at `path`, which Terraform reports
+    /// against the matching line of configuration
+    pub fn attribute_error(
+        path: AttributePath,
+        summary: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self::error(summary, detail).with_attribute(path)
+    }
+
+    /// A warning about the attribute at `path`
+    pub fn attribute_warning(
+        path: AttributePath,
+        summary: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self::warning(summary, detail).with_attribute(path)
+    }
+
     pub fn with_attribute(mut self, path: AttributePath) -> Self {
         self.attribute = Some(path);
         self
     }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+/// Whether any of the diagnostics is an error. Warnings alone do not fail
+/// an operation.
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(Diagnostic::is_error)
 }
 
 /// Severity level for diagnostics
@@ -624,6 +849,39 @@ pub struct IdentityAttribute {
     pub description: String,
 }
 
+impl IdentityAttribute {
+    /// Create an identity attribute of the given type, neither required
+    /// nor optional for import
+    pub fn new(name: &str, type_: crate::schema::AttributeType) -> Self {
+        Self {
+            name: name.to_string(),
+            type_: type_.type_json().to_string().into_bytes(),
+            required_for_import: false,
+            optional_for_import: false,
+            description: String::new(),
+        }
+    }
+
+    /// Must be given when importing by identity
+    pub fn required_for_import(mut self) -> Self {
+        self.required_for_import = true;
+        self
+    }
+
+    /// May be given when importing by identity, the provider fills it in
+    /// otherwise
+    pub fn optional_for_import(mut self) -> Self {
+        self.optional_for_import = true;
+        self
+    }
+
+    /// Set description
+    pub fn description(mut self, desc: &str) -> Self {
+        self.description = desc.to_string();
+        self
+    }
+}
+
 /// ResourceIdentityData contains actual identity data
 #[derive(Debug, Clone)]
 pub struct ResourceIdentityData {
@@ -644,6 +902,7 @@ pub type Config = DynamicValue;
 pub type State = DynamicValue;
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)]
 mod tests {
     use super::*;
 
@@ -668,6 +927,23 @@ mod tests {
         assert_eq!(result, "https://example.com");
     }
 
+    #[test]
+    fn dynamic_value_integer_access() {
+        let mut dv = DynamicValue::new(Dynamic::Map(HashMap::new()));
+        dv.set_i64(&AttributePath::new("vmid"), 100).unwrap();
+        dv.set_number(&AttributePath::new("cores"), 2.5).unwrap();
+        dv.set_number(&AttributePath::new("offset"), -1.0).unwrap();
+
+        assert_eq!(dv.get_u32(&AttributePath::new("vmid")).unwrap(), 100);
+        assert_eq!(dv.get_i64(&AttributePath::new("offset")).unwrap(), -1);
+        assert!(matches!(
+            dv.get_i64(&AttributePath::new("cores")),
+            Err(TfplugError::TypeMismatch { .. })
+        ));
+        assert!(dv.get_u32(&AttributePath::new("offset")).is_err());
+        assert!(dv.get_u64(&AttributePath::new("offset")).is_err());
+    }
+
     #[test]
     fn private_state_encoding() {
         let mut ps = PrivateStateData::new();
@@ -678,4 +954,122 @@ mod tests {
 
         assert_eq!(decoded.get_key("etag"), Some(&b"12345"[..]));
     }
+
+    #[test]
+    fn private_state_typed_values() {
+        let mut ps = PrivateStateData::from_private(&[]).unwrap();
+        assert!(ps.is_empty());
+        ps.set("digest", &"abc123").unwrap();
+        ps.set("lineage", &vec![1, 2]).unwrap();
+
+        let decoded = PrivateStateData::from_private(&ps.encode().unwrap()).unwrap();
+        assert_eq!(
+            decoded.get::<String>("digest").unwrap(),
+            Some("abc123".to_string())
+        );
+        assert_eq!(
+            decoded.get::<Vec<u32>>("lineage").unwrap(),
+            Some(vec![1, 2])
+        );
+        assert_eq!(decoded.get::<String>("missing").unwrap(), None);
+        assert!(decoded.get::<u32>("digest").is_err());
+    }
+
+    #[test]
+    fn dynamic_value_map_key_access() {
+        let mut dv = DynamicValue::new(Dynamic::Map(HashMap::new()));
+        let path = AttributePath::new("disks").key("scsi0").attribute("size");
+        dv.set_string(&path, "10G".to_string()).unwrap();
+
+        assert_eq!(dv.get_string(&path).unwrap(), "10G");
+        assert!(dv
+            .get_map(&AttributePath::new("disks"))
+            .unwrap()
+            .contains_key("scsi0"));
+        assert!(dv
+            .get_string(&AttributePath::new("disks").key("scsi1").attribute("size"))
+            .is_err());
+
+        let encoded = dv.encode_msgpack().unwrap();
+        assert_eq!(DynamicValue::decode_msgpack(&encoded).unwrap(), dv);
+    }
+
+    #[test]
+    fn unknown_values_use_the_cty_msgpack_extension() {
+        let mut dv = DynamicValue::new(Dynamic::Map(HashMap::new()));
+        dv.mark_unknown(&AttributePath::new("macaddr")).unwrap();
+
+        let encoded = dv.encode_msgpack().unwrap();
+        // {"macaddr": fixext1 type 0}
+        assert_eq!(&encoded[encoded.len() - 3..], &[0xd4, 0x00, 0x00]);
+        assert_eq!(DynamicValue::decode_msgpack(&encoded).unwrap(), dv);
+
+        // A refined unknown, known to be not null
+        let refined = [0x81, 0xa1, b'a', 0xd5, 0x0c, 0x81, 0x01];
+        let decoded = DynamicValue::decode_msgpack(&refined).unwrap();
+        assert!(decoded.is_unknown_at(&AttributePath::new("a")));
+
+        // Only JSON treats the placeholder string as unknown
+        let mut placeholder = DynamicValue::new(Dynamic::Map(HashMap::new()));
+        placeholder
+            .set_string(&AttributePath::new("name"), "__unknown__".to_string())
+            .unwrap();
+        let encoded = placeholder.encode_msgpack().unwrap();
+        assert_eq!(DynamicValue::decode_msgpack(&encoded).unwrap(), placeholder);
+    }
+
+    #[test]
+    fn accessors_tell_missing_null_and_unknown_apart() {
+        let dv = DynamicValue::new(Dynamic::Map(HashMap::from([
+            ("name".to_string(), Dynamic::Null),
+            ("macaddr".to_string(), Dynamic::Unknown),
+            ("network".to_string(), Dynamic::Unknown),
+        ])));
+
+        assert!(matches!(
+            dv.get_string(&AttributePath::new("missing")),
+            Err(TfplugError::AttributeNotFound(path)) if path == "missing"
+        ));
+        assert!(matches!(
+            dv.get_string(&AttributePath::new("name")),
+            Err(TfplugError::NullValue(_))
+        ));
+        assert!(matches!(
+            dv.get_string(&AttributePath::new("macaddr")),
+            Err(TfplugError::UnknownValue(_))
+        ));
+
+        let nested = AttributePath::new("network").index(0).attribute("macaddr");
+        assert!(dv.is_unknown_at(&nested));
+        assert!(matches!(
+            dv.get_string(&nested),
+            Err(TfplugError::UnknownValue(path)) if path == "network[0].macaddr"
+        ));
+        assert!(dv.is_null_at(&AttributePath::new("name")));
+        assert!(!dv.is_null_at(&AttributePath::new("missing")));
+    }
+
+    #[test]
+    fn attribute_path_display() {
+        let path = AttributePath::new("disk").index(0).attribute("size");
+        assert_eq!(path.to_string(), "disk[0].size");
+        assert_eq!(
+            AttributePath::new("tags").key("env").to_string(),
+            "tags[\"env\"]"
+        );
+        assert_eq!(AttributePath::root().to_string(), "");
+    }
+
+    #[test]
+    fn attribute_diagnostics() {
+        let path = AttributePath::new("cdrom").index(1).attribute("iso");
+        let error = Diagnostic::attribute_error(path.clone(), "Invalid cdrom iso", "bad");
+        assert!(error.is_error());
+        assert_eq!(error.attribute, Some(path.clone()));
+
+        let warning = Diagnostic::attribute_warning(path, "Deprecated", "old");
+        assert!(!warning.is_error());
+        assert!(!has_errors(&[warning.clone()]));
+        assert!(has_errors(&[warning, error]));
+    }
 }
@@ -0,0 +1,461 @@
+//! State upgrade helpers
+//!
+//! A resource that bumps its schema version describes how stored state moves
+//! forward, one version at a time, with a StateUpgrader per prior version.
+//! When Terraform sends state written under an older version, the framework
+//! decodes it, chains the upgraders up to the current version and shapes the
+//! result to the current schema.
+
+use crate::resource::UpgradeResourceStateResponse;
+use crate::schema::{Attribute, Block, NestingMode, ObjectNestingMode, Schema};
+use crate::types::{Diagnostic, Dynamic, DynamicValue, RawState};
+use std::collections::HashMap;
+
+type UpgradeFn = dyn Fn(DynamicValue) -> Result<DynamicValue, Vec<Diagnostic>> + Send + Sync;
+
+/// Upgrades state written under one schema version to the next version
+pub struct StateUpgrader {
+    /// Schema version of the state this upgrader reads
+    pub from_version: i64,
+    upgrade: Box<UpgradeFn>,
+}
+
+impl StateUpgrader {
+    /// Create an upgrader turning state of `from_version` into state of
+    /// `from_version + 1`. The function receives the state as stored, so
+    /// attributes removed since then are still present.
+    pub fn new<F>(from_version: i64, upgrade: F) -> Self
+    where
+        F: Fn(DynamicValue) -> Result<DynamicValue, Vec<Diagnostic>> + Send + Sync + 'static,
+    {
+        Self {
+            from_version,
+            upgrade: Box::new(upgrade),
+        }
+    }
+}
+
+/// Upgrades raw state stored under `version` to the version of `schema` by
+/// running the upgrader of each version in between. State already at that
+/// version is only shaped to the schema.
+pub fn upgrade_state(
+    upgraders: &[StateUpgrader],
+    schema: &Schema,
+    version: i64,
+    raw_state: &RawState,
+) -> UpgradeResourceStateResponse {
+    let upgraded = run_upgraders(upgraders, schema, version, raw_state)
+        .and_then(|state| conform_to_block(&schema.block, state).map_err(|d| vec![d]));
+    match upgraded {
+        Ok(state) => UpgradeResourceStateResponse {
+            upgraded_state: state,
+            diagnostics: vec![],
+        },
+        Err(diagnostics) => UpgradeResourceStateResponse {
+            upgraded_state: DynamicValue::null(),
+            diagnostics,
+        },
+    }
+}
+
+fn run_upgraders(
+    upgraders: &[StateUpgrader],
+    schema: &Schema,
+    version: i64,
+    raw_state: &RawState,
+) -> Result<DynamicValue, Vec<Diagnostic>> {
+    let json = raw_state.json.as_ref().ok_or_else(|| {
+        vec![Diagnostic::error(
+            "Unsupported state format",
+            "Only JSON state can be upgraded, the stored state is in the legacy flatmap format",
+        )]
+    })?;
+    let mut state = DynamicValue::decode_json(json).map_err(|e| {
+        vec![Diagnostic::error(
+            "Failed to decode state",
+            format!("Could not decode the stored state: {}", e),
+        )]
+    })?;
+
+    if version > schema.version {
+        return Err(vec![Diagnostic::error(
+            "State from a newer provider",
+            format!(
+                "The state was written with schema version {} but this provider only knows up to version {}. Upgrade the provider.",
+                version, schema.version
+            ),
+        )]);
+    }
+
+    for from_version in version..schema.version {
+        let upgrader = upgraders
+            .iter()
+            .find(|u| u.from_version == from_version)
+            .ok_or_else(|| {
+                vec![Diagnostic::error(
+                    "Missing state upgrader",
+                    format!(
+                        "No state upgrader from schema version {} to {}",
+                        from_version,
+                        from_version + 1
+                    ),
+                )]
+            })?;
+        state = (upgrader.upgrade)(state)?;
+    }
+
+    Ok(state)
+}
+
+/// Shapes upgraded state to a block: attributes the schema no longer has are
+/// dropped and missing ones are set to null, as Terraform expects every
+/// attribute of the current schema to be present. A block holding a value of
+/// another shape, usually an attribute that became a block without an
+/// upgrader, is an error rather than state Terraform can't decode.
+pub(crate) fn conform_to_block(
+    block: &Block,
+    state: DynamicValue,
+) -> Result<DynamicValue, Diagnostic> {
+    conform_value(block, state.value, "")
+        .map(DynamicValue::new)
+        .map_err(|detail| Diagnostic::error("State does not match the schema", detail))
+}
+
+fn conform_value(block: &Block, value: Dynamic, path: &str) -> Result<Dynamic, String> {
+    let mut values = match value {
+        Dynamic::Map(values) => values,
+        Dynamic::Null | Dynamic::Unknown => return Ok(value),
+        other => {
+            return Err(format!(
+                "Block '{}' holds a {} where an object is expected",
+                path,
+                kind(&other)
+            ))
+        }
+    };
+
+    let mut conformed: HashMap<String, Dynamic> = block
+        .attributes
+        .iter()
+        .map(|attribute| {
+            let value = values.remove(&attribute.name).unwrap_or(Dynamic::Null);
+            (attribute.name.clone(), conform_attribute(attribute, value))
+        })
+        .collect();
+
+    for nested in &block.block_types {
+        let path = if path.is_empty() {
+            nested.type_name.clone()
+        } else {
+            format!("{}.{}", path, nested.type_name)
+        };
+        let value = values.remove(&nested.type_name).unwrap_or(Dynamic::Null);
+        let value = match (nested.nesting, value) {
+            (NestingMode::List | NestingMode::Set, Dynamic::List(items)) => Dynamic::List(
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, item)| {
+                        conform_value(&nested.block, item, &format!("{}[{}]", path, index))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            (NestingMode::List | NestingMode::Set, Dynamic::Null) => Dynamic::List(vec![]),
+            (NestingMode::Map, Dynamic::Map(items)) => Dynamic::Map(
+                items
+                    .into_iter()
+                    .map(|(key, item)| {
+                        let item =
+                            conform_value(&nested.block, item, &format!("{}.{}", path, key))?;
+                        Ok((key, item))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+            (NestingMode::Map, Dynamic::Null) => Dynamic::Map(HashMap::new()),
+            (NestingMode::Group, Dynamic::Null) => {
+                conform_value(&nested.block, Dynamic::Map(HashMap::new()), &path)?
+            }
+            (NestingMode::Single | NestingMode::Group, value) => {
+                conform_value(&nested.block, value, &path)?
+            }
+            (_, value) => {
+                return Err(format!(
+                    "Block '{}' holds a {} where a collection of blocks is expected",
+                    path,
+                    kind(&value)
+                ))
+            }
+        };
+        conformed.insert(nested.type_name.clone(), value);
+    }
+
+    Ok(Dynamic::Map(conformed))
+}
+
+fn kind(value: &Dynamic) -> &'static str {
+    match value {
+        Dynamic::Null => "null",
+        Dynamic::Bool(_) => "bool",
+        Dynamic::Number(_) => "number",
+        Dynamic::String(_) => "string",
+        Dynamic::List(_) => "list",
+        Dynamic::Map(_) => "map",
+        Dynamic::Unknown => "unknown value",
+    }
+}
+
+/// Shapes the objects of a nested attribute like conform_value does for
+/// blocks. Unlike blocks, nested attributes may be null.
+fn conform_attribute(attribute: &Attribute, value: Dynamic) -> Dynamic {
+    let nested = match &attribute.nested_type {
+        Some(nested) => nested,
+        None => return value,
+    };
+    let conform_object = |value: Dynamic| match value {
+        Dynamic::Map(mut values) => Dynamic::Map(
+            nested
+                .attributes
+                .iter()
+                .map(|attribute| {
+                    let value = values.remove(&attribute.name).unwrap_or(Dynamic::Null);
+                    (attribute.name.clone(), conform_attribute(attribute, value))
+                })
+                .collect(),
+        ),
+        other => other,
+    };
+
+    match (nested.nesting, value) {
+        (ObjectNestingMode::List | ObjectNestingMode::Set, Dynamic::List(items)) => {
+            Dynamic::List(items.into_iter().map(conform_object).collect())
+        }
+        (ObjectNestingMode::Map, Dynamic::Map(items)) => Dynamic::Map(
+            items
+                .into_iter()
+                .map(|(key, item)| (key, conform_object(item)))
+                .collect(),
+        ),
+        (_, value) => conform_object(value),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)]
+mod tests {
+    use super::*;
+    use crate::schema::{AttributeBuilder, AttributeType, NestedBlock, SchemaBuilder};
+    use crate::types::AttributePath;
+
+    /// Version 2 schema: `tags` became a list and the `disk` blocks gained
+    /// a `ssd` flag
+    fn schema() -> Schema {
+        let disk = Block {
+            version: 0,
+            attributes: vec![
+                AttributeBuilder::new("size", AttributeType::String)
+                    .optional()
+                    .build(),
+                AttributeBuilder::new("ssd", AttributeType::Bool)
+                    .optional()
+                    .build(),
+            ],
+            block_types: vec![],
+            description: String::new(),
+            description_kind: crate::schema::StringKind::Plain,
+            deprecated: false,
+        };
+        SchemaBuilder::new()
+            .version(2)
+            .attribute(
+                AttributeBuilder::new("name", AttributeType::String)
+                    .required()
+                    .build(),
+            )
+            .attribute(
+                AttributeBuilder::new("tags", AttributeType::List(Box::new(AttributeType::String)))
+                    .optional()
+                    .build(),
+            )
+            .block(NestedBlock {
+                type_name: "disk".to_string(),
+                block: disk,
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 0,
+            })
+            .build()
+    }
+
+    fn upgraders() -> Vec<StateUpgrader> {
+        vec![
+            StateUpgrader::new(0, |mut state| {
+                let tags = state
+                    .get_string(&AttributePath::new("tags"))
+                    .unwrap_or_default();
+                let tags = tags
+                    .split(';')
+                    .filter(|tag| !tag.is_empty())
+                    .map(|tag| Dynamic::String(tag.to_string()))
+                    .collect();
+                state
+                    .set_list(&AttributePath::new("tags"), tags)
+                    .map_err(|e| vec![Diagnostic::error("Upgrade failed", e.to_string())])?;
+                Ok(state)
+            }),
+            StateUpgrader::new(1, Ok),
+        ]
+    }
+
+    fn raw_state(json: &str) -> RawState {
+        RawState {
+            json: Some(json.as_bytes().to_vec()),
+            flatmap: None,
+        }
+    }
+
+    #[test]
+    fn upgrade_state_chains_upgraders_and_conforms_to_schema() {
+        let response = upgrade_state(
+            &upgraders(),
+            &schema(),
+            0,
+            &raw_state(r#"{"name":"vm","tags":"web;prod","legacy":"x","disk":[{"size":"10G"}]}"#),
+        );
+
+        assert!(response.diagnostics.is_empty());
+        let state = response.upgraded_state;
+        assert_eq!(
+            state.get_list(&AttributePath::new("tags")).unwrap(),
+            vec![
+                Dynamic::String("web".to_string()),
+                Dynamic::String("prod".to_string())
+            ]
+        );
+        assert_eq!(
+            state
+                .get_string(&AttributePath::new("disk").index(0).attribute("size"))
+                .unwrap(),
+            "10G"
+        );
+        let Dynamic::Map(values) = &state.value else {
+            panic!("state must be an object");
+        };
+        assert!(!values.contains_key("legacy"));
+        let Dynamic::List(disks) = &values["disk"] else {
+            panic!("disk must be a list");
+        };
+        let Dynamic::Map(disk) = &disks[0] else {
+            panic!("disk must be an object");
+        };
+        assert_eq!(disk.get("ssd"), Some(&Dynamic::Null));
+    }
+
+    #[test]
+    fn upgrade_state_starts_from_stored_version() {
+        let response = upgrade_state(
+            &upgraders(),
+            &schema(),
+            1,
+            &raw_state(r#"{"name":"vm","tags":["web"]}"#),
+        );
+
+        assert!(response.diagnostics.is_empty());
+        assert_eq!(
+            response
+                .upgraded_state
+                .get_list(&AttributePath::new("tags"))
+                .unwrap(),
+            vec![Dynamic::String("web".to_string())]
+        );
+        assert_eq!(
+            response
+                .upgraded_state
+                .get_list(&AttributePath::new("disk"))
+                .unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn upgrade_state_conforms_state_of_the_current_version() {
+        // Written before `tags` and the `ssd` flag were added at version 2
+        let response = upgrade_state(
+            &[],
+            &schema(),
+            2,
+            &raw_state(r#"{"name":"vm","legacy":"x","disk":[{"size":"10G"}]}"#),
+        );
+
+        assert!(response.diagnostics.is_empty());
+        let Dynamic::Map(values) = &response.upgraded_state.value else {
+            panic!("state must be an object");
+        };
+        assert_eq!(values.get("tags"), Some(&Dynamic::Null));
+        assert!(!values.contains_key("legacy"));
+        assert_eq!(
+            response
+                .upgraded_state
+                .get(&AttributePath::new("disk").index(0).attribute("ssd"))
+                .unwrap(),
+            &Dynamic::Null
+        );
+    }
+
+    #[test]
+    fn upgrade_state_rejects_scalar_stored_for_block() {
+        let response = upgrade_state(
+            &upgraders(),
+            &schema(),
+            1,
+            &raw_state(r#"{"name":"vm","tags":["web"],"disk":"10G"}"#),
+        );
+
+        assert!(response.upgraded_state.is_null());
+        assert_eq!(
+            response.diagnostics[0].summary,
+            "State does not match the schema"
+        );
+        assert_eq!(
+            response.diagnostics[0].detail,
+            "Block 'disk' holds a string where a collection of blocks is expected"
+        );
+
+        let response = upgrade_state(
+            &upgraders(),
+            &schema(),
+            1,
+            &raw_state(r#"{"name":"vm","disk":[{"size":"10G"},2048]}"#),
+        );
+        assert_eq!(
+            response.diagnostics[0].detail,
+            "Block 'disk[1]' holds a number where an object is expected"
+        );
+    }
+
+    #[test]
+    fn upgrade_state_reports_unsupported_versions() {
+        let state = raw_state(r#"{"name":"vm"}"#);
+        let summary = |upgraders: &[StateUpgrader], version: i64, state: &RawState| {
+            let response = upgrade_state(upgraders, &schema(), version, state);
+            assert!(response.upgraded_state.is_null());
+            response.diagnostics[0].summary.clone()
+        };
+
+        assert_eq!(
+            summary(&upgraders()[1..], 0, &state),
+            "Missing state upgrader"
+        );
+        assert_eq!(
+            summary(&upgraders(), 3, &state),
+            "State from a newer provider"
+        );
+        let flatmap = RawState {
+            json: None,
+            flatmap: Some(HashMap::new()),
+        };
+        assert_eq!(
+            summary(&upgraders(), 0, &flatmap),
+            "Unsupported state format"
+        );
+    }
+}
@@ -2,8 +2,13 @@
 //!
 //! This module provides built-in validators and the trait for custom validators.
 
-use crate::schema::{Validator, ValidatorRequest, ValidatorResponse};
-use crate::types::{Diagnostic, Dynamic};
+use crate::schema::{
+    Attribute, Block, ConfigValidator, ConfigValidatorRequest, ConfigValidatorResponse,
+    NestingMode, ObjectNestingMode, Validator, ValidatorRequest, ValidatorResponse,
+};
+use crate::types::{AttributePath, AttributePathStep, Diagnostic, Dynamic, DynamicValue};
+use regex::Regex;
+use std::net::IpAddr;
 
 /// String length validator - validates string minimum and maximum length
 pub struct StringLengthValidator {
@@ -196,6 +201,67 @@ impl Validator for NumberRangeValidator {
     }
 }
 
+/// Validates that a number is a whole number, for attributes such as IDs and
+/// counts that are integers in the remote API
+pub struct IntegerValidator;
+
+impl IntegerValidator {
+    /// Create a validator that rejects fractional numbers
+    pub fn create() -> Box<dyn Validator> {
+        Box::new(Self)
+    }
+}
+
+impl Validator for IntegerValidator {
+    fn description(&self) -> String {
+        "number must be a whole number".to_string()
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if let Dynamic::Number(n) = &request.config_value.value {
+            if n.fract() != 0.0 {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid integer",
+                        format!("Value {} must be a whole number", n),
+                    )
+                    .with_attribute(request.path),
+                );
+            }
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Runs IntegerValidator against the named top level attributes of a config.
+/// Intended for `validate` implementations so fractional values are rejected
+/// at plan time.
+pub fn validate_integers(config: &DynamicValue, names: &[&str]) -> Vec<Diagnostic> {
+    let validator = IntegerValidator;
+    names
+        .iter()
+        .filter_map(|name| {
+            let path = AttributePath::new(name);
+            config
+                .get_number(&path)
+                .ok()
+                .map(|n| (path, DynamicValue::new(Dynamic::Number(n))))
+        })
+        .flat_map(|(path, config_value)| {
+            validator
+                .validate(ValidatorRequest {
+                    config_value,
+                    path,
+                    parent: config.clone(),
+                })
+                .diagnostics
+        })
+        .collect()
+}
+
 /// Validates list length
 pub struct ListLengthValidator {
     min: Option<usize>,
@@ -273,7 +339,795 @@ impl Validator for ListLengthValidator {
     }
 }
 
+/// Validates that a number is a whole number within a range, for IDs and
+/// counts with limits set by the remote API
+pub struct IntegerRangeValidator {
+    min: Option<i64>,
+    max: Option<i64>,
+}
+
+impl IntegerRangeValidator {
+    /// Create a validator with minimum value
+    pub fn min(value: i64) -> Box<dyn Validator> {
+        Box::new(Self {
+            min: Some(value),
+            max: None,
+        })
+    }
+
+    /// Create a validator with maximum value
+    pub fn max(value: i64) -> Box<dyn Validator> {
+        Box::new(Self {
+            min: None,
+            max: Some(value),
+        })
+    }
+
+    /// Create a validator with both min and max value
+    pub fn between(min: i64, max: i64) -> Box<dyn Validator> {
+        Box::new(Self {
+            min: Some(min),
+            max: Some(max),
+        })
+    }
+}
+
+impl Validator for IntegerRangeValidator {
+    fn description(&self) -> String {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => format!("whole number between {} and {}", min, max),
+            (Some(min), None) => format!("whole number of at least {}", min),
+            (None, Some(max)) => format!("whole number of at most {}", max),
+            (None, None) => "whole number".to_string(),
+        }
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let n = match &request.config_value.value {
+            Dynamic::Number(n) => *n,
+            _ => {
+                return ValidatorResponse {
+                    diagnostics: Vec::new(),
+                }
+            }
+        };
+
+        if n.fract() != 0.0 {
+            return IntegerValidator.validate(request);
+        }
+
+        let below = self.min.is_some_and(|min| n < min as f64);
+        let above = self.max.is_some_and(|max| n > max as f64);
+        let mut diagnostics = Vec::new();
+        if below || above {
+            diagnostics.push(
+                Diagnostic::error(
+                    "Value out of range",
+                    format!("Value {} must be a {}", n, self.description()),
+                )
+                .with_attribute(request.path),
+            );
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Validates that a string matches a regular expression as a whole
+pub struct StringRegexValidator {
+    pattern: Result<Regex, regex::Error>,
+    message: String,
+}
+
+impl StringRegexValidator {
+    /// Create a validator for `pattern`, which is anchored at both ends.
+    /// `message` describes the expected format, e.g. "must be a UUID".
+    pub fn create(pattern: &str, message: &str) -> Box<dyn Validator> {
+        Box::new(Self {
+            pattern: Regex::new(&format!("^(?:{})$", pattern)),
+            message: message.to_string(),
+        })
+    }
+}
+
+impl Validator for StringRegexValidator {
+    fn description(&self) -> String {
+        format!("value {}", self.message)
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if let Dynamic::String(s) = &request.config_value.value {
+            match &self.pattern {
+                Ok(pattern) if pattern.is_match(s) => {}
+                Ok(_) => diagnostics.push(
+                    Diagnostic::error("Invalid value", format!("\"{}\" {}", s, self.message))
+                        .with_attribute(request.path),
+                ),
+                Err(e) => diagnostics.push(
+                    Diagnostic::error("Invalid validator pattern", e.to_string())
+                        .with_attribute(request.path),
+                ),
+            }
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Validates that a string is an IP network in CIDR notation, such as
+/// 192.168.1.10/24 or fd00::1/64
+pub struct CidrValidator;
+
+impl CidrValidator {
+    /// Create a validator accepting IPv4 and IPv6 networks
+    pub fn create() -> Box<dyn Validator> {
+        Box::new(Self)
+    }
+
+    fn is_cidr(value: &str) -> bool {
+        let (address, prefix) = match value.split_once('/') {
+            Some(parts) => parts,
+            None => return false,
+        };
+        let max_prefix = match address.parse::<IpAddr>() {
+            Ok(IpAddr::V4(_)) => 32,
+            Ok(IpAddr::V6(_)) => 128,
+            Err(_) => return false,
+        };
+        prefix.chars().all(|c| c.is_ascii_digit())
+            && prefix.parse::<u8>().is_ok_and(|p| p <= max_prefix)
+    }
+}
+
+impl Validator for CidrValidator {
+    fn description(&self) -> String {
+        "value must be an IP network in CIDR notation".to_string()
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if let Dynamic::String(s) = &request.config_value.value {
+            if !Self::is_cidr(s) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid CIDR",
+                        format!(
+                            "\"{}\" is not an IP network in CIDR notation like 192.168.1.10/24",
+                            s
+                        ),
+                    )
+                    .with_attribute(request.path),
+                );
+            }
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Validates that a string is a duration such as 30s, 10m or 1h30m
+pub struct DurationValidator;
+
+impl DurationValidator {
+    /// Create a duration validator
+    pub fn create() -> Box<dyn Validator> {
+        Box::new(Self)
+    }
+}
+
+impl Validator for DurationValidator {
+    fn description(&self) -> String {
+        "value must be a duration such as 30s, 10m or 1h30m".to_string()
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if let Dynamic::String(s) = &request.config_value.value {
+            if let Err(e) = crate::timeouts::parse_duration(s) {
+                diagnostics
+                    .push(Diagnostic::error("Invalid duration", e).with_attribute(request.path));
+            }
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Validates that a string is a MAC address of six colon separated hex
+/// octets, such as BC:24:11:2A:3B:4C
+pub struct MacAddressValidator;
+
+impl MacAddressValidator {
+    /// Create a MAC address validator
+    pub fn create() -> Box<dyn Validator> {
+        Box::new(Self)
+    }
+
+    fn is_mac(value: &str) -> bool {
+        let octets: Vec<&str> = value.split(':').collect();
+        octets.len() == 6
+            && octets
+                .iter()
+                .all(|o| o.len() == 2 && o.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+}
+
+impl Validator for MacAddressValidator {
+    fn description(&self) -> String {
+        "value must be a MAC address".to_string()
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if let Dynamic::String(s) = &request.config_value.value {
+            if !Self::is_mac(s) {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Invalid MAC address",
+                        format!("\"{}\" is not a MAC address like BC:24:11:2A:3B:4C", s),
+                    )
+                    .with_attribute(request.path),
+                );
+            }
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Validates that a string is an absolute URL with one of the allowed schemes
+pub struct UrlValidator {
+    schemes: Vec<String>,
+}
+
+impl UrlValidator {
+    /// Create a validator accepting the given schemes, e.g. `&["https"]`.
+    /// An empty list accepts any scheme.
+    pub fn create(schemes: &[&str]) -> Box<dyn Validator> {
+        Box::new(Self {
+            schemes: schemes.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+
+    fn problem(&self, value: &str) -> Option<String> {
+        let (scheme, rest) = match value.split_once("://") {
+            Some(parts) => parts,
+            None => return Some("is missing a scheme such as https://".to_string()),
+        };
+        if !self.schemes.is_empty() && !self.schemes.iter().any(|s| s == scheme) {
+            return Some(format!("must use one of the schemes {:?}", self.schemes));
+        }
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host_port = authority.rsplit('@').next().unwrap_or_default();
+        // IPv6 hosts are bracketed, e.g. https://[fd00::1]:8006
+        let (host, port) = match host_port.strip_prefix('[') {
+            Some(bracketed) => match bracketed.split_once(']') {
+                Some((host, "")) => (host, None),
+                Some((host, rest)) => (host, Some(rest.strip_prefix(':').unwrap_or(rest))),
+                None => return Some("has no valid host".to_string()),
+            },
+            None => match host_port.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_port, None),
+            },
+        };
+        if host.is_empty() || host.chars().any(char::is_whitespace) {
+            return Some("has no valid host".to_string());
+        }
+        if port.is_some_and(|port| port.parse::<u16>().is_err()) {
+            return Some("has an invalid port".to_string());
+        }
+        None
+    }
+}
+
+impl Validator for UrlValidator {
+    fn description(&self) -> String {
+        if self.schemes.is_empty() {
+            "value must be an absolute URL".to_string()
+        } else {
+            format!("value must be a URL with scheme {:?}", self.schemes)
+        }
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if let Dynamic::String(s) = &request.config_value.value {
+            if let Some(problem) = self.problem(s) {
+                diagnostics.push(
+                    Diagnostic::error("Invalid URL", format!("\"{}\" {}", s, problem))
+                        .with_attribute(request.path),
+                );
+            }
+        }
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Names of the attributes of `parent` that hold a value. Unknown values
+/// count as set since they will have one once applied.
+fn set_attributes<'a>(parent: &DynamicValue, names: &'a [String]) -> Vec<&'a str> {
+    let values = match &parent.value {
+        Dynamic::Map(values) => values,
+        _ => return Vec::new(),
+    };
+    names
+        .iter()
+        .filter(|name| {
+            values
+                .get(*name)
+                .is_some_and(|v| !matches!(v, Dynamic::Null))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Rejects the attribute when any of the named sibling attributes is set too
+pub struct ConflictsWithValidator {
+    others: Vec<String>,
+}
+
+impl ConflictsWithValidator {
+    /// Create a validator conflicting with the named sibling attributes
+    pub fn create(others: &[&str]) -> Box<dyn Validator> {
+        Box::new(Self {
+            others: others.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+}
+
+impl Validator for ConflictsWithValidator {
+    fn description(&self) -> String {
+        format!("conflicts with {:?}", self.others)
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let diagnostics = set_attributes(&request.parent, &self.others)
+            .into_iter()
+            .map(|other| {
+                Diagnostic::error(
+                    "Conflicting attributes",
+                    format!(
+                        "\"{}\" cannot be set together with \"{}\"",
+                        attribute_name(&request.path),
+                        other
+                    ),
+                )
+                .with_attribute(request.path.clone())
+            })
+            .collect();
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Requires the named sibling attributes to be set whenever the attribute is
+pub struct RequiredWithValidator {
+    others: Vec<String>,
+}
+
+impl RequiredWithValidator {
+    /// Create a validator requiring the named sibling attributes
+    pub fn create(others: &[&str]) -> Box<dyn Validator> {
+        Box::new(Self {
+            others: others.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+}
+
+impl Validator for RequiredWithValidator {
+    fn description(&self) -> String {
+        format!("requires {:?} to be set", self.others)
+    }
+
+    fn validate(&self, request: ValidatorRequest) -> ValidatorResponse {
+        let set = set_attributes(&request.parent, &self.others);
+        let diagnostics = self
+            .others
+            .iter()
+            .filter(|other| !set.contains(&other.as_str()))
+            .map(|other| {
+                Diagnostic::error(
+                    "Missing required attribute",
+                    format!(
+                        "\"{}\" must be set when \"{}\" is set",
+                        other,
+                        attribute_name(&request.path)
+                    ),
+                )
+                .with_attribute(request.path.clone())
+            })
+            .collect();
+
+        ValidatorResponse { diagnostics }
+    }
+}
+
+/// Last attribute name of a path, for messages
+fn attribute_name(path: &AttributePath) -> &str {
+    path.steps
+        .iter()
+        .rev()
+        .find_map(|step| match step {
+            AttributePathStep::AttributeName(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Names of the top level attributes and blocks of a config that are set.
+/// Blocks that aren't configured come through as empty lists, so those
+/// count as unset; unknown values count as set like in set_attributes.
+fn configured<'a>(config: &DynamicValue, names: &'a [String]) -> Vec<&'a str> {
+    let values = match &config.value {
+        Dynamic::Map(values) => values,
+        _ => return Vec::new(),
+    };
+    names
+        .iter()
+        .filter(|name| match values.get(*name) {
+            None | Some(Dynamic::Null) => false,
+            Some(Dynamic::List(items)) => !items.is_empty(),
+            Some(_) => true,
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+fn quoted(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("\"{}\"", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn owned(names: &[&str]) -> Vec<String> {
+    names.iter().map(|s| s.to_string()).collect()
+}
+
+/// Rejects configs setting more than one of the named attributes or blocks
+pub struct ConflictingValidator {
+    names: Vec<String>,
+}
+
+impl ConflictingValidator {
+    /// Create a validator allowing at most one of the named attributes
+    pub fn create(names: &[&str]) -> Box<dyn ConfigValidator> {
+        Box::new(Self {
+            names: owned(names),
+        })
+    }
+}
+
+impl ConfigValidator for ConflictingValidator {
+    fn description(&self) -> String {
+        format!("at most one of {} can be set", quoted(&self.names))
+    }
+
+    fn validate(&self, request: ConfigValidatorRequest) -> ConfigValidatorResponse {
+        let set = configured(&request.config, &self.names);
+        let diagnostics = set
+            .iter()
+            .skip(1)
+            .map(|name| {
+                Diagnostic::attribute_error(
+                    AttributePath::new(name),
+                    "Conflicting attributes",
+                    format!("\"{}\" cannot be set together with \"{}\"", name, set[0]),
+                )
+            })
+            .collect();
+
+        ConfigValidatorResponse { diagnostics }
+    }
+}
+
+/// Requires the named attributes or blocks to be set all together or not
+/// at all
+pub struct RequiredTogetherValidator {
+    names: Vec<String>,
+}
+
+impl RequiredTogetherValidator {
+    /// Create a validator requiring the named attributes together
+    pub fn create(names: &[&str]) -> Box<dyn ConfigValidator> {
+        Box::new(Self {
+            names: owned(names),
+        })
+    }
+}
+
+impl ConfigValidator for RequiredTogetherValidator {
+    fn description(&self) -> String {
+        format!("{} must be set together", quoted(&self.names))
+    }
+
+    fn validate(&self, request: ConfigValidatorRequest) -> ConfigValidatorResponse {
+        let set = configured(&request.config, &self.names);
+        let diagnostics = if set.is_empty() {
+            Vec::new()
+        } else {
+            self.names
+                .iter()
+                .filter(|name| !set.contains(&name.as_str()))
+                .map(|name| {
+                    Diagnostic::attribute_error(
+                        AttributePath::new(name),
+                        "Missing required attribute",
+                        format!("\"{}\" must be set when \"{}\" is set", name, set[0]),
+                    )
+                })
+                .collect()
+        };
+
+        ConfigValidatorResponse { diagnostics }
+    }
+}
+
+/// Requires at least one of the named attributes or blocks to be set
+pub struct AtLeastOneOfValidator {
+    names: Vec<String>,
+}
+
+impl AtLeastOneOfValidator {
+    /// Create a validator requiring one or more of the named attributes
+    pub fn create(names: &[&str]) -> Box<dyn ConfigValidator> {
+        Box::new(Self {
+            names: owned(names),
+        })
+    }
+}
+
+impl ConfigValidator for AtLeastOneOfValidator {
+    fn description(&self) -> String {
+        format!("at least one of {} must be set", quoted(&self.names))
+    }
+
+    fn validate(&self, request: ConfigValidatorRequest) -> ConfigValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if configured(&request.config, &self.names).is_empty() {
+            diagnostics.push(Diagnostic::error(
+                "Missing required attribute",
+                format!("At least one of {} must be set", quoted(&self.names)),
+            ));
+        }
+
+        ConfigValidatorResponse { diagnostics }
+    }
+}
+
+/// Requires exactly one of the named attributes or blocks to be set
+pub struct ExactlyOneOfValidator {
+    names: Vec<String>,
+}
+
+impl ExactlyOneOfValidator {
+    /// Create a validator requiring exactly one of the named attributes
+    pub fn create(names: &[&str]) -> Box<dyn ConfigValidator> {
+        Box::new(Self {
+            names: owned(names),
+        })
+    }
+}
+
+impl ConfigValidator for ExactlyOneOfValidator {
+    fn description(&self) -> String {
+        format!("exactly one of {} must be set", quoted(&self.names))
+    }
+
+    fn validate(&self, request: ConfigValidatorRequest) -> ConfigValidatorResponse {
+        if configured(&request.config, &self.names).is_empty() {
+            return ConfigValidatorResponse {
+                diagnostics: vec![Diagnostic::error(
+                    "Missing required attribute",
+                    format!("Exactly one of {} must be set", quoted(&self.names)),
+                )],
+            };
+        }
+
+        // More than one set is the same mistake as conflicting attributes
+        ConflictingValidator {
+            names: self.names.clone(),
+        }
+        .validate(request)
+    }
+}
+
+/// Requires other attributes or blocks to be set whenever the named one is,
+/// e.g. full_clone only means something together with clone
+pub struct AlsoRequiresValidator {
+    name: String,
+    required: Vec<String>,
+}
+
+impl AlsoRequiresValidator {
+    /// Create a validator requiring `required` whenever `name` is set
+    pub fn create(name: &str, required: &[&str]) -> Box<dyn ConfigValidator> {
+        Box::new(Self {
+            name: name.to_string(),
+            required: owned(required),
+        })
+    }
+}
+
+impl ConfigValidator for AlsoRequiresValidator {
+    fn description(&self) -> String {
+        format!("\"{}\" requires {}", self.name, quoted(&self.required))
+    }
+
+    fn validate(&self, request: ConfigValidatorRequest) -> ConfigValidatorResponse {
+        let mut diagnostics = Vec::new();
+
+        if !configured(&request.config, std::slice::from_ref(&self.name)).is_empty() {
+            let set = configured(&request.config, &self.required);
+            for required in &self.required {
+                if !set.contains(&required.as_str()) {
+                    diagnostics.push(Diagnostic::attribute_error(
+                        AttributePath::new(&self.name),
+                        "Missing required attribute",
+                        format!("\"{}\" must be set when \"{}\" is set", required, self.name),
+                    ));
+                }
+            }
+        }
+
+        ConfigValidatorResponse { diagnostics }
+    }
+}
+
+/// Runs config validators against a config
+pub fn run_config_validators(
+    validators: &[Box<dyn ConfigValidator>],
+    config: &DynamicValue,
+) -> Vec<Diagnostic> {
+    validators
+        .iter()
+        .flat_map(|validator| {
+            validator
+                .validate(ConfigValidatorRequest {
+                    config: config.clone(),
+                })
+                .diagnostics
+        })
+        .collect()
+}
+
+/// Runs the validators declared on the attributes of a block, and of its
+/// nested blocks, against a config. Null and unknown values are skipped so
+/// validators only see values that are set.
+pub fn validate_config(block: &Block, config: &DynamicValue) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    validate_block(
+        block,
+        &config.value,
+        AttributePath::root(),
+        &mut diagnostics,
+    );
+    diagnostics
+}
+
+fn validate_block(
+    block: &Block,
+    value: &Dynamic,
+    path: AttributePath,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let values = match value {
+        Dynamic::Map(values) => values,
+        _ => return,
+    };
+    validate_attributes(&block.attributes, value, &path, diagnostics);
+
+    for nested in &block.block_types {
+        let value = match values.get(&nested.type_name) {
+            Some(value) => value,
+            None => continue,
+        };
+        let nested_path = path.clone().attribute(&nested.type_name);
+        let start = diagnostics.len();
+        for (item_path, item) in nested_items(value, nested_path.clone(), nested.nesting) {
+            validate_block(&nested.block, item, item_path, diagnostics);
+        }
+        if nested.nesting == NestingMode::Set {
+            point_at(&mut diagnostics[start..], &nested_path);
+        }
+    }
+}
+
+/// Runs the validators of a list of attributes against the object holding
+/// them, descending into nested attributes
+fn validate_attributes(
+    attributes: &[Attribute],
+    value: &Dynamic,
+    path: &AttributePath,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let values = match value {
+        Dynamic::Map(values) => values,
+        _ => return,
+    };
+    let parent = DynamicValue::new(value.clone());
+
+    for attribute in attributes {
+        let config_value = match values.get(&attribute.name) {
+            Some(Dynamic::Null | Dynamic::Unknown) | None => continue,
+            Some(config_value) => config_value,
+        };
+        let attribute_path = path.clone().attribute(&attribute.name);
+        for validator in &attribute.validators {
+            let response = validator.validate(ValidatorRequest {
+                config_value: DynamicValue::new(config_value.clone()),
+                path: attribute_path.clone(),
+                parent: parent.clone(),
+            });
+            diagnostics.extend(response.diagnostics);
+        }
+
+        if let Some(nested) = &attribute.nested_type {
+            let nesting = match nested.nesting {
+                ObjectNestingMode::List => NestingMode::List,
+                ObjectNestingMode::Set => NestingMode::Set,
+                ObjectNestingMode::Map => NestingMode::Map,
+                ObjectNestingMode::Single | ObjectNestingMode::Invalid => NestingMode::Single,
+            };
+            let start = diagnostics.len();
+            for (item_path, item) in nested_items(config_value, attribute_path.clone(), nesting) {
+                validate_attributes(&nested.attributes, item, &item_path, diagnostics);
+            }
+            if nesting == NestingMode::Set {
+                point_at(&mut diagnostics[start..], &attribute_path);
+            }
+        }
+    }
+}
+
+/// Objects of a nested block or attribute with their paths: list items by
+/// index, map items by key, or the value itself for single objects. Set
+/// items get the path of the set as Terraform can't address them by index.
+fn nested_items(
+    value: &Dynamic,
+    path: AttributePath,
+    nesting: NestingMode,
+) -> Vec<(AttributePath, &Dynamic)> {
+    match (nesting, value) {
+        (NestingMode::Set, Dynamic::List(items)) => {
+            items.iter().map(|item| (path.clone(), item)).collect()
+        }
+        (_, Dynamic::List(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (path.clone().index(index as i64), item))
+            .collect(),
+        (NestingMode::Map, Dynamic::Map(items)) => {
+            let mut keys: Vec<&String> = items.keys().collect();
+            keys.sort();
+            keys.into_iter()
+                .map(|key| (path.clone().key(key), &items[key]))
+                .collect()
+        }
+        (_, Dynamic::Map(_)) => vec![(path, value)],
+        _ => Vec::new(),
+    }
+}
+
+/// Attaches diagnostics raised inside set items to the set itself
+fn point_at(diagnostics: &mut [Diagnostic], path: &AttributePath) {
+    for diagnostic in diagnostics {
+        diagnostic.attribute = Some(path.clone());
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)]
 mod tests {
     use super::*;
     use crate::types::{AttributePath, DynamicValue};
@@ -285,6 +1139,7 @@ mod tests {
         let request = ValidatorRequest {
             config_value: DynamicValue::new(Dynamic::String("ab".to_string())),
             path: AttributePath::new("test"),
+            parent: DynamicValue::null(),
         };
 
         let response = validator.validate(request);
@@ -299,6 +1154,7 @@ mod tests {
         let request = ValidatorRequest {
             config_value: DynamicValue::new(Dynamic::String("baz".to_string())),
             path: AttributePath::new("test"),
+            parent: DynamicValue::null(),
         };
 
         let response = validator.validate(request);
@@ -315,10 +1171,431 @@ mod tests {
         let request = ValidatorRequest {
             config_value: DynamicValue::new(Dynamic::Number(15.0)),
             path: AttributePath::new("test"),
+            parent: DynamicValue::null(),
         };
 
         let response = validator.validate(request);
         assert_eq!(response.diagnostics.len(), 1);
         assert!(response.diagnostics[0].summary.contains("too large"));
     }
+
+    #[test]
+    fn integer_validator_rejects_fractions() {
+        let validator = IntegerValidator::create();
+
+        let request = ValidatorRequest {
+            config_value: DynamicValue::new(Dynamic::Number(2.5)),
+            path: AttributePath::new("cores"),
+            parent: DynamicValue::null(),
+        };
+        let response = validator.validate(request);
+        assert_eq!(response.diagnostics.len(), 1);
+        assert_eq!(response.diagnostics[0].summary, "Invalid integer");
+        assert!(response.diagnostics[0].attribute.is_some());
+
+        let request = ValidatorRequest {
+            config_value: DynamicValue::new(Dynamic::Number(4.0)),
+            path: AttributePath::new("cores"),
+            parent: DynamicValue::null(),
+        };
+        assert!(validator.validate(request).diagnostics.is_empty());
+    }
+
+    #[test]
+    fn validate_integers_checks_named_attributes() {
+        let mut config = DynamicValue::new(Dynamic::Map(std::collections::HashMap::new()));
+        config
+            .set_number(&AttributePath::new("vmid"), 100.5)
+            .unwrap();
+        config
+            .set_number(&AttributePath::new("cores"), 2.0)
+            .unwrap();
+        config
+            .set_number(&AttributePath::new("ratio"), 0.5)
+            .unwrap();
+
+        let diagnostics = validate_integers(&config, &["vmid", "cores", "sockets"]);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].detail.contains("100.5"));
+    }
+
+    fn string_request(value: &str) -> ValidatorRequest {
+        ValidatorRequest {
+            config_value: DynamicValue::new(Dynamic::String(value.to_string())),
+            path: AttributePath::new("test"),
+            parent: DynamicValue::null(),
+        }
+    }
+
+    fn summaries(validator: &dyn Validator, values: &[&str]) -> Vec<String> {
+        values
+            .iter()
+            .flat_map(|value| validator.validate(string_request(value)).diagnostics)
+            .map(|d| d.summary)
+            .collect()
+    }
+
+    #[test]
+    fn integer_range_validator() {
+        let validator = IntegerRangeValidator::between(100, 999);
+        let summary = |n: f64| {
+            validator
+                .validate(ValidatorRequest {
+                    config_value: DynamicValue::new(Dynamic::Number(n)),
+                    path: AttributePath::new("vmid"),
+                    parent: DynamicValue::null(),
+                })
+                .diagnostics
+                .into_iter()
+                .map(|d| d.summary)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(summary(100.0).is_empty());
+        assert!(summary(999.0).is_empty());
+        assert_eq!(summary(50.0), vec!["Value out of range"]);
+        assert_eq!(summary(1000.0), vec!["Value out of range"]);
+        assert_eq!(summary(100.5), vec!["Invalid integer"]);
+    }
+
+    #[test]
+    fn string_regex_validator_matches_whole_string() {
+        let validator = StringRegexValidator::create("[a-z]+", "must be lowercase letters");
+        assert!(summaries(validator.as_ref(), &["abc"]).is_empty());
+        assert_eq!(
+            summaries(validator.as_ref(), &["abc1", "ABC"]),
+            vec!["Invalid value", "Invalid value"]
+        );
+
+        let broken = StringRegexValidator::create("[a-", "is broken");
+        assert_eq!(
+            summaries(broken.as_ref(), &["a"]),
+            vec!["Invalid validator pattern"]
+        );
+    }
+
+    #[test]
+    fn cidr_validator() {
+        let validator = CidrValidator::create();
+        assert!(summaries(
+            validator.as_ref(),
+            &["192.168.1.10/24", "10.0.0.0/8", "fd00::1/64", "0.0.0.0/0"]
+        )
+        .is_empty());
+        assert_eq!(
+            summaries(
+                validator.as_ref(),
+                &[
+                    "192.168.1.10",
+                    "192.168.1.10/33",
+                    "fd00::1/129",
+                    "host/24",
+                    "1.2.3.4/+8"
+                ]
+            )
+            .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn mac_address_validator() {
+        let validator = MacAddressValidator::create();
+        assert!(summaries(
+            validator.as_ref(),
+            &["BC:24:11:2A:3B:4C", "bc:24:11:2a:3b:4c"]
+        )
+        .is_empty());
+        assert_eq!(
+            summaries(
+                validator.as_ref(),
+                &[
+                    "BC:24:11:2A:3B",
+                    "BC-24-11-2A-3B-4C",
+                    "BC:24:11:2A:3B:4G",
+                    "BC:24:11:2A:3B:4C4"
+                ]
+            )
+            .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn url_validator() {
+        let validator = UrlValidator::create(&["https"]);
+        assert!(summaries(
+            validator.as_ref(),
+            &[
+                "https://pve.example.com:8006/api2/json",
+                "https://user@pve",
+                "https://[fd00::1]:8006"
+            ]
+        )
+        .is_empty());
+
+        let invalid = [
+            "pve.example.com",
+            "http://pve.example.com",
+            "https://",
+            "https://pve:port",
+            "https://[fd00::1",
+        ];
+        for value in invalid {
+            let diagnostics = validator.validate(string_request(value)).diagnostics;
+            assert_eq!(diagnostics.len(), 1, "{}", value);
+            assert_eq!(diagnostics[0].summary, "Invalid URL");
+        }
+
+        let any_scheme = UrlValidator::create(&[]);
+        assert!(summaries(any_scheme.as_ref(), &["ftp://mirror"]).is_empty());
+    }
+
+    #[test]
+    fn conflicts_with_and_required_with_validators() {
+        let parent = DynamicValue::new(Dynamic::Map(
+            [
+                (
+                    "password".to_string(),
+                    Dynamic::String("secret".to_string()),
+                ),
+                ("token".to_string(), Dynamic::Unknown),
+                ("username".to_string(), Dynamic::Null),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+        let request = || ValidatorRequest {
+            config_value: DynamicValue::new(Dynamic::String("secret".to_string())),
+            path: AttributePath::new("password"),
+            parent: parent.clone(),
+        };
+
+        let conflicts = ConflictsWithValidator::create(&["token", "username"]);
+        let diagnostics = conflicts.validate(request()).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].summary, "Conflicting attributes");
+        assert!(diagnostics[0].detail.contains("\"token\""));
+
+        let required = RequiredWithValidator::create(&["token", "username"]);
+        let diagnostics = required.validate(request()).diagnostics;
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].summary, "Missing required attribute");
+        assert!(diagnostics[0].detail.contains("\"username\""));
+    }
+
+    #[test]
+    fn config_validators_treat_empty_blocks_as_unset() {
+        let config = DynamicValue::new(Dynamic::Map(
+            [
+                ("clone".to_string(), Dynamic::String("template".to_string())),
+                ("cdrom".to_string(), Dynamic::List(vec![])),
+                ("full_clone".to_string(), Dynamic::Bool(true)),
+                ("name".to_string(), Dynamic::Unknown),
+                ("pool".to_string(), Dynamic::Null),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+        let run = |validator: Box<dyn ConfigValidator>| {
+            run_config_validators(&[validator], &config)
+                .into_iter()
+                .map(|d| d.detail)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(run(ConflictingValidator::create(&["clone", "cdrom"])).is_empty());
+        assert_eq!(
+            run(ConflictingValidator::create(&["clone", "name"])),
+            ["\"name\" cannot be set together with \"clone\""]
+        );
+        assert_eq!(
+            run(RequiredTogetherValidator::create(&["clone", "pool"])),
+            ["\"pool\" must be set when \"clone\" is set"]
+        );
+        assert_eq!(
+            run(AtLeastOneOfValidator::create(&["cdrom", "pool"])),
+            ["At least one of \"cdrom\", \"pool\" must be set"]
+        );
+        assert!(run(ExactlyOneOfValidator::create(&["clone", "pool"])).is_empty());
+        assert_eq!(
+            run(ExactlyOneOfValidator::create(&["clone", "name"])).len(),
+            1
+        );
+        assert!(run(AlsoRequiresValidator::create("full_clone", &["clone"])).is_empty());
+        assert_eq!(
+            run(AlsoRequiresValidator::create("full_clone", &["pool"])),
+            ["\"pool\" must be set when \"full_clone\" is set"]
+        );
+    }
+
+    #[test]
+    fn validate_config_runs_nested_validators() {
+        use crate::schema::{AttributeBuilder, AttributeType, NestedBlock};
+
+        let block = Block {
+            version: 0,
+            attributes: vec![AttributeBuilder::new("vmid", AttributeType::Number)
+                .validators(vec![IntegerRangeValidator::min(100)])
+                .build()],
+            block_types: vec![NestedBlock {
+                type_name: "network".to_string(),
+                block: Block {
+                    version: 0,
+                    attributes: vec![AttributeBuilder::new("macaddr", AttributeType::String)
+                        .validator(MacAddressValidator::create())
+                        .build()],
+                    block_types: vec![],
+                    description: String::new(),
+                    description_kind: crate::schema::StringKind::Plain,
+                    deprecated: false,
+                },
+                nesting: NestingMode::List,
+                min_items: 0,
+                max_items: 0,
+            }],
+            description: String::new(),
+            description_kind: crate::schema::StringKind::Plain,
+            deprecated: false,
+        };
+
+        let network =
+            |mac: Dynamic| Dynamic::Map([("macaddr".to_string(), mac)].into_iter().collect());
+        let config = DynamicValue::new(Dynamic::Map(
+            [
+                ("vmid".to_string(), Dynamic::Number(50.0)),
+                (
+                    "network".to_string(),
+                    Dynamic::List(vec![
+                        network(Dynamic::String("BC:24:11:2A:3B:4C".to_string())),
+                        network(Dynamic::String("not-a-mac".to_string())),
+                        network(Dynamic::Unknown),
+                    ]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+
+        let diagnostics = validate_config(&block, &config);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].summary, "Value out of range");
+        assert_eq!(diagnostics[0].attribute, Some(AttributePath::new("vmid")));
+        assert_eq!(diagnostics[1].summary, "Invalid MAC address");
+        assert_eq!(
+            diagnostics[1].attribute,
+            Some(AttributePath::new("network").index(1).attribute("macaddr"))
+        );
+    }
+
+    #[test]
+    fn validate_config_runs_nested_attribute_validators() {
+        use crate::schema::{AttributeBuilder, AttributeType, StringKind};
+
+        let block = Block {
+            version: 0,
+            attributes: vec![
+                AttributeBuilder::single_nested(
+                    "cpu",
+                    vec![AttributeBuilder::new("cores", AttributeType::Number)
+                        .validator(IntegerRangeValidator::between(1, 128))
+                        .build()],
+                )
+                .build(),
+                AttributeBuilder::map_nested(
+                    "nics",
+                    vec![AttributeBuilder::new("macaddr", AttributeType::String)
+                        .validator(MacAddressValidator::create())
+                        .build()],
+                )
+                .build(),
+            ],
+            block_types: vec![],
+            description: String::new(),
+            description_kind: StringKind::Plain,
+            deprecated: false,
+        };
+
+        let object = |name: &str, value: Dynamic| {
+            Dynamic::Map([(name.to_string(), value)].into_iter().collect())
+        };
+        let config = DynamicValue::new(Dynamic::Map(
+            [
+                ("cpu".to_string(), object("cores", Dynamic::Number(256.0))),
+                (
+                    "nics".to_string(),
+                    Dynamic::Map(
+                        [
+                            (
+                                "net0".to_string(),
+                                object("macaddr", Dynamic::String("BC:24:11:2A:3B:4C".to_string())),
+                            ),
+                            (
+                                "net1".to_string(),
+                                object("macaddr", Dynamic::String("bogus".to_string())),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+
+        let diagnostics = validate_config(&block, &config);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].attribute,
+            Some(AttributePath::new("cpu").attribute("cores"))
+        );
+        assert_eq!(
+            diagnostics[1].attribute,
+            Some(AttributePath::new("nics").key("net1").attribute("macaddr"))
+        );
+    }
+
+    #[test]
+    fn validate_config_reports_set_items_at_the_set() {
+        use crate::schema::{AttributeBuilder, AttributeType, NestedBlock, SchemaBuilder};
+
+        let block = SchemaBuilder::new()
+            .block(NestedBlock {
+                type_name: "rule".to_string(),
+                block: SchemaBuilder::new()
+                    .attribute(
+                        AttributeBuilder::new("source", AttributeType::String)
+                            .validator(CidrValidator::create())
+                            .build(),
+                    )
+                    .build()
+                    .block,
+                nesting: NestingMode::Set,
+                min_items: 0,
+                max_items: 0,
+            })
+            .build()
+            .block;
+
+        let rule = |source: &str| {
+            Dynamic::Map(
+                [("source".to_string(), Dynamic::String(source.to_string()))]
+                    .into_iter()
+                    .collect(),
+            )
+        };
+        let config = DynamicValue::new(Dynamic::Map(
+            [(
+                "rule".to_string(),
+                Dynamic::List(vec![rule("10.0.0.0/8"), rule("10.0.0.1")]),
+            )]
+            .into_iter()
+            .collect(),
+        ));
+
+        let diagnostics = validate_config(&block, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].attribute, Some(AttributePath::new("rule")));
+    }
 }
@@ -979,12 +979,10 @@ async fn test_data_source_can_access_provider_config() {
     // Note: Due to the new architecture, data sources don't automatically
     // get provider config. This would need to be implemented through
     // the configure method and provider_data mechanism.
-    assert!(
-        resp.state
-            .get_bool(&AttributePath::new("has_config"))
-            .unwrap_or(false)
-            == false
-    );
+    assert!(!resp
+        .state
+        .get_bool(&AttributePath::new("has_config"))
+        .unwrap_or(false));
 }
 
 #[tokio::test]